@@ -0,0 +1,300 @@
+//! Typed access to the `availability` API.
+
+use anyhow::Context;
+use committable::Commitment;
+use espresso_types::{
+    BlockNamespacesQueryData, Header, NamespaceId, NamespaceProofQueryData, SeqTypes, Transaction,
+};
+use futures::stream::BoxStream;
+use hotshot_query_service::{
+    availability::{
+        BlockQueryData, BlockSummaryQueryData, LeafQueryData, Limits, PayloadQueryData,
+        StateCertQueryData, TransactionQueryData, VidCommonQueryData,
+    },
+    types::HeightIndexed,
+};
+
+use crate::SequencerClient;
+
+impl SequencerClient {
+    /// Get implementation-defined limits on the size of range requests.
+    pub async fn get_availability_limits(&self) -> anyhow::Result<Limits> {
+        self.get("availability/limits")
+            .await
+            .context("getting availability limits")
+    }
+
+    /// Get the leaf at `height`.
+    pub async fn get_leaf(&self, height: u64) -> anyhow::Result<LeafQueryData<SeqTypes>> {
+        self.get(&format!("availability/leaf/{height}"))
+            .await
+            .with_context(|| format!("getting leaf {height}"))
+    }
+
+    /// Get the leaves in `[from, until)`.
+    ///
+    /// The range may not exceed [`Limits::small_object_range_limit`]. Use
+    /// [`leaves`](Self::leaves) to load a range of any size.
+    pub async fn get_leaf_range(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<Vec<LeafQueryData<SeqTypes>>> {
+        self.get(&format!("availability/leaf/{from}/{until}"))
+            .await
+            .with_context(|| format!("getting leaves {from}..{until}"))
+    }
+
+    /// Get the header at `height`.
+    pub async fn get_header(&self, height: u64) -> anyhow::Result<Header> {
+        self.get(&format!("availability/header/{height}"))
+            .await
+            .with_context(|| format!("getting header {height}"))
+    }
+
+    /// Get the headers in `[from, until)`.
+    ///
+    /// The range may not exceed [`Limits::large_object_range_limit`]. Use
+    /// [`headers`](Self::headers) to load a range of any size.
+    pub async fn get_header_range(&self, from: u64, until: u64) -> anyhow::Result<Vec<Header>> {
+        self.get(&format!("availability/header/{from}/{until}"))
+            .await
+            .with_context(|| format!("getting headers {from}..{until}"))
+    }
+
+    /// Get the block at `height`.
+    pub async fn get_block(&self, height: u64) -> anyhow::Result<BlockQueryData<SeqTypes>> {
+        self.get(&format!("availability/block/{height}"))
+            .await
+            .with_context(|| format!("getting block {height}"))
+    }
+
+    /// Get the blocks in `[from, until)`.
+    ///
+    /// The range may not exceed [`Limits::large_object_range_limit`]. Use
+    /// [`blocks`](Self::blocks) to load a range of any size.
+    pub async fn get_block_range(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<Vec<BlockQueryData<SeqTypes>>> {
+        self.get(&format!("availability/block/{from}/{until}"))
+            .await
+            .with_context(|| format!("getting blocks {from}..{until}"))
+    }
+
+    /// Get the payload of the block at `height`.
+    pub async fn get_payload(&self, height: u64) -> anyhow::Result<PayloadQueryData<SeqTypes>> {
+        self.get(&format!("availability/payload/{height}"))
+            .await
+            .with_context(|| format!("getting payload {height}"))
+    }
+
+    /// Get the payloads of the blocks in `[from, until)`.
+    ///
+    /// The range may not exceed [`Limits::large_object_range_limit`]. Use
+    /// [`payloads`](Self::payloads) to load a range of any size.
+    pub async fn get_payload_range(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<Vec<PayloadQueryData<SeqTypes>>> {
+        self.get(&format!("availability/payload/{from}/{until}"))
+            .await
+            .with_context(|| format!("getting payloads {from}..{until}"))
+    }
+
+    /// Get the VID common data for the block at `height`.
+    pub async fn get_vid_common(
+        &self,
+        height: u64,
+    ) -> anyhow::Result<VidCommonQueryData<SeqTypes>> {
+        self.get(&format!("availability/vid/common/{height}"))
+            .await
+            .with_context(|| format!("getting VID common {height}"))
+    }
+
+    /// Get the transaction at position `index` in the block at `height`.
+    pub async fn get_transaction(
+        &self,
+        height: u64,
+        index: u64,
+    ) -> anyhow::Result<TransactionQueryData<SeqTypes>> {
+        self.get(&format!("availability/transaction/{height}/{index}"))
+            .await
+            .with_context(|| format!("getting transaction {height}/{index}"))
+    }
+
+    /// Get the earliest transaction with the given hash.
+    pub async fn get_transaction_by_hash(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> anyhow::Result<TransactionQueryData<SeqTypes>> {
+        self.get(&format!("availability/transaction/hash/{hash}"))
+            .await
+            .with_context(|| format!("getting transaction {hash}"))
+    }
+
+    /// Get a summary of the block at `height`.
+    pub async fn get_block_summary(
+        &self,
+        height: u64,
+    ) -> anyhow::Result<BlockSummaryQueryData<SeqTypes>> {
+        self.get(&format!("availability/block/summary/{height}"))
+            .await
+            .with_context(|| format!("getting block summary {height}"))
+    }
+
+    /// Get summaries of the blocks in `[from, until)`.
+    pub async fn get_block_summary_range(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<Vec<BlockSummaryQueryData<SeqTypes>>> {
+        self.get(&format!("availability/block/summaries/{from}/{until}"))
+            .await
+            .with_context(|| format!("getting block summaries {from}..{until}"))
+    }
+
+    /// Get the transactions in namespace `ns` of the block at `height`, with a namespace proof.
+    ///
+    /// The proof is not checked. Use [`VerifiedSequencerClient`](crate::VerifiedSequencerClient)
+    /// to get verified namespace data.
+    pub async fn get_namespace_proof(
+        &self,
+        height: u64,
+        ns: NamespaceId,
+    ) -> anyhow::Result<NamespaceProofQueryData> {
+        let ns_id = u32::from(ns);
+        self.get(&format!("availability/block/{height}/namespace/{ns_id}"))
+            .await
+            .with_context(|| format!("getting namespace {ns_id} of block {height}"))
+    }
+
+    /// Get the light client state update certificate for `epoch`.
+    pub async fn get_state_cert(&self, epoch: u64) -> anyhow::Result<StateCertQueryData<SeqTypes>> {
+        self.get(&format!("availability/state-cert/{epoch}"))
+            .await
+            .with_context(|| format!("getting state certificate for epoch {epoch}"))
+    }
+
+    /// Load the leaves in `[from, until)`, splitting the range into pages as necessary.
+    pub async fn leaves(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<LeafQueryData<SeqTypes>>>> {
+        let limits = self.get_availability_limits().await?;
+        Ok(self.paginate(
+            "availability/leaf",
+            from,
+            until,
+            limits.small_object_range_limit,
+        ))
+    }
+
+    /// Load the headers in `[from, until)`, splitting the range into pages as necessary.
+    pub async fn headers(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Header>>> {
+        let limits = self.get_availability_limits().await?;
+        Ok(self.paginate(
+            "availability/header",
+            from,
+            until,
+            limits.large_object_range_limit,
+        ))
+    }
+
+    /// Load the blocks in `[from, until)`, splitting the range into pages as necessary.
+    pub async fn blocks(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<BlockQueryData<SeqTypes>>>> {
+        let limits = self.get_availability_limits().await?;
+        Ok(self.paginate(
+            "availability/block",
+            from,
+            until,
+            limits.large_object_range_limit,
+        ))
+    }
+
+    /// Load the payloads in `[from, until)`, splitting the range into pages as necessary.
+    pub async fn payloads(
+        &self,
+        from: u64,
+        until: u64,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<PayloadQueryData<SeqTypes>>>> {
+        let limits = self.get_availability_limits().await?;
+        Ok(self.paginate(
+            "availability/payload",
+            from,
+            until,
+            limits.large_object_range_limit,
+        ))
+    }
+
+    /// Stream leaves starting from `height`, reconnecting automatically if the connection drops.
+    pub fn stream_leaves(&self, height: u64) -> BoxStream<'static, LeafQueryData<SeqTypes>> {
+        self.resumable_stream("availability/stream/leaves", height, HeightIndexed::height)
+    }
+
+    /// Stream headers starting from `height`, reconnecting automatically if the connection drops.
+    pub fn stream_headers(&self, height: u64) -> BoxStream<'static, Header> {
+        self.resumable_stream("availability/stream/headers", height, Header::height)
+    }
+
+    /// Stream blocks starting from `height`, reconnecting automatically if the connection drops.
+    pub fn stream_blocks(&self, height: u64) -> BoxStream<'static, BlockQueryData<SeqTypes>> {
+        self.resumable_stream("availability/stream/blocks", height, HeightIndexed::height)
+    }
+
+    /// Stream payloads starting from `height`, reconnecting automatically if the connection
+    /// drops.
+    pub fn stream_payloads(&self, height: u64) -> BoxStream<'static, PayloadQueryData<SeqTypes>> {
+        self.resumable_stream(
+            "availability/stream/payloads",
+            height,
+            HeightIndexed::height,
+        )
+    }
+
+    /// Stream the transactions of `namespaces` in each block, starting from `height`,
+    /// reconnecting automatically if the connection drops.
+    ///
+    /// Each item includes namespace proofs which are not checked. Use
+    /// [`BlockNamespacesQueryData::verify`] to check them.
+    pub fn stream_namespaces(
+        &self,
+        height: u64,
+        namespaces: &[NamespaceId],
+    ) -> BoxStream<'static, BlockNamespacesQueryData> {
+        let namespaces = namespaces
+            .iter()
+            .map(|ns| u32::from(*ns).to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.resumable_stream_with(
+            move |height| format!("availability/stream/blocks/{height}/namespaces/{namespaces}"),
+            height,
+            |data: &BlockNamespacesQueryData| data.header.height(),
+        )
+    }
+
+    /// Stream VID common data starting from `height`, reconnecting automatically if the
+    /// connection drops.
+    pub fn stream_vid_common(
+        &self,
+        height: u64,
+    ) -> BoxStream<'static, VidCommonQueryData<SeqTypes>> {
+        self.resumable_stream(
+            "availability/stream/vid/common",
+            height,
+            HeightIndexed::height,
+        )
+    }
+}
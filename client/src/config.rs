@@ -0,0 +1,20 @@
+//! Typed access to the `config` API.
+
+use anyhow::Context;
+use espresso_types::config::PublicNetworkConfig;
+
+use crate::SequencerClient;
+
+impl SequencerClient {
+    /// Get the public parts of the HotShot configuration of the node.
+    pub async fn get_hotshot_config(&self) -> anyhow::Result<PublicNetworkConfig> {
+        self.get("config/hotshot")
+            .await
+            .context("getting HotShot config")
+    }
+
+    /// Get the `ESPRESSO_*` environment variables set for the node.
+    pub async fn get_env(&self) -> anyhow::Result<Vec<String>> {
+        self.get("config/env").await.context("getting node env")
+    }
+}
@@ -0,0 +1,106 @@
+//! Typed access to the `explorer` API.
+
+use anyhow::Context;
+use committable::Commitment;
+use espresso_types::{NamespaceId, SeqTypes, Transaction};
+use hotshot_query_service::explorer::{
+    BlockDetail, BlockDetailResponse, BlockSummary, BlockSummaryResponse, ExplorerSummary,
+    ExplorerSummaryResponse, SearchResult, SearchResultResponse, TransactionDetailResponse,
+    TransactionSummariesResponse, TransactionSummary,
+};
+
+use crate::SequencerClient;
+
+impl SequencerClient {
+    /// Get explorer details for the block at `height`.
+    pub async fn get_explorer_block(&self, height: u64) -> anyhow::Result<BlockDetail<SeqTypes>> {
+        let res: BlockDetailResponse<SeqTypes> = self
+            .get(&format!("explorer/block/{height}"))
+            .await
+            .with_context(|| format!("getting explorer block {height}"))?;
+        Ok(res.block_detail)
+    }
+
+    /// Get up to `limit` block summaries, ending with the block at `from`, or the latest block if
+    /// `from` is `None`.
+    pub async fn get_explorer_blocks(
+        &self,
+        from: Option<u64>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<BlockSummary<SeqTypes>>> {
+        let path = match from {
+            Some(from) => format!("explorer/blocks/{from}/{limit}"),
+            None => format!("explorer/blocks/latest/{limit}"),
+        };
+        let res: BlockSummaryResponse<SeqTypes> = self
+            .get(&path)
+            .await
+            .context("getting explorer block summaries")?;
+        Ok(res.block_summaries)
+    }
+
+    /// Get explorer details for the transaction at position `offset` in the block at `height`.
+    pub async fn get_explorer_transaction(
+        &self,
+        height: u64,
+        offset: u64,
+    ) -> anyhow::Result<TransactionDetailResponse<SeqTypes>> {
+        self.get(&format!("explorer/transaction/{height}/{offset}"))
+            .await
+            .with_context(|| format!("getting explorer transaction {height}/{offset}"))
+    }
+
+    /// Get explorer details for the transaction with the given hash.
+    pub async fn get_explorer_transaction_by_hash(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> anyhow::Result<TransactionDetailResponse<SeqTypes>> {
+        self.get(&format!("explorer/transaction/hash/{hash}"))
+            .await
+            .with_context(|| format!("getting explorer transaction {hash}"))
+    }
+
+    /// Get up to `limit` transaction summaries, ending with the transaction at position `offset`
+    /// in block `height`, or the latest transaction if `from` is `None`.
+    ///
+    /// If `namespace` is given, only transactions in that namespace are returned.
+    pub async fn get_explorer_transactions(
+        &self,
+        from: Option<(u64, u64)>,
+        limit: usize,
+        namespace: Option<NamespaceId>,
+    ) -> anyhow::Result<Vec<TransactionSummary<SeqTypes>>> {
+        let mut path = match from {
+            Some((height, offset)) => {
+                format!("explorer/transactions/from/{height}/{offset}/{limit}")
+            },
+            None => format!("explorer/transactions/latest/{limit}"),
+        };
+        if let Some(ns) = namespace {
+            path = format!("{path}/namespace/{}", u32::from(ns));
+        }
+        let res: TransactionSummariesResponse<SeqTypes> = self
+            .get(&path)
+            .await
+            .context("getting explorer transaction summaries")?;
+        Ok(res.transaction_summaries)
+    }
+
+    /// Get an at-a-glance summary of the chain.
+    pub async fn get_explorer_summary(&self) -> anyhow::Result<ExplorerSummary<SeqTypes>> {
+        let res: ExplorerSummaryResponse<SeqTypes> = self
+            .get("explorer/explorer-summary")
+            .await
+            .context("getting explorer summary")?;
+        Ok(res.explorer_summary)
+    }
+
+    /// Search for blocks and transactions matching `query`.
+    pub async fn explorer_search(&self, query: &str) -> anyhow::Result<SearchResult<SeqTypes>> {
+        let res: SearchResultResponse<SeqTypes> = self
+            .get(&format!("explorer/search/{query}"))
+            .await
+            .with_context(|| format!("searching explorer for {query}"))?;
+        Ok(res.search_results)
+    }
+}
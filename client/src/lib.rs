@@ -1,13 +1,16 @@
-use std::time::Duration;
+use std::{future::Future, time::Duration};
 
 use alloy::primitives::Address;
 use anyhow::Context;
-use espresso_types::{FeeAccount, FeeAmount, FeeMerkleTree, Header};
+use espresso_types::{
+    v0_1::RewardMerkleTree, BlockMerkleTree, FeeAccount, FeeAmount, FeeMerkleTree, Header,
+};
 use futures::{stream::BoxStream, StreamExt};
 use jf_merkle_tree::{
     prelude::{MerkleProof, Sha3Node},
     MerkleTreeScheme,
 };
+use serde::{de::DeserializeOwned, Serialize};
 use surf_disco::{
     error::ClientError,
     socket::{Connection, Unsupported},
@@ -16,32 +19,98 @@ use surf_disco::{
 use tokio::time::sleep;
 use vbs::version::StaticVersion;
 
+mod availability;
+mod config;
+mod explorer;
+mod node;
+mod state;
+mod stream;
+mod submit;
+pub mod verified;
+
+pub use node::CurrentStakeTable;
+pub use verified::{TrustedCheckpoint, VerifiedSequencerClient};
+
 pub type SequencerApiVersion = StaticVersion<0, 1>;
 
 #[derive(Clone, Debug)]
-pub struct SequencerClient(surf_disco::Client<ClientError, SequencerApiVersion>);
+pub struct SequencerClient {
+    inner: surf_disco::Client<ClientError, SequencerApiVersion>,
+    retry: RetryPolicy,
+}
 
 pub type FeeMerkleProof = MerkleProof<FeeAmount, FeeAccount, Sha3Node, { FeeMerkleTree::ARITY }>;
+pub type RewardMerkleProof = <RewardMerkleTree as MerkleTreeScheme>::MembershipProof;
+pub type BlockMerkleProof = <BlockMerkleTree as MerkleTreeScheme>::MembershipProof;
+
+/// How a [`SequencerClient`] retries failed requests.
+///
+/// Requests are retried with exponential backoff: the delay starts at `base_delay` and is
+/// multiplied by `factor` after each failure, up to `max_delay`. After `max_retries` failed
+/// attempts the last error is returned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub factor: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            factor: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which fails after the first error.
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 1,
+            ..Default::default()
+        }
+    }
+
+    /// The delay to wait after `delay`.
+    pub fn backoff(&self, delay: Duration) -> Duration {
+        (delay * self.factor).min(self.max_delay)
+    }
+}
 
 impl SequencerClient {
     pub fn new(provider: Url) -> Self {
-        Self(surf_disco::Client::new(provider))
+        Self {
+            inner: surf_disco::Client::new(provider),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Use a custom retry policy for requests made by this client.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// The retry policy used by this client.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry
     }
 
     /// GET Block Height from the node
     pub async fn get_height(&self) -> anyhow::Result<u64> {
-        self.0
-            .get::<u64>("node/block-height")
-            .send()
+        self.get::<u64>("node/block-height")
             .await
             .context("getting Espresso block height")
     }
 
     /// Get the Number of Transactions
     pub async fn get_transaction_count(&self) -> anyhow::Result<u64> {
-        self.0
-            .get::<u64>("node/transactions/count")
-            .send()
+        self.get::<u64>("node/transactions/count")
             .await
             .context("getting Espresso transaction count")
     }
@@ -51,7 +120,7 @@ impl SequencerClient {
         &self,
         height: u64,
     ) -> anyhow::Result<BoxStream<'static, Result<Header, ClientError>>> {
-        self.0
+        self.inner
             .socket(&format!("availability/stream/headers/{height}"))
             .subscribe::<Header>()
             .await
@@ -64,7 +133,7 @@ impl SequencerClient {
         &self,
         height: u64,
     ) -> anyhow::Result<Connection<Header, Unsupported, ClientError, SequencerApiVersion>> {
-        self.0
+        self.inner
             .socket(&format!("availability/stream/blocks/{height}"))
             .subscribe()
             .await
@@ -88,37 +157,66 @@ impl SequencerClient {
         }
         // Block is non-zero, we can safely decrement to query the state as of the previous block.
         block -= 1;
-        // Download the Merkle path for this fee account at the specified block height. Transient errors
-        // are possible (for example, if we are fetching from the latest block, the block height might
-        // get incremented shortly before the state becomes available) so retry a few times.
+        // Download the Merkle path for this fee account at the specified block height.
+        let proof = self
+            .get::<FeeMerkleProof>(&format!("fee-state/{block}/{address:#x}"))
+            .await
+            .context("getting account balance")?;
+
+        // If the element in the Merkle path is missing -- there is no account with this address -- the
+        // balance is defined to be 0.
+        let balance = proof.elem().copied().unwrap_or(0.into());
+        Ok(balance)
+    }
+
+    /// Fetch and deserialize the resource at `path`.
+    ///
+    /// Transient errors are possible (for example, if we are fetching state from the latest block,
+    /// the block height might get incremented shortly before the state becomes available) so the
+    /// request is retried according to the client's [`RetryPolicy`].
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.retry(path, || self.inner.get::<T>(path).send()).await
+    }
+
+    /// Post `body` as JSON to `path` and deserialize the response.
+    ///
+    /// This is retried just like [`get`](Self::get), so it should only be used for idempotent
+    /// requests.
+    pub async fn post<T: DeserializeOwned, B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        self.retry(path, || async {
+            self.inner.post::<T>(path).body_json(body)?.send().await
+        })
+        .await
+    }
+
+    async fn retry<T, F, Fut>(&self, path: &str, f: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
         let mut retry = 0;
-        let max_retries = 10;
-        let proof = loop {
-            tracing::debug!(%address, block, retry, "fetching Espresso balance");
-            match self
-                .0
-                .get::<FeeMerkleProof>(&format!("fee-state/{block}/{address:#x}"))
-                .send()
-                .await
-            {
-                Ok(proof) => break proof,
+        let mut delay = self.retry.base_delay;
+        loop {
+            tracing::debug!(path, retry, "request to Espresso query service");
+            match f().await {
+                Ok(res) => return Ok(res),
                 Err(err) => {
-                    tracing::warn!("error getting account balance: {err:#}");
+                    tracing::warn!(path, retry, "error from Espresso query service: {err:#}");
                     retry += 1;
 
-                    if retry == max_retries {
-                        return Err(err).context("getting account balance");
+                    if retry >= self.retry.max_retries {
+                        return Err(err).context(format!("request to {path} failed"));
                     } else {
-                        sleep(Duration::from_millis(200)).await;
+                        sleep(delay).await;
+                        delay = self.retry.backoff(delay);
                     }
                 },
             }
-        };
-
-        // If the element in the Merkle path is missing -- there is no account with this address -- the
-        // balance is defined to be 0.
-        let balance = proof.elem().copied().unwrap_or(0.into());
-        Ok(balance)
+        }
     }
 }
 
@@ -138,4 +236,17 @@ mod tests {
             0.into()
         )
     }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy::default();
+        let mut delay = policy.base_delay;
+        for _ in 0..10 {
+            let next = policy.backoff(delay);
+            assert!(next >= delay);
+            assert!(next <= policy.max_delay);
+            delay = next;
+        }
+        assert_eq!(delay, policy.max_delay);
+    }
 }
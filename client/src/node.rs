@@ -0,0 +1,141 @@
+//! Typed access to the `node` API.
+
+use alloy::primitives::Address;
+use anyhow::Context;
+use espresso_types::{v0_3::Validator, Header, PubKey, SeqTypes};
+use hotshot_query_service::node::{self, SyncStatus, TimeWindowQueryData};
+use hotshot_types::{
+    data::{EpochNumber, VidShare},
+    PeerConfig,
+};
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::SequencerClient;
+
+/// Response of the `node/stake-table/current` endpoint.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CurrentStakeTable {
+    /// The current epoch, or `None` before epochs are enabled.
+    pub epoch: Option<EpochNumber>,
+    pub stake_table: Vec<PeerConfig<SeqTypes>>,
+}
+
+impl SequencerClient {
+    /// Get implementation-defined limits on the size of node API requests.
+    pub async fn get_node_limits(&self) -> anyhow::Result<node::Limits> {
+        self.get("node/limits").await.context("getting node limits")
+    }
+
+    /// Get the number of transactions in blocks `[from, to]`.
+    pub async fn get_transaction_count_range(&self, from: u64, to: u64) -> anyhow::Result<u64> {
+        self.get(&format!("node/transactions/count/{from}/{to}"))
+            .await
+            .with_context(|| format!("getting transaction count for blocks {from}..={to}"))
+    }
+
+    /// Get the total size in bytes of all payloads.
+    pub async fn get_total_payload_size(&self) -> anyhow::Result<u64> {
+        self.get("node/payloads/total-size")
+            .await
+            .context("getting total payload size")
+    }
+
+    /// Get the total size in bytes of the payloads in blocks `[from, to]`.
+    pub async fn get_payload_size_range(&self, from: u64, to: u64) -> anyhow::Result<u64> {
+        self.get(&format!("node/payloads/size/{from}/{to}"))
+            .await
+            .with_context(|| format!("getting payload size for blocks {from}..={to}"))
+    }
+
+    /// Get this node's VID share for the block at `height`.
+    pub async fn get_vid_share(&self, height: u64) -> anyhow::Result<VidShare> {
+        self.get(&format!("node/vid/share/{height}"))
+            .await
+            .with_context(|| format!("getting VID share {height}"))
+    }
+
+    /// Get the status of the node's sync with the rest of the network.
+    pub async fn get_sync_status(&self) -> anyhow::Result<SyncStatus> {
+        self.get("node/sync-status")
+            .await
+            .context("getting sync status")
+    }
+
+    /// Get the headers with timestamps in `[start, end)`.
+    ///
+    /// The response may be incomplete if the window is larger than the node's window limit or
+    /// not all headers in the window exist yet. In this case `next` is `None`, and the rest of the
+    /// window can be loaded with [`get_header_window_from`](Self::get_header_window_from).
+    pub async fn get_header_window(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> anyhow::Result<TimeWindowQueryData<Header>> {
+        self.get(&format!("node/header/window/{start}/{end}"))
+            .await
+            .with_context(|| format!("getting header window {start}..{end}"))
+    }
+
+    /// Get the headers from `height` up to the first header with a timestamp of at least `end`.
+    pub async fn get_header_window_from(
+        &self,
+        height: u64,
+        end: u64,
+    ) -> anyhow::Result<TimeWindowQueryData<Header>> {
+        self.get(&format!("node/header/window/from/{height}/{end}"))
+            .await
+            .with_context(|| format!("getting header window from {height} to {end}"))
+    }
+
+    /// Get all headers with timestamps in `[start, end)`, following up with additional requests
+    /// until the window is complete.
+    ///
+    /// If the end of the window is in the future, this waits until it has been produced.
+    pub async fn get_full_header_window(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> anyhow::Result<Vec<Header>> {
+        let mut res = self.get_header_window(start, end).await?;
+        let mut window = std::mem::take(&mut res.window);
+        while res.next.is_none() {
+            let prev_len = window.len();
+            res = match window.last() {
+                Some(last) => self.get_header_window_from(last.height() + 1, end).await?,
+                None => self.get_header_window(start, end).await?,
+            };
+            window.append(&mut res.window);
+            if res.next.is_none() && window.len() == prev_len {
+                // No progress; wait for more headers to be produced.
+                sleep(self.retry.base_delay).await;
+            }
+        }
+        Ok(window)
+    }
+
+    /// Get the stake table for `epoch`.
+    pub async fn get_stake_table(&self, epoch: u64) -> anyhow::Result<Vec<PeerConfig<SeqTypes>>> {
+        self.get(&format!("node/stake-table/{epoch}"))
+            .await
+            .with_context(|| format!("getting stake table for epoch {epoch}"))
+    }
+
+    /// Get the stake table for the current epoch.
+    pub async fn get_current_stake_table(&self) -> anyhow::Result<CurrentStakeTable> {
+        self.get("node/stake-table/current")
+            .await
+            .context("getting current stake table")
+    }
+
+    /// Get the validators for `epoch`, keyed by account address.
+    pub async fn get_validators(
+        &self,
+        epoch: u64,
+    ) -> anyhow::Result<IndexMap<Address, Validator<PubKey>>> {
+        self.get(&format!("node/validators/{epoch}"))
+            .await
+            .with_context(|| format!("getting validators for epoch {epoch}"))
+    }
+}
@@ -0,0 +1,186 @@
+//! Typed access to the merklized state (`fee-state`, `reward-state`, `block-state`) and `catchup`
+//! APIs.
+//!
+//! Proofs returned by these methods are not verified. Use
+//! [`VerifiedSequencerClient`](crate::VerifiedSequencerClient) to check them against a trusted
+//! header.
+
+use alloy::primitives::Address;
+use anyhow::Context;
+use committable::Commitment;
+use espresso_types::{
+    v0_1::{RewardAccountQueryData, RewardAmount, RewardMerkleTree},
+    v0_99::ChainConfig,
+    AccountQueryData, FeeAmount, Leaf2,
+};
+use hotshot_types::{data::ViewNumber, traits::node_implementation::ConsensusTime};
+
+use crate::{BlockMerkleProof, FeeMerkleProof, RewardMerkleProof, SequencerClient};
+
+impl SequencerClient {
+    /// Get the Merkle path for fee account `address` in the fee state after block `height`.
+    pub async fn get_fee_proof(
+        &self,
+        height: u64,
+        address: Address,
+    ) -> anyhow::Result<FeeMerkleProof> {
+        self.get(&format!("fee-state/{height}/{address:#x}"))
+            .await
+            .with_context(|| format!("getting fee proof for {address} at height {height}"))
+    }
+
+    /// Get the latest balance of fee account `address`, or `None` if the account does not exist.
+    pub async fn get_latest_fee_balance(
+        &self,
+        address: Address,
+    ) -> anyhow::Result<Option<FeeAmount>> {
+        self.get(&format!("fee-state/fee-balance/latest/{address:#x}"))
+            .await
+            .with_context(|| format!("getting latest fee balance for {address}"))
+    }
+
+    /// Get the latest block height for which the fee state is available.
+    pub async fn get_fee_state_height(&self) -> anyhow::Result<u64> {
+        self.get("fee-state/block-height")
+            .await
+            .context("getting fee state height")
+    }
+
+    /// Get the Merkle path for reward account `address` in the reward state after block `height`.
+    pub async fn get_reward_proof(
+        &self,
+        height: u64,
+        address: Address,
+    ) -> anyhow::Result<RewardMerkleProof> {
+        self.get(&format!("reward-state/{height}/{address:#x}"))
+            .await
+            .with_context(|| format!("getting reward proof for {address} at height {height}"))
+    }
+
+    /// Get the balance of reward account `address` after block `height`, or `None` if the
+    /// account does not exist.
+    pub async fn get_reward_balance(
+        &self,
+        height: u64,
+        address: Address,
+    ) -> anyhow::Result<Option<RewardAmount>> {
+        self.get(&format!(
+            "reward-state/reward-balance/{height}/{address:#x}"
+        ))
+        .await
+        .with_context(|| format!("getting reward balance for {address} at height {height}"))
+    }
+
+    /// Get the latest balance of reward account `address`, or `None` if the account does not
+    /// exist.
+    pub async fn get_latest_reward_balance(
+        &self,
+        address: Address,
+    ) -> anyhow::Result<Option<RewardAmount>> {
+        self.get(&format!("reward-state/reward-balance/latest/{address:#x}"))
+            .await
+            .with_context(|| format!("getting latest reward balance for {address}"))
+    }
+
+    /// Get the latest block height for which the reward state is available.
+    pub async fn get_reward_state_height(&self) -> anyhow::Result<u64> {
+        self.get("reward-state/block-height")
+            .await
+            .context("getting reward state height")
+    }
+
+    /// Get the Merkle path for the header at `index` in the block Merkle tree after block
+    /// `height`.
+    pub async fn get_block_proof(
+        &self,
+        height: u64,
+        index: u64,
+    ) -> anyhow::Result<BlockMerkleProof> {
+        self.get(&format!("block-state/{height}/{index}"))
+            .await
+            .with_context(|| format!("getting block proof for {index} at height {height}"))
+    }
+
+    /// Get the fee account `address` with a proof, from the state at `height` and `view`.
+    ///
+    /// This uses the `catchup` API, so `view` should be no older than the last decided view.
+    pub async fn get_catchup_account(
+        &self,
+        height: u64,
+        view: ViewNumber,
+        address: Address,
+    ) -> anyhow::Result<AccountQueryData> {
+        self.get(&format!(
+            "catchup/{height}/{}/account/{address:#x}",
+            view.u64()
+        ))
+        .await
+        .with_context(|| format!("catching up fee account {address} at height {height}"))
+    }
+
+    /// Get the reward account `address` with a proof, from the state at `height` and `view`.
+    ///
+    /// This uses the `catchup` API, so `view` should be no older than the last decided view.
+    pub async fn get_catchup_reward_account(
+        &self,
+        height: u64,
+        view: ViewNumber,
+        address: Address,
+    ) -> anyhow::Result<RewardAccountQueryData> {
+        self.get(&format!(
+            "catchup/{height}/{}/reward-account/{address:#x}",
+            view.u64()
+        ))
+        .await
+        .with_context(|| format!("catching up reward account {address} at height {height}"))
+    }
+
+    /// Get the subset of the reward Merkle tree containing `accounts`, from the state at `height`
+    /// and `view`.
+    pub async fn get_catchup_reward_accounts(
+        &self,
+        height: u64,
+        view: ViewNumber,
+        accounts: &[Address],
+    ) -> anyhow::Result<RewardMerkleTree> {
+        let accounts = accounts
+            .iter()
+            .copied()
+            .map(espresso_types::v0_1::RewardAccount)
+            .collect::<Vec<_>>();
+        self.post(
+            &format!("catchup/{height}/{}/reward-accounts", view.u64()),
+            &accounts,
+        )
+        .await
+        .with_context(|| format!("catching up reward accounts at height {height}"))
+    }
+
+    /// Get the block Merkle tree frontier from the state at `height` and `view`.
+    pub async fn get_blocks_frontier(
+        &self,
+        height: u64,
+        view: ViewNumber,
+    ) -> anyhow::Result<BlockMerkleProof> {
+        self.get(&format!("catchup/{height}/{}/blocks", view.u64()))
+            .await
+            .with_context(|| format!("catching up blocks frontier at height {height}"))
+    }
+
+    /// Get the full chain config with the given commitment.
+    pub async fn get_chain_config(
+        &self,
+        commitment: Commitment<ChainConfig>,
+    ) -> anyhow::Result<ChainConfig> {
+        self.get(&format!("catchup/chain-config/{commitment}"))
+            .await
+            .with_context(|| format!("getting chain config {commitment}"))
+    }
+
+    /// Get a chain of leaves proving that the block at `height` was decided.
+    pub async fn get_leaf_chain(&self, height: u64) -> anyhow::Result<Vec<Leaf2>> {
+        self.get(&format!("catchup/{height}/leafchain"))
+            .await
+            .with_context(|| format!("getting leaf chain for height {height}"))
+    }
+}
@@ -0,0 +1,162 @@
+//! Pagination and resumable subscriptions.
+
+use std::time::Duration;
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use serde::de::DeserializeOwned;
+use tokio::time::sleep;
+
+use crate::SequencerClient;
+
+impl SequencerClient {
+    /// Load the objects in `[from, until)` from a range endpoint, in pages of at most `page_size`.
+    ///
+    /// `path` is the prefix of the range endpoint, such that `{path}/{start}/{end}` returns the
+    /// objects in `[start, end)`. Pages are requested lazily as the stream is consumed, and each
+    /// page is retried according to the client's retry policy. The stream ends after the first
+    /// error.
+    pub fn paginate<T>(
+        &self,
+        path: impl Into<String>,
+        from: u64,
+        until: u64,
+        page_size: usize,
+    ) -> BoxStream<'static, anyhow::Result<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let client = self.clone();
+        let path = path.into();
+        let page_size = page_size.max(1) as u64;
+        stream::unfold(Some(from), move |start| {
+            let client = client.clone();
+            let path = path.clone();
+            async move {
+                let start = start.filter(|start| *start < until)?;
+                let end = until.min(start + page_size);
+                match client.get::<Vec<T>>(&format!("{path}/{start}/{end}")).await {
+                    Ok(page) => Some((Ok(page), Some(end))),
+                    Err(err) => Some((Err(err), None)),
+                }
+            }
+        })
+        .flat_map(|page| match page {
+            Ok(page) => stream::iter(page.into_iter().map(Ok)).boxed(),
+            Err(err) => stream::once(async move { Err(err) }).boxed(),
+        })
+        .boxed()
+    }
+
+    /// Subscribe to a stream of height-indexed objects which survives disconnects.
+    ///
+    /// `path` is the prefix of a streaming endpoint, such that `{path}/{height}` streams objects
+    /// starting from `height`, one per height. Whenever the connection fails or is closed by the server, the
+    /// client reconnects (with backoff according to its retry policy) starting from the height
+    /// after the last object it received. Objects the server replays are dropped, and if the
+    /// server skips ahead, the client reconnects to fetch the missing objects again, so the
+    /// returned stream never skips or repeats an object.
+    pub fn resumable_stream<T>(
+        &self,
+        path: impl Into<String>,
+        from: u64,
+        height: fn(&T) -> u64,
+    ) -> BoxStream<'static, T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let path = path.into();
+        self.resumable_stream_with(move |height| format!("{path}/{height}"), from, height)
+    }
+
+    /// Subscribe to a stream of height-indexed objects which survives disconnects.
+    ///
+    /// This is like [`resumable_stream`](Self::resumable_stream), but for endpoints where the
+    /// starting height is not the last path segment: `url(height)` gives the endpoint to stream
+    /// objects starting from `height`.
+    pub fn resumable_stream_with<T>(
+        &self,
+        url: impl Fn(u64) -> String + Send + 'static,
+        from: u64,
+        height: fn(&T) -> u64,
+    ) -> BoxStream<'static, T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        struct State<T> {
+            client: SequencerClient,
+            url: Box<dyn Fn(u64) -> String + Send>,
+            next: u64,
+            conn: Option<BoxStream<'static, Result<T, surf_disco::error::ClientError>>>,
+            delay: Duration,
+        }
+
+        let state = State {
+            delay: self.retry.base_delay,
+            client: self.clone(),
+            url: Box::new(url),
+            next: from,
+            conn: None,
+        };
+        stream::unfold(state, move |mut state| async move {
+            loop {
+                let url = (state.url)(state.next);
+                if state.conn.is_none() {
+                    match state.client.inner.socket(&url).subscribe::<T>().await {
+                        Ok(conn) => state.conn = Some(conn.boxed()),
+                        Err(err) => {
+                            tracing::warn!(
+                                %url,
+                                "error subscribing, will retry in {:?}: {err:#}",
+                                state.delay
+                            );
+                            sleep(state.delay).await;
+                            state.delay = state.client.retry.backoff(state.delay);
+                            continue;
+                        },
+                    }
+                }
+                let Some(conn) = state.conn.as_mut() else {
+                    continue;
+                };
+
+                match conn.next().await {
+                    Some(Ok(obj)) => {
+                        let obj_height = height(&obj);
+                        if obj_height < state.next {
+                            // We may receive duplicates if the server replays objects after a
+                            // reconnect; skip anything we have already yielded.
+                            continue;
+                        }
+                        if obj_height > state.next {
+                            // The server skipped ahead of where we asked it to start. Rather than
+                            // leave a gap, reconnect and ask for the missing objects again.
+                            tracing::warn!(
+                                %url,
+                                expected = state.next,
+                                got = obj_height,
+                                "gap in stream, reconnecting"
+                            );
+                        } else {
+                            state.next = obj_height + 1;
+                            state.delay = state.client.retry.base_delay;
+                            return Some((obj, state));
+                        }
+                    },
+                    Some(Err(err)) => {
+                        tracing::warn!(%url, "stream error, reconnecting: {err:#}");
+                    },
+                    None => {
+                        tracing::info!(%url, "stream closed by server, reconnecting");
+                    },
+                }
+                state.conn = None;
+                sleep(state.delay).await;
+                state.delay = state.client.retry.backoff(state.delay);
+            }
+        })
+        .boxed()
+    }
+}
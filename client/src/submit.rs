@@ -0,0 +1,114 @@
+//! Typed access to the `submit` API.
+
+use anyhow::{bail, Context};
+use committable::Commitment;
+use espresso_types::{Transaction, TransactionReceipt, TransactionStatus, TransactionSubmitResult};
+use futures::{stream::BoxStream, StreamExt};
+
+use crate::SequencerClient;
+
+impl SequencerClient {
+    /// Submit a transaction to the sequencer, returning its hash.
+    ///
+    /// Submission is not retried, since a request which failed on the client side may still have
+    /// reached the sequencer, and resubmitting would sequence a duplicate transaction. Callers
+    /// that want retries can check for inclusion with
+    /// [`get_transaction_status`](Self::get_transaction_status) before resubmitting.
+    pub async fn submit_transaction(
+        &self,
+        tx: &Transaction,
+    ) -> anyhow::Result<Commitment<Transaction>> {
+        self.inner
+            .post::<Commitment<Transaction>>("submit/submit")
+            .body_json(tx)?
+            .send()
+            .await
+            .context("submitting transaction")
+    }
+
+    /// Submit a batch of transactions to the sequencer.
+    ///
+    /// Each transaction is accepted or rejected independently. The result for each transaction is
+    /// returned in the same order as `txs`. Like
+    /// [`submit_transaction`](Self::submit_transaction), this is not retried.
+    pub async fn submit_transactions(
+        &self,
+        txs: &[Transaction],
+    ) -> anyhow::Result<Vec<TransactionSubmitResult>> {
+        self.inner
+            .post::<Vec<TransactionSubmitResult>>("submit/batch")
+            .body_json(&txs)?
+            .send()
+            .await
+            .context("submitting transaction batch")
+    }
+
+    /// Get the status of the transaction with the given hash.
+    ///
+    /// Pending and dropped statuses are only reported for transactions submitted through the
+    /// same node this client is connected to.
+    pub async fn get_transaction_status(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> anyhow::Result<TransactionStatus> {
+        self.get(&format!("submit/status/{hash}"))
+            .await
+            .with_context(|| format!("getting status of transaction {hash}"))
+    }
+
+    /// Get a receipt proving that the transaction with the given hash was sequenced.
+    ///
+    /// The receipt is not checked. Use [`TransactionReceipt::verify_inclusion`] and
+    /// [`TransactionReceipt::verify_block_proof`] to check it.
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> anyhow::Result<TransactionReceipt> {
+        self.get(&format!("submit/receipt/{hash}"))
+            .await
+            .with_context(|| format!("getting receipt for transaction {hash}"))
+    }
+
+    /// Subscribe to status updates for the transaction with the given hash.
+    ///
+    /// The stream yields the current status followed by each change, and ends once the status is
+    /// final.
+    pub async fn subscribe_transaction_status(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<TransactionStatus>>> {
+        let stream = self
+            .inner
+            .socket(&format!("submit/stream/status/{hash}"))
+            .subscribe::<TransactionStatus>()
+            .await
+            .with_context(|| format!("subscribing to status of transaction {hash}"))?;
+        Ok(stream
+            .map(|res| res.context("receiving transaction status"))
+            .boxed())
+    }
+
+    /// Wait until the transaction with the given hash is sequenced, and get its receipt.
+    ///
+    /// Fails if the sequencer reports that the transaction was dropped, or if the status stream
+    /// ends before the transaction is sequenced.
+    pub async fn wait_for_transaction(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> anyhow::Result<TransactionReceipt> {
+        let mut statuses = self.subscribe_transaction_status(hash).await?;
+        while let Some(status) = statuses.next().await {
+            match status? {
+                TransactionStatus::Included { height, index } => {
+                    tracing::debug!(%hash, height, index, "transaction sequenced");
+                    return self.get_transaction_receipt(hash).await;
+                },
+                TransactionStatus::Dropped { submitted_at } => {
+                    bail!("transaction {hash} submitted at {submitted_at} was dropped");
+                },
+                status => tracing::debug!(%hash, ?status, "waiting for transaction"),
+            }
+        }
+        bail!("status stream for transaction {hash} ended before it was sequenced")
+    }
+}
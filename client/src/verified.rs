@@ -0,0 +1,548 @@
+//! Verified light-client mode for [`SequencerClient`].
+//!
+//! [`VerifiedSequencerClient`] talks to an untrusted query service, but never returns data it has
+//! not checked. It maintains a chain of trusted headers, seeded from a [`TrustedCheckpoint`]. A
+//! header newer than the latest trusted header is accepted only if the server can produce a leaf
+//! chain deciding it, with every QC signed by a quorum of the trusted stake table. Older headers,
+//! fee and reward balances and namespace payloads are accepted only with a Merkle or namespace
+//! proof relative to an already trusted header.
+
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
+
+use alloy::primitives::{Address, U256};
+use anyhow::{bail, ensure, Context};
+use async_lock::RwLock;
+use committable::Committable;
+use espresso_types::{
+    v0_1::{RewardAccount, RewardAmount, RewardMerkleTree},
+    BlockMerkleTree, EpochVersion, FeeAccount, FeeAmount, FeeMerkleTree, FeeVersion, Header, Leaf2,
+    NamespaceId, NamespaceProofQueryData, SeqTypes, SequencerVersions, Transaction, V0_1,
+};
+use futures::{stream::BoxStream, try_join, StreamExt};
+use hotshot_query_service::availability::{LeafQueryData, VidCommonQueryData};
+use hotshot_types::{
+    light_client::{compute_stake_table_commitment, LightClientState, StakeTableState},
+    message::UpgradeLock,
+    stake_table,
+    traits::{
+        block_contents::BlockHeader, node_implementation::ConsensusTime,
+        signature_key::StakeTableEntryType,
+    },
+    utils::{epoch_from_block_number, verify_leaf_chain},
+    PeerConfig,
+};
+use jf_merkle_tree::{MerkleCommitment, MerkleTreeScheme, UniversalMerkleTreeScheme};
+use vbs::version::StaticVersionType;
+
+use crate::{BlockMerkleProof, FeeMerkleProof, RewardMerkleProof, SequencerClient};
+
+/// A stake table, as used to verify QCs.
+pub type StakeTable = Vec<PeerConfig<SeqTypes>>;
+
+/// A header and stake table which the client trusts without verification.
+///
+/// All data returned by a [`VerifiedSequencerClient`] is ultimately verified relative to the
+/// checkpoint it was created with, so the checkpoint must come from a trusted source: an operator
+/// supplied configuration, a node the caller runs themselves, or the light client contract (see
+/// [`TrustedCheckpoint::from_light_client_state`]).
+#[derive(Clone, Debug)]
+pub struct TrustedCheckpoint {
+    /// The trusted header.
+    pub header: Header,
+    /// The stake table which certifies leaves in the epoch of `header`.
+    pub stake_table: StakeTable,
+}
+
+impl TrustedCheckpoint {
+    /// Create a checkpoint from the finalized state of the light client contract.
+    ///
+    /// The light client contract only stores commitments, so the corresponding header and stake
+    /// table are downloaded from the (untrusted) `client` and checked against `state` and
+    /// `stake_table_state` before they are accepted.
+    pub async fn from_light_client_state(
+        client: &SequencerClient,
+        state: LightClientState,
+        stake_table_state: StakeTableState,
+        stake_table_capacity: usize,
+        epoch: Option<u64>,
+    ) -> anyhow::Result<Self> {
+        let leaf: LeafQueryData<SeqTypes> = client
+            .get(&format!("availability/leaf/{}", state.block_height))
+            .await
+            .context("fetching checkpoint leaf")?;
+        let view = leaf.leaf().view_number();
+        ensure!(
+            view.u64() == state.view_number,
+            "checkpoint leaf has view {view:?}, but light client state has view {}",
+            state.view_number
+        );
+        let header = leaf.header().clone();
+        let header_state = header
+            .get_light_client_state(view)
+            .context("computing light client state of checkpoint header")?;
+        ensure!(
+            header_state == state,
+            "checkpoint header {} does not match light client state",
+            header.height()
+        );
+
+        let stake_table: StakeTable = match epoch {
+            Some(epoch) => client.get(&format!("node/stake-table/{epoch}")).await,
+            None => client
+                .get_current_stake_table()
+                .await
+                .map(|current| current.stake_table),
+        }
+        .context("fetching checkpoint stake table")?;
+        verify_stake_table(&stake_table, &stake_table_state, stake_table_capacity)?;
+
+        Ok(Self {
+            header,
+            stake_table,
+        })
+    }
+}
+
+/// Check that `stake_table` matches the commitment `expected`, as stored in the light client
+/// contract.
+pub fn verify_stake_table(
+    stake_table: &[PeerConfig<SeqTypes>],
+    expected: &StakeTableState,
+    stake_table_capacity: usize,
+) -> anyhow::Result<()> {
+    let actual = compute_stake_table_commitment(stake_table, stake_table_capacity)
+        .context("computing stake table commitment")?;
+    ensure!(
+        actual == *expected,
+        "stake table commitment mismatch: expected {expected:?}, got {actual:?}"
+    );
+    Ok(())
+}
+
+/// The stake required to form a QC from `stake_table`.
+pub fn success_threshold(stake_table: &[PeerConfig<SeqTypes>]) -> U256 {
+    stake_table::success_threshold(
+        stake_table
+            .iter()
+            .map(|peer| peer.stake_table_entry.stake())
+            .sum(),
+    )
+}
+
+/// Verify that `leaf_chain` decides the leaf at `height`, with QCs signed by `stake_table`.
+///
+/// QC signatures commit to the protocol version of the view they were formed in, so the chain is
+/// checked with the version of the headers it contains. A chain which spans a protocol upgrade is
+/// rejected; the caller can instead verify a chain for a later height.
+pub async fn verify_leaf_chain_at(
+    leaf_chain: Vec<Leaf2>,
+    stake_table: &[PeerConfig<SeqTypes>],
+    height: u64,
+) -> anyhow::Result<Leaf2> {
+    let mut versions = leaf_chain.iter().map(|leaf| leaf.block_header().version());
+    let version = versions.next().context("empty leaf chain")?;
+    ensure!(
+        versions.all(|v| v == version),
+        "leaf chain at height {height} spans a protocol upgrade"
+    );
+
+    let threshold = success_threshold(stake_table);
+    match version {
+        v if v == V0_1::VERSION => {
+            verify_leaf_chain(
+                leaf_chain,
+                stake_table,
+                threshold,
+                height,
+                &UpgradeLock::<SeqTypes, SequencerVersions<V0_1, V0_1>>::new(),
+            )
+            .await
+        },
+        v if v == FeeVersion::VERSION => {
+            verify_leaf_chain(
+                leaf_chain,
+                stake_table,
+                threshold,
+                height,
+                &UpgradeLock::<SeqTypes, SequencerVersions<FeeVersion, FeeVersion>>::new(),
+            )
+            .await
+        },
+        v if v == EpochVersion::VERSION => {
+            verify_leaf_chain(
+                leaf_chain,
+                stake_table,
+                threshold,
+                height,
+                &UpgradeLock::<SeqTypes, SequencerVersions<EpochVersion, EpochVersion>>::new(),
+            )
+            .await
+        },
+        v => bail!("unsupported protocol version {v}"),
+    }
+}
+
+#[derive(Debug)]
+struct TrustedState {
+    /// The most recent header verified by the client.
+    latest: Header,
+    /// Stake tables for each epoch the client knows about.
+    ///
+    /// Before epochs are enabled, there is a single stake table stored under epoch 0.
+    stake_tables: BTreeMap<u64, StakeTable>,
+}
+
+/// A [`SequencerClient`] which verifies all data received from the server.
+///
+/// See the [module-level documentation](self) for details.
+#[derive(Clone, Debug)]
+pub struct VerifiedSequencerClient {
+    client: SequencerClient,
+    epoch_height: Option<u64>,
+    state: Arc<RwLock<TrustedState>>,
+}
+
+impl VerifiedSequencerClient {
+    /// Create a verifying client starting from a trusted checkpoint.
+    ///
+    /// `epoch_height` must be set if and only if the chain has epochs enabled, in which case the
+    /// checkpoint stake table is used for the epoch containing the checkpoint header.
+    pub fn new(
+        client: SequencerClient,
+        checkpoint: TrustedCheckpoint,
+        epoch_height: Option<u64>,
+    ) -> Self {
+        let epoch = Self::epoch_of(epoch_height, checkpoint.header.height());
+        Self {
+            client,
+            epoch_height,
+            state: Arc::new(RwLock::new(TrustedState {
+                latest: checkpoint.header,
+                stake_tables: [(epoch, checkpoint.stake_table)].into(),
+            })),
+        }
+    }
+
+    /// The underlying, unverified client.
+    pub fn inner(&self) -> &SequencerClient {
+        &self.client
+    }
+
+    /// The most recent header this client has verified.
+    pub async fn latest_trusted_header(&self) -> Header {
+        self.state.read().await.latest.clone()
+    }
+
+    /// Add a trusted stake table for `epoch`.
+    ///
+    /// Leaves from an epoch other than that of the checkpoint can only be verified once the stake
+    /// table for that epoch is known.
+    pub async fn insert_stake_table(&self, epoch: u64, stake_table: StakeTable) {
+        self.state
+            .write()
+            .await
+            .stake_tables
+            .insert(epoch, stake_table);
+    }
+
+    /// Download the stake table for `epoch` and verify it against a commitment from the light
+    /// client contract.
+    pub async fn fetch_stake_table(
+        &self,
+        epoch: u64,
+        expected: &StakeTableState,
+        stake_table_capacity: usize,
+    ) -> anyhow::Result<()> {
+        let stake_table: StakeTable = self
+            .client
+            .get(&format!("node/stake-table/{epoch}"))
+            .await
+            .context("fetching stake table")?;
+        verify_stake_table(&stake_table, expected, stake_table_capacity)?;
+        self.insert_stake_table(epoch, stake_table).await;
+        Ok(())
+    }
+
+    /// Get the header at `height`, verified relative to the trusted header chain.
+    ///
+    /// If `height` is beyond the latest trusted header, this will advance the trusted header
+    /// chain, which requires `height` to be decided by a leaf chain available from the server.
+    pub async fn get_header(&self, height: u64) -> anyhow::Result<Header> {
+        let latest = self.latest_trusted_header().await;
+        match height.cmp(&latest.height()) {
+            Ordering::Equal => Ok(latest),
+            Ordering::Greater => self.advance(height).await,
+            Ordering::Less => {
+                let header: Header = self
+                    .client
+                    .get(&format!("availability/header/{height}"))
+                    .await
+                    .context("fetching header")?;
+                self.verify_ancestor(&latest, &header).await?;
+                Ok(header)
+            },
+        }
+    }
+
+    /// Advance the trusted header chain to `height`.
+    ///
+    /// Fetches a leaf chain deciding the block at `height` and checks all of its QCs against the
+    /// stake table for the corresponding epoch. The newly trusted header must also commit to the
+    /// previous trusted header in its block Merkle tree, so the trusted chain can never fork.
+    pub async fn advance(&self, height: u64) -> anyhow::Result<Header> {
+        let epoch = Self::epoch_of(self.epoch_height, height);
+        let stake_table = self
+            .state
+            .read()
+            .await
+            .stake_tables
+            .get(&epoch)
+            .cloned()
+            .with_context(|| format!("no trusted stake table for epoch {epoch}"))?;
+
+        let leaf_chain: Vec<Leaf2> = self
+            .client
+            .get(&format!("catchup/{height}/leafchain"))
+            .await
+            .context("fetching leaf chain")?;
+        let leaf = verify_leaf_chain_at(leaf_chain, &stake_table, height)
+            .await
+            .with_context(|| format!("failed to verify leaf chain at height {height}"))?;
+        let header = leaf.block_header().clone();
+        ensure!(
+            header.height() == height,
+            "leaf chain decided header {} instead of {height}",
+            header.height()
+        );
+
+        // Check the new header against the trusted chain without holding the lock, since this
+        // requires a round trip to the server. If another task advanced the chain in the meantime,
+        // check again against the new latest header.
+        let mut ancestor = self.latest_trusted_header().await;
+        loop {
+            if ancestor.height() >= height {
+                // The trusted chain has already advanced past `height`; `header` is still valid
+                // since it was decided by a verified leaf chain.
+                return Ok(header);
+            }
+            self.verify_ancestor(&header, &ancestor)
+                .await
+                .context("new header does not extend trusted chain")?;
+
+            let mut state = self.state.write().await;
+            if state.latest.commit() == ancestor.commit() {
+                state.latest = header.clone();
+                return Ok(header);
+            }
+            ancestor = state.latest.clone();
+        }
+    }
+
+    /// Subscribe to a stream of headers starting at `height`, verifying each one before it is
+    /// yielded.
+    pub async fn subscribe_headers(
+        &self,
+        height: u64,
+    ) -> anyhow::Result<BoxStream<'static, anyhow::Result<Header>>> {
+        let headers = self.client.subscribe_headers(height).await?;
+        let client = self.clone();
+        Ok(headers
+            .then(move |res| {
+                let client = client.clone();
+                async move {
+                    let header = res.context("receiving header")?;
+                    let verified = client.get_header(header.height()).await?;
+                    ensure!(
+                        verified.commit() == header.commit(),
+                        "streamed header {} does not match verified header",
+                        header.height()
+                    );
+                    Ok(verified)
+                }
+            })
+            .boxed())
+    }
+
+    /// Get the fee balance for a given account at a given block height, defaulting to current
+    /// balance.
+    ///
+    /// The balance is verified against the fee Merkle root of a trusted header.
+    pub async fn get_espresso_balance(
+        &self,
+        address: Address,
+        block: Option<u64>,
+    ) -> anyhow::Result<FeeAmount> {
+        let block = match block {
+            Some(block) => block,
+            None => self.client.get_height().await?,
+        };
+        // As of block zero the state is empty, and the balance will be zero.
+        if block == 0 {
+            return Ok(0.into());
+        }
+        // The state as of `block` is the state after applying the header at height `block - 1`.
+        let header = self.get_header(block - 1).await?;
+        let proof: FeeMerkleProof = self
+            .client
+            .get(&format!("fee-state/{}/{address:#x}", block - 1))
+            .await
+            .context("fetching fee account proof")?;
+
+        if let Some(balance) = proof.elem() {
+            FeeMerkleTree::verify(
+                header.fee_merkle_tree_root().digest(),
+                FeeAccount(address),
+                &proof,
+            )
+            .context("malformed fee membership proof")?
+            .or_else(|_| bail!("invalid fee membership proof"))?;
+            Ok(*balance)
+        } else {
+            ensure!(
+                FeeMerkleTree::from_commitment(header.fee_merkle_tree_root())
+                    .non_membership_verify(FeeAccount(address), &proof)
+                    .context("malformed fee non-membership proof")?,
+                "invalid fee non-membership proof"
+            );
+            Ok(0.into())
+        }
+    }
+
+    /// Get the reward balance for a given account as of the header at `height`.
+    ///
+    /// The balance is verified against the reward Merkle root of that header.
+    pub async fn get_reward_balance(
+        &self,
+        address: Address,
+        height: u64,
+    ) -> anyhow::Result<RewardAmount> {
+        let header = self.get_header(height).await?;
+        let proof: RewardMerkleProof = self
+            .client
+            .get(&format!("reward-state/{height}/{address:#x}"))
+            .await
+            .context("fetching reward account proof")?;
+
+        if let Some(balance) = proof.elem() {
+            RewardMerkleTree::verify(
+                header.reward_merkle_tree_root().digest(),
+                RewardAccount(address),
+                &proof,
+            )
+            .context("malformed reward membership proof")?
+            .or_else(|_| bail!("invalid reward membership proof"))?;
+            Ok(*balance)
+        } else {
+            ensure!(
+                RewardMerkleTree::from_commitment(header.reward_merkle_tree_root())
+                    .non_membership_verify(RewardAccount(address), &proof)
+                    .context("malformed reward non-membership proof")?,
+                "invalid reward non-membership proof"
+            );
+            Ok(U256::ZERO.into())
+        }
+    }
+
+    /// Get the transactions in namespace `ns` of the block at `height`.
+    ///
+    /// The transactions are checked with a namespace proof against the payload commitment and
+    /// namespace table of the trusted header, so the result is guaranteed to be complete.
+    pub async fn get_namespace_transactions(
+        &self,
+        height: u64,
+        ns: NamespaceId,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        let ns_id = u32::from(ns);
+        let (header, data, common) = try_join!(
+            self.get_header(height),
+            self.client.get::<NamespaceProofQueryData>(&format!(
+                "availability/block/{height}/namespace/{ns_id}"
+            )),
+            self.client
+                .get::<VidCommonQueryData<SeqTypes>>(&format!("availability/vid/common/{height}")),
+        )?;
+
+        let Some(proof) = data.proof else {
+            // The server claims the namespace is not present in this block. Check this against the
+            // namespace table, which is committed to by the header.
+            ensure!(
+                header.ns_table().find_ns_id(&ns).is_none(),
+                "server omitted proof for namespace {ns_id} which is present in block {height}"
+            );
+            return Ok(vec![]);
+        };
+        let (transactions, proven_ns) = proof
+            .verify(
+                header.ns_table(),
+                &header.payload_commitment(),
+                common.common(),
+            )
+            .context("invalid namespace proof")?;
+        ensure!(
+            proven_ns == ns,
+            "namespace proof is for namespace {proven_ns}, expected {ns_id}"
+        );
+        ensure!(
+            transactions == data.transactions,
+            "server returned transactions which do not match namespace proof"
+        );
+        Ok(transactions)
+    }
+
+    /// Check that `header` is an ancestor of the trusted header `trusted`.
+    async fn verify_ancestor(&self, trusted: &Header, header: &Header) -> anyhow::Result<()> {
+        ensure!(
+            header.height() < trusted.height(),
+            "header {} is not an ancestor of header {}",
+            header.height(),
+            trusted.height()
+        );
+        let proof: BlockMerkleProof = self
+            .client
+            .get(&format!(
+                "block-state/{}/{}",
+                trusted.height(),
+                header.height()
+            ))
+            .await
+            .context("fetching block Merkle proof")?;
+        BlockMerkleTree::verify(
+            trusted.block_merkle_tree_root().digest(),
+            header.height(),
+            &proof,
+        )
+        .context("malformed block Merkle proof")?
+        .or_else(|_| bail!("invalid block Merkle proof"))?;
+        ensure!(
+            proof.elem() == Some(&header.commit()),
+            "block Merkle proof is for wrong header: {:?} != {}",
+            proof.elem(),
+            header.commit()
+        );
+        Ok(())
+    }
+
+    fn epoch_of(epoch_height: Option<u64>, height: u64) -> u64 {
+        epoch_height
+            .map(|epoch_height| epoch_from_block_number(height, epoch_height))
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_empty_leaf_chain() {
+        verify_leaf_chain_at(vec![], &[], 0).await.unwrap_err();
+    }
+
+    #[test]
+    fn test_epoch_of() {
+        assert_eq!(VerifiedSequencerClient::epoch_of(None, 100), 0);
+        assert_eq!(VerifiedSequencerClient::epoch_of(Some(10), 1), 1);
+        assert_eq!(VerifiedSequencerClient::epoch_of(Some(10), 10), 1);
+        assert_eq!(VerifiedSequencerClient::epoch_of(Some(10), 11), 2);
+    }
+}
@@ -14,7 +14,7 @@ use cdn_broker::{reexports::def::hook::NoMessageHook, Broker};
 use cdn_marshal::Marshal;
 use hotshot::{
     helpers::initialize_logging,
-    traits::implementations::{KeyPair, TestingDef, WrappedSignatureKey},
+    traits::implementations::{CdnSigningKey, KeyPair, TestingDef, WrappedSignatureKey},
     types::SignatureKey,
 };
 use hotshot_example_types::{node_types::TestVersions, state_types::TestTypes};
@@ -79,7 +79,7 @@ async fn main() {
 
                 keypair: KeyPair {
                     public_key: WrappedSignatureKey(broker_public_key),
-                    private_key: broker_private_key.clone(),
+                    private_key: CdnSigningKey::Local(broker_private_key.clone()),
                 },
 
                 user_message_hook: NoMessageHook,
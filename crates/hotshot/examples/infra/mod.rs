@@ -24,9 +24,9 @@ use futures::StreamExt;
 use hotshot::{
     traits::{
         implementations::{
-            derive_libp2p_multiaddr, derive_libp2p_peer_id, CdnMetricsValue, CdnTopic,
-            CombinedNetworks, Libp2pMetricsValue, Libp2pNetwork, PushCdnNetwork,
-            WrappedSignatureKey,
+            derive_libp2p_keypair, derive_libp2p_multiaddr, derive_libp2p_peer_id,
+            CdnMetricsValue, CdnSigningKey, CdnTopic, CombinedNetworks, Libp2pMetricsValue, Libp2pNetwork,
+            PushCdnNetwork, WrappedSignatureKey,
         },
         BlockPayload, NodeImplementation,
     },
@@ -62,6 +62,7 @@ use hotshot_types::{
         election::Membership,
         network::ConnectedNetwork,
         node_implementation::{ConsensusTime, NodeType, Versions},
+        signer::LocalSigner,
         states::TestableState,
         storage::storage_add_drb_result,
     },
@@ -638,7 +639,7 @@ where
         // Convert to the Push-CDN-compatible type
         let keypair = KeyPair {
             public_key: WrappedSignatureKey(validator_config.public_key.clone()),
-            private_key: validator_config.private_key.clone(),
+            private_key: CdnSigningKey::Local(validator_config.private_key.clone()),
         };
 
         // See if we should be DA, subscribe to the DA topic if so
@@ -724,7 +725,12 @@ where
     ) -> Libp2pDaRun<TYPES> {
         // Extrapolate keys for ease of use
         let public_key = &validator_config.public_key;
-        let private_key = &validator_config.private_key;
+        let keypair = derive_libp2p_keypair::<TYPES::SignatureKey>(&validator_config.private_key)
+            .expect("failed to derive libp2p keypair");
+        let signer = LocalSigner::<TYPES>::new(
+            validator_config.private_key.clone(),
+            validator_config.state_private_key.clone(),
+        );
 
         // In an example, we can calculate the libp2p bind address as a function
         // of the advertise address.
@@ -762,7 +768,8 @@ where
             RequestResponseConfig::default(),
             bind_address,
             public_key,
-            private_key,
+            keypair,
+            &signer,
             Libp2pMetricsValue::default(),
         )
         .await
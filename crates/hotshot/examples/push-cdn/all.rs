@@ -17,7 +17,7 @@ use cdn_broker::{
 use cdn_marshal::Marshal;
 use hotshot::{
     helpers::initialize_logging,
-    traits::implementations::{TestingDef, WrappedSignatureKey},
+    traits::implementations::{CdnSigningKey, TestingDef, WrappedSignatureKey},
     types::SignatureKey,
 };
 use hotshot_example_types::{node_types::TestVersions, state_types::TestTypes};
@@ -90,7 +90,7 @@ async fn main() {
 
                 keypair: KeyPair {
                     public_key: WrappedSignatureKey(broker_public_key),
-                    private_key: broker_private_key.clone(),
+                    private_key: CdnSigningKey::Local(broker_private_key.clone()),
                 },
 
                 user_message_hook: NoMessageHook,
@@ -9,7 +9,9 @@
 use anyhow::Result;
 use cdn_broker::{reexports::def::hook::NoMessageHook, Broker, Config};
 use clap::Parser;
-use hotshot::traits::implementations::{KeyPair, ProductionDef, WrappedSignatureKey};
+use hotshot::traits::implementations::{
+    CdnSigningKey, KeyPair, ProductionDef, WrappedSignatureKey,
+};
 use hotshot_example_types::node_types::TestTypes;
 use hotshot_types::traits::{node_implementation::NodeType, signature_key::SignatureKey};
 use sha2::Digest;
@@ -100,7 +102,7 @@ async fn main() -> Result<()> {
         metrics_bind_endpoint: args.metrics_bind_endpoint,
         keypair: KeyPair {
             public_key: WrappedSignatureKey(public_key),
-            private_key,
+            private_key: CdnSigningKey::Local(private_key),
         },
 
         user_message_hook: NoMessageHook,
@@ -69,6 +69,7 @@ use hotshot_types::{
         network::ConnectedNetwork,
         node_implementation::{ConsensusTime, NodeType},
         signature_key::SignatureKey,
+        signer::{ConsensusSigner, LocalSigner},
         states::ValidatedState,
     },
     utils::genesis_epoch_from_version,
@@ -106,11 +107,8 @@ pub struct SystemContext<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versi
     /// The public key of this node
     public_key: TYPES::SignatureKey,
 
-    /// The private key of this node
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
-
-    /// The private key to sign the light client state
-    state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+    /// Signs votes, proposals and light client states
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Configuration items for this hotshot instance
     pub config: HotShotConfig<TYPES>,
@@ -171,8 +169,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> Clone
     fn clone(&self) -> Self {
         Self {
             public_key: self.public_key.clone(),
-            private_key: self.private_key.clone(),
-            state_private_key: self.state_private_key.clone(),
+            signer: Arc::clone(&self.signer),
             config: self.config.clone(),
             network: Arc::clone(&self.network),
             membership_coordinator: self.membership_coordinator.clone(),
@@ -218,14 +215,45 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
         metrics: ConsensusMetricsValue,
         storage: I::Storage,
         marketplace_config: MarketplaceConfig<TYPES, I>,
+    ) -> Arc<Self> {
+        Self::new_with_signer(
+            public_key,
+            Arc::new(LocalSigner::new(private_key, state_private_key)),
+            nonce,
+            config,
+            memberships,
+            network,
+            initializer,
+            metrics,
+            storage,
+            marketplace_config,
+        )
+        .await
+    }
+
+    /// Creates a new [`Arc<SystemContext>`] which signs through `signer`, so that this instance
+    /// never holds the node's private keys.
+    ///
+    /// See [`Self::new`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new_with_signer(
+        public_key: TYPES::SignatureKey,
+        signer: Arc<dyn ConsensusSigner<TYPES>>,
+        nonce: u64,
+        config: HotShotConfig<TYPES>,
+        memberships: EpochMembershipCoordinator<TYPES>,
+        network: Arc<I::Network>,
+        initializer: HotShotInitializer<TYPES>,
+        metrics: ConsensusMetricsValue,
+        storage: I::Storage,
+        marketplace_config: MarketplaceConfig<TYPES, I>,
     ) -> Arc<Self> {
         let internal_chan = broadcast(EVENT_CHANNEL_SIZE);
         let external_chan = broadcast(EXTERNAL_EVENT_CHANNEL_SIZE);
 
         Self::new_from_channels(
             public_key,
-            private_key,
-            state_private_key,
+            signer,
             nonce,
             config,
             memberships,
@@ -250,8 +278,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
     #[allow(clippy::too_many_arguments, clippy::type_complexity)]
     pub async fn new_from_channels(
         public_key: TYPES::SignatureKey,
-        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
-        state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+        signer: Arc<dyn ConsensusSigner<TYPES>>,
         nonce: u64,
         config: HotShotConfig<TYPES>,
         membership_coordinator: EpochMembershipCoordinator<TYPES>,
@@ -378,8 +405,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
             consensus: OuterConsensus::new(consensus),
             instance_state: Arc::new(instance_state),
             public_key,
-            private_key,
-            state_private_key,
+            signer,
             config,
             start_view: initializer.start_view,
             start_epoch: initializer.start_epoch,
@@ -513,7 +539,25 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
         &self,
         transaction: TYPES::Transaction,
     ) -> Result<(), HotShotError<TYPES>> {
-        trace!("Adding transaction to our own queue");
+        self.publish_transactions_async(vec![transaction]).await
+    }
+
+    /// Publishes a batch of transactions asynchronously to the network.
+    ///
+    /// The view, epoch and DA committee are looked up once for the whole batch, and the
+    /// transactions are broadcast one after another by a single background task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a transaction cannot be serialized or the DA committee cannot be
+    /// determined; does not return an error if the transactions couldn't be published to the
+    /// network
+    #[instrument(skip(self, transactions), err, target = "SystemContext", fields(id = self.id))]
+    pub async fn publish_transactions_async(
+        &self,
+        transactions: Vec<TYPES::Transaction>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        trace!("Adding transactions to our own queue");
 
         let api = self.clone();
 
@@ -522,17 +566,24 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
         let epoch = consensus_reader.cur_epoch();
         drop(consensus_reader);
 
-        // Wrap up a message
-        let message_kind: DataMessage<TYPES> =
-            DataMessage::SubmitTransaction(transaction.clone(), view_number);
-        let message = Message {
-            sender: api.public_key.clone(),
-            kind: MessageKind::from(message_kind),
-        };
+        // Wrap up a message for each transaction
+        let mut serialized_messages = Vec::with_capacity(transactions.len());
+        for transaction in &transactions {
+            let message_kind: DataMessage<TYPES> =
+                DataMessage::SubmitTransaction(transaction.clone(), view_number);
+            let message = Message {
+                sender: api.public_key.clone(),
+                kind: MessageKind::from(message_kind),
+            };
 
-        let serialized_message = self.upgrade_lock.serialize(&message).await.map_err(|err| {
-            HotShotError::FailedToSerialize(format!("failed to serialize transaction: {err}"))
-        })?;
+            let serialized_message =
+                self.upgrade_lock.serialize(&message).await.map_err(|err| {
+                    HotShotError::FailedToSerialize(format!(
+                        "failed to serialize transaction: {err}"
+                    ))
+                })?;
+            serialized_messages.push(serialized_message);
+        }
 
         let membership = match api.membership_coordinator.membership_for_epoch(epoch).await {
             Ok(m) => m,
@@ -540,7 +591,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
         };
 
         spawn(async move {
-            let memberships_da_committee_members = membership
+            let memberships_da_committee_members: Vec<_> = membership
                 .da_committee_members(view_number)
                 .await
                 .iter()
@@ -551,22 +602,22 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
                 // TODO We should have a function that can return a network error if there is one
                 // but first we'd need to ensure our network implementations can support that
                 // (and not hang instead)
-
-                // version <0, 1> currently fixed; this is the same as VERSION_0_1,
-                // and will be updated to be part of SystemContext. I wanted to use associated
-                // constants in NodeType, but that seems to be unavailable in the current Rust.
-                api
-                    .network.da_broadcast_message(
-                        serialized_message,
-                        memberships_da_committee_members,
-                        BroadcastDelay::None,
-                    ),
+                async {
+                    for serialized_message in serialized_messages {
+                        let _ = api
+                            .network
+                            .da_broadcast_message(
+                                serialized_message,
+                                memberships_da_committee_members.clone(),
+                                BroadcastDelay::None,
+                            )
+                            .await;
+                    }
+                },
                 api
                     .send_external_event(Event {
                         view_number,
-                        event: EventType::Transactions {
-                            transactions: vec![transaction],
-                        },
+                        event: EventType::Transactions { transactions },
                     }),
             }
         });
@@ -658,10 +709,51 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> SystemContext<T
         ),
         HotShotError<TYPES>,
     > {
-        let hotshot = Self::new(
+        Self::init_with_signer(
             public_key,
-            private_key,
-            state_private_key,
+            Arc::new(LocalSigner::new(private_key, state_private_key)),
+            node_id,
+            config,
+            memberships,
+            network,
+            initializer,
+            metrics,
+            storage,
+            marketplace_config,
+        )
+        .await
+    }
+
+    /// Initializes a new [`SystemContext`] which signs through `signer`, so that this instance
+    /// never holds the node's private keys, and sets up all the background tasks.
+    ///
+    /// See [`Self::init`].
+    /// # Errors
+    ///
+    /// Can throw an error if `Self::new_with_signer` fails.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn init_with_signer(
+        public_key: TYPES::SignatureKey,
+        signer: Arc<dyn ConsensusSigner<TYPES>>,
+        node_id: u64,
+        config: HotShotConfig<TYPES>,
+        memberships: EpochMembershipCoordinator<TYPES>,
+        network: Arc<I::Network>,
+        initializer: HotShotInitializer<TYPES>,
+        metrics: ConsensusMetricsValue,
+        storage: I::Storage,
+        marketplace_config: MarketplaceConfig<TYPES, I>,
+    ) -> Result<
+        (
+            SystemContextHandle<TYPES, I, V>,
+            Sender<Arc<HotShotEvent<TYPES>>>,
+            Receiver<Arc<HotShotEvent<TYPES>>>,
+        ),
+        HotShotError<TYPES>,
+    > {
+        let hotshot = Self::new_with_signer(
+            public_key,
+            signer,
             node_id,
             config,
             memberships,
@@ -1009,14 +1101,8 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> ConsensusApi<TY
         &self.hotshot.public_key
     }
 
-    fn private_key(&self) -> &<TYPES::SignatureKey as SignatureKey>::PrivateKey {
-        &self.hotshot.private_key
-    }
-
-    fn state_private_key(
-        &self,
-    ) -> &<TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey {
-        &self.hotshot.state_private_key
+    fn signer(&self) -> &Arc<dyn ConsensusSigner<TYPES>> {
+        &self.hotshot.signer
     }
 }
 
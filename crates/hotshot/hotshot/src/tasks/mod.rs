@@ -38,6 +38,7 @@ use hotshot_types::{
     traits::{
         network::ConnectedNetwork,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
+        signer::ConsensusSigner,
     },
 };
 use tokio::{spawn, time::sleep};
@@ -85,7 +86,7 @@ pub fn add_response_task<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versi
         handle.hotshot.consensus(),
         handle.membership_coordinator.clone(),
         handle.public_key().clone(),
-        handle.private_key().clone(),
+        Arc::clone(handle.signer()),
         handle.hotshot.id,
         handle.hotshot.upgrade_lock.clone(),
     );
@@ -313,7 +314,7 @@ where
         &mut self,
         event: &HotShotEvent<TYPES>,
         public_key: &TYPES::SignatureKey,
-        private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: &dyn ConsensusSigner<TYPES>,
         upgrade_lock: &UpgradeLock<TYPES, V>,
         consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>>;
@@ -416,7 +417,7 @@ where
         // and broadcast the transformed events to the replacement event stream we just created.
         let shutdown_signal = create_shutdown_event_monitor(handle).fuse();
         let public_key = handle.public_key().clone();
-        let private_key = handle.private_key().clone();
+        let signer = Arc::clone(handle.signer());
         let upgrade_lock = handle.hotshot.upgrade_lock.clone();
         let consensus = Arc::clone(&handle.hotshot.consensus());
         let send_handle = spawn(async move {
@@ -448,7 +449,7 @@ where
                                 let mut results = state.send_handler(
                                     &msg,
                                     &public_key,
-                                    &private_key,
+                                    signer.as_ref(),
                                     &upgrade_lock,
                                     Arc::clone(&consensus)
                                 ).await;
@@ -54,7 +54,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             delay: handle.hotshot.config.data_request_delay,
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             id: handle.hotshot.id,
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             spawned_tasks: BTreeMap::new(),
@@ -76,7 +76,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             vote_collectors: BTreeMap::default(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             id: handle.hotshot.id,
             start_proposing_view: handle.hotshot.config.start_proposing_view,
             stop_proposing_view: handle.hotshot.config.stop_proposing_view,
@@ -101,7 +101,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             network: Arc::clone(&handle.hotshot.network),
             vote_collector: None.into(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             id: handle.hotshot.id,
             start_proposing_view: 5,
             stop_proposing_view: 10,
@@ -128,7 +128,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             network: Arc::clone(&handle.hotshot.network),
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             id: handle.hotshot.id,
             upgrade_lock: handle.hotshot.upgrade_lock.clone(),
             epoch_height: handle.epoch_height,
@@ -150,7 +150,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             cur_epoch: handle.cur_epoch().await,
             vote_collectors: BTreeMap::default(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             id: handle.hotshot.id,
             storage: handle.storage.clone(),
             upgrade_lock: handle.hotshot.upgrade_lock.clone(),
@@ -171,7 +171,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             cur_epoch: handle.cur_epoch().await,
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             num_timeouts_tracked: 0,
             replica_task_map: HashMap::default().into(),
             pre_commit_relay_map: HashMap::default().into(),
@@ -198,7 +198,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             cur_epoch: handle.cur_epoch().await,
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             instance_state: handle.hotshot.instance_state(),
             id: handle.hotshot.id,
             builder_clients: handle
@@ -235,8 +235,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
 
         Self {
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
-            state_private_key: handle.state_private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             consensus: OuterConsensus::new(consensus),
             instance_state: handle.hotshot.instance_state(),
             latest_voted_view: handle.cur_view().await,
@@ -272,7 +271,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
             instance_state: handle.hotshot.instance_state(),
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             storage: handle.storage.clone(),
             timeout: handle.hotshot.config.next_view_timeout,
             id: handle.hotshot.id,
@@ -292,7 +291,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
 
         Self {
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             consensus: OuterConsensus::new(consensus),
             cur_view: handle.cur_view().await,
             cur_epoch: handle.cur_epoch().await,
@@ -317,7 +316,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
 
         Self {
             public_key: handle.public_key().clone(),
-            private_key: handle.private_key().clone(),
+            signer: Arc::clone(handle.signer()),
             instance_state: handle.hotshot.instance_state(),
             network: Arc::clone(&handle.hotshot.network),
             membership_coordinator: handle.hotshot.membership_coordinator.clone(),
@@ -347,9 +346,6 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> CreateTaskState
     for RewindTaskState<TYPES>
 {
     async fn create_from(handle: &SystemContextHandle<TYPES, I, V>) -> Self {
-        Self {
-            events: Vec::new(),
-            id: handle.hotshot.id,
-        }
+        Self::new(handle.hotshot.id)
     }
 }
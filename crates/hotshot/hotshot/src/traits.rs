@@ -19,13 +19,14 @@ pub mod implementations {
     pub use super::networking::{
         combined_network::{CombinedNetworks, UnderlyingCombinedNetworks},
         libp2p_network::{
-            derive_libp2p_keypair, derive_libp2p_multiaddr, derive_libp2p_peer_id, GossipConfig,
-            Libp2pMetricsValue, Libp2pNetwork, PeerInfoVec, RequestResponseConfig,
+            derive_libp2p_keypair, derive_libp2p_multiaddr, derive_libp2p_peer_id,
+            derive_libp2p_secret, libp2p_keypair_from_secret, GossipConfig, Libp2pMetricsValue,
+            Libp2pNetwork, PeerInfoVec, RequestResponseConfig,
         },
         memory_network::{MasterMap, MemoryNetwork},
         push_cdn_network::{
-            CdnMetricsValue, KeyPair, ProductionDef, PushCdnNetwork, TestingDef, Topic as CdnTopic,
-            WrappedSignatureKey,
+            CdnMetricsValue, CdnSigningKey, KeyPair, ProductionDef, PushCdnNetwork, TestingDef,
+            Topic as CdnTopic, WrappedSignatureKey,
         },
     };
 }
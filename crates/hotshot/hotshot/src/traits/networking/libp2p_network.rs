@@ -37,7 +37,7 @@ use hotshot_libp2p_networking::{
             store::persistent::DhtPersistentStorage,
         },
         spawn_network_node,
-        transport::construct_auth_message,
+        transport::{auth_message_payload, construct_signed_auth_message},
         NetworkEvent::{self, DirectRequest, DirectResponse, GossipMsg},
         NetworkNodeConfig, NetworkNodeConfigBuilder, NetworkNodeHandle, NetworkNodeReceiver,
         DEFAULT_REPLICATION_FACTOR,
@@ -58,6 +58,7 @@ use hotshot_types::{
         network::{ConnectedNetwork, NetworkError, Topic},
         node_implementation::{ConsensusTime, NodeType},
         signature_key::{PrivateSignatureKey, SignatureKey},
+        signer::{ConsensusSigner, SignableMessage},
     },
     BoxSyncFuture,
 };
@@ -312,12 +313,27 @@ impl<T: NodeType> TestableNetworkingImplementation<T> for Libp2pNetwork<T> {
 pub fn derive_libp2p_keypair<K: SignatureKey>(
     private_key: &K::PrivateKey,
 ) -> anyhow::Result<Keypair> {
+    libp2p_keypair_from_secret(derive_libp2p_secret::<K>(private_key))
+}
+
+/// Derive the secret of a Libp2p keypair from a given private key
+///
+/// This lets the holder of a private key hand a node its Libp2p identity without handing over the
+/// private key itself.
+pub fn derive_libp2p_secret<K: SignatureKey>(private_key: &K::PrivateKey) -> [u8; 32] {
     // Derive a secondary key from our primary private key
-    let derived_key = blake3::derive_key("libp2p key", &private_key.to_bytes());
-    let derived_key = SecretKey::try_from_bytes(derived_key)?;
+    blake3::derive_key("libp2p key", &private_key.to_bytes())
+}
+
+/// Create a Libp2p keypair from a secret returned by [`derive_libp2p_secret`]
+///
+/// # Errors
+/// If the secret is not a valid `ed25519` secret key
+pub fn libp2p_keypair_from_secret(secret: [u8; 32]) -> anyhow::Result<Keypair> {
+    let secret_key = SecretKey::try_from_bytes(secret)?;
 
     // Create an `ed25519` keypair from the derived key
-    Ok(ed25519::Keypair::from(derived_key).into())
+    Ok(ed25519::Keypair::from(secret_key).into())
 }
 
 /// Derive a Libp2p Peer ID from a given private key
@@ -387,6 +403,9 @@ impl<T: NodeType> Libp2pNetwork<T> {
     /// Create and return a Libp2p network from a network config file
     /// and various other configuration-specific values.
     ///
+    /// The node authenticates with its peers using `keypair`, and proves that the keypair belongs
+    /// to `pub_key` with signatures from `signer`.
+    ///
     /// # Errors
     /// If we are unable to parse a Multiaddress
     ///
@@ -401,7 +420,8 @@ impl<T: NodeType> Libp2pNetwork<T> {
         request_response_config: RequestResponseConfig,
         bind_address: Multiaddr,
         pub_key: &T::SignatureKey,
-        priv_key: &<T::SignatureKey as SignatureKey>::PrivateKey,
+        keypair: Keypair,
+        signer: &dyn ConsensusSigner<T>,
         metrics: Libp2pMetricsValue,
     ) -> anyhow::Result<Self> {
         // Try to take our Libp2p config from our broader network config
@@ -410,9 +430,6 @@ impl<T: NodeType> Libp2pNetwork<T> {
             .take()
             .ok_or(anyhow!("Libp2p config not supplied"))?;
 
-        // Derive our Libp2p keypair from our supplied private key
-        let keypair = derive_libp2p_keypair::<T::SignatureKey>(priv_key)?;
-
         // Build our libp2p configuration
         let mut config_builder = NetworkNodeConfigBuilder::default();
 
@@ -421,9 +438,15 @@ impl<T: NodeType> Libp2pNetwork<T> {
         config_builder.request_response_config(request_response_config);
 
         // Construct the auth message
-        let auth_message =
-            construct_auth_message(pub_key, &keypair.public().to_peer_id(), priv_key)
-                .with_context(|| "Failed to construct auth message")?;
+        let peer_id = keypair.public().to_peer_id();
+        let auth_signature = signer
+            .sign(&SignableMessage::Other(auth_message_payload(
+                pub_key, &peer_id,
+            )))
+            .await
+            .with_context(|| "Failed to sign auth message")?;
+        let auth_message = construct_signed_auth_message(pub_key, &peer_id, auth_signature)
+            .with_context(|| "Failed to construct auth message")?;
 
         // Set the auth message and stake table
         config_builder
@@ -441,14 +464,16 @@ impl<T: NodeType> Libp2pNetwork<T> {
         ))
         .with_context(|| "Failed to calculate replication factor")?;
 
-        // Sign our DHT lookup record
-        let lookup_record_value = RecordValue::new_signed(
-            &RecordKey::new(Namespace::Lookup, pub_key.to_bytes()),
-            // The value is our Libp2p Peer ID
-            keypair.public().to_peer_id().to_bytes(),
-            priv_key,
-        )
-        .with_context(|| "Failed to sign DHT lookup record")?;
+        // Sign our DHT lookup record. The value is our Libp2p Peer ID
+        let lookup_record_key = RecordKey::new(Namespace::Lookup, pub_key.to_bytes());
+        let lookup_value = peer_id.to_bytes();
+        let lookup_signature = signer
+            .sign(&SignableMessage::Other(
+                RecordValue::<T::SignatureKey>::signing_payload(&lookup_record_key, &lookup_value),
+            ))
+            .await
+            .with_context(|| "Failed to sign DHT lookup record")?;
+        let lookup_record_value = RecordValue::Signed(lookup_value, lookup_signature);
 
         config_builder
             .keypair(keypair)
@@ -11,10 +11,11 @@
 
 use core::time::Duration;
 use std::{
+    collections::HashMap,
     fmt::Debug,
     sync::{
         atomic::{AtomicUsize, Ordering},
-        Arc,
+        Arc, RwLock as StdRwLock,
     },
 };
 
@@ -53,6 +54,9 @@ pub struct MasterMap<K: SignatureKey> {
 
     /// The list of `MemoryNetwork`s aggregated by topic
     subscribed_map: DashMap<Topic, Vec<(K, MemoryNetwork<K>)>>,
+
+    /// The group of each node while the network is partitioned, or `None` if it is not
+    partition: StdRwLock<Option<HashMap<K, usize>>>,
 }
 
 impl<K: SignatureKey> MasterMap<K> {
@@ -62,13 +66,51 @@ impl<K: SignatureKey> MasterMap<K> {
         Arc::new(MasterMap {
             map: DashMap::new(),
             subscribed_map: DashMap::new(),
+            partition: StdRwLock::new(None),
         })
     }
+
+    /// Split the network into `groups` of nodes which can only communicate within their group,
+    /// replacing any existing partition.
+    ///
+    /// Nodes which are not in any group are cut off from all other nodes. If `groups` is empty,
+    /// the network is healed and all nodes can communicate again. Messages which are already in
+    /// flight are delivered regardless.
+    ///
+    /// # Panics
+    /// Panics if the partition lock is poisoned.
+    pub fn partition(&self, groups: &[Vec<K>]) {
+        let partition = (!groups.is_empty()).then(|| {
+            groups
+                .iter()
+                .enumerate()
+                .flat_map(|(group, keys)| keys.iter().map(move |key| (key.clone(), group)))
+                .collect()
+        });
+        info!(?groups, "Partitioning memory network");
+        *self.partition.write().unwrap() = partition;
+    }
+
+    /// Whether messages from `sender` can currently reach `recipient`.
+    fn connected(&self, sender: &K, recipient: &K) -> bool {
+        if sender == recipient {
+            return true;
+        }
+        match &*self.partition.read().unwrap() {
+            Some(groups) => match (groups.get(sender), groups.get(recipient)) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+            None => true,
+        }
+    }
 }
 
 /// Internal state for a `MemoryNetwork` instance
 #[derive(Debug)]
 struct MemoryNetworkInner<K: SignatureKey> {
+    /// The public key of this node
+    pub_key: K,
     /// Input for messages
     input: RwLock<Option<Sender<Vec<u8>>>>,
     /// Output for messages
@@ -140,6 +182,7 @@ impl<K: SignatureKey> MemoryNetwork<K> {
         trace!("Task spawned, creating MemoryNetwork");
         let mn = MemoryNetwork {
             inner: Arc::new(MemoryNetworkInner {
+                pub_key: pub_key.clone(),
                 input: RwLock::new(Some(input)),
                 output: Mutex::new(output),
                 master_map: Arc::clone(master_map),
@@ -214,6 +257,11 @@ impl<TYPES: NodeType> TestableNetworkingImplementation<TYPES>
     fn in_flight_message_count(&self) -> Option<usize> {
         Some(self.inner.in_flight_message_count.load(Ordering::Relaxed))
     }
+
+    fn partition(&self, groups: &[Vec<TYPES::SignatureKey>]) -> bool {
+        self.inner.master_map.partition(groups);
+        true
+    }
 }
 
 // TODO instrument these functions
@@ -260,6 +308,10 @@ impl<K: SignatureKey + 'static> ConnectedNetwork<K> for MemoryNetwork<K> {
         {
             // TODO delay/drop etc here
             let (key, node) = node;
+            if !self.inner.master_map.connected(&self.inner.pub_key, key) {
+                trace!(?key, "Dropping message to node in another partition");
+                continue;
+            }
             trace!(?key, "Sending message to node");
             if let Some(ref config) = &self.inner.reliability_config {
                 {
@@ -314,6 +366,10 @@ impl<K: SignatureKey + 'static> ConnectedNetwork<K> for MemoryNetwork<K> {
             }
             // TODO delay/drop etc here
             let (key, node) = node;
+            if !self.inner.master_map.connected(&self.inner.pub_key, key) {
+                trace!(?key, "Dropping message to node in another partition");
+                continue;
+            }
             trace!(?key, "Sending message to node");
             if let Some(ref config) = &self.inner.reliability_config {
                 {
@@ -351,6 +407,14 @@ impl<K: SignatureKey + 'static> ConnectedNetwork<K> for MemoryNetwork<K> {
         // debug!(?message, ?recipient, "Sending direct message");
         // Bincode the message
         trace!("Message bincoded, finding recipient");
+        if !self
+            .inner
+            .master_map
+            .connected(&self.inner.pub_key, &recipient)
+        {
+            trace!(?recipient, "Dropping message to node in another partition");
+            return Ok(());
+        }
         if let Some(node) = self.inner.master_map.map.get(&recipient) {
             let node = node.value().clone();
             if let Some(ref config) = &self.inner.reliability_config {
@@ -41,6 +41,7 @@ use hotshot_types::{
         network::{BroadcastDelay, ConnectedNetwork, Topic as HotShotTopic},
         node_implementation::NodeType,
         signature_key::SignatureKey,
+        signer::{ConsensusSigner, SignableMessage},
     },
     utils::bincode_opts,
     BoxSyncFuture,
@@ -49,7 +50,9 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use parking_lot::Mutex;
 #[cfg(feature = "hotshot-testing")]
 use rand::{rngs::StdRng, RngCore, SeedableRng};
-use tokio::{spawn, sync::mpsc::error::TrySendError, time::sleep};
+use tokio::{
+    runtime::Handle, spawn, sync::mpsc::error::TrySendError, task::block_in_place, time::sleep,
+};
 #[cfg(feature = "hotshot-testing")]
 use tracing::error;
 
@@ -82,12 +85,58 @@ impl Default for CdnMetricsValue {
     }
 }
 
+/// A function signing a message to the CDN with a private key held elsewhere
+pub type RemoteCdnSigner<T> = Arc<
+    dyn Fn(&[u8]) -> anyhow::Result<<T as SignatureKey>::PureAssembledSignatureType> + Send + Sync,
+>;
+
+/// The key we sign messages to the CDN with
+pub enum CdnSigningKey<T: SignatureKey + 'static> {
+    /// A private key held by this process
+    Local(T::PrivateKey),
+    /// A signer holding the private key elsewhere
+    Remote(RemoteCdnSigner<T>),
+}
+
+impl<T: SignatureKey + 'static> Clone for CdnSigningKey<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Local(private_key) => Self::Local(private_key.clone()),
+            Self::Remote(signer) => Self::Remote(Arc::clone(signer)),
+        }
+    }
+}
+
+impl<T: SignatureKey + 'static> CdnSigningKey<T> {
+    /// Sign with `signer`, which may hold the private key in another process.
+    ///
+    /// The CDN signs synchronously, so this blocks the calling thread until the signer answers. It
+    /// must only be used from within a multi-threaded Tokio runtime.
+    pub fn from_signer<TYPES: NodeType<SignatureKey = T>>(
+        signer: Arc<dyn ConsensusSigner<TYPES>>,
+    ) -> Self {
+        Self::Remote(Arc::new(move |message| {
+            block_in_place(|| {
+                Handle::current().block_on(signer.sign(&SignableMessage::Other(message.to_vec())))
+            })
+        }))
+    }
+
+    /// Sign a message of arbitrary data
+    fn sign(&self, message: &[u8]) -> anyhow::Result<T::PureAssembledSignatureType> {
+        match self {
+            Self::Local(private_key) => Ok(T::sign(private_key, message)?),
+            Self::Remote(signer) => signer(message),
+        }
+    }
+}
+
 /// A wrapped `SignatureKey`. We need to implement the Push CDN's `SignatureScheme`
 /// trait in order to sign and verify messages to/from the CDN.
 #[derive(Clone, Eq, PartialEq)]
 pub struct WrappedSignatureKey<T: SignatureKey + 'static>(pub T);
 impl<T: SignatureKey> SignatureScheme for WrappedSignatureKey<T> {
-    type PrivateKey = T::PrivateKey;
+    type PrivateKey = CdnSigningKey<T>;
     type PublicKey = Self;
 
     /// Sign a message of arbitrary data and return the serialized signature
@@ -99,7 +148,7 @@ impl<T: SignatureKey> SignatureScheme for WrappedSignatureKey<T> {
         // Combine the namespace and message into a single byte array
         let message = [namespace.as_bytes(), message].concat();
 
-        let signature = T::sign(private_key, &message)?;
+        let signature = private_key.sign(&message)?;
         Ok(bincode_opts().serialize(&signature)?)
     }
 
@@ -349,7 +398,7 @@ impl<TYPES: NodeType> TestableNetworkingImplementation<TYPES>
                 metrics_bind_endpoint: None,
                 keypair: KeyPair {
                     public_key: WrappedSignatureKey(broker_public_key.clone()),
-                    private_key: broker_private_key.clone(),
+                    private_key: CdnSigningKey::Local(broker_private_key.clone()),
                 },
                 discovery_endpoint: discovery_endpoint.clone(),
 
@@ -431,7 +480,7 @@ impl<TYPES: NodeType> TestableNetworkingImplementation<TYPES>
                         ClientConfig {
                             keypair: KeyPair {
                                 public_key: WrappedSignatureKey(public_key.clone()),
-                                private_key,
+                                private_key: CdnSigningKey::Local(private_key),
                             },
                             subscribed_topics: topics,
                             endpoint: marshal_endpoint,
@@ -26,7 +26,7 @@ use hotshot_types::{
         consensus_api::ConsensusApi,
         network::{BroadcastDelay, ConnectedNetwork, Topic},
         node_implementation::NodeType,
-        signature_key::SignatureKey,
+        signer::SignableMessage,
     },
 };
 use tracing::instrument;
@@ -133,7 +133,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions>
     ///
     /// # Errors
     /// Errors if signing the request for proposal fails
-    pub fn request_proposal(
+    pub async fn request_proposal(
         &self,
         view: TYPES::View,
         leaf_commitment: Commitment<Leaf2<TYPES>>,
@@ -147,10 +147,12 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions>
         };
 
         // Finally, compute the signature for the payload.
-        let signature = TYPES::SignatureKey::sign(
-            self.private_key(),
-            signed_proposal_request.commit().as_ref(),
-        )?;
+        let signature = self
+            .signer()
+            .sign(&SignableMessage::ProposalRequest(
+                signed_proposal_request.clone(),
+            ))
+            .await?;
 
         let mut receiver = self.internal_event_stream.1.activate_cloned();
         let sender = self.internal_event_stream.0.clone();
@@ -251,6 +253,21 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions>
         self.hotshot.publish_transaction_async(tx).await
     }
 
+    /// Submits a batch of transactions to the backing [`SystemContext`] instance.
+    ///
+    /// The current node broadcasts the transactions to all nodes on the network.
+    ///
+    /// # Errors
+    ///
+    /// Will return a [`HotShotError`] if some error occurs in the underlying
+    /// [`SystemContext`] instance.
+    pub async fn submit_transactions(
+        &self,
+        txs: Vec<TYPES::Transaction>,
+    ) -> Result<(), HotShotError<TYPES>> {
+        self.hotshot.publish_transactions_async(txs).await
+    }
+
     /// Get the underlying consensus state for this [`SystemContext`]
     #[must_use]
     pub fn consensus(&self) -> Arc<RwLock<Consensus<TYPES>>> {
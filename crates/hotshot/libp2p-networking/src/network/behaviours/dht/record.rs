@@ -118,17 +118,22 @@ impl<K: SignatureKey + 'static> RecordValue<K> {
         value: Vec<u8>,
         private_key: &K::PrivateKey,
     ) -> Result<Self> {
-        // The value to sign should be the record key concatenated with the value
-        let mut value_to_sign = record_key.to_bytes();
-        value_to_sign.extend_from_slice(&value);
-
-        let signature =
-            K::sign(private_key, &value_to_sign).with_context(|| "Failed to sign record")?;
+        let signature = K::sign(private_key, &Self::signing_payload(record_key, &value))
+            .with_context(|| "Failed to sign record")?;
 
         // Return the signed record
         Ok(Self::Signed(value, signature))
     }
 
+    /// The bytes which must be signed to create a signed record with the given key and value:
+    /// the record key concatenated with the value
+    #[must_use]
+    pub fn signing_payload(record_key: &RecordKey, value: &[u8]) -> Vec<u8> {
+        let mut payload = record_key.to_bytes();
+        payload.extend_from_slice(value);
+        payload
+    }
+
     /// Creates and returns a new unsigned record
     #[must_use]
     pub fn new(value: Vec<u8>) -> Self {
@@ -155,12 +160,8 @@ impl<K: SignatureKey + 'static> RecordValue<K> {
             return false;
         };
 
-        // The value to sign should be the record key concatenated with the value
-        let mut signed_value = record_key.to_bytes();
-        signed_value.extend_from_slice(value);
-
         // Validate the signature
-        public_key.validate(signature, &signed_value)
+        public_key.validate(signature, &Self::signing_payload(record_key, value))
     }
 
     /// Get the underlying value of the record
@@ -265,21 +265,38 @@ pub fn construct_auth_message<S: SignatureKey + 'static>(
     peer_id: &PeerId,
     private_key: &S::PrivateKey,
 ) -> AnyhowResult<Vec<u8>> {
+    // Sign our public key
+    let signature = S::sign(private_key, &auth_message_payload(public_key, peer_id))
+        .with_context(|| "Failed to sign public key")?;
+
+    construct_signed_auth_message(public_key, peer_id, signature)
+}
+
+/// The bytes which must be signed to authenticate `peer_id` as belonging to `public_key`
+#[must_use]
+pub fn auth_message_payload<S: SignatureKey>(public_key: &S, peer_id: &PeerId) -> Vec<u8> {
     // Serialize the stake table public key
     let mut public_key_bytes = public_key.to_bytes();
 
     // Serialize the peer ID and append it
-    let peer_id_bytes = peer_id.to_bytes();
-    public_key_bytes.extend_from_slice(&peer_id_bytes);
-
-    // Sign our public key
-    let signature =
-        S::sign(private_key, &public_key_bytes).with_context(|| "Failed to sign public key")?;
+    public_key_bytes.extend_from_slice(&peer_id.to_bytes());
+    public_key_bytes
+}
 
+/// Create an authentication message from a signature over [`auth_message_payload`], for nodes
+/// which do not hold their private key themselves
+///
+/// # Errors
+/// - If we fail to serialize the authentication message
+pub fn construct_signed_auth_message<S: SignatureKey + 'static>(
+    public_key: &S,
+    peer_id: &PeerId,
+    signature: S::PureAssembledSignatureType,
+) -> AnyhowResult<Vec<u8>> {
     // Create the auth message
     let auth_message = AuthMessage::<S> {
-        public_key_bytes,
-        peer_id_bytes,
+        public_key_bytes: auth_message_payload(public_key, peer_id),
+        peer_id_bytes: peer_id.to_bytes(),
         signature,
     };
 
@@ -177,10 +177,37 @@ pub async fn get_complete_config<TYPES: NodeType>(
     ValidatorConfig<TYPES>,
     NetworkConfigSource,
 )> {
+    let (run_config, is_da, source) = get_complete_config_for_peer(
+        client,
+        &validator_config.public_config(),
+        validator_config.is_da,
+        libp2p_advertise_address,
+        libp2p_public_key,
+    )
+    .await?;
+    validator_config.is_da = is_da;
+    Ok((run_config, validator_config, source))
+}
+
+/// Asynchronously retrieves a `NetworkConfig` from an orchestrator, using only our public config.
+///
+/// This is for nodes which do not hold their own private keys. Returns the config, and whether the
+/// orchestrator made us a DA committee member.
+///
+/// # Errors
+/// If we are unable to get the configuration from the orchestrator
+pub async fn get_complete_config_for_peer<TYPES: NodeType>(
+    client: &OrchestratorClient,
+    peer_config: &PeerConfig<TYPES>,
+    da_requested: bool,
+    libp2p_advertise_address: Option<Multiaddr>,
+    libp2p_public_key: Option<PeerId>,
+) -> anyhow::Result<(NetworkConfig<TYPES>, bool, NetworkConfigSource)> {
     // get the configuration from the orchestrator
-    let run_config: NetworkConfig<TYPES> = client
+    let (run_config, is_da) = client
         .post_and_wait_all_public_keys::<TYPES>(
-            &mut validator_config,
+            peer_config,
+            da_requested,
             libp2p_advertise_address,
             libp2p_public_key,
         )
@@ -188,13 +215,9 @@ pub async fn get_complete_config<TYPES: NodeType>(
 
     info!(
         "Retrieved config; our node index is {}. DA committee member: {}",
-        run_config.node_index, validator_config.is_da
+        run_config.node_index, is_da
     );
-    Ok((
-        run_config,
-        validator_config,
-        NetworkConfigSource::Orchestrator,
-    ))
+    Ok((run_config, is_da, NetworkConfigSource::Orchestrator))
 }
 
 impl ValidatorArgs {
@@ -395,20 +418,19 @@ impl OrchestratorClient {
     }
 
     /// Sends my public key to the orchestrator so that it can collect all public keys
-    /// And get the updated config
+    /// And get the updated config, and whether we are a DA committee member
     /// Blocks until the orchestrator collects all peer's public keys/configs
     /// # Panics
     /// if unable to post
     #[instrument(skip(self), name = "orchestrator public keys")]
     pub async fn post_and_wait_all_public_keys<TYPES: NodeType>(
         &self,
-        validator_config: &mut ValidatorConfig<TYPES>,
+        peer_config: &PeerConfig<TYPES>,
+        da_requested: bool,
         libp2p_advertise_address: Option<Multiaddr>,
         libp2p_public_key: Option<PeerId>,
-    ) -> NetworkConfig<TYPES> {
-        let pubkey: Vec<u8> =
-            PeerConfig::<TYPES>::to_bytes(&validator_config.public_config()).clone();
-        let da_requested: bool = validator_config.is_da;
+    ) -> (NetworkConfig<TYPES>, bool) {
+        let pubkey: Vec<u8> = PeerConfig::<TYPES>::to_bytes(peer_config).clone();
 
         // Serialize our (possible) libp2p-specific data
         let request_body = vbs::Serializer::<OrchestratorVersion>::serialize(&(
@@ -436,8 +458,6 @@ impl OrchestratorClient {
             sleep(Duration::from_millis(250)).await;
         };
 
-        validator_config.is_da = is_da;
-
         // wait for all nodes' public keys
         let wait_for_all_nodes_pub_key = |client: Client<ClientError, OrchestratorVersion>| {
             async move {
@@ -456,7 +476,7 @@ impl OrchestratorClient {
 
         network_config.node_index = node_index;
 
-        network_config
+        (network_config, is_da)
     }
 
     /// Tells the orchestrator this validator is ready to start
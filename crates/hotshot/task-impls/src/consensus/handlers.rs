@@ -520,14 +520,14 @@ pub(crate) async fn handle_timeout<TYPES: NodeType, I: NodeImplementation<TYPES>
         debug!("We were not chosen for the consensus committee for view {view_number:?}",)
     );
 
-    let vote = TimeoutVote2::create_signed_vote(
+    let vote = TimeoutVote2::create_vote_with_signer(
         TimeoutData2::<TYPES> {
             view: view_number,
             epoch,
         },
         view_number,
         &task_state.public_key,
-        &task_state.private_key,
+        task_state.signer.as_ref(),
         &task_state.upgrade_lock,
     )
     .await
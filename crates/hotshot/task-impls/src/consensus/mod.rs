@@ -19,7 +19,7 @@ use hotshot_types::{
     simple_vote::{HasEpoch, NextEpochQuorumVote2, QuorumVote2, TimeoutVote2},
     traits::{
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
-        signature_key::SignatureKey,
+        signer::ConsensusSigner,
         storage::Storage,
     },
     utils::{epoch_from_block_number, is_last_block},
@@ -47,8 +47,8 @@ pub struct ConsensusTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V:
     /// Our public key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for votes and proposals
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Immutable instance state
     pub instance_state: Arc<TYPES::InstanceState>,
@@ -21,6 +21,7 @@ use hotshot_types::{
         network::ConnectedNetwork,
         node_implementation::{NodeImplementation, NodeType, Versions},
         signature_key::SignatureKey,
+        signer::{ConsensusSigner, SignableMessage},
         storage::Storage,
         BlockPayload, EncodeBytes,
     },
@@ -66,8 +67,8 @@ pub struct DaTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Version
     /// This Nodes public key
     pub public_key: TYPES::SignatureKey,
 
-    /// This Nodes private key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for votes and proposals
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// This state's ID
     pub id: u64,
@@ -231,7 +232,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> DaTaskState<TYP
                     .wrap()
                     .context(error!("Failed to append DA proposal to storage"))?;
                 // Generate and send vote
-                let vote = DaVote2::create_signed_vote(
+                let vote = DaVote2::create_vote_with_signer(
                     DaData2 {
                         payload_commit: payload_commitment,
                         next_epoch_payload_commit: next_epoch_payload_commitment,
@@ -239,7 +240,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> DaTaskState<TYP
                     },
                     view_number,
                     &self.public_key,
-                    &self.private_key,
+                    self.signer.as_ref(),
                     &self.upgrade_lock,
                 )
                 .await?;
@@ -278,7 +279,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> DaTaskState<TYP
                     let my_id = self.id;
                     let consensus =
                         OuterConsensus::new(Arc::clone(&self.consensus.inner_consensus));
-                    let pk = self.private_key.clone();
+                    let signer = Arc::clone(&self.signer);
                     let public_key = self.public_key.clone();
                     let chan = event_stream.clone();
                     let upgrade_lock = self.upgrade_lock.clone();
@@ -311,7 +312,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> DaTaskState<TYP
                                 view_number,
                                 target_epoch,
                                 membership.coordinator.clone(),
-                                &pk,
+                                signer.as_ref(),
                                 &upgrade_lock,
                             )
                             .await;
@@ -399,13 +400,15 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> DaTaskState<TYP
                 } = packed_bundle;
                 let view_number = *view_number;
 
-                // quick hash the encoded txns with sha256
-                let encoded_transactions_hash = Sha256::digest(encoded_transactions);
-
-                // sign the encoded transactions as opposed to the VID commitment
-                let signature =
-                    TYPES::SignatureKey::sign(&self.private_key, &encoded_transactions_hash)
-                        .wrap()?;
+                // sign the hash of the encoded transactions as opposed to the VID commitment
+                let signature = self
+                    .signer
+                    .sign(&SignableMessage::DaProposal {
+                        view: view_number,
+                        encoded_transactions: Arc::clone(encoded_transactions),
+                    })
+                    .await
+                    .wrap()?;
 
                 let epoch = self.cur_epoch;
                 let leader = self
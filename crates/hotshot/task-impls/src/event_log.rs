@@ -0,0 +1,264 @@
+// Copyright (c) 2021-2024 Espresso Systems (espressosys.com)
+// This file is part of the HotShot repository.
+
+// You should have received a copy of the MIT License
+// along with the HotShot repository. If not, see <https://mit-license.org/>.
+
+//! Binary recordings of the consensus event stream.
+//!
+//! An event log starts with a header identifying the format version and the node which recorded
+//! it, followed by one length-prefixed, bincode-encoded [`EventRecord`] for each event, in the order
+//! the node received them. Records are written as they arrive, so a log can be streamed to disk
+//! while the node runs, and a log cut short by a crash can still be read up to the last complete
+//! record.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    marker::PhantomData,
+    path::Path,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, ensure, Context};
+use hotshot_types::traits::node_implementation::NodeType;
+use serde::{Deserialize, Serialize};
+
+use crate::events::HotShotEvent;
+
+/// Magic bytes at the start of every event log.
+const EVENT_LOG_MAGIC: &[u8; 8] = b"HSEVTLOG";
+
+/// The version of the event log format written by this software.
+pub const EVENT_LOG_VERSION: u16 = 1;
+
+/// Information about the recording, stored at the start of an event log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventLogHeader {
+    /// The id of the node which recorded the log.
+    pub node_id: u64,
+    /// Wall clock time at which recording started, in milliseconds since the Unix epoch.
+    pub start_time_ms: u64,
+}
+
+/// A single recorded event.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(deserialize = ""))]
+pub struct EventRecord<TYPES: NodeType> {
+    /// Time at which the event was received, relative to the start of the recording.
+    pub elapsed: Duration,
+    /// The event.
+    pub event: HotShotEvent<TYPES>,
+}
+
+/// Writes events to an event log as they are received.
+pub struct EventLogWriter<W: Write> {
+    /// Destination of the log.
+    writer: W,
+    /// When recording started, for timestamping records.
+    start: Instant,
+}
+
+impl EventLogWriter<BufWriter<File>> {
+    /// Create a log file at `path`, replacing any existing file.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be created or the header cannot be written.
+    pub fn create(path: impl AsRef<Path>, node_id: u64) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).context(format!("creating {}", path.display()))?;
+        Self::new(BufWriter::new(file), node_id)
+    }
+}
+
+impl<W: Write> EventLogWriter<W> {
+    /// Start a new log, writing the header to `writer`.
+    ///
+    /// # Errors
+    /// Fails if the header cannot be written.
+    pub fn new(mut writer: W, node_id: u64) -> anyhow::Result<Self> {
+        let header = EventLogHeader {
+            node_id,
+            start_time_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis()
+                .try_into()
+                .unwrap_or(u64::MAX),
+        };
+        writer.write_all(EVENT_LOG_MAGIC)?;
+        writer.write_all(&EVENT_LOG_VERSION.to_le_bytes())?;
+        write_frame(&mut writer, &header)?;
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Append `event` to the log, timestamped with the current time.
+    ///
+    /// # Errors
+    /// Fails if the event cannot be serialized or written.
+    pub fn record<TYPES: NodeType>(&mut self, event: &HotShotEvent<TYPES>) -> anyhow::Result<()> {
+        #[derive(Serialize)]
+        #[serde(bound = "")]
+        struct RecordRef<'a, TYPES: NodeType> {
+            /// Time at which the event was received.
+            elapsed: Duration,
+            /// The event.
+            event: &'a HotShotEvent<TYPES>,
+        }
+
+        write_frame(
+            &mut self.writer,
+            &RecordRef {
+                elapsed: self.start.elapsed(),
+                event,
+            },
+        )
+    }
+
+    /// Flush buffered records to the underlying writer.
+    ///
+    /// # Errors
+    /// Fails if the underlying writer cannot be flushed.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flush the log and return the underlying writer.
+    ///
+    /// # Errors
+    /// Fails if the underlying writer cannot be flushed.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads the records of an event log one at a time.
+pub struct EventLogReader<TYPES: NodeType, R: Read> {
+    /// Source of the log.
+    reader: R,
+    /// The header read from the start of the log.
+    header: EventLogHeader,
+    /// Set once the end of the log, or an error, has been reached.
+    done: bool,
+    /// Phantom for `TYPES`
+    _pd: PhantomData<TYPES>,
+}
+
+impl<TYPES: NodeType> EventLogReader<TYPES, BufReader<File>> {
+    /// Open the log file at `path`.
+    ///
+    /// # Errors
+    /// Fails if the file cannot be opened, or does not start with a valid header.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).context(format!("opening {}", path.display()))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<TYPES: NodeType, R: Read> EventLogReader<TYPES, R> {
+    /// Start reading a log from `reader`.
+    ///
+    /// # Errors
+    /// Fails if `reader` does not start with a valid header, or the log was written with an
+    /// unsupported version of the format.
+    pub fn new(mut reader: R) -> anyhow::Result<Self> {
+        let mut magic = [0; EVENT_LOG_MAGIC.len()];
+        reader
+            .read_exact(&mut magic)
+            .context("reading event log header")?;
+        ensure!(&magic == EVENT_LOG_MAGIC, "not an event log");
+
+        let mut version = [0; 2];
+        reader
+            .read_exact(&mut version)
+            .context("reading event log version")?;
+        match u16::from_le_bytes(version) {
+            1 => {},
+            v => bail!("unsupported event log version {v}"),
+        }
+
+        let Some(header) = read_frame(&mut reader)? else {
+            bail!("event log is missing its header");
+        };
+        Ok(Self {
+            reader,
+            header,
+            done: false,
+            _pd: PhantomData,
+        })
+    }
+
+    /// The header of the log.
+    pub fn header(&self) -> &EventLogHeader {
+        &self.header
+    }
+}
+
+impl<TYPES: NodeType, R: Read> Iterator for EventLogReader<TYPES, R> {
+    type Item = anyhow::Result<EventRecord<TYPES>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match read_frame(&mut self.reader) {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
+}
+
+/// Read all the records of the event log at `path`.
+///
+/// # Errors
+/// Fails if the file cannot be read, or any record is malformed or truncated.
+pub fn read_event_log<TYPES: NodeType>(
+    path: impl AsRef<Path>,
+) -> anyhow::Result<(EventLogHeader, Vec<EventRecord<TYPES>>)> {
+    let reader = EventLogReader::open(path)?;
+    let header = reader.header().clone();
+    let records = reader.collect::<anyhow::Result<_>>()?;
+    Ok((header, records))
+}
+
+/// Write `value` as a length-prefixed bincode frame.
+fn write_frame(writer: &mut impl Write, value: &impl Serialize) -> anyhow::Result<()> {
+    let bytes = bincode::serialize(value).context("serializing event log frame")?;
+    let len = u32::try_from(bytes.len()).context("event log frame too large")?;
+    writer.write_all(&len.to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Read a length-prefixed bincode frame, or `None` at a clean end of the log.
+fn read_frame<T: for<'de> Deserialize<'de>>(reader: &mut impl Read) -> anyhow::Result<Option<T>> {
+    let mut len = [0; 4];
+    let mut read = 0;
+    while read < len.len() {
+        match reader.read(&mut len[read..])? {
+            0 if read == 0 => return Ok(None),
+            0 => bail!("event log truncated in frame length"),
+            n => read += n,
+        }
+    }
+
+    let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+    reader
+        .read_exact(&mut bytes)
+        .context("event log truncated in frame")?;
+    Ok(Some(
+        bincode::deserialize(&bytes).context("malformed event log frame")?,
+    ))
+}
@@ -32,6 +32,7 @@ use hotshot_types::{
     utils::BuilderCommitment,
     vote::HasViewNumber,
 };
+use serde::{Deserialize, Serialize};
 use vec1::Vec1;
 
 use crate::view_sync::ViewSyncPhase;
@@ -65,7 +66,8 @@ impl<TYPES: NodeType> Eq for ProposalMissing<TYPES> {}
 pub struct HotShotTaskCompleted;
 
 /// All of the possible events that can be passed between Sequencing `HotShot` tasks
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = ""))]
 #[allow(clippy::large_enum_variant)]
 pub enum HotShotEvent<TYPES: NodeType> {
     /// Shutdown the task
@@ -134,13 +136,21 @@ pub enum HotShotEvent<TYPES: NodeType> {
     /// Send a DA vote to the DA leader; emitted by DA committee members in the DA task after seeing a valid DA proposal
     DaVoteSend(DaVote2<TYPES>),
     /// The next leader has collected enough votes to form a QC; emitted by the next leader in the consensus task; an internal event only
-    QcFormed(Either<QuorumCertificate<TYPES>, TimeoutCertificate<TYPES>>),
+    QcFormed(
+        #[serde(with = "either_serde")] Either<QuorumCertificate<TYPES>, TimeoutCertificate<TYPES>>,
+    ),
     /// The next leader has collected enough votes to form a QC; emitted by the next leader in the consensus task; an internal event only
-    Qc2Formed(Either<QuorumCertificate2<TYPES>, TimeoutCertificate2<TYPES>>),
+    Qc2Formed(
+        #[serde(with = "either_serde")]
+        Either<QuorumCertificate2<TYPES>, TimeoutCertificate2<TYPES>>,
+    ),
     /// The next leader has collected enough votes to form an epoch root QC; emitted by the next leader in the consensus task; an internal event only
     EpochRootQcFormed(EpochRootQuorumCertificate<TYPES>),
     /// The next leader has collected enough votes from the next epoch nodes to form a QC; emitted by the next leader in the consensus task; an internal event only
-    NextEpochQc2Formed(Either<NextEpochQuorumCertificate2<TYPES>, TimeoutCertificate<TYPES>>),
+    NextEpochQc2Formed(
+        #[serde(with = "either_serde")]
+        Either<NextEpochQuorumCertificate2<TYPES>, TimeoutCertificate<TYPES>>,
+    ),
     /// A validator formed both a current epoch eQC and a next epoch eQC
     ExtendedQc2Formed(QuorumCertificate2<TYPES>),
     /// The DA leader has collected enough votes to form a DAC; emitted by the DA leader in the DA task; sent to the entire network via the networking task
@@ -298,6 +308,42 @@ pub enum HotShotEvent<TYPES: NodeType> {
     EpochRootQcRecv(EpochRootQuorumCertificate<TYPES>, TYPES::SignatureKey),
 }
 
+/// Serialization for [`Either`], which only supports serde with its `serde` feature.
+mod either_serde {
+    use either::Either;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialized representation of [`Either`].
+    #[derive(Serialize, Deserialize)]
+    enum EitherRepr<L, R> {
+        /// A value of the left type.
+        Left(L),
+        /// A value of the right type.
+        Right(R),
+    }
+
+    /// Serialize an [`Either`] as an enum with `Left` and `Right` variants.
+    pub fn serialize<S: Serializer, L: Serialize, R: Serialize>(
+        value: &Either<L, R>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Either::Left(l) => EitherRepr::<&L, &R>::Left(l).serialize(serializer),
+            Either::Right(r) => EitherRepr::<&L, &R>::Right(r).serialize(serializer),
+        }
+    }
+
+    /// Deserialize an [`Either`] serialized by [`serialize`].
+    pub fn deserialize<'de, D: Deserializer<'de>, L: Deserialize<'de>, R: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<Either<L, R>, D::Error> {
+        Ok(match EitherRepr::deserialize(deserializer)? {
+            EitherRepr::Left(l) => Either::Left(l),
+            EitherRepr::Right(r) => Either::Right(r),
+        })
+    }
+}
+
 impl<TYPES: NodeType> HotShotEvent<TYPES> {
     #[allow(clippy::too_many_lines)]
     /// Return the view number for a hotshot event if present
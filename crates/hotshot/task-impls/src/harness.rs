@@ -11,7 +11,10 @@ use hotshot_task::task::{ConsensusTaskRegistry, Task, TaskState};
 use hotshot_types::traits::node_implementation::NodeType;
 use tokio::time::timeout;
 
-use crate::events::{HotShotEvent, HotShotTaskCompleted};
+use crate::{
+    event_log::EventRecord,
+    events::{HotShotEvent, HotShotTaskCompleted},
+};
 
 /// The state for the test harness task. Keeps track of which events and how many we expect to get
 pub struct TestHarnessState<TYPES: NodeType> {
@@ -75,6 +78,84 @@ pub async fn run_harness<TYPES, S: TaskState<Event = HotShotEvent<TYPES>> + Send
     );
 }
 
+/// The differences between the outputs of a task during a replay and the outputs recorded in the
+/// event log.
+#[derive(Debug)]
+pub struct ReplayReport<TYPES: NodeType> {
+    /// Recorded outputs which the task did not produce.
+    pub missing: Vec<HotShotEvent<TYPES>>,
+    /// Outputs the task produced which were not recorded.
+    pub unexpected: Vec<HotShotEvent<TYPES>>,
+}
+
+impl<TYPES: NodeType> ReplayReport<TYPES> {
+    /// Whether the task reproduced exactly the recorded outputs.
+    #[must_use]
+    pub fn is_match(&self) -> bool {
+        self.missing.is_empty() && self.unexpected.is_empty()
+    }
+}
+
+/// Replays a recorded event log into a task, and compares the outputs of the task with the
+/// outputs which were recorded.
+///
+/// The recorded events for which `is_output` returns `true` are the expected outputs of the task;
+/// all other recorded events are fed to the task as inputs, in the order they were recorded. Like
+/// [`run_harness`], outputs are compared regardless of order. Outputs are collected until the
+/// task has produced nothing for `idle_timeout`.
+///
+/// # Panics
+/// Panics if the task stops receiving inputs.
+pub async fn replay_event_log<TYPES, S>(
+    records: impl IntoIterator<Item = EventRecord<TYPES>>,
+    state: S,
+    is_output: impl Fn(&HotShotEvent<TYPES>) -> bool,
+    idle_timeout: Duration,
+) -> ReplayReport<TYPES>
+where
+    TYPES: NodeType,
+    S: TaskState<Event = HotShotEvent<TYPES>> + Send + 'static,
+{
+    let (input, mut missing): (Vec<_>, Vec<_>) = records
+        .into_iter()
+        .map(|record| record.event)
+        .partition(|event| !is_output(event));
+
+    let mut registry = ConsensusTaskRegistry::new();
+    let (to_task, from_test) = broadcast(1024);
+    let (to_test, mut from_task) = broadcast(1024);
+    let task = Task::new(state, to_test.clone(), from_test.clone());
+    registry.register(task.run());
+
+    let feed = async move {
+        for event in input {
+            to_task.broadcast_direct(Arc::new(event)).await.unwrap();
+        }
+    };
+    let collect = async move {
+        let mut outputs = vec![];
+        while let Ok(Ok(event)) = timeout(idle_timeout, from_task.recv_direct()).await {
+            outputs.push(Arc::unwrap_or_clone(event));
+        }
+        outputs
+    };
+    let ((), outputs) = tokio::join!(feed, collect);
+
+    let mut unexpected = vec![];
+    for event in outputs {
+        match missing.iter().position(|expected| *expected == event) {
+            Some(idx) => {
+                missing.remove(idx);
+            },
+            None => unexpected.push(event),
+        }
+    }
+    ReplayReport {
+        missing,
+        unexpected,
+    }
+}
+
 /// Handles an event for the Test Harness Task.  If the event is expected, remove it from
 /// the `expected_output` in state.  If unexpected fail test.
 ///
@@ -33,7 +33,8 @@ use hotshot_types::{
         block_contents::BlockHeader,
         election::Membership,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
-        signature_key::{SignatureKey, StakeTableEntryType, StateSignatureKey},
+        signature_key::{StakeTableEntryType, StateSignatureKey},
+        signer::{ConsensusSigner, SignableMessage},
         storage::Storage,
         BlockPayload, ValidatedState,
     },
@@ -61,7 +62,7 @@ pub(crate) async fn fetch_proposal<TYPES: NodeType, V: Versions>(
     membership_coordinator: EpochMembershipCoordinator<TYPES>,
     consensus: OuterConsensus<TYPES>,
     sender_public_key: TYPES::SignatureKey,
-    sender_private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
     upgrade_lock: &UpgradeLock<TYPES, V>,
     epoch_height: u64,
 ) -> Result<(Leaf2<TYPES>, View<TYPES>)> {
@@ -75,12 +76,13 @@ pub(crate) async fn fetch_proposal<TYPES: NodeType, V: Versions>(
     };
 
     // Finally, compute the signature for the payload.
-    let signature = TYPES::SignatureKey::sign(
-        &sender_private_key,
-        signed_proposal_request.commit().as_ref(),
-    )
-    .wrap()
-    .context(error!("Failed to sign proposal. This should never happen."))?;
+    let signature = signer
+        .sign(&SignableMessage::ProposalRequest(
+            signed_proposal_request.clone(),
+        ))
+        .await
+        .wrap()
+        .context(error!("Failed to sign proposal request"))?;
 
     tracing::info!("Sending proposal request for view {}", view_number);
 
@@ -601,7 +603,7 @@ pub(crate) async fn parent_leaf_and_state<TYPES: NodeType, V: Versions>(
     event_receiver: &Receiver<Arc<HotShotEvent<TYPES>>>,
     membership: EpochMembershipCoordinator<TYPES>,
     public_key: TYPES::SignatureKey,
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
     consensus: OuterConsensus<TYPES>,
     upgrade_lock: &UpgradeLock<TYPES, V>,
     parent_qc: &QuorumCertificate2<TYPES>,
@@ -621,7 +623,7 @@ pub(crate) async fn parent_leaf_and_state<TYPES: NodeType, V: Versions>(
             membership,
             consensus.clone(),
             public_key.clone(),
-            private_key.clone(),
+            signer,
             upgrade_lock,
             epoch_height,
         )
@@ -61,3 +61,6 @@ pub mod quorum_proposal_recv;
 
 /// Task for storing and replaying all received tasks by a node
 pub mod rewind;
+
+/// Binary format for recordings of the events received by a node
+pub mod event_log;
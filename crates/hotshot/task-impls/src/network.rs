@@ -12,10 +12,11 @@ use std::{
 
 use async_broadcast::{Receiver, Sender};
 use async_trait::async_trait;
+use committable::Committable;
 use hotshot_task::task::TaskState;
 use hotshot_types::{
     consensus::OuterConsensus,
-    data::{VidDisperse, VidDisperseShare},
+    data::{Leaf, Leaf2, QuorumProposalWrapper, VidDisperse, VidDisperseShare},
     epoch_membership::EpochMembershipCoordinator,
     event::{Event, EventType, HotShotAction},
     message::{
@@ -29,11 +30,13 @@ use hotshot_types::{
             ViewMessage,
         },
         node_implementation::{ConsensusTime, NodeType, Versions},
+        signer::SignedMessageKind,
         storage::Storage,
     },
     vote::{HasViewNumber, Vote},
 };
 use hotshot_utils::anytrace::*;
+use sha2::{Digest, Sha256};
 use tokio::{spawn, task::JoinHandle};
 use tracing::instrument;
 
@@ -654,6 +657,7 @@ impl<
         spawn(async move {
             if NetworkEventTaskState::<TYPES, V, NET, S>::maybe_record_action(
                 Some(HotShotAction::VidDisperse),
+                None,
                 storage,
                 consensus,
                 view,
@@ -674,23 +678,30 @@ impl<
     }
 
     /// Record `HotShotAction` if available
+    ///
+    /// If the action signed a message, `signed` is the kind of that message and a commitment to
+    /// it, which storage records so that it can refuse to sign a conflicting message later.
     async fn maybe_record_action(
         maybe_action: Option<HotShotAction>,
+        signed: Option<(SignedMessageKind, [u8; 32])>,
         storage: S,
         consensus: OuterConsensus<TYPES>,
         view: <TYPES as NodeType>::View,
         epoch: Option<<TYPES as NodeType>::Epoch>,
     ) -> std::result::Result<(), ()> {
-        if let Some(mut action) = maybe_action {
+        if let Some(action) = maybe_action {
             if !consensus.write().await.update_action(action, view) {
                 return Err(());
             }
-            // If the action was view sync record it as a vote, but we don't
-            // want to limit to 1 View sync vote above so change the action here.
-            if matches!(action, HotShotAction::ViewSyncVote) {
-                action = HotShotAction::Vote;
-            }
-            match storage.record_action(view, epoch, action).await {
+            let res = match signed {
+                Some((kind, commitment)) => {
+                    storage
+                        .record_signed_action(view, epoch, action, kind, commitment)
+                        .await
+                },
+                None => storage.record_action(view, epoch, action).await,
+            };
+            match res {
                 Ok(()) => Ok(()),
                 Err(e) => {
                     tracing::warn!("Not Sending {action:?} because of storage error: {e:?}");
@@ -1089,14 +1100,18 @@ impl<
 
                 Some((vote.signing_key(), message, TransmitType::Direct(leader)))
             },
-            HotShotEvent::UpgradeProposalSend(proposal, sender) => Some((
-                sender,
-                MessageKind::<TYPES>::from_consensus_message(SequencingMessage::General(
-                    GeneralConsensusMessage::UpgradeProposal(proposal),
-                )),
-                TransmitType::Broadcast,
-            )),
+            HotShotEvent::UpgradeProposalSend(proposal, sender) => {
+                *maybe_action = Some(HotShotAction::UpgradePropose);
+                Some((
+                    sender,
+                    MessageKind::<TYPES>::from_consensus_message(SequencingMessage::General(
+                        GeneralConsensusMessage::UpgradeProposal(proposal),
+                    )),
+                    TransmitType::Broadcast,
+                ))
+            },
             HotShotEvent::UpgradeVoteSend(vote) => {
+                *maybe_action = Some(HotShotAction::UpgradeVote);
                 tracing::error!("Sending upgrade vote!");
                 let view_number = vote.view_number();
                 let leader = match self
@@ -1248,9 +1263,11 @@ impl<
         let storage = self.storage.clone();
         let consensus = OuterConsensus::new(Arc::clone(&self.consensus.inner_consensus));
         let upgrade_lock = self.upgrade_lock.clone();
+        let signed = signed_commitment(&message.kind);
         let handle = spawn(async move {
             if NetworkEventTaskState::<TYPES, V, NET, S>::maybe_record_action(
                 maybe_action,
+                signed,
                 storage.clone(),
                 consensus,
                 view_number,
@@ -1314,6 +1331,94 @@ impl<
     }
 }
 
+/// The kind of an outgoing message and the commitment to its signed content, if it is one storage
+/// must record.
+///
+/// Votes commit to their vote data, quorum proposals to the proposed leaf, DA proposals to the
+/// hash of the proposed payload, and upgrade proposals to the proposed upgrade.
+fn signed_commitment<TYPES: NodeType>(
+    kind: &MessageKind<TYPES>,
+) -> Option<(SignedMessageKind, [u8; 32])> {
+    let MessageKind::Consensus(message) = kind else {
+        return None;
+    };
+    let signed = match message {
+        SequencingMessage::General(message) => match message {
+            GeneralConsensusMessage::Proposal(proposal) => (
+                SignedMessageKind::QuorumProposal,
+                Leaf::from_quorum_proposal(&proposal.data).commit().into(),
+            ),
+            GeneralConsensusMessage::Proposal2(proposal) => (
+                SignedMessageKind::QuorumProposal,
+                Leaf2::from_quorum_proposal(&QuorumProposalWrapper::from(proposal.data.clone()))
+                    .commit()
+                    .into(),
+            ),
+            GeneralConsensusMessage::Vote(vote) => {
+                (SignedMessageKind::QuorumVote, vote.data_commitment().into())
+            },
+            GeneralConsensusMessage::Vote2(vote) => {
+                (SignedMessageKind::QuorumVote, vote.data_commitment().into())
+            },
+            GeneralConsensusMessage::EpochRootQuorumVote(vote) => (
+                SignedMessageKind::QuorumVote,
+                vote.vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::TimeoutVote(vote) => (
+                SignedMessageKind::TimeoutVote,
+                vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::TimeoutVote2(vote) => (
+                SignedMessageKind::TimeoutVote,
+                vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::ViewSyncCommitVote(vote) => (
+                SignedMessageKind::ViewSyncVote,
+                vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::ViewSyncCommitVote2(vote) => (
+                SignedMessageKind::ViewSyncVote,
+                vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::ViewSyncFinalizeVote(vote) => (
+                SignedMessageKind::ViewSyncVote,
+                vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::ViewSyncFinalizeVote2(vote) => (
+                SignedMessageKind::ViewSyncVote,
+                vote.data_commitment().into(),
+            ),
+            GeneralConsensusMessage::UpgradeProposal(proposal) => (
+                SignedMessageKind::UpgradeProposal,
+                proposal.data.upgrade_proposal.commit().into(),
+            ),
+            GeneralConsensusMessage::UpgradeVote(vote) => (
+                SignedMessageKind::UpgradeVote,
+                vote.data_commitment().into(),
+            ),
+            _ => return None,
+        },
+        SequencingMessage::Da(message) => match message {
+            DaConsensusMessage::DaProposal(proposal) => (
+                SignedMessageKind::DaProposal,
+                Sha256::digest(&proposal.data.encoded_transactions).into(),
+            ),
+            DaConsensusMessage::DaProposal2(proposal) => (
+                SignedMessageKind::DaProposal,
+                Sha256::digest(&proposal.data.encoded_transactions).into(),
+            ),
+            DaConsensusMessage::DaVote(vote) => {
+                (SignedMessageKind::DaVote, vote.data_commitment().into())
+            },
+            DaConsensusMessage::DaVote2(vote) => {
+                (SignedMessageKind::DaVote, vote.data_commitment().into())
+            },
+            _ => return None,
+        },
+    };
+    Some(signed)
+}
+
 /// A module with test helpers
 pub mod test {
     use std::ops::{Deref, DerefMut};
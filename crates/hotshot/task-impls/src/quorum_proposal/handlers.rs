@@ -29,7 +29,7 @@ use hotshot_types::{
     traits::{
         block_contents::BlockHeader,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
-        signature_key::SignatureKey,
+        signer::{ConsensusSigner, SignableMessage},
         BlockPayload,
     },
     utils::{
@@ -97,8 +97,8 @@ pub struct ProposalDependencyHandle<TYPES: NodeType, V: Versions> {
     /// Our public key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for proposals
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Shared consensus task state
     pub consensus: OuterConsensus<TYPES>,
@@ -420,7 +420,7 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
             &self.receiver,
             self.membership.coordinator.clone(),
             self.public_key.clone(),
-            self.private_key.clone(),
+            Arc::clone(&self.signer),
             OuterConsensus::new(Arc::clone(&self.consensus.inner_consensus)),
             &self.upgrade_lock,
             &parent_qc,
@@ -618,10 +618,12 @@ impl<TYPES: NodeType, V: Versions> ProposalDependencyHandle<TYPES, V> {
             "Proposed leaf parent does not equal high qc"
         );
 
-        let signature =
-            TYPES::SignatureKey::sign(&self.private_key, proposed_leaf.commit().as_ref())
-                .wrap()
-                .context(error!("Failed to compute proposed_leaf.commit()"))?;
+        let signature = self
+            .signer
+            .sign(&SignableMessage::QuorumProposal(proposal.clone()))
+            .await
+            .wrap()
+            .context(error!("Failed to sign proposed leaf"))?;
 
         let message = Proposal {
             data: proposal,
@@ -24,7 +24,7 @@ use hotshot_types::{
     },
     traits::{
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
-        signature_key::SignatureKey,
+        signer::ConsensusSigner,
         storage::Storage,
     },
     utils::{is_epoch_transition, EpochTransitionIndicator},
@@ -67,8 +67,8 @@ pub struct QuorumProposalTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>
     /// Our public key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for votes and proposals
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// View timeout from config.
     pub timeout: u64,
@@ -394,7 +394,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
                 receiver: event_receiver,
                 membership: epoch_membership,
                 public_key: self.public_key.clone(),
-                private_key: self.private_key.clone(),
+                signer: Arc::clone(&self.signer),
                 instance_state: Arc::clone(&self.instance_state),
                 consensus: OuterConsensus::new(Arc::clone(&self.consensus.inner_consensus)),
                 timeout: self.timeout,
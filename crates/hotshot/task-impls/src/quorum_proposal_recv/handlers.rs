@@ -22,7 +22,7 @@ use hotshot_types::{
         block_contents::{BlockHeader, BlockPayload},
         election::Membership,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
-        signature_key::SignatureKey,
+        signer::ConsensusSigner,
         storage::Storage,
         ValidatedState,
     },
@@ -58,7 +58,7 @@ fn spawn_fetch_proposal<TYPES: NodeType, V: Versions>(
     membership: EpochMembershipCoordinator<TYPES>,
     consensus: OuterConsensus<TYPES>,
     sender_public_key: TYPES::SignatureKey,
-    sender_private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
     upgrade_lock: UpgradeLock<TYPES, V>,
     epoch_height: u64,
 ) {
@@ -73,7 +73,7 @@ fn spawn_fetch_proposal<TYPES: NodeType, V: Versions>(
             membership,
             consensus,
             sender_public_key,
-            sender_private_key,
+            signer,
             &lock,
             epoch_height,
         )
@@ -351,7 +351,7 @@ pub(crate) async fn handle_quorum_proposal_recv<
             // This is because the key that we receive is for the prior leader, so the payload would be routed
             // incorrectly.
             validation_info.public_key.clone(),
-            validation_info.private_key.clone(),
+            Arc::clone(&validation_info.signer),
             validation_info.upgrade_lock.clone(),
             validation_info.epoch_height,
         );
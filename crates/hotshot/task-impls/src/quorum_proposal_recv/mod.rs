@@ -26,6 +26,7 @@ use hotshot_types::{
         block_contents::BlockHeader,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
         signature_key::SignatureKey,
+        signer::ConsensusSigner,
     },
     utils::option_epoch_from_block_number,
     vote::{Certificate, HasViewNumber},
@@ -49,8 +50,8 @@ pub struct QuorumProposalRecvTaskState<TYPES: NodeType, I: NodeImplementation<TY
     /// Our public key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for requests to peers
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Reference to consensus. The replica will require a write lock on this.
     pub consensus: OuterConsensus<TYPES>,
@@ -96,8 +97,8 @@ pub(crate) struct ValidationInfo<TYPES: NodeType, I: NodeImplementation<TYPES>,
     /// Our public key
     pub(crate) public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub(crate) private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for requests to peers
+    pub(crate) signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Reference to consensus. The replica will require a write lock on this.
     pub(crate) consensus: OuterConsensus<TYPES>,
@@ -170,7 +171,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions>
                 let validation_info = ValidationInfo::<TYPES, I, V> {
                     id: self.id,
                     public_key: self.public_key.clone(),
-                    private_key: self.private_key.clone(),
+                    signer: Arc::clone(&self.signer),
                     consensus: self.consensus.clone(),
                     membership: epoch_membership,
                     output_event_stream: self.output_event_stream.clone(),
@@ -22,7 +22,7 @@ use hotshot_types::{
         block_contents::BlockHeader,
         election::Membership,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType},
-        signature_key::{SignatureKey, StateSignatureKey},
+        signer::ConsensusSigner,
         storage::Storage,
         ValidatedState,
     },
@@ -355,7 +355,7 @@ pub(crate) async fn update_shared_state<
     receiver: InactiveReceiver<Arc<HotShotEvent<TYPES>>>,
     membership: EpochMembershipCoordinator<TYPES>,
     public_key: TYPES::SignatureKey,
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
     upgrade_lock: UpgradeLock<TYPES, V>,
     view_number: TYPES::View,
     instance_state: Arc<TYPES::InstanceState>,
@@ -394,7 +394,7 @@ pub(crate) async fn update_shared_state<
                 membership.clone(),
                 OuterConsensus::new(Arc::clone(&consensus.inner_consensus)),
                 public_key.clone(),
-                private_key.clone(),
+                signer,
                 &upgrade_lock,
                 epoch_height,
             )
@@ -468,7 +468,7 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     sender: Sender<Arc<HotShotEvent<TYPES>>>,
     membership: EpochMembership<TYPES>,
     public_key: TYPES::SignatureKey,
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
     upgrade_lock: UpgradeLock<TYPES, V>,
     view_number: TYPES::View,
     storage: I::Storage,
@@ -477,7 +477,6 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     extended_vote: bool,
     epoch_root_vote: bool,
     epoch_height: u64,
-    state_private_key: &<TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
 ) -> Result<()> {
     let committee_member_in_current_epoch = membership.has_stake(&public_key).await;
     // If the proposed leaf is for the last block in the epoch and the node is part of the quorum committee
@@ -502,7 +501,7 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     };
 
     // Create and send the vote.
-    let vote = QuorumVote2::<TYPES>::create_signed_vote(
+    let vote = QuorumVote2::<TYPES>::create_vote_with_signer(
         QuorumData2 {
             leaf_commit: leaf.commit(),
             epoch: membership.epoch(),
@@ -510,7 +509,7 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
         },
         view_number,
         &public_key,
-        &private_key,
+        signer.as_ref(),
         &upgrade_lock,
     )
     .await
@@ -553,13 +552,11 @@ pub(crate) async fn submit_vote<TYPES: NodeType, I: NodeImplementation<TYPES>, V
         )
         .wrap()
         .context(error!("Failed to compute stake table commitment"))?;
-        let signature = <TYPES::StateSignatureKey as StateSignatureKey>::sign_state(
-            state_private_key,
-            &light_client_state,
-            &next_stake_table_state,
-        )
-        .wrap()
-        .context(error!("Failed to sign the light client state"))?;
+        let signature = signer
+            .sign_state(&light_client_state, &next_stake_table_state)
+            .await
+            .wrap()
+            .context(error!("Failed to sign the light client state"))?;
         let state_vote = LightClientStateUpdateVote {
             epoch: TYPES::Epoch::new(epoch_from_block_number(leaf.height(), epoch_height)),
             light_client_state,
@@ -24,7 +24,8 @@ use hotshot_types::{
     traits::{
         block_contents::BlockHeader,
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
-        signature_key::{SignatureKey, StateSignatureKey},
+        signature_key::SignatureKey,
+        signer::ConsensusSigner,
         storage::Storage,
     },
     utils::{is_epoch_root, is_epoch_transition, is_last_block, option_epoch_from_block_number},
@@ -60,9 +61,6 @@ pub struct VoteDependencyHandle<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     /// Public key.
     pub public_key: TYPES::SignatureKey,
 
-    /// Private Key.
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
-
     /// Reference to consensus. The replica will require a write lock on this.
     pub consensus: OuterConsensus<TYPES>,
 
@@ -96,8 +94,8 @@ pub struct VoteDependencyHandle<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     /// Number of blocks in an epoch, zero means there are no epochs
     pub epoch_height: u64,
 
-    /// Signature key for light client state
-    pub state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+    /// Signer for votes and light client states
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// View timeout from config.
     pub timeout: u64,
@@ -298,7 +296,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions> Handl
             self.receiver.clone(),
             self.membership_coordinator.clone(),
             self.public_key.clone(),
-            self.private_key.clone(),
+            Arc::clone(&self.signer),
             self.upgrade_lock.clone(),
             self.view_number,
             Arc::clone(&self.instance_state),
@@ -359,7 +357,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions> Handl
                 self.sender.clone(),
                 epoch_membership,
                 self.public_key.clone(),
-                self.private_key.clone(),
+                Arc::clone(&self.signer),
                 self.upgrade_lock.clone(),
                 self.view_number,
                 self.storage.clone(),
@@ -368,7 +366,6 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + 'static, V: Versions> Handl
                 is_vote_leaf_extended,
                 is_vote_epoch_root,
                 self.epoch_height,
-                &self.state_private_key,
             )
             .await
         )
@@ -382,9 +379,6 @@ pub struct QuorumVoteTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V:
     /// Public key.
     pub public_key: TYPES::SignatureKey,
 
-    /// Private Key.
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
-
     /// Reference to consensus. The replica will require a write lock on this.
     pub consensus: OuterConsensus<TYPES>,
 
@@ -421,8 +415,8 @@ pub struct QuorumVoteTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V:
     /// Number of blocks in an epoch, zero means there are no epochs
     pub epoch_height: u64,
 
-    /// Signature key for light client state
-    pub state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+    /// Signer for votes and light client states
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// View timeout from config.
     pub timeout: u64,
@@ -519,7 +513,6 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> QuorumVoteTaskS
             dependency_chain,
             VoteDependencyHandle::<TYPES, I, V> {
                 public_key: self.public_key.clone(),
-                private_key: self.private_key.clone(),
                 consensus: OuterConsensus::new(Arc::clone(&self.consensus.inner_consensus)),
                 instance_state: Arc::clone(&self.instance_state),
                 membership_coordinator: self.membership.clone(),
@@ -531,7 +524,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> QuorumVoteTaskS
                 id: self.id,
                 epoch_height: self.epoch_height,
                 consensus_metrics: Arc::clone(&self.consensus_metrics),
-                state_private_key: self.state_private_key.clone(),
+                signer: Arc::clone(&self.signer),
                 timeout: self.timeout,
                 view_start_time: Instant::now(),
             },
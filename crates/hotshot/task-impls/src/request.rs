@@ -25,13 +25,13 @@ use hotshot_types::{
         network::{ConnectedNetwork, DataRequest, RequestKind},
         node_implementation::{NodeImplementation, NodeType},
         signature_key::SignatureKey,
+        signer::{ConsensusSigner, SignableMessage},
     },
     utils::is_epoch_transition,
     vote::HasViewNumber,
 };
 use hotshot_utils::anytrace::*;
 use rand::{seq::SliceRandom, thread_rng};
-use sha2::{Digest, Sha256};
 use tokio::{spawn, task::JoinHandle, time::sleep};
 use tracing::instrument;
 
@@ -65,8 +65,8 @@ pub struct NetworkRequestState<TYPES: NodeType, I: NodeImplementation<TYPES>> {
     /// This nodes public key
     pub public_key: TYPES::SignatureKey,
 
-    /// This nodes private/signing key, used to sign requests.
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for requests to peers
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// The node's id
     pub id: u64,
@@ -242,7 +242,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> NetworkRequestState<TYPES, I
         let request = RequestKind::Vid(view, self.public_key.clone());
 
         // First sign the request for the VID shares.
-        if let Some(signature) = self.serialize_and_sign(&request) {
+        if let Some(signature) = self.sign_request(&request).await {
             self.create_vid_request_task(
                 request,
                 signature,
@@ -400,17 +400,18 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>> NetworkRequestState<TYPES, I
         cancel
     }
 
-    /// Sign the serialized version of the request
-    fn serialize_and_sign(&self, request: &RequestKind<TYPES>) -> Option<Signature<TYPES>> {
-        let Ok(data) = bincode::serialize(&request) else {
-            tracing::error!("Failed to serialize request!");
-            return None;
-        };
-        let Ok(signature) = TYPES::SignatureKey::sign(&self.private_key, &Sha256::digest(data))
-        else {
-            tracing::error!("Failed to sign Data Request");
-            return None;
-        };
-        Some(signature)
+    /// Sign the request
+    async fn sign_request(&self, request: &RequestKind<TYPES>) -> Option<Signature<TYPES>> {
+        match self
+            .signer
+            .sign(&SignableMessage::DataRequest(request.clone()))
+            .await
+        {
+            Ok(signature) => Some(signature),
+            Err(err) => {
+                tracing::error!("Failed to sign Data Request: {err:#}");
+                None
+            },
+        }
     }
 }
@@ -17,6 +17,7 @@ use hotshot_types::{
         network::DataRequest,
         node_implementation::{NodeType, Versions},
         signature_key::SignatureKey,
+        signer::ConsensusSigner,
     },
     utils::{View, ViewInner},
 };
@@ -41,8 +42,8 @@ pub struct NetworkResponseState<TYPES: NodeType, V: Versions> {
     /// This replicas public key
     pub_key: TYPES::SignatureKey,
 
-    /// This replicas private key
-    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for VID shares calculated on request
+    signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// The node's id
     id: u64,
@@ -57,7 +58,7 @@ impl<TYPES: NodeType, V: Versions> NetworkResponseState<TYPES, V> {
         consensus: LockedConsensusState<TYPES>,
         membership: EpochMembershipCoordinator<TYPES>,
         pub_key: TYPES::SignatureKey,
-        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: Arc<dyn ConsensusSigner<TYPES>>,
         id: u64,
         upgrade_lock: UpgradeLock<TYPES, V>,
     ) -> Self {
@@ -65,7 +66,7 @@ impl<TYPES: NodeType, V: Versions> NetworkResponseState<TYPES, V> {
             consensus,
             membership,
             pub_key,
-            private_key,
+            signer,
             id,
             upgrade_lock,
         }
@@ -206,7 +207,7 @@ impl<TYPES: NodeType, V: Versions> NetworkResponseState<TYPES, V> {
                 view,
                 target_epoch,
                 self.membership.clone(),
-                &self.private_key,
+                self.signer.as_ref(),
                 &self.upgrade_lock,
             )
             .await
@@ -219,7 +220,7 @@ impl<TYPES: NodeType, V: Versions> NetworkResponseState<TYPES, V> {
                     view,
                     target_epoch,
                     self.membership.clone(),
-                    &self.private_key,
+                    self.signer.as_ref(),
                     &self.upgrade_lock,
                 )
                 .await;
@@ -4,7 +4,7 @@
 // You should have received a copy of the MIT License
 // along with the HotShot repository. If not, see <https://mit-license.org/>.
 
-use std::{fs::OpenOptions, io::Write, sync::Arc};
+use std::{fs::File, io::BufWriter, marker::PhantomData, sync::Arc};
 
 use async_broadcast::{Receiver, Sender};
 use async_trait::async_trait;
@@ -12,22 +12,63 @@ use hotshot_task::task::TaskState;
 use hotshot_types::traits::node_implementation::NodeType;
 use hotshot_utils::anytrace::Result;
 
-use crate::events::HotShotEvent;
+use crate::{event_log::EventLogWriter, events::HotShotEvent};
 
 /// The task state for the `Rewind` task is used to capture all events received
 /// by a particular node, in the order they've been received.
+///
+/// Events are streamed to an [event log](crate::event_log) in `rewind_{id}.bin`, which can be
+/// replayed with [`replay_event_log`](crate::harness::replay_event_log).
 pub struct RewindTaskState<TYPES: NodeType> {
-    /// All events received by this node since the beginning of time.
-    pub events: Vec<Arc<HotShotEvent<TYPES>>>,
+    /// The log events are recorded to, if it could be created.
+    log: Option<EventLogWriter<BufWriter<File>>>,
+
+    /// The number of events recorded so far.
+    recorded: usize,
 
     /// The id of this node
     pub id: u64,
+
+    /// Phantom for `TYPES`
+    _pd: PhantomData<TYPES>,
 }
 
 impl<TYPES: NodeType> RewindTaskState<TYPES> {
-    /// Handles all events, storing them to the private state
+    /// Start recording the events received by node `id`.
+    ///
+    /// If the log file cannot be created, the error is logged and no events are recorded.
+    #[must_use]
+    pub fn new(id: u64) -> Self {
+        let filename = format!("rewind_{id}.bin");
+        let log = match EventLogWriter::create(&filename, id) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                tracing::error!("Failed to create event log {filename}; error = {e:#}");
+                None
+            },
+        };
+        Self {
+            log,
+            recorded: 0,
+            id,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Handles all events, recording them to the event log
     pub fn handle(&mut self, event: &Arc<HotShotEvent<TYPES>>) {
-        self.events.push(Arc::clone(event));
+        let Some(log) = &mut self.log else {
+            return;
+        };
+        // We do not want to die here, so we log and move on capturing as many events as we can.
+        if let Err(e) = log.record(event) {
+            tracing::error!(
+                "Failed to record event number {} and event {event}; error = {e:#}",
+                self.recorded
+            );
+            return;
+        }
+        self.recorded += 1;
     }
 }
 
@@ -46,27 +87,10 @@ impl<TYPES: NodeType> TaskState for RewindTaskState<TYPES> {
     }
 
     fn cancel_subtasks(&mut self) {
-        tracing::info!("Node ID {} Recording {} events", self.id, self.events.len());
-        let filename = format!("rewind_{}.log", self.id);
-        let mut file = match OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&filename)
-        {
-            Ok(file) => file,
-            Err(e) => {
-                tracing::error!("Failed to write file {filename}; error = {e}");
-                return;
-            },
-        };
-
-        for (event_number, event) in self.events.iter().enumerate() {
-            // We do not want to die here, so we log and move on capturing as many events as we can.
-            if let Err(e) = writeln!(file, "{event_number}: {event}") {
-                tracing::error!(
-                    "Failed to write event number {event_number} and event {event}; error = {e}"
-                );
+        tracing::info!("Node ID {} Recorded {} events", self.id, self.recorded);
+        if let Some(log) = &mut self.log {
+            if let Err(e) = log.flush() {
+                tracing::error!("Failed to flush event log; error = {e}");
             }
         }
     }
@@ -27,6 +27,7 @@ use hotshot_types::{
         block_contents::{BuilderFee, EncodeBytes},
         node_implementation::{ConsensusTime, HasUrls, NodeImplementation, NodeType, Versions},
         signature_key::{BuilderSignatureKey, SignatureKey},
+        signer::{ConsensusSigner, SignableMessage},
         BlockPayload,
     },
     utils::{is_epoch_transition, is_last_block, ViewInner},
@@ -100,8 +101,8 @@ pub struct TransactionTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V
     /// This Nodes Public Key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for requests to builders
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// InstanceState
     pub instance_state: Arc<TYPES::InstanceState>,
@@ -653,10 +654,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> TransactionTask
             },
         };
 
-        let parent_comm_sig = match <<TYPES as NodeType>::SignatureKey as SignatureKey>::sign(
-            &self.private_key,
-            parent_comm.as_ref(),
-        ) {
+        let parent_comm_sig = match self
+            .signer
+            .sign(&SignableMessage::BuilderRequest {
+                view: block_view,
+                hash: AsRef::<[u8]>::as_ref(&parent_comm).to_vec(),
+            })
+            .await
+        {
             Ok(sig) => sig,
             Err(err) => {
                 tracing::error!(%err, "Failed to sign block hash");
@@ -801,10 +806,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> TransactionTask
                 continue;
             }
 
-            let request_signature = match <<TYPES as NodeType>::SignatureKey as SignatureKey>::sign(
-                &self.private_key,
-                block_info.block_hash.as_ref(),
-            ) {
+            let request_signature = match self
+                .signer
+                .sign(&SignableMessage::BuilderRequest {
+                    view: view_number,
+                    hash: block_info.block_hash.as_ref().to_vec(),
+                })
+                .await
+            {
                 Ok(request_signature) => request_signature,
                 Err(err) => {
                     tracing::error!(%err, "Failed to sign block hash");
@@ -8,7 +8,6 @@ use std::{marker::PhantomData, sync::Arc, time::SystemTime};
 
 use async_broadcast::{Receiver, Sender};
 use async_trait::async_trait;
-use committable::Committable;
 use hotshot_task::task::TaskState;
 use hotshot_types::{
     consensus::OuterConsensus,
@@ -21,7 +20,7 @@ use hotshot_types::{
     traits::{
         block_contents::BlockHeader,
         node_implementation::{ConsensusTime, NodeType, Versions},
-        signature_key::SignatureKey,
+        signer::{ConsensusSigner, SignableMessage},
     },
     utils::{epoch_from_block_number, EpochTransitionIndicator},
     vote::HasViewNumber,
@@ -56,8 +55,8 @@ pub struct UpgradeTaskState<TYPES: NodeType, V: Versions> {
     /// This Nodes public key
     pub public_key: TYPES::SignatureKey,
 
-    /// This Nodes private key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for upgrade votes and proposals
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// This state's ID
     pub id: u64,
@@ -255,11 +254,11 @@ impl<TYPES: NodeType, V: Versions> UpgradeTaskState<TYPES, V> {
                 .await;
 
                 // If everything is fine up to here, we generate and send a vote on the proposal.
-                let vote = UpgradeVote::create_signed_vote(
+                let vote = UpgradeVote::create_vote_with_signer(
                     proposal.data.upgrade_proposal.clone(),
                     view,
                     &self.public_key,
-                    &self.private_key,
+                    self.signer.as_ref(),
                     &self.upgrade_lock,
                 )
                 .await?;
@@ -377,17 +376,18 @@ impl<TYPES: NodeType, V: Versions> UpgradeTaskState<TYPES, V> {
                     };
 
                     let upgrade_proposal = UpgradeProposal {
-                        upgrade_proposal: upgrade_proposal_data.clone(),
+                        upgrade_proposal: upgrade_proposal_data,
                         view_number: TYPES::View::new(
                             view + TYPES::UPGRADE_CONSTANTS.propose_offset,
                         ),
                     };
 
-                    let signature = TYPES::SignatureKey::sign(
-                        &self.private_key,
-                        upgrade_proposal_data.commit().as_ref(),
-                    )
-                    .expect("Failed to sign upgrade proposal commitment!");
+                    let signature = self
+                        .signer
+                        .sign(&SignableMessage::UpgradeProposal(upgrade_proposal.clone()))
+                        .await
+                        .wrap()
+                        .context(error!("Failed to sign upgrade proposal commitment"))?;
 
                     tracing::warn!("Sending upgrade proposal:\n\n {:?}", upgrade_proposal);
 
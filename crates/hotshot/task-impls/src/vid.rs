@@ -18,7 +18,7 @@ use hotshot_types::{
     traits::{
         block_contents::BlockHeader,
         node_implementation::{NodeImplementation, NodeType, Versions},
-        signature_key::SignatureKey,
+        signer::ConsensusSigner,
         BlockPayload,
     },
     utils::{is_epoch_transition, option_epoch_from_block_number},
@@ -51,8 +51,8 @@ pub struct VidTaskState<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versio
     /// This Nodes Public Key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for VID dispersals
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// This state's ID
     pub id: u64,
@@ -114,6 +114,21 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> VidTaskState<TY
                 .ok()?;
                 let payload_commitment = vid_disperse.payload_commitment();
                 let shares = VidDisperseShare::from_vid_disperse(vid_disperse.clone());
+                // The dispersal and every share of it commit to the same payload, so one
+                // signature covers them all.
+                let signature = match VidDisperseShare::sign_dispersal(
+                    &shares,
+                    &self.membership_coordinator,
+                    self.signer.as_ref(),
+                )
+                .await
+                {
+                    Ok(signature) => signature,
+                    Err(err) => {
+                        error!("VID: failed to sign dispersal payload: {err:#}");
+                        return None;
+                    },
+                };
                 let payload_with_metadata = Arc::new(PayloadWithMetadata {
                     payload,
                     metadata: metadata.clone(),
@@ -127,9 +142,14 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> VidTaskState<TY
                     tracing::debug!(error=?e);
                 }
                 for share in shares {
-                    if let Some(share) = share.to_proposal(&self.private_key) {
-                        consensus_writer.update_vid_shares(*view_number, share);
-                    }
+                    consensus_writer.update_vid_shares(
+                        *view_number,
+                        Proposal {
+                            data: share,
+                            signature: signature.clone(),
+                            _pd: PhantomData,
+                        },
+                    );
                 }
                 drop(consensus_writer);
 
@@ -148,13 +168,6 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> VidTaskState<TY
                 .await;
 
                 let view_number = *view_number;
-                let Ok(signature) = TYPES::SignatureKey::sign(
-                    &self.private_key,
-                    vid_disperse.payload_commitment_ref(),
-                ) else {
-                    error!("VID: failed to sign dispersal payload");
-                    return None;
-                };
                 debug!("publishing VID disperse for view {view_number} and epoch {epoch:?}");
                 broadcast_event(
                     Arc::new(HotShotEvent::VidDisperseSend(
@@ -229,12 +242,18 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> VidTaskState<TY
                 )
                 .await
                 .ok()?;
-                let Ok(next_epoch_signature) = TYPES::SignatureKey::sign(
-                    &self.private_key,
-                    next_epoch_vid_disperse.payload_commitment().as_ref(),
-                ) else {
-                    error!("VID: failed to sign dispersal payload for the next epoch");
-                    return None;
+                let next_epoch_signature = match VidDisperseShare::sign_dispersal(
+                    &VidDisperseShare::from_vid_disperse(next_epoch_vid_disperse.clone()),
+                    &self.membership_coordinator,
+                    self.signer.as_ref(),
+                )
+                .await
+                {
+                    Ok(signature) => signature,
+                    Err(err) => {
+                        error!("VID: failed to sign dispersal payload for the next epoch: {err:#}");
+                        return None;
+                    },
                 };
                 debug!(
                     "publishing VID disperse for view {proposal_view_number} and epoch {target_epoch:?}"
@@ -27,13 +27,14 @@ use hotshot_types::{
     },
     traits::{
         node_implementation::{ConsensusTime, NodeType, Versions},
-        signature_key::SignatureKey,
+        signer::ConsensusSigner,
     },
     utils::EpochTransitionIndicator,
     vote::{Certificate, HasViewNumber, Vote},
     StakeTableEntries,
 };
 use hotshot_utils::anytrace::*;
+use serde::{Deserialize, Serialize};
 use tokio::{spawn, task::JoinHandle, time::sleep};
 use tracing::instrument;
 
@@ -44,7 +45,7 @@ use crate::{
         create_vote_accumulator, AccumulatorInfo, HandleVoteEvent, VoteCollectionTaskState,
     },
 };
-#[derive(PartialEq, PartialOrd, Clone, Debug, Eq, Hash)]
+#[derive(PartialEq, PartialOrd, Clone, Debug, Eq, Hash, Serialize, Deserialize)]
 /// Phases of view sync
 pub enum ViewSyncPhase {
     /// No phase; before the protocol has begun
@@ -91,8 +92,8 @@ pub struct ViewSyncTaskState<TYPES: NodeType, V: Versions> {
     /// This Nodes Public Key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for view sync votes
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Our node id; for logging
     pub id: u64,
@@ -175,8 +176,8 @@ pub struct ViewSyncReplicaTaskState<TYPES: NodeType, V: Versions> {
     /// This Nodes Public Key
     pub public_key: TYPES::SignatureKey,
 
-    /// Our Private Key
-    pub private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// Signer for view sync votes
+    pub signer: Arc<dyn ConsensusSigner<TYPES>>,
 
     /// Lock for a decided upgrade
     pub upgrade_lock: UpgradeLock<TYPES, V>,
@@ -249,7 +250,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncTaskState<TYPES, V> {
             timeout_task: None,
             membership_coordinator: self.membership_coordinator.clone(),
             public_key: self.public_key.clone(),
-            private_key: self.private_key.clone(),
+            signer: Arc::clone(&self.signer),
             view_sync_timeout: self.view_sync_timeout,
             id: self.id,
             upgrade_lock: self.upgrade_lock.clone(),
@@ -618,7 +619,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     self.relay = certificate.data().relay;
                 }
 
-                let Ok(vote) = ViewSyncCommitVote2::<TYPES>::create_signed_vote(
+                let Ok(vote) = ViewSyncCommitVote2::<TYPES>::create_vote_with_signer(
                     ViewSyncCommitData2 {
                         relay: certificate.data().relay,
                         round: self.next_view,
@@ -626,7 +627,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     },
                     self.next_view,
                     &self.public_key,
-                    &self.private_key,
+                    self.signer.as_ref(),
                     &self.upgrade_lock,
                 )
                 .await
@@ -710,7 +711,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     self.relay = certificate.data().relay;
                 }
 
-                let Ok(vote) = ViewSyncFinalizeVote2::<TYPES>::create_signed_vote(
+                let Ok(vote) = ViewSyncFinalizeVote2::<TYPES>::create_vote_with_signer(
                     ViewSyncFinalizeData2 {
                         relay: certificate.data().relay,
                         round: self.next_view,
@@ -718,7 +719,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     },
                     self.next_view,
                     &self.public_key,
-                    &self.private_key,
+                    self.signer.as_ref(),
                     &self.upgrade_lock,
                 )
                 .await
@@ -836,7 +837,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     return None;
                 }
 
-                let Ok(vote) = ViewSyncPreCommitVote2::<TYPES>::create_signed_vote(
+                let Ok(vote) = ViewSyncPreCommitVote2::<TYPES>::create_vote_with_signer(
                     ViewSyncPreCommitData2 {
                         relay: 0,
                         round: view_number,
@@ -844,7 +845,7 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     },
                     view_number,
                     &self.public_key,
-                    &self.private_key,
+                    self.signer.as_ref(),
                     &self.upgrade_lock,
                 )
                 .await
@@ -892,18 +893,19 @@ impl<TYPES: NodeType, V: Versions> ViewSyncReplicaTaskState<TYPES, V> {
                     self.relay += 1;
                     match last_seen_certificate {
                         ViewSyncPhase::None | ViewSyncPhase::PreCommit | ViewSyncPhase::Commit => {
-                            let Ok(vote) = ViewSyncPreCommitVote2::<TYPES>::create_signed_vote(
-                                ViewSyncPreCommitData2 {
-                                    relay: self.relay,
-                                    round: self.next_view,
-                                    epoch: self.cur_epoch,
-                                },
-                                self.next_view,
-                                &self.public_key,
-                                &self.private_key,
-                                &self.upgrade_lock,
-                            )
-                            .await
+                            let Ok(vote) =
+                                ViewSyncPreCommitVote2::<TYPES>::create_vote_with_signer(
+                                    ViewSyncPreCommitData2 {
+                                        relay: self.relay,
+                                        round: self.next_view,
+                                        epoch: self.cur_epoch,
+                                    },
+                                    self.next_view,
+                                    &self.public_key,
+                                    self.signer.as_ref(),
+                                    &self.upgrade_lock,
+                                )
+                                .await
                             else {
                                 tracing::error!("Failed to sign ViewSyncPreCommitData!");
                                 return None;
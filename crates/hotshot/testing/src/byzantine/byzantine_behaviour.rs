@@ -6,10 +6,7 @@ use std::{
 use anyhow::Context;
 use async_lock::RwLock;
 use async_trait::async_trait;
-use hotshot::{
-    tasks::EventTransformerState,
-    types::{SignatureKey, SystemContextHandle},
-};
+use hotshot::{tasks::EventTransformerState, types::SystemContextHandle};
 use hotshot_task_impls::{
     events::HotShotEvent,
     network::{
@@ -22,7 +19,10 @@ use hotshot_types::{
     data::QuorumProposalWrapper,
     message::{Proposal, UpgradeLock},
     simple_vote::QuorumVote2,
-    traits::node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
+    traits::{
+        node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
+        signer::ConsensusSigner,
+    },
 };
 
 #[derive(Debug)]
@@ -46,7 +46,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> EventTransforme
         &mut self,
         event: &HotShotEvent<TYPES>,
         _public_key: &TYPES::SignatureKey,
-        _private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        _signer: &dyn ConsensusSigner<TYPES>,
         _upgrade_lock: &UpgradeLock<TYPES, V>,
         consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
@@ -89,7 +89,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> EventTransforme
         &mut self,
         event: &HotShotEvent<TYPES>,
         _public_key: &TYPES::SignatureKey,
-        _private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        _signer: &dyn ConsensusSigner<TYPES>,
         _upgrade_lock: &UpgradeLock<TYPES, V>,
         _consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
@@ -171,7 +171,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + std::fmt::Debug, V: Version
         &mut self,
         event: &HotShotEvent<TYPES>,
         _public_key: &TYPES::SignatureKey,
-        _private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        _signer: &dyn ConsensusSigner<TYPES>,
         _upgrade_lock: &UpgradeLock<TYPES, V>,
         _consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
@@ -215,7 +215,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + std::fmt::Debug, V: Version
         &mut self,
         event: &HotShotEvent<TYPES>,
         _public_key: &TYPES::SignatureKey,
-        _private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        _signer: &dyn ConsensusSigner<TYPES>,
         _upgrade_lock: &UpgradeLock<TYPES, V>,
         _consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
@@ -285,7 +285,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + std::fmt::Debug, V: Version
         &mut self,
         event: &HotShotEvent<TYPES>,
         _public_key: &TYPES::SignatureKey,
-        _private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        _signer: &dyn ConsensusSigner<TYPES>,
         _upgrade_lock: &UpgradeLock<TYPES, V>,
         _consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
@@ -313,17 +313,17 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + std::fmt::Debug, V: Version
         &mut self,
         event: &HotShotEvent<TYPES>,
         public_key: &TYPES::SignatureKey,
-        private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: &dyn ConsensusSigner<TYPES>,
         upgrade_lock: &UpgradeLock<TYPES, V>,
         _consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
         if let HotShotEvent::QuorumVoteSend(vote) = event {
             let new_view = vote.view_number + self.view_increment;
-            let spoofed_vote = QuorumVote2::<TYPES>::create_signed_vote(
+            let spoofed_vote = QuorumVote2::<TYPES>::create_vote_with_signer(
                 vote.data.clone(),
                 new_view,
                 public_key,
-                private_key,
+                signer,
                 upgrade_lock,
             )
             .await
@@ -389,7 +389,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + std::fmt::Debug, V: Version
         &mut self,
         event: &HotShotEvent<TYPES>,
         public_key: &TYPES::SignatureKey,
-        private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: &dyn ConsensusSigner<TYPES>,
         upgrade_lock: &UpgradeLock<TYPES, V>,
         _consensus: Arc<RwLock<Consensus<TYPES>>>,
     ) -> Vec<HotShotEvent<TYPES>> {
@@ -401,11 +401,11 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES> + std::fmt::Debug, V: Version
                     // Create a vote using data from most recent vote and the current event number
                     // We wont update internal consensus state for this Byzantine replica but we are at least
                     // Going to send a vote to the next honest leader
-                    let vote = QuorumVote2::<TYPES>::create_signed_vote(
+                    let vote = QuorumVote2::<TYPES>::create_vote_with_signer(
                         self.votes_sent.last().unwrap().data.clone(),
                         event.view_number().unwrap(),
                         public_key,
-                        private_key,
+                        signer,
                         upgrade_lock,
                     )
                     .await
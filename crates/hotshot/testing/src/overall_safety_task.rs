@@ -5,14 +5,25 @@
 // along with the HotShot repository. If not, see <https://mit-license.org/>.
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     time::Duration,
 };
 
-use hotshot_types::traits::node_implementation::NodeType;
+use async_trait::async_trait;
+use committable::{Commitment, Committable};
+use hotshot_types::{
+    data::Leaf2,
+    event::{Event, EventType},
+    traits::node_implementation::NodeType,
+};
 use thiserror::Error;
 use tracing::error;
 
+use crate::{
+    spinning_task::NetworkPartition,
+    test_task::{TestResult, TestTaskState},
+};
+
 /// convenience type alias for state and block
 pub type StateAndBlock<S, B> = (Vec<S>, Vec<B>);
 
@@ -62,6 +73,21 @@ pub enum OverallSafetyTaskErr<TYPES: NodeType> {
 
     #[error("View timed out")]
     ViewTimeout,
+
+    #[error("Conflicting decides at height {height}: node {node_id} decided a leaf in view {view:?}, but node {other_node_id} decided a different leaf in view {other_view:?}")]
+    ConflictingDecides {
+        height: u64,
+        node_id: usize,
+        view: TYPES::View,
+        other_node_id: usize,
+        other_view: TYPES::View,
+    },
+
+    #[error("The network did not decide anything after the partition {groups:?} healed in view {heal_view}")]
+    NoDecideAfterPartition {
+        groups: Vec<Vec<usize>>,
+        heal_view: u64,
+    },
 }
 
 /// cross node safety properties
@@ -101,3 +127,115 @@ impl Default for OverallSafetyPropertiesDescription {
         }
     }
 }
+
+/// Task checking safety properties that must hold across all nodes, regardless of which views
+/// succeeded.
+pub struct OverallSafetyTask<TYPES: NodeType> {
+    /// the first leaf decided at each height, with the node and view it was decided in
+    pub decided: BTreeMap<u64, (Commitment<Leaf2<TYPES>>, usize, TYPES::View)>,
+    /// the highest view in which any node decided a leaf
+    pub latest_decided_view: Option<TYPES::View>,
+    /// network partitions introduced during the test, which the network must recover from
+    pub partitions: Vec<NetworkPartition>,
+    /// a list of errors accumulated by the task
+    pub errors: Vec<OverallSafetyTaskErr<TYPES>>,
+}
+
+impl<TYPES: NodeType> OverallSafetyTask<TYPES> {
+    /// Create a new task checking recovery from the given network partitions.
+    pub fn new(partitions: Vec<NetworkPartition>) -> Self {
+        Self {
+            decided: BTreeMap::new(),
+            latest_decided_view: None,
+            partitions,
+            errors: vec![],
+        }
+    }
+
+    /// Record a leaf decided by `node_id`, checking that no other node decided a different leaf
+    /// at the same height.
+    ///
+    /// Unlike the per-view agreement check, this also catches forks where nodes that could not
+    /// communicate (e.g. across a network partition) decide conflicting leaves in different views.
+    fn record_decide(
+        &mut self,
+        node_id: usize,
+        leaf: &Leaf2<TYPES>,
+    ) -> Result<(), OverallSafetyTaskErr<TYPES>> {
+        let view = leaf.view_number();
+        self.latest_decided_view = self.latest_decided_view.max(Some(view));
+
+        match self.decided.entry(leaf.height()) {
+            Entry::Vacant(entry) => {
+                entry.insert((leaf.commit(), node_id, view));
+            },
+            Entry::Occupied(entry) => {
+                let (other_commit, other_node_id, other_view) = entry.get();
+                if leaf.commit() != *other_commit {
+                    return Err(OverallSafetyTaskErr::ConflictingDecides {
+                        height: leaf.height(),
+                        node_id,
+                        view,
+                        other_node_id: *other_node_id,
+                        other_view: *other_view,
+                    });
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Check that the network decided something again after each partition healed.
+    fn check_partitions_healed(&self) -> Result<(), OverallSafetyTaskErr<TYPES>> {
+        for partition in &self.partitions {
+            if !self
+                .latest_decided_view
+                .is_some_and(|view| *view > partition.heal_view)
+            {
+                return Err(OverallSafetyTaskErr::NoDecideAfterPartition {
+                    groups: partition.groups.clone(),
+                    heal_view: partition.heal_view,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<TYPES: NodeType> TestTaskState for OverallSafetyTask<TYPES> {
+    type Event = Event<TYPES>;
+    type Error = OverallSafetyTaskErr<TYPES>;
+
+    async fn handle_event(
+        &mut self,
+        (message, id): (Self::Event, usize),
+    ) -> Result<(), Self::Error> {
+        if let EventType::Decide { leaf_chain, .. } = message.event {
+            for leaf_info in leaf_chain.iter().rev() {
+                if let Err(e) = self.record_decide(id, &leaf_info.leaf) {
+                    error!("{e}");
+                    self.errors.push(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check(&self) -> TestResult {
+        let mut errors: Vec<_> = self.errors.iter().map(|e| e.to_string()).collect();
+
+        if let Err(e) = self.check_partitions_healed() {
+            errors.push(e.to_string());
+        }
+
+        if errors.is_empty() {
+            TestResult::Pass
+        } else {
+            TestResult::Fail(Box::new(errors))
+        }
+    }
+}
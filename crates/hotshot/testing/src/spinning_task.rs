@@ -34,7 +34,7 @@ use hotshot_types::{
         LightClientStateUpdateCertificate, NextEpochQuorumCertificate2, QuorumCertificate2,
     },
     traits::{
-        network::{AsyncGenerator, ConnectedNetwork},
+        network::{AsyncGenerator, ConnectedNetwork, TestableNetworkingImplementation},
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
     },
     utils::genesis_epoch_from_version,
@@ -71,6 +71,12 @@ pub struct SpinningTask<
     pub(crate) late_start: HashMap<u64, LateStartNode<TYPES, I, V>>,
     /// time based changes
     pub(crate) changes: BTreeMap<TYPES::View, Vec<ChangeNode>>,
+    /// time based network partitions, view -> groups of node indices (empty to heal)
+    pub(crate) partition_changes: BTreeMap<TYPES::View, Vec<Vec<usize>>>,
+    /// public keys of the nodes in the test, by node index
+    pub(crate) node_keys: Vec<TYPES::SignatureKey>,
+    /// set if a partition was scheduled but the network does not support partitions
+    pub(crate) partition_unsupported: bool,
     /// most recent view seen by spinning task
     pub(crate) latest_view: Option<TYPES::View>,
     /// Last decided leaf that can be used as the anchor leaf to initialize the node.
@@ -97,7 +103,7 @@ impl<
             BlockHeader = TestBlockHeader,
         >,
         I: TestableNodeImplementation<TYPES>,
-        N: ConnectedNetwork<TYPES::SignatureKey>,
+        N: ConnectedNetwork<TYPES::SignatureKey> + TestableNetworkingImplementation<TYPES>,
         V: Versions,
     > TestTaskState for SpinningTask<TYPES, N, I, V>
 where
@@ -361,6 +367,8 @@ where
                     }
                 }
             }
+            self.apply_partition_changes(view_number).await;
+
             let mut ready_futs = vec![];
             while let Some(net) = new_networks.pop() {
                 ready_futs.push(async move {
@@ -404,7 +412,64 @@ where
     }
 
     async fn check(&self) -> TestResult {
-        TestResult::Pass
+        if self.partition_unsupported {
+            TestResult::Fail(Box::new(
+                "network partitions are not supported by this network",
+            ))
+        } else {
+            TestResult::Pass
+        }
+    }
+}
+
+impl<
+        TYPES: NodeType,
+        N: ConnectedNetwork<TYPES::SignatureKey> + TestableNetworkingImplementation<TYPES>,
+        I: TestableNodeImplementation<TYPES>,
+        V: Versions,
+    > SpinningTask<TYPES, N, I, V>
+where
+    I: NodeImplementation<TYPES, Network = N>,
+{
+    /// Apply the latest partition change scheduled at or before `view_number`.
+    ///
+    /// Changes for skipped views are not lost, so a partition still heals if no node reports the
+    /// exact view it was scheduled to heal in.
+    async fn apply_partition_changes(&mut self, view_number: TYPES::View) {
+        let later = self.partition_changes.split_off(&(view_number + 1));
+        let due = std::mem::replace(&mut self.partition_changes, later);
+        let Some(groups) = due.into_values().last() else {
+            return;
+        };
+        let handles = self.handles.read().await;
+        let Some(node) = handles.first() else {
+            return;
+        };
+
+        if groups.is_empty() {
+            tracing::error!("Healing network partition");
+        } else {
+            tracing::error!("Partitioning network into {:?}", groups);
+        }
+        let groups = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter_map(|&idx| {
+                        let key = self.node_keys.get(idx).cloned();
+                        if key.is_none() {
+                            tracing::error!("No node with index {idx} to partition");
+                        }
+                        key
+                    })
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        if !node.network.partition(&groups) {
+            tracing::error!("Network does not support partitions");
+            self.partition_unsupported = true;
+        }
     }
 }
 
@@ -446,6 +511,36 @@ pub struct ChangeNode {
     pub updown: NodeAction,
 }
 
+/// a partition of the network into groups of nodes which can only communicate within their group
+///
+/// Views in which no group holds a quorum are expected to fail, so tests should list them in
+/// `possible_view_failures` (or `expected_view_failures`).
+#[derive(Clone, Debug)]
+pub struct NetworkPartition {
+    /// the view in which the network is partitioned
+    pub start_view: u64,
+    /// the view in which the partition heals
+    pub heal_view: u64,
+    /// the indices of the nodes in each group; nodes not in any group are cut off from all others
+    pub groups: Vec<Vec<usize>>,
+}
+
+impl NetworkPartition {
+    /// Build the schedule of partition changes, view -> groups (empty to heal).
+    ///
+    /// A partition starting in the same view another one heals replaces it.
+    pub(crate) fn schedule(partitions: &[Self]) -> BTreeMap<u64, Vec<Vec<usize>>> {
+        let mut changes = BTreeMap::new();
+        for partition in partitions {
+            changes.insert(partition.heal_view, vec![]);
+        }
+        for partition in partitions {
+            changes.insert(partition.start_view, partition.groups.clone());
+        }
+        changes
+    }
+}
+
 /// description of the spinning task
 /// (used to build a spinning task)
 #[derive(Clone, Debug)]
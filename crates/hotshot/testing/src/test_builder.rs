@@ -38,7 +38,7 @@ use super::{
 };
 use crate::{
     helpers::{key_pair_for_id, TestNodeKeyMap},
-    spinning_task::SpinningTaskDescription,
+    spinning_task::{NetworkPartition, SpinningTaskDescription},
     test_launcher::{Network, ResourceGenerators, TestLauncher},
     test_task::TestTaskStateSeed,
     view_sync_task::ViewSyncTaskDescription,
@@ -137,6 +137,8 @@ pub struct TestDescription<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Ver
     pub overall_safety_properties: OverallSafetyPropertiesDescription,
     /// spinning properties
     pub spinning_properties: SpinningTaskDescription,
+    /// network partitions to introduce during the test
+    pub partitions: Vec<NetworkPartition>,
     /// txns timing
     pub txn_description: TxnTaskDescription,
     /// completion task
@@ -502,6 +504,7 @@ impl<TYPES: NodeType, I: NodeImplementation<TYPES>, V: Versions> Default
             spinning_properties: SpinningTaskDescription {
                 node_changes: vec![],
             },
+            partitions: vec![],
             overall_safety_properties: OverallSafetyPropertiesDescription::default(),
             // arbitrary, haven't done the math on this
             txn_description: TxnTaskDescription::RoundRobinTimeBased(Duration::from_millis(100)),
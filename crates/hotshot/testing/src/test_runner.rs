@@ -37,8 +37,10 @@ use hotshot_types::{
     simple_certificate::QuorumCertificate2,
     traits::{
         election::Membership,
-        network::ConnectedNetwork,
+        network::{ConnectedNetwork, TestableNetworkingImplementation},
         node_implementation::{ConsensusTime, NodeImplementation, NodeType, Versions},
+        signature_key::StakeTableEntryType,
+        signer::LocalSigner,
         storage::storage_add_drb_result,
     },
     HotShotConfig, ValidatorConfig,
@@ -54,7 +56,8 @@ use super::{
 use crate::{
     block_builder::{BuilderTask, TestBuilderImplementation},
     completion_task::CompletionTaskDescription,
-    spinning_task::{ChangeNode, NodeAction, SpinningTask},
+    overall_safety_task::OverallSafetyTask,
+    spinning_task::{ChangeNode, NetworkPartition, NodeAction, SpinningTask},
     test_builder::create_test_handle,
     test_launcher::{Network, TestLauncher},
     test_task::{spawn_timeout_task, TestResult, TestTask},
@@ -73,7 +76,7 @@ impl<
         >,
         I: TestableNodeImplementation<TYPES>,
         V: Versions,
-        N: ConnectedNetwork<TYPES::SignatureKey>,
+        N: ConnectedNetwork<TYPES::SignatureKey> + TestableNetworkingImplementation<TYPES>,
     > TestRunner<TYPES, I, V, N>
 where
     I: TestableNodeImplementation<TYPES>,
@@ -179,6 +182,11 @@ where
                 .append(&mut change);
         }
 
+        let partition_changes = NetworkPartition::schedule(&meta.partitions)
+            .into_iter()
+            .map(|(view, groups)| (TYPES::View::new(view), groups))
+            .collect();
+
         let spinning_task_state = SpinningTask {
             epoch_height: launcher.metadata.test_config.epoch_height,
             epoch_start_block: launcher.metadata.test_config.epoch_start_block,
@@ -187,6 +195,14 @@ where
             late_start,
             latest_view: None,
             changes,
+            partition_changes,
+            node_keys: meta
+                .test_config
+                .known_nodes_with_stake
+                .iter()
+                .map(|peer| peer.stake_table_entry.public_key())
+                .collect(),
+            partition_unsupported: false,
             last_decided_leaf: Leaf2::genesis::<V>(
                 &TestValidatedState::default(),
                 &TestInstanceState::default(),
@@ -229,6 +245,12 @@ where
             test_receiver.clone(),
         );
 
+        let overall_safety_task = TestTask::<OverallSafetyTask<TYPES>>::new(
+            OverallSafetyTask::new(launcher.metadata.partitions),
+            event_rxs.clone(),
+            test_receiver.clone(),
+        );
+
         // add view sync task
         let view_sync_task_state = ViewSyncTask {
             hit_view_sync: HashSet::new(),
@@ -268,6 +290,7 @@ where
         }
 
         task_futs.push(consistency_task.run());
+        task_futs.push(overall_safety_task.run());
         task_futs.push(view_sync_task.run());
         task_futs.push(spinning_task.run());
 
@@ -654,8 +677,7 @@ where
 
         SystemContext::new_from_channels(
             public_key,
-            private_key,
-            state_private_key,
+            Arc::new(LocalSigner::new(private_key, state_private_key)),
             node_id,
             config,
             EpochMembershipCoordinator::new(
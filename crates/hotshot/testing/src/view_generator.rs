@@ -526,7 +526,7 @@ impl TestView {
         &self,
         handle: &SystemContextHandle<TestTypes, MemoryImpl, TestVersions>,
     ) -> QuorumVote2<TestTypes> {
-        QuorumVote2::<TestTypes>::create_signed_vote(
+        QuorumVote2::<TestTypes>::create_vote_with_signer(
             QuorumData2 {
                 leaf_commit: self.leaf.commit(),
                 epoch: self.epoch_number,
@@ -534,7 +534,7 @@ impl TestView {
             },
             self.view_number,
             &handle.public_key(),
-            handle.private_key(),
+            handle.signer().as_ref(),
             &handle.hotshot.upgrade_lock,
         )
         .await
@@ -546,11 +546,11 @@ impl TestView {
         data: UpgradeProposalData<TestTypes>,
         handle: &SystemContextHandle<TestTypes, MemoryImpl, TestVersions>,
     ) -> UpgradeVote<TestTypes> {
-        UpgradeVote::<TestTypes>::create_signed_vote(
+        UpgradeVote::<TestTypes>::create_vote_with_signer(
             data,
             self.view_number,
             &handle.public_key(),
-            handle.private_key(),
+            handle.signer().as_ref(),
             &handle.hotshot.upgrade_lock,
         )
         .await
@@ -562,11 +562,11 @@ impl TestView {
         data: DaData2<TestTypes>,
         handle: &SystemContextHandle<TestTypes, MemoryImpl, TestVersions>,
     ) -> DaVote2<TestTypes> {
-        DaVote2::create_signed_vote(
+        DaVote2::create_vote_with_signer(
             data,
             self.view_number,
             &handle.public_key(),
-            handle.private_key(),
+            handle.signer().as_ref(),
             &handle.hotshot.upgrade_lock,
         )
         .await
@@ -0,0 +1,91 @@
+// Copyright (c) 2021-2024 Espresso Systems (espressosys.com)
+// This file is part of the HotShot repository.
+
+// You should have received a copy of the MIT License
+// along with the HotShot repository. If not, see <https://mit-license.org/>.
+
+use std::time::Duration;
+
+use hotshot::tasks::task_state::CreateTaskState;
+use hotshot_example_types::node_types::{MemoryImpl, TestTypes, TestVersions};
+use hotshot_task_impls::{
+    event_log::{EventLogReader, EventLogWriter},
+    events::HotShotEvent,
+    harness::replay_event_log,
+    view_sync::ViewSyncTaskState,
+};
+use hotshot_testing::helpers::build_system_handle;
+use hotshot_types::{
+    data::ViewNumber,
+    simple_vote::{ViewSyncPreCommitData2, ViewSyncPreCommitVote2},
+    traits::{consensus_api::ConsensusApi, node_implementation::ConsensusTime},
+};
+
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_event_log_replay() {
+    hotshot::helpers::initialize_logging();
+
+    // Build the API for node 5.
+    let handle = build_system_handle::<TestTypes, MemoryImpl, TestVersions>(5)
+        .await
+        .0;
+
+    let vote = ViewSyncPreCommitVote2::<TestTypes>::create_vote_with_signer(
+        ViewSyncPreCommitData2 {
+            relay: 0,
+            round: ViewNumber::new(4),
+            epoch: None,
+        },
+        ViewNumber::new(4),
+        ConsensusApi::public_key(&handle),
+        ConsensusApi::signer(&handle).as_ref(),
+        &handle.hotshot.upgrade_lock,
+    )
+    .await
+    .expect("Failed to create a ViewSyncPreCommitVote!");
+
+    // Record the events a node would have seen, with the outputs of the view sync task
+    // interleaved with its inputs.
+    let events = [
+        HotShotEvent::Timeout(ViewNumber::new(2), None),
+        HotShotEvent::Timeout(ViewNumber::new(3), None),
+        HotShotEvent::ViewChange(ViewNumber::new(3), None),
+        HotShotEvent::ViewSyncPreCommitVoteSend(vote),
+        HotShotEvent::Shutdown,
+    ];
+    let mut writer = EventLogWriter::new(vec![], 5).unwrap();
+    for event in &events {
+        writer.record(event).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+
+    let reader = EventLogReader::<TestTypes, _>::new(bytes.as_slice()).unwrap();
+    assert_eq!(reader.header().node_id, 5);
+    let records = reader.collect::<anyhow::Result<Vec<_>>>().unwrap();
+    assert_eq!(
+        records.iter().map(|r| r.event.clone()).collect::<Vec<_>>(),
+        events
+    );
+    assert!(records.is_sorted_by_key(|r| r.elapsed));
+
+    // A truncated log is an error, not a shorter log.
+    let mut truncated = EventLogReader::<TestTypes, _>::new(&bytes[..bytes.len() - 1]).unwrap();
+    assert!(truncated.any(|record| record.is_err()));
+
+    // Replaying the log reproduces the recorded outputs.
+    let view_sync_state = ViewSyncTaskState::<TestTypes, TestVersions>::create_from(&handle).await;
+    let report = replay_event_log(
+        records,
+        view_sync_state,
+        |event| {
+            matches!(
+                event,
+                HotShotEvent::ViewChange(..) | HotShotEvent::ViewSyncPreCommitVoteSend(_)
+            )
+        },
+        Duration::from_secs(1),
+    )
+    .await;
+    assert!(report.is_match(), "{report:?}");
+}
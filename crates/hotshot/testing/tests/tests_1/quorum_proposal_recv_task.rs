@@ -21,7 +21,7 @@ use hotshot_task_impls::{
     events::HotShotEvent::*, quorum_proposal_recv::QuorumProposalRecvTaskState,
 };
 use hotshot_testing::{
-    helpers::{build_fake_view_with_leaf_and_state, build_system_handle},
+    helpers::{build_fake_view_with_leaf_and_state, build_system_handle, key_pair_for_id},
     predicates::event::{all_predicates, exact},
     script::InputOrder,
     serial,
@@ -181,9 +181,9 @@ async fn test_quorum_proposal_recv_task_liveness_check() {
     };
 
     // make the signed commitment
+    let (private_key, _) = key_pair_for_id::<TestTypes>(4);
     let signature =
-        <TestTypes as NodeType>::SignatureKey::sign(handle.private_key(), req.commit().as_ref())
-            .unwrap();
+        <TestTypes as NodeType>::SignatureKey::sign(&private_key, req.commit().as_ref()).unwrap();
 
     let expectations = vec![Expectations::from_outputs(all_predicates![
         exact(QuorumProposalPreliminarilyValidated(proposals[2].clone())),
@@ -15,7 +15,7 @@ use hotshot_example_types::{
 use hotshot_macros::{run_test, test_scripts};
 use hotshot_task_impls::{events::HotShotEvent::*, vid::VidTaskState};
 use hotshot_testing::{
-    helpers::build_system_handle,
+    helpers::{build_system_handle, key_pair_for_id},
     predicates::event::exact,
     script::{Expectations, InputOrder, TaskScript},
     serial,
@@ -80,8 +80,9 @@ async fn test_vid_task() {
     let encoded_transactions: Arc<[u8]> = Arc::from(TestTransaction::encode(&transactions));
     let payload_commitment = vid_disperse.payload_commitment();
 
+    let (private_key, _) = key_pair_for_id::<TestTypes>(2);
     let signature = <TestTypes as NodeType>::SignatureKey::sign(
-        handle.private_key(),
+        &private_key,
         payload_commitment.as_ref(),
     )
     .expect("Failed to sign block payload!");
@@ -30,15 +30,16 @@ async fn test_view_sync_task() {
         round: <TestTypes as hotshot_types::traits::node_implementation::NodeType>::View::new(4),
         epoch: None,
     };
-    let vote = hotshot_types::simple_vote::ViewSyncPreCommitVote2::<TestTypes>::create_signed_vote(
-        vote_data,
-        <TestTypes as hotshot_types::traits::node_implementation::NodeType>::View::new(4),
-        hotshot_types::traits::consensus_api::ConsensusApi::public_key(&handle),
-        hotshot_types::traits::consensus_api::ConsensusApi::private_key(&handle),
-        &handle.hotshot.upgrade_lock,
-    )
-    .await
-    .expect("Failed to create a ViewSyncPreCommitVote!");
+    let vote =
+        hotshot_types::simple_vote::ViewSyncPreCommitVote2::<TestTypes>::create_vote_with_signer(
+            vote_data,
+            <TestTypes as hotshot_types::traits::node_implementation::NodeType>::View::new(4),
+            hotshot_types::traits::consensus_api::ConsensusApi::public_key(&handle),
+            hotshot_types::traits::consensus_api::ConsensusApi::signer(&handle).as_ref(),
+            &handle.hotshot.upgrade_lock,
+        )
+        .await
+        .expect("Failed to create a ViewSyncPreCommitVote!");
 
     tracing::error!("Vote in test is {:?}", vote.clone());
 
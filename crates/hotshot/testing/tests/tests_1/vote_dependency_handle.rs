@@ -84,7 +84,6 @@ async fn test_vote_dependency_handle() {
         let vote_dependency_handle_state =
             VoteDependencyHandle::<TestTypes, MemoryImpl, TestVersions> {
                 public_key: handle.public_key(),
-                private_key: handle.private_key().clone(),
                 consensus: OuterConsensus::new(consensus.clone()),
                 consensus_metrics: Arc::clone(&consensus.read().await.metrics),
                 instance_state: handle.hotshot.instance_state(),
@@ -96,7 +95,7 @@ async fn test_vote_dependency_handle() {
                 upgrade_lock: handle.hotshot.upgrade_lock.clone(),
                 id: handle.hotshot.id,
                 epoch_height: handle.hotshot.config.epoch_height,
-                state_private_key: handle.state_private_key().clone(),
+                signer: Arc::clone(handle.signer()),
                 timeout: handle.hotshot.config.next_view_timeout,
                 view_start_time: Instant::now(),
             };
@@ -353,3 +353,48 @@ async fn memory_network_test_in_flight_message_count() {
         Some(0)
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[instrument]
+async fn memory_network_partition() {
+    hotshot::helpers::initialize_logging();
+
+    let group: Arc<MasterMap<<Test as NodeType>::SignatureKey>> = MasterMap::new();
+    let pub_key_1 = pubkey();
+    let network1 = MemoryNetwork::new(&pub_key_1, &group.clone(), &[Topic::Global], Option::None);
+    let pub_key_2 = pubkey();
+    let network2 = MemoryNetwork::new(&pub_key_2, &group, &[Topic::Global], Option::None);
+
+    let upgrade_lock = UpgradeLock::<Test, TestVersions>::new();
+    let message = upgrade_lock
+        .serialize(&gen_messages(1, 100, pub_key_1)[0])
+        .await
+        .unwrap();
+
+    // While partitioned, neither direct nor broadcast messages cross the partition.
+    TestableNetworkingImplementation::<Test>::partition(
+        &network1,
+        &[vec![pub_key_1], vec![pub_key_2]],
+    );
+    network1
+        .direct_message(message.clone(), pub_key_2)
+        .await
+        .unwrap();
+    network1
+        .broadcast_message(message.clone(), Topic::Global, BroadcastDelay::None)
+        .await
+        .unwrap();
+    assert!(timeout(Duration::from_secs(1), network2.recv_message())
+        .await
+        .is_err());
+    // A broadcast still reaches the sender's own group.
+    assert_eq!(network1.recv_message().await.unwrap(), message);
+
+    // Once healed, messages get through again.
+    TestableNetworkingImplementation::<Test>::partition(&network2, &[]);
+    network1
+        .direct_message(message.clone(), pub_key_2)
+        .await
+        .unwrap();
+    assert_eq!(network2.recv_message().await.unwrap(), message);
+}
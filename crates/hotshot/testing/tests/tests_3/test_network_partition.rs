@@ -0,0 +1,77 @@
+// Copyright (c) 2021-2024 Espresso Systems (espressosys.com)
+// This file is part of the HotShot repository.
+
+// You should have received a copy of the MIT License
+// along with the HotShot repository. If not, see <https://mit-license.org/>.
+
+use std::time::Duration;
+
+use hotshot_example_types::node_types::{MemoryImpl, TestTypes, TestVersions};
+use hotshot_testing::{
+    block_builder::SimpleBuilderImplementation,
+    completion_task::{CompletionTaskDescription, TimeBasedCompletionTaskDescription},
+    spinning_task::NetworkPartition,
+    test_builder::{TestDescription, TimingData},
+};
+
+/// Build a test of 10 nodes, which are partitioned into `groups` from view `start_view` until
+/// view `heal_view`.
+fn partition_test(
+    groups: Vec<Vec<usize>>,
+    start_view: u64,
+    heal_view: u64,
+) -> TestDescription<TestTypes, MemoryImpl, TestVersions> {
+    let mut metadata =
+        TestDescription::<TestTypes, MemoryImpl, TestVersions>::default().set_num_nodes(10, 10);
+    metadata.test_config.epoch_height = 0;
+    metadata.timing_data = TimingData {
+        next_view_timeout: 2000,
+        ..Default::default()
+    };
+    metadata.partitions = vec![NetworkPartition {
+        start_view,
+        heal_view,
+        groups,
+    }];
+
+    // Any view may fail while the network is partitioned, or while it is catching up afterwards.
+    metadata.overall_safety_properties.possible_view_failures =
+        (start_view..heal_view + 5).collect();
+    metadata.overall_safety_properties.num_successful_views = 20;
+    metadata.overall_safety_properties.decide_timeout = Duration::from_secs(60);
+    metadata.completion_task_description =
+        CompletionTaskDescription::TimeBasedCompletionTaskBuilder(
+            TimeBasedCompletionTaskDescription {
+                duration: Duration::from_secs(120),
+            },
+        );
+    metadata
+}
+
+// A partition leaving a quorum on one side: the minority must not decide anything conflicting,
+// and must catch up once the partition heals.
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_network_partition_minority() {
+    hotshot::helpers::initialize_logging();
+
+    partition_test(vec![(0..7).collect(), (7..10).collect()], 5, 15)
+        .gen_launcher()
+        .launch()
+        .run_test::<SimpleBuilderImplementation>()
+        .await;
+}
+
+// A partition leaving no quorum on either side: consensus stalls, and resumes once the partition
+// heals.
+#[cfg(test)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_network_partition_even_split() {
+    hotshot::helpers::initialize_logging();
+
+    partition_test(vec![(0..5).collect(), (5..10).collect()], 5, 10)
+        .gen_launcher()
+        .launch()
+        .run_test::<SimpleBuilderImplementation>()
+        .await;
+}
@@ -8,6 +8,7 @@
 
 use std::{
     collections::{BTreeMap, HashMap},
+    marker::PhantomData,
     mem::ManuallyDrop,
     ops::{Deref, DerefMut},
     sync::Arc,
@@ -36,7 +37,7 @@ use crate::{
         block_contents::{BlockHeader, BuilderFee},
         metrics::{Counter, Gauge, Histogram, Metrics, NoMetrics},
         node_implementation::{ConsensusTime, NodeType, Versions},
-        signature_key::SignatureKey,
+        signer::ConsensusSigner,
         BlockPayload, ValidatedState,
     },
     utils::{
@@ -1165,7 +1166,7 @@ impl<TYPES: NodeType> Consensus<TYPES> {
         view: <TYPES as NodeType>::View,
         target_epoch: Option<<TYPES as NodeType>::Epoch>,
         membership_coordinator: EpochMembershipCoordinator<TYPES>,
-        private_key: &<TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        signer: &dyn ConsensusSigner<TYPES>,
         upgrade_lock: &UpgradeLock<TYPES, V>,
     ) -> Option<()> {
         let payload_with_metadata = Arc::clone(consensus.read().await.saved_payloads().get(&view)?);
@@ -1190,11 +1191,32 @@ impl<TYPES: NodeType> Consensus<TYPES> {
         .ok()?;
 
         let shares = VidDisperseShare::from_vid_disperse(vid);
+
+        // Every share commits to the same payload, so sign it once, before taking the lock.
+        let signature = match VidDisperseShare::sign_dispersal(
+            &shares,
+            &membership_coordinator,
+            signer,
+        )
+        .await
+        {
+            Ok(signature) => signature,
+            Err(err) => {
+                tracing::error!("VID: failed to sign dispersal share payload: {err:#}");
+                return None;
+            },
+        };
+
         let mut consensus_writer = consensus.write().await;
         for share in shares {
-            if let Some(prop) = share.to_proposal(private_key) {
-                consensus_writer.update_vid_shares(view, prop);
-            }
+            consensus_writer.update_vid_shares(
+                view,
+                Proposal {
+                    data: share,
+                    signature: signature.clone(),
+                    _pd: PhantomData,
+                },
+            );
         }
 
         Some(())
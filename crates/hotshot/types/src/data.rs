@@ -46,6 +46,7 @@ use crate::{
         },
         node_implementation::{ConsensusTime, NodeType, Versions},
         signature_key::SignatureKey,
+        signer::{ConsensusSigner, SignableMessage},
         states::TestableState,
         BlockPayload,
     },
@@ -622,6 +623,41 @@ impl<TYPES: NodeType> VidDisperseShare<TYPES> {
             Self::V1(share) => share.view_number = view_number,
         }
     }
+
+    /// Sign the payload commitment of the dispersal split into `shares`.
+    ///
+    /// Every share commits to the same payload, so one signature covers them all. The signer is
+    /// given one of the shares, so that it can check the commitment is really that of a payload.
+    ///
+    /// # Errors
+    /// If there are no shares, the stake table of the target epoch is not available, or the signer
+    /// refuses to sign.
+    pub async fn sign_dispersal(
+        shares: &[Self],
+        membership: &EpochMembershipCoordinator<TYPES>,
+        signer: &dyn ConsensusSigner<TYPES>,
+    ) -> Result<<TYPES::SignatureKey as SignatureKey>::PureAssembledSignatureType> {
+        let share = shares
+            .first()
+            .ok_or(error!("VID dispersal has no shares"))?
+            .clone();
+        let target_epoch = share.target_epoch();
+        let total_weight = vid_disperse::vid_total_weight::<TYPES>(
+            membership
+                .membership_for_epoch(target_epoch)
+                .await?
+                .stake_table()
+                .await,
+            target_epoch,
+        );
+        signer
+            .sign(&SignableMessage::VidDisperse {
+                share,
+                total_weight,
+            })
+            .await
+            .wrap()
+    }
 }
 
 impl<TYPES: NodeType> HasViewNumber<TYPES> for VidDisperseShare<TYPES> {
@@ -1930,9 +1966,11 @@ pub mod null_block {
 }
 
 /// A packed bundle constructed from a sequence of bundles.
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(bound(deserialize = ""))]
 pub struct PackedBundle<TYPES: NodeType> {
     /// The combined transactions as bytes.
+    #[serde(with = "shared_bytes")]
     pub encoded_transactions: Arc<[u8]>,
 
     /// The metadata of the block.
@@ -1951,6 +1989,23 @@ pub struct PackedBundle<TYPES: NodeType> {
     pub auction_result: Option<TYPES::AuctionResult>,
 }
 
+/// Serialization for shared byte slices, which serde only supports with the `rc` feature.
+mod shared_bytes {
+    use std::sync::Arc;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize the bytes like a `Vec<u8>`.
+    pub fn serialize<S: Serializer>(bytes: &Arc<[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+        bytes.as_ref().serialize(serializer)
+    }
+
+    /// Deserialize the bytes like a `Vec<u8>`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<[u8]>, D::Error> {
+        Ok(Vec::<u8>::deserialize(deserializer)?.into())
+    }
+}
+
 impl<TYPES: NodeType> PackedBundle<TYPES> {
     /// Create a new [`PackedBundle`].
     pub fn new(
@@ -185,7 +185,7 @@ pub enum EventType<TYPES: NodeType> {
         data: Vec<u8>,
     },
 }
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 /// A list of actions that we track for nodes
 pub enum HotShotAction {
     /// A quorum vote was sent
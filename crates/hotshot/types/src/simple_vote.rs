@@ -25,6 +25,7 @@ use crate::{
     traits::{
         node_implementation::{ConsensusTime, NodeType, Versions},
         signature_key::{SignatureKey, StateSignatureKey},
+        signer::{ConsensusSigner, SignableMessage, SignableVoteData},
     },
     vote::{HasViewNumber, Vote},
 };
@@ -280,6 +281,44 @@ impl<TYPES: NodeType, DATA: Voteable<TYPES> + 'static> SimpleVote<TYPES, DATA> {
             view_number: view,
         })
     }
+
+    /// Creates a vote signed by `signer`, which may refuse to sign a vote conflicting with one it
+    /// already signed
+    /// # Errors
+    /// If the signer is unable or unwilling to sign the data
+    pub async fn create_vote_with_signer<V: Versions>(
+        data: DATA,
+        view: TYPES::View,
+        pub_key: &TYPES::SignatureKey,
+        signer: &dyn ConsensusSigner<TYPES>,
+        upgrade_lock: &UpgradeLock<TYPES, V>,
+    ) -> Result<Self>
+    where
+        DATA: Into<SignableVoteData<TYPES>>,
+    {
+        // The signer computes the same commitment as `VersionedVoteData`, but this still checks
+        // that the version of the view is supported.
+        upgrade_lock.version(view).await?;
+
+        let msg = SignableMessage::Vote {
+            view,
+            data: data.clone().into(),
+        };
+        let signature = (
+            pub_key.clone(),
+            signer
+                .sign(&msg)
+                .await
+                .wrap()
+                .context(error!("Failed to sign vote"))?,
+        );
+
+        Ok(Self {
+            signature,
+            data,
+            view_number: view,
+        })
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Hash, Eq)]
@@ -342,13 +381,24 @@ impl<TYPES: NodeType, DATA: Voteable<TYPES>, V: Versions> Committable
     for VersionedVoteData<TYPES, DATA, V>
 {
     fn commit(&self) -> Commitment<Self> {
-        committable::RawCommitmentBuilder::new("Vote")
-            .var_size_bytes(self.data.commit().as_ref())
-            .u64(*self.view)
-            .finalize()
+        vote_commitment(&self.data, *self.view)
     }
 }
 
+/// The commitment signed by a vote on `data` in `view`.
+///
+/// This is the commitment to the [`VersionedVoteData`], which does not depend on the version, so
+/// it can be computed without an upgrade lock.
+pub(crate) fn vote_commitment<T: Committable, DATA: Committable>(
+    data: &DATA,
+    view: u64,
+) -> Commitment<T> {
+    committable::RawCommitmentBuilder::new("Vote")
+        .var_size_bytes(data.commit().as_ref())
+        .u64(view)
+        .finalize()
+}
+
 impl<TYPES: NodeType> Committable for QuorumData<TYPES> {
     fn commit(&self) -> Commitment<Self> {
         committable::RawCommitmentBuilder::new("Quorum data")
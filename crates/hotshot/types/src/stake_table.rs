@@ -40,4 +40,18 @@ impl<K: SignatureKey> StakeTableEntry<K> {
     }
 }
 
+/// The stake required for a quorum certificate: strictly more than two thirds of `total_stake`.
+///
+/// Avoids overflow for total stakes close to `U256::MAX`.
+pub fn success_threshold(total_stake: U256) -> U256 {
+    let one = U256::ONE;
+    let two = U256::from(2);
+    let three = U256::from(3);
+    if total_stake < U256::MAX / two {
+        ((total_stake * two) / three) + one
+    } else {
+        ((total_stake / three) * two) + two
+    }
+}
+
 // TODO(Chengyu): add stake table snapshot here
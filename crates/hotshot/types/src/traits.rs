@@ -14,6 +14,7 @@ pub mod network;
 pub mod node_implementation;
 pub mod qc;
 pub mod signature_key;
+pub mod signer;
 pub mod states;
 pub mod storage;
 
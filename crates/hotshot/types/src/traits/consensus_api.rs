@@ -6,7 +6,7 @@
 
 //! Contains the [`ConsensusApi`] trait.
 
-use std::{num::NonZeroUsize, time::Duration};
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 
@@ -14,7 +14,7 @@ use crate::{
     event::Event,
     traits::{
         node_implementation::{NodeImplementation, NodeType},
-        signature_key::{SignatureKey, StateSignatureKey},
+        signer::ConsensusSigner,
     },
 };
 
@@ -31,13 +31,8 @@ pub trait ConsensusApi<TYPES: NodeType, I: NodeImplementation<TYPES>>: Send + Sy
     /// Get a reference to the public key.
     fn public_key(&self) -> &TYPES::SignatureKey;
 
-    /// Get a reference to the private key.
-    fn private_key(&self) -> &<TYPES::SignatureKey as SignatureKey>::PrivateKey;
-
-    /// Get a reference to the light client signing key.
-    fn state_private_key(
-        &self,
-    ) -> &<TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey;
+    /// Get the signer for votes, proposals and light client states.
+    fn signer(&self) -> &Arc<dyn ConsensusSigner<TYPES>>;
 
     /// Notify the system of an event within `hotshot-consensus`.
     async fn send_event(&self, event: Event<TYPES>);
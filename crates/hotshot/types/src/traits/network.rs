@@ -295,6 +295,17 @@ where
     ///
     /// Some implementations will not be able to tell how many messages there are in-flight. These implementations should return `None`.
     fn in_flight_message_count(&self) -> Option<usize>;
+
+    /// Split the network into `groups` of nodes which can only communicate within their group.
+    ///
+    /// Nodes which are not in any group are cut off from all other nodes. Passing no groups heals
+    /// the network. The partition applies to every node generated by the same generator.
+    ///
+    /// Returns `false`, without changing anything, if the implementation does not support
+    /// partitions.
+    fn partition(&self, _groups: &[Vec<TYPES::SignatureKey>]) -> bool {
+        false
+    }
 }
 
 /// Changes that can occur in the network
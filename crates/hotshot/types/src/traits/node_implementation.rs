@@ -221,6 +221,7 @@ pub trait NodeType:
     /// run, for a particular view.
     type AuctionResult: Debug
         + HasUrls
+        + Serialize
         + DeserializeOwned
         + Default
         + PartialEq
@@ -0,0 +1,349 @@
+// Copyright (c) 2021-2024 Espresso Systems (espressosys.com)
+// This file is part of the HotShot repository.
+
+// You should have received a copy of the MIT License
+// along with the HotShot repository. If not, see <https://mit-license.org/>.
+
+//! Abstraction over the holder of a node's consensus keys
+//!
+//! This module provides the [`ConsensusSigner`] trait, through which consensus signs its votes and
+//! proposals, and the [`LocalSigner`] implementation, which signs with keys held in memory. Other
+//! implementations may keep the keys in a separate process, and refuse to sign conflicting
+//! messages.
+//!
+//! Consensus messages are passed to the signer as [`SignableMessage`]s, from which the signer
+//! computes the bytes to sign, and the kind and view of the message, itself. Thus a signer need not
+//! trust the node to describe what it is asking to have signed.
+
+use std::{fmt::Debug, sync::Arc};
+
+use anyhow::{anyhow, ensure, Result};
+use async_trait::async_trait;
+use committable::Committable;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{
+    network::RequestKind,
+    node_implementation::{ConsensusTime, NodeType},
+    signature_key::{SignatureKey, StateSignatureKey},
+};
+use crate::{
+    data::{Leaf2, QuorumProposalWrapper, UpgradeProposal, VidDisperseShare},
+    light_client::{LightClientState, StakeTableState},
+    request_response::ProposalRequestPayload,
+    simple_vote::{
+        vote_commitment, DaData2, QuorumData2, TimeoutData2, UpgradeProposalData,
+        ViewSyncCommitData2, ViewSyncFinalizeData2, ViewSyncPreCommitData2,
+    },
+    vote::HasViewNumber,
+};
+
+/// The length of the commitments consensus messages are signed over.
+///
+/// Arbitrary messages of this length are never signed, since they could be the commitment to a
+/// vote or proposal.
+const COMMITMENT_LEN: usize = 32;
+
+/// The kind of message a signature is requested for.
+///
+/// A node must never sign two different messages of the same kind for the same view, unless
+/// [`is_unique_per_view`](Self::is_unique_per_view) says otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SignedMessageKind {
+    /// A vote for a quorum proposal
+    QuorumVote,
+    /// A vote for a DA proposal
+    DaVote,
+    /// A vote to time out a view
+    TimeoutVote,
+    /// A quorum proposal
+    QuorumProposal,
+    /// A DA proposal
+    DaProposal,
+    /// A pre-commit, commit or finalize vote in view sync
+    ViewSyncVote,
+    /// A vote for an upgrade proposal
+    UpgradeVote,
+    /// An upgrade proposal
+    UpgradeProposal,
+    /// A VID dispersal, or a share of one
+    VidDisperse,
+    /// Any other message, such as a request to a peer or builder or a network handshake, which
+    /// is not part of consensus and may be signed any number of times
+    Other,
+}
+
+impl SignedMessageKind {
+    /// Whether at most one message of this kind may be signed in each view.
+    ///
+    /// View sync votes are exempt, since a node votes in several rounds of view sync for the same
+    /// view, and a leader disperses VID for both the current and the next epoch in the same view.
+    pub fn is_unique_per_view(self) -> bool {
+        !matches!(self, Self::ViewSyncVote | Self::VidDisperse | Self::Other)
+    }
+}
+
+/// The data a vote is cast on.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "TYPES: NodeType")]
+pub enum SignableVoteData<TYPES: NodeType> {
+    /// A vote for a quorum proposal
+    Quorum(QuorumData2<TYPES>),
+    /// A vote for a DA proposal
+    Da(DaData2<TYPES>),
+    /// A vote to time out a view
+    Timeout(TimeoutData2<TYPES>),
+    /// A pre-commit vote in view sync
+    ViewSyncPreCommit(ViewSyncPreCommitData2<TYPES>),
+    /// A commit vote in view sync
+    ViewSyncCommit(ViewSyncCommitData2<TYPES>),
+    /// A finalize vote in view sync
+    ViewSyncFinalize(ViewSyncFinalizeData2<TYPES>),
+    /// A vote for an upgrade proposal
+    Upgrade(UpgradeProposalData<TYPES>),
+}
+
+impl<TYPES: NodeType> SignableVoteData<TYPES> {
+    /// The kind of vote cast on this data.
+    pub fn kind(&self) -> SignedMessageKind {
+        match self {
+            Self::Quorum(_) => SignedMessageKind::QuorumVote,
+            Self::Da(_) => SignedMessageKind::DaVote,
+            Self::Timeout(_) => SignedMessageKind::TimeoutVote,
+            Self::ViewSyncPreCommit(_) | Self::ViewSyncCommit(_) | Self::ViewSyncFinalize(_) => {
+                SignedMessageKind::ViewSyncVote
+            },
+            Self::Upgrade(_) => SignedMessageKind::UpgradeVote,
+        }
+    }
+
+    /// The commitment signed by a vote on this data in `view`.
+    fn signed_bytes(&self, view: u64) -> Vec<u8> {
+        fn commit<D: Committable>(data: &D, view: u64) -> Vec<u8> {
+            <[u8; 32]>::from(vote_commitment::<D, D>(data, view)).to_vec()
+        }
+        match self {
+            Self::Quorum(data) => commit(data, view),
+            Self::Da(data) => commit(data, view),
+            Self::Timeout(data) => commit(data, view),
+            Self::ViewSyncPreCommit(data) => commit(data, view),
+            Self::ViewSyncCommit(data) => commit(data, view),
+            Self::ViewSyncFinalize(data) => commit(data, view),
+            Self::Upgrade(data) => commit(data, view),
+        }
+    }
+}
+
+/// Implement conversions from each kind of vote data into [`SignableVoteData`].
+macro_rules! impl_signable_vote_data {
+    ($($variant:ident($data:ident)),* $(,)?) => {
+        $(
+            impl<TYPES: NodeType> From<$data<TYPES>> for SignableVoteData<TYPES> {
+                fn from(data: $data<TYPES>) -> Self {
+                    Self::$variant(data)
+                }
+            }
+        )*
+    };
+}
+
+impl_signable_vote_data!(
+    Quorum(QuorumData2),
+    Da(DaData2),
+    Timeout(TimeoutData2),
+    ViewSyncPreCommit(ViewSyncPreCommitData2),
+    ViewSyncCommit(ViewSyncCommitData2),
+    ViewSyncFinalize(ViewSyncFinalizeData2),
+    Upgrade(UpgradeProposalData),
+);
+
+/// A message to be signed with a node's consensus key.
+///
+/// Votes and proposals are given in full rather than as the commitment to be signed, so that the
+/// signer computes what it signs, and the kind and view of the message, itself.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "TYPES: NodeType")]
+pub enum SignableMessage<TYPES: NodeType> {
+    /// A vote on `data` in `view`
+    Vote {
+        view: TYPES::View,
+        data: SignableVoteData<TYPES>,
+    },
+    /// A quorum proposal, signed by signing the commitment to the proposed leaf
+    QuorumProposal(QuorumProposalWrapper<TYPES>),
+    /// A DA proposal of `encoded_transactions` in `view`, signed by signing their hash
+    DaProposal {
+        view: TYPES::View,
+        encoded_transactions: Arc<[u8]>,
+    },
+    /// An upgrade proposal, signed by signing the commitment to the proposed upgrade
+    UpgradeProposal(UpgradeProposal<TYPES>),
+    /// A VID dispersal among storage nodes with `total_weight` in total, signed by signing its
+    /// payload commitment
+    ///
+    /// One share of the dispersal is given, which must be valid for the payload commitment, so
+    /// that the commitment cannot be anything but the commitment to a dispersed payload.
+    VidDisperse {
+        share: VidDisperseShare<TYPES>,
+        total_weight: usize,
+    },
+    /// A request to a peer for the proposal of a view
+    ProposalRequest(ProposalRequestPayload<TYPES>),
+    /// A request to a peer for data, signed by signing the hash of the serialized request
+    DataRequest(RequestKind<TYPES>),
+    /// A request to a builder in `view`, signed by signing the `hash` of a block or its parent
+    ///
+    /// The builder protocol signs hashes the signer cannot check, so this is the one way a node can
+    /// have a signer sign bytes of its choosing which could be the commitment to a vote.
+    BuilderRequest { view: TYPES::View, hash: Vec<u8> },
+    /// Any other message, such as a request to a peer or a network handshake, which is not part of
+    /// consensus
+    ///
+    /// Messages the length of a commitment are refused, since they could be the commitment to a
+    /// vote or proposal relabelled to get around the signer's checks.
+    Other(Vec<u8>),
+}
+
+impl<TYPES: NodeType> SignableMessage<TYPES> {
+    /// The kind of this message.
+    pub fn kind(&self) -> SignedMessageKind {
+        match self {
+            Self::Vote { data, .. } => data.kind(),
+            Self::QuorumProposal(_) => SignedMessageKind::QuorumProposal,
+            Self::DaProposal { .. } => SignedMessageKind::DaProposal,
+            Self::UpgradeProposal(_) => SignedMessageKind::UpgradeProposal,
+            Self::VidDisperse { .. } => SignedMessageKind::VidDisperse,
+            Self::ProposalRequest(_)
+            | Self::DataRequest(_)
+            | Self::BuilderRequest { .. }
+            | Self::Other(_) => SignedMessageKind::Other,
+        }
+    }
+
+    /// The view this message is for, if it is for any particular view.
+    pub fn view(&self) -> Option<TYPES::View> {
+        match self {
+            Self::Vote { view, .. }
+            | Self::DaProposal { view, .. }
+            | Self::BuilderRequest { view, .. } => Some(*view),
+            Self::QuorumProposal(proposal) => Some(proposal.view_number()),
+            Self::UpgradeProposal(proposal) => Some(proposal.view_number()),
+            Self::VidDisperse { share, .. } => Some(share.view_number()),
+            Self::ProposalRequest(request) => Some(request.view_number),
+            Self::DataRequest(
+                RequestKind::Vid(view, _)
+                | RequestKind::DaProposal(view)
+                | RequestKind::Proposal(view),
+            ) => Some(*view),
+            Self::Other(_) => None,
+        }
+    }
+
+    /// The bytes a signature on this message is over.
+    ///
+    /// # Errors
+    /// If the message is malformed: a VID share which is not valid for its own payload
+    /// commitment, or an arbitrary message which could be the commitment to a consensus message.
+    pub fn signed_bytes(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Vote { view, data } => data.signed_bytes(view.u64()),
+            Self::QuorumProposal(proposal) => {
+                <[u8; 32]>::from(Leaf2::from_quorum_proposal(proposal).commit()).to_vec()
+            },
+            Self::DaProposal {
+                encoded_transactions,
+                ..
+            } => Sha256::digest(encoded_transactions).to_vec(),
+            Self::UpgradeProposal(proposal) => {
+                <[u8; 32]>::from(proposal.upgrade_proposal.commit()).to_vec()
+            },
+            Self::VidDisperse {
+                share,
+                total_weight,
+            } => {
+                share
+                    .verify_share(*total_weight)
+                    .map_err(|()| anyhow!("VID share is not valid for its payload commitment"))?;
+                share.payload_commitment_ref().to_vec()
+            },
+            Self::ProposalRequest(request) => <[u8; 32]>::from(request.commit()).to_vec(),
+            Self::DataRequest(request) => Sha256::digest(bincode::serialize(request)?).to_vec(),
+            Self::BuilderRequest { hash, .. } => hash.clone(),
+            Self::Other(msg) => {
+                ensure!(
+                    msg.len() != COMMITMENT_LEN,
+                    "refusing to sign an arbitrary message the length of a commitment"
+                );
+                msg.clone()
+            },
+        })
+    }
+}
+
+/// Signs messages on behalf of a node, so that the node itself need not hold its private keys.
+#[async_trait]
+pub trait ConsensusSigner<TYPES: NodeType>: Debug + Send + Sync {
+    /// Sign `msg`.
+    ///
+    /// # Errors
+    /// If the message cannot be signed, for example because it is malformed or conflicts with a
+    /// message already signed for the same view.
+    async fn sign(
+        &self,
+        msg: &SignableMessage<TYPES>,
+    ) -> Result<<TYPES::SignatureKey as SignatureKey>::PureAssembledSignatureType>;
+
+    /// Sign a light client state, along with the stake table for the next block.
+    ///
+    /// # Errors
+    /// If the state cannot be signed, for example because it conflicts with a state already signed
+    /// for the same block height.
+    async fn sign_state(
+        &self,
+        state: &LightClientState,
+        next_stake_table: &StakeTableState,
+    ) -> Result<<TYPES::StateSignatureKey as StateSignatureKey>::StateSignature>;
+}
+
+/// A signer which holds the node's keys in memory.
+#[derive(Clone, Debug)]
+pub struct LocalSigner<TYPES: NodeType> {
+    /// The key for signing consensus messages
+    private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+    /// The key for signing light client states
+    state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+}
+
+impl<TYPES: NodeType> LocalSigner<TYPES> {
+    /// Create a signer from the node's private keys.
+    pub fn new(
+        private_key: <TYPES::SignatureKey as SignatureKey>::PrivateKey,
+        state_private_key: <TYPES::StateSignatureKey as StateSignatureKey>::StatePrivateKey,
+    ) -> Self {
+        Self {
+            private_key,
+            state_private_key,
+        }
+    }
+}
+
+#[async_trait]
+impl<TYPES: NodeType> ConsensusSigner<TYPES> for LocalSigner<TYPES> {
+    async fn sign(
+        &self,
+        msg: &SignableMessage<TYPES>,
+    ) -> Result<<TYPES::SignatureKey as SignatureKey>::PureAssembledSignatureType> {
+        TYPES::SignatureKey::sign(&self.private_key, &msg.signed_bytes()?)
+            .map_err(|err| anyhow!("failed to sign message: {err}"))
+    }
+
+    async fn sign_state(
+        &self,
+        state: &LightClientState,
+        next_stake_table: &StakeTableState,
+    ) -> Result<<TYPES::StateSignatureKey as StateSignatureKey>::StateSignature> {
+        TYPES::StateSignatureKey::sign_state(&self.state_private_key, state, next_stake_table)
+            .map_err(|err| anyhow!("failed to sign light client state: {err}"))
+    }
+}
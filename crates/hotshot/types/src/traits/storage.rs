@@ -15,7 +15,7 @@ use anyhow::Result;
 use async_trait::async_trait;
 use futures::future::BoxFuture;
 
-use super::node_implementation::NodeType;
+use super::{node_implementation::NodeType, signer::SignedMessageKind};
 use crate::{
     data::{
         vid_disperse::{ADVZDisperseShare, VidDisperseShare2},
@@ -105,6 +105,26 @@ pub trait Storage<TYPES: NodeType>: Send + Sync + Clone {
         epoch: Option<TYPES::Epoch>,
         action: HotShotAction,
     ) -> Result<()>;
+    /// Record a HotShotAction taken, along with the kind of message signed for it and a commitment
+    /// to that message.
+    ///
+    /// Storage which protects against double signing should fail if a conflicting message of the
+    /// same kind was already recorded for the same view, in which case the message is not sent.
+    async fn record_signed_action(
+        &self,
+        view: TYPES::View,
+        epoch: Option<TYPES::Epoch>,
+        action: HotShotAction,
+        _kind: SignedMessageKind,
+        _commitment: [u8; 32],
+    ) -> Result<()> {
+        // View sync votes are recorded as votes, but are not limited to one per view.
+        let action = match action {
+            HotShotAction::ViewSyncVote => HotShotAction::Vote,
+            action => action,
+        };
+        self.record_action(view, epoch, action).await
+    }
     /// Update the current high QC in storage.
     async fn update_high_qc(&self, high_qc: QuorumCertificate<TYPES>) -> Result<()>;
     /// Update the current high QC in storage.
@@ -9,7 +9,10 @@ use snafu::Snafu;
 use tide_disco::{api::ApiError, method::ReadState, Api, RequestError, StatusCode};
 use vbs::version::StaticVersionType;
 
-use crate::{api::load_api, events_source::EventsSource};
+use crate::{
+    api::load_api,
+    events_source::{EventFilter, EventFilterSet, EventsSource},
+};
 
 #[derive(Args, Default, Debug)]
 pub struct Options {
@@ -76,7 +79,7 @@ impl tide_disco::error::Error for Error {
                 EventError::Missing => StatusCode::NOT_FOUND,
                 EventError::Error { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             },
-            Error::Custom { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            Error::Custom { status, .. } => *status,
         }
     }
 }
@@ -97,12 +100,27 @@ where
         options.extensions.clone(),
     )?;
     api.with_version(api_ver)
-        .stream("events", move |_, state| {
+        .stream("events", move |req, state| {
             async move {
-                tracing::info!("client subscribed to events");
+                let filter = parse_filter::<Types>(
+                    req.opt_string_param("kinds")?,
+                    req.opt_string_param("namespaces")?,
+                )?;
+                tracing::info!(?filter, "client subscribed to events");
                 state
                     .read(|state| {
-                        async move { Ok(state.get_event_stream(None).await.map(Ok)) }.boxed()
+                        async move {
+                            let by_namespace =
+                                filter.as_ref().is_some_and(|f| f.namespaces.is_some());
+                            if by_namespace && !state.supports_namespace_filter().await {
+                                return Err(Error::Custom {
+                                    message: "filtering by namespace is not supported".into(),
+                                    status: StatusCode::BAD_REQUEST,
+                                });
+                            }
+                            Ok(state.get_event_stream(filter).await.map(Ok))
+                        }
+                        .boxed()
                     })
                     .await
             }
@@ -115,3 +133,41 @@ where
 
     Ok(api)
 }
+
+/// Parse the optional filter parameters of the `events` route.
+///
+/// Returns [`None`] if no filter was requested, so that all events are sent.
+fn parse_filter<Types: NodeType>(
+    kinds: Option<String>,
+    namespaces: Option<String>,
+) -> Result<Option<EventFilterSet<Types>>, Error> {
+    if kinds.is_none() && namespaces.is_none() {
+        return Ok(None);
+    }
+
+    let mut filter = match kinds {
+        Some(kinds) => kinds
+            .split(',')
+            .map(|kind| kind.trim().parse::<EventFilter<Types>>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|message| Error::Custom {
+                message,
+                status: StatusCode::BAD_REQUEST,
+            })?
+            .into(),
+        None => EventFilterSet::all(),
+    };
+    if let Some(namespaces) = namespaces {
+        let namespaces = namespaces
+            .split(',')
+            .map(|ns| {
+                ns.trim().parse::<u64>().map_err(|err| Error::Custom {
+                    message: format!("invalid namespace {ns}: {err}"),
+                    status: StatusCode::BAD_REQUEST,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        filter = filter.with_namespaces(namespaces);
+    }
+    Ok(Some(filter))
+}
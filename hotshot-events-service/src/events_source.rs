@@ -1,7 +1,8 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, str::FromStr, sync::Arc};
 
 use async_broadcast::{broadcast, InactiveReceiver, Sender as BroadcastSender};
 use async_trait::async_trait;
+use derivative::Derivative;
 use futures::{
     future::BoxFuture,
     stream::{BoxStream, Stream, StreamExt},
@@ -23,6 +24,11 @@ where
     type EventStream: Stream<Item = Arc<Event<Types>>> + Unpin + Send + 'static;
     async fn get_event_stream(&self, filter: Option<EventFilterSet<Types>>) -> Self::EventStream;
     async fn get_startup_info(&self) -> StartupInfo<Types>;
+
+    /// Whether event streams can be filtered by namespace.
+    async fn supports_namespace_filter(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -40,7 +46,31 @@ where
     async fn handle_event(&mut self, event: Event<Types>);
 }
 
-#[derive(Debug)]
+/// Application-specific knowledge of namespaces, used to filter events by namespace.
+///
+/// HotShot itself has no notion of namespaces, so an application which wants to support namespace
+/// filtering must tell the events service how to find the namespaces of its transactions and
+/// blocks.
+pub trait NamespaceSource<Types: NodeType>: Send + Sync {
+    /// The namespace of a transaction.
+    fn transaction_namespace(&self, transaction: &Types::Transaction) -> u64;
+
+    /// The namespaces which have transactions in the block with the given header.
+    fn block_namespaces(&self, header: &Types::BlockHeader) -> Vec<u64>;
+
+    /// A payload containing only the transactions of `payload` in the given namespaces.
+    ///
+    /// The result need not match the commitment of the original payload; it is only used to send
+    /// clients the transactions they are interested in.
+    fn restrict_payload(
+        &self,
+        payload: &Types::BlockPayload,
+        namespaces: &[u64],
+    ) -> Types::BlockPayload;
+}
+
+#[derive(Derivative)]
+#[derivative(Debug(bound = ""))]
 pub struct EventsStreamer<Types: NodeType> {
     // required for api subscription
     inactive_to_subscribe_clone_recv: InactiveReceiver<Arc<Event<Types>>>,
@@ -49,6 +79,10 @@ pub struct EventsStreamer<Types: NodeType> {
     // required for sending startup info
     known_nodes_with_stake: Vec<PeerConfig<Types>>,
     non_staked_node_count: usize,
+
+    // required for filtering events by namespace
+    #[derivative(Debug = "ignore")]
+    namespace_source: Option<Arc<dyn NamespaceSource<Types>>>,
 }
 
 impl<Types: NodeType> EventsStreamer<Types> {
@@ -72,28 +106,58 @@ impl<Types: NodeType> EventConsumer<Types> for EventsStreamer<Types> {
 
 /// Wrapper struct representing a set of event filters.
 #[derive(Clone, Debug)]
-pub struct EventFilterSet<Types: NodeType>(pub(crate) Vec<EventFilter<Types>>);
+pub struct EventFilterSet<Types: NodeType> {
+    /// The kinds of events to broadcast, or [`None`] to broadcast every kind of event.
+    pub(crate) kinds: Option<Vec<EventFilter<Types>>>,
+    /// If set, `Transactions` and `Decide` events are restricted to these namespaces.
+    pub(crate) namespaces: Option<Vec<u64>>,
+}
 
 /// `From` trait impl to create an `EventFilterSet` from a vector of `EventFilter`s.
 impl<Types: NodeType> From<Vec<EventFilter<Types>>> for EventFilterSet<Types> {
-    fn from(filters: Vec<EventFilter<Types>>) -> Self {
-        EventFilterSet(filters)
+    fn from(kinds: Vec<EventFilter<Types>>) -> Self {
+        EventFilterSet {
+            kinds: Some(kinds),
+            namespaces: None,
+        }
     }
 }
 
 /// `From` trait impl to create an `EventFilterSet` from a single `EventFilter`.
 impl<Types: NodeType> From<EventFilter<Types>> for EventFilterSet<Types> {
     fn from(filter: EventFilter<Types>) -> Self {
-        EventFilterSet(vec![filter])
+        vec![filter].into()
     }
 }
 
 impl<Types: NodeType> EventFilterSet<Types> {
+    /// A filter set which allows every kind of event, including kinds with no [`EventFilter`].
+    pub fn all() -> Self {
+        EventFilterSet {
+            kinds: None,
+            namespaces: None,
+        }
+    }
+
+    /// Restrict `Transactions` and `Decide` events to the given namespaces.
+    ///
+    /// `Transactions` events are stripped of transactions from other namespaces, and dropped
+    /// entirely if none are left. `Decide` events are dropped unless one of the decided blocks
+    /// contains one of the namespaces; otherwise the payload of each decided leaf is restricted to
+    /// the namespaces, and VID shares, which encode the whole payload, are removed. Other kinds of
+    /// events are not affected.
+    pub fn with_namespaces(mut self, namespaces: impl IntoIterator<Item = u64>) -> Self {
+        self.namespaces = Some(namespaces.into_iter().collect());
+        self
+    }
+
     /// Determines whether the given hotshot event should be broadcast based on the filters in the set.
     ///
     ///  Returns `true` if the event should be broadcast, `false` otherwise.
     pub(crate) fn should_broadcast(&self, hotshot_event: &EventType<Types>) -> bool {
-        let filter = &self.0;
+        let Some(filter) = &self.kinds else {
+            return true;
+        };
 
         match hotshot_event {
             EventType::Error { .. } => filter.contains(&EventFilter::Error),
@@ -110,6 +174,79 @@ impl<Types: NodeType> EventFilterSet<Types> {
             _ => false,
         }
     }
+
+    /// Apply the filters in the set to an event.
+    ///
+    /// Returns the event to broadcast, which may be a restricted version of `event`, or `None` if
+    /// nothing should be broadcast. Namespace filters are only applied if `namespace_source` is
+    /// provided.
+    pub(crate) fn apply(
+        &self,
+        event: Arc<Event<Types>>,
+        namespace_source: Option<&dyn NamespaceSource<Types>>,
+    ) -> Option<Arc<Event<Types>>> {
+        if !self.should_broadcast(&event.event) {
+            return None;
+        }
+        let (Some(namespaces), Some(source)) = (&self.namespaces, namespace_source) else {
+            return Some(event);
+        };
+
+        match &event.event {
+            EventType::Transactions { transactions } => {
+                let transactions = transactions
+                    .iter()
+                    .filter(|tx| namespaces.contains(&source.transaction_namespace(tx)))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if transactions.is_empty() {
+                    return None;
+                }
+                Some(Arc::new(Event {
+                    view_number: event.view_number,
+                    event: EventType::Transactions { transactions },
+                }))
+            },
+            EventType::Decide {
+                leaf_chain,
+                qc,
+                block_size,
+            } => {
+                let relevant = leaf_chain.iter().any(|info| {
+                    source
+                        .block_namespaces(info.leaf.block_header())
+                        .iter()
+                        .any(|ns| namespaces.contains(ns))
+                });
+                if !relevant {
+                    return None;
+                }
+
+                let leaf_chain = leaf_chain
+                    .iter()
+                    .map(|info| {
+                        let mut info = info.clone();
+                        if let Some(payload) = info.leaf.unfill_block_payload() {
+                            info.leaf.fill_block_payload_unchecked(
+                                source.restrict_payload(&payload, namespaces),
+                            );
+                        }
+                        info.vid_share = None;
+                        info
+                    })
+                    .collect::<Vec<_>>();
+                Some(Arc::new(Event {
+                    view_number: event.view_number,
+                    event: EventType::Decide {
+                        leaf_chain: Arc::new(leaf_chain),
+                        qc: qc.clone(),
+                        block_size: *block_size,
+                    },
+                }))
+            },
+            _ => Some(event),
+        }
+    }
 }
 
 /// Possible event filters
@@ -128,6 +265,25 @@ pub enum EventFilter<Types: NodeType> {
     Pd(PhantomData<Types>),
 }
 
+impl<Types: NodeType> FromStr for EventFilter<Types> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Self::Error),
+            "decide" => Ok(Self::Decide),
+            "replica_view_timeout" => Ok(Self::ReplicaViewTimeout),
+            "view_finished" => Ok(Self::ViewFinished),
+            "view_timeout" => Ok(Self::ViewTimeout),
+            "transactions" => Ok(Self::Transactions),
+            "da_proposal" => Ok(Self::DaProposal),
+            "quorum_proposal" => Ok(Self::QuorumProposal),
+            "upgrade_proposal" => Ok(Self::UpgradeProposal),
+            _ => Err(format!("unknown event kind {s}")),
+        }
+    }
+}
+
 #[async_trait]
 impl<Types: NodeType> EventsSource<Types> for EventsStreamer<Types> {
     type EventStream = BoxStream<'static, Arc<Event<Types>>>;
@@ -136,9 +292,15 @@ impl<Types: NodeType> EventsSource<Types> for EventsStreamer<Types> {
         let receiver = self.inactive_to_subscribe_clone_recv.activate_cloned();
 
         if let Some(filter) = filter {
+            if filter.namespaces.is_some() && self.namespace_source.is_none() {
+                // Never send unfiltered events to a client which asked for certain namespaces
+                tracing::warn!("namespace filtering is not supported, sending no events");
+                return futures::stream::empty().boxed();
+            }
+            let namespace_source = self.namespace_source.clone();
             receiver
-                .filter(move |event| {
-                    futures::future::ready(filter.should_broadcast(&event.as_ref().event))
+                .filter_map(move |event| {
+                    futures::future::ready(filter.apply(event, namespace_source.as_deref()))
                 })
                 .boxed()
         } else {
@@ -152,6 +314,10 @@ impl<Types: NodeType> EventsSource<Types> for EventsStreamer<Types> {
             non_staked_node_count: self.non_staked_node_count(),
         }
     }
+
+    async fn supports_namespace_filter(&self) -> bool {
+        self.namespace_source.is_some()
+    }
 }
 
 impl<Types: NodeType> EventsStreamer<Types> {
@@ -171,8 +337,16 @@ impl<Types: NodeType> EventsStreamer<Types> {
             inactive_to_subscribe_clone_recv,
             known_nodes_with_stake,
             non_staked_node_count,
+            namespace_source: None,
         }
     }
+
+    /// Support filtering events by namespace, using `source` to find the namespaces of
+    /// transactions and blocks.
+    pub fn with_namespace_source(mut self, source: impl NamespaceSource<Types> + 'static) -> Self {
+        self.namespace_source = Some(Arc::new(source));
+        self
+    }
 }
 
 #[async_trait]
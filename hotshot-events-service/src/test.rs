@@ -5,12 +5,17 @@ mod tests {
     use alloy::primitives::U256;
     use async_lock::RwLock;
     use futures::stream::StreamExt;
-    use hotshot_example_types::node_types::TestTypes;
+    use hotshot_example_types::{
+        block_types::{TestBlockHeader, TestBlockPayload, TestTransaction},
+        node_types::{TestTypes, TestVersions},
+        state_types::{TestInstanceState, TestValidatedState},
+    };
     use hotshot_types::{
-        data::ViewNumber,
-        event::{Event, EventType},
+        data::{Leaf2, ViewNumber},
+        event::{Event, EventType, LeafInfo},
         light_client::StateKeyPair,
         signature_key::BLSPubKey,
+        simple_certificate::QuorumCertificate2,
         traits::{
             node_implementation::{ConsensusTime, NodeType},
             signature_key::SignatureKey,
@@ -25,7 +30,9 @@ mod tests {
 
     //use crate::fetch::Fetch;
     use crate::events::{define_api, Error, Options};
-    use crate::events_source::{EventConsumer, EventsStreamer, StartupInfo}; // EventsUpdater};
+    use crate::events_source::{
+        EventConsumer, EventFilter, EventFilterSet, EventsStreamer, NamespaceSource, StartupInfo,
+    }; // EventsUpdater};
 
     // return a empty transaction event
     fn generate_event<Types: NodeType<View = ViewNumber>>(view_number: u64) -> Event<Types> {
@@ -37,6 +44,49 @@ mod tests {
         }
     }
 
+    // test namespaces, where the namespace of a transaction is its first byte, and each block only
+    // contains the namespace equal to its height
+    struct TestNamespaces;
+
+    impl NamespaceSource<TestTypes> for TestNamespaces {
+        fn transaction_namespace(&self, transaction: &TestTransaction) -> u64 {
+            transaction
+                .bytes()
+                .first()
+                .copied()
+                .unwrap_or_default()
+                .into()
+        }
+
+        fn block_namespaces(&self, header: &TestBlockHeader) -> Vec<u64> {
+            vec![header.block_number]
+        }
+
+        fn restrict_payload(
+            &self,
+            payload: &TestBlockPayload,
+            namespaces: &[u64],
+        ) -> TestBlockPayload {
+            TestBlockPayload {
+                transactions: payload
+                    .transactions
+                    .iter()
+                    .filter(|tx| namespaces.contains(&self.transaction_namespace(tx)))
+                    .cloned()
+                    .collect(),
+            }
+        }
+    }
+
+    // a decided leaf at the given height, with the given transactions
+    async fn decided_leaf(height: u64, transactions: Vec<TestTransaction>) -> LeafInfo<TestTypes> {
+        let state = TestValidatedState::default();
+        let mut leaf = Leaf2::genesis::<TestVersions>(&state, &TestInstanceState::default()).await;
+        leaf.block_header_mut().block_number = height;
+        leaf.fill_block_payload_unchecked(TestBlockPayload { transactions });
+        LeafInfo::new(leaf, Arc::new(state), None, None, None)
+    }
+
     #[tokio::test]
     #[traced_test]
     async fn test_no_active_receiver() {
@@ -273,4 +323,218 @@ mod tests {
         receive_handle_1.await.unwrap();
         receive_handle_2.await.unwrap();
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_event_stream_filter() {
+        let port = portpicker::pick_unused_port().expect("Could not find an open port");
+        let api_url = Url::parse(format!("http://localhost:{port}").as_str()).unwrap();
+
+        let events_streamer = Arc::new(RwLock::new(
+            EventsStreamer::new(vec![], 0).with_namespace_source(TestNamespaces),
+        ));
+
+        // Start the web server.
+        let mut app = App::<_, Error>::with_state(events_streamer.clone());
+
+        let hotshot_events_api = define_api::<
+            Arc<RwLock<EventsStreamer<TestTypes>>>,
+            TestTypes,
+            StaticVersion<0, 1>,
+        >(&Options::default(), "1.0.0".parse().unwrap())
+        .expect("Failed to define hotshot eventsAPI");
+
+        app.register_module("hotshot_events", hotshot_events_api)
+            .expect("Failed to register hotshot events API");
+
+        spawn(app.serve(api_url, StaticVersion::<0, 1>::instance()));
+
+        let client = Client::<Error, StaticVersion<0, 1>>::new(
+            format!("http://localhost:{}/hotshot_events", port)
+                .parse()
+                .unwrap(),
+        );
+        client.connect(None).await;
+
+        // Invalid filters are rejected, either when connecting or as the first message.
+        match client
+            .socket("events/kinds/not_a_kind")
+            .subscribe::<Event<TestTypes>>()
+            .await
+        {
+            Ok(mut invalid) => {
+                let res = invalid.next().await;
+                assert!(matches!(res, Some(Err(_))), "{res:?}");
+            },
+            Err(err) => tracing::info!("invalid filter rejected: {err}"),
+        }
+
+        // Subscribe to transactions in namespace 1 only.
+        let mut events = client
+            .socket("events/kinds/transactions/namespaces/1")
+            .subscribe::<Event<TestTypes>>()
+            .await
+            .unwrap();
+
+        let tx = |ns: u8, view: u8| TestTransaction::new(vec![ns, view]);
+        let sent = vec![
+            Event {
+                view_number: ViewNumber::new(0),
+                event: EventType::ViewFinished {
+                    view_number: ViewNumber::new(0),
+                },
+            },
+            Event {
+                view_number: ViewNumber::new(1),
+                event: EventType::Transactions {
+                    transactions: vec![tx(2, 1)],
+                },
+            },
+            Event {
+                view_number: ViewNumber::new(2),
+                event: EventType::Transactions {
+                    transactions: vec![tx(1, 2), tx(2, 2), tx(1, 2)],
+                },
+            },
+        ];
+        for event in sent {
+            events_streamer.write().await.handle_event(event).await;
+        }
+
+        // Only the transactions in namespace 1 from the last event should be received.
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!(event.view_number, ViewNumber::new(2));
+        let EventType::Transactions { transactions } = event.event else {
+            panic!("expected transactions event, got {:?}", event.event);
+        };
+        assert_eq!(transactions, vec![tx(1, 2), tx(1, 2)]);
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_namespace_filter_unsupported() {
+        let port = portpicker::pick_unused_port().expect("Could not find an open port");
+        let api_url = Url::parse(format!("http://localhost:{port}").as_str()).unwrap();
+
+        // An events streamer with no namespace source cannot filter by namespace.
+        let events_streamer = Arc::new(RwLock::new(EventsStreamer::new(vec![], 0)));
+
+        let mut app = App::<_, Error>::with_state(events_streamer.clone());
+        let hotshot_events_api = define_api::<
+            Arc<RwLock<EventsStreamer<TestTypes>>>,
+            TestTypes,
+            StaticVersion<0, 1>,
+        >(&Options::default(), "1.0.0".parse().unwrap())
+        .expect("Failed to define hotshot eventsAPI");
+        app.register_module("hotshot_events", hotshot_events_api)
+            .expect("Failed to register hotshot events API");
+        spawn(app.serve(api_url, StaticVersion::<0, 1>::instance()));
+
+        let client = Client::<Error, StaticVersion<0, 1>>::new(
+            format!("http://localhost:{}/hotshot_events", port)
+                .parse()
+                .unwrap(),
+        );
+        client.connect(None).await;
+
+        // The namespace filter is rejected rather than ignored, either when connecting or as the
+        // first message.
+        match client
+            .socket("events/namespaces/1")
+            .subscribe::<Event<TestTypes>>()
+            .await
+        {
+            Ok(mut events) => {
+                events_streamer
+                    .write()
+                    .await
+                    .handle_event(generate_event(0))
+                    .await;
+                let res = events.next().await;
+                assert!(matches!(res, Some(Err(_))), "{res:?}");
+            },
+            Err(err) => tracing::info!("namespace filter rejected: {err}"),
+        }
+
+        // Filtering by kind alone still works.
+        let mut events = client
+            .socket("events/kinds/transactions")
+            .subscribe::<Event<TestTypes>>()
+            .await
+            .unwrap();
+        events_streamer
+            .write()
+            .await
+            .handle_event(generate_event(1))
+            .await;
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!(event.view_number, ViewNumber::new(1));
+    }
+
+    #[tokio::test]
+    async fn test_namespace_filter_without_kinds() {
+        let filter = EventFilterSet::<TestTypes>::all().with_namespaces([1]);
+
+        // Events which are not restricted by namespace are all sent, even those with no kind filter.
+        let (sender, _) = BLSPubKey::generated_from_seed_indexed([0; 32], 0);
+        for event in [
+            EventType::ViewFinished {
+                view_number: ViewNumber::new(1),
+            },
+            EventType::ExternalMessageReceived {
+                sender,
+                data: vec![1, 2, 3],
+            },
+        ] {
+            let event = Arc::new(Event {
+                view_number: ViewNumber::new(1),
+                event,
+            });
+            assert!(filter.apply(event, Some(&TestNamespaces)).is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decide_namespace_filter() {
+        let filter: EventFilterSet<TestTypes> =
+            EventFilterSet::from(EventFilter::Decide).with_namespaces([1]);
+        let tx = |ns: u8| TestTransaction::new(vec![ns, 0]);
+        let qc = Arc::new(
+            QuorumCertificate2::genesis::<TestVersions>(
+                &TestValidatedState::default(),
+                &TestInstanceState::default(),
+            )
+            .await,
+        );
+        let decide = |leaf_chain: Vec<LeafInfo<TestTypes>>| {
+            Arc::new(Event {
+                view_number: ViewNumber::new(1),
+                event: EventType::Decide {
+                    leaf_chain: Arc::new(leaf_chain),
+                    qc: qc.clone(),
+                    block_size: None,
+                },
+            })
+        };
+
+        // A decide of blocks without namespace 1 is dropped.
+        let event = decide(vec![decided_leaf(2, vec![tx(2)]).await]);
+        assert!(filter.apply(event, Some(&TestNamespaces)).is_none());
+
+        // Otherwise, only transactions in namespace 1 are sent.
+        let event = decide(vec![
+            decided_leaf(2, vec![tx(2)]).await,
+            decided_leaf(1, vec![tx(1), tx(2), tx(1)]).await,
+        ]);
+        let event = filter.apply(event, Some(&TestNamespaces)).unwrap();
+        let EventType::Decide { leaf_chain, .. } = &event.event else {
+            panic!("expected decide event, got {:?}", event.event);
+        };
+        let payloads = leaf_chain
+            .iter()
+            .map(|info| info.leaf.block_payload().unwrap().transactions)
+            .collect::<Vec<_>>();
+        assert_eq!(payloads, vec![vec![], vec![tx(1), tx(1)]]);
+        assert!(leaf_chain.iter().all(|info| info.vid_share.is_none()));
+    }
 }
@@ -0,0 +1,18 @@
+-- Running totals for each namespace, so that namespace summaries and the explorer summary do not
+-- have to scan `namespace_aggregate`. The aggregator updates these alongside `namespace_aggregate`.
+-- Rows are not removed when old blocks are pruned, so the totals cover the whole history.
+CREATE TABLE namespace_rollup (
+    namespace JSONB PRIMARY KEY,
+    num_transactions BIGINT NOT NULL,
+    payload_size BIGINT NOT NULL,
+    first_block BIGINT NOT NULL,
+    last_block BIGINT NOT NULL
+);
+
+CREATE INDEX namespace_rollup_num_transactions_idx ON namespace_rollup (num_transactions);
+CREATE INDEX namespace_rollup_payload_size_idx ON namespace_rollup (payload_size);
+
+INSERT INTO namespace_rollup (namespace, num_transactions, payload_size, first_block, last_block)
+    SELECT namespace, sum(num_transactions), sum(payload_size), min(height), max(height)
+        FROM namespace_aggregate
+        GROUP BY namespace;
@@ -0,0 +1,22 @@
+-- Statistics for each namespace in each block, maintained by the aggregator alongside `aggregate`.
+-- Namespace IDs and fee accounts are application-specific types, so like `transactions.idx` we
+-- store them as JSON.
+CREATE TABLE namespace_aggregate (
+    height BIGINT NOT NULL REFERENCES header (height) ON DELETE CASCADE,
+    namespace JSONB NOT NULL,
+    num_transactions BIGINT NOT NULL,
+    payload_size BIGINT NOT NULL,
+    fee_account JSONB NOT NULL,
+    PRIMARY KEY (namespace, height)
+);
+
+CREATE INDEX namespace_aggregate_height_idx ON namespace_aggregate (height);
+
+-- Blocks which were already aggregated when this table was added are backfilled by the aggregator,
+-- from the most recent block down to genesis. Blocks below `height` have yet to be backfilled.
+CREATE TABLE namespace_aggregate_backfill (
+    id INT PRIMARY KEY,
+    height BIGINT NOT NULL
+);
+INSERT INTO namespace_aggregate_backfill (id, height)
+    SELECT 0, coalesce(max(height) + 1, 0) FROM aggregate;
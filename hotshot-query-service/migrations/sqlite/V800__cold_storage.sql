@@ -0,0 +1,11 @@
+-- Index of segments in cold storage. Each segment holds the data for a contiguous range of pruned
+-- blocks, and is stored in the object store under `key`, which is derived from its contents.
+CREATE TABLE archive_segment (
+    start_height BIGINT PRIMARY KEY,
+    end_height BIGINT NOT NULL,
+    key TEXT NOT NULL,
+    num_blocks BIGINT NOT NULL,
+    size BIGINT NOT NULL
+);
+
+CREATE INDEX archive_segment_end_height_idx ON archive_segment (end_height);
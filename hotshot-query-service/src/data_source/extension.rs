@@ -500,6 +500,26 @@ where
     > {
         self.data_source.get_search_results(query).await
     }
+
+    async fn get_namespace_detail(
+        &self,
+        namespace: u64,
+    ) -> Result<
+        explorer::query_data::NamespaceDetail<Types>,
+        explorer::query_data::GetNamespaceDetailError,
+    > {
+        self.data_source.get_namespace_detail(namespace).await
+    }
+
+    async fn get_namespace_summaries(
+        &self,
+        request: explorer::query_data::GetNamespaceSummariesRequest,
+    ) -> Result<
+        Vec<explorer::query_data::NamespaceSummary<Types>>,
+        explorer::query_data::GetNamespaceSummariesError,
+    > {
+        self.data_source.get_namespace_summaries(request).await
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
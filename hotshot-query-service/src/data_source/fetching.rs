@@ -90,7 +90,7 @@ use backoff::{backoff::Backoff, ExponentialBackoff, ExponentialBackoffBuilder};
 use derivative::Derivative;
 use futures::{
     channel::oneshot,
-    future::{self, join_all, BoxFuture, Either, Future, FutureExt},
+    future::{self, join, join_all, BoxFuture, Either, Future, FutureExt},
     stream::{self, BoxStream, StreamExt},
 };
 use hotshot_types::{
@@ -554,12 +554,16 @@ where
         };
 
         let aggregator = if aggregator && !leaf_only {
-            Some(BackgroundTask::spawn(
-                "aggregator",
-                fetcher
-                    .clone()
-                    .aggregate(aggregator_chunk_size, aggregator_metrics),
-            ))
+            let fetcher = fetcher.clone();
+            Some(BackgroundTask::spawn("aggregator", async move {
+                join(
+                    fetcher
+                        .clone()
+                        .aggregate(aggregator_chunk_size, aggregator_metrics),
+                    fetcher.backfill_aggregates(aggregator_chunk_size),
+                )
+                .await;
+            }))
         } else {
             None
         };
@@ -1671,6 +1675,34 @@ where
             tracing::warn!("aggregator block stream ended unexpectedly; will restart");
         }
     }
+
+    /// Backfill aggregate statistics for blocks which were aggregated before those statistics
+    /// were added.
+    #[tracing::instrument(skip_all)]
+    async fn backfill_aggregates(self: Arc<Self>, chunk_size: usize) {
+        loop {
+            let res = async {
+                let mut tx = self.write().await.context("opening transaction")?;
+                let remaining = tx.backfill_aggregates(chunk_size).await?;
+                tx.commit().await.context("committing transaction")?;
+                anyhow::Result::<_>::Ok(remaining)
+            }
+            .await;
+            match res {
+                Ok(0) => {
+                    tracing::info!("aggregate backfill complete");
+                    return;
+                },
+                Ok(remaining) => {
+                    tracing::debug!(remaining, "backfilled aggregates for chunk");
+                },
+                Err(err) => {
+                    tracing::warn!("failed to backfill aggregates: {err:#}");
+                    sleep(Duration::from_secs(1)).await;
+                },
+            }
+        }
+    }
 }
 
 impl<Types, S, P> Fetcher<Types, S, P>
@@ -1984,6 +2016,32 @@ where
         })?;
         tx.get_search_results(query).await
     }
+
+    async fn get_namespace_detail(
+        &self,
+        namespace: u64,
+    ) -> Result<
+        explorer::query_data::NamespaceDetail<Types>,
+        explorer::query_data::GetNamespaceDetailError,
+    > {
+        let mut tx = self.read().await.map_err(|err| QueryError::Error {
+            message: err.to_string(),
+        })?;
+        tx.get_namespace_detail(namespace).await
+    }
+
+    async fn get_namespace_summaries(
+        &self,
+        request: explorer::query_data::GetNamespaceSummariesRequest,
+    ) -> Result<
+        Vec<explorer::query_data::NamespaceSummary<Types>>,
+        explorer::query_data::GetNamespaceSummariesError,
+    > {
+        let mut tx = self.read().await.map_err(|err| QueryError::Error {
+            message: err.to_string(),
+        })?;
+        tx.get_namespace_summaries(request).await
+    }
 }
 
 /// A provider which can be used as a fetcher by the availability service.
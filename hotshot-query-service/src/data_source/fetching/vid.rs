@@ -202,7 +202,7 @@ pub(super) fn fetch_vid_common_with_header<Types, S, P>(
         header.block_number()
     );
     vid_fetcher.spawn_fetch(
-        request::VidCommonRequest(header.payload_commitment()),
+        request::VidCommonRequest(header.payload_commitment(), header.block_number()),
         fetcher.provider.clone(),
         once(VidCommonCallback {
             header,
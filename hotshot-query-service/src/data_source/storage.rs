@@ -74,9 +74,10 @@ use crate::{
         query_data::{
             BlockDetail, BlockIdentifier, BlockSummary, ExplorerSummary, GetBlockDetailError,
             GetBlockSummariesError, GetBlockSummariesRequest, GetExplorerSummaryError,
+            GetNamespaceDetailError, GetNamespaceSummariesError, GetNamespaceSummariesRequest,
             GetSearchResultsError, GetTransactionDetailError, GetTransactionSummariesError,
-            GetTransactionSummariesRequest, SearchResult, TransactionDetailResponse,
-            TransactionIdentifier, TransactionSummary,
+            GetTransactionSummariesRequest, NamespaceDetail, NamespaceSummary, SearchResult,
+            TransactionDetailResponse, TransactionIdentifier, TransactionSummary,
         },
         traits::{ExplorerHeader, ExplorerTransaction},
     },
@@ -272,6 +273,16 @@ where
         aggregate: Aggregate,
         blocks: &[PayloadMetadata<Types>],
     ) -> impl Future<Output = anyhow::Result<Aggregate>> + Send;
+
+    /// Backfill statistics for up to `chunk_size` blocks which were aggregated before those
+    /// statistics were added.
+    ///
+    /// Blocks are backfilled from the most recent down to genesis. Returns the number of blocks
+    /// which still need to be backfilled.
+    fn backfill_aggregates(
+        &mut self,
+        chunk_size: usize,
+    ) -> impl Future<Output = anyhow::Result<usize>> + Send;
 }
 
 /// An interface for querying Data and Statistics from the HotShot Blockchain.
@@ -337,6 +348,22 @@ where
         &mut self,
         query: TaggedBase64,
     ) -> Result<SearchResult<Types>, GetSearchResultsError>;
+
+    /// `get_namespace_detail` is a method that retrieves the aggregate
+    /// statistics and recent activity of a specific namespace.  The namespace
+    /// is identified by the integer value of its id.
+    async fn get_namespace_detail(
+        &mut self,
+        namespace: u64,
+    ) -> Result<NamespaceDetail<Types>, GetNamespaceDetailError>;
+
+    /// `get_namespace_summaries` is a method that retrieves a leaderboard of
+    /// the most active namespaces.  The list is generated from the given
+    /// [GetNamespaceSummariesRequest].
+    async fn get_namespace_summaries(
+        &mut self,
+        request: GetNamespaceSummariesRequest,
+    ) -> Result<Vec<NamespaceSummary<Types>>, GetNamespaceSummariesError>;
 }
 
 /// This trait defines methods that a data source should implement
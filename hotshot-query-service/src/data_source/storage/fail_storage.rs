@@ -592,4 +592,9 @@ where
         self.maybe_fail_write(FailableAction::Any).await?;
         self.inner.update_aggregates(prev, blocks).await
     }
+
+    async fn backfill_aggregates(&mut self, chunk_size: usize) -> anyhow::Result<usize> {
+        self.maybe_fail_write(FailableAction::Any).await?;
+        self.inner.backfill_aggregates(chunk_size).await
+    }
 }
@@ -863,6 +863,10 @@ where
     ) -> anyhow::Result<Aggregate> {
         Ok(Aggregate::default())
     }
+
+    async fn backfill_aggregates(&mut self, _chunk_size: usize) -> anyhow::Result<usize> {
+        Ok(0)
+    }
 }
 
 impl<T: Revert> PrunedHeightStorage for Transaction<T> {}
@@ -49,12 +49,14 @@ use crate::{
 pub extern crate sqlx;
 pub use sqlx::{Database, Sqlite};
 
+mod cold_storage;
 mod db;
 mod migrate;
 mod queries;
 mod transaction;
 
 pub use anyhow::Error;
+pub use cold_storage::{ColdStorage, FileSystemObjectStore, ObjectStore, S3Config, S3ObjectStore};
 pub use db::*;
 pub use include_dir::include_dir;
 pub use queries::QueryBuilder;
@@ -223,6 +225,7 @@ pub struct Config {
     no_migrations: bool,
     pruner_cfg: Option<PrunerCfg>,
     archive: bool,
+    cold_storage: Option<ColdStorage>,
     pool: Option<Pool<Db>>,
 }
 
@@ -261,6 +264,7 @@ impl From<SqliteConnectOptions> for Config {
             no_migrations: false,
             pruner_cfg: None,
             archive: false,
+            cold_storage: None,
             pool: None,
         }
     }
@@ -278,6 +282,7 @@ impl From<PgConnectOptions> for Config {
             no_migrations: false,
             pruner_cfg: None,
             archive: false,
+            cold_storage: None,
             pool: None,
         }
     }
@@ -428,6 +433,16 @@ impl Config {
         self
     }
 
+    /// Export pruned data to cold storage.
+    ///
+    /// When this is set, the pruner writes each batch of data to `store` before deleting it from
+    /// the database, and queries for pruned objects by height are answered from `store`. See
+    /// [`ColdStorage`] for details.
+    pub fn cold_storage(mut self, store: impl ObjectStore) -> Self {
+        self.cold_storage = Some(ColdStorage::new(store));
+        self
+    }
+
     /// Set the maximum idle time of a connection.
     ///
     /// Any connection which has been open and unused longer than this duration will be
@@ -485,6 +500,7 @@ pub struct SqlStorage {
     metrics: PrometheusMetrics,
     pool_metrics: PoolMetrics,
     pruner_cfg: Option<PrunerCfg>,
+    cold_storage: Option<ColdStorage>,
 }
 
 #[derive(Debug, Default)]
@@ -504,6 +520,7 @@ impl SqlStorage {
         let pool_metrics = PoolMetrics::new(&*metrics.subgroup("sql".into()));
         let pool = config.pool_opt.clone();
         let pruner_cfg = config.pruner_cfg;
+        let cold_storage = config.cold_storage;
 
         // re-use the same pool if present and return early
         if let Some(pool) = config.pool {
@@ -512,6 +529,7 @@ impl SqlStorage {
                 pool_metrics,
                 pool,
                 pruner_cfg,
+                cold_storage,
             });
         }
 
@@ -600,6 +618,7 @@ impl SqlStorage {
             pool_metrics,
             metrics,
             pruner_cfg,
+            cold_storage,
         })
     }
 }
@@ -815,11 +834,21 @@ impl VersionedDataSource for SqlStorage {
         Self: 'a;
 
     async fn write(&self) -> anyhow::Result<Transaction<Write>> {
-        Transaction::new(&self.pool, self.pool_metrics.clone()).await
+        Transaction::new(
+            &self.pool,
+            self.pool_metrics.clone(),
+            self.cold_storage.clone(),
+        )
+        .await
     }
 
     async fn read(&self) -> anyhow::Result<Transaction<Read>> {
-        Transaction::new(&self.pool, self.pool_metrics.clone()).await
+        Transaction::new(
+            &self.pool,
+            self.pool_metrics.clone(),
+            self.cold_storage.clone(),
+        )
+        .await
     }
 }
 
@@ -1336,8 +1365,12 @@ mod test {
 
     use super::{testing::TmpDb, *};
     use crate::{
-        availability::{LeafQueryData, QueryableHeader},
-        data_source::storage::{pruning::PrunedHeightStorage, UpdateAvailabilityStorage},
+        availability::{
+            BlockId, BlockQueryData, LeafId, LeafQueryData, QueryableHeader, VidCommonQueryData,
+        },
+        data_source::storage::{
+            pruning::PrunedHeightStorage, AvailabilityStorage, UpdateAvailabilityStorage,
+        },
         merklized_state::{MerklizedState, UpdateStateData},
         testing::{
             mocks::{MockHeader, MockMerkleTree, MockPayload, MockTypes, MockVersions},
@@ -1689,6 +1722,123 @@ mod test {
         assert_eq!(header_rows, 0);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_cold_storage() {
+        setup_test();
+
+        let db = TmpDb::init().await;
+        let dir = tempfile::TempDir::new().unwrap();
+        let cfg = db
+            .config()
+            .cold_storage(FileSystemObjectStore::new(dir.path()));
+        let mut storage = SqlStorage::connect(cfg).await.unwrap();
+
+        // Insert some leaves, with the full block and VID common data for the genesis block.
+        let validated_state = TestValidatedState::default();
+        let instance_state = TestInstanceState::default();
+        let mut leaf =
+            LeafQueryData::<MockTypes>::genesis::<TestVersions>(&validated_state, &instance_state)
+                .await;
+        let block =
+            BlockQueryData::<MockTypes>::genesis::<TestVersions>(&validated_state, &instance_state)
+                .await;
+        let common = VidCommonQueryData::<MockTypes>::genesis::<TestVersions>(
+            &validated_state,
+            &instance_state,
+        )
+        .await;
+        let mut leaves = vec![];
+        for i in 0..20 {
+            if i > 0 {
+                leaf.leaf.block_header_mut().block_number = i;
+                leaf.leaf.block_header_mut().timestamp = Utc::now().timestamp() as u64;
+            }
+            let mut tx = storage.write().await.unwrap();
+            tx.insert_leaf(leaf.clone()).await.unwrap();
+            if i == 0 {
+                tx.insert_block(block.clone()).await.unwrap();
+                tx.insert_vid(common.clone(), None).await.unwrap();
+            }
+            tx.commit().await.unwrap();
+            leaves.push(leaf.clone());
+        }
+
+        // Prune everything, in several batches.
+        storage.set_pruning_config(
+            PrunerCfg::new()
+                .with_target_retention(Duration::from_secs(1))
+                .with_batch_size(5),
+        );
+        sleep(Duration::from_secs(2)).await;
+        let mut pruner = Default::default();
+        while storage.prune(&mut pruner).await.unwrap().is_some() {}
+
+        let mut tx = storage.read().await.unwrap();
+        let header_rows = tx
+            .fetch_one("select count(*) as count from header")
+            .await
+            .unwrap()
+            .get::<i64, _>("count");
+        assert_eq!(header_rows, 0);
+        let segments = tx
+            .fetch_one("select count(*) as count from archive_segment")
+            .await
+            .unwrap()
+            .get::<i64, _>("count");
+        assert_eq!(segments, 4);
+
+        // The pruned data is still available from cold storage.
+        for (i, leaf) in leaves.iter().enumerate() {
+            assert_eq!(
+                tx.get_leaf(LeafId::<MockTypes>::Number(i)).await.unwrap(),
+                *leaf
+            );
+            assert_eq!(
+                tx.get_header(BlockId::<MockTypes>::Number(i))
+                    .await
+                    .unwrap(),
+                *leaf.header()
+            );
+        }
+        assert_eq!(
+            tx.get_block(BlockId::<MockTypes>::Number(0)).await.unwrap(),
+            block.clone()
+        );
+        assert_eq!(
+            tx.get_vid_common(BlockId::<MockTypes>::Number(0))
+                .await
+                .unwrap(),
+            common
+        );
+        assert_eq!(
+            tx.get_payload_metadata(BlockId::<MockTypes>::Number(0))
+                .await
+                .unwrap()
+                .num_transactions,
+            block.num_transactions()
+        );
+
+        // Data which was missing when it was pruned is still missing.
+        assert!(matches!(
+            tx.get_block(BlockId::<MockTypes>::Number(1)).await,
+            Err(QueryError::NotFound)
+        ));
+
+        // Ranges spanning multiple segments are loaded in order.
+        let range = AvailabilityStorage::<MockTypes>::get_leaf_range(&mut tx, 3..17)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect::<QueryResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(range, leaves[3..17]);
+        let blocks = AvailabilityStorage::<MockTypes>::get_block_range(&mut tx, ..)
+            .await
+            .unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(*blocks[0].as_ref().unwrap(), block);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_pruned_height_storage() {
         setup_test();
@@ -47,6 +47,12 @@ where
     Header<Types>: QueryableHeader<Types>,
 {
     async fn get_leaf(&mut self, id: LeafId<Types>) -> QueryResult<LeafQueryData<Types>> {
+        if let LeafId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.leaf();
+            }
+        }
+
         let mut query = QueryBuilder::default();
         let where_clause = match id {
             LeafId::Number(n) => format!("height = {}", query.bind(n as i64)?),
@@ -63,6 +69,12 @@ where
     }
 
     async fn get_block(&mut self, id: BlockId<Types>) -> QueryResult<BlockQueryData<Types>> {
+        if let BlockId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.block();
+            }
+        }
+
         let mut query = QueryBuilder::default();
         let where_clause = query.header_where_clause(id)?;
         // ORDER BY h.height ASC ensures that if there are duplicate blocks (this can happen when
@@ -81,10 +93,22 @@ where
     }
 
     async fn get_header(&mut self, id: BlockId<Types>) -> QueryResult<Header<Types>> {
+        if let BlockId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.header::<Types>();
+            }
+        }
+
         self.load_header(id).await
     }
 
     async fn get_payload(&mut self, id: BlockId<Types>) -> QueryResult<PayloadQueryData<Types>> {
+        if let BlockId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.payload();
+            }
+        }
+
         let mut query = QueryBuilder::default();
         let where_clause = query.header_where_clause(id)?;
         // ORDER BY h.height ASC ensures that if there are duplicate blocks (this can happen when
@@ -106,6 +130,12 @@ where
         &mut self,
         id: BlockId<Types>,
     ) -> QueryResult<PayloadMetadata<Types>> {
+        if let BlockId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.payload_metadata();
+            }
+        }
+
         let mut query = QueryBuilder::default();
         let where_clause = query.header_where_clause(id)?;
         // ORDER BY h.height ASC ensures that if there are duplicate blocks (this can happen when
@@ -131,6 +161,12 @@ where
         &mut self,
         id: BlockId<Types>,
     ) -> QueryResult<VidCommonQueryData<Types>> {
+        if let BlockId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.vid_common();
+            }
+        }
+
         let mut query = QueryBuilder::default();
         let where_clause = query.header_where_clause(id)?;
         // ORDER BY h.height ASC ensures that if there are duplicate blocks (this can happen when
@@ -152,6 +188,12 @@ where
         &mut self,
         id: BlockId<Types>,
     ) -> QueryResult<VidCommonMetadata<Types>> {
+        if let BlockId::Number(n) = id {
+            if let Some(block) = self.load_archived(n as u64).await? {
+                return block.vid_common_metadata();
+            }
+        }
+
         let mut query = QueryBuilder::default();
         let where_clause = query.header_where_clause(id)?;
         // ORDER BY h.height ASC ensures that if there are duplicate blocks (this can happen when
@@ -176,16 +218,23 @@ where
     where
         R: RangeBounds<usize> + Send,
     {
+        let mut results = self
+            .load_archived_range(&range, |block| block.leaf())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "height")?;
         let sql = format!("SELECT {LEAF_COLUMNS} FROM leaf2 {where_clause} ORDER BY height ASC");
-        Ok(query
-            .query(&sql)
-            .fetch(self.as_mut())
-            .map(|res| LeafQueryData::from_row(&res?))
-            .map_err(QueryError::from)
-            .collect()
-            .await)
+        results.extend(
+            query
+                .query(&sql)
+                .fetch(self.as_mut())
+                .map(|res| LeafQueryData::from_row(&res?))
+                .map_err(QueryError::from)
+                .collect::<Vec<_>>()
+                .await,
+        );
+        Ok(results)
     }
 
     async fn get_block_range<R>(
@@ -195,6 +244,10 @@ where
     where
         R: RangeBounds<usize> + Send,
     {
+        let mut results = self
+            .load_archived_range(&range, |block| block.block())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "h.height")?;
         let sql = format!(
@@ -204,13 +257,16 @@ where
               {where_clause}
               ORDER BY h.height"
         );
-        Ok(query
-            .query(&sql)
-            .fetch(self.as_mut())
-            .map(|res| BlockQueryData::from_row(&res?))
-            .map_err(QueryError::from)
-            .collect()
-            .await)
+        results.extend(
+            query
+                .query(&sql)
+                .fetch(self.as_mut())
+                .map(|res| BlockQueryData::from_row(&res?))
+                .map_err(QueryError::from)
+                .collect::<Vec<_>>()
+                .await,
+        );
+        Ok(results)
     }
 
     async fn get_header_range<R>(
@@ -220,20 +276,26 @@ where
     where
         R: RangeBounds<usize> + Send,
     {
+        let mut headers = self
+            .load_archived_range(&range, |block| block.header::<Types>())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "h.height")?;
 
-        let headers = query
-            .query(&format!(
-                "SELECT data
-                  FROM header AS h
-                  {where_clause}
-                  ORDER BY h.height"
-            ))
-            .fetch(self.as_mut())
-            .map(|res| serde_json::from_value(res?.get("data")).unwrap())
-            .collect()
-            .await;
+        headers.extend(
+            query
+                .query(&format!(
+                    "SELECT data
+                      FROM header AS h
+                      {where_clause}
+                      ORDER BY h.height"
+                ))
+                .fetch(self.as_mut())
+                .map(|res| serde_json::from_value(res?.get("data")).unwrap())
+                .collect::<Vec<_>>()
+                .await,
+        );
 
         Ok(headers)
     }
@@ -245,6 +307,10 @@ where
     where
         R: RangeBounds<usize> + Send,
     {
+        let mut results = self
+            .load_archived_range(&range, |block| block.payload())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "h.height")?;
         let sql = format!(
@@ -254,13 +320,16 @@ where
               {where_clause}
               ORDER BY h.height"
         );
-        Ok(query
-            .query(&sql)
-            .fetch(self.as_mut())
-            .map(|res| PayloadQueryData::from_row(&res?))
-            .map_err(QueryError::from)
-            .collect()
-            .await)
+        results.extend(
+            query
+                .query(&sql)
+                .fetch(self.as_mut())
+                .map(|res| PayloadQueryData::from_row(&res?))
+                .map_err(QueryError::from)
+                .collect::<Vec<_>>()
+                .await,
+        );
+        Ok(results)
     }
 
     async fn get_payload_metadata_range<R>(
@@ -270,6 +339,10 @@ where
     where
         R: RangeBounds<usize> + Send + 'static,
     {
+        let mut results = self
+            .load_archived_range(&range, |block| block.payload_metadata())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "h.height")?;
         let sql = format!(
@@ -279,13 +352,16 @@ where
               {where_clause} AND p.num_transactions IS NOT NULL
               ORDER BY h.height ASC"
         );
-        Ok(query
-            .query(&sql)
-            .fetch(self.as_mut())
-            .map(|res| PayloadMetadata::from_row(&res?))
-            .map_err(QueryError::from)
-            .collect()
-            .await)
+        results.extend(
+            query
+                .query(&sql)
+                .fetch(self.as_mut())
+                .map(|res| PayloadMetadata::from_row(&res?))
+                .map_err(QueryError::from)
+                .collect::<Vec<_>>()
+                .await,
+        );
+        Ok(results)
     }
 
     async fn get_vid_common_range<R>(
@@ -295,6 +371,10 @@ where
     where
         R: RangeBounds<usize> + Send,
     {
+        let mut results = self
+            .load_archived_range(&range, |block| block.vid_common())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "h.height")?;
         let sql = format!(
@@ -304,13 +384,16 @@ where
               {where_clause}
               ORDER BY h.height"
         );
-        Ok(query
-            .query(&sql)
-            .fetch(self.as_mut())
-            .map(|res| VidCommonQueryData::from_row(&res?))
-            .map_err(QueryError::from)
-            .collect()
-            .await)
+        results.extend(
+            query
+                .query(&sql)
+                .fetch(self.as_mut())
+                .map(|res| VidCommonQueryData::from_row(&res?))
+                .map_err(QueryError::from)
+                .collect::<Vec<_>>()
+                .await,
+        );
+        Ok(results)
     }
 
     async fn get_vid_common_metadata_range<R>(
@@ -320,6 +403,10 @@ where
     where
         R: RangeBounds<usize> + Send,
     {
+        let mut results = self
+            .load_archived_range(&range, |block| block.vid_common_metadata())
+            .await?;
+
         let mut query = QueryBuilder::default();
         let where_clause = query.bounds_to_where_clause(range, "h.height")?;
         let sql = format!(
@@ -329,13 +416,16 @@ where
               {where_clause}
               ORDER BY h.height ASC"
         );
-        Ok(query
-            .query(&sql)
-            .fetch(self.as_mut())
-            .map(|res| VidCommonMetadata::from_row(&res?))
-            .map_err(QueryError::from)
-            .collect()
-            .await)
+        results.extend(
+            query
+                .query(&sql)
+                .fetch(self.as_mut())
+                .map(|res| VidCommonMetadata::from_row(&res?))
+                .map_err(QueryError::from)
+                .collect::<Vec<_>>()
+                .await,
+        );
+        Ok(results)
     }
 
     async fn get_transaction(
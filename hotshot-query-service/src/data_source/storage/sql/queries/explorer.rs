@@ -14,6 +14,7 @@
 
 use std::{collections::VecDeque, num::NonZeroUsize};
 
+use anyhow::Context;
 use async_trait::async_trait;
 use committable::{Commitment, Committable};
 use futures::stream::{self, StreamExt, TryStreamExt};
@@ -23,7 +24,7 @@ use sqlx::{types::Json, FromRow, Row};
 use tagged_base64::{Tagged, TaggedBase64};
 
 use super::{
-    super::transaction::{query, Transaction, TransactionMode},
+    super::transaction::{query, query_as, Transaction, TransactionMode, Write},
     Database, Db, DecodeError, BLOCK_COLUMNS,
 };
 use crate::{
@@ -33,13 +34,15 @@ use crate::{
         self,
         errors::{self, NotFound},
         query_data::TransactionDetailResponse,
-        traits::ExplorerHeader,
+        traits::{ExplorerHeader, ExplorerTransaction},
         BalanceAmount, BlockDetail, BlockIdentifier, BlockRange, BlockSummary, ExplorerHistograms,
         ExplorerSummary, GenesisOverview, GetBlockDetailError, GetBlockSummariesError,
-        GetBlockSummariesRequest, GetExplorerSummaryError, GetSearchResultsError,
+        GetBlockSummariesRequest, GetExplorerSummaryError, GetNamespaceDetailError,
+        GetNamespaceSummariesError, GetNamespaceSummariesRequest, GetSearchResultsError,
         GetTransactionDetailError, GetTransactionSummariesError, GetTransactionSummariesRequest,
-        MonetaryValue, SearchResult, TransactionIdentifier, TransactionRange, TransactionSummary,
-        TransactionSummaryFilter,
+        MonetaryValue, NamespaceDetail, NamespaceHistograms, NamespaceOrdering, NamespaceSummary,
+        SearchResult, TransactionIdentifier, TransactionRange, TransactionSummary,
+        TransactionSummaryFilter, WalletAddress,
     },
     Header, Payload, QueryError, QueryResult, Transaction as HotshotTransaction,
 };
@@ -80,6 +83,18 @@ impl From<sqlx::Error> for GetSearchResultsError {
     }
 }
 
+impl From<sqlx::Error> for GetNamespaceDetailError {
+    fn from(err: sqlx::Error) -> Self {
+        Self::from(QueryError::from(err))
+    }
+}
+
+impl From<sqlx::Error> for GetNamespaceSummariesError {
+    fn from(err: sqlx::Error) -> Self {
+        Self::from(QueryError::from(err))
+    }
+}
+
 impl<'r, Types> FromRow<'r, <Db as Database>::Row> for BlockSummary<Types>
 where
     Types: NodeType,
@@ -107,6 +122,36 @@ where
     }
 }
 
+impl<'r, Types> FromRow<'r, <Db as Database>::Row> for NamespaceSummary<Types>
+where
+    Types: NodeType,
+    HotshotTransaction<Types>: ExplorerTransaction,
+{
+    fn from_row(row: &'r <Db as Database>::Row) -> sqlx::Result<Self> {
+        // Namespace IDs are stored as JSON. As with transaction indices, we skip the type check,
+        // since SQLite may store a JSON number as an integer.
+        let namespace = row.try_get_unchecked::<Json<_>, _>("namespace")?.0;
+        let transactions: i64 = row.try_get("transactions")?;
+        let bytes: i64 = row.try_get("bytes")?;
+        let first_block: i64 = row.try_get("first_block")?;
+        let last_block: i64 = row.try_get("last_block")?;
+
+        Ok(Self {
+            namespace,
+            transactions: transactions as u64,
+            bytes: bytes as u64,
+            first_block: first_block as u64,
+            last_block: last_block as u64,
+        })
+    }
+}
+
+const NAMESPACE_SUMMARY_COLUMNS: &str = "namespace,
+    num_transactions AS transactions,
+    payload_size AS bytes,
+    first_block,
+    last_block";
+
 lazy_static::lazy_static! {
     static ref GET_BLOCK_SUMMARIES_QUERY_FOR_LATEST: String = {
         format!(
@@ -265,6 +310,18 @@ const EXPLORER_SUMMARY_NUM_BLOCKS: usize = 10;
 /// to return in our explorer summary.
 const EXPLORER_SUMMARY_NUM_TRANSACTIONS: usize = 10;
 
+/// [EXPLORER_SUMMARY_NUM_ROLLUPS] is the number of most active rollups we want
+/// to return in our explorer summary.
+const EXPLORER_SUMMARY_NUM_ROLLUPS: usize = 10;
+
+/// [NAMESPACE_DETAIL_HISTOGRAM_NUM_ENTRIES] is the number of entries we want
+/// to return in the histograms of a namespace detail.
+const NAMESPACE_DETAIL_HISTOGRAM_NUM_ENTRIES: usize = 50;
+
+/// [NAMESPACE_DETAIL_NUM_BUILDERS] is the number of builders we want to
+/// return in a namespace detail.
+const NAMESPACE_DETAIL_NUM_BUILDERS: usize = 10;
+
 #[async_trait]
 impl<Mode, Types> ExplorerStorage<Types> for Transaction<Mode>
 where
@@ -538,8 +595,11 @@ where
             let blocks = NodeStorage::<Types>::block_height(self).await? as u64;
             let transactions =
                 NodeStorage::<Types>::count_transactions_in_range(self, ..).await? as u64;
+            let (rollups,): (i64,) = query_as("SELECT count(*) FROM namespace_rollup")
+                .fetch_one(self.as_mut())
+                .await?;
             GenesisOverview {
-                rollups: 0,
+                rollups: rollups as u64,
                 transactions,
                 blocks,
             }
@@ -565,11 +625,19 @@ where
             })
             .await?;
 
+        let most_active_rollups: Vec<NamespaceSummary<Types>> = self
+            .get_namespace_summaries(GetNamespaceSummariesRequest {
+                num_namespaces: NonZeroUsize::new(EXPLORER_SUMMARY_NUM_ROLLUPS).unwrap(),
+                order: NamespaceOrdering::Transactions,
+            })
+            .await?;
+
         Ok(ExplorerSummary {
             genesis_overview,
             latest_block,
             latest_transactions,
             latest_blocks,
+            most_active_rollups,
             histograms,
         })
     }
@@ -647,4 +715,306 @@ where
             })
         }
     }
+
+    async fn get_namespace_detail(
+        &mut self,
+        namespace: u64,
+    ) -> Result<NamespaceDetail<Types>, GetNamespaceDetailError> {
+        // Namespace IDs are stored as JSON, so we look up the JSON representation of the requested
+        // ID, which for integer IDs is just the integer itself.
+        let namespace_json = serde_json::Value::from(namespace);
+
+        let summary_query = format!(
+            "SELECT {NAMESPACE_SUMMARY_COLUMNS}
+                FROM namespace_rollup
+                WHERE namespace = $1"
+        );
+        let Some(row) = query(summary_query.as_str())
+            .bind(&namespace_json)
+            .fetch_optional(self.as_mut())
+            .await?
+        else {
+            return Err(GetNamespaceDetailError::NamespaceNotFound(NotFound {
+                key: namespace.to_string(),
+            }));
+        };
+        let summary = NamespaceSummary::from_row(&row)?;
+
+        // The fee account of each block is the account of the builder which paid for it.
+        let builders = query(
+            "SELECT fee_account
+                FROM namespace_aggregate
+                WHERE namespace = $1
+                GROUP BY fee_account
+                ORDER BY count(*) DESC
+                LIMIT $2",
+        )
+        .bind(&namespace_json)
+        .bind(NAMESPACE_DETAIL_NUM_BUILDERS as i64)
+        .fetch(self.as_mut())
+        .map(|row| {
+            Ok::<_, sqlx::Error>(
+                row?.try_get_unchecked::<Json<WalletAddress<Types>>, _>("fee_account")?
+                    .0,
+            )
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+
+        // Fetch the most recent blocks first, and push each one to the front of the histograms so
+        // that they end up in increasing order of height.
+        let histograms = query_as::<(i64, i64, i64)>(
+            "SELECT height, num_transactions, payload_size
+                FROM namespace_aggregate
+                WHERE namespace = $1
+                ORDER BY height DESC
+                LIMIT $2",
+        )
+        .bind(&namespace_json)
+        .bind(NAMESPACE_DETAIL_HISTOGRAM_NUM_ENTRIES as i64)
+        .fetch(self.as_mut())
+        .try_fold(
+            NamespaceHistograms::default(),
+            |mut histograms, (height, num_transactions, payload_size)| async move {
+                histograms.block_heights.push_front(height as u64);
+                histograms
+                    .block_transactions
+                    .push_front(num_transactions as u64);
+                histograms.block_size.push_front(payload_size as u64);
+                Ok(histograms)
+            },
+        )
+        .await?;
+
+        Ok(NamespaceDetail {
+            summary,
+            builders,
+            histograms,
+        })
+    }
+
+    async fn get_namespace_summaries(
+        &mut self,
+        request: GetNamespaceSummariesRequest,
+    ) -> Result<Vec<NamespaceSummary<Types>>, GetNamespaceSummariesError> {
+        let order = match request.order {
+            NamespaceOrdering::Transactions => "num_transactions",
+            NamespaceOrdering::Bytes => "payload_size",
+        };
+
+        let summaries_query = format!(
+            "SELECT {NAMESPACE_SUMMARY_COLUMNS}
+                FROM namespace_rollup
+                ORDER BY {order} DESC, first_block
+                LIMIT $1"
+        );
+        let row_stream = query(summaries_query.as_str())
+            .bind(request.num_namespaces.get() as i64)
+            .fetch(self.as_mut());
+        let result = row_stream.map(|row| NamespaceSummary::from_row(&row?));
+
+        Ok(result.try_collect().await?)
+    }
+}
+
+impl Transaction<Write> {
+    /// Update the per-namespace statistics for the blocks in the range `[from, to]`.
+    ///
+    /// This is called by the aggregator, alongside updating the cumulative statistics in the
+    /// `aggregate` table, so that the explorer's namespace pages stay up to date with the rest of
+    /// the aggregated data. Both the statistics for each block in `namespace_aggregate` and the
+    /// running totals for each namespace in `namespace_rollup` are updated.
+    pub(super) async fn update_namespace_aggregates<Types>(
+        &mut self,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<()>
+    where
+        Types: NodeType,
+        Payload<Types>: QueryablePayload<Types>,
+        Header<Types>: QueryableHeader<Types> + ExplorerHeader<Types>,
+        HotshotTransaction<Types>: ExplorerTransaction,
+    {
+        let blocks_query = format!(
+            "SELECT {BLOCK_COLUMNS}
+                FROM header AS h
+                JOIN payload AS p ON h.height = p.height
+                WHERE h.height >= $1 AND h.height <= $2 AND p.data IS NOT NULL
+                ORDER BY h.height"
+        );
+        let blocks = query(blocks_query.as_str())
+            .bind(from as i64)
+            .bind(to as i64)
+            .fetch(self.as_mut())
+            .map(|row| BlockQueryData::<Types>::from_row(&row?))
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut rows = vec![];
+        for block in &blocks {
+            let fee_account = serde_json::to_value(block.header().fee_info_account())
+                .context("failed to serialize fee account")?;
+
+            // Namespace IDs are only required to be comparable for equality, and there are usually
+            // only a few namespaces in a block, so a simple list is enough to group transactions.
+            let mut namespaces: Vec<(serde_json::Value, i64, i64)> = vec![];
+            for (_, txn) in block.enumerate() {
+                let namespace = serde_json::to_value(txn.namespace_id())
+                    .context("failed to serialize namespace ID")?;
+                let size = txn.payload_size() as i64;
+                match namespaces.iter_mut().find(|(ns, ..)| *ns == namespace) {
+                    Some((_, num_transactions, payload_size)) => {
+                        *num_transactions += 1;
+                        *payload_size += size;
+                    },
+                    None => namespaces.push((namespace, 1, size)),
+                }
+            }
+
+            rows.extend(namespaces.into_iter().map(
+                |(namespace, num_transactions, payload_size)| {
+                    (
+                        block.height() as i64,
+                        namespace,
+                        num_transactions,
+                        payload_size,
+                        fee_account.clone(),
+                    )
+                },
+            ));
+        }
+
+        // The running totals only change by the difference between the new statistics and whatever
+        // was already recorded for these blocks, so that aggregating a block twice does not count
+        // it twice.
+        let mut rollups: Vec<NamespaceRollup> = vec![];
+        let old_rows = query(
+            "SELECT namespace, num_transactions, payload_size
+                FROM namespace_aggregate
+                WHERE height >= $1 AND height <= $2",
+        )
+        .bind(from as i64)
+        .bind(to as i64)
+        .fetch(self.as_mut())
+        .map(|row| {
+            let row = row?;
+            Ok::<_, sqlx::Error>((
+                row.try_get_unchecked::<Json<serde_json::Value>, _>("namespace")?
+                    .0,
+                row.try_get::<i64, _>("num_transactions")?,
+                row.try_get::<i64, _>("payload_size")?,
+            ))
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+        for (namespace, num_transactions, payload_size) in old_rows {
+            let rollup = NamespaceRollup::find_or_insert(&mut rollups, namespace);
+            rollup.num_transactions -= num_transactions;
+            rollup.payload_size -= payload_size;
+        }
+        for (height, namespace, num_transactions, payload_size, _) in &rows {
+            let rollup = NamespaceRollup::find_or_insert(&mut rollups, namespace.clone());
+            rollup.num_transactions += num_transactions;
+            rollup.payload_size += payload_size;
+            rollup.first_block = rollup.first_block.min(*height);
+            rollup.last_block = rollup.last_block.max(*height);
+        }
+
+        if !rows.is_empty() {
+            self.upsert(
+                "namespace_aggregate",
+                [
+                    "height",
+                    "namespace",
+                    "num_transactions",
+                    "payload_size",
+                    "fee_account",
+                ],
+                ["namespace", "height"],
+                rows,
+            )
+            .await?;
+        }
+
+        for rollup in rollups {
+            // A namespace whose blocks were only aggregated before has no new blocks to extend its
+            // range with, and nothing to insert if it is not in the table yet.
+            if rollup.first_block > rollup.last_block {
+                if rollup.num_transactions != 0 || rollup.payload_size != 0 {
+                    query(
+                        "UPDATE namespace_rollup
+                            SET num_transactions = num_transactions + $2,
+                                payload_size = payload_size + $3
+                            WHERE namespace = $1",
+                    )
+                    .bind(&rollup.namespace)
+                    .bind(rollup.num_transactions)
+                    .bind(rollup.payload_size)
+                    .execute(self.as_mut())
+                    .await?;
+                }
+                continue;
+            }
+
+            query(
+                "INSERT INTO namespace_rollup
+                    (namespace, num_transactions, payload_size, first_block, last_block)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT (namespace) DO UPDATE SET
+                        num_transactions =
+                            namespace_rollup.num_transactions + excluded.num_transactions,
+                        payload_size = namespace_rollup.payload_size + excluded.payload_size,
+                        first_block = CASE
+                            WHEN excluded.first_block < namespace_rollup.first_block
+                            THEN excluded.first_block
+                            ELSE namespace_rollup.first_block
+                        END,
+                        last_block = CASE
+                            WHEN excluded.last_block > namespace_rollup.last_block
+                            THEN excluded.last_block
+                            ELSE namespace_rollup.last_block
+                        END",
+            )
+            .bind(&rollup.namespace)
+            .bind(rollup.num_transactions)
+            .bind(rollup.payload_size)
+            .bind(rollup.first_block)
+            .bind(rollup.last_block)
+            .execute(self.as_mut())
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// A change to the running totals of a namespace in `namespace_rollup`.
+struct NamespaceRollup {
+    namespace: serde_json::Value,
+    num_transactions: i64,
+    payload_size: i64,
+    first_block: i64,
+    last_block: i64,
+}
+
+impl NamespaceRollup {
+    /// Find the change for `namespace` in `rollups`, adding an empty one if there is none yet.
+    fn find_or_insert(rollups: &mut Vec<Self>, namespace: serde_json::Value) -> &mut Self {
+        let i = match rollups
+            .iter()
+            .position(|rollup| rollup.namespace == namespace)
+        {
+            Some(i) => i,
+            None => {
+                rollups.push(Self {
+                    namespace,
+                    num_transactions: 0,
+                    payload_size: 0,
+                    first_block: i64::MAX,
+                    last_block: i64::MIN,
+                });
+                rollups.len() - 1
+            },
+        };
+        &mut rollups[i]
+    }
 }
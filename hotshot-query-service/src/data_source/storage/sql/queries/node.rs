@@ -29,12 +29,14 @@ use super::{
     parse_header, DecodeError, QueryBuilder, HEADER_COLUMNS,
 };
 use crate::{
+    availability::{QueryableHeader, QueryablePayload},
     data_source::storage::{
         Aggregate, AggregatesStorage, NodeStorage, PayloadMetadata, UpdateAggregatesStorage,
     },
+    explorer::traits::{ExplorerHeader, ExplorerTransaction},
     node::{BlockId, SyncStatus, TimeWindowQueryData, WindowStart},
     types::HeightIndexed,
-    Header, MissingSnafu, NotFoundSnafu, QueryError, QueryResult,
+    Header, MissingSnafu, NotFoundSnafu, Payload, QueryError, QueryResult,
 };
 
 #[async_trait]
@@ -309,7 +311,13 @@ impl<Mode: TransactionMode> AggregatesStorage for Transaction<Mode> {
     }
 }
 
-impl<Types: NodeType> UpdateAggregatesStorage<Types> for Transaction<Write> {
+impl<Types> UpdateAggregatesStorage<Types> for Transaction<Write>
+where
+    Types: NodeType,
+    Payload<Types>: QueryablePayload<Types>,
+    Header<Types>: QueryableHeader<Types> + ExplorerHeader<Types>,
+    crate::Transaction<Types>: ExplorerTransaction,
+{
     async fn update_aggregates(
         &mut self,
         prev: Aggregate,
@@ -354,12 +362,43 @@ impl<Types: NodeType> UpdateAggregatesStorage<Types> for Transaction<Write> {
         let (height, num_transactions, payload_size) =
             last_aggregate.ok_or_else(|| anyhow!("no row"))?;
 
+        // Break down the statistics for the same blocks by namespace.
+        self.update_namespace_aggregates::<Types>(blocks[0].height(), height as u64)
+            .await?;
+
         Ok(Aggregate {
             height,
             num_transactions,
             payload_size,
         })
     }
+
+    async fn backfill_aggregates(&mut self, chunk_size: usize) -> anyhow::Result<usize> {
+        // Namespace statistics were added after the `aggregate` table, so blocks below the height
+        // recorded when they were added need to be backfilled.
+        let Some((end,)) =
+            query_as::<(i64,)>("SELECT height FROM namespace_aggregate_backfill WHERE id = 0")
+                .fetch_optional(self.as_mut())
+                .await?
+        else {
+            return Ok(0);
+        };
+        if end <= 0 {
+            return Ok(0);
+        }
+
+        let start = end.saturating_sub(chunk_size as i64).max(0);
+        self.update_namespace_aggregates::<Types>(start as u64, (end - 1) as u64)
+            .await?;
+        self.upsert(
+            "namespace_aggregate_backfill",
+            ["id", "height"],
+            ["id"],
+            [(0i32, start)],
+        )
+        .await?;
+        Ok(start as usize)
+    }
 }
 
 impl<Mode: TransactionMode> Transaction<Mode> {
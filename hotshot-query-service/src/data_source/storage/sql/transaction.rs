@@ -47,6 +47,7 @@ use sqlx::{
 };
 
 use super::{
+    cold_storage::ColdStorage,
     queries::{
         self,
         state::{build_hash_batch_insert, Node},
@@ -227,14 +228,26 @@ pub struct Transaction<Mode> {
     #[deref_mut]
     inner: sqlx::Transaction<'static, Db>,
     metrics: TransactionMetricsGuard<Mode>,
+    pub(super) cold_storage: Option<ColdStorage>,
+    /// The height pruned by this transaction, which is cached in cold storage once it commits.
+    pruned_height: Option<u64>,
 }
 
 impl<Mode: TransactionMode> Transaction<Mode> {
-    pub(super) async fn new(pool: &Pool<Db>, metrics: PoolMetrics) -> anyhow::Result<Self> {
+    pub(super) async fn new(
+        pool: &Pool<Db>,
+        metrics: PoolMetrics,
+        cold_storage: Option<ColdStorage>,
+    ) -> anyhow::Result<Self> {
         let mut inner = pool.begin().await?;
         let metrics = TransactionMetricsGuard::begin(metrics);
         Mode::begin(inner.as_mut()).await?;
-        Ok(Self { inner, metrics })
+        Ok(Self {
+            inner,
+            metrics,
+            cold_storage,
+            pruned_height: None,
+        })
     }
 }
 
@@ -242,6 +255,9 @@ impl<Mode: TransactionMode> update::Transaction for Transaction<Mode> {
     async fn commit(mut self) -> anyhow::Result<()> {
         self.inner.commit().await?;
         self.metrics.set_closed(CloseType::Commit);
+        if let (Some(cold_storage), Some(height)) = (&self.cold_storage, self.pruned_height) {
+            cold_storage.cache_pruned_height(Some(height));
+        }
         Ok(())
     }
     fn revert(mut self) -> impl Future + Send {
@@ -421,6 +437,15 @@ impl Transaction<Write> {
         state_tables: Vec<String>,
         height: u64,
     ) -> anyhow::Result<()> {
+        // If cold storage is enabled, archive the batch before deleting it, so that it is only
+        // deleted if it was archived successfully.
+        if let Some(cold_storage) = self.cold_storage.clone() {
+            cold_storage
+                .export(self, height)
+                .await
+                .context("exporting batch to cold storage")?;
+        }
+
         self.execute(query("DELETE FROM header WHERE height <= $1").bind(height as i64))
             .await?;
 
@@ -443,6 +468,7 @@ impl Transaction<Write> {
         }
 
         self.save_pruned_height(height).await?;
+        self.pruned_height = Some(height);
         Ok(())
     }
 
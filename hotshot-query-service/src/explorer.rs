@@ -48,6 +48,8 @@ pub enum Error {
     GetTransactionSummaries(GetTransactionSummariesError),
     GetExplorerSummary(GetExplorerSummaryError),
     GetSearchResults(GetSearchResultsError),
+    GetNamespaceDetail(GetNamespaceDetailError),
+    GetNamespaceSummaries(GetNamespaceSummariesError),
 }
 
 impl Error {
@@ -59,6 +61,8 @@ impl Error {
             Error::GetTransactionSummaries(e) => e.status(),
             Error::GetExplorerSummary(e) => e.status(),
             Error::GetSearchResults(e) => e.status(),
+            Error::GetNamespaceDetail(e) => e.status(),
+            Error::GetNamespaceSummaries(e) => e.status(),
         }
     }
 }
@@ -72,6 +76,8 @@ impl Display for Error {
             Error::GetTransactionSummaries(e) => e.fmt(f),
             Error::GetExplorerSummary(e) => e.fmt(f),
             Error::GetSearchResults(e) => e.fmt(f),
+            Error::GetNamespaceDetail(e) => e.fmt(f),
+            Error::GetNamespaceSummaries(e) => e.fmt(f),
         }
     }
 }
@@ -85,6 +91,8 @@ impl std::error::Error for Error {
             Error::GetTransactionSummaries(e) => Some(e),
             Error::GetExplorerSummary(e) => Some(e),
             Error::GetSearchResults(e) => Some(e),
+            Error::GetNamespaceDetail(e) => Some(e),
+            Error::GetNamespaceSummaries(e) => Some(e),
         }
     }
 }
@@ -213,6 +221,50 @@ where
     }
 }
 
+/// [NamespaceDetailResponse] is a struct that represents the response from the
+/// `get_namespace_detail` endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct NamespaceDetailResponse<Types: NodeType>
+where
+    Header<Types>: ExplorerHeader<Types>,
+    Transaction<Types>: ExplorerTransaction,
+{
+    pub namespace_detail: NamespaceDetail<Types>,
+}
+
+impl<Types: NodeType> From<NamespaceDetail<Types>> for NamespaceDetailResponse<Types>
+where
+    Header<Types>: ExplorerHeader<Types>,
+    Transaction<Types>: ExplorerTransaction,
+{
+    fn from(namespace_detail: NamespaceDetail<Types>) -> Self {
+        Self { namespace_detail }
+    }
+}
+
+/// [NamespaceSummariesResponse] is a struct that represents the response from
+/// the `get_namespace_summaries` endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct NamespaceSummariesResponse<Types: NodeType>
+where
+    Transaction<Types>: ExplorerTransaction,
+{
+    pub namespace_summaries: Vec<NamespaceSummary<Types>>,
+}
+
+impl<Types: NodeType> From<Vec<NamespaceSummary<Types>>> for NamespaceSummariesResponse<Types>
+where
+    Transaction<Types>: ExplorerTransaction,
+{
+    fn from(namespace_summaries: Vec<NamespaceSummary<Types>>) -> Self {
+        Self {
+            namespace_summaries,
+        }
+    }
+}
+
 fn validate_limit(
     limit: Result<usize, tide_disco::RequestError>,
 ) -> Result<NonZeroUsize, InvalidLimit> {
@@ -389,6 +441,51 @@ where
                     .map_err(Error::GetSearchResults)
             }
             .boxed()
+        })?
+        .get("get_namespace_detail", move |req, state| {
+            async move {
+                let namespace = req
+                    .integer_param("namespace")
+                    .map_err(|err| {
+                        tracing::error!("namespace param error: {}", err);
+                        GetNamespaceDetailError::NamespaceNotFound(errors::NotFound {
+                            key: "namespace".to_string(),
+                        })
+                    })
+                    .map_err(Error::GetNamespaceDetail)?;
+
+                state
+                    .get_namespace_detail(namespace)
+                    .await
+                    .map(NamespaceDetailResponse::from)
+                    .map_err(Error::GetNamespaceDetail)
+            }
+            .boxed()
+        })?
+        .get("get_namespace_summaries", move |req, state| {
+            async move {
+                let num_namespaces = validate_limit(req.integer_param("limit"))
+                    .map_err(GetNamespaceSummariesError::InvalidLimit)
+                    .map_err(Error::GetNamespaceSummaries)?;
+
+                let order = match req.opt_string_param("order") {
+                    Ok(Some(order)) => order
+                        .parse()
+                        .map_err(GetNamespaceSummariesError::InvalidQuery)
+                        .map_err(Error::GetNamespaceSummaries)?,
+                    _ => NamespaceOrdering::default(),
+                };
+
+                state
+                    .get_namespace_summaries(GetNamespaceSummariesRequest {
+                        num_namespaces,
+                        order,
+                    })
+                    .await
+                    .map(NamespaceSummariesResponse::from)
+                    .map_err(Error::GetNamespaceSummaries)
+            }
+            .boxed()
         })?;
     Ok(api)
 }
@@ -401,6 +498,7 @@ mod test {
     use portpicker::pick_unused_port;
     use surf_disco::Client;
     use tide_disco::App;
+    use tokio::time::{sleep, timeout};
 
     use super::*;
     use crate::{
@@ -846,6 +944,78 @@ mod test {
                     assert_eq!(a, b);
                 }
             }
+
+            // Namespace pages. All mock transactions are in namespace 0. The namespace statistics
+            // are maintained by the aggregator, which may lag behind, so wait for it to catch up.
+            let namespace_detail = timeout(Duration::from_secs(60), async {
+                loop {
+                    match client
+                        .get::<NamespaceDetailResponse<MockTypes>>("namespace/0")
+                        .send()
+                        .await
+                    {
+                        Ok(res)
+                            if res.namespace_detail.summary.transactions >= num_transactions =>
+                        {
+                            break res.namespace_detail;
+                        },
+                        _ => sleep(Duration::from_millis(100)).await,
+                    }
+                }
+            })
+            .await
+            .expect("timed out waiting for namespace statistics");
+            let summary = &namespace_detail.summary;
+            assert_eq!(summary.namespace, 0);
+            assert_eq!(summary.transactions, num_transactions);
+            assert!(summary.bytes > 0);
+            assert!(summary.first_block <= summary.last_block);
+            assert_eq!(namespace_detail.builders, vec![[0; 32]]);
+
+            let histograms = &namespace_detail.histograms;
+            assert!(!histograms.block_heights.is_empty());
+            assert!(histograms.block_heights.len() <= 50);
+            assert_eq!(
+                histograms.block_transactions.len(),
+                histograms.block_heights.len()
+            );
+            assert_eq!(histograms.block_size.len(), histograms.block_heights.len());
+            assert_eq!(
+                histograms.block_heights.back().copied(),
+                Some(summary.last_block)
+            );
+
+            for order in ["", "/transactions", "/bytes"] {
+                let namespace_summaries_response: NamespaceSummariesResponse<MockTypes> = client
+                    .get(format!("namespaces/leaderboard/10{order}").as_str())
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    namespace_summaries_response.namespace_summaries,
+                    vec![namespace_detail.summary.clone()]
+                );
+            }
+
+            let explorer_summary_response: ExplorerSummaryResponse<MockTypes> =
+                client.get("explorer-summary").send().await.unwrap();
+            let explorer_summary = explorer_summary_response.explorer_summary;
+            assert_eq!(explorer_summary.genesis_overview.rollups, 1);
+            assert_eq!(
+                explorer_summary.most_active_rollups,
+                vec![namespace_detail.summary.clone()]
+            );
+
+            client
+                .get::<NamespaceDetailResponse<MockTypes>>("namespace/1")
+                .send()
+                .await
+                .unwrap_err();
+            client
+                .get::<NamespaceSummariesResponse<MockTypes>>("namespaces/leaderboard/10/nonsense")
+                .send()
+                .await
+                .unwrap_err();
         }
     }
 
@@ -18,9 +18,10 @@ use super::{
     query_data::{
         BlockDetail, BlockIdentifier, BlockSummary, ExplorerSummary, GetBlockDetailError,
         GetBlockSummariesError, GetBlockSummariesRequest, GetExplorerSummaryError,
+        GetNamespaceDetailError, GetNamespaceSummariesError, GetNamespaceSummariesRequest,
         GetSearchResultsError, GetTransactionDetailError, GetTransactionSummariesError,
-        GetTransactionSummariesRequest, SearchResult, TransactionDetailResponse,
-        TransactionIdentifier, TransactionSummary,
+        GetTransactionSummariesRequest, NamespaceDetail, NamespaceSummary, SearchResult,
+        TransactionDetailResponse, TransactionIdentifier, TransactionSummary,
     },
     traits::{ExplorerHeader, ExplorerTransaction},
 };
@@ -91,4 +92,20 @@ where
         &self,
         query: TaggedBase64,
     ) -> Result<SearchResult<Types>, GetSearchResultsError>;
+
+    /// `get_namespace_detail` is a method that retrieves the aggregate
+    /// statistics and recent activity of a specific namespace.  The namespace
+    /// is identified by the integer value of its id.
+    async fn get_namespace_detail(
+        &self,
+        namespace: u64,
+    ) -> Result<NamespaceDetail<Types>, GetNamespaceDetailError>;
+
+    /// `get_namespace_summaries` is a method that retrieves a leaderboard of
+    /// the most active namespaces.  The list is generated from the given
+    /// [GetNamespaceSummariesRequest].
+    async fn get_namespace_summaries(
+        &self,
+        request: GetNamespaceSummariesRequest,
+    ) -> Result<Vec<NamespaceSummary<Types>>, GetNamespaceSummariesError>;
 }
@@ -498,7 +498,9 @@ where
     pub genesis_overview: GenesisOverview,
     pub latest_blocks: Vec<BlockSummary<Types>>,
     pub latest_transactions: Vec<TransactionSummary<Types>>,
-    //  Most Active Rollups
+    // Missing from summaries served by older versions.
+    #[serde(default)]
+    pub most_active_rollups: Vec<NamespaceSummary<Types>>,
     pub histograms: ExplorerHistograms,
 }
 
@@ -516,6 +518,92 @@ where
     pub transactions: Vec<TransactionSummary<Types>>,
 }
 
+/// [NamespaceSummary] is a struct that represents the aggregate statistics of
+/// a single namespace (rollup) over all of the blocks that contain it.  It is
+/// useful for displaying a leaderboard of the most active namespaces.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(bound = "")]
+pub struct NamespaceSummary<Types: NodeType>
+where
+    Transaction<Types>: ExplorerTransaction,
+{
+    pub namespace: TransactionNamespaceId<Types>,
+    pub transactions: u64,
+    pub bytes: u64,
+    pub first_block: u64,
+    pub last_block: u64,
+}
+
+/// [NamespaceHistograms] provides a series of data points describing the
+/// recent activity of a single namespace.
+///
+/// It contains data for the last N blocks that contain transactions in the
+/// namespace, indicated by the length of the vectors contained within the
+/// struct.  All of the vectors **MUST** have the same length.  The labels of
+/// the graph points is the `block_heights` vector.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NamespaceHistograms {
+    pub block_heights: VecDeque<u64>,
+    pub block_transactions: VecDeque<u64>,
+    pub block_size: VecDeque<u64>,
+}
+
+/// [NamespaceDetail] is a struct that represents the details of a specific
+/// namespace (rollup) for use in a Block Explorer.
+///
+/// In addition to the aggregate statistics of the [NamespaceSummary], it
+/// contains the builders which sequenced the most blocks containing the
+/// namespace, and histograms of the recent activity of the namespace.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(bound = "")]
+pub struct NamespaceDetail<Types: NodeType>
+where
+    Header<Types>: ExplorerHeader<Types>,
+    Transaction<Types>: ExplorerTransaction,
+{
+    pub summary: NamespaceSummary<Types>,
+    /// The fee accounts of the builders which sequenced the most blocks
+    /// containing the namespace, most frequent first.
+    ///
+    /// Builders pay the fee for each block. Transactions do not record who
+    /// paid for them, so this does not include the rollups or users which
+    /// paid builders to include their transactions.
+    pub builders: Vec<WalletAddress<Types>>,
+    pub histograms: NamespaceHistograms,
+}
+
+/// [NamespaceOrdering] represents the statistic by which namespaces are ranked
+/// when retrieving a list of [NamespaceSummary] entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NamespaceOrdering {
+    #[default]
+    Transactions,
+    Bytes,
+}
+
+impl std::str::FromStr for NamespaceOrdering {
+    type Err = BadQuery;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "transactions" => Ok(Self::Transactions),
+            "bytes" => Ok(Self::Bytes),
+            _ => Err(BadQuery {}),
+        }
+    }
+}
+
+/// GetNamespaceSummariesRequest is a struct that represents an incoming
+/// request for a leaderboard of Namespace Summaries.  This isn't sent on the
+/// line, but an endpoint will be mapped to this struct in order for the
+/// request to be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GetNamespaceSummariesRequest {
+    pub num_namespaces: NonZeroUsize,
+    pub order: NamespaceOrdering,
+}
+
 /// [GetBlockDetailError] represents an error that has occurred in response to
 /// the `get_block_detail` request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -770,6 +858,7 @@ pub enum GetExplorerSummaryError {
     GetBlockDetailError(GetBlockDetailError),
     GetBlockSummariesError(GetBlockSummariesError),
     GetTransactionSummariesError(GetTransactionSummariesError),
+    GetNamespaceSummariesError(GetNamespaceSummariesError),
 }
 
 impl GetExplorerSummaryError {
@@ -780,6 +869,7 @@ impl GetExplorerSummaryError {
             GetExplorerSummaryError::GetBlockDetailError(err) => err.status(),
             GetExplorerSummaryError::GetBlockSummariesError(err) => err.status(),
             GetExplorerSummaryError::GetTransactionSummariesError(err) => err.status(),
+            GetExplorerSummaryError::GetNamespaceSummariesError(err) => err.status(),
         }
     }
 }
@@ -792,6 +882,7 @@ impl Display for GetExplorerSummaryError {
             GetExplorerSummaryError::GetBlockDetailError(err) => write!(f, "{err}"),
             GetExplorerSummaryError::GetBlockSummariesError(err) => write!(f, "{err}"),
             GetExplorerSummaryError::GetTransactionSummariesError(err) => write!(f, "{err}"),
+            GetExplorerSummaryError::GetNamespaceSummariesError(err) => write!(f, "{err}"),
         }
     }
 }
@@ -804,6 +895,7 @@ impl ExplorerAPIError for GetExplorerSummaryError {
             GetExplorerSummaryError::GetBlockDetailError(err) => err.code(),
             GetExplorerSummaryError::GetBlockSummariesError(err) => err.code(),
             GetExplorerSummaryError::GetTransactionSummariesError(err) => err.code(),
+            GetExplorerSummaryError::GetNamespaceSummariesError(err) => err.code(),
         }
     }
 }
@@ -816,6 +908,7 @@ impl std::error::Error for GetExplorerSummaryError {
             GetExplorerSummaryError::GetBlockDetailError(err) => Some(err),
             GetExplorerSummaryError::GetBlockSummariesError(err) => Some(err),
             GetExplorerSummaryError::GetTransactionSummariesError(err) => Some(err),
+            GetExplorerSummaryError::GetNamespaceSummariesError(err) => Some(err),
         }
     }
 }
@@ -846,6 +939,12 @@ impl From<GetTransactionSummariesError> for GetExplorerSummaryError {
     }
 }
 
+impl From<GetNamespaceSummariesError> for GetExplorerSummaryError {
+    fn from(value: GetNamespaceSummariesError) -> Self {
+        GetExplorerSummaryError::GetNamespaceSummariesError(value)
+    }
+}
+
 /// [GetSearchResultsError] represents an error that has occurred in response
 /// to the `get_search_results` request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -901,3 +1000,120 @@ impl From<crate::QueryError> for GetSearchResultsError {
         GetSearchResultsError::QueryError(QueryError { error: value })
     }
 }
+
+/// [GetNamespaceDetailError] represents an error that has occurred in response
+/// to the `get_namespace_detail` request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetNamespaceDetailError {
+    Unimplemented(Unimplemented),
+    NamespaceNotFound(NotFound),
+    QueryError(QueryError),
+}
+
+impl GetNamespaceDetailError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            GetNamespaceDetailError::Unimplemented(err) => err.status(),
+            GetNamespaceDetailError::QueryError(err) => err.status(),
+            GetNamespaceDetailError::NamespaceNotFound(err) => err.status(),
+        }
+    }
+}
+
+impl Display for GetNamespaceDetailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GetNamespaceDetailError::Unimplemented(err) => write!(f, "{err}"),
+            GetNamespaceDetailError::QueryError(err) => write!(f, "{err}"),
+            GetNamespaceDetailError::NamespaceNotFound(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl ExplorerAPIError for GetNamespaceDetailError {
+    fn code(&self) -> &str {
+        match self {
+            GetNamespaceDetailError::Unimplemented(err) => err.code(),
+            GetNamespaceDetailError::QueryError(err) => err.code(),
+            GetNamespaceDetailError::NamespaceNotFound(err) => err.code(),
+        }
+    }
+}
+
+impl std::error::Error for GetNamespaceDetailError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GetNamespaceDetailError::Unimplemented(err) => Some(err),
+            GetNamespaceDetailError::QueryError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<crate::QueryError> for GetNamespaceDetailError {
+    fn from(value: crate::QueryError) -> Self {
+        GetNamespaceDetailError::QueryError(QueryError { error: value })
+    }
+}
+
+/// [GetNamespaceSummariesError] represents an error that has occurred in
+/// response to the [GetNamespaceSummariesRequest] request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetNamespaceSummariesError {
+    Unimplemented(Unimplemented),
+    InvalidLimit(InvalidLimit),
+    InvalidQuery(BadQuery),
+    QueryError(QueryError),
+}
+
+impl GetNamespaceSummariesError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            GetNamespaceSummariesError::Unimplemented(err) => err.status(),
+            GetNamespaceSummariesError::InvalidLimit(err) => err.status(),
+            GetNamespaceSummariesError::InvalidQuery(err) => err.status(),
+            GetNamespaceSummariesError::QueryError(err) => err.status(),
+        }
+    }
+}
+
+impl Display for GetNamespaceSummariesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GetNamespaceSummariesError::Unimplemented(err) => write!(f, "{err}"),
+            GetNamespaceSummariesError::InvalidLimit(err) => write!(f, "{err}"),
+            GetNamespaceSummariesError::InvalidQuery(err) => write!(f, "{err}"),
+            GetNamespaceSummariesError::QueryError(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl ExplorerAPIError for GetNamespaceSummariesError {
+    fn code(&self) -> &str {
+        match self {
+            GetNamespaceSummariesError::Unimplemented(err) => err.code(),
+            GetNamespaceSummariesError::InvalidLimit(err) => err.code(),
+            GetNamespaceSummariesError::InvalidQuery(err) => err.code(),
+            GetNamespaceSummariesError::QueryError(err) => err.code(),
+        }
+    }
+}
+
+impl std::error::Error for GetNamespaceSummariesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GetNamespaceSummariesError::Unimplemented(err) => Some(err),
+            GetNamespaceSummariesError::InvalidLimit(err) => Some(err),
+            GetNamespaceSummariesError::InvalidQuery(err) => Some(err),
+            GetNamespaceSummariesError::QueryError(err) => Some(err),
+        }
+    }
+}
+
+impl From<crate::QueryError> for GetNamespaceSummariesError {
+    fn from(value: crate::QueryError) -> Self {
+        GetNamespaceSummariesError::QueryError(QueryError { error: value })
+    }
+}
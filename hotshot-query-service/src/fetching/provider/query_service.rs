@@ -100,7 +100,7 @@ impl<Ver: StaticVersionType> QueryServiceProvider<Ver> {
         bytes: Vec<u8>,
         req: VidCommonRequest,
     ) -> Option<VidCommon> {
-        let VidCommonRequest(VidCommitment::V0(advz_commit)) = req else {
+        let VidCommonRequest(VidCommitment::V0(advz_commit), _) = req else {
             return None;
         };
 
@@ -1429,9 +1429,11 @@ mod test {
 
         // Query for a random VID common, the server will respond with a different one, and we
         // should detect the error.
-        let res =
-            ProviderTrait::<MockTypes, _>::fetch(&provider, VidCommonRequest(random_vid_commit()))
-                .await;
+        let res = ProviderTrait::<MockTypes, _>::fetch(
+            &provider,
+            VidCommonRequest(random_vid_commit(), 0),
+        )
+        .await;
         assert_eq!(res, None);
     }
 
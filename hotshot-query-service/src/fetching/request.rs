@@ -36,9 +36,13 @@ impl<Types: NodeType> Request<Types> for PayloadRequest {
     type Response = Payload<Types>;
 }
 
-/// A request for VID common data.
+/// A request for VID common data, given the payload commitment and height of its block.
+///
+/// The height is not needed to check the response against the commitment, but some VID schemes
+/// do not bind all of the common data to the commitment. Providers can use the height to look up
+/// what the rest of the common data should be (e.g. the stake table the block was dispersed to).
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct VidCommonRequest(pub VidCommitment);
+pub struct VidCommonRequest(pub VidCommitment, pub u64);
 
 impl<Types: NodeType> Request<Types> for VidCommonRequest {
     type Response = VidCommon;
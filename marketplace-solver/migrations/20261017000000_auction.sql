@@ -0,0 +1,11 @@
+CREATE TABLE bids (
+    view_number BIGINT NOT NULL,
+    account BYTEA NOT NULL,
+    data BYTEA NOT NULL,
+    PRIMARY KEY (view_number, account)
+);
+
+CREATE TABLE auction_results (
+    view_number BIGINT PRIMARY KEY,
+    data BYTEA NOT NULL
+);
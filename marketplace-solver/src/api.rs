@@ -31,6 +31,14 @@ pub enum SolverError {
     SignatureKeysMismatch(String),
     #[error("Signature key {0} does not match signatures in the database")]
     SignatureDatabaseKeysMismatch(String),
+    #[error("Invalid bid: {0}")]
+    InvalidBid(String),
+    #[error("Insufficient balance to pay for bid: {0}")]
+    InsufficientBalance(String),
+    #[error("Auction for view {0} is already closed")]
+    AuctionClosed(u64),
+    #[error("Auction for view {0} is still open")]
+    AuctionOpen(u64),
     #[error("bincode err: {0}")]
     BincodeError(String),
     #[error("database err: {0}")]
@@ -113,13 +121,25 @@ where
     .get("auction_results_permissioned", |req, state| {
         async move {
             let view_num: u64 = req.integer_param("view_number")?;
+            let signer = req.blob_param("signer")?;
             let signature = req.blob_param("signature")?;
             state
-                .calculate_auction_results_permissioned(ViewNumber::new(view_num), signature)
+                .calculate_auction_results_permissioned(
+                    ViewNumber::new(view_num),
+                    signer,
+                    signature,
+                )
                 .await
         }
         .boxed()
     })?
+    .get("auction_outcome", |req, state| {
+        async move {
+            let view_num: u64 = req.integer_param("view_number")?;
+            state.get_auction_outcome(ViewNumber::new(view_num)).await
+        }
+        .boxed()
+    })?
     .post("register_rollup", |req, state| {
         async move {
             let body = req.body_json::<RollupRegistration>()?;
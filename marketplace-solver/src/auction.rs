@@ -0,0 +1,295 @@
+//! Winner determination for the sequencing auction.
+//!
+//! The auction for a view is run once, over all the valid bids submitted for that view. Bids are
+//! considered from highest to lowest. A bid wins if all of its namespaces are registered and active,
+//! it meets the sum of their reserve prices, and none of its namespaces were already won by a
+//! higher bid. Thus the highest valid bid for each set of namespaces wins, unless it overlaps with
+//! an even higher bid for a different set. Namespaces which no bid wins fall back to the reserve
+//! builder of the rollup, if it has one.
+//!
+//! The outcome depends only on the set of bids and registrations, not on the order bids were
+//! received in: bids with equal amounts are ordered by commitment.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use alloy::primitives::U256;
+use committable::{Commitment, Committable};
+use espresso_types::{
+    v0_99::{BidTx, RollupRegistration, RollupRegistrationBody, SolverAuctionResults},
+    FeeAmount, NamespaceId,
+};
+use hotshot_types::data::ViewNumber;
+use serde::{Deserialize, Serialize};
+
+/// The full outcome of the auction for a view.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuctionOutcome {
+    /// The winning and reserve bids, as served to the leader.
+    pub results: SolverAuctionResults,
+    /// The bids which did not win, and why.
+    pub losing_bids: Vec<LosingBid>,
+}
+
+/// A bid which did not win the auction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LosingBid {
+    pub bid: BidTx,
+    pub reason: LossReason,
+}
+
+/// The reason a bid did not win the auction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LossReason {
+    /// The bid does not name any namespaces.
+    NoNamespaces,
+    /// The bid is for a namespace with no registered rollup.
+    UnregisteredNamespace(NamespaceId),
+    /// The bid is for a namespace whose rollup is not active in the marketplace.
+    InactiveNamespace(NamespaceId),
+    /// The bid is lower than the sum of the reserve prices of its namespaces.
+    BelowReservePrice { reserve_price: FeeAmount },
+    /// Some of the namespaces of the bid were won by a higher bid.
+    Outbid { winner: Commitment<BidTx> },
+}
+
+/// Run the auction for `view`.
+pub fn run_auction(
+    view: ViewNumber,
+    bids: impl IntoIterator<Item = BidTx>,
+    registrations: &[RollupRegistration],
+) -> AuctionOutcome {
+    let rollups = registrations
+        .iter()
+        .map(|r| (r.body.namespace_id, &r.body))
+        .collect::<BTreeMap<_, _>>();
+
+    // Order bids from highest to lowest, breaking ties by commitment.
+    let mut bids = bids
+        .into_iter()
+        .map(|bid| (bid.commit(), bid))
+        .collect::<Vec<_>>();
+    bids.sort_by(|(comm_a, a), (comm_b, b)| {
+        b.amount()
+            .cmp(&a.amount())
+            .then_with(|| comm_a.as_ref().cmp(comm_b.as_ref()))
+    });
+
+    let mut won = BTreeMap::<NamespaceId, Commitment<BidTx>>::new();
+    let mut winning_bids = vec![];
+    let mut losing_bids = vec![];
+    for (comm, bid) in bids {
+        let namespaces = bid.namespaces().iter().copied().collect::<BTreeSet<_>>();
+        if let Err(reason) = check_eligibility(&bid, &namespaces, &rollups) {
+            losing_bids.push(LosingBid { bid, reason });
+            continue;
+        }
+        if let Some(&winner) = namespaces.iter().find_map(|ns| won.get(ns)) {
+            losing_bids.push(LosingBid {
+                bid,
+                reason: LossReason::Outbid { winner },
+            });
+            continue;
+        }
+        won.extend(namespaces.into_iter().map(|ns| (ns, comm)));
+        winning_bids.push(bid);
+    }
+
+    let reserve_bids = rollups
+        .values()
+        .filter(|rollup| rollup.active && !won.contains_key(&rollup.namespace_id))
+        .filter_map(|rollup| Some((rollup.namespace_id, rollup.reserve_url.clone()?)))
+        .collect();
+
+    AuctionOutcome {
+        results: SolverAuctionResults::new(view, winning_bids, reserve_bids),
+        losing_bids,
+    }
+}
+
+fn check_eligibility(
+    bid: &BidTx,
+    namespaces: &BTreeSet<NamespaceId>,
+    rollups: &BTreeMap<NamespaceId, &RollupRegistrationBody>,
+) -> Result<(), LossReason> {
+    if namespaces.is_empty() {
+        return Err(LossReason::NoNamespaces);
+    }
+    let mut reserve_price = U256::ZERO;
+    for ns in namespaces {
+        let Some(rollup) = rollups.get(ns) else {
+            return Err(LossReason::UnregisteredNamespace(*ns));
+        };
+        if !rollup.active {
+            return Err(LossReason::InactiveNamespace(*ns));
+        }
+        reserve_price = reserve_price.saturating_add(rollup.reserve_price.0);
+    }
+    if bid.amount().0 < reserve_price {
+        return Err(LossReason::BelowReservePrice {
+            reserve_price: FeeAmount(reserve_price),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use espresso_types::{eth_signature_key::EthKeyPair, v0_99::BidTxBody, SeqTypes};
+    use hotshot::types::{BLSPubKey, SignatureKey};
+    use hotshot_types::traits::node_implementation::{ConsensusTime, NodeType};
+    use tide_disco::Url;
+
+    use super::*;
+
+    fn registration(namespace_id: u64, reserve_price: u64, active: bool) -> RollupRegistration {
+        let private_key =
+            <BLSPubKey as SignatureKey>::PrivateKey::generate(&mut rand::thread_rng());
+        let signature_key = BLSPubKey::from_private(&private_key);
+        let body = RollupRegistrationBody {
+            namespace_id: namespace_id.into(),
+            reserve_url: Some(Url::from_str(&format!("http://reserve-{namespace_id}")).unwrap()),
+            reserve_price: reserve_price.into(),
+            active,
+            signature_keys: vec![signature_key],
+            signature_key,
+            text: String::new(),
+        };
+        let signature =
+            <SeqTypes as NodeType>::SignatureKey::sign(&private_key, body.commit().as_ref())
+                .unwrap();
+        RollupRegistration { body, signature }
+    }
+
+    fn bid(amount: u64, namespaces: &[u64]) -> BidTx {
+        let key = EthKeyPair::random();
+        BidTxBody::new(
+            key.fee_account(),
+            amount.into(),
+            ViewNumber::new(1),
+            namespaces.iter().map(|&ns| ns.into()).collect(),
+            Url::from_str("http://builder").unwrap(),
+            FeeAmount::default(),
+        )
+        .signed(&key)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_highest_bid_wins() {
+        let registrations = [registration(1, 10, true), registration(2, 10, true)];
+        let low = bid(20, &[1]);
+        let high = bid(30, &[1]);
+
+        let outcome = run_auction(
+            ViewNumber::new(1),
+            [low.clone(), high.clone()],
+            &registrations,
+        );
+        assert_eq!(outcome.results.winning_bids(), [high.clone()]);
+        assert_eq!(
+            outcome.losing_bids,
+            [LosingBid {
+                bid: low,
+                reason: LossReason::Outbid {
+                    winner: high.commit()
+                },
+            }]
+        );
+        // Namespace 2 was not won, so it falls back to its reserve builder.
+        assert_eq!(
+            outcome.results.reserve_bids(),
+            [(
+                NamespaceId::from(2u64),
+                Url::from_str("http://reserve-2").unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_overlapping_namespace_sets() {
+        let registrations = [
+            registration(1, 0, true),
+            registration(2, 0, true),
+            registration(3, 0, true),
+        ];
+        let pair = bid(50, &[1, 2]);
+        let overlapping = bid(40, &[2, 3]);
+        let single = bid(10, &[3]);
+
+        let outcome = run_auction(
+            ViewNumber::new(1),
+            [single.clone(), overlapping.clone(), pair.clone()],
+            &registrations,
+        );
+        assert_eq!(outcome.results.winning_bids(), [pair.clone(), single]);
+        assert_eq!(
+            outcome.losing_bids,
+            [LosingBid {
+                bid: overlapping,
+                reason: LossReason::Outbid {
+                    winner: pair.commit()
+                },
+            }]
+        );
+        assert!(outcome.results.reserve_bids().is_empty());
+    }
+
+    #[test]
+    fn test_ineligible_bids() {
+        let registrations = [registration(1, 10, true), registration(2, 10, false)];
+        let bids = [bid(100, &[]), bid(100, &[3]), bid(100, &[2]), bid(15, &[1])];
+
+        let outcome = run_auction(ViewNumber::new(1), bids.clone(), &registrations);
+        assert!(outcome.results.winning_bids().is_empty());
+        let mut reasons = outcome
+            .losing_bids
+            .into_iter()
+            .map(|loser| {
+                let i = bids.iter().position(|bid| *bid == loser.bid).unwrap();
+                (i, loser.reason)
+            })
+            .collect::<Vec<_>>();
+        reasons.sort_by_key(|(i, _)| *i);
+        assert_eq!(
+            reasons,
+            [
+                (0, LossReason::NoNamespaces),
+                (
+                    1,
+                    LossReason::UnregisteredNamespace(NamespaceId::from(3u64))
+                ),
+                (2, LossReason::InactiveNamespace(NamespaceId::from(2u64))),
+                (
+                    3,
+                    LossReason::BelowReservePrice {
+                        reserve_price: FeeAmount::from(10u64)
+                    }
+                ),
+            ]
+        );
+        // Inactive rollups do not get a reserve builder.
+        assert_eq!(
+            outcome.results.reserve_bids(),
+            [(
+                NamespaceId::from(1u64),
+                Url::from_str("http://reserve-1").unwrap()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_outcome_is_deterministic() {
+        let registrations = [registration(1, 0, true), registration(2, 0, true)];
+        let bids = [bid(10, &[1]), bid(10, &[1]), bid(10, &[1, 2]), bid(5, &[2])];
+
+        let outcome = run_auction(ViewNumber::new(1), bids.clone(), &registrations);
+        let mut reversed = bids.clone();
+        reversed.reverse();
+        assert_eq!(
+            run_auction(ViewNumber::new(1), reversed, &registrations),
+            outcome
+        );
+    }
+}
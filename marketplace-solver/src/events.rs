@@ -47,7 +47,7 @@ impl EventsServiceClient {
 
 pub async fn handle_events(
     mut stream: Pin<Box<dyn Stream<Item = Result<Event<SeqTypes>, events::Error>> + Send>>,
-    _state: Arc<RwLock<GlobalState>>,
+    state: Arc<RwLock<GlobalState>>,
 ) -> anyhow::Result<()> {
     while let Some(event) = stream.next().await {
         let event = event?;
@@ -58,7 +58,10 @@ pub async fn handle_events(
         #[allow(clippy::single_match)]
         match event.event {
             hotshot::types::EventType::ViewFinished { view_number } => {
-                tracing::debug!("received view finished event {view_number:?}")
+                tracing::debug!("received view finished event {view_number:?}");
+                if let Err(err) = state.read().await.start_view(view_number + 1).await {
+                    tracing::warn!(?view_number, "failed to close auction: {err}");
+                }
             },
             _ => (),
         }
@@ -95,12 +98,11 @@ pub mod mock {
     const STAKED_NODES: usize = 10;
     pub type StaticVer01 = StaticVersion<0, 1>;
 
+    /// A stake table of equally staked nodes, whose keys are generated from the seed `[0; 32]`.
     pub fn generate_stake_table() -> Vec<PeerConfig<SeqTypes>> {
         (0..STAKED_NODES)
-            .map(|_| {
-                let private_key =
-                    <BLSPubKey as SignatureKey>::PrivateKey::generate(&mut rand::thread_rng());
-                let pub_key = BLSPubKey::from_private(&private_key);
+            .map(|i| {
+                let (pub_key, _) = BLSPubKey::generated_from_seed_indexed([0; 32], i as u64);
                 let state_key_pair = StateKeyPair::generate();
 
                 PeerConfig::<SeqTypes> {
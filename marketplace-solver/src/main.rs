@@ -7,7 +7,7 @@ use espresso_types::MarketplaceVersion;
 use hotshot::helpers::initialize_logging;
 use marketplace_solver::{
     define_api, handle_events,
-    state::{GlobalState, SolverState, StakeTable},
+    state::{GlobalState, SequencerClient, SolverState, StakeTable},
     EventsServiceClient, Options, SolverError,
 };
 use tide_disco::App;
@@ -22,6 +22,8 @@ async fn main() -> anyhow::Result<()> {
     let Options {
         solver_api_port,
         events_api_url,
+        sequencer_url,
+        solver_url,
         database_options,
     } = args;
 
@@ -48,7 +50,11 @@ async fn main() -> anyhow::Result<()> {
         stake_table: StakeTable {
             known_nodes_with_stake: startup_info.known_node_with_stake,
         },
-        bid_txs: Default::default(),
+        sequencer: sequencer_url.map(SequencerClient::new),
+        url: match solver_url {
+            Some(url) => url,
+            None => format!("http://localhost:{solver_api_port}").parse()?,
+        },
     };
 
     let global_state = Arc::new(RwLock::new(GlobalState::new(database, solver_state)?));
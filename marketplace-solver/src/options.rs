@@ -16,6 +16,20 @@ pub struct Options {
     #[arg(short, long, env = "ESPRESSO_SEQUENCER_HOTSHOT_EVENT_API_URL")]
     pub events_api_url: Url,
 
+    /// URL of a sequencer node providing the fee state API, used to check that bidders can pay for
+    /// their bids.
+    ///
+    /// If not provided, bids are accepted regardless of the balance of the bidder.
+    #[arg(long, env = "ESPRESSO_MARKETPLACE_SOLVER_SEQUENCER_URL")]
+    pub sequencer_url: Option<Url>,
+
+    /// Public URL of this solver.
+    ///
+    /// Leaders sign requests for permissioned auction results for this URL, so it must match the
+    /// solver URL they are configured with. Defaults to `http://localhost:<solver-api-port>`.
+    #[arg(long, env = "ESPRESSO_MARKETPLACE_SOLVER_URL")]
+    pub solver_url: Option<Url>,
+
     #[command(flatten)]
     pub database_options: DatabaseOptions,
 }
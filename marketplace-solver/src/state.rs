@@ -1,26 +1,74 @@
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 
+use alloy::primitives::U256;
 use async_trait::async_trait;
-use committable::Committable;
+use committable::{Commitment, Committable, RawCommitmentBuilder};
 use espresso_types::{
     v0_99::{
         BidTx, RollupRegistration, RollupRegistrationBody, RollupUpdate, RollupUpdatebody,
         SolverAuctionResults,
     },
-    SeqTypes,
+    FeeAmount, SeqTypes,
     Update::Set,
 };
 use hotshot::types::SignatureKey;
-use hotshot_types::{data::ViewNumber, traits::node_implementation::NodeType, PeerConfig};
+use hotshot_types::{
+    data::ViewNumber,
+    traits::{
+        node_implementation::{ConsensusTime, NodeType},
+        signature_key::StakeTableEntryType,
+    },
+    PeerConfig,
+};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgConnection, PgPool};
+use tide_disco::{error::ServerError, StatusCode, Url};
+use vbs::version::StaticVersion;
+
+use crate::{
+    auction::{run_auction, AuctionOutcome},
+    database::PostgresClient,
+    overflow_err, SolverError, SolverResult,
+};
 
-use crate::{database::PostgresClient, overflow_err, SolverError, SolverResult};
+/// Client for the API of a sequencer node.
+pub type SequencerClient = surf_disco::Client<ServerError, StaticVersion<0, 1>>;
+
+/// Signature of a leader on an [`AuctionResultsRequest`].
+pub type AuctionResultsSignature =
+    <<SeqTypes as NodeType>::SignatureKey as SignatureKey>::PureAssembledSignatureType;
+
+/// A request from the leader of a view for the results of the auction for that view.
+///
+/// The leader signs the commitment of this request to use the permissioned auction results
+/// endpoint. The request names the solver it is for, so that it cannot be replayed to another
+/// solver.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuctionResultsRequest {
+    pub view_number: ViewNumber,
+    /// Public URL of the solver.
+    pub solver: Url,
+}
+
+impl Committable for AuctionResultsRequest {
+    fn tag() -> String {
+        "AUCTION_RESULTS_REQUEST".to_string()
+    }
+
+    fn commit(&self) -> Commitment<Self> {
+        RawCommitmentBuilder::new(&Self::tag())
+            .u64_field("view_number", self.view_number.u64())
+            .var_size_field("solver", self.solver.as_str().as_bytes())
+            .finalize()
+    }
+}
 
 // TODO ED: Implement a shared solver state with the HotShot events received
 pub struct GlobalState {
     solver: SolverState,
     database: PostgresClient,
+    /// The latest view HotShot has started, according to the events service.
+    current_view: AtomicU64,
 }
 
 impl GlobalState {
@@ -38,19 +86,158 @@ impl GlobalState {
         Ok(Self {
             solver: state,
             database: db,
+            current_view: AtomicU64::new(ViewNumber::genesis().u64()),
         })
     }
+
+    /// The latest view HotShot has started.
+    pub fn current_view(&self) -> ViewNumber {
+        ViewNumber::new(self.current_view.load(Ordering::Acquire))
+    }
+
+    /// Record that HotShot has started `view_number`, and close the auction for it.
+    ///
+    /// Views never go backwards: if a later view has already started, this does nothing.
+    pub async fn start_view(&self, view_number: ViewNumber) -> SolverResult<()> {
+        let prev = self
+            .current_view
+            .fetch_max(view_number.u64(), Ordering::AcqRel);
+        if view_number.u64() > prev {
+            self.close_auction(view_number).await?;
+        }
+        Ok(())
+    }
+
+    /// Load the outcome of the auction for `view_number`, if the auction has been run.
+    async fn load_auction_outcome(
+        &self,
+        view_number: ViewNumber,
+    ) -> SolverResult<Option<AuctionOutcome>> {
+        let data: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT data FROM auction_results WHERE view_number = $1;")
+                .bind::<i64>(view_number.u64().try_into().map_err(overflow_err)?)
+                .fetch_optional(self.database())
+                .await
+                .map_err(SolverError::from)?;
+        Ok(data.map(|data| bincode::deserialize(&data)).transpose()?)
+    }
+
+    /// Run the auction for `view_number`, if it has not been run yet.
+    ///
+    /// Only auctions for views which HotShot has already started can be closed. Once the auction
+    /// has run, its outcome is persisted and bids for the view are no longer accepted, so every
+    /// subsequent request for the results of this view gets the same answer.
+    async fn close_auction(&self, view_number: ViewNumber) -> SolverResult<AuctionOutcome> {
+        if let Some(outcome) = self.load_auction_outcome(view_number).await? {
+            return Ok(outcome);
+        }
+        if view_number > self.current_view() {
+            return Err(SolverError::AuctionOpen(view_number.u64()));
+        }
+        let rollups = self.get_all_rollup_registrations().await?;
+
+        let view = view_number.u64().try_into().map_err(overflow_err)?;
+        let mut tx = self.database().begin().await.map_err(SolverError::from)?;
+        lock_auction(&mut tx, view).await?;
+
+        // Another solver sharing the database may have closed the auction while we were waiting for
+        // the lock, in which case its outcome is the one which counts.
+        let data: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT data FROM auction_results WHERE view_number = $1;")
+                .bind::<i64>(view)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(SolverError::from)?;
+        if let Some(data) = data {
+            return Ok(bincode::deserialize(&data)?);
+        }
+
+        // The losing bids are part of the outcome, so the individual bids are no longer needed.
+        let bids: Vec<Vec<u8>> =
+            sqlx::query_scalar("DELETE FROM bids WHERE view_number = $1 RETURNING data;")
+                .bind::<i64>(view)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(SolverError::from)?;
+        let bids = bids
+            .iter()
+            .map(|data| bincode::deserialize(data).map_err(SolverError::from))
+            .collect::<SolverResult<Vec<BidTx>>>()?;
+
+        let outcome = run_auction(view_number, bids, &rollups);
+        tracing::info!(
+            ?view_number,
+            winners = outcome.results.winning_bids().len(),
+            losers = outcome.losing_bids.len(),
+            "auction closed"
+        );
+
+        sqlx::query("INSERT INTO auction_results (view_number, data) VALUES ($1, $2);")
+            .bind::<i64>(view)
+            .bind(bincode::serialize(&outcome)?)
+            .execute(&mut *tx)
+            .await
+            .map_err(SolverError::from)?;
+        tx.commit().await.map_err(SolverError::from)?;
+        Ok(outcome)
+    }
+
+    /// Check that the account submitting `bid_tx` can pay for it, according to the sequencer.
+    async fn check_balance(&self, bid_tx: &BidTx) -> SolverResult<()> {
+        let Some(sequencer) = &self.solver.sequencer else {
+            return Ok(());
+        };
+        let account = bid_tx.account();
+        let balance = sequencer
+            .get::<Option<FeeAmount>>(&format!("fee-state/fee-balance/latest/{account}"))
+            .send()
+            .await
+            .map_err(|err| SolverError::Custom {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                message: format!("failed to fetch balance of {account}: {err}"),
+            })?
+            .unwrap_or_default();
+        let cost = bid_tx.amount().0.saturating_add(bid_tx.gas_price().0);
+        if balance.0 < cost {
+            return Err(SolverError::InsufficientBalance(account.to_string()));
+        }
+        Ok(())
+    }
 }
 
 pub struct SolverState {
     pub stake_table: StakeTable,
-    pub bid_txs: HashMap<ViewNumber, HashMap<<SeqTypes as NodeType>::BuilderSignatureKey, BidTx>>,
+    /// Sequencer to check bidder balances against, using the fee state API.
+    ///
+    /// If not set, balances are not checked.
+    pub sequencer: Option<SequencerClient>,
+    /// Public URL of this solver, which leaders sign requests for permissioned auction results for.
+    pub url: Url,
 }
 
 pub struct StakeTable {
     pub known_nodes_with_stake: Vec<PeerConfig<SeqTypes>>,
 }
 
+impl StakeTable {
+    /// The leader of `view_number`.
+    ///
+    /// This follows the leader rotation HotShot uses before epochs, which is the only setting the
+    /// marketplace runs in.
+    pub fn leader(&self, view_number: ViewNumber) -> Option<<SeqTypes as NodeType>::SignatureKey> {
+        let eligible = self
+            .known_nodes_with_stake
+            .iter()
+            .filter(|peer| peer.stake_table_entry.stake() > U256::ZERO)
+            .collect::<Vec<_>>();
+        if eligible.is_empty() {
+            return None;
+        }
+        let index = (view_number.u64() % eligible.len() as u64) as usize;
+        Some(eligible[index].stake_table_entry.stake_key)
+    }
+}
+
 #[async_trait]
 pub trait UpdateSolverState {
     async fn submit_bid_tx(&mut self, bid_tx: BidTx) -> SolverResult<()>;
@@ -66,26 +253,75 @@ pub trait UpdateSolverState {
 
     async fn get_all_rollup_registrations(&self) -> SolverResult<Vec<RollupRegistration>>;
 
+    /// Results of the auction for `view_number`, once it has been closed.
     async fn calculate_auction_results_permissionless(
         &self,
         view_number: ViewNumber,
     ) -> SolverResult<SolverAuctionResults>;
 
+    /// Results of the auction for `view_number`, closing it if necessary.
+    ///
+    /// Only the leader of `view_number` may use this, by signing an [`AuctionResultsRequest`] for
+    /// this view and solver.
     async fn calculate_auction_results_permissioned(
         &self,
         view_number: ViewNumber,
-        _signature: <SeqTypes as NodeType>::SignatureKey,
+        signer: <SeqTypes as NodeType>::SignatureKey,
+        signature: AuctionResultsSignature,
     ) -> SolverResult<SolverAuctionResults>;
+
+    async fn get_auction_outcome(&self, view_number: ViewNumber) -> SolverResult<AuctionOutcome>;
 }
 
 #[async_trait]
 impl UpdateSolverState for GlobalState {
     async fn submit_bid_tx(&mut self, bid_tx: BidTx) -> SolverResult<()> {
         let view = bid_tx.view();
-        let builder_key = bid_tx.account();
+        let account = bid_tx.account();
+
+        if bid_tx.verify().is_err() {
+            return Err(SolverError::InvalidSignature(account.to_string()));
+        }
+        if bid_tx.namespaces().is_empty() {
+            return Err(SolverError::InvalidBid(
+                "bid is not for any namespace".to_string(),
+            ));
+        }
+        if self.load_auction_outcome(view).await?.is_some() {
+            return Err(SolverError::AuctionClosed(view.u64()));
+        }
+        self.check_balance(&bid_tx).await?;
+
+        // Hold the auction lock while adding the bid, so the auction cannot close in between
+        // checking that it is open and adding the bid.
+        let view_number = view.u64().try_into().map_err(overflow_err)?;
+        let mut tx = self.database().begin().await.map_err(SolverError::from)?;
+        lock_auction(&mut tx, view_number).await?;
+        let closed: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM auction_results WHERE view_number = $1);",
+        )
+        .bind::<i64>(view_number)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(SolverError::from)?;
+        if closed {
+            return Err(SolverError::AuctionClosed(view.u64()));
+        }
+
+        // A new bid from the same account for the same view replaces the previous one.
+        let bytes = bincode::serialize(&bid_tx)?;
+        sqlx::query(
+            "INSERT INTO bids (view_number, account, data) VALUES ($1, $2, $3)
+             ON CONFLICT (view_number, account) DO UPDATE SET data = excluded.data;",
+        )
+        .bind::<i64>(view_number)
+        .bind(account.to_fixed_bytes().to_vec())
+        .bind(&bytes)
+        .execute(&mut *tx)
+        .await
+        .map_err(SolverError::from)?;
+        tx.commit().await.map_err(SolverError::from)?;
 
-        let bid_txs = &mut self.solver.bid_txs;
-        bid_txs.entry(view).or_default().insert(builder_key, bid_tx);
         Ok(())
     }
 
@@ -263,45 +499,54 @@ impl UpdateSolverState for GlobalState {
         &self,
         view_number: ViewNumber,
     ) -> SolverResult<SolverAuctionResults> {
-        // todo (ab): actual logic needs to implemented
-        // for we, we just return some default results
-
-        let rollups = self.get_all_rollup_registrations().await?;
-
-        let results = SolverAuctionResults::new(
-            view_number,
-            Vec::new(),
-            rollups
-                .into_iter()
-                .filter_map(|r| Some((r.body.namespace_id, r.body.reserve_url?)))
-                .collect(),
-        );
-
-        Ok(results)
+        Ok(self.get_auction_outcome(view_number).await?.results)
     }
+
     async fn calculate_auction_results_permissioned(
         &self,
         view_number: ViewNumber,
-        _signature: <SeqTypes as NodeType>::SignatureKey,
+        signer: <SeqTypes as NodeType>::SignatureKey,
+        signature: AuctionResultsSignature,
     ) -> SolverResult<SolverAuctionResults> {
-        // todo (ab): actual logic needs to implemented
-        // for we, we just return some default results
-
-        let rollups = self.get_all_rollup_registrations().await?;
-
-        let results = SolverAuctionResults::new(
+        let request = AuctionResultsRequest {
             view_number,
-            Vec::new(),
-            rollups
-                .into_iter()
-                .filter_map(|r| Some((r.body.namespace_id, r.body.reserve_url?)))
-                .collect(),
-        );
+            solver: self.solver.url.clone(),
+        };
+        if !signer.validate(&signature, request.commit().as_ref()) {
+            return Err(SolverError::InvalidSignature(signer.to_string()));
+        }
+        if self.solver.stake_table.leader(view_number).as_ref() != Some(&signer) {
+            return Err(SolverError::Custom {
+                status: StatusCode::UNAUTHORIZED,
+                message: format!("{signer} is not the leader of view {}", view_number.u64()),
+            });
+        }
+        Ok(self.close_auction(view_number).await?.results)
+    }
 
-        Ok(results)
+    async fn get_auction_outcome(&self, view_number: ViewNumber) -> SolverResult<AuctionOutcome> {
+        self.load_auction_outcome(view_number)
+            .await?
+            .ok_or_else(|| SolverError::Custom {
+                status: StatusCode::NOT_FOUND,
+                message: format!("auction for view {} has not been run", view_number.u64()),
+            })
     }
 }
 
+/// Lock the auction for `view` until the end of the transaction `tx`.
+///
+/// This serializes closing an auction with adding bids to it, also between solvers sharing the
+/// database.
+async fn lock_auction(tx: &mut PgConnection, view: i64) -> SolverResult<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock($1);")
+        .bind(view)
+        .execute(tx)
+        .await
+        .map_err(SolverError::from)?;
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, Serialize, FromRow)]
 struct RollupRegistrationResult {
     namespace_id: i64,
@@ -347,7 +592,8 @@ impl SolverState {
             stake_table: StakeTable {
                 known_nodes_with_stake: crate::mock::generate_stake_table(),
             },
-            bid_txs: Default::default(),
+            sequencer: None,
+            url: "http://localhost".parse().unwrap(),
         }
     }
 }
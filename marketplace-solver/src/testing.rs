@@ -65,11 +65,15 @@ impl MockSolver {
         let startup_info = client.get_startup_info().await.unwrap();
         let stream = client.get_event_stream().await.unwrap();
 
+        let solver_api_port = pick_unused_port().expect("no free port");
+        let solver_url: Url = Url::parse(&format!("http://localhost:{solver_api_port}")).unwrap();
+
         let solver_state = SolverState {
             stake_table: StakeTable {
                 known_nodes_with_stake: startup_info.known_node_with_stake,
             },
-            bid_txs: Default::default(),
+            sequencer: None,
+            url: solver_url.clone(),
         };
 
         let state = Arc::new(RwLock::new(
@@ -92,9 +96,6 @@ impl MockSolver {
         app.register_module::<SolverError, MarketplaceVersion>(SOLVER_API_PATH, api)
             .unwrap();
 
-        let solver_api_port = pick_unused_port().expect("no free port");
-        let solver_url: Url = Url::parse(&format!("http://localhost:{solver_api_port}")).unwrap();
-
         let solver_api_handle = spawn({
             let solver_url = solver_url.clone();
             async move {
@@ -126,17 +127,30 @@ mod test {
 
     use committable::Committable;
     use espresso_types::{
+        eth_signature_key::EthKeyPair,
         v0_99::{
-            BidTx, RollupRegistration, RollupRegistrationBody, RollupUpdate, RollupUpdatebody,
+            BidTx, BidTxBody, RollupRegistration, RollupRegistrationBody, RollupUpdate,
+            RollupUpdatebody, SolverAuctionResults,
         },
-        FeeAccount, MarketplaceVersion, SeqTypes,
+        FeeAccount, FeeAmount, MarketplaceVersion, NamespaceId, SeqTypes,
         Update::{Set, Skip},
     };
     use hotshot::types::{BLSPubKey, SignatureKey};
-    use hotshot_types::traits::node_implementation::NodeType;
+    use hotshot_types::{
+        data::ViewNumber,
+        traits::node_implementation::{ConsensusTime, NodeType},
+    };
     use tide_disco::Url;
 
-    use crate::{testing::MockSolver, SolverError};
+    use crate::{
+        auction::{AuctionOutcome, LossReason},
+        state::AuctionResultsRequest,
+        testing::MockSolver,
+        SolverError,
+    };
+
+    /// A view far enough ahead that the mock events service never reaches it during a test.
+    const VIEW: u64 = 1_000_000;
 
     async fn register_rollup_helper(
         namespace_id: u64,
@@ -521,6 +535,225 @@ mod test {
             .unwrap();
     }
 
+    fn bid_helper(view: u64, amount: u64, namespaces: Vec<NamespaceId>) -> BidTx {
+        let key = EthKeyPair::random();
+        BidTxBody::new(
+            key.fee_account(),
+            amount.into(),
+            ViewNumber::new(view),
+            namespaces,
+            Url::from_str("http://localhost:3131").unwrap(),
+            FeeAmount::default(),
+        )
+        .signed(&key)
+        .unwrap()
+    }
+
+    /// Tell the solver that HotShot has started `view`, which closes the auction for it.
+    async fn start_view(mock_solver: &MockSolver, view: u64) {
+        mock_solver
+            .state()
+            .read()
+            .await
+            .start_view(ViewNumber::new(view))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_auction() {
+        let mock_solver = MockSolver::init().await;
+        let solver_api = mock_solver.solver_api();
+
+        let client = surf_disco::Client::<SolverError, MarketplaceVersion>::new(solver_api);
+        client.connect(None).await;
+
+        let (reg, ..) =
+            register_rollup_helper(1, Some("http://localhost"), 200, true, "test").await;
+        let _: RollupRegistration = client
+            .post("register_rollup")
+            .body_json(&reg)
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let ns = reg.body.namespace_id;
+
+        // A bid which is not for any namespace is rejected.
+        client
+            .post::<()>("submit_bid")
+            .body_json(&bid_helper(VIEW, 300, vec![]))
+            .unwrap()
+            .send()
+            .await
+            .unwrap_err();
+
+        let winner = bid_helper(VIEW, 300, vec![ns]);
+        let below_reserve = bid_helper(VIEW, 100, vec![ns]);
+        for bid in [&below_reserve, &winner] {
+            client
+                .post::<()>("submit_bid")
+                .body_json(bid)
+                .unwrap()
+                .send()
+                .await
+                .unwrap();
+        }
+
+        // The auction has not been run yet.
+        client
+            .get::<AuctionOutcome>(&format!("auction_outcome/{VIEW}"))
+            .send()
+            .await
+            .unwrap_err();
+        client
+            .get::<SolverAuctionResults>(&format!("auction_results/{VIEW}"))
+            .send()
+            .await
+            .unwrap_err();
+
+        // The auction closes when HotShot starts the view.
+        start_view(&mock_solver, VIEW).await;
+        let results: SolverAuctionResults = client
+            .get(&format!("auction_results/{VIEW}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(results.winning_bids(), [winner.clone()]);
+        assert!(results.reserve_bids().is_empty());
+
+        // The results are final: they do not change and no more bids are accepted for this view.
+        client
+            .post::<()>("submit_bid")
+            .body_json(&bid_helper(VIEW, 1000, vec![ns]))
+            .unwrap()
+            .send()
+            .await
+            .unwrap_err();
+        let again: SolverAuctionResults = client
+            .get(&format!("auction_results/{VIEW}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(again, results);
+
+        let outcome: AuctionOutcome = client
+            .get(&format!("auction_outcome/{VIEW}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(outcome.results, results);
+        assert_eq!(outcome.losing_bids.len(), 1);
+        assert_eq!(outcome.losing_bids[0].bid, below_reserve);
+        assert_eq!(
+            outcome.losing_bids[0].reason,
+            LossReason::BelowReservePrice {
+                reserve_price: 200u64.into()
+            }
+        );
+
+        // With no bids, the rollup falls back to its reserve builder.
+        start_view(&mock_solver, VIEW + 1).await;
+        let results: SolverAuctionResults = client
+            .get(&format!("auction_results/{}", VIEW + 1))
+            .send()
+            .await
+            .unwrap();
+        assert!(results.winning_bids().is_empty());
+        assert_eq!(
+            results.reserve_bids(),
+            [(ns, Url::from_str("http://localhost").unwrap())]
+        );
+    }
+
+    /// Path of the permissioned auction results for `view`, as requested by the node with `index`
+    /// in the mock stake table, from the solver at `solver`.
+    fn permissioned_path(view: u64, index: u64, solver: &Url) -> String {
+        let (signer, private_key) = BLSPubKey::generated_from_seed_indexed([0; 32], index);
+        let request = AuctionResultsRequest {
+            view_number: ViewNumber::new(view),
+            solver: solver.clone(),
+        };
+        let signature = BLSPubKey::sign(&private_key, request.commit().as_ref()).unwrap();
+        format!("auction_results_permissioned/{view}/{signer}/{signature}")
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_permissioned_auction_results() {
+        let mock_solver = MockSolver::init().await;
+        let solver_api = mock_solver.solver_api();
+        let solver_url = mock_solver.solver_url.clone();
+
+        let client = surf_disco::Client::<SolverError, MarketplaceVersion>::new(solver_api);
+        client.connect(None).await;
+
+        let (reg, ..) =
+            register_rollup_helper(1, Some("http://localhost"), 200, true, "test").await;
+        let _: RollupRegistration = client
+            .post("register_rollup")
+            .body_json(&reg)
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let winner = bid_helper(VIEW, 300, vec![reg.body.namespace_id]);
+        client
+            .post::<()>("submit_bid")
+            .body_json(&winner)
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+
+        // The mock stake table has 10 equally staked nodes, which take turns leading.
+        let leader = VIEW % 10;
+        let other = (VIEW + 1) % 10;
+
+        // Only the leader of the view can close the auction early, and only once HotShot has
+        // started the view.
+        let err = client
+            .get::<SolverAuctionResults>(&permissioned_path(VIEW, leader, &solver_url))
+            .send()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SolverError::AuctionOpen(VIEW)), "{err}");
+        start_view(&mock_solver, VIEW + 1).await;
+        client
+            .get::<SolverAuctionResults>(&permissioned_path(VIEW, other, &solver_url))
+            .send()
+            .await
+            .unwrap_err();
+        client
+            .get::<SolverAuctionResults>(&permissioned_path(
+                VIEW,
+                leader,
+                &Url::from_str("http://other-solver").unwrap(),
+            ))
+            .send()
+            .await
+            .unwrap_err();
+        client
+            .get::<SolverAuctionResults>(&format!("auction_results/{VIEW}"))
+            .send()
+            .await
+            .unwrap_err();
+
+        let results: SolverAuctionResults = client
+            .get(&permissioned_path(VIEW, leader, &solver_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(results.winning_bids(), [winner]);
+
+        // Now that the auction is closed, the results are available to everyone.
+        let public: SolverAuctionResults = client
+            .get(&format!("auction_results/{VIEW}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(public, results);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_database_state() {
         // Initialize a mock solver and register two rollups
@@ -14,13 +14,14 @@ use std::{
 use anyhow::{anyhow, Context, Result};
 use data_source::DataSource;
 use derive_more::derive::Deref;
-use hotshot_types::traits::signature_key::SignatureKey;
-use message::{Message, RequestMessage, ResponseMessage};
+use hotshot_types::traits::{metrics::Metrics, signature_key::SignatureKey};
+use message::{LegacyResponseMessage, Message, ProtocolVersion, RequestMessage, ResponseMessage};
 use network::{Bytes, Receiver, Sender};
 use parking_lot::RwLock;
-use rand::seq::SliceRandom;
 use recipient_source::RecipientSource;
+use reputation::PeerReputation;
 use request::Request;
+use signer::Signer;
 use tokio::{
     spawn,
     time::{sleep, timeout},
@@ -39,8 +40,13 @@ pub mod network;
 /// The recipient source trait. Is what we use to get the recipients that a specific message should
 /// expect responses from
 pub mod recipient_source;
+/// The peer reputation tracker. Is what we use to prefer peers that respond well and to ban
+/// peers that send invalid responses
+pub mod reputation;
 /// The request trait. Is what we use to define a request and a corresponding response type
 pub mod request;
+/// The signer trait. Is what we use to sign our requests and responses
+pub mod signer;
 /// Utility types and functions
 mod util;
 
@@ -112,6 +118,15 @@ pub struct RequestResponseConfig {
     /// We need this because responses coming in need to be validated [asynchronously] that they
     /// satisfy the request they are responding to
     pub max_incoming_responses: usize,
+    /// How long to wait for a peer to respond to a request before counting it against the peer's
+    /// reputation
+    pub peer_response_timeout: Duration,
+    /// How long to stop sending requests to (and accepting responses from) a peer once it is
+    /// banned
+    pub peer_ban_duration: Duration,
+    /// The reliability (between 0 and 1) below which a peer that sends us a response that fails
+    /// validation is banned. See [`reputation::PeerStats::reliability`]
+    pub peer_ban_threshold: f64,
 }
 
 /// A protocol that allows for request-response communication. Is cheaply cloneable, so there is no
@@ -174,10 +189,24 @@ impl<
         // The [response] data source that [`RequestResponseProtocol`] will use to derive the
         // response data for a specific request
         data_source: DS,
+        // Our public key, which we attach to our responses
+        public_key: K,
+        // The signer we sign our responses with
+        signer: Arc<dyn Signer<K>>,
+        // The metrics that [`RequestResponseProtocol`] will export peer reputation through
+        metrics: &dyn Metrics,
     ) -> Self {
         // Create the active requests map
         let active_requests = ActiveRequestsMap::default();
 
+        // Create the peer reputation tracker
+        let reputation = PeerReputation::new(
+            config.peer_response_timeout,
+            config.peer_ban_duration,
+            config.peer_ban_threshold,
+            metrics,
+        );
+
         // Create the inner implementation
         let inner = Arc::new(RequestResponseInner {
             config,
@@ -185,6 +214,10 @@ impl<
             recipient_source,
             data_source,
             active_requests,
+            public_key,
+            signer,
+            reputation,
+            request_version: RwLock::new(ProtocolVersion::V1),
             phantom_data: PhantomData,
         });
 
@@ -232,6 +265,16 @@ pub struct RequestResponseInner<
     data_source: DS,
     /// The map of currently active requests
     active_requests: ActiveRequestsMap<Req>,
+    /// Our public key, which we attach to our responses
+    public_key: K,
+    /// The signer we sign our responses with
+    signer: Arc<dyn Signer<K>>,
+    /// The reputation of the peers we send requests to
+    pub reputation: PeerReputation<K>,
+    /// The wire format version we send requests in. This starts at version 1, which every peer
+    /// understands, and is raised with [`set_request_version`](Self::set_request_version) once
+    /// every peer is known to understand version 2
+    request_version: RwLock<ProtocolVersion>,
     /// Phantom data to help with type inference
     phantom_data: PhantomData<(K, R, Req, DS)>,
 }
@@ -244,6 +287,21 @@ impl<
         K: SignatureKey + 'static,
     > RequestResponseInner<S, R, Req, RS, DS, K>
 {
+    /// Set the wire format version to send requests in from now on.
+    ///
+    /// Responses to version 1 requests are not signed, so they can not be attributed to a peer and
+    /// do not count towards its reputation. Only raise this to version 2 once every peer is known
+    /// to understand it (e.g. after a protocol upgrade), or peers which have not upgraded will not
+    /// be able to answer our requests
+    pub fn set_request_version(&self, version: ProtocolVersion) {
+        *self.request_version.write() = version;
+    }
+
+    /// The wire format version requests are currently sent in
+    pub fn request_version(&self) -> ProtocolVersion {
+        *self.request_version.read()
+    }
+
     /// Request something from the protocol indefinitely until we get a response
     /// or there was a critical error (e.g. the request could not be signed)
     ///
@@ -253,7 +311,7 @@ impl<
     pub async fn request_indefinitely<F, Fut, O>(
         self: &Arc<Self>,
         public_key: &K,
-        private_key: &K::PrivateKey,
+        signer: &(impl Signer<K> + ?Sized),
         // The estimated TTL of other participants. This is used to decide when to
         // stop making requests and sign a new one
         estimated_request_ttl: Duration,
@@ -269,7 +327,8 @@ impl<
     {
         loop {
             // Sign a request message
-            let request_message = RequestMessage::new_signed(public_key, private_key, &request)
+            let request_message = RequestMessage::new_signed_by(public_key, signer, &request)
+                .await
                 .map_err(|e| {
                     RequestError::InvalidRequest(anyhow::anyhow!(
                         "failed to sign request message: {e}"
@@ -302,7 +361,7 @@ impl<
     /// - If the request we sign is invalid
     pub async fn request<F, Fut, O>(
         self: &Arc<Self>,
-        request_message: RequestMessage<Req, K>,
+        mut request_message: RequestMessage<Req, K>,
         timeout_duration: Duration,
         response_validation_fn: F,
     ) -> std::result::Result<O, RequestError>
@@ -311,6 +370,10 @@ impl<
         Fut: Future<Output = anyhow::Result<O>> + Send + Sync + 'static,
         O: Send + Sync + 'static + Clone,
     {
+        // Send the request in the version every peer understands. The version is not covered by
+        // the request signature, so we can set it here
+        request_message.version = self.request_version();
+
         timeout(timeout_duration, async move {
             // Calculate the hash of the request
             let request_hash = blake3::hash(&request_message.request.to_bytes().map_err(|e| {
@@ -361,9 +424,10 @@ impl<
                 }
             };
 
-            // Get the recipients that the request should expect responses from. Shuffle them so
-            // that we don't always send to the same recipients in the same order
-            let mut recipients = self
+            // Get the recipients that the request should expect responses from. Order them by
+            // reputation (with some randomness) so that we ask peers that have responded well
+            // first, but don't always send to the same recipients in the same order
+            let recipients = self
                 .recipient_source
                 .get_expected_responders(&request_message.request)
                 .await
@@ -372,7 +436,11 @@ impl<
                         "failed to get expected responders for request: {e}"
                     ))
                 })?;
-            recipients.shuffle(&mut rand::thread_rng());
+            let recipients = self.reputation.order_recipients(recipients);
+
+            // Only peers answering a version 2 request sign their responses, so only then can we
+            // tell who answered and score them for it
+            let track_reputation = request_message.version == ProtocolVersion::V2;
 
             // Create a request message and serialize it
             let message =
@@ -401,6 +469,14 @@ impl<
                     // Send out requests to the network in their own separate tasks
                     for recipient_batch in recipients.chunks(self_clone.config.request_batch_size) {
                         for recipient in recipient_batch {
+                            // Keep track of who we asked, so we can score their response (or lack
+                            // thereof)
+                            if track_reputation {
+                                self_clone
+                                    .reputation
+                                    .record_request_sent(request_hash, recipient.clone());
+                            }
+
                             // Clone ourselves, the message, and the recipient so they can be moved
                             let self_clone = Arc::clone(&self_clone);
                             let recipient_clone = recipient.clone();
@@ -475,6 +551,9 @@ impl<
                         Message::Response(response_message) => {
                             self.handle_response(response_message, &mut incoming_responses);
                         },
+                        Message::LegacyResponse(response_message) => {
+                            self.handle_legacy_response(response_message, &mut incoming_responses);
+                        },
                     }
                 },
                 // An error here means the receiver will _NEVER_ receive any more messages
@@ -515,14 +594,31 @@ impl<
                     .await
                     .with_context(|| "failed to derive response for request")?;
 
-                // Create the response message and serialize it
+                // Create the response message in the version the request was sent in, and
+                // serialize it
+                let request_hash = blake3::hash(&request_message.request.to_bytes()?);
+                let response = match request_message.version {
+                    ProtocolVersion::V1 => {
+                        Message::LegacyResponse::<Req, K>(LegacyResponseMessage {
+                            request_hash,
+                            response,
+                        })
+                    },
+                    ProtocolVersion::V2 => Message::Response(
+                        ResponseMessage::new_signed_by(
+                            &self_clone.public_key,
+                            self_clone.signer.as_ref(),
+                            request_hash,
+                            response,
+                        )
+                        .await
+                        .with_context(|| "failed to sign response")?,
+                    ),
+                };
                 let response = Bytes::from(
-                    Message::Response::<Req, K>(ResponseMessage {
-                        request_hash: blake3::hash(&request_message.request.to_bytes()?),
-                        response,
-                    })
-                    .to_bytes()
-                    .with_context(|| "failed to serialize response message")?,
+                    response
+                        .to_bytes()
+                        .with_context(|| "failed to serialize response message")?,
                 );
 
                 // Send the response to the requester
@@ -551,9 +647,21 @@ impl<
     /// Handle a response sent to us
     fn handle_response(
         self: &Arc<Self>,
-        response: ResponseMessage<Req>,
+        response: ResponseMessage<Req, K>,
         incoming_responses: &mut IncomingResponses,
     ) {
+        // Ignore responses from banned peers
+        if self.reputation.is_banned(&response.public_key) {
+            return;
+        }
+
+        // Make sure the response was actually sent by the peer it claims to be from before we
+        // hold that peer accountable for it
+        if let Err(e) = response.verify() {
+            debug!("Received response with an invalid signature: {e:#}");
+            return;
+        }
+
         // Get the entry in the map, ignoring it if it doesn't exist
         let Some(active_request) = self
             .active_requests
@@ -562,27 +670,46 @@ impl<
             .cloned()
             .and_then(|r| r.upgrade())
         else {
+            // We are no longer waiting for this response, so we can't validate it. The peer did
+            // respond though, so don't count the request as timed out
+            self.reputation
+                .record_unvalidated_response(response.request_hash, &response.public_key);
             return;
         };
 
         // Spawn a task to validate the response and send it to the requester (us)
         let response_validate_timeout = self.config.response_validate_timeout;
+        let self_clone = Arc::clone(self);
+        let ResponseMessage {
+            public_key,
+            request_hash,
+            response,
+            ..
+        } = response;
         let response_task = AbortOnDropHandle::new(tokio::spawn(async move {
-            if timeout(response_validate_timeout, async move {
+            if timeout(response_validate_timeout, async {
                 // Make sure the response is valid for the given request
                 let validation_result = match (active_request.response_validation_fn)(
                     &active_request.request,
-                    response.response,
+                    response,
                 )
                 .await
                 {
                     Ok(validation_result) => validation_result,
                     Err(e) => {
-                        warn!("Received invalid response: {e:#}");
+                        warn!(%public_key, "Received invalid response: {e:#}");
+                        self_clone
+                            .reputation
+                            .record_invalid_response(request_hash, &public_key);
                         return;
                     },
                 };
 
+                // Update the peer's reputation
+                self_clone
+                    .reputation
+                    .record_valid_response(request_hash, &public_key);
+
                 // Send the response to the requester (the user of [`RequestResponse::request`])
                 let _ = active_request.sender.try_broadcast(validation_result);
             })
@@ -597,6 +724,53 @@ impl<
         // if there are more than [`config.max_incoming_responses`] responses being processed
         incoming_responses.push(response_task);
     }
+
+    /// Handle an unsigned response, sent in reply to a version 1 request. These are validated
+    /// just like signed responses, but we can't tell who sent them, so they do not count towards
+    /// any peer's reputation
+    fn handle_legacy_response(
+        self: &Arc<Self>,
+        response: LegacyResponseMessage<Req>,
+        incoming_responses: &mut IncomingResponses,
+    ) {
+        // Get the entry in the map, ignoring it if it doesn't exist
+        let Some(active_request) = self
+            .active_requests
+            .read()
+            .get(&response.request_hash)
+            .cloned()
+            .and_then(|r| r.upgrade())
+        else {
+            return;
+        };
+
+        // Spawn a task to validate the response and send it to the requester (us)
+        let response_validate_timeout = self.config.response_validate_timeout;
+        let response_task = AbortOnDropHandle::new(tokio::spawn(async move {
+            if timeout(response_validate_timeout, async {
+                // Make sure the response is valid for the given request
+                match (active_request.response_validation_fn)(
+                    &active_request.request,
+                    response.response,
+                )
+                .await
+                {
+                    Ok(validation_result) => {
+                        let _ = active_request.sender.try_broadcast(validation_result);
+                    },
+                    Err(e) => warn!("Received invalid unsigned response: {e:#}"),
+                }
+            })
+            .await
+            .is_err()
+            {
+                warn!("Timed out while validating response");
+            }
+        }));
+
+        // Add the response task to the incoming responses queue
+        incoming_responses.push(response_task);
+    }
 }
 
 /// An active request. This is what we use to track a request and its corresponding response
@@ -637,11 +811,14 @@ mod tests {
     };
 
     use async_trait::async_trait;
-    use hotshot_types::signature_key::{BLSPrivKey, BLSPubKey};
+    use hotshot_types::{
+        signature_key::{BLSPrivKey, BLSPubKey},
+        traits::metrics::NoMetrics,
+    };
     use rand::Rng;
     use tokio::{sync::mpsc, task::JoinSet};
 
-    use super::*;
+    use super::{signer::LocalSigner, *};
 
     /// This test makes sure that when all references to an active request are dropped, it is
     /// removed from the active requests map
@@ -779,6 +956,9 @@ mod tests {
             max_outgoing_responses: 10,
             response_validate_timeout: Duration::from_secs(1),
             max_incoming_responses: 5,
+            peer_response_timeout: Duration::from_secs(10),
+            peer_ban_duration: Duration::from_secs(60),
+            peer_ban_threshold: 0.2,
         }
     }
 
@@ -877,6 +1057,9 @@ mod tests {
                         take_data: false,
                         taken: Arc::new(AtomicBool::new(false)),
                     },
+                    public_key,
+                    Arc::new(LocalSigner::<BLSPubKey>(private_key.clone())),
+                    &NoMetrics,
                 );
 
                 // Add the handle to the handles list so it doesn't get dropped and
@@ -1003,6 +1186,9 @@ mod tests {
                     data_available_time: Instant::now() + Duration::from_secs(2),
                     taken: Arc::new(AtomicBool::new(false)),
                 },
+                public_key,
+                Arc::new(LocalSigner::<BLSPubKey>(private_key.clone())),
+                &NoMetrics,
             );
 
             // Add the participants to the list
@@ -1053,4 +1239,143 @@ mod tests {
                 .expect("failed to request data");
         }
     }
+
+    /// Test that requests in both wire format versions are answered, and that the protocol
+    /// starts out sending version 1 requests, which peers that have not upgraded understand
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_request_versions() {
+        let mut participants = Vec::new();
+        for (sender, receiver, (public_key, private_key)) in create_participants(2) {
+            let protocol = RequestResponse::new(
+                default_protocol_config(),
+                sender.clone(),
+                receiver,
+                sender,
+                TestDataSource {
+                    take_data: false,
+                    has_data: true,
+                    data_available_time: Instant::now(),
+                    taken: Arc::new(AtomicBool::new(false)),
+                },
+                public_key,
+                Arc::new(LocalSigner::<BLSPubKey>(private_key.clone())),
+                &NoMetrics,
+            );
+            participants.push((protocol, public_key, private_key));
+        }
+        let (protocol, public_key, private_key) = &participants[0];
+        assert_eq!(protocol.request_version(), ProtocolVersion::V1);
+
+        for version in [ProtocolVersion::V1, ProtocolVersion::V2] {
+            protocol.set_request_version(version);
+            let request = TestRequest(vec![rand::thread_rng().gen(); 100]);
+            let expected = blake3::hash(&request.0).as_bytes().to_vec();
+            let request_message =
+                RequestMessage::new_signed(public_key, private_key, &request).unwrap();
+            let response = protocol
+                .request(
+                    request_message,
+                    Duration::from_secs(10),
+                    |_request, response| async move { Ok(response) },
+                )
+                .await
+                .unwrap();
+            assert_eq!(response, expected);
+        }
+    }
+
+    /// Test that a response is only held against the peer that actually signed it, so that an
+    /// attacker can neither frame an honest peer nor avoid a ban by claiming to be someone else
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_responses_are_attributed_by_signature() {
+        // Create a single participant which will make a request
+        let (sender, receiver, (public_key, private_key)) = create_participants(1).pop().unwrap();
+        let protocol = RequestResponse::new(
+            default_protocol_config(),
+            sender.clone(),
+            receiver,
+            sender.clone(),
+            TestDataSource {
+                take_data: false,
+                has_data: false,
+                data_available_time: Instant::now(),
+                taken: Arc::new(AtomicBool::new(false)),
+            },
+            public_key,
+            Arc::new(LocalSigner::<BLSPubKey>(private_key.clone())),
+            &NoMetrics,
+        );
+
+        // Start a request which no response will satisfy, so every response is validated
+        let request = TestRequest(vec![rand::thread_rng().gen(); 100]);
+        let request_hash = blake3::hash(&request.to_bytes().unwrap());
+        let request_message = RequestMessage::new_signed(&public_key, &private_key, &request)
+            .expect("failed to sign request");
+        let protocol_clone = protocol.clone();
+        let _request_task = AbortOnDropHandle::new(tokio::spawn(async move {
+            protocol_clone
+                .request(
+                    request_message,
+                    Duration::from_secs(20),
+                    |_request, _response| async move {
+                        Err::<(), _>(anyhow::anyhow!("invalid response"))
+                    },
+                )
+                .await
+        }));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // An attacker and an honest peer that the attacker tries to frame
+        let (attacker, attacker_private_key) = BLSPubKey::generated_from_seed_indexed([3; 32], 0);
+        let (honest, _) = BLSPubKey::generated_from_seed_indexed([3; 32], 1);
+
+        // Send an invalid response claiming to be from the honest peer, signed by the attacker
+        let mut forged = ResponseMessage::<TestRequest, BLSPubKey>::new_signed(
+            &attacker,
+            &attacker_private_key,
+            request_hash,
+            vec![1, 2, 3],
+        )
+        .unwrap();
+        forged.public_key = honest;
+        sender
+            .send_message(
+                &Bytes::from(Message::Response(forged).to_bytes().unwrap()),
+                public_key,
+            )
+            .await
+            .unwrap();
+
+        // Send invalid responses properly signed by the attacker, until it is banned
+        for _ in 0..2 {
+            let signed = ResponseMessage::<TestRequest, BLSPubKey>::new_signed(
+                &attacker,
+                &attacker_private_key,
+                request_hash,
+                vec![1, 2, 3],
+            )
+            .unwrap();
+            sender
+                .send_message(
+                    &Bytes::from(Message::Response(signed).to_bytes().unwrap()),
+                    public_key,
+                )
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(500)).await;
+        }
+
+        // Only the attacker is held responsible
+        assert!(protocol.reputation.peer_stats(&honest).is_none());
+        assert!(!protocol.reputation.is_banned(&honest));
+        assert!(protocol.reputation.is_banned(&attacker));
+        assert_eq!(
+            protocol
+                .reputation
+                .peer_stats(&attacker)
+                .unwrap()
+                .invalid_responses,
+            2
+        );
+    }
 }
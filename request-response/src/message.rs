@@ -7,7 +7,31 @@ use anyhow::{Context, Result};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use hotshot_types::traits::signature_key::SignatureKey;
 
-use super::{request::Request, RequestHash, Serializable};
+use super::{request::Request, signer::Signer, RequestHash, Serializable};
+
+/// The version of the wire format a message was sent in.
+///
+/// Version 1 responses are not signed. Version 2 responses are signed by the responder, so the
+/// requester can hold peers accountable for what they send. We answer each request in the version
+/// it was sent in, so nodes which still speak version 1 can keep making requests to us, and only
+/// send version 2 requests once every peer understands them (see
+/// [`RequestResponseInner::set_request_version`](crate::RequestResponseInner::set_request_version))
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    /// Unsigned responses
+    V1,
+    /// Signed responses
+    V2,
+}
+
+/// The type byte of a version 1 request
+const V1_REQUEST: u8 = 0;
+/// The type byte of a version 1 (unsigned) response
+const V1_RESPONSE: u8 = 1;
+/// The type byte of a version 2 request
+const V2_REQUEST: u8 = 2;
+/// The type byte of a version 2 (signed) response
+const V2_RESPONSE: u8 = 3;
 
 /// The outer message type for the request-response protocol. Can either be a request or a response
 #[derive(Clone, Debug)]
@@ -15,8 +39,10 @@ use super::{request::Request, RequestHash, Serializable};
 pub enum Message<R: Request, K: SignatureKey> {
     /// A request
     Request(RequestMessage<R, K>),
-    /// A response
-    Response(ResponseMessage<R>),
+    /// A signed response
+    Response(ResponseMessage<R, K>),
+    /// An unsigned response, in reply to a version 1 request
+    LegacyResponse(LegacyResponseMessage<R>),
 }
 
 /// A request message, which includes the requester's public key, the request's signature, a timestamp, and the request itself
@@ -32,12 +58,21 @@ pub struct RequestMessage<R: Request, K: SignatureKey> {
     pub timestamp_unix_seconds: u64,
     /// The actual request data. This is from the application
     pub request: R,
+    /// The wire format version the request was sent in, which is also the version the requester
+    /// expects the response in. This is not signed; it is determined by the message type
+    pub version: ProtocolVersion,
 }
 
-/// A response message, which includes the hash of the request we're responding to and the response itself.
+/// A response message, which includes the responder's public key and signature, the hash of the
+/// request we're responding to and the response itself.
 #[derive(Clone, Debug)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
-pub struct ResponseMessage<R: Request> {
+pub struct ResponseMessage<R: Request, K: SignatureKey> {
+    /// The responder's public key. We use this to keep track of how well peers respond to our
+    /// requests
+    pub public_key: K,
+    /// The responder's signature over [the request hash + the actual response content]
+    pub signature: K::PureAssembledSignatureType,
     /// The hash of the application-specific request we're responding to. The hash is a free way
     /// to identify the request and weed out any potential incompatibilities
     pub request_hash: RequestHash,
@@ -45,6 +80,17 @@ pub struct ResponseMessage<R: Request> {
     pub response: R::Response,
 }
 
+/// A version 1 response message, which includes the hash of the request we're responding to and
+/// the response itself. It is not signed, so it can not be attributed to a peer
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(PartialEq, Eq))]
+pub struct LegacyResponseMessage<R: Request> {
+    /// The hash of the application-specific request we're responding to
+    pub request_hash: RequestHash,
+    /// The actual response content
+    pub response: R::Response,
+}
+
 impl<R: Request, K: SignatureKey> RequestMessage<R, K> {
     /// Create a new signed request message from a request
     ///
@@ -59,13 +105,56 @@ impl<R: Request, K: SignatureKey> RequestMessage<R, K> {
         <K as SignatureKey>::SignError: 'static,
     {
         // Get the current timestamp
-        let timestamp_unix_seconds = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("time went backwards")
-            .as_secs();
+        let timestamp_unix_seconds = unix_timestamp();
 
-        // Concatenate the content and timestamp
-        let content_to_sign = [
+        // Sign the actual request content (+ a namespace) with the private key
+        let signature = K::sign(
+            private_key,
+            &Self::content_to_sign(request, timestamp_unix_seconds)?,
+        )
+        .with_context(|| "failed to sign message")?;
+
+        Ok(Self::with_signature(
+            public_key,
+            signature,
+            timestamp_unix_seconds,
+            request,
+        ))
+    }
+
+    /// Create a new request message from a request, signed by `signer`
+    ///
+    /// # Errors
+    /// - If the request's content cannot be serialized
+    /// - If the request cannot be signed
+    ///
+    /// # Panics
+    /// - If time is not monotonic
+    pub async fn new_signed_by(
+        public_key: &K,
+        signer: &(impl Signer<K> + ?Sized),
+        request: &R,
+    ) -> Result<Self> {
+        // Get the current timestamp
+        let timestamp_unix_seconds = unix_timestamp();
+
+        // Sign the actual request content (+ a namespace)
+        let signature = signer
+            .sign(&Self::content_to_sign(request, timestamp_unix_seconds)?)
+            .await
+            .with_context(|| "failed to sign message")?;
+
+        Ok(Self::with_signature(
+            public_key,
+            signature,
+            timestamp_unix_seconds,
+            request,
+        ))
+    }
+
+    /// The content the requester signs: the request, the timestamp and a namespace
+    fn content_to_sign(request: &R, timestamp_unix_seconds: u64) -> Result<Vec<u8>> {
+        Ok([
             request
                 .to_bytes()
                 .with_context(|| "failed to serialize request content")?
@@ -73,19 +162,23 @@ impl<R: Request, K: SignatureKey> RequestMessage<R, K> {
             timestamp_unix_seconds.to_le_bytes().as_slice(),
             b"espresso-request-response",
         ]
-        .concat();
-
-        // Sign the actual request content (+ a namespace) with the private key
-        let signature =
-            K::sign(private_key, &content_to_sign).with_context(|| "failed to sign message")?;
+        .concat())
+    }
 
-        // Return the newly signed request message
-        Ok(RequestMessage {
+    /// Assemble a signed request message
+    fn with_signature(
+        public_key: &K,
+        signature: K::PureAssembledSignatureType,
+        timestamp_unix_seconds: u64,
+        request: &R,
+    ) -> Self {
+        RequestMessage {
             public_key: public_key.clone(),
             signature,
             timestamp_unix_seconds,
             request: request.clone(),
-        })
+            version: ProtocolVersion::V2,
+        }
     }
 
     /// Validate the [`RequestMessage`], checking the signature and the timestamp and
@@ -102,22 +195,14 @@ impl<R: Request, K: SignatureKey> RequestMessage<R, K> {
         if self
             .timestamp_unix_seconds
             .saturating_add(incoming_request_ttl.as_secs())
-            < SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("time went backwards")
-                .as_secs()
+            < unix_timestamp()
         {
             return Err(anyhow::anyhow!("request is too old"));
         }
         // Check the signature over the request content and timestamp
         if !self.public_key.validate(
             &self.signature,
-            &[
-                self.request.to_bytes()?,
-                self.timestamp_unix_seconds.to_le_bytes().to_vec(),
-                b"espresso-request-response".to_vec(),
-            ]
-            .concat(),
+            &Self::content_to_sign(&self.request, self.timestamp_unix_seconds)?,
         ) {
             return Err(anyhow::anyhow!("invalid request signature"));
         }
@@ -127,6 +212,88 @@ impl<R: Request, K: SignatureKey> RequestMessage<R, K> {
     }
 }
 
+impl<R: Request, K: SignatureKey> ResponseMessage<R, K> {
+    /// Create a new signed response message
+    ///
+    /// # Errors
+    /// - If the response's content cannot be serialized
+    /// - If the response cannot be signed
+    pub fn new_signed(
+        public_key: &K,
+        private_key: &K::PrivateKey,
+        request_hash: RequestHash,
+        response: R::Response,
+    ) -> Result<Self>
+    where
+        <K as SignatureKey>::SignError: 'static,
+    {
+        // Sign the request hash and the response content (+ a namespace) with the private key
+        let content_to_sign = Self::content_to_sign(&request_hash, &response)?;
+        let signature =
+            K::sign(private_key, &content_to_sign).with_context(|| "failed to sign response")?;
+
+        Ok(ResponseMessage {
+            public_key: public_key.clone(),
+            signature,
+            request_hash,
+            response,
+        })
+    }
+
+    /// Create a new response message, signed by `signer`
+    ///
+    /// # Errors
+    /// - If the response's content cannot be serialized
+    /// - If the response cannot be signed
+    pub async fn new_signed_by(
+        public_key: &K,
+        signer: &(impl Signer<K> + ?Sized),
+        request_hash: RequestHash,
+        response: R::Response,
+    ) -> Result<Self> {
+        // Sign the request hash and the response content (+ a namespace)
+        let signature = signer
+            .sign(&Self::content_to_sign(&request_hash, &response)?)
+            .await
+            .with_context(|| "failed to sign response")?;
+
+        Ok(ResponseMessage {
+            public_key: public_key.clone(),
+            signature,
+            request_hash,
+            response,
+        })
+    }
+
+    /// Check the responder's signature over the response
+    ///
+    /// # Errors
+    /// - If the signature is invalid
+    /// - If the response's content cannot be serialized
+    pub fn verify(&self) -> Result<()> {
+        if !self.public_key.validate(
+            &self.signature,
+            &Self::content_to_sign(&self.request_hash, &self.response)?,
+        ) {
+            return Err(anyhow::anyhow!("invalid response signature"));
+        }
+        Ok(())
+    }
+
+    /// The bytes a response signature is over
+    fn content_to_sign(request_hash: &RequestHash, response: &R::Response) -> Result<Vec<u8>> {
+        Ok([
+            request_hash.as_bytes().as_slice(),
+            response
+                .to_bytes()
+                .with_context(|| "failed to serialize response content")?
+                .as_slice(),
+            b"espresso-request-response-response",
+        ]
+        .concat())
+    }
+}
+
 /// A blanket implementation of the [`Serializable`] trait for any [`Message`]
 impl<R: Request, K: SignatureKey> Serializable for Message<R, K> {
     /// Converts any [`Message`] to bytes if the content is also [`Serializable`]
@@ -134,18 +301,29 @@ impl<R: Request, K: SignatureKey> Serializable for Message<R, K> {
         // Create a buffer for the bytes
         let mut bytes = Vec::new();
 
-        // Convert the message to bytes based on the type. By default it is just type-prefixed
+        // Convert the message to bytes based on the type. By default it is just type-prefixed. The
+        // type also determines the version of the wire format
         match self {
             Message::Request(request_message) => {
                 // Write the type (request)
-                bytes.push(0);
+                bytes.push(match request_message.version {
+                    ProtocolVersion::V1 => V1_REQUEST,
+                    ProtocolVersion::V2 => V2_REQUEST,
+                });
 
                 // Write the request content
                 bytes.extend_from_slice(request_message.to_bytes()?.as_slice());
             },
             Message::Response(response_message) => {
-                // Write the type (response)
-                bytes.push(1);
+                // Write the type (signed response)
+                bytes.push(V2_RESPONSE);
+
+                // Write the response content
+                bytes.extend_from_slice(response_message.to_bytes()?.as_slice());
+            },
+            Message::LegacyResponse(response_message) => {
+                // Write the type (unsigned response)
+                bytes.push(V1_RESPONSE);
 
                 // Write the response content
                 bytes.extend_from_slice(response_message.to_bytes()?.as_slice());
@@ -165,13 +343,23 @@ impl<R: Request, K: SignatureKey> Serializable for Message<R, K> {
 
         // Deserialize the message based on the type
         match type_byte {
-            0 => {
+            V1_REQUEST | V2_REQUEST => {
                 // Read the `RequestMessage`
-                Ok(Message::Request(RequestMessage::from_bytes(&read_to_end(
-                    &mut bytes,
-                )?)?))
+                let mut request_message = RequestMessage::from_bytes(&read_to_end(&mut bytes)?)?;
+                request_message.version = if type_byte == V1_REQUEST {
+                    ProtocolVersion::V1
+                } else {
+                    ProtocolVersion::V2
+                };
+                Ok(Message::Request(request_message))
             },
-            1 => {
+            V1_RESPONSE => {
+                // Read the `LegacyResponseMessage`
+                Ok(Message::LegacyResponse(LegacyResponseMessage::from_bytes(
+                    &read_to_end(&mut bytes)?,
+                )?))
+            },
+            V2_RESPONSE => {
                 // Read the `ResponseMessage`
                 Ok(Message::Response(ResponseMessage::from_bytes(
                     &read_to_end(&mut bytes)?,
@@ -223,15 +411,23 @@ impl<R: Request, K: SignatureKey> Serializable for RequestMessage<R, K> {
             signature,
             timestamp_unix_seconds: timestamp,
             request,
+            // Set by the outer [`Message`] from the message type
+            version: ProtocolVersion::V2,
         })
     }
 }
 
-impl<R: Request> Serializable for ResponseMessage<R> {
+impl<R: Request, K: SignatureKey> Serializable for ResponseMessage<R, K> {
     fn to_bytes(&self) -> Result<Vec<u8>> {
         // Create a buffer for the bytes
         let mut bytes = Vec::new();
 
+        // Write the public key (length-prefixed)
+        write_length_prefixed(&mut bytes, &self.public_key.to_bytes())?;
+
+        // Write the signature (length-prefixed)
+        write_length_prefixed(&mut bytes, &bincode::serialize(&self.signature)?)?;
+
         // Write the request hash as bytes
         bytes.write_all(self.request_hash.as_bytes())?;
 
@@ -245,6 +441,12 @@ impl<R: Request> Serializable for ResponseMessage<R> {
         // Create a buffer for the bytes
         let mut bytes = Cursor::new(bytes);
 
+        // Read the public key (length-prefixed)
+        let public_key = K::from_bytes(&read_length_prefixed(&mut bytes)?)?;
+
+        // Read the signature (length-prefixed)
+        let signature = bincode::deserialize(&read_length_prefixed(&mut bytes)?)?;
+
         // Read the request hash as a [`blake3::Hash`]
         let mut request_hash_bytes = [0; 32];
         bytes.read_exact(&mut request_hash_bytes)?;
@@ -254,12 +456,58 @@ impl<R: Request> Serializable for ResponseMessage<R> {
         let response = R::Response::from_bytes(&read_to_end(&mut bytes)?)?;
 
         Ok(Self {
+            public_key,
+            signature,
             request_hash,
             response,
         })
     }
 }
 
+impl<R: Request> Serializable for LegacyResponseMessage<R> {
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        // Create a buffer for the bytes
+        let mut bytes = Vec::new();
+
+        // Write the request hash as bytes
+        bytes.write_all(self.request_hash.as_bytes())?;
+
+        // Write the response content
+        bytes.write_all(self.response.to_bytes()?.as_slice())?;
+
+        Ok(bytes)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        // Create a buffer for the bytes
+        let mut bytes = Cursor::new(bytes);
+
+        // Read the request hash as a [`blake3::Hash`]
+        let mut request_hash_bytes = [0; 32];
+        bytes.read_exact(&mut request_hash_bytes)?;
+        let request_hash = RequestHash::from(request_hash_bytes);
+
+        // Read the response content to the end
+        let response = R::Response::from_bytes(&read_to_end(&mut bytes)?)?;
+
+        Ok(Self {
+            request_hash,
+            response,
+        })
+    }
+}
+
+/// A helper function to get the current unix timestamp in seconds
+///
+/// # Panics
+/// - If time is not monotonic
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_secs()
+}
+
 /// A helper function to write a length-prefixed value to a writer
 fn write_length_prefixed<W: Write>(writer: &mut W, value: &[u8]) -> Result<()> {
     // Write the length of the value as a u32
@@ -379,28 +627,49 @@ mod tests {
             let mut rng = rand::thread_rng();
 
             // Generate a random message type
-            let is_request = rng.gen::<u8>() % 2 == 0;
+            let message_type = rng.gen_range(0..4);
 
             // The request content will be a random vector of bytes
             let request = vec![rng.gen::<u8>(); rng.gen_range(0..10000)];
 
+            // Create a random keypair
+            let (public_key, private_key) =
+                BLSPubKey::generated_from_seed_indexed([1; 32], rng.gen::<u64>());
+
             // Create a message
-            let message = if is_request {
-                // Create a random keypair
-                let (public_key, private_key) =
-                    BLSPubKey::generated_from_seed_indexed([1; 32], rng.gen::<u64>());
-
-                // Create a new signed request
-                let request = RequestMessage::new_signed(&public_key, &private_key, &request)
-                    .expect("Failed to create signed request");
-
-                Message::Request(request)
-            } else {
-                // Create a response message
-                Message::Response(ResponseMessage {
-                    request_hash: blake3::hash(&request),
-                    response: vec![rng.gen::<u8>(); rng.gen_range(0..10000)],
-                })
+            let response = vec![rng.gen::<u8>(); rng.gen_range(0..10000)];
+            let message = match message_type {
+                0 | 1 => {
+                    // Create a new signed request, in either version
+                    let mut request =
+                        RequestMessage::new_signed(&public_key, &private_key, &request)
+                            .expect("Failed to create signed request");
+                    if message_type == 0 {
+                        request.version = ProtocolVersion::V1;
+                    }
+
+                    Message::Request(request)
+                },
+                2 => {
+                    // Create a signed response message
+                    Message::Response(
+                        ResponseMessage::new_signed(
+                            &public_key,
+                            &private_key,
+                            blake3::hash(&request),
+                            response,
+                        )
+                        .expect("Failed to create signed response"),
+                    )
+                },
+                3 => {
+                    // Create an unsigned response message
+                    Message::LegacyResponse(LegacyResponseMessage {
+                        request_hash: blake3::hash(&request),
+                        response,
+                    })
+                },
+                _ => unreachable!(),
             };
 
             // Serialize the message
@@ -415,6 +684,86 @@ mod tests {
         }
     }
 
+    /// Tests that properly signed responses are verified correctly and that responses with an
+    /// altered response, request hash or public key are rejected
+    #[test]
+    fn test_response_verification() {
+        // Create some RNG
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            // Create two random keypairs
+            let (public_key, private_key) =
+                BLSPubKey::generated_from_seed_indexed([1; 32], rng.gen::<u64>());
+            let (other_public_key, _) =
+                BLSPubKey::generated_from_seed_indexed([2; 32], rng.gen::<u64>());
+
+            // Create a valid response with some random content
+            let mut response = ResponseMessage::<Vec<u8>, BLSPubKey>::new_signed(
+                &public_key,
+                &private_key,
+                blake3::hash(&[rng.gen::<u8>(); 32]),
+                vec![rng.gen::<u8>(); rng.gen_range(1..10000)],
+            )
+            .expect("Failed to create signed response");
+
+            let should_be_valid = match rng.gen_range(0..4) {
+                0 => true,
+                1 => {
+                    // Alter the response's actual content
+                    response.response[0] = !response.response[0];
+                    false
+                },
+                2 => {
+                    // Claim the response is for a different request
+                    response.request_hash = blake3::hash(&response.response);
+                    false
+                },
+                3 => {
+                    // Claim the response is from a different peer
+                    response.public_key = other_public_key;
+                    false
+                },
+                _ => unreachable!(),
+            };
+
+            assert_eq!(response.verify().is_ok(), should_be_valid);
+        }
+    }
+
+    /// Tests that messages in the version 1 wire format (as sent by nodes which do not sign
+    /// responses) are still understood
+    #[test]
+    fn test_v1_compatibility() {
+        let (public_key, private_key) = BLSPubKey::generated_from_seed_indexed([1; 32], 0);
+        let request = RequestMessage::<Vec<u8>, BLSPubKey>::new_signed(
+            &public_key,
+            &private_key,
+            &vec![1, 2, 3],
+        )
+        .unwrap();
+
+        // A version 1 request is the type byte 0 followed by the request
+        let bytes = [vec![0], request.to_bytes().unwrap()].concat();
+        let Message::Request(decoded) = Message::<Vec<u8>, BLSPubKey>::from_bytes(&bytes).unwrap()
+        else {
+            panic!("expected a request");
+        };
+        assert_eq!(decoded.version, ProtocolVersion::V1);
+        assert_eq!(decoded.request, request.request);
+
+        // A version 1 response is the type byte 1 followed by the request hash and the response
+        let request_hash = blake3::hash(&request.request);
+        let bytes = [vec![1], request_hash.as_bytes().to_vec(), vec![4, 5, 6]].concat();
+        assert_eq!(
+            Message::<Vec<u8>, BLSPubKey>::from_bytes(&bytes).unwrap(),
+            Message::LegacyResponse(LegacyResponseMessage {
+                request_hash,
+                response: vec![4, 5, 6],
+            })
+        );
+    }
+
     /// Tests that length-prefixed values are read and written correctly
     #[test]
     fn test_length_prefix_parity() {
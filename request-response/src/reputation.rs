@@ -0,0 +1,409 @@
+//! This file contains the [`PeerReputation`] type. It keeps track of how well each peer has
+//! responded to our requests, so that we can prefer peers that respond quickly and reliably and
+//! temporarily stop asking peers that keep sending us invalid data.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use hotshot_types::traits::{
+    metrics::{Counter, Gauge, Histogram, Metrics},
+    signature_key::SignatureKey,
+};
+use parking_lot::RwLock;
+use rand::Rng;
+
+use super::RequestHash;
+
+/// The weight of the newest sample in the moving average of a peer's response latency
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// How much of a peer's past outcomes we keep each time we record a new one. Old outcomes decay
+/// geometrically, so the reliability of a peer mostly reflects its last few dozen outcomes and a
+/// peer can recover from occasional failures
+const OUTCOME_DECAY: f64 = 0.95;
+
+/// How many failures an invalid response counts as. Sending invalid data is worse than not
+/// responding in time
+const INVALID_RESPONSE_WEIGHT: f64 = 2.0;
+
+/// The statistics we keep about a single peer
+#[derive(Clone, Debug, Default)]
+pub struct PeerStats {
+    /// The number of valid responses the peer has sent us
+    pub valid_responses: u64,
+    /// The number of responses from the peer that failed validation
+    pub invalid_responses: u64,
+    /// The number of requests the peer did not respond to in time
+    pub timed_out_requests: u64,
+    /// The number of recent successes, decayed by [`OUTCOME_DECAY`] for every outcome recorded
+    /// after them
+    pub recent_successes: f64,
+    /// The (weighted) number of recent failures, decayed like `recent_successes`
+    pub recent_failures: f64,
+    /// An exponential moving average of the time it took the peer to respond to our requests
+    pub average_latency: Option<Duration>,
+    /// If the peer is banned, the time at which the ban expires
+    pub banned_until: Option<Instant>,
+}
+
+impl PeerStats {
+    /// The reliability of the peer, between 0 and 1. Higher is better.
+    ///
+    /// This is the peer's recent success rate, assuming one success and one failure up front so
+    /// that peers we know nothing about start at 0.5.
+    #[must_use]
+    pub fn reliability(&self) -> f64 {
+        let successes = self.recent_successes + 1.0;
+        let failures = self.recent_failures + 1.0;
+        successes / (successes + failures)
+    }
+
+    /// The score of the peer, between 0 and 1. Higher is better.
+    ///
+    /// The score is the peer's [reliability](Self::reliability) divided by one plus its average
+    /// latency in seconds.
+    #[must_use]
+    pub fn score(&self) -> f64 {
+        let latency = self
+            .average_latency
+            .map_or(0.0, |latency| latency.as_secs_f64());
+        self.reliability() / (1.0 + latency)
+    }
+
+    /// Whether the peer is banned at the given time
+    #[must_use]
+    pub fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+
+    /// Record an outcome, decaying the older ones
+    fn add_outcome(&mut self, successes: f64, failures: f64) {
+        self.recent_successes = self.recent_successes * OUTCOME_DECAY + successes;
+        self.recent_failures = self.recent_failures * OUTCOME_DECAY + failures;
+    }
+
+    /// Add a latency sample to the moving average
+    fn add_latency(&mut self, latency: Duration) {
+        self.average_latency = Some(match self.average_latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SMOOTHING) + latency.mul_f64(LATENCY_SMOOTHING)
+            },
+            None => latency,
+        });
+    }
+}
+
+/// The metrics we export about peer reputation
+struct ReputationMetrics {
+    /// The number of valid responses received
+    valid_responses: Box<dyn Counter>,
+    /// The number of responses received that failed validation
+    invalid_responses: Box<dyn Counter>,
+    /// The number of requests to individual peers that were not answered in time
+    timed_out_requests: Box<dyn Counter>,
+    /// The number of peers that are currently banned
+    banned_peers: Box<dyn Gauge>,
+    /// The time it took peers to send a valid response, in seconds
+    response_latency: Box<dyn Histogram>,
+}
+
+impl ReputationMetrics {
+    /// Create the metrics in the `request_response` subgroup of `metrics`
+    fn new(metrics: &dyn Metrics) -> Self {
+        let metrics = metrics.subgroup("request_response".into());
+        Self {
+            valid_responses: metrics.create_counter("valid_responses".into(), None),
+            invalid_responses: metrics.create_counter("invalid_responses".into(), None),
+            timed_out_requests: metrics.create_counter("timed_out_requests".into(), None),
+            banned_peers: metrics.create_gauge("banned_peers".into(), None),
+            response_latency: metrics
+                .create_histogram("response_latency".into(), Some("seconds".into())),
+        }
+    }
+}
+
+/// Keeps track of the reputation of the peers we send requests to
+pub struct PeerReputation<K: SignatureKey> {
+    /// The statistics for each peer we have interacted with
+    peers: RwLock<HashMap<K, PeerStats>>,
+    /// The requests we have sent to peers and not received a response to yet, with the time we
+    /// first sent them
+    outstanding: RwLock<HashMap<(RequestHash, K), Instant>>,
+    /// How long to wait for a response before counting a request as timed out
+    response_timeout: Duration,
+    /// How long to ban a peer for once its reliability drops below `ban_threshold`
+    ban_duration: Duration,
+    /// The reliability below which a peer sending us an invalid response is banned
+    ban_threshold: f64,
+    /// The metrics we export
+    metrics: ReputationMetrics,
+}
+
+impl<K: SignatureKey> PeerReputation<K> {
+    /// Create a new, empty reputation tracker
+    pub fn new(
+        response_timeout: Duration,
+        ban_duration: Duration,
+        ban_threshold: f64,
+        metrics: &dyn Metrics,
+    ) -> Self {
+        Self {
+            peers: RwLock::default(),
+            outstanding: RwLock::default(),
+            response_timeout,
+            ban_duration,
+            ban_threshold,
+            metrics: ReputationMetrics::new(metrics),
+        }
+    }
+
+    /// Get the statistics we have for a peer, if any
+    pub fn peer_stats(&self, peer: &K) -> Option<PeerStats> {
+        self.peers.read().get(peer).cloned()
+    }
+
+    /// Whether a peer is currently banned
+    pub fn is_banned(&self, peer: &K) -> bool {
+        self.peers
+            .read()
+            .get(peer)
+            .is_some_and(|stats| stats.is_banned(Instant::now()))
+    }
+
+    /// Order the recipients of a request so that well-scored peers tend to be asked first,
+    /// removing any that are banned.
+    ///
+    /// The order is randomized with each peer's score as its weight, so that peers with a poor
+    /// score are still asked first occasionally and have a chance to improve it.
+    pub fn order_recipients(&self, recipients: Vec<K>) -> Vec<K> {
+        // Count requests that were never answered as timed out
+        self.expire_outstanding();
+
+        let now = Instant::now();
+        let peers = self.peers.read();
+        let mut rng = rand::thread_rng();
+        let mut weighted = recipients
+            .into_iter()
+            .filter_map(|recipient| {
+                let score = match peers.get(&recipient) {
+                    Some(stats) if stats.is_banned(now) => return None,
+                    Some(stats) => stats.score(),
+                    None => PeerStats::default().score(),
+                };
+
+                // Weighted random sampling: sorting by `u^(1/w)` for uniform random `u` orders
+                // the peers as if we repeatedly picked one with probability proportional to `w`
+                let key = rng.gen::<f64>().powf(1.0 / score.max(f64::MIN_POSITIVE));
+                Some((key, recipient))
+            })
+            .collect::<Vec<_>>();
+        weighted.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        weighted
+            .into_iter()
+            .map(|(_, recipient)| recipient)
+            .collect()
+    }
+
+    /// Record that we sent a request to a peer. Only the first time we send a particular request
+    /// to a peer counts
+    pub fn record_request_sent(&self, request_hash: RequestHash, peer: K) {
+        self.outstanding
+            .write()
+            .entry((request_hash, peer))
+            .or_insert_with(Instant::now);
+    }
+
+    /// Record a response from a peer that passed validation
+    pub fn record_valid_response(&self, request_hash: RequestHash, peer: &K) {
+        let sent = self
+            .outstanding
+            .write()
+            .remove(&(request_hash, peer.clone()));
+
+        let mut peers = self.peers.write();
+        let stats = peers.entry(peer.clone()).or_default();
+        stats.valid_responses += 1;
+        stats.add_outcome(1.0, 0.0);
+        self.metrics.valid_responses.add(1);
+
+        // We can only measure the latency if we actually asked the peer
+        if let Some(sent) = sent {
+            let latency = sent.elapsed();
+            stats.add_latency(latency);
+            self.metrics
+                .response_latency
+                .add_point(latency.as_secs_f64());
+        }
+    }
+
+    /// Record a response from a peer that failed validation, banning the peer if this brings its
+    /// reliability below the ban threshold
+    pub fn record_invalid_response(&self, request_hash: RequestHash, peer: &K) {
+        self.outstanding
+            .write()
+            .remove(&(request_hash, peer.clone()));
+
+        let now = Instant::now();
+        let mut peers = self.peers.write();
+        let stats = peers.entry(peer.clone()).or_default();
+        stats.invalid_responses += 1;
+        stats.add_outcome(0.0, INVALID_RESPONSE_WEIGHT);
+        if stats.reliability() < self.ban_threshold {
+            stats.banned_until = Some(now + self.ban_duration);
+        }
+        self.metrics.invalid_responses.add(1);
+
+        self.metrics
+            .banned_peers
+            .set(peers.values().filter(|stats| stats.is_banned(now)).count());
+    }
+
+    /// Record a response from a peer that we could not validate, because we already got what we
+    /// needed from someone else. It does not count for or against the peer, but it did respond.
+    pub fn record_unvalidated_response(&self, request_hash: RequestHash, peer: &K) {
+        self.outstanding
+            .write()
+            .remove(&(request_hash, peer.clone()));
+    }
+
+    /// Count the outstanding requests that have not been answered within the response timeout
+    /// as timed out, and stop tracking them
+    fn expire_outstanding(&self) {
+        let now = Instant::now();
+        let mut timed_out = Vec::new();
+        self.outstanding.write().retain(|(_, peer), sent| {
+            if now.duration_since(*sent) < self.response_timeout {
+                true
+            } else {
+                timed_out.push(peer.clone());
+                false
+            }
+        });
+
+        let mut peers = self.peers.write();
+        for peer in &timed_out {
+            let stats = peers.entry(peer.clone()).or_default();
+            stats.timed_out_requests += 1;
+            stats.add_outcome(0.0, 1.0);
+        }
+        self.metrics.timed_out_requests.add(timed_out.len());
+
+        // Bans may have expired in the meantime
+        self.metrics
+            .banned_peers
+            .set(peers.values().filter(|stats| stats.is_banned(now)).count());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hotshot_types::{signature_key::BLSPubKey, traits::metrics::NoMetrics};
+
+    use super::*;
+
+    /// Create a reputation tracker and some peers to track
+    fn setup(num_peers: u64) -> (PeerReputation<BLSPubKey>, Vec<BLSPubKey>) {
+        let reputation = PeerReputation::new(
+            Duration::from_millis(50),
+            Duration::from_secs(60),
+            0.2,
+            &NoMetrics,
+        );
+        let peers = (0..num_peers)
+            .map(|i| BLSPubKey::generated_from_seed_indexed([3; 32], i).0)
+            .collect();
+        (reputation, peers)
+    }
+
+    /// Peers that respond well should be asked first more often than peers that time out
+    #[test]
+    fn test_prefers_well_scored_peers() {
+        let (reputation, peers) = setup(2);
+        let (good, bad) = (peers[0], peers[1]);
+
+        for i in 0..10u8 {
+            let hash = blake3::hash(&[i]);
+            reputation.record_request_sent(hash, good);
+            reputation.record_request_sent(hash, bad);
+            reputation.record_valid_response(hash, &good);
+        }
+
+        // Let the requests to the bad peer time out
+        std::thread::sleep(Duration::from_millis(60));
+        reputation.order_recipients(vec![]);
+
+        let good_stats = reputation.peer_stats(&good).unwrap();
+        let bad_stats = reputation.peer_stats(&bad).unwrap();
+        assert_eq!(good_stats.valid_responses, 10);
+        assert_eq!(bad_stats.timed_out_requests, 10);
+        assert!(good_stats.score() > bad_stats.score());
+
+        // The good peer should come first in the vast majority of orderings
+        let good_first = (0..1000)
+            .filter(|_| reputation.order_recipients(vec![bad, good])[0] == good)
+            .count();
+        assert!(good_first > 800, "good peer was first {good_first} times");
+    }
+
+    /// Peers that keep sending invalid responses should be banned, and late responses should not
+    /// count as timeouts
+    #[test]
+    fn test_ban_and_late_responses() {
+        let (reputation, peers) = setup(3);
+        let hash = blake3::hash(&[1, 2, 3]);
+        for peer in &peers {
+            reputation.record_request_sent(hash, *peer);
+        }
+
+        // A single invalid response only lowers the peer's score
+        reputation.record_invalid_response(hash, &peers[0]);
+        assert!(!reputation.is_banned(&peers[0]));
+        reputation.record_invalid_response(hash, &peers[0]);
+        reputation.record_unvalidated_response(hash, &peers[1]);
+
+        // Only the peer that never responded should time out
+        std::thread::sleep(Duration::from_millis(60));
+        let ordered = reputation.order_recipients(peers.clone());
+        assert_eq!(ordered.len(), 2);
+        assert!(!ordered.contains(&peers[0]));
+        assert!(reputation.is_banned(&peers[0]));
+        assert!(reputation.peer_stats(&peers[1]).is_none());
+        assert_eq!(
+            reputation.peer_stats(&peers[2]).unwrap().timed_out_requests,
+            1
+        );
+    }
+
+    /// A peer with a good track record is not banned for an occasional invalid response, and its
+    /// reliability recovers as it keeps responding well
+    #[test]
+    fn test_score_decays() {
+        let (reputation, peers) = setup(1);
+        let peer = peers[0];
+
+        for i in 0..20u8 {
+            reputation.record_valid_response(blake3::hash(&[i]), &peer);
+        }
+        reputation.record_invalid_response(blake3::hash(&[0]), &peer);
+        assert!(!reputation.is_banned(&peer));
+        let after_failure = reputation.peer_stats(&peer).unwrap().reliability();
+
+        for i in 0..20u8 {
+            reputation.record_valid_response(blake3::hash(&[i]), &peer);
+        }
+        let recovered = reputation.peer_stats(&peer).unwrap().reliability();
+        assert!(recovered > after_failure);
+
+        // Old successes decay too, so a peer that turns bad is eventually banned
+        let mut failures = 0;
+        while !reputation.is_banned(&peer) {
+            reputation.record_invalid_response(blake3::hash(&[0]), &peer);
+            failures += 1;
+            assert!(failures < 100, "peer was never banned");
+        }
+        assert!(failures > 1);
+    }
+}
@@ -0,0 +1,45 @@
+//! This file contains the [`Signer`] trait. This trait allows the [`RequestResponseProtocol`] to
+//! sign our requests and responses without holding our private key itself, e.g. when the key is
+//! held by a remote signer
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use hotshot_types::traits::{
+    node_implementation::NodeType,
+    signature_key::SignatureKey,
+    signer::{ConsensusSigner, SignableMessage},
+};
+
+/// The trait that allows the [`RequestResponseProtocol`] to sign messages with our private key
+#[async_trait]
+pub trait Signer<K: SignatureKey>: Send + Sync + 'static {
+    /// Sign a message with our private key
+    async fn sign(&self, message: &[u8]) -> Result<K::PureAssembledSignatureType>;
+}
+
+/// A [`Signer`] which holds our private key in memory
+pub struct LocalSigner<K: SignatureKey>(pub K::PrivateKey);
+
+#[async_trait]
+impl<K: SignatureKey + 'static> Signer<K> for LocalSigner<K>
+where
+    <K as SignatureKey>::SignError: 'static,
+{
+    async fn sign(&self, message: &[u8]) -> Result<K::PureAssembledSignatureType> {
+        K::sign(&self.0, message).with_context(|| "failed to sign message")
+    }
+}
+
+/// Requests and responses are signed by the same signer as consensus messages, so that nodes
+/// whose keys are held by a remote signer can take part in the protocol
+#[async_trait]
+impl<TYPES: NodeType> Signer<TYPES::SignatureKey> for Arc<dyn ConsensusSigner<TYPES>> {
+    async fn sign(
+        &self,
+        message: &[u8],
+    ) -> Result<<TYPES::SignatureKey as SignatureKey>::PureAssembledSignatureType> {
+        ConsensusSigner::sign(self.as_ref(), &SignableMessage::Other(message.to_vec())).await
+    }
+}
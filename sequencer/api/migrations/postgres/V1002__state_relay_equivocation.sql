@@ -0,0 +1,11 @@
+-- Evidence of state signers signing conflicting light client states for the same block height,
+-- kept permanently so that it can be used to hold the signers accountable.
+CREATE TABLE state_relay_equivocation (
+  height BIGINT NOT NULL,
+  key BYTEA NOT NULL,
+  epoch BIGINT NOT NULL,
+  data BYTEA NOT NULL,
+  PRIMARY KEY (height, key)
+);
+
+CREATE INDEX state_relay_equivocation_epoch_key ON state_relay_equivocation (epoch, key);
@@ -0,0 +1,21 @@
+-- Finalized L1 blocks, so the L1 client does not have to fetch them again after a restart.
+CREATE TABLE finalized_l1_block (
+  number BIGINT PRIMARY KEY,
+  data JSONB NOT NULL,
+  parent_hash BYTEA NOT NULL
+);
+
+-- Fee deposits made in each finalized L1 block. Blocks with no deposits have no rows.
+CREATE TABLE l1_deposits (
+  fee_contract BYTEA NOT NULL,
+  l1_block BIGINT NOT NULL,
+  data JSONB NOT NULL,
+  PRIMARY KEY (fee_contract, l1_block)
+);
+
+-- The range of finalized L1 blocks which has already been scanned for deposits to each contract.
+CREATE TABLE l1_deposits_scanned (
+  fee_contract BYTEA PRIMARY KEY,
+  from_block BIGINT NOT NULL,
+  to_block BIGINT NOT NULL
+);
@@ -0,0 +1,22 @@
+-- The validators in each epoch, as seen by the staking explorer.
+CREATE TABLE epoch_validator (
+  epoch BIGINT NOT NULL,
+  account TEXT NOT NULL,
+  stake_table_key TEXT NOT NULL,
+  data JSONB NOT NULL,
+  PRIMARY KEY (epoch, account)
+);
+
+CREATE INDEX epoch_validator_key_idx ON epoch_validator (stake_table_key);
+
+-- The leader of each view. `height` is the height of the block proposed in this view, or NULL if
+-- no block was decided in this view, meaning the leader missed it.
+CREATE TABLE view_leader (
+  view BIGINT PRIMARY KEY,
+  epoch BIGINT NOT NULL,
+  leader TEXT NOT NULL,
+  height BIGINT
+);
+
+CREATE INDEX view_leader_epoch_idx ON view_leader (epoch, leader);
+CREATE INDEX view_leader_height_idx ON view_leader (height);
@@ -0,0 +1,7 @@
+-- The state of the last imported snapshot, so that consensus can start from the snapshot leaf
+-- with the full state.
+CREATE TABLE snapshot_state (
+  id INTEGER PRIMARY KEY CHECK (id = 0),
+  height BIGINT NOT NULL,
+  data BLOB NOT NULL
+);
@@ -0,0 +1,24 @@
+-- Stake tables used by the state relay server to weigh light client state signatures, and the
+-- threshold a bundle of signatures must reach to be certified, for each epoch.
+CREATE TABLE state_relay_stake_table (
+  epoch BIGINT PRIMARY KEY,
+  threshold TEXT NOT NULL,
+  stake_table BLOB NOT NULL
+);
+
+-- Light client state signatures collected by the state relay server, for heights which have not
+-- been certified yet. Signers may sign different states for the same height, so signatures are
+-- grouped by both height and state.
+CREATE TABLE state_relay_signature (
+  height BIGINT NOT NULL,
+  state BLOB NOT NULL,
+  key BLOB NOT NULL,
+  data BLOB NOT NULL,
+  PRIMARY KEY (height, state, key)
+);
+
+-- Certified light client state signature bundles, by block height.
+CREATE TABLE state_relay_bundle (
+  height BIGINT PRIMARY KEY,
+  data BLOB NOT NULL
+);
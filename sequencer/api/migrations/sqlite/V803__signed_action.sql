@@ -0,0 +1,16 @@
+-- Every message this node has signed, so that it never signs a conflicting message for the same
+-- view, even after a restart. `action` is the kind of message signed, so that different kinds of
+-- votes in the same view are told apart.
+CREATE TABLE signed_action (
+  view BIGINT NOT NULL,
+  action TEXT NOT NULL,
+  commitment BLOB NOT NULL,
+  epoch BIGINT,
+  PRIMARY KEY (view, action, commitment)
+);
+
+-- At most one message of each kind may be signed per view, except for the kinds which a node
+-- legitimately signs several times in the same view. Enforcing this in the database means two
+-- conflicting messages can never both be recorded, even by concurrent transactions.
+CREATE UNIQUE INDEX signed_action_unique_per_view ON signed_action (view, action)
+  WHERE action NOT IN ('ViewSyncVote', 'VidDisperse', 'Other');
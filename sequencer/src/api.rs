@@ -1,13 +1,14 @@
 use std::{pin::Pin, sync::Arc};
 
 use alloy::primitives::Address;
-use anyhow::{bail, Context};
+use anyhow::{anyhow, bail, ensure, Context};
 use async_lock::RwLock;
 use async_once_cell::Lazy;
 use async_trait::async_trait;
-use committable::Commitment;
+use committable::{Commitment, Committable};
 use data_source::{
-    CatchupDataSource, StakeTableDataSource, StakeTableWithEpochNumber, SubmitDataSource,
+    CatchupDataSource, StakeTableDataSource, StakeTableWithEpochNumber, StakingExplorerDataSource,
+    SubmitDataSource, TransactionStatusDataSource,
 };
 use derivative::Derivative;
 use espresso_types::{
@@ -17,8 +18,9 @@ use espresso_types::{
     v0_1::{RewardAccount, RewardMerkleTree},
     v0_3::Validator,
     v0_99::ChainConfig,
-    AccountQueryData, BlockMerkleTree, FeeAccount, FeeMerkleTree, Leaf2, NodeState, PubKey,
-    Transaction,
+    AccountQueryData, BlockMerkleTree, DelegatorSummary, FeeAccount, FeeMerkleTree, Header, Leaf2,
+    NodeState, Payload, PubKey, RewardHistoryEntry, Transaction, TransactionStatus,
+    ValidatorDetail, ValidatorSummary,
 };
 use futures::{
     future::{BoxFuture, Future, FutureExt},
@@ -26,11 +28,11 @@ use futures::{
 };
 use hotshot::types::BLSPubKey;
 use hotshot_events_service::events_source::{
-    EventFilterSet, EventsSource, EventsStreamer, StartupInfo,
+    EventFilterSet, EventsSource, EventsStreamer, NamespaceSource, StartupInfo,
 };
 use hotshot_query_service::data_source::ExtensibleDataSource;
 use hotshot_types::{
-    data::ViewNumber,
+    data::{EpochNumber, ViewNumber},
     event::Event,
     light_client::StateSignatureRequestBody,
     network::NetworkConfig,
@@ -45,7 +47,11 @@ use indexmap::IndexMap;
 use itertools::Itertools;
 use jf_merkle_tree::MerkleTreeScheme;
 
-use self::data_source::{HotShotConfigDataSource, NodeStateDataSource, StateSignatureDataSource};
+use self::{
+    data_source::{HotShotConfigDataSource, NodeStateDataSource, StateSignatureDataSource},
+    options::Submit,
+    tx_status::SubmittedTransactions,
+};
 use crate::{
     catchup::{add_fee_accounts_to_state, add_reward_accounts_to_state, CatchupStorage},
     context::Consensus,
@@ -59,6 +65,8 @@ pub mod endpoints;
 pub mod fs;
 pub mod options;
 pub mod sql;
+mod staking_explorer;
+mod tx_status;
 mod update;
 
 pub use options::Options;
@@ -102,15 +110,31 @@ struct ApiState<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Version
     // without waiting.
     #[derivative(Debug = "ignore")]
     consensus: BoxLazy<ConsensusState<N, P, V>>,
+
+    // Transactions submitted through this node, for reporting the status of in-flight
+    // transactions.
+    submitted: Arc<RwLock<SubmittedTransactions>>,
+
+    // The maximum number of transactions accepted in a single batch submission.
+    max_batch_size: usize,
 }
 
 impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> ApiState<N, P, V> {
     fn new(init: impl Future<Output = ConsensusState<N, P, V>> + Send + 'static) -> Self {
         Self {
             consensus: Arc::pin(Lazy::from_future(init.boxed())),
+            submitted: Default::default(),
+            max_batch_size: Submit::default().max_batch_size,
         }
     }
 
+    /// Track submitted transactions according to the options for the submit API.
+    fn with_submit_options(mut self, opt: &Submit) -> Self {
+        self.submitted = Arc::new(RwLock::new(SubmittedTransactions::new(opt)));
+        self.max_batch_size = opt.max_batch_size;
+        self
+    }
+
     async fn state_signer(&self) -> &Arc<RwLock<StateSigner<SequencerApiVersion>>> {
         &self.consensus.as_ref().get().await.get_ref().state_signer
     }
@@ -136,6 +160,30 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> ApiState
 
 type StorageState<N, P, D, V> = ExtensibleDataSource<D, ApiState<N, P, V>>;
 
+/// Namespace information for Espresso transactions and blocks, so that clients of the HotShot
+/// events API can filter events by namespace.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct EspressoNamespaces;
+
+impl NamespaceSource<SeqTypes> for EspressoNamespaces {
+    fn transaction_namespace(&self, transaction: &Transaction) -> u64 {
+        u32::from(transaction.namespace()).into()
+    }
+
+    fn block_namespaces(&self, header: &Header) -> Vec<u64> {
+        let ns_table = header.ns_table();
+        ns_table
+            .iter()
+            .filter_map(|index| ns_table.read_ns_id(&index))
+            .map(|ns| u32::from(ns).into())
+            .collect()
+    }
+
+    fn restrict_payload(&self, payload: &Payload, namespaces: &[u64]) -> Payload {
+        payload.restrict_namespaces(|ns| namespaces.contains(&u32::from(ns).into()))
+    }
+}
+
 #[async_trait]
 impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> EventsSource<SeqTypes>
     for ApiState<N, P, V>
@@ -144,13 +192,13 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> EventsSo
 
     async fn get_event_stream(
         &self,
-        _filter: Option<EventFilterSet<SeqTypes>>,
+        filter: Option<EventFilterSet<SeqTypes>>,
     ) -> Self::EventStream {
         self.event_streamer()
             .await
             .read()
             .await
-            .get_event_stream(None)
+            .get_event_stream(filter)
             .await
     }
     async fn get_startup_info(&self) -> StartupInfo<SeqTypes> {
@@ -161,6 +209,15 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> EventsSo
             .get_startup_info()
             .await
     }
+
+    async fn supports_namespace_filter(&self) -> bool {
+        self.event_streamer()
+            .await
+            .read()
+            .await
+            .supports_namespace_filter()
+            .await
+    }
 }
 
 impl<N: ConnectedNetwork<PubKey>, D: Send + Sync, V: Versions, P: SequencerPersistence>
@@ -169,6 +226,21 @@ impl<N: ConnectedNetwork<PubKey>, D: Send + Sync, V: Versions, P: SequencerPersi
     async fn submit(&self, tx: Transaction) -> anyhow::Result<()> {
         self.as_ref().submit(tx).await
     }
+
+    async fn submit_batch(&self, txs: Vec<Transaction>) -> anyhow::Result<Vec<anyhow::Result<()>>> {
+        self.as_ref().submit_batch(txs).await
+    }
+}
+
+impl<N: ConnectedNetwork<PubKey>, D: Sync, V: Versions, P: SequencerPersistence>
+    TransactionStatusDataSource for StorageState<N, P, D, V>
+{
+    async fn submitted_transaction_status(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> Option<TransactionStatus> {
+        self.as_ref().submitted_transaction_status(hash).await
+    }
 }
 
 impl<N: ConnectedNetwork<PubKey>, D: Sync, V: Versions, P: SequencerPersistence>
@@ -196,6 +268,47 @@ impl<N: ConnectedNetwork<PubKey>, D: Sync, V: Versions, P: SequencerPersistence>
     }
 }
 
+impl<N: ConnectedNetwork<PubKey>, D: StakingExplorerDataSource + Sync, V: Versions, P>
+    StakingExplorerDataSource for StorageState<N, P, D, V>
+where
+    P: SequencerPersistence,
+{
+    async fn get_validator_summaries(
+        &self,
+        epoch: EpochNumber,
+    ) -> anyhow::Result<Vec<ValidatorSummary>> {
+        self.inner().get_validator_summaries(epoch).await
+    }
+
+    async fn get_validator_detail(
+        &self,
+        epoch: EpochNumber,
+        account: Address,
+    ) -> anyhow::Result<Option<ValidatorDetail>> {
+        self.inner().get_validator_detail(epoch, account).await
+    }
+
+    async fn get_reward_history(
+        &self,
+        account: Address,
+        from: EpochNumber,
+        until: EpochNumber,
+        epoch_height: u64,
+    ) -> anyhow::Result<Vec<RewardHistoryEntry>> {
+        self.inner()
+            .get_reward_history(account, from, until, epoch_height)
+            .await
+    }
+
+    async fn get_delegator(
+        &self,
+        epoch: EpochNumber,
+        address: Address,
+    ) -> anyhow::Result<DelegatorSummary> {
+        self.inner().get_delegator(epoch, address).await
+    }
+}
+
 impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence>
     StakeTableDataSource<SeqTypes> for ApiState<N, P, V>
 {
@@ -261,22 +374,13 @@ impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence>
     }
 }
 
-impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> SubmitDataSource<N, P>
-    for ApiState<N, P, V>
-{
-    async fn submit(&self, tx: Transaction) -> anyhow::Result<()> {
-        let handle = self.consensus().await;
-
-        let consensus_read_lock = handle.read().await;
-
+impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> ApiState<N, P, V> {
+    /// The maximum size of a block, and thus of a single transaction.
+    async fn max_block_size(&self, consensus: &Consensus<N, P, V>) -> u64 {
         // Fetch full chain config from the validated state, if present.
         // This is necessary because we support chain config upgrades,
         // so the updated chain config is found in the validated state.
-        let cf = consensus_read_lock
-            .decided_state()
-            .await
-            .chain_config
-            .resolve();
+        let cf = consensus.decided_state().await.chain_config.resolve();
 
         // Use the chain config from the validated state if available,
         // otherwise, use the node state's chain config
@@ -286,17 +390,94 @@ impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> SubmitDa
             None => self.node_state().await.chain_config,
         };
 
-        let max_block_size: u64 = cf.max_block_size.into();
-        let txn_size = tx.payload().len() as u64;
+        cf.max_block_size.into()
+    }
+}
 
-        // reject transaction bigger than block size
-        if txn_size > max_block_size {
-            bail!("transaction size ({txn_size}) is greater than max_block_size ({max_block_size})")
-        }
+/// Check that a transaction can fit in a block.
+fn validate_transaction_size(tx: &Transaction, max_block_size: u64) -> anyhow::Result<()> {
+    let txn_size = tx.payload().len() as u64;
+
+    // reject transaction bigger than block size
+    if txn_size > max_block_size {
+        bail!("transaction size ({txn_size}) is greater than max_block_size ({max_block_size})")
+    }
+    Ok(())
+}
+
+impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> SubmitDataSource<N, P>
+    for ApiState<N, P, V>
+{
+    async fn submit(&self, tx: Transaction) -> anyhow::Result<()> {
+        let handle = self.consensus().await;
+
+        let consensus_read_lock = handle.read().await;
+        let max_block_size = self.max_block_size(&consensus_read_lock).await;
+        validate_transaction_size(&tx, max_block_size)?;
 
+        let hash = tx.commit();
         consensus_read_lock.submit_transaction(tx).await?;
+        self.submitted.write().await.insert(hash);
         Ok(())
     }
+
+    async fn submit_batch(&self, txs: Vec<Transaction>) -> anyhow::Result<Vec<anyhow::Result<()>>> {
+        ensure!(
+            txs.len() <= self.max_batch_size,
+            "batch of {} transactions exceeds the maximum batch size ({})",
+            txs.len(),
+            self.max_batch_size
+        );
+
+        let handle = self.consensus().await;
+
+        // Hold the consensus lock and resolve the chain config once for the whole batch.
+        let consensus_read_lock = handle.read().await;
+        let max_block_size = self.max_block_size(&consensus_read_lock).await;
+
+        // Validate each transaction independently, and submit all the valid ones at once.
+        let mut results = Vec::with_capacity(txs.len());
+        let mut valid = vec![];
+        for tx in txs {
+            match validate_transaction_size(&tx, max_block_size) {
+                Ok(()) => {
+                    results.push(Ok(()));
+                    valid.push(tx);
+                },
+                Err(err) => results.push(Err(err)),
+            }
+        }
+        if valid.is_empty() {
+            return Ok(results);
+        }
+
+        let hashes = valid.iter().map(|tx| tx.commit()).collect::<Vec<_>>();
+        if let Err(err) = consensus_read_lock.submit_transactions(valid).await {
+            // None of the valid transactions were submitted.
+            let err = format!("{err:#}");
+            for res in results.iter_mut().filter(|res| res.is_ok()) {
+                *res = Err(anyhow!("{err}"));
+            }
+            return Ok(results);
+        }
+
+        let mut submitted = self.submitted.write().await;
+        for hash in hashes {
+            submitted.insert(hash);
+        }
+        Ok(results)
+    }
+}
+
+impl<N: ConnectedNetwork<PubKey>, V: Versions, P: SequencerPersistence> TransactionStatusDataSource
+    for ApiState<N, P, V>
+{
+    async fn submitted_transaction_status(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> Option<TransactionStatus> {
+        self.submitted.read().await.status(hash)
+    }
 }
 
 impl<N, P, D, V> NodeStateDataSource for StorageState<N, P, D, V>
@@ -651,7 +832,8 @@ pub mod test_helpers {
     };
     use espresso_types::{
         v0::traits::{NullEventConsumer, PersistenceOptions, StateCatchup},
-        EpochVersion, MarketplaceVersion, MockSequencerVersions, NamespaceId, ValidatedState,
+        EpochVersion, MarketplaceVersion, MockSequencerVersions, NamespaceId,
+        TransactionSubmitResult, ValidatedState,
     };
     use futures::{
         future::{join_all, FutureExt},
@@ -1100,7 +1282,11 @@ pub mod test_helpers {
         let url = format!("http://localhost:{port}").parse().unwrap();
         let client: Client<ServerError, StaticVersion<0, 1>> = Client::new(url);
 
-        let options = opt(Options::with_port(port).submit(Default::default()));
+        const MAX_BATCH_SIZE: usize = 3;
+        let options = opt(Options::with_port(port).submit(Submit {
+            max_batch_size: MAX_BATCH_SIZE,
+            ..Default::default()
+        }));
         let network_config = TestConfigBuilder::default().build();
         let config = TestNetworkConfigBuilder::default()
             .api_config(options)
@@ -1122,6 +1308,57 @@ pub mod test_helpers {
 
         // Wait for a Decide event containing transaction matching the one we sent
         wait_for_decide_on_handle(&mut events, &txn).await;
+
+        // Submit a batch. The transactions may be sequenced in the same block, so wait for each
+        // one on a separate event stream.
+        let batch = [2, 3]
+            .map(|i| Transaction::new(NamespaceId::from(1_u32), vec![i; 4]))
+            .to_vec();
+        let mut batch_events = join_all(batch.iter().map(|_| network.server.event_stream())).await;
+        let results: Vec<TransactionSubmitResult> = client
+            .post("submit/batch")
+            .body_json(&batch)
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(results.len(), batch.len());
+        for ((txn, res), events) in batch.iter().zip(results).zip(&mut batch_events) {
+            assert_eq!(res.hash, txn.commit());
+            assert!(res.is_ok(), "{res:?}");
+            wait_for_decide_on_handle(events, txn).await;
+        }
+
+        // An invalid transaction in a batch is rejected without affecting the others.
+        let valid = Transaction::new(NamespaceId::from(1_u32), vec![5; 4]);
+        let too_big = Transaction::new(NamespaceId::from(1_u32), vec![0; 1 << 20]);
+        let mut events = network.server.event_stream().await;
+        let results: Vec<TransactionSubmitResult> = client
+            .post("submit/batch")
+            .body_json(&vec![too_big.clone(), valid.clone()])
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].hash, too_big.commit());
+        assert!(!results[0].is_ok(), "{:?}", results[0]);
+        assert_eq!(results[1].hash, valid.commit());
+        assert!(results[1].is_ok(), "{:?}", results[1]);
+        wait_for_decide_on_handle(&mut events, &valid).await;
+
+        // A batch larger than the maximum batch size is rejected entirely.
+        let batch = (0..=MAX_BATCH_SIZE)
+            .map(|i| Transaction::new(NamespaceId::from(1_u32), vec![i as u8; 4]))
+            .collect::<Vec<_>>();
+        let err = client
+            .post::<Vec<TransactionSubmitResult>>("submit/batch")
+            .body_json(&batch)
+            .unwrap()
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST, "{err:#}");
     }
 
     /// Test the state signature API.
@@ -1327,7 +1564,8 @@ mod api_tests {
     use data_source::testing::TestableSequencerDataSource;
     use espresso_types::{
         traits::{EventConsumer, PersistenceOptions},
-        Header, Leaf2, MockSequencerVersions, NamespaceId, NamespaceProofQueryData, ValidatedState,
+        BlockNamespacesQueryData, Header, Leaf2, MockSequencerVersions, NamespaceId,
+        NamespaceProofQueryData, TransactionReceipt, ValidatedState,
     };
     use futures::{future, stream::StreamExt};
     use hotshot_example_types::node_types::{EpochsTestVersions, TestVersions};
@@ -1474,6 +1712,132 @@ mod api_tests {
         }
         assert!(found_txn);
         assert!(found_empty_block);
+
+        // The namespace stream should give the same results, with proofs.
+        let mut blocks = client
+            .socket(&format!(
+                "availability/stream/blocks/0/namespaces/{ns_id},{}",
+                u32::from(ns_id) + 1
+            ))
+            .subscribe::<BlockNamespacesQueryData>()
+            .await
+            .unwrap();
+        let mut found_txn = false;
+        for block_num in 0..=block_height {
+            let block = blocks.next().await.unwrap().unwrap();
+            assert_eq!(block.header.height(), block_num as u64);
+            let namespaces = block.verify().unwrap();
+            assert_eq!(namespaces.len(), 2);
+            assert_eq!(namespaces[0].0, ns_id);
+            // Nothing was ever submitted to the second namespace.
+            assert!(namespaces[1].1.is_empty());
+            found_txn = found_txn || namespaces[0].1.iter().any(|txn| txn.commit() == hash);
+        }
+        assert!(found_txn);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub(crate) async fn test_transaction_status<D: TestableSequencerDataSource>() {
+        setup_test();
+
+        let txn = Transaction::new(NamespaceId::from(42_u32), vec![1, 2, 3, 4]);
+        let hash = txn.commit();
+
+        let port = pick_unused_port().expect("No ports free");
+        let storage = D::create_storage().await;
+        let network_config = TestConfigBuilder::default().build();
+        let config = TestNetworkConfigBuilder::default()
+            .api_config(D::options(&storage, Options::with_port(port)).submit(Default::default()))
+            .network_config(network_config)
+            .build();
+        let network = TestNetwork::new(config, MockSequencerVersions::new()).await;
+        let mut events = network.server.event_stream().await;
+
+        let client: Client<ServerError, StaticVersion<0, 1>> =
+            Client::new(format!("http://localhost:{port}").parse().unwrap());
+        client.connect(None).await;
+
+        // A transaction we have never seen has unknown status.
+        let status: TransactionStatus = client
+            .get(&format!("submit/status/{hash}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status, TransactionStatus::Unknown);
+        client
+            .get::<TransactionReceipt>(&format!("submit/receipt/{hash}"))
+            .send()
+            .await
+            .unwrap_err();
+
+        // Subscribe to status updates, then submit the transaction.
+        let mut updates = client
+            .socket(&format!("submit/stream/status/{hash}"))
+            .subscribe::<TransactionStatus>()
+            .await
+            .unwrap();
+        assert_eq!(
+            updates.next().await.unwrap().unwrap(),
+            TransactionStatus::Unknown
+        );
+        client
+            .post::<Commitment<Transaction>>("submit/submit")
+            .body_json(&txn)
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let block_height = wait_for_decide_on_handle(&mut events, &txn).await;
+
+        // The stream ends once the transaction is included.
+        let mut statuses = vec![];
+        while let Some(status) = updates.next().await {
+            statuses.push(status.unwrap());
+        }
+        tracing::info!(?statuses, "got status updates");
+        let included = *statuses.last().unwrap();
+        let TransactionStatus::Included { height, .. } = included else {
+            panic!("transaction not included: {included:?}");
+        };
+        assert_eq!(height, block_height);
+        assert!(statuses[..statuses.len() - 1]
+            .iter()
+            .all(|status| matches!(status, TransactionStatus::Pending { .. })));
+
+        let status: TransactionStatus = client
+            .get(&format!("submit/status/{hash}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(status, included);
+
+        // Wait for the next block, so that the receipt can include a block proof. The proof may
+        // still be missing if the node no longer has the state for the next block in memory and
+        // does not store merklized state.
+        let next: Header = client
+            .get(&format!("availability/header/{}", height + 1))
+            .send()
+            .await
+            .unwrap();
+        let receipt: TransactionReceipt = client
+            .get(&format!("submit/receipt/{hash}"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(receipt.hash(), hash);
+        assert_eq!(receipt.height(), height);
+        // The transaction is the only one in its namespace.
+        assert_eq!(receipt.index, 0);
+        receipt.verify_inclusion().unwrap();
+        // A receipt claiming the wrong position is rejected.
+        let mut wrong_index = receipt.clone();
+        wrong_index.index = 1;
+        wrong_index.verify_inclusion().unwrap_err();
+        if receipt.block_proof.is_some() {
+            receipt
+                .verify_block_proof(next.block_merkle_tree_root())
+                .unwrap();
+        }
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -1857,6 +2221,7 @@ mod test {
     use std::{collections::HashSet, time::Duration};
 
     use alloy::primitives::U256;
+    use client::{SequencerClient, TrustedCheckpoint, VerifiedSequencerClient};
     use committable::{Commitment, Committable};
     use espresso_types::{
         config::PublicHotShotConfig,
@@ -1864,7 +2229,7 @@ mod test {
         v0_1::{block_reward, RewardAmount},
         v0_3::StakeTableFetcher,
         validators_from_l1_events, EpochVersion, FeeAmount, Header, L1ClientOptions,
-        MarketplaceVersion, MockSequencerVersions, SequencerVersions, ValidatedState,
+        MarketplaceVersion, MockSequencerVersions, NamespaceId, SequencerVersions, ValidatedState,
     };
     use futures::{
         future::{self, join_all},
@@ -1891,7 +2256,7 @@ mod test {
         TestNetwork, TestNetworkConfigBuilder,
     };
     use tide_disco::{app::AppHealth, error::ServerError, healthcheck::HealthStatus};
-    use tokio::time::sleep;
+    use tokio::time::{sleep, timeout};
     use vbs::version::{StaticVersion, StaticVersionType};
 
     use self::{
@@ -1902,7 +2267,7 @@ mod test {
     use crate::{
         catchup::{NullStateCatchup, StatePeers},
         persistence::no_storage,
-        testing::{TestConfig, TestConfigBuilder},
+        testing::{wait_for_decide_on_handle, TestConfig, TestConfigBuilder},
     };
 
     #[tokio::test(flavor = "multi_thread")]
@@ -2023,6 +2388,239 @@ mod test {
         assert_eq!(expected, amount.0);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_verified_client() {
+        setup_test();
+
+        let port = pick_unused_port().expect("No ports free");
+        let storage = SqlDataSource::create_storage().await;
+        let options =
+            SqlDataSource::options(&storage, Options::with_port(port)).catchup(Default::default());
+        let config = TestNetworkConfigBuilder::default()
+            .api_config(options)
+            .network_config(TestConfigBuilder::default().build())
+            .build();
+        let _network = TestNetwork::new(config, MockSequencerVersions::new()).await;
+        let url: url::Url = format!("http://localhost:{port}").parse().unwrap();
+        let client: Client<ServerError, SequencerApiVersion> = Client::new(url.clone());
+        client.connect(Some(Duration::from_secs(15))).await;
+
+        // Wait for enough blocks that a leaf chain is available for the blocks we verify.
+        let blocks = client
+            .socket("availability/stream/blocks/0")
+            .subscribe::<BlockQueryData<SeqTypes>>()
+            .await
+            .unwrap()
+            .take(10)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        // Give the state update loop time to store Merkle proofs.
+        sleep(Duration::from_secs(5)).await;
+
+        let genesis = client
+            .get::<Header>("availability/header/0")
+            .send()
+            .await
+            .unwrap();
+        let stake_table = client
+            .get::<StakeTableWithEpochNumber<SeqTypes>>("node/stake-table/current")
+            .send()
+            .await
+            .unwrap()
+            .stake_table;
+        let verified = VerifiedSequencerClient::new(
+            SequencerClient::new(url.clone()),
+            TrustedCheckpoint {
+                header: genesis.clone(),
+                stake_table: stake_table.clone(),
+            },
+            None,
+        );
+
+        // Advance the trusted chain, concurrently from several tasks.
+        let heights = [3, 5, 5, 4];
+        let headers = join_all(heights.map(|height| verified.get_header(height)))
+            .await
+            .into_iter()
+            .collect::<anyhow::Result<Vec<_>>>()
+            .unwrap();
+        for (height, header) in heights.into_iter().zip(headers) {
+            assert_eq!(header.commit(), blocks[height as usize].header().commit());
+        }
+        assert_eq!(verified.latest_trusted_header().await.height(), 5);
+
+        // Older headers are verified against the trusted chain.
+        let header = verified.get_header(1).await.unwrap();
+        assert_eq!(header.commit(), blocks[1].header().commit());
+
+        // Balances are verified against a trusted header.
+        let builder = TestConfig::<5>::builder_key().fee_account();
+        let balance = verified
+            .get_espresso_balance(builder.address(), Some(5))
+            .await
+            .unwrap();
+        assert!(balance > 0.into(), "{balance:?}");
+
+        // A client trusting a different stake table rejects the real leaf chain.
+        let mut wrong_stake_table = stake_table;
+        wrong_stake_table.pop();
+        let forged = VerifiedSequencerClient::new(
+            SequencerClient::new(url),
+            TrustedCheckpoint {
+                header: genesis,
+                stake_table: wrong_stake_table,
+            },
+            None,
+        );
+        forged.get_header(3).await.unwrap_err();
+        assert_eq!(forged.latest_trusted_header().await.height(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_client_sdk() {
+        setup_test();
+
+        let port = pick_unused_port().expect("No ports free");
+        let storage = SqlDataSource::create_storage().await;
+        let options = SqlDataSource::options(&storage, Options::with_port(port));
+        let config = TestNetworkConfigBuilder::default()
+            .api_config(options)
+            .network_config(TestConfigBuilder::default().build())
+            .build();
+        let _network = TestNetwork::new(config, MockSequencerVersions::new()).await;
+        let url: url::Url = format!("http://localhost:{port}").parse().unwrap();
+        let client = SequencerClient::new(url);
+
+        // The resumable stream yields consecutive heights starting from the requested one.
+        let headers = client.stream_headers(1).take(5).collect::<Vec<_>>().await;
+        assert_eq!(
+            headers
+                .iter()
+                .map(|header| header.height())
+                .collect::<Vec<_>>(),
+            (1..6).collect::<Vec<_>>()
+        );
+        let leaves = client.stream_leaves(0).take(6).collect::<Vec<_>>().await;
+
+        // Typed getters agree with the stream.
+        for header in &headers {
+            assert_eq!(
+                client.get_header(header.height()).await.unwrap().commit(),
+                header.commit()
+            );
+        }
+        client.get_node_limits().await.unwrap();
+        assert!(client.get_height().await.unwrap() >= 6);
+
+        // Pagination splits the range into pages but yields every object exactly once, in order.
+        let paged = client
+            .paginate::<LeafQueryData<SeqTypes>>("availability/leaf", 0, 6, 4)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(paged, leaves);
+        let paged = client
+            .leaves(0, 6)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(paged, leaves);
+        // An empty range yields nothing.
+        assert!(client
+            .paginate::<Header>("availability/header", 3, 3, 4)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap()
+            .is_empty());
+
+        // The stake table endpoint deserializes into the SDK type.
+        let current = client.get_current_stake_table().await.unwrap();
+        let expected = client
+            .get::<StakeTableWithEpochNumber<SeqTypes>>("node/stake-table/current")
+            .await
+            .unwrap();
+        assert_eq!(current.epoch, expected.epoch);
+        assert_eq!(current.stake_table, expected.stake_table);
+        assert_eq!(current.stake_table.len(), 5);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_request_response_availability() {
+        setup_test();
+
+        let port = pick_unused_port().expect("No ports free");
+        let storage = SqlDataSource::create_storage().await;
+        let options =
+            SqlDataSource::options(&storage, Options::with_port(port)).submit(Default::default());
+        let config = TestNetworkConfigBuilder::default()
+            .api_config(options)
+            .network_config(TestConfigBuilder::default().build())
+            .build();
+        let network = TestNetwork::new(config, MockSequencerVersions::new()).await;
+        let mut events = network.server.event_stream().await;
+        let client: Client<ServerError, SequencerApiVersion> =
+            Client::new(format!("http://localhost:{port}").parse().unwrap());
+        client.connect(None).await;
+
+        // Sequence a transaction, so there is a non-empty payload to fetch.
+        let ns_id = NamespaceId::from(42_u32);
+        let txn = Transaction::new(ns_id, vec![1, 2, 3, 4]);
+        client
+            .post::<Commitment<Transaction>>("submit/submit")
+            .body_json(&txn)
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let height = wait_for_decide_on_handle(&mut events, &txn).await;
+        let block = client
+            .socket(&format!("availability/stream/blocks/{height}"))
+            .subscribe::<BlockQueryData<SeqTypes>>()
+            .await
+            .unwrap()
+            .next()
+            .await
+            .unwrap()
+            .unwrap();
+        let commit = block.payload_hash();
+        let ns_table = block.header().ns_table().clone();
+
+        // Fetch the data from the other nodes through one of the peers. The responses are checked
+        // against the commitment by the protocol itself.
+        let protocol = network.peers[0].request_response_protocol();
+        let fetch_timeout = Duration::from_secs(60);
+        let (payload, _) = timeout(fetch_timeout, protocol.fetch_payload(commit))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&payload, block.payload());
+        timeout(
+            fetch_timeout,
+            protocol.fetch_vid_common(commit, block.height()),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let transactions = timeout(
+            fetch_timeout,
+            protocol.fetch_namespace_payload(commit, ns_table.clone(), ns_id),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(transactions, vec![txn]);
+
+        // A namespace that is not in the payload is empty, without asking anyone.
+        let transactions = protocol
+            .fetch_namespace_payload(commit, ns_table, NamespaceId::from(43_u32))
+            .await
+            .unwrap();
+        assert_eq!(transactions, vec![]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_leaf_only_data_source() {
         setup_test();
@@ -2908,7 +3506,7 @@ mod test {
         // Fetch the config from node 1, a different node than the one running the service.
         let validator =
             ValidatorConfig::generated_from_seed_indexed([0; 32], 1, U256::from(1), false);
-        let config = peers.fetch_config(validator.clone()).await.unwrap();
+        let config = peers.fetch_config(validator.public_key).await.unwrap();
 
         // Check the node-specific information in the recovered config is correct.
         assert_eq!(config.node_index, 1);
@@ -3170,6 +3768,107 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_staking_cli_reward_balance() -> anyhow::Result<()> {
+        // The staking CLI reads reward balances through a verified client, so an untrusted query
+        // service cannot report a balance which does not match a header certified by the stake
+        // table.
+        setup_test();
+        let epoch_height = 20;
+
+        type PosVersion = SequencerVersions<StaticVersion<0, 3>, StaticVersion<0, 0>>;
+
+        let network_config = TestConfigBuilder::default()
+            .epoch_height(epoch_height)
+            .build();
+
+        let api_port = pick_unused_port().expect("No ports free for query service");
+
+        const NUM_NODES: usize = 1;
+        let storage = join_all((0..NUM_NODES).map(|_| SqlDataSource::create_storage())).await;
+        let persistence: [_; NUM_NODES] = storage
+            .iter()
+            .map(<SqlDataSource as TestableSequencerDataSource>::persistence_options)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let config = TestNetworkConfigBuilder::with_num_nodes()
+            .api_config(
+                SqlDataSource::options(&storage[0], Options::with_port(api_port))
+                    .catchup(Default::default()),
+            )
+            .network_config(network_config.clone())
+            .persistences(persistence.clone())
+            .catchups(std::array::from_fn(|_| {
+                StatePeers::<StaticVersion<0, 1>>::from_urls(
+                    vec![format!("http://localhost:{api_port}").parse().unwrap()],
+                    Default::default(),
+                    &NoMetrics,
+                )
+            }))
+            .pos_hook::<PosVersion>(false)
+            .await
+            .unwrap()
+            .build();
+
+        let _network = TestNetwork::new(config, PosVersion::new()).await;
+        let url: url::Url = format!("http://localhost:{api_port}").parse().unwrap();
+        let client: Client<ServerError, SequencerApiVersion> = Client::new(url.clone());
+
+        // Wait until rewards have been paid for a while in the third epoch.
+        let _blocks = client
+            .socket("availability/stream/blocks/0")
+            .subscribe::<BlockQueryData<SeqTypes>>()
+            .await
+            .unwrap()
+            .take(65)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+
+        // Trust a header and stake table from the third epoch.
+        let epoch_start_block = 40;
+        let checkpoint = TrustedCheckpoint {
+            header: client
+                .get::<Header>(&format!("availability/header/{}", epoch_start_block + 1))
+                .send()
+                .await
+                .unwrap(),
+            stake_table: client
+                .get::<Vec<PeerConfig<SeqTypes>>>("node/stake-table/3")
+                .send()
+                .await
+                .unwrap(),
+        };
+        let verified =
+            VerifiedSequencerClient::new(SequencerClient::new(url), checkpoint, Some(epoch_height));
+
+        let address = network_config.staking_priv_keys()[0].0.address();
+        let block_height = 60;
+        let balance =
+            staking_cli::rewards::reward_balance(&verified, address, Some(block_height)).await?;
+        assert_eq!(balance.address, address);
+        assert_eq!(balance.height, block_height);
+        assert_eq!(
+            balance.balance,
+            block_reward().0 * U256::from(block_height - epoch_start_block)
+        );
+
+        // Without a height, the balance is read at the latest trusted header.
+        let latest = staking_cli::rewards::reward_balance(&verified, address, None).await?;
+        assert_eq!(latest.height, block_height);
+        assert_eq!(latest.balance, balance.balance);
+
+        // The verified client has no stake table for the fourth epoch, so it cannot verify a
+        // balance there.
+        staking_cli::rewards::reward_balance(&verified, address, Some(epoch_height * 3 + 1))
+            .await
+            .unwrap_err();
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_cumulative_pos_rewards() -> anyhow::Result<()> {
         // This test registers 5 validators and multiple delegators for each validator.
@@ -8,7 +8,8 @@ use espresso_types::{
     v0_1::{RewardAccount, RewardAccountProof, RewardAccountQueryData, RewardMerkleTree},
     v0_3::Validator,
     v0_99::ChainConfig,
-    FeeAccount, FeeAccountProof, FeeMerkleTree, Leaf2, NodeState, PubKey, Transaction,
+    DelegatorSummary, FeeAccount, FeeAccountProof, FeeMerkleTree, Leaf2, NodeState, PubKey,
+    RewardHistoryEntry, Transaction, TransactionStatus, ValidatorDetail, ValidatorSummary,
 };
 use futures::future::Future;
 use hotshot::types::BLSPubKey;
@@ -101,6 +102,28 @@ pub fn provider<V: Versions>(
 
 pub(crate) trait SubmitDataSource<N: ConnectedNetwork<PubKey>, P: SequencerPersistence> {
     fn submit(&self, tx: Transaction) -> impl Send + Future<Output = anyhow::Result<()>>;
+
+    /// Submit a batch of transactions.
+    ///
+    /// Each transaction is validated independently, so some may be rejected while others are
+    /// accepted. The result for each transaction is returned in the same order as the input. The
+    /// whole batch is rejected if it exceeds the configured maximum batch size.
+    fn submit_batch(
+        &self,
+        txs: Vec<Transaction>,
+    ) -> impl Send + Future<Output = anyhow::Result<Vec<anyhow::Result<()>>>>;
+}
+
+pub(crate) trait TransactionStatusDataSource {
+    /// Get the status of a transaction which was submitted through this node.
+    ///
+    /// This only knows about transactions which are still being tracked after submission, so it
+    /// cannot report whether a transaction has been sequenced. Returns [`None`] if `hash` is not
+    /// being tracked.
+    fn submitted_transaction_status(
+        &self,
+        hash: Commitment<Transaction>,
+    ) -> impl Send + Future<Output = Option<TransactionStatus>>;
 }
 
 pub(crate) trait HotShotConfigDataSource {
@@ -142,6 +165,42 @@ pub(crate) trait StakeTableDataSource<T: NodeType> {
     ) -> impl Send + Future<Output = anyhow::Result<IndexMap<Address, Validator<BLSPubKey>>>>;
 }
 
+pub(crate) trait StakingExplorerDataSource {
+    /// Get the validators in the stake table for `epoch`, with their performance in that epoch.
+    ///
+    /// Returns an empty list if the stake table for `epoch` has not been indexed.
+    fn get_validator_summaries(
+        &self,
+        epoch: EpochNumber,
+    ) -> impl Send + Future<Output = anyhow::Result<Vec<ValidatorSummary>>>;
+
+    /// Get the validator with the given `account` in the stake table for `epoch`.
+    fn get_validator_detail(
+        &self,
+        epoch: EpochNumber,
+        account: Address,
+    ) -> impl Send + Future<Output = anyhow::Result<Option<ValidatorDetail>>>;
+
+    /// Get the reward balance of `account` at the end of each epoch in `[from, until)`.
+    ///
+    /// The history stops early at the latest epoch for which we have reward state; the entry for
+    /// an epoch which is still in progress has the latest known balance.
+    fn get_reward_history(
+        &self,
+        account: Address,
+        from: EpochNumber,
+        until: EpochNumber,
+        epoch_height: u64,
+    ) -> impl Send + Future<Output = anyhow::Result<Vec<RewardHistoryEntry>>>;
+
+    /// Get the delegations of `address` in the stake table for `epoch`.
+    fn get_delegator(
+        &self,
+        epoch: EpochNumber,
+        address: Address,
+    ) -> impl Send + Future<Output = anyhow::Result<DelegatorSummary>>;
+}
+
 pub(crate) trait CatchupDataSource: Sync {
     /// Get the state of the requested `account`.
     ///
@@ -3,13 +3,16 @@
 use std::{
     collections::{BTreeSet, HashMap},
     env,
+    time::Duration,
 };
 
+use alloy::primitives::Address;
 use anyhow::Result;
-use committable::Committable;
+use committable::{Commitment, Committable};
 use espresso_types::{
     v0_1::{ADVZNsProof, RewardAccount, RewardMerkleTree},
-    FeeAccount, FeeMerkleTree, NamespaceId, NsProof, PubKey, Transaction,
+    BlockNamespacesQueryData, FeeAccount, FeeMerkleTree, NamespaceId, NsProof, PubKey, Transaction,
+    TransactionReceipt, TransactionStatus, TransactionSubmitResult,
 };
 // re-exported here to avoid breaking changes in consumers
 // "deprecated" does not work with "pub use": https://github.com/rust-lang/rust/issues/30827
@@ -18,14 +21,22 @@ pub type ADVZNamespaceProofQueryData = espresso_types::ADVZNamespaceProofQueryDa
 #[deprecated(note = "use espresso_types::NamespaceProofQueryData")]
 pub type NamespaceProofQueryData = espresso_types::NamespaceProofQueryData;
 
-use futures::{try_join, FutureExt};
+use futures::{
+    future,
+    stream::{self, BoxStream},
+    try_join, FutureExt, StreamExt, TryFutureExt,
+};
 use hotshot_query_service::{
-    availability::{self, AvailabilityDataSource, CustomSnafu, FetchBlockSnafu},
+    availability::{
+        self, AvailabilityDataSource, BlockQueryData, CustomSnafu, FetchBlockSnafu,
+        VidCommonQueryData,
+    },
     explorer::{self, ExplorerDataSource},
     merklized_state::{
         self, MerklizedState, MerklizedStateDataSource, MerklizedStateHeightPersistence, Snapshot,
     },
     node::{self, NodeDataSource},
+    types::HeightIndexed,
     ApiState, Error, VidCommon,
 };
 use hotshot_types::{
@@ -34,18 +45,20 @@ use hotshot_types::{
         network::ConnectedNetwork,
         node_implementation::{ConsensusTime, Versions},
     },
+    vote::HasViewNumber,
 };
 use jf_merkle_tree::MerkleTreeScheme;
 use serde::de::Error as _;
 use snafu::OptionExt;
 use tagged_base64::TaggedBase64;
-use tide_disco::{method::ReadState, Api, Error as _, StatusCode};
+use tide_disco::{method::ReadState, Api, Error as _, RequestParams, StatusCode};
 use vbs::version::{StaticVersion, StaticVersionType};
 
 use super::{
     data_source::{
         CatchupDataSource, HotShotConfigDataSource, NodeStateDataSource, SequencerDataSource,
-        StakeTableDataSource, StateSignatureDataSource, SubmitDataSource,
+        StakeTableDataSource, StakingExplorerDataSource, StateSignatureDataSource,
+        SubmitDataSource, TransactionStatusDataSource,
     },
     StorageState,
 };
@@ -221,6 +234,29 @@ where
                 }
             }
             .boxed()
+        })?
+        .stream("stream_namespaces", move |req, state| {
+            async move {
+                let height: usize = req.integer_param("height")?;
+                let namespaces = match req.opt_integer_param::<_, u32>("namespace")? {
+                    Some(ns_id) => vec![NamespaceId::from(ns_id)],
+                    None => parse_namespaces(req.string_param("namespaces")?)?,
+                };
+                state
+                    .read(|state| {
+                        async move {
+                            let blocks = state.subscribe_blocks(height).await;
+                            let common = state.subscribe_vid_common(height).await;
+                            Ok(blocks.zip(common).map(move |(block, common)| {
+                                block_namespaces(&block, &common, &namespaces)
+                            }))
+                        }
+                        .boxed()
+                    })
+                    .await
+            }
+            .try_flatten_stream()
+            .boxed()
         })?;
     } else {
         api.get("getnamespaceproof", move |req, state| {
@@ -284,6 +320,62 @@ where
     Ok(api)
 }
 
+fn parse_namespaces(namespaces: &str) -> Result<Vec<NamespaceId>, availability::Error> {
+    namespaces
+        .split(',')
+        .map(|ns_id| {
+            ns_id
+                .trim()
+                .parse::<u32>()
+                .map(NamespaceId::from)
+                .map_err(|err| availability::Error::Custom {
+                    message: format!("invalid namespace ID {ns_id}: {err}"),
+                    status: StatusCode::BAD_REQUEST,
+                })
+        })
+        .collect()
+}
+
+/// Extract the requested namespaces, with proofs, from a block.
+fn block_namespaces(
+    block: &BlockQueryData<SeqTypes>,
+    common: &VidCommonQueryData<SeqTypes>,
+    namespaces: &[NamespaceId],
+) -> Result<BlockNamespacesQueryData, availability::Error> {
+    let namespaces = namespaces
+        .iter()
+        .map(|ns_id| {
+            let data = match block.payload().ns_table().find_ns_id(ns_id) {
+                Some(ns_index) => {
+                    let proof = NsProof::new(block.payload(), &ns_index, common.common()).context(
+                        CustomSnafu {
+                            message: format!(
+                                "failed to make proof for namespace {ns_id} in block {}",
+                                block.height()
+                            ),
+                            status: StatusCode::INTERNAL_SERVER_ERROR,
+                        },
+                    )?;
+                    espresso_types::NamespaceProofQueryData {
+                        transactions: proof.export_all_txs(ns_id),
+                        proof: Some(proof),
+                    }
+                },
+                None => espresso_types::NamespaceProofQueryData {
+                    proof: None,
+                    transactions: Vec::new(),
+                },
+            };
+            Ok((*ns_id, data))
+        })
+        .collect::<Result<_, availability::Error>>()?;
+    Ok(BlockNamespacesQueryData {
+        header: block.header().clone(),
+        vid_common: common.common().clone(),
+        namespaces,
+    })
+}
+
 type ExplorerApi<N, P, D, V, ApiVer> = Api<AvailState<N, P, D, V>, explorer::Error, ApiVer>;
 
 pub(super) fn explorer<N, P, D, V: Versions>(
@@ -383,9 +475,83 @@ where
     S::State: Send + Sync + SubmitDataSource<N, P>,
 {
     let toml = toml::from_str::<toml::Value>(include_str!("../../api/submit.toml"))?;
+    define_submit_api::<N, P, S, ApiVer>(toml, api_ver)
+}
+
+/// The submit API, extended with endpoints for tracking the status of submitted transactions.
+///
+/// The status endpoints are backed by the query service's transaction index, so this version of
+/// the API is only available when the query module is enabled.
+pub(super) fn submit_with_status<N, P, S, ApiVer: StaticVersionType + 'static>(
+    api_ver: semver::Version,
+) -> Result<Api<S, Error, ApiVer>>
+where
+    N: ConnectedNetwork<PubKey>,
+    S: 'static + Send + Sync + Clone + ReadState,
+    P: SequencerPersistence,
+    S::State: Send
+        + Sync
+        + SubmitDataSource<N, P>
+        + TransactionStatusDataSource
+        + AvailabilityDataSource<SeqTypes>
+        + NodeDataSource<SeqTypes>
+        + CatchupDataSource
+        + NodeStateDataSource,
+{
+    let mut toml = toml::from_str::<toml::Value>(include_str!("../../api/submit.toml"))?;
+    let extension = toml::from_str::<toml::Value>(include_str!("../../api/submit_status.toml"))?;
+    let routes = toml
+        .get_mut("route")
+        .and_then(toml::Value::as_table_mut)
+        .ok_or_else(|| anyhow::anyhow!("submit API has no routes"))?;
+    if let Some(extension) = extension.get("route").and_then(toml::Value::as_table) {
+        routes.extend(extension.clone());
+    }
+    let timeout = availability::Options::default().fetch_timeout;
+
+    let mut api = define_submit_api::<N, P, S, ApiVer>(toml, api_ver)?;
+    api.get("status", |req, state| {
+        async move {
+            let hash = req.blob_param("hash").map_err(Error::from_request_error)?;
+            Ok(transaction_status(state, hash).await)
+        }
+        .boxed()
+    })?
+    .get("receipt", move |req, state| {
+        async move {
+            let hash = req.blob_param("hash").map_err(Error::from_request_error)?;
+            transaction_receipt(state, hash, timeout).await
+        }
+        .boxed()
+    })?
+    .stream("stream_status", |req, state| {
+        let state = state.clone();
+        async move {
+            let hash = req.blob_param("hash").map_err(Error::from_request_error)?;
+            transaction_status_stream(state, hash).await
+        }
+        .try_flatten_stream()
+        .boxed()
+    })?;
+
+    Ok(api)
+}
+
+fn define_submit_api<N, P, S, ApiVer: StaticVersionType + 'static>(
+    toml: toml::Value,
+    api_ver: semver::Version,
+) -> Result<Api<S, Error, ApiVer>>
+where
+    N: ConnectedNetwork<PubKey>,
+    S: 'static + Send + Sync + ReadState,
+    P: SequencerPersistence,
+    S::State: Send + Sync + SubmitDataSource<N, P>,
+{
     let mut api = Api::<S, Error, ApiVer>::new(toml)?;
 
-    api.with_version(api_ver).at("submit", |req, state| {
+    api.with_version(api_ver);
+
+    api.at("submit", |req, state| {
         async move {
             let tx = req
                 .body_auto::<Transaction, ApiVer>(ApiVer::instance())
@@ -399,11 +565,193 @@ where
             Ok(hash)
         }
         .boxed()
+    })?
+    .at("submit_batch", |req, state| {
+        async move {
+            let txs = req
+                .body_auto::<Vec<Transaction>, ApiVer>(ApiVer::instance())
+                .map_err(Error::from_request_error)?;
+
+            let hashes = txs.iter().map(|tx| tx.commit()).collect::<Vec<_>>();
+            let results = state
+                .read(|state| state.submit_batch(txs).boxed())
+                .await
+                .map_err(|err| Error::catch_all(StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+            Ok(hashes
+                .into_iter()
+                .zip(results)
+                .map(|(hash, res)| TransactionSubmitResult {
+                    hash,
+                    error: res.err().map(|err| format!("{err:#}")),
+                })
+                .collect::<Vec<_>>())
+        }
+        .boxed()
     })?;
 
     Ok(api)
 }
 
+async fn transaction_status<S>(state: &S, hash: Commitment<Transaction>) -> TransactionStatus
+where
+    S: AvailabilityDataSource<SeqTypes> + TransactionStatusDataSource,
+{
+    // If the transaction has been sequenced, it will be in the query service's transaction index.
+    // Don't wait for it to be fetched: if it is not available locally, it is not sequenced yet as
+    // far as this node knows.
+    if let Ok(tx) = state.get_transaction(hash).await.try_resolve() {
+        return TransactionStatus::Included {
+            height: tx.block_height(),
+            index: tx.index(),
+        };
+    }
+    state
+        .submitted_transaction_status(hash)
+        .await
+        .unwrap_or(TransactionStatus::Unknown)
+}
+
+async fn transaction_receipt<S>(
+    state: &S,
+    hash: Commitment<Transaction>,
+    timeout: Duration,
+) -> Result<TransactionReceipt, Error>
+where
+    S: AvailabilityDataSource<SeqTypes> + CatchupDataSource + NodeStateDataSource,
+{
+    let tx = state
+        .get_transaction(hash)
+        .await
+        .try_resolve()
+        .map_err(|_| Error::Custom {
+            message: format!("transaction {hash} has not been sequenced"),
+            status: StatusCode::NOT_FOUND,
+        })?;
+    let height = tx.block_height() as usize;
+    let (block, common) = try_join!(
+        state
+            .get_block(height)
+            .then(|fetch| fetch.with_timeout(timeout))
+            .map(|block| block.ok_or_else(|| Error::Custom {
+                message: format!("block {height} not available"),
+                status: StatusCode::NOT_FOUND,
+            })),
+        state
+            .get_vid_common(height)
+            .then(|fetch| fetch.with_timeout(timeout))
+            .map(|common| common.ok_or_else(|| Error::Custom {
+                message: format!("VID common {height} not available"),
+                status: StatusCode::NOT_FOUND,
+            })),
+    )?;
+
+    let ns_id = tx.transaction().namespace();
+    let ns_proof = block
+        .payload()
+        .ns_table()
+        .find_ns_id(&ns_id)
+        .and_then(|ns_index| NsProof::new(block.payload(), &ns_index, common.common()))
+        .ok_or_else(|| {
+            Error::internal(format!(
+                "failed to make proof for namespace {ns_id} in block {height}"
+            ))
+        })?;
+
+    // The block Merkle tree in the header after this block contains a proof for this block's
+    // header: it is the frontier of the tree. If we don't have the next leaf yet, or can't load the
+    // state, return the receipt without a block proof; the client can try again later.
+    let block_proof = match state.get_leaf(height + 1).await.try_resolve() {
+        Ok(next) => state
+            .get_frontier(
+                state.node_state().await,
+                height as u64 + 1,
+                next.leaf().view_number(),
+            )
+            .await
+            .inspect_err(|err| {
+                tracing::info!(height, "block proof not available for receipt: {err:#}")
+            })
+            .ok(),
+        Err(_) => None,
+    };
+
+    // The receipt gives the position of the transaction within its namespace, which is what the
+    // namespace proof can attest to.
+    let index = block
+        .enumerate()
+        .take(tx.index() as usize)
+        .filter(|(_, tx)| tx.namespace() == ns_id)
+        .count() as u64;
+
+    Ok(TransactionReceipt {
+        transaction: tx.transaction().clone(),
+        index,
+        header: block.header().clone(),
+        ns_proof,
+        vid_common: common.common().clone(),
+        block_proof,
+    })
+}
+
+async fn transaction_status_stream<S>(
+    state: S,
+    hash: Commitment<Transaction>,
+) -> Result<BoxStream<'static, Result<TransactionStatus, Error>>, Error>
+where
+    S: 'static + Send + Sync + Clone + ReadState,
+    S::State: AvailabilityDataSource<SeqTypes>
+        + NodeDataSource<SeqTypes>
+        + TransactionStatusDataSource
+        + Send
+        + Sync,
+{
+    // Subscribe to new blocks before checking the current status, so we can't miss the
+    // transaction being sequenced in between.
+    let height = state
+        .read(|state| state.block_height().boxed())
+        .await
+        .map_err(|err| Error::internal(format!("failed to get block height: {err}")))?;
+    let blocks = state
+        .read(|state| state.subscribe_blocks(height).boxed())
+        .await;
+    let current = state
+        .read(|state| transaction_status(state, hash).boxed())
+        .await;
+
+    let updates = blocks.then(move |block| {
+        let state = state.clone();
+        async move {
+            match block.enumerate().position(|(_, tx)| tx.commit() == hash) {
+                Some(index) => TransactionStatus::Included {
+                    height: block.height(),
+                    index: index as u64,
+                },
+                None => {
+                    state
+                        .read(|state| transaction_status(state, hash).boxed())
+                        .await
+                },
+            }
+        }
+    });
+
+    // Only report changes in status, and end the stream once the status is final.
+    Ok(stream::once(future::ready(current))
+        .chain(updates)
+        .scan((None, false), |(prev, done), status| {
+            if *done {
+                return future::ready(None);
+            }
+            *done = status.is_final();
+            let changed = *prev != Some(status);
+            *prev = Some(status);
+            future::ready(Some(changed.then_some(status)))
+        })
+        .filter_map(future::ready)
+        .map(Ok)
+        .boxed())
+}
+
 pub(super) fn state_signature<N, S, ApiVer: StaticVersionType + 'static>(
     _: ApiVer,
     api_ver: semver::Version,
@@ -621,6 +969,119 @@ where
 
 type MerklizedStateApi<N, P, D, V, ApiVer> =
     Api<AvailState<N, P, D, V>, merklized_state::Error, ApiVer>;
+/// The maximum number of epochs that can be requested at once from the reward history endpoint.
+const MAX_REWARD_HISTORY_EPOCHS: u64 = 100;
+
+pub(super) fn staking_explorer<S, ApiVer: StaticVersionType + 'static>(
+    _: ApiVer,
+    api_ver: semver::Version,
+) -> Result<Api<S, Error, ApiVer>>
+where
+    S: 'static + Send + Sync + ReadState,
+    S::State: Send + Sync + NodeStateDataSource + StakingExplorerDataSource,
+{
+    let toml = toml::from_str::<toml::Value>(include_str!("../../api/staking_explorer.toml"))?;
+    let mut api = Api::<S, Error, ApiVer>::new(toml)?;
+    api.with_version(api_ver);
+
+    api.get("get_validators", |req, state| {
+        async move {
+            let epoch = EpochNumber::new(
+                req.integer_param("epoch")
+                    .map_err(Error::from_request_error)?,
+            );
+            state
+                .get_validator_summaries(epoch)
+                .await
+                .map_err(|err| Error::internal(format!("{err:#}")))
+        }
+        .boxed()
+    })?
+    .get("get_validator", |req, state| {
+        async move {
+            let epoch = EpochNumber::new(
+                req.integer_param("epoch")
+                    .map_err(Error::from_request_error)?,
+            );
+            let account = address_param(&req, "address")?;
+            state
+                .get_validator_detail(epoch, account)
+                .await
+                .map_err(|err| Error::internal(format!("{err:#}")))?
+                .ok_or_else(|| {
+                    Error::catch_all(
+                        StatusCode::NOT_FOUND,
+                        format!("validator {account} not found in epoch {epoch}"),
+                    )
+                })
+        }
+        .boxed()
+    })?
+    .get("get_reward_history", |req, state| {
+        async move {
+            let account = address_param(&req, "address")?;
+            let from: u64 = req
+                .integer_param("from")
+                .map_err(Error::from_request_error)?;
+            let until: u64 = req
+                .integer_param("until")
+                .map_err(Error::from_request_error)?;
+            if until < from || until - from > MAX_REWARD_HISTORY_EPOCHS {
+                return Err(Error::catch_all(
+                    StatusCode::BAD_REQUEST,
+                    format!(
+                        "invalid epoch range [{from}, {until}): at most \
+                         {MAX_REWARD_HISTORY_EPOCHS} epochs can be requested at once"
+                    ),
+                ));
+            }
+            let Some(epoch_height) = state.node_state().await.epoch_height.filter(|h| *h > 0)
+            else {
+                return Err(Error::catch_all(
+                    StatusCode::NOT_FOUND,
+                    "epochs are not enabled".into(),
+                ));
+            };
+            state
+                .get_reward_history(
+                    account,
+                    EpochNumber::new(from),
+                    EpochNumber::new(until),
+                    epoch_height,
+                )
+                .await
+                .map_err(|err| Error::catch_all(StatusCode::NOT_FOUND, format!("{err:#}")))
+        }
+        .boxed()
+    })?
+    .get("get_delegator", |req, state| {
+        async move {
+            let epoch = EpochNumber::new(
+                req.integer_param("epoch")
+                    .map_err(Error::from_request_error)?,
+            );
+            let address = address_param(&req, "address")?;
+            state
+                .get_delegator(epoch, address)
+                .await
+                .map_err(|err| Error::catch_all(StatusCode::NOT_FOUND, format!("{err:#}")))
+        }
+        .boxed()
+    })?;
+
+    Ok(api)
+}
+
+fn address_param(req: &RequestParams, name: &str) -> Result<Address, Error> {
+    let address = req.string_param(name).map_err(Error::from_request_error)?;
+    address.parse().map_err(|err| {
+        Error::catch_all(
+            StatusCode::BAD_REQUEST,
+            format!("malformed address {address}: {err}"),
+        )
+    })
+}
+
 pub(super) fn merklized_state<N, P, D, S, V: Versions, const ARITY: usize>(
     api_ver: semver::Version,
 ) -> Result<MerklizedStateApi<N, P, D, V, SequencerApiVersion>>
@@ -1,10 +1,11 @@
 //! Sequencer-specific API options and initialization.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use anyhow::{bail, Context};
 use clap::Parser;
 use espresso_types::{
+    parse_duration,
     v0::traits::{EventConsumer, NullEventConsumer, PersistenceOptions, SequencerPersistence},
     BlockMerkleTree, PubKey,
 };
@@ -33,6 +34,7 @@ use super::{
         SequencerDataSource, StateSignatureDataSource, SubmitDataSource,
     },
     endpoints, fs, sql,
+    staking_explorer::update_staking_explorer_loop,
     update::ApiEventConsumer,
     ApiState, StorageState,
 };
@@ -40,6 +42,7 @@ use crate::{
     catchup::CatchupStorage,
     context::{SequencerContext, TaskList},
     persistence,
+    request_response::catchup::availability::RequestResponseProvider,
     state::update_state_storage_loop,
     SequencerApiVersion,
 };
@@ -158,13 +161,20 @@ impl Options {
         // allows the web server to start before initialization can complete, since initialization
         // can take a long time (and is dependent on other nodes).
         let (send_ctx, recv_ctx) = oneshot::channel();
-        let state = ApiState::new(async move {
+        let mut state = ApiState::new(async move {
             recv_ctx
                 .await
                 .expect("context initialized and sent over channel")
         });
+        if let Some(opt) = &self.submit {
+            state = state.with_submit_options(opt);
+        }
         let mut tasks = TaskList::default();
 
+        // The query service can fetch missing data from peers over the request-response protocol,
+        // but the protocol is only created along with the context, so we fill it in afterwards.
+        let peer_provider = RequestResponseProvider::default();
+
         // The server state type depends on whether we are running a query or status API or not, so
         // we handle the two cases differently.
         #[allow(clippy::type_complexity)]
@@ -178,6 +188,7 @@ impl Options {
                     query_opt,
                     opt,
                     state,
+                    peer_provider.clone(),
                     &mut tasks,
                     SequencerApiVersion::instance(),
                 )
@@ -187,6 +198,7 @@ impl Options {
                     query_opt,
                     opt,
                     state,
+                    peer_provider.clone(),
                     &mut tasks,
                     SequencerApiVersion::instance(),
                 )
@@ -247,6 +259,7 @@ impl Options {
         };
 
         let ctx = init_context(metrics, consumer, storage).await?;
+        peer_provider.set(ctx.request_response_protocol());
         send_ctx
             .send(super::ConsensusState::from(&ctx))
             .ok()
@@ -294,10 +307,10 @@ impl Options {
             endpoints::node(ver).context("failed to define node api")
         })?;
 
-        // Initialize submit API
+        // Initialize submit API, with transaction status tracking backed by the query service.
         if self.submit.is_some() {
             register_api("submit", &mut app, move |ver| {
-                endpoints::submit::<_, _, _, SequencerApiVersion>(ver)
+                endpoints::submit_with_status::<_, _, _, SequencerApiVersion>(ver)
                     .context("failed to define submit api")
             })?;
         }
@@ -326,6 +339,7 @@ impl Options {
         query_opt: Query,
         mod_opt: persistence::fs::Options,
         state: ApiState<N, P, V>,
+        peer_provider: RequestResponseProvider,
         tasks: &mut TaskList,
         bind_version: SequencerApiVersion,
     ) -> anyhow::Result<(
@@ -339,7 +353,7 @@ impl Options {
     {
         let ds = <fs::DataSource as SequencerDataSource>::create(
             mod_opt,
-            provider::<V>(query_opt.peers, bind_version),
+            provider::<V>(query_opt.peers, bind_version).with_provider(peer_provider),
             false,
         )
         .await?;
@@ -361,6 +375,7 @@ impl Options {
         query_opt: Query,
         mod_opt: persistence::sql::Options,
         state: ApiState<N, P, V>,
+        peer_provider: RequestResponseProvider,
         tasks: &mut TaskList,
         bind_version: SequencerApiVersion,
     ) -> anyhow::Result<(
@@ -382,6 +397,9 @@ impl Options {
             tracing::info!("will fetch missing data from {peer}");
             provider = provider.with_provider(QueryServiceProvider::new(peer, bind_version));
         }
+        // Finally, fall back to asking peers over the request-response protocol, which needs no
+        // archive URLs.
+        provider = provider.with_provider(peer_provider);
 
         let ds = sql::DataSource::create(mod_opt.clone(), provider, false).await?;
         let inner_storage = ds.inner();
@@ -393,6 +411,10 @@ impl Options {
             register_api("explorer", &mut app, move |ver| {
                 endpoints::explorer(ver).context("failed to define explorer api")
             })?;
+            register_api("staking-explorer", &mut app, move |ver| {
+                endpoints::staking_explorer(bind_version, ver)
+                    .context("failed to define staking explorer api")
+            })?;
         }
 
         // Initialize merklized state module for block merkle tree
@@ -421,6 +443,12 @@ impl Options {
             "merklized state storage update loop",
             update_state_storage_loop(ds.clone(), get_node_state),
         );
+        if self.explorer.is_some() {
+            tasks.spawn(
+                "staking explorer update loop",
+                update_staking_explorer_loop(ds.clone()),
+            );
+        }
         if self.hotshot_events.is_some() {
             self.init_and_spawn_hotshot_event_streaming_module(state, tasks)?;
         }
@@ -583,8 +611,44 @@ impl Http {
 }
 
 /// Options for the submission API module.
-#[derive(Parser, Clone, Copy, Debug, Default)]
-pub struct Submit;
+#[derive(Parser, Clone, Copy, Debug)]
+pub struct Submit {
+    /// How long to wait for a transaction submitted through this node to be sequenced before
+    /// reporting it as dropped.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_SUBMIT_TX_TIMEOUT",
+        value_parser = parse_duration,
+        default_value = "5m"
+    )]
+    pub tx_timeout: Duration,
+
+    /// Maximum number of submitted transactions to track for status reporting.
+    ///
+    /// When this limit is reached, the oldest transactions are forgotten, and their status is
+    /// reported as unknown until they are sequenced.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_SUBMIT_MAX_TRACKED_TXS",
+        default_value = "10000"
+    )]
+    pub max_tracked_txs: usize,
+
+    /// Maximum number of transactions accepted in a single batch submission.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_SUBMIT_MAX_BATCH_SIZE",
+        default_value = "100"
+    )]
+    pub max_batch_size: usize,
+}
+
+impl Default for Submit {
+    fn default() -> Self {
+        // Use the same defaults as the command line, so the two cannot drift apart.
+        Self::parse_from(["submit"])
+    }
+}
 
 /// Options for the status API module.
 #[derive(Parser, Clone, Copy, Debug, Default)]
@@ -7,11 +7,12 @@ use espresso_types::{
     get_l1_deposits,
     v0_1::{RewardAccount, RewardMerkleTree, REWARD_MERKLE_TREE_HEIGHT},
     v0_99::{ChainConfig, IterableFeeInfo},
-    BlockMerkleTree, EpochVersion, FeeAccount, FeeMerkleTree, Leaf2, NodeState, ValidatedState,
+    BlockMerkleTree, EpochVersion, FeeAccount, FeeMerkleTree, Leaf2, NodeState, Payload,
+    ValidatedState,
 };
 use hotshot::traits::ValidatedState as _;
 use hotshot_query_service::{
-    availability::LeafId,
+    availability::{BlockId, LeafId},
     data_source::{
         sql::{Config, SqlDataSource, Transaction},
         storage::{
@@ -21,10 +22,10 @@ use hotshot_query_service::{
         VersionedDataSource,
     },
     merklized_state::Snapshot,
-    Resolvable,
+    Resolvable, VidCommon,
 };
 use hotshot_types::{
-    data::{EpochNumber, QuorumProposalWrapper, ViewNumber},
+    data::{EpochNumber, QuorumProposalWrapper, VidCommitment, ViewNumber},
     message::Proposal,
     traits::node_implementation::ConsensusTime,
     utils::epoch_from_block_number,
@@ -247,6 +248,30 @@ impl CatchupStorage for SqlStorage {
 
         Ok(chain)
     }
+
+    async fn get_vid_common(&self, commit: VidCommitment) -> anyhow::Result<VidCommon> {
+        let mut tx = self
+            .read()
+            .await
+            .context(format!("opening transaction to fetch VID common {commit}"))?;
+        let common = tx
+            .get_vid_common(BlockId::PayloadHash(commit))
+            .await
+            .context(format!("VID common {commit} not available"))?;
+        Ok(common.common().clone())
+    }
+
+    async fn get_payload(&self, commit: VidCommitment) -> anyhow::Result<Payload> {
+        let mut tx = self
+            .read()
+            .await
+            .context(format!("opening transaction to fetch payload {commit}"))?;
+        let payload = tx
+            .get_payload(BlockId::PayloadHash(commit))
+            .await
+            .context(format!("payload {commit} not available"))?;
+        Ok(payload.data().clone())
+    }
 }
 
 impl CatchupStorage for DataSource {
@@ -292,6 +317,14 @@ impl CatchupStorage for DataSource {
     async fn get_leaf_chain(&self, height: u64) -> anyhow::Result<Vec<Leaf2>> {
         self.as_ref().get_leaf_chain(height).await
     }
+
+    async fn get_vid_common(&self, commit: VidCommitment) -> anyhow::Result<VidCommon> {
+        self.as_ref().get_vid_common(commit).await
+    }
+
+    async fn get_payload(&self, commit: VidCommitment) -> anyhow::Result<Payload> {
+        self.as_ref().get_payload(commit).await
+    }
 }
 
 #[async_trait]
@@ -309,7 +342,7 @@ impl ChainConfigPersistence for Transaction<Write> {
     }
 }
 
-async fn load_frontier<Mode: TransactionMode>(
+pub(crate) async fn load_frontier<Mode: TransactionMode>(
     tx: &mut Transaction<Mode>,
     height: u64,
 ) -> anyhow::Result<BlocksFrontier> {
@@ -728,14 +761,14 @@ where
 }
 
 #[cfg(any(test, feature = "testing"))]
-mod impl_testable_data_source {
+pub(crate) mod impl_testable_data_source {
 
     use hotshot_query_service::data_source::storage::sql::testing::TmpDb;
 
     use super::*;
     use crate::api::{self, data_source::testing::TestableSequencerDataSource};
 
-    fn tmp_options(db: &TmpDb) -> Options {
+    pub(crate) fn tmp_options(db: &TmpDb) -> Options {
         #[cfg(not(feature = "embedded-db"))]
         {
             let opt = crate::persistence::sql::PostgresOptions {
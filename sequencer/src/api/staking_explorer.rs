@@ -0,0 +1,465 @@
+//! Validator and delegator information for the explorer.
+//!
+//! The stake table for each epoch is only held in memory by consensus, and only for recent epochs,
+//! so archive nodes could not answer questions about validators in old epochs from consensus state
+//! alone. Instead, a background task snapshots the validators for each epoch and the leader of
+//! each view into the query database as leaves are decided, and the explorer endpoints are served
+//! from those tables.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use alloy::primitives::Address;
+use anyhow::Context;
+use espresso_types::{
+    v0::traits::SequencerPersistence,
+    v0_1::{RewardAccount, RewardAmount, RewardMerkleTree},
+    v0_3::{Delegator, Validator},
+    DelegatorSummary, PubKey, RewardHistoryEntry, ValidatorDetail, ValidatorSummary,
+};
+use futures::StreamExt;
+use hotshot_query_service::{
+    availability::AvailabilityDataSource,
+    data_source::{
+        storage::{
+            sql::{query_as, Transaction, TransactionMode},
+            MerklizedStateHeightStorage, MerklizedStateStorage,
+        },
+        VersionedDataSource,
+    },
+    merklized_state::Snapshot,
+    types::HeightIndexed,
+};
+use hotshot_types::{
+    data::{EpochNumber, ViewNumber},
+    traits::{
+        network::ConnectedNetwork,
+        node_implementation::{ConsensusTime, Versions},
+    },
+    vote::HasViewNumber,
+};
+use jf_merkle_tree::MerkleTreeScheme;
+use tokio::time::sleep;
+
+use super::{
+    data_source::{NodeStateDataSource, StakeTableDataSource, StakingExplorerDataSource},
+    sql, StorageState,
+};
+use crate::SeqTypes;
+
+/// Keep the validator and view leader tables up to date as new leaves are decided.
+#[tracing::instrument(skip_all)]
+pub(super) async fn update_staking_explorer_loop<N, P, V>(
+    ds: Arc<StorageState<N, P, sql::DataSource, V>>,
+) -> anyhow::Result<()>
+where
+    N: ConnectedNetwork<PubKey>,
+    P: SequencerPersistence,
+    V: Versions,
+{
+    let Some(epoch_height) = ds.node_state().await.epoch_height.filter(|h| *h > 0) else {
+        tracing::info!("epochs are not enabled, not indexing validators");
+        return Ok(());
+    };
+
+    // Resume after the last leaf we indexed.
+    let (from, mut last) = {
+        let mut tx = ds.read().await.context("opening transaction")?;
+        let (height,) = query_as::<(Option<i64>,)>("SELECT max(height) FROM view_leader")
+            .fetch_one(tx.as_mut())
+            .await?;
+        let last = query_as::<(i64, i64)>(
+            "SELECT view, epoch FROM view_leader ORDER BY view DESC LIMIT 1",
+        )
+        .fetch_optional(tx.as_mut())
+        .await?
+        .map(|(view, epoch)| (view as u64, EpochNumber::new(epoch as u64)));
+        (height.map_or(0, |h| h as u64 + 1), last)
+    };
+    tracing::info!(from, ?last, "indexing validators");
+
+    let mut leaves = ds.subscribe_leaves(from as usize).await;
+    while let Some(leaf) = leaves.next().await {
+        let height = leaf.height();
+        let view = leaf.leaf().view_number().u64();
+        let Some(epoch) = leaf.leaf().epoch(epoch_height) else {
+            continue;
+        };
+
+        loop {
+            match index_leaf(&ds, epoch, height, view, last).await {
+                Ok(()) => break,
+                Err(err) => {
+                    tracing::error!(height, "failed to index validators: {err:#}");
+                    sleep(Duration::from_secs(1)).await;
+                },
+            }
+        }
+        last = Some((view, epoch));
+    }
+
+    tracing::warn!("leaf stream ended, no longer indexing validators");
+    Ok(())
+}
+
+/// Record the leaders of the views leading up to the leaf at `height`.
+///
+/// `last` is the view and epoch of the previously indexed leaf. Every view after it and before
+/// `view` was missed by its leader, since no leaf was decided in it. Consensus only moves to a new
+/// epoch once it sees a block from that epoch, so these views still belong to the epoch of the
+/// previous leaf, and their leaders come from its stake table. If `epoch` is new, its stake table
+/// is snapshotted as well.
+async fn index_leaf<N, P, V>(
+    ds: &StorageState<N, P, sql::DataSource, V>,
+    epoch: EpochNumber,
+    height: u64,
+    view: u64,
+    last: Option<(u64, EpochNumber)>,
+) -> anyhow::Result<()>
+where
+    N: ConnectedNetwork<PubKey>,
+    P: SequencerPersistence,
+    V: Versions,
+{
+    // If the stake table for an epoch is not available yet, fail so that the caller retries this
+    // leaf, rather than moving on and leaving a gap in the index.
+    let coordinator = ds
+        .as_ref()
+        .consensus()
+        .await
+        .read()
+        .await
+        .membership_coordinator
+        .clone();
+    let membership_for = |epoch: EpochNumber| {
+        let coordinator = coordinator.clone();
+        async move {
+            coordinator
+                .membership_for_epoch(Some(epoch))
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!("stake table for epoch {epoch:?} not available: {err}")
+                })
+        }
+    };
+    let membership = membership_for(epoch).await?;
+
+    let new_epoch = last.is_none_or(|(_, last_epoch)| last_epoch != epoch);
+    let validators = if new_epoch {
+        ds.get_validators(epoch)
+            .await?
+            .into_values()
+            .map(|validator| {
+                Ok((
+                    epoch.u64() as i64,
+                    validator.account.to_string(),
+                    validator.stake_table_key.to_string(),
+                    serde_json::to_value(&validator)?,
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    } else {
+        vec![]
+    };
+
+    let mut leaders = vec![];
+    if let Some((last_view, last_epoch)) = last {
+        let missed_membership = membership_for(last_epoch).await?;
+        for v in last_view + 1..view {
+            let leader = missed_membership
+                .leader(ViewNumber::new(v))
+                .await
+                .context(format!("computing leader for view {v}"))?;
+            leaders.push((v as i64, last_epoch.u64() as i64, leader.to_string(), None));
+        }
+    }
+    let leader = membership
+        .leader(ViewNumber::new(view))
+        .await
+        .context(format!("computing leader for view {view}"))?;
+    leaders.push((
+        view as i64,
+        epoch.u64() as i64,
+        leader.to_string(),
+        Some(height as i64),
+    ));
+
+    let mut tx = ds.write().await.context("opening transaction")?;
+    if !validators.is_empty() {
+        tx.upsert(
+            "epoch_validator",
+            ["epoch", "account", "stake_table_key", "data"],
+            ["epoch", "account"],
+            validators,
+        )
+        .await?;
+    }
+    tx.upsert(
+        "view_leader",
+        ["view", "epoch", "leader", "height"],
+        ["view"],
+        leaders,
+    )
+    .await?;
+    tx.commit().await
+}
+
+impl StakingExplorerDataSource for sql::DataSource {
+    async fn get_validator_summaries(
+        &self,
+        epoch: EpochNumber,
+    ) -> anyhow::Result<Vec<ValidatorSummary>> {
+        let mut tx = self.read().await.context("opening transaction")?;
+        let validators = load_validators(&mut tx, epoch).await?;
+        let performance = query_as::<(String, i64, i64)>(
+            "SELECT leader, count(height), count(*) - count(height) FROM view_leader
+              WHERE epoch = $1
+              GROUP BY leader",
+        )
+        .bind(epoch.u64() as i64)
+        .fetch_all(tx.as_mut())
+        .await?
+        .into_iter()
+        .map(|(leader, proposed, missed)| (leader, (proposed as u64, missed as u64)))
+        .collect::<HashMap<_, _>>();
+
+        Ok(validators
+            .into_iter()
+            .map(|validator| {
+                let (blocks_proposed, views_missed) = performance
+                    .get(&validator.stake_table_key.to_string())
+                    .copied()
+                    .unwrap_or_default();
+                ValidatorSummary {
+                    account: validator.account,
+                    stake_table_key: validator.stake_table_key,
+                    stake: validator.stake,
+                    commission: validator.commission,
+                    num_delegators: validator.delegators.len() as u64,
+                    blocks_proposed,
+                    views_missed,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_validator_detail(
+        &self,
+        epoch: EpochNumber,
+        account: Address,
+    ) -> anyhow::Result<Option<ValidatorDetail>> {
+        let mut tx = self.read().await.context("opening transaction")?;
+        let Some((data,)) = query_as::<(serde_json::Value,)>(
+            "SELECT data FROM epoch_validator WHERE epoch = $1 AND account = $2",
+        )
+        .bind(epoch.u64() as i64)
+        .bind(account.to_string())
+        .fetch_optional(tx.as_mut())
+        .await?
+        else {
+            return Ok(None);
+        };
+        let validator: Validator<PubKey> = serde_json::from_value(data)?;
+
+        let (blocks_proposed, views_missed) = query_as::<(i64, i64)>(
+            "SELECT count(height), count(*) - count(height) FROM view_leader
+              WHERE epoch = $1 AND leader = $2",
+        )
+        .bind(epoch.u64() as i64)
+        .bind(validator.stake_table_key.to_string())
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        Ok(Some(ValidatorDetail {
+            epoch,
+            validator,
+            blocks_proposed: blocks_proposed as u64,
+            views_missed: views_missed as u64,
+        }))
+    }
+
+    async fn get_reward_history(
+        &self,
+        account: Address,
+        from: EpochNumber,
+        until: EpochNumber,
+        epoch_height: u64,
+    ) -> anyhow::Result<Vec<RewardHistoryEntry>> {
+        let mut tx = self.read().await.context("opening transaction")?;
+        let last_height = tx.get_last_state_height().await? as u64;
+
+        let mut history = vec![];
+        for epoch in from.u64()..until.u64() {
+            // Read the balance at the end of the epoch, or the latest state we have if the epoch
+            // is not over yet.
+            let end = epoch * epoch_height;
+            let height = end.min(last_height);
+            let balance = load_reward_balance(&mut tx, height, account).await?;
+            history.push(RewardHistoryEntry {
+                epoch: EpochNumber::new(epoch),
+                height,
+                balance,
+            });
+            if end >= last_height {
+                break;
+            }
+        }
+        Ok(history)
+    }
+
+    async fn get_delegator(
+        &self,
+        epoch: EpochNumber,
+        address: Address,
+    ) -> anyhow::Result<DelegatorSummary> {
+        let mut tx = self.read().await.context("opening transaction")?;
+        let delegations = load_validators(&mut tx, epoch)
+            .await?
+            .into_iter()
+            .filter_map(|validator| {
+                let stake = *validator.delegators.get(&address)?;
+                Some(Delegator {
+                    address,
+                    validator: validator.account,
+                    stake,
+                })
+            })
+            .collect();
+        let last_height = tx.get_last_state_height().await? as u64;
+        let reward_balance = load_reward_balance(&mut tx, last_height, address).await?;
+
+        Ok(DelegatorSummary {
+            address,
+            epoch,
+            delegations,
+            reward_balance,
+        })
+    }
+}
+
+async fn load_validators<Mode: TransactionMode>(
+    tx: &mut Transaction<Mode>,
+    epoch: EpochNumber,
+) -> anyhow::Result<Vec<Validator<PubKey>>> {
+    query_as::<(serde_json::Value,)>(
+        "SELECT data FROM epoch_validator WHERE epoch = $1 ORDER BY account",
+    )
+    .bind(epoch.u64() as i64)
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|(data,)| Ok(serde_json::from_value(data)?))
+    .collect()
+}
+
+async fn load_reward_balance<Mode: TransactionMode>(
+    tx: &mut Transaction<Mode>,
+    height: u64,
+    account: Address,
+) -> anyhow::Result<Option<RewardAmount>> {
+    let path =
+        MerklizedStateStorage::<SeqTypes, RewardMerkleTree, { RewardMerkleTree::ARITY }>::get_path(
+            tx,
+            Snapshot::Index(height),
+            RewardAccount(account),
+        )
+        .await
+        .context(format!("reward state not available at height {height}"))?;
+    Ok(path.elem().copied())
+}
+
+#[cfg(test)]
+mod test {
+    use hotshot_query_service::data_source::storage::sql::Write;
+    use sequencer_utils::test_utils::setup_test;
+
+    use super::*;
+    use crate::api::data_source::{testing::TestableSequencerDataSource, SequencerDataSource};
+
+    async fn insert_validators(
+        tx: &mut Transaction<Write>,
+        epoch: EpochNumber,
+        validators: &[Validator<PubKey>],
+    ) {
+        tx.upsert(
+            "epoch_validator",
+            ["epoch", "account", "stake_table_key", "data"],
+            ["epoch", "account"],
+            validators.iter().map(|validator| {
+                (
+                    epoch.u64() as i64,
+                    validator.account.to_string(),
+                    validator.stake_table_key.to_string(),
+                    serde_json::to_value(validator).unwrap(),
+                )
+            }),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_validator_performance() {
+        setup_test();
+
+        let storage = sql::DataSource::create_storage().await;
+        let ds = sql::DataSource::create(
+            sql::DataSource::persistence_options(&storage),
+            Default::default(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        let epoch = EpochNumber::new(3);
+        let validators = [Validator::mock(), Validator::mock()];
+        let key = |i: usize| validators[i].stake_table_key.to_string();
+
+        // The first validator proposes two blocks and misses a view; the second only misses a
+        // view.
+        let mut tx = ds.write().await.unwrap();
+        insert_validators(&mut tx, epoch, &validators).await;
+        tx.upsert(
+            "view_leader",
+            ["view", "epoch", "leader", "height"],
+            ["view"],
+            [
+                (1i64, 3i64, key(0), Some(1i64)),
+                (2, 3, key(1), None),
+                (3, 3, key(0), None),
+                (4, 3, key(0), Some(2)),
+            ],
+        )
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        let summaries = ds.get_validator_summaries(epoch).await.unwrap();
+        assert_eq!(summaries.len(), 2);
+        for (validator, performance) in validators.iter().zip([(2, 1), (0, 1)]) {
+            let summary = summaries
+                .iter()
+                .find(|summary| summary.account == validator.account)
+                .unwrap();
+            assert_eq!(summary.stake, validator.stake);
+            assert_eq!(summary.num_delegators, validator.delegators.len() as u64);
+            assert_eq!((summary.blocks_proposed, summary.views_missed), performance);
+        }
+
+        let detail = ds
+            .get_validator_detail(epoch, validators[1].account)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(detail.validator, validators[1]);
+        assert_eq!((detail.blocks_proposed, detail.views_missed), (0, 1));
+
+        // Nothing is known about epochs which have not been indexed.
+        let next = EpochNumber::new(4);
+        assert!(ds.get_validator_summaries(next).await.unwrap().is_empty());
+        assert!(ds
+            .get_validator_detail(next, validators[0].account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,76 @@
+//! Tracking of transactions submitted through this node.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use committable::Commitment;
+use espresso_types::{Transaction, TransactionStatus};
+
+use super::options::Submit;
+
+/// Transactions recently submitted through this node.
+///
+/// The query service can tell us when a transaction has been sequenced, but it knows nothing about
+/// transactions which are still in flight. This keeps a bounded record of submissions, so that we
+/// can report transactions as pending until they are sequenced, and as dropped if they are not
+/// sequenced within a timeout.
+#[derive(Debug)]
+pub(crate) struct SubmittedTransactions {
+    timeout: Duration,
+    capacity: usize,
+    submitted: HashMap<Commitment<Transaction>, SystemTime>,
+    // Submission order, for evicting the oldest entries when we are at capacity.
+    order: VecDeque<Commitment<Transaction>>,
+}
+
+impl Default for SubmittedTransactions {
+    fn default() -> Self {
+        Self::new(&Submit::default())
+    }
+}
+
+impl SubmittedTransactions {
+    pub(crate) fn new(opt: &Submit) -> Self {
+        Self {
+            timeout: opt.tx_timeout,
+            capacity: opt.max_tracked_txs,
+            submitted: Default::default(),
+            order: Default::default(),
+        }
+    }
+
+    /// Record that `hash` was submitted just now.
+    pub(crate) fn insert(&mut self, hash: Commitment<Transaction>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.submitted.insert(hash, SystemTime::now()).is_none() {
+            self.order.push_back(hash);
+        }
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.submitted.remove(&evicted);
+            }
+        }
+    }
+
+    /// The status of `hash`, assuming it has not been sequenced.
+    ///
+    /// Returns [`None`] if the transaction was not submitted through this node, or was submitted so
+    /// long ago that it has since been evicted.
+    pub(crate) fn status(&self, hash: Commitment<Transaction>) -> Option<TransactionStatus> {
+        let time = self.submitted.get(&hash)?;
+        let submitted_at = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let elapsed = time.elapsed().unwrap_or_default();
+        if elapsed > self.timeout {
+            Some(TransactionStatus::Dropped { submitted_at })
+        } else {
+            Some(TransactionStatus::Pending { submitted_at })
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! A signer holding a node's consensus keys in a separate process.
+//!
+//! The node connects to the signer with `--remote-signer`, and sends it votes, proposals and light
+//! client states to sign. The signer never signs two conflicting messages, even across restarts.
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Context;
+use clap::Parser;
+use hotshot_types::{light_client::StateSignKey, signature_key::BLSPrivKey};
+use sequencer::signer::{
+    read_secret,
+    server::{SignerServer, SlashingProtection},
+    SignerAddress,
+};
+use sequencer_utils::{
+    keystore::{read_password, Keystore},
+    logging,
+};
+
+#[derive(Debug, Parser)]
+struct Options {
+    /// Address to listen on, either `tcp://HOST:PORT` or `unix:PATH`.
+    #[clap(long, env = "ESPRESSO_SIGNER_LISTEN")]
+    listen: SignerAddress,
+
+    /// File containing the secret nodes must use to authenticate with the signer.
+    #[clap(long, env = "ESPRESSO_SIGNER_SECRET_FILE")]
+    secret_file: PathBuf,
+
+    /// Append-only log in which to record everything the signer has signed.
+    ///
+    /// This file must be kept across restarts of the signer, since it is what prevents the signer
+    /// from signing conflicting messages.
+    #[clap(long, env = "ESPRESSO_SIGNER_HISTORY_FILE")]
+    history_file: PathBuf,
+
+    /// Encrypted keystore containing the BLS staking key.
+    #[clap(long, env = "ESPRESSO_SIGNER_STAKING_KEYSTORE")]
+    staking_keystore: PathBuf,
+
+    /// Encrypted keystore containing the Schnorr state key.
+    #[clap(long, env = "ESPRESSO_SIGNER_STATE_KEYSTORE")]
+    state_keystore: PathBuf,
+
+    /// File containing the password for the keystores.
+    ///
+    /// If not provided, the password is read from the first line of stdin.
+    #[clap(long, env = "ESPRESSO_SIGNER_PASSWORD_FILE")]
+    password_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    logging: logging::Config,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let opt = Options::parse();
+    opt.logging.init();
+
+    let password = read_password(opt.password_file.as_deref())?;
+    let staking_key: BLSPrivKey = Keystore::load(&opt.staking_keystore)?
+        .decrypt(&password)
+        .context("decrypting staking keystore")?
+        .try_into()?;
+    let state_key: StateSignKey = Keystore::load(&opt.state_keystore)?
+        .decrypt(&password)
+        .context("decrypting state keystore")?
+        .try_into()?;
+    drop(password);
+
+    let server = SignerServer::new(
+        staking_key,
+        state_key,
+        read_secret(&opt.secret_file)?,
+        SlashingProtection::open(opt.history_file)?,
+    );
+    Arc::new(server).serve(&opt.listen).await
+}
@@ -1,6 +1,7 @@
 //! Utility program to generate keypairs
 
 use std::{
+    fmt::Display,
     fs::{self, File},
     io::Write,
     path::PathBuf,
@@ -13,8 +14,13 @@ use derive_more::Display;
 use hotshot::types::SignatureKey;
 use hotshot_types::{light_client::StateKeyPair, signature_key::BLSPubKey};
 use rand::{RngCore, SeedableRng};
-use sequencer_utils::logging;
+use sequencer_utils::{
+    keystore::{read_password, KdfFunction, Keystore},
+    logging,
+};
+use tagged_base64::TaggedBase64;
 use tracing::info_span;
+use zeroize::Zeroizing;
 
 #[derive(Clone, Copy, Debug, Display, Default, ValueEnum)]
 enum Scheme {
@@ -28,20 +34,23 @@ enum Scheme {
 }
 
 impl Scheme {
-    fn gen(self, seed: [u8; 32], index: u64, env_file: &mut impl Write) -> anyhow::Result<()> {
+    fn gen(
+        self,
+        seed: [u8; 32],
+        index: u64,
+        env_file: &mut impl Write,
+        out: &Output,
+    ) -> anyhow::Result<()> {
         match self {
             Self::All => {
-                Self::Bls.gen(seed, index, env_file)?;
-                Self::Schnorr.gen(seed, index, env_file)?;
+                Self::Bls.gen(seed, index, env_file, out)?;
+                Self::Schnorr.gen(seed, index, env_file, out)?;
             },
             Self::Bls => {
                 let (pub_key, priv_key) = BLSPubKey::generated_from_seed_indexed(seed, index);
                 let priv_key = priv_key.to_tagged_base64()?;
                 writeln!(env_file, "ESPRESSO_SEQUENCER_PUBLIC_STAKING_KEY={pub_key}")?;
-                writeln!(
-                    env_file,
-                    "ESPRESSO_SEQUENCER_PRIVATE_STAKING_KEY={priv_key}"
-                )?;
+                out.write_private_key(env_file, index, "STAKING", &priv_key, &pub_key)?;
                 tracing::info!(%pub_key, "generated staking key")
             },
             Self::Schnorr => {
@@ -52,7 +61,7 @@ impl Scheme {
                     "ESPRESSO_SEQUENCER_PUBLIC_STATE_KEY={}",
                     key_pair.ver_key()
                 )?;
-                writeln!(env_file, "ESPRESSO_SEQUENCER_PRIVATE_STATE_KEY={priv_key}")?;
+                out.write_private_key(env_file, index, "STATE", &priv_key, key_pair.ver_key())?;
                 tracing::info!(pub_key = %key_pair.ver_key(), "generated state key");
             },
         }
@@ -60,6 +69,38 @@ impl Scheme {
     }
 }
 
+/// Where and how private keys are written.
+struct Output {
+    /// The directory keys are written to.
+    dir: PathBuf,
+    /// The password and key derivation function to encrypt keys with, if they are encrypted.
+    encryption: Option<(Zeroizing<String>, KdfFunction)>,
+}
+
+impl Output {
+    /// Write the private key `name` for the setup `index`.
+    ///
+    /// Plaintext keys are written directly to the .env file. Encrypted keys are written to a
+    /// keystore file in the output directory, which the .env file refers to.
+    fn write_private_key(
+        &self,
+        env_file: &mut impl Write,
+        index: u64,
+        name: &str,
+        priv_key: &TaggedBase64,
+        pub_key: impl Display,
+    ) -> anyhow::Result<()> {
+        let Some((password, kdf)) = &self.encryption else {
+            writeln!(env_file, "ESPRESSO_SEQUENCER_PRIVATE_{name}_KEY={priv_key}")?;
+            return Ok(());
+        };
+        let file_name = format!("{index}.{}.json", name.to_lowercase());
+        Keystore::encrypt(priv_key, pub_key, password, *kdf)?.save(self.dir.join(&file_name))?;
+        writeln!(env_file, "ESPRESSO_SEQUENCER_{name}_KEYSTORE={file_name}")?;
+        Ok(())
+    }
+}
+
 /// Utility program to generate keypairs
 ///
 /// With no options, this program generates the keys needed to run a single instance of the Espresso
@@ -99,6 +140,28 @@ struct Options {
     #[arg(short, long, name = "OUT")]
     out: PathBuf,
 
+    /// Encrypt private keys with a password.
+    ///
+    /// Instead of being written to the .env files in plaintext, each private key is encrypted into
+    /// a keystore file under OUT, with names like 0.staking.json and 0.state.json, and the .env
+    /// file refers to the keystore instead. The password is read from PASSWORD_FILE, or from the
+    /// first line of stdin.
+    #[arg(long)]
+    encrypt: bool,
+
+    /// Key derivation function used to encrypt private keys.
+    #[arg(long, default_value = "scrypt", requires = "encrypt")]
+    kdf: KdfFunction,
+
+    /// File containing the password used to encrypt private keys.
+    #[arg(
+        long,
+        name = "PASSWORD_FILE",
+        env = "ESPRESSO_KEYGEN_PASSWORD_FILE",
+        requires = "encrypt"
+    )]
+    password_file: Option<PathBuf>,
+
     #[command(flatten)]
     logging: logging::Config,
 }
@@ -137,6 +200,15 @@ fn main() -> anyhow::Result<()> {
     });
     fs::write(opts.out.join(".seed"), hex::encode(seed))?;
 
+    let out = Output {
+        dir: opts.out.clone(),
+        encryption: if opts.encrypt {
+            Some((read_password(opts.password_file.as_deref())?, opts.kdf))
+        } else {
+            None
+        },
+    };
+
     for index in 0..opts.num {
         let span = info_span!("gen", index);
         let _enter = span.enter();
@@ -148,7 +220,7 @@ fn main() -> anyhow::Result<()> {
             .create(true)
             .truncate(true)
             .open(&path)?;
-        opts.scheme.gen(seed, index as u64, &mut file)?;
+        opts.scheme.gen(seed, index as u64, &mut file, &out)?;
 
         tracing::info!("private keys written to {}", path.display());
     }
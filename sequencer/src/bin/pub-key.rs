@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 use anyhow::bail;
 use clap::Parser;
@@ -8,6 +8,7 @@ use hotshot_types::{
     light_client::{StateKeyPair, StateSignKey},
     traits::signature_key::SignatureKey,
 };
+use sequencer_utils::keystore::{read_password, Keystore};
 use tagged_base64::TaggedBase64;
 
 #[derive(Clone, Debug)]
@@ -34,17 +35,40 @@ impl FromStr for PrivateKey {
 #[derive(Clone, Debug, Parser)]
 struct Options {
     /// The private key to get the public key for.
-    key: PrivateKey,
+    #[arg(required_unless_present = "keystore")]
+    key: Option<PrivateKey>,
+
+    /// Encrypted keystore containing the private key, instead of the plaintext key.
+    #[arg(long, conflicts_with = "key")]
+    keystore: Option<PathBuf>,
+
+    /// File containing the keystore password.
+    ///
+    /// If not given, the password is read from the first line of stdin.
+    #[arg(long, env = "ESPRESSO_KEYSTORE_PASSWORD_FILE", requires = "keystore")]
+    password_file: Option<PathBuf>,
 
     // Whether or not to derive the libp2p peer ID from the private key.
     #[arg(long, short)]
     libp2p: bool,
 }
 
-fn main() {
+fn main() -> anyhow::Result<()> {
     let opt = Options::parse();
 
-    match (opt.libp2p, opt.key) {
+    let key = match (opt.key, &opt.keystore) {
+        (Some(key), _) => key,
+        (None, Some(path)) => {
+            let password = read_password(opt.password_file.as_deref())?;
+            Keystore::load(path)?
+                .decrypt(&password)?
+                .to_string()
+                .parse()?
+        },
+        (None, None) => bail!("no private key given"),
+    };
+
+    match (opt.libp2p, key) {
         // Non-libp2p
         (false, PrivateKey::Bls(key)) => println!("{}", PubKey::from_private(&key)),
         (false, PrivateKey::Schnorr(key)) => {
@@ -62,4 +86,5 @@ fn main() {
             eprintln!("Key type unsupported for libp2p peer ID derivation");
         },
     }
+    Ok(())
 }
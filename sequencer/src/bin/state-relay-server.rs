@@ -1,6 +1,12 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use hotshot_types::light_client::STAKE_TABLE_CAPACITY;
-use sequencer::{state_signature::relay_server::run_relay_server, SequencerApiVersion};
+use sequencer::{
+    persistence,
+    state_signature::relay_server::{
+        run_relay_server_with_state, RelayStorage, StateRelayServerState,
+    },
+    SequencerApiVersion,
+};
 use sequencer_utils::logging;
 use url::Url;
 use vbs::version::StaticVersionType;
@@ -29,8 +35,25 @@ struct Args {
     #[arg(short, long, env = "ESPRESSO_SEQUENCER_STAKE_TABLE_CAPACITY", default_value_t = STAKE_TABLE_CAPACITY)]
     pub stake_table_capacity: usize,
 
+    /// Reject signatures from a key for the rest of an epoch once it has signed conflicting light
+    /// client states.
+    #[arg(long, env = "ESPRESSO_STATE_RELAY_SERVER_REJECT_EQUIVOCATORS")]
+    pub reject_equivocators: bool,
+
     #[command(flatten)]
     logging: logging::Config,
+
+    #[command(subcommand)]
+    storage: Option<Storage>,
+}
+
+#[derive(Subcommand)]
+enum Storage {
+    /// Persist collected signatures, stake tables and certified states in a SQL database.
+    ///
+    /// Several relay servers using the same database act as replicas of each other, and can be run
+    /// behind a load balancer.
+    StorageSql(Box<persistence::sql::Options>),
 }
 
 #[tokio::main]
@@ -40,11 +63,19 @@ async fn main() {
 
     tracing::info!(port = args.port, "starting state relay server");
 
-    run_relay_server(
-        None,
-        args.sequencer_url,
+    let mut state = StateRelayServerState::new(args.sequencer_url)
+        .with_reject_equivocators(args.reject_equivocators);
+    if let Some(Storage::StorageSql(opt)) = &args.storage {
+        let storage = RelayStorage::connect(opt)
+            .await
+            .expect("failed to connect to relay server storage");
+        state = state.with_storage(storage);
+    }
+
+    run_relay_server_with_state(
         format!("http://0.0.0.0:{}", args.port).parse().unwrap(),
         SequencerApiVersion::instance(),
+        state,
     )
     .await
     .unwrap();
@@ -3,8 +3,11 @@
 use clap::{Parser, Subcommand};
 use sequencer_utils::logging;
 mod keygen;
+mod migrate_storage;
 mod pubkey;
 mod reset_storage;
+mod slashing_protection;
+mod snapshot;
 
 #[derive(Debug, Parser)]
 struct Options {
@@ -18,9 +21,15 @@ struct Options {
 #[derive(Debug, Subcommand)]
 enum Command {
     Keygen(keygen::Options),
+    #[command(subcommand)]
+    MigrateStorage(migrate_storage::Commands),
     Pubkey(pubkey::Options),
     #[command(subcommand)]
     ResetStorage(reset_storage::Commands),
+    #[command(subcommand)]
+    SlashingProtection(slashing_protection::Commands),
+    #[command(subcommand)]
+    Snapshot(snapshot::Commands),
 }
 
 #[tokio::main]
@@ -30,10 +39,13 @@ async fn main() -> anyhow::Result<()> {
 
     match opt.command {
         Command::Keygen(opt) => keygen::run(opt),
+        Command::MigrateStorage(opt) => migrate_storage::run(opt).await,
         Command::Pubkey(opt) => {
             pubkey::run(opt);
             Ok(())
         },
         Command::ResetStorage(opt) => reset_storage::run(opt).await,
+        Command::SlashingProtection(opt) => slashing_protection::run(opt).await,
+        Command::Snapshot(opt) => snapshot::run(opt).await,
     }
 }
@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use espresso_types::{
+    traits::MembershipPersistence,
+    v0::traits::{PersistenceOptions, SequencerPersistence},
+};
+use hotshot_query_service::data_source::{sql::Config, storage::SqlStorage};
+use sequencer::persistence::{
+    self,
+    migrate::{migrate_storage, verify_storage},
+};
+
+/// Migrate consensus storage between the file system and SQL backends.
+///
+/// This copies everything a node needs to restart from one storage backend to the other, so a node
+/// can switch backends without resetting its state. Do not run this program while the sequencer is
+/// running. To move from SQLite to Postgres, first migrate from SQLite to file system storage using
+/// the SQLite build of this program, and then from file system storage to Postgres.
+#[derive(Clone, Debug, Subcommand)]
+pub enum Commands {
+    /// Migrate from file system storage to SQL storage.
+    FsToSql(Options),
+    /// Migrate from SQL storage to file system storage.
+    SqlToFs(Options),
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct Options {
+    /// Path of the file system storage.
+    #[clap(long)]
+    fs_path: PathBuf,
+
+    /// File to record progress in.
+    ///
+    /// If the migration is interrupted, running it again with the same checkpoint file resumes it
+    /// from where it left off.
+    #[clap(long)]
+    checkpoint: Option<PathBuf>,
+
+    /// Only check that the destination matches the source, without migrating anything.
+    #[clap(long, conflicts_with = "skip_verify")]
+    verify_only: bool,
+
+    /// Skip checking that the destination matches the source after the migration.
+    #[clap(long)]
+    skip_verify: bool,
+
+    /// Do not migrate the merklized state when migrating from SQL storage.
+    ///
+    /// By default, the fee, block and reward state at the anchor leaf is read from the query
+    /// service tables of the SQL database, and the migration fails if it is not available there.
+    /// With this flag the state is not migrated, and the node will catch it up from its peers after
+    /// restarting.
+    #[clap(long)]
+    skip_merklized_state: bool,
+
+    #[clap(flatten)]
+    sql: Box<persistence::sql::Options>,
+}
+
+pub async fn run(opt: Commands) -> anyhow::Result<()> {
+    match opt {
+        Commands::FsToSql(mut opt) => {
+            let src = persistence::fs::Options::new(opt.fs_path.clone())
+                .create()
+                .await?;
+            let dst = opt.sql.create().await?;
+            // File system storage has no merklized state, other than the state of an imported
+            // snapshot, which is migrated along with the anchor leaf.
+            migrate(&src, &dst, None, &opt).await
+        },
+        Commands::SqlToFs(mut opt) => {
+            let src = opt.sql.create().await?;
+            let state = if opt.skip_merklized_state {
+                None
+            } else {
+                Some(SqlStorage::connect(Config::try_from(&*opt.sql)?).await?)
+            };
+            let dst = persistence::fs::Options::new(opt.fs_path.clone())
+                .create()
+                .await?;
+            migrate(&src, &dst, state.as_ref(), &opt).await
+        },
+    }
+}
+
+async fn migrate<S, D>(
+    src: &S,
+    dst: &D,
+    state: Option<&SqlStorage>,
+    opt: &Options,
+) -> anyhow::Result<()>
+where
+    S: SequencerPersistence + MembershipPersistence,
+    D: SequencerPersistence + MembershipPersistence,
+{
+    if !opt.verify_only {
+        migrate_storage(src, dst, state, opt.checkpoint.as_deref()).await?;
+    }
+    if !opt.skip_verify {
+        verify_storage(src, dst, state).await?;
+    }
+    Ok(())
+}
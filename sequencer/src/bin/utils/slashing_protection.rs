@@ -0,0 +1,93 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use espresso_types::{
+    v0::traits::{PersistenceOptions, SequencerPersistence},
+    SlashingProtectionInterchange,
+};
+use sequencer::persistence;
+
+/// Export or import the history of messages a node has signed.
+///
+/// When moving a node to a new machine, export its signing history from the old storage and import
+/// it into the new storage before starting the node, so that the node never signs a message
+/// conflicting with one it signed on the old machine. Do not run this program while the sequencer
+/// is running.
+#[derive(Clone, Debug, Subcommand)]
+pub enum Commands {
+    /// Export the signing history to a JSON file.
+    #[command(subcommand)]
+    Export(Storage),
+    /// Import a signing history from a JSON file.
+    #[command(subcommand)]
+    Import(Storage),
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum Storage {
+    /// Use file system storage.
+    Fs(FsOptions),
+    /// Use SQL storage.
+    Sql(SqlOptions),
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct FsOptions {
+    /// The interchange file to export to or import from.
+    #[clap(long)]
+    file: PathBuf,
+
+    #[clap(flatten)]
+    storage: persistence::fs::Options,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct SqlOptions {
+    /// The interchange file to export to or import from.
+    #[clap(long)]
+    file: PathBuf,
+
+    #[clap(flatten)]
+    storage: Box<persistence::sql::Options>,
+}
+
+pub async fn run(opt: Commands) -> anyhow::Result<()> {
+    match opt {
+        Commands::Export(Storage::Fs(mut opt)) => {
+            export(opt.storage.create().await?, opt.file).await
+        },
+        Commands::Export(Storage::Sql(mut opt)) => {
+            export(opt.storage.create().await?, opt.file).await
+        },
+        Commands::Import(Storage::Fs(mut opt)) => {
+            import(opt.storage.create().await?, opt.file).await
+        },
+        Commands::Import(Storage::Sql(mut opt)) => {
+            import(opt.storage.create().await?, opt.file).await
+        },
+    }
+}
+
+async fn export(storage: impl SequencerPersistence, file: PathBuf) -> anyhow::Result<()> {
+    let interchange = storage.export_slashing_protection().await?;
+    fs::write(&file, serde_json::to_vec_pretty(&interchange)?)
+        .context(format!("writing {}", file.display()))?;
+    tracing::info!(
+        records = interchange.records.len(),
+        latest_acted_view = ?interchange.latest_acted_view,
+        "exported signing history to {}",
+        file.display()
+    );
+    Ok(())
+}
+
+async fn import(storage: impl SequencerPersistence, file: PathBuf) -> anyhow::Result<()> {
+    let bytes = fs::read(&file).context(format!("reading {}", file.display()))?;
+    let interchange: SlashingProtectionInterchange =
+        serde_json::from_slice(&bytes).context("malformed interchange file")?;
+    let records = interchange.records.len();
+    storage.import_slashing_protection(interchange).await?;
+    tracing::info!(records, "imported signing history from {}", file.display());
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use committable::Commitment;
+use espresso_types::{
+    v0::traits::{PersistenceOptions, SequencerPersistence},
+    Leaf2, StateSnapshot,
+};
+use hotshot_query_service::data_source::{sql::Config, storage::SqlStorage};
+use sequencer::{
+    persistence,
+    snapshot::{export_snapshot, import_snapshot},
+};
+use tagged_base64::TaggedBase64;
+
+/// Export and import snapshots of the full state.
+///
+/// A snapshot is exported from a node running the SQL query service, and can be imported into the
+/// storage of a new node before starting it for the first time, so that it starts from the snapshot
+/// rather than from genesis.
+#[derive(Clone, Debug, Subcommand)]
+pub enum Commands {
+    /// Export a state snapshot from SQL storage.
+    Export(ExportOptions),
+    /// Import a state snapshot into consensus storage.
+    Import(ImportOptions),
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ExportOptions {
+    /// The block height to take the snapshot at.
+    ///
+    /// If not given, the snapshot is taken at the latest height for which state is available.
+    #[clap(long)]
+    height: Option<u64>,
+
+    /// File to write the snapshot to.
+    #[clap(short, long)]
+    output: PathBuf,
+
+    #[clap(flatten)]
+    storage: persistence::sql::Options,
+}
+
+#[derive(Clone, Debug, Parser)]
+pub struct ImportOptions {
+    /// File to read the snapshot from.
+    #[clap(short, long)]
+    input: PathBuf,
+
+    /// Commitment of the snapshot leaf, obtained from a trusted node.
+    ///
+    /// The snapshot is only imported if its leaf matches this commitment. The signatures on the
+    /// snapshot are not checked, so this is what authenticates the snapshot.
+    #[clap(long)]
+    leaf_commitment: TaggedBase64,
+
+    #[command(subcommand)]
+    storage: ImportStorage,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ImportStorage {
+    /// Import into file system storage.
+    Fs(persistence::fs::Options),
+    /// Import into SQL storage.
+    Sql(Box<persistence::sql::Options>),
+}
+
+pub async fn run(opt: Commands) -> anyhow::Result<()> {
+    match opt {
+        Commands::Export(opt) => export(opt).await,
+        Commands::Import(opt) => {
+            let snapshot = StateSnapshot::load(&opt.input)?;
+            let trusted_leaf = Commitment::<Leaf2>::try_from(&opt.leaf_commitment)?;
+            match opt.storage {
+                ImportStorage::Fs(mut storage) => {
+                    import(&storage.create().await?, &snapshot, trusted_leaf).await
+                },
+                ImportStorage::Sql(mut storage) => {
+                    import(&storage.create().await?, &snapshot, trusted_leaf).await
+                },
+            }
+        },
+    }
+}
+
+async fn export(opt: ExportOptions) -> anyhow::Result<()> {
+    let storage = SqlStorage::connect(Config::try_from(&opt.storage)?).await?;
+    let snapshot = export_snapshot(&storage, opt.height).await?;
+    snapshot.save(&opt.output)?;
+    tracing::info!(
+        height = snapshot.height(),
+        leaf = %snapshot.leaf_commitment(),
+        "exported snapshot to {}",
+        opt.output.display()
+    );
+    Ok(())
+}
+
+async fn import(
+    persistence: &impl SequencerPersistence,
+    snapshot: &StateSnapshot,
+    trusted_leaf: Commitment<Leaf2>,
+) -> anyhow::Result<()> {
+    import_snapshot(persistence, snapshot, trusted_leaf).await?;
+    tracing::info!(
+        height = snapshot.height(),
+        leaf = %snapshot.leaf_commitment(),
+        "imported snapshot"
+    );
+    Ok(())
+}
@@ -18,16 +18,17 @@ use espresso_types::{
     v0_1::{RewardAccount, RewardAccountProof, RewardMerkleCommitment, RewardMerkleTree},
     v0_99::ChainConfig,
     BackoffParams, BlockMerkleTree, EpochVersion, FeeAccount, FeeAccountProof, FeeMerkleCommitment,
-    FeeMerkleTree, Leaf2, NodeState, PubKey, SeqTypes, SequencerVersions, ValidatedState,
+    FeeMerkleTree, Leaf2, NodeState, Payload, PubKey, SeqTypes, SequencerVersions, ValidatedState,
 };
 use futures::{
     future::{Future, FutureExt, TryFuture, TryFutureExt},
     stream::FuturesUnordered,
     StreamExt,
 };
+use hotshot_query_service::VidCommon;
 use hotshot_types::{
     consensus::Consensus,
-    data::ViewNumber,
+    data::{VidCommitment, ViewNumber},
     message::UpgradeLock,
     network::NetworkConfig,
     traits::{
@@ -37,7 +38,7 @@ use hotshot_types::{
         ValidatedState as ValidatedStateTrait,
     },
     utils::{verify_leaf_chain, View, ViewInner},
-    PeerConfig, ValidatorConfig,
+    PeerConfig,
 };
 use itertools::Itertools;
 use jf_merkle_tree::{prelude::MerkleNode, ForgetableMerkleTreeScheme, MerkleTreeScheme};
@@ -226,21 +227,20 @@ impl<ApiVer: StaticVersionType> StatePeers<ApiVer> {
         }
     }
 
-    #[tracing::instrument(skip(self, my_own_validator_config))]
+    #[tracing::instrument(skip(self))]
     pub async fn fetch_config(
         &self,
-        my_own_validator_config: ValidatorConfig<SeqTypes>,
+        my_own_public_key: PubKey,
     ) -> anyhow::Result<NetworkConfig<SeqTypes>> {
         self.backoff()
             .retry(self, move |provider, retry| {
-                let my_own_validator_config = my_own_validator_config.clone();
                 async move {
                     let cfg = provider
                         .fetch(retry, |client| {
                             client.get::<PublicNetworkConfig>("config/hotshot").send()
                         })
                         .await?;
-                    cfg.into_network_config(my_own_validator_config)
+                    cfg.into_network_config(my_own_public_key)
                         .context("fetched config, but failed to convert to private config")
                 }
                 .boxed()
@@ -499,6 +499,26 @@ pub(crate) trait CatchupStorage: Sync {
             bail!("leaf chain catchup is not supported for this data source");
         }
     }
+
+    /// Get the VID common data for the payload with the given commitment.
+    fn get_vid_common(
+        &self,
+        _commit: VidCommitment,
+    ) -> impl Send + Future<Output = anyhow::Result<VidCommon>> {
+        async {
+            bail!("VID common catchup is not supported for this data source");
+        }
+    }
+
+    /// Get the payload with the given commitment.
+    fn get_payload(
+        &self,
+        _commit: VidCommitment,
+    ) -> impl Send + Future<Output = anyhow::Result<Payload>> {
+        async {
+            bail!("payload catchup is not supported for this data source");
+        }
+    }
 }
 
 impl CatchupStorage for hotshot_query_service::data_source::MetricsDataSource {}
@@ -550,6 +570,14 @@ where
     async fn get_leaf_chain(&self, height: u64) -> anyhow::Result<Vec<Leaf2>> {
         self.inner().get_leaf_chain(height).await
     }
+
+    async fn get_vid_common(&self, commit: VidCommitment) -> anyhow::Result<VidCommon> {
+        self.inner().get_vid_common(commit).await
+    }
+
+    async fn get_payload(&self, commit: VidCommitment) -> anyhow::Result<Payload> {
+        self.inner().get_payload(commit).await
+    }
 }
 
 #[derive(Debug)]
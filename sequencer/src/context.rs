@@ -29,16 +29,20 @@ use hotshot_types::{
     epoch_membership::EpochMembershipCoordinator,
     light_client::compute_stake_table_commitment,
     network::NetworkConfig,
-    traits::{metrics::Metrics, network::ConnectedNetwork, node_implementation::Versions},
-    PeerConfig, ValidatorConfig,
+    traits::{
+        metrics::Metrics, network::ConnectedNetwork, node_implementation::Versions,
+        signer::ConsensusSigner,
+    },
+    PeerConfig,
 };
 use parking_lot::Mutex;
-use request_response::RequestResponseConfig;
+use request_response::{message::ProtocolVersion, RequestResponseConfig};
 use tokio::{spawn, sync::mpsc::channel, task::JoinHandle};
 use tracing::{Instrument, Level};
 use url::Url;
 
 use crate::{
+    api::EspressoNamespaces,
     catchup::ParallelStateCatchup,
     external_event_handler::ExternalEventHandler,
     proposal_fetcher::ProposalFetcherConfig,
@@ -86,7 +90,7 @@ pub struct SequencerContext<N: ConnectedNetwork<PubKey>, P: SequencerPersistence
     network_config: NetworkConfig<SeqTypes>,
 
     #[derivative(Debug = "ignore")]
-    validator_config: ValidatorConfig<SeqTypes>,
+    peer_config: PeerConfig<SeqTypes>,
 }
 
 impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> SequencerContext<N, P, V> {
@@ -94,7 +98,8 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
     #[allow(clippy::too_many_arguments)]
     pub async fn init(
         network_config: NetworkConfig<SeqTypes>,
-        validator_config: ValidatorConfig<SeqTypes>,
+        peer_config: PeerConfig<SeqTypes>,
+        signer: Arc<dyn ConsensusSigner<SeqTypes>>,
         coordinator: EpochMembershipCoordinator<SeqTypes>,
         instance_state: NodeState,
         storage: Option<Arc<SqlStorage>>,
@@ -108,9 +113,10 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
         _: V,
         marketplace_config: MarketplaceConfig<SeqTypes, Node<N, P>>,
         proposal_fetcher_cfg: ProposalFetcherConfig,
+        signed_request_responses: bool,
     ) -> anyhow::Result<Self> {
         let config = &network_config.config;
-        let pub_key = validator_config.public_key;
+        let pub_key = peer_config.stake_table_entry.stake_key;
         tracing::info!(%pub_key, "initializing consensus");
 
         // Stick our node ID in `metrics` so it is easily accessible via the status API.
@@ -130,15 +136,14 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
             compute_stake_table_commitment(&config.known_nodes_with_stake, stake_table_capacity)?;
         let stake_table_epoch = None;
 
-        let event_streamer = Arc::new(RwLock::new(EventsStreamer::<SeqTypes>::new(
-            config.known_nodes_with_stake.clone(),
-            0,
-        )));
+        let event_streamer = Arc::new(RwLock::new(
+            EventsStreamer::<SeqTypes>::new(config.known_nodes_with_stake.clone(), 0)
+                .with_namespace_source(EspressoNamespaces),
+        ));
 
-        let handle = SystemContext::init(
-            validator_config.public_key,
-            validator_config.private_key.clone(),
-            validator_config.state_private_key.clone(),
+        let handle = SystemContext::new_with_signer(
+            pub_key,
+            Arc::clone(&signer),
             instance_state.node_id,
             config.clone(),
             coordinator.clone(),
@@ -148,12 +153,13 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
             Arc::clone(&persistence),
             marketplace_config,
         )
-        .await?
-        .0;
+        .await
+        .run_tasks()
+        .await;
 
         let mut state_signer = StateSigner::new(
-            validator_config.state_private_key.clone(),
-            validator_config.state_public_key.clone(),
+            Arc::clone(&signer),
+            peer_config.state_ver_key.clone(),
             stake_table_commit,
             stake_table_epoch,
             stake_table_capacity,
@@ -175,6 +181,9 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
             max_outgoing_responses: 20,
             response_validate_timeout: Duration::from_secs(1),
             max_incoming_responses: 20,
+            peer_response_timeout: Duration::from_secs(10),
+            peer_ban_duration: Duration::from_secs(300),
+            peer_ban_threshold: 0.2,
         };
 
         // Create the request-response protocol
@@ -183,9 +192,9 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
             RequestResponseSender::new(outbound_message_sender),
             request_response_receiver,
             RecipientSource {
-                memberships: coordinator,
+                memberships: coordinator.clone(),
                 consensus: handle.hotshot.clone(),
-                public_key: validator_config.public_key,
+                public_key: pub_key,
             },
             DataSource {
                 node_state: instance_state.clone(),
@@ -193,8 +202,10 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
                 consensus: handle.hotshot.clone(),
                 phantom: PhantomData,
             },
-            validator_config.public_key,
-            validator_config.private_key.clone(),
+            pub_key,
+            signer,
+            coordinator,
+            metrics,
         );
 
         // Add the request-response protocol to the list of providers for state catchup. Since the interior is mutable,
@@ -202,6 +213,12 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
         // itself)
         state_catchup.add_provider(Arc::new(request_response_protocol.clone()));
 
+        // Until every node in the network can answer requests for signed responses, we keep
+        // making version 1 requests, which nodes that have not been upgraded yet understand
+        if signed_request_responses {
+            request_response_protocol.set_request_version(ProtocolVersion::V2);
+        }
+
         // Create the external event handler
         let mut tasks = TaskList::default();
         let external_event_handler = ExternalEventHandler::<V>::new(
@@ -224,7 +241,7 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
             event_streamer,
             instance_state,
             network_config,
-            validator_config,
+            peer_config,
             event_consumer,
             anchor_view,
             proposal_fetcher_cfg,
@@ -244,7 +261,7 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
         event_streamer: Arc<RwLock<EventsStreamer<SeqTypes>>>,
         node_state: NodeState,
         network_config: NetworkConfig<SeqTypes>,
-        validator_config: ValidatorConfig<SeqTypes>,
+        peer_config: PeerConfig<SeqTypes>,
         event_consumer: impl PersistenceEventConsumer + 'static,
         anchor_view: Option<ViewNumber>,
         proposal_fetcher_cfg: ProposalFetcherConfig,
@@ -263,7 +280,7 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
             events_streamer: event_streamer.clone(),
             node_state,
             network_config,
-            validator_config,
+            peer_config,
         };
 
         // Spawn proposal fetching tasks.
@@ -330,6 +347,11 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
         Arc::clone(&self.handle)
     }
 
+    /// Return a handle to the request-response protocol used to fetch data from peers.
+    pub fn request_response_protocol(&self) -> RequestResponseProtocol<Node<N, P>, V, N, P> {
+        self.request_response_protocol.clone()
+    }
+
     pub async fn shutdown_consensus(&self) {
         self.handle.write().await.shut_down().await
     }
@@ -358,7 +380,7 @@ impl<N: ConnectedNetwork<PubKey>, P: SequencerPersistence, V: Versions> Sequence
     pub async fn start_consensus(&self) {
         if let Some(orchestrator_client) = &self.wait_for_orchestrator {
             tracing::warn!("waiting for orchestrated start");
-            let peer_config = PeerConfig::to_bytes(&self.validator_config.public_config()).clone();
+            let peer_config = PeerConfig::to_bytes(&self.peer_config).clone();
             orchestrator_client
                 .wait_for_all_nodes_ready(peer_config)
                 .await;
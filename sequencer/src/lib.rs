@@ -7,6 +7,7 @@ mod request_response;
 
 mod external_event_handler;
 pub mod options;
+pub mod signer;
 pub mod state_signature;
 
 mod restart_tests;
@@ -21,7 +22,7 @@ use async_lock::{Mutex, RwLock};
 use catchup::{ParallelStateCatchup, StatePeers};
 use context::SequencerContext;
 use espresso_types::{
-    traits::{EventConsumer, MembershipPersistence},
+    traits::{EventConsumer, L1Persistence, MembershipPersistence},
     v0_3::StakeTableFetcher,
     BackoffParams, EpochCommittees, L1ClientOptions, NodeState, PubKey, SeqTypes,
     SolverAuctionResultsProvider, ValidatedState,
@@ -37,6 +38,7 @@ use tokio::select;
 use tracing::info;
 use url::Url;
 pub mod persistence;
+pub mod snapshot;
 pub mod state;
 use std::{fmt::Debug, marker::PhantomData, time::Duration};
 
@@ -45,30 +47,27 @@ use espresso_types::v0::traits::SequencerPersistence;
 pub use genesis::Genesis;
 use hotshot::{
     traits::implementations::{
-        derive_libp2p_multiaddr, derive_libp2p_peer_id, CdnMetricsValue, CdnTopic,
-        CombinedNetworks, GossipConfig, KeyPair, Libp2pNetwork, MemoryNetwork, PushCdnNetwork,
-        RequestResponseConfig, WrappedSignatureKey,
+        derive_libp2p_multiaddr, CdnMetricsValue, CdnTopic, CombinedNetworks, GossipConfig,
+        KeyPair, Libp2pNetwork, MemoryNetwork, PushCdnNetwork, RequestResponseConfig,
+        WrappedSignatureKey,
     },
-    types::SignatureKey,
     MarketplaceConfig,
 };
-use hotshot_orchestrator::client::{get_complete_config, OrchestratorClient};
+use hotshot_orchestrator::client::{get_complete_config_for_peer, OrchestratorClient};
 use hotshot_types::{
     data::ViewNumber,
     epoch_membership::EpochMembershipCoordinator,
-    light_client::{StateKeyPair, StateSignKey},
-    signature_key::{BLSPrivKey, BLSPubKey},
     traits::{
         metrics::{Metrics, NoMetrics},
         network::ConnectedNetwork,
-        node_implementation::{NodeImplementation, NodeType, Versions},
+        node_implementation::{NodeImplementation, Versions},
         storage::{storage_add_drb_result, Storage},
     },
     utils::BuilderCommitment,
-    ValidatorConfig,
 };
 pub use options::Options;
 use serde::{Deserialize, Serialize};
+use signer::NodeKeys;
 use vbs::version::{StaticVersion, StaticVersionType};
 pub mod network;
 
@@ -112,8 +111,8 @@ pub struct NetworkParams {
     pub cdn_endpoint: String,
     pub orchestrator_url: Url,
     pub state_relay_server_url: Url,
-    pub private_staking_key: BLSPrivKey,
-    pub private_state_key: StateSignKey,
+    /// The node's keys, and the signer holding its private keys
+    pub keys: NodeKeys,
     pub state_peers: Vec<Url>,
     pub config_peers: Option<Vec<Url>>,
     pub catchup_backoff: BackoffParams,
@@ -186,6 +185,9 @@ pub struct NetworkParams {
 
     /// Minimum number of Libp2p peers to emit gossip to during a heartbeat
     pub libp2p_gossip_lazy: usize,
+
+    /// Whether to request signed responses from peers when fetching missing data
+    pub signed_request_responses: bool,
 }
 
 pub struct L1Params {
@@ -194,7 +196,10 @@ pub struct L1Params {
 }
 
 #[allow(clippy::too_many_arguments)]
-pub async fn init_node<P: SequencerPersistence + MembershipPersistence, V: Versions>(
+pub async fn init_node<
+    P: SequencerPersistence + MembershipPersistence + L1Persistence,
+    V: Versions,
+>(
     genesis: Genesis,
     network_params: NetworkParams,
     metrics: &dyn Metrics,
@@ -267,7 +272,8 @@ where
         ]);
 
     // Stick our public key in `metrics` so it is easily accessible via the status API.
-    let pub_key = BLSPubKey::from_private(&network_params.private_staking_key);
+    let keys = network_params.keys;
+    let pub_key = keys.public_key;
     metrics
         .text_family("node".into(), vec!["key".into()])
         .create(vec![pub_key.to_string()]);
@@ -293,21 +299,10 @@ where
 
     // Orchestrator client
     let orchestrator_client = OrchestratorClient::new(network_params.orchestrator_url);
-    let state_key_pair = StateKeyPair::from_sign_key(network_params.private_state_key);
-    let validator_config = ValidatorConfig {
-        public_key: pub_key,
-        private_key: network_params.private_staking_key,
-        stake_value: U256::ONE,
-        state_public_key: state_key_pair.ver_key(),
-        state_private_key: state_key_pair.sign_key(),
-        is_da,
-    };
+    let peer_config = keys.peer_config(U256::ONE);
 
-    // Derive our Libp2p public key from our private key
-    let libp2p_public_key = derive_libp2p_peer_id::<<SeqTypes as NodeType>::SignatureKey>(
-        &validator_config.private_key,
-    )
-    .with_context(|| "Failed to derive Libp2p peer ID")?;
+    // Our Libp2p identity is derived from our staking key
+    let libp2p_public_key = keys.libp2p_keypair.public().to_peer_id();
 
     // Print the libp2p public key
     info!("Starting Libp2p with PeerID: {}", libp2p_public_key);
@@ -328,7 +323,7 @@ where
                 network_params.catchup_backoff,
                 &NoMetrics,
             );
-            let config = peers.fetch_config(validator_config.clone()).await?;
+            let config = peers.fetch_config(pub_key).await?;
 
             tracing::info!(
                 node_id = config.node_index,
@@ -344,9 +339,10 @@ where
             tracing::error!(
                 "waiting for other nodes to connect, DO NOT RESTART until fully connected"
             );
-            let config = get_complete_config(
+            let config = get_complete_config_for_peer(
                 &orchestrator_client,
-                validator_config.clone(),
+                &peer_config,
+                is_da,
                 // Register in our Libp2p advertise address and public key so other nodes
                 // can contact us on startup
                 Some(libp2p_advertise_address),
@@ -413,8 +409,8 @@ where
         network_params.cdn_endpoint,
         topics,
         KeyPair {
-            public_key: WrappedSignatureKey(validator_config.public_key),
-            private_key: validator_config.private_key.clone(),
+            public_key: WrappedSignatureKey(pub_key),
+            private_key: keys.cdn_signing_key(),
         },
         CdnMetricsValue::new(metrics),
     )
@@ -454,7 +450,8 @@ where
         .options
         .with_metrics(metrics)
         .connect(l1_params.urls)
-        .with_context(|| "failed to create L1 client")?;
+        .with_context(|| "failed to create L1 client")?
+        .with_persistence(persistence.clone());
     genesis.validate_fee_contract(&l1_client).await?;
 
     l1_client.spawn_tasks().await;
@@ -549,10 +546,9 @@ where
             gossip_config,
             request_response_config,
             libp2p_bind_address,
-            &validator_config.public_key,
-            // We need the private key so we can derive our Libp2p keypair
-            // (using https://docs.rs/blake3/latest/blake3/fn.derive_key.html)
-            &validator_config.private_key,
+            &pub_key,
+            keys.libp2p_keypair.clone(),
+            keys.signer.as_ref(),
             hotshot::traits::implementations::Libp2pMetricsValue::new(metrics),
         )
         .await
@@ -583,7 +579,8 @@ where
 
     let mut ctx = SequencerContext::init(
         network_config,
-        validator_config,
+        peer_config,
+        keys.signer,
         coordinator,
         instance_state,
         storage,
@@ -597,6 +594,7 @@ where
         seq_versions,
         marketplace_config,
         proposal_fetcher_config,
+        network_params.signed_request_responses,
     )
     .await?;
     if wait_for_orchestrator {
@@ -665,10 +663,12 @@ pub mod testing {
     use hotshot_types::{
         event::LeafInfo,
         light_client::StateKeyPair,
-        signature_key::BLSKeyPair,
+        signature_key::{BLSKeyPair, BLSPrivKey},
         traits::{
-            block_contents::BlockHeader, metrics::NoMetrics, network::Topic,
-            signature_key::BuilderSignatureKey,
+            block_contents::BlockHeader,
+            metrics::NoMetrics,
+            network::Topic,
+            signature_key::{BuilderSignatureKey, SignatureKey},
         },
         HotShotConfig, PeerConfig,
     };
@@ -1217,15 +1217,12 @@ pub mod testing {
             let my_peer_config = &config.known_nodes_with_stake[i];
             let is_da = config.known_da_nodes.contains(my_peer_config);
 
-            // Create our own (private, local) validator config
-            let validator_config = ValidatorConfig {
-                public_key: my_peer_config.stake_table_entry.stake_key,
-                private_key: self.priv_keys[i].clone(),
-                stake_value: my_peer_config.stake_table_entry.stake_amount,
-                state_public_key: self.state_key_pairs[i].ver_key(),
-                state_private_key: self.state_key_pairs[i].sign_key(),
-                is_da,
-            };
+            // Sign with our own (private, local) keys
+            let signer = Arc::new(hotshot_types::traits::signer::LocalSigner::<SeqTypes>::new(
+                self.priv_keys[i].clone(),
+                self.state_key_pairs[i].sign_key(),
+            ));
+            let peer_config = my_peer_config.clone();
 
             let topics = if is_da {
                 vec![Topic::Global, Topic::Da]
@@ -1281,7 +1278,8 @@ pub mod testing {
             };
             let l1_client = l1_opt
                 .connect(vec![self.l1_url.clone()])
-                .expect("failed to create L1 client");
+                .expect("failed to create L1 client")
+                .with_persistence(persistence.clone());
             l1_client.spawn_tasks().await;
 
             let fetcher = StakeTableFetcher::new(
@@ -1335,7 +1333,8 @@ pub mod testing {
                     // the base consensus config does not matter.
                     ..Default::default()
                 },
-                validator_config,
+                peer_config,
+                signer,
                 coordinator,
                 node_state,
                 storage,
@@ -1352,6 +1351,7 @@ pub mod testing {
                     fallback_builder_url: marketplace_builder_url,
                 },
                 Default::default(),
+                true,
             )
             .await
             .unwrap()
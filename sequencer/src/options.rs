@@ -6,7 +6,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Formatter},
     iter::once,
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::Duration,
 };
 
@@ -17,11 +17,17 @@ use espresso_types::{parse_duration, BackoffParams, L1ClientOptions};
 use hotshot_types::{light_client::StateSignKey, signature_key::BLSPrivKey};
 use jf_signature::{bls_over_bn254, schnorr};
 use libp2p::Multiaddr;
-use sequencer_utils::logging;
+use sequencer_utils::{
+    keystore::{read_password, Keystore},
+    logging,
+};
 use tagged_base64::TaggedBase64;
 use url::Url;
+use zeroize::Zeroizing;
 
-use crate::{api, persistence, proposal_fetcher::ProposalFetcherConfig};
+use crate::{
+    api, persistence, proposal_fetcher::ProposalFetcherConfig, signer::RemoteSignerOptions,
+};
 
 // This options struct is a bit unconventional. The sequencer has multiple optional modules which
 // can be added, in any combination, to the service. These include, for example, the API server.
@@ -294,6 +300,9 @@ pub struct Options {
     /// * ESPRESSO_SEQUENCER_PRIVATE_STAKING_KEY
     /// * ESPRESSO_SEQUENCER_PRIVATE_STATE_KEY
     ///
+    /// Either key can instead be given as the path of an encrypted keystore, relative to the key
+    /// file, using ESPRESSO_SEQUENCER_STAKING_KEYSTORE or ESPRESSO_SEQUENCER_STATE_KEYSTORE.
+    ///
     /// Appropriate key files can be generated with the `keygen` utility program.
     #[arg(long, name = "KEY_FILE", env = "ESPRESSO_SEQUENCER_KEY_FILE")]
     pub key_file: Option<PathBuf>,
@@ -320,6 +329,42 @@ pub struct Options {
     #[derivative(Debug = "ignore")]
     pub private_state_key: Option<TaggedBase64>,
 
+    /// Encrypted keystore containing the private staking key.
+    ///
+    /// This can be used as an alternative to KEY_FILE or the plaintext private staking key.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_STAKING_KEYSTORE",
+        conflicts_with_all = ["KEY_FILE", "private_staking_key"]
+    )]
+    pub staking_keystore: Option<PathBuf>,
+
+    /// Encrypted keystore containing the private state signing key.
+    ///
+    /// This can be used as an alternative to KEY_FILE or the plaintext private state key.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_STATE_KEYSTORE",
+        conflicts_with_all = ["KEY_FILE", "private_state_key"]
+    )]
+    pub state_keystore: Option<PathBuf>,
+
+    /// Password for encrypted keystores.
+    ///
+    /// If neither this nor a password file is given, the password is read from the first line of
+    /// stdin when a keystore needs to be decrypted.
+    #[arg(long, env = "ESPRESSO_SEQUENCER_KEYSTORE_PASSWORD")]
+    #[derivative(Debug = "ignore")]
+    pub keystore_password: Option<String>,
+
+    /// File containing the password for encrypted keystores.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_KEYSTORE_PASSWORD_FILE",
+        conflicts_with = "keystore_password"
+    )]
+    pub keystore_password_file: Option<PathBuf>,
+
     /// Add optional modules to the service.
     ///
     /// Modules are added by specifying the name of the module followed by it's arguments, as in
@@ -355,6 +400,14 @@ pub struct Options {
     #[arg(long, env = "ESPRESSO_SEQUENCER_IS_DA", action)]
     pub is_da: bool,
 
+    /// Request signed responses from peers when fetching missing data.
+    ///
+    /// Signed responses let this node hold peers accountable for what they send, but nodes which
+    /// have not been upgraded cannot answer requests for them. Only enable this once every node in
+    /// the network has been upgraded.
+    #[arg(long, env = "ESPRESSO_SEQUENCER_SIGNED_REQUEST_RESPONSES", action)]
+    pub signed_request_responses: bool,
+
     /// Peer nodes use to fetch missing state
     #[arg(long, env = "ESPRESSO_SEQUENCER_STATE_PEERS", value_delimiter = ',')]
     #[derivative(Debug(format_with = "fmt_urls"))]
@@ -380,6 +433,9 @@ pub struct Options {
     #[command(flatten)]
     pub identity: Identity,
 
+    #[command(flatten)]
+    pub remote_signer: RemoteSignerOptions,
+
     #[command(flatten)]
     pub proposal_fetcher_config: ProposalFetcherConfig,
 }
@@ -390,33 +446,69 @@ impl Options {
     }
 
     pub fn private_keys(&self) -> anyhow::Result<(BLSPrivKey, StateSignKey)> {
+        let mut password = None;
         if let Some(path) = &self.key_file {
             let vars = dotenvy::from_path_iter(path)?.collect::<Result<HashMap<_, _>, _>>()?;
-            let staking = TaggedBase64::parse(
-                vars.get("ESPRESSO_SEQUENCER_PRIVATE_STAKING_KEY")
-                    .context("key file missing ESPRESSO_SEQUENCER_PRIVATE_STAKING_KEY")?,
-            )?
-            .try_into()?;
-
-            let state = TaggedBase64::parse(
-                vars.get("ESPRESSO_SEQUENCER_PRIVATE_STATE_KEY")
-                    .context("key file missing ESPRESSO_SEQUENCER_PRIVATE_STATE_KEY")?,
-            )?
-            .try_into()?;
+            let dir = path.parent().unwrap_or(Path::new(""));
+            let mut key_from_file = |name: &str| -> anyhow::Result<TaggedBase64> {
+                if let Some(key) = vars.get(&format!("ESPRESSO_SEQUENCER_PRIVATE_{name}_KEY")) {
+                    Ok(TaggedBase64::parse(key)?)
+                } else if let Some(keystore) =
+                    vars.get(&format!("ESPRESSO_SEQUENCER_{name}_KEYSTORE"))
+                {
+                    self.decrypt_keystore(&dir.join(keystore), &mut password)
+                } else {
+                    bail!("key file missing ESPRESSO_SEQUENCER_PRIVATE_{name}_KEY")
+                }
+            };
+            let staking = key_from_file("STAKING")?.try_into()?;
+            let state = key_from_file("STATE")?.try_into()?;
 
             Ok((staking, state))
-        } else if let (Some(staking), Some(state)) = (
-            self.private_staking_key.clone(),
-            self.private_state_key.clone(),
-        ) {
+        } else {
+            let staking = match (&self.private_staking_key, &self.staking_keystore) {
+                (Some(key), _) => key.clone(),
+                (None, Some(path)) => self.decrypt_keystore(path, &mut password)?,
+                (None, None) => {
+                    bail!("neither key file nor full set of private keys was provided")
+                },
+            };
+            let state = match (&self.private_state_key, &self.state_keystore) {
+                (Some(key), _) => key.clone(),
+                (None, Some(path)) => self.decrypt_keystore(path, &mut password)?,
+                (None, None) => {
+                    bail!("neither key file nor full set of private keys was provided")
+                },
+            };
             let staking = bls_over_bn254::SignKey::try_from(staking)?;
             let state = schnorr::SignKey::try_from(state)?;
 
             Ok((staking, state))
-        } else {
-            bail!("neither key file nor full set of private keys was provided")
         }
     }
+
+    /// Decrypt the keystore at `path`.
+    ///
+    /// The password is taken from `password` if it has already been read, so that a password read
+    /// from stdin is only prompted for once.
+    fn decrypt_keystore(
+        &self,
+        path: &Path,
+        password: &mut Option<Zeroizing<String>>,
+    ) -> anyhow::Result<TaggedBase64> {
+        let pw = match password.take() {
+            Some(pw) => pw,
+            None => match &self.keystore_password {
+                Some(pw) => Zeroizing::new(pw.clone()),
+                None => read_password(self.keystore_password_file.as_deref())?,
+            },
+        };
+        let key = Keystore::load(path)
+            .and_then(|keystore| keystore.decrypt(&pw))
+            .context(format!("decrypting keystore {}", path.display()));
+        *password = Some(pw);
+        key
+    }
 }
 
 /// Identity represents identifying information concerning the sequencer node.
@@ -12,6 +12,7 @@ use async_trait::async_trait;
 use espresso_types::v0_99::ChainConfig;
 
 pub mod fs;
+pub mod migrate;
 pub mod no_storage;
 pub mod sql;
 
@@ -24,14 +25,16 @@ pub trait ChainConfigPersistence: Sized + Send + Sync {
 mod testing {
 
     use espresso_types::{
-        traits::MembershipPersistence,
+        traits::{L1Persistence, MembershipPersistence},
         v0::traits::{PersistenceOptions, SequencerPersistence},
     };
 
     use super::*;
     #[allow(dead_code)]
     #[async_trait]
-    pub trait TestablePersistence: SequencerPersistence + MembershipPersistence {
+    pub trait TestablePersistence:
+        SequencerPersistence + MembershipPersistence + L1Persistence
+    {
         type Storage: Sync;
 
         async fn tmp_storage() -> Self::Storage;
@@ -48,14 +51,16 @@ mod testing {
 mod persistence_tests {
     use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
 
+    use alloy::primitives::{Address, B256, U256};
     use anyhow::bail;
     use async_lock::RwLock;
     use committable::{Commitment, Committable};
     use espresso_types::{
-        traits::{EventConsumer, NullEventConsumer, PersistenceOptions},
+        traits::{EventConsumer, L1Persistence, NullEventConsumer, PersistenceOptions},
         v0_3::{StakeTableFetcher, Validator},
-        Event, L1Client, Leaf, Leaf2, NodeState, PubKey, SeqTypes, SequencerVersions,
-        ValidatedState,
+        Event, FeeAccount, FeeAmount, FeeInfo, L1BlockInfo, L1Client, Leaf, Leaf2, NodeState,
+        PubKey, SeqTypes, SequencerVersions, SignedActionRecord, SlashingProtectionInterchange,
+        StateSnapshot, ValidatedState,
     };
     use futures::{future::join_all, StreamExt, TryStreamExt};
     use hotshot::{
@@ -79,6 +84,7 @@ mod persistence_tests {
         traits::{
             block_contents::BlockHeader,
             node_implementation::{ConsensusTime, Versions},
+            signer::SignedMessageKind,
             EncodeBytes,
         },
         utils::EpochTransitionIndicator,
@@ -86,6 +92,7 @@ mod persistence_tests {
         vote::HasViewNumber,
     };
     use indexmap::IndexMap;
+    use jf_merkle_tree::UniversalMerkleTreeScheme;
     use portpicker::pick_unused_port;
     use sequencer_utils::test_utils::setup_test;
     use surf_disco::Client;
@@ -175,6 +182,237 @@ mod persistence_tests {
         );
     }
 
+    fn signed_action(kind: SignedMessageKind, view: u64, commitment: u8) -> SignedActionRecord {
+        SignedActionRecord {
+            kind,
+            view: ViewNumber::new(view),
+            epoch: Some(EpochNumber::new(1)),
+            commitment: B256::repeat_byte(commitment),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub async fn test_signed_actions<P: TestablePersistence>() {
+        setup_test();
+
+        let tmp = P::tmp_storage().await;
+        let storage = P::connect(&tmp).await;
+        assert_eq!(storage.load_signed_actions().await.unwrap(), vec![]);
+
+        // Record a vote; recording the same vote again is harmless.
+        let vote = signed_action(SignedMessageKind::QuorumVote, 1, 1);
+        storage.record_signed_action(vote).await.unwrap();
+        storage.record_signed_action(vote).await.unwrap();
+        assert_eq!(
+            storage.load_latest_acted_view().await.unwrap(),
+            Some(ViewNumber::new(1))
+        );
+
+        // Other kinds of messages may be signed in the same view, but DA votes do not count as
+        // acting in the view.
+        let da_vote = signed_action(SignedMessageKind::DaVote, 2, 2);
+        storage.record_signed_action(da_vote).await.unwrap();
+        assert_eq!(
+            storage.load_latest_acted_view().await.unwrap(),
+            Some(ViewNumber::new(1))
+        );
+
+        // A timeout vote is a different kind of message from a quorum vote in the same view.
+        let timeout_vote = signed_action(SignedMessageKind::TimeoutVote, 1, 2);
+        storage.record_signed_action(timeout_vote).await.unwrap();
+
+        // View sync votes for different data may be signed in the same view.
+        let view_sync1 = signed_action(SignedMessageKind::ViewSyncVote, 3, 1);
+        let view_sync2 = signed_action(SignedMessageKind::ViewSyncVote, 3, 2);
+        storage.record_signed_action(view_sync1).await.unwrap();
+        storage.record_signed_action(view_sync2).await.unwrap();
+
+        // A conflicting vote is refused, even after a restart.
+        let conflict = signed_action(SignedMessageKind::QuorumVote, 1, 2);
+        storage.record_signed_action(conflict).await.unwrap_err();
+        drop(storage);
+        let storage = P::connect(&tmp).await;
+        storage.record_signed_action(conflict).await.unwrap_err();
+
+        // Of two conflicting votes recorded concurrently, exactly one is accepted.
+        let (res1, res2) = futures::join!(
+            storage.record_signed_action(signed_action(SignedMessageKind::QuorumVote, 4, 1)),
+            storage.record_signed_action(signed_action(SignedMessageKind::QuorumVote, 4, 2)),
+        );
+        assert!(res1.is_ok() != res2.is_ok(), "{res1:?} {res2:?}");
+        let concurrent = if res1.is_ok() {
+            signed_action(SignedMessageKind::QuorumVote, 4, 1)
+        } else {
+            signed_action(SignedMessageKind::QuorumVote, 4, 2)
+        };
+
+        let mut records = storage.load_signed_actions().await.unwrap();
+        records.sort_by_key(|record| (record.view, record.commitment));
+        assert_eq!(
+            records,
+            vec![
+                vote,
+                timeout_vote,
+                da_vote,
+                view_sync1,
+                view_sync2,
+                concurrent
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub async fn test_slashing_protection_interchange<P: TestablePersistence>() {
+        setup_test();
+
+        let tmp = P::tmp_storage().await;
+        let storage = P::connect(&tmp).await;
+        let vote = signed_action(SignedMessageKind::QuorumVote, 5, 1);
+        let proposal = signed_action(SignedMessageKind::QuorumProposal, 6, 1);
+        storage.record_signed_action(vote).await.unwrap();
+        storage.record_signed_action(proposal).await.unwrap();
+
+        let interchange = storage.export_slashing_protection().await.unwrap();
+        assert_eq!(
+            interchange,
+            SlashingProtectionInterchange::new(Some(ViewNumber::new(6)), [vote, proposal])
+        );
+
+        // Import the history into storage on a new machine.
+        let new_tmp = P::tmp_storage().await;
+        let new_storage = P::connect(&new_tmp).await;
+        let other = signed_action(SignedMessageKind::DaVote, 4, 1);
+        new_storage.record_signed_action(other).await.unwrap();
+        new_storage
+            .import_slashing_protection(interchange.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            new_storage.load_latest_acted_view().await.unwrap(),
+            Some(ViewNumber::new(6))
+        );
+        assert_eq!(
+            new_storage.load_signed_actions().await.unwrap(),
+            vec![other, vote, proposal]
+        );
+
+        // The imported history prevents conflicting messages from being signed.
+        new_storage
+            .record_signed_action(signed_action(SignedMessageKind::QuorumVote, 5, 2))
+            .await
+            .unwrap_err();
+
+        // Importing a history which conflicts with the local history fails, and imports nothing.
+        let conflicting = SlashingProtectionInterchange::new(
+            Some(ViewNumber::new(7)),
+            [
+                signed_action(SignedMessageKind::QuorumProposal, 7, 1),
+                signed_action(SignedMessageKind::QuorumProposal, 6, 2),
+            ],
+        );
+        storage
+            .import_slashing_protection(conflicting)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            storage.load_latest_acted_view().await.unwrap(),
+            Some(ViewNumber::new(6))
+        );
+        assert_eq!(
+            storage.load_signed_actions().await.unwrap(),
+            vec![vote, proposal]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub async fn test_l1_cache<P: TestablePersistence>() {
+        setup_test();
+
+        let tmp = P::tmp_storage().await;
+        let storage = P::connect(&tmp).await;
+
+        // Finalized blocks.
+        assert_eq!(storage.load_finalized_l1_block(1).await.unwrap(), None);
+        let block = L1BlockInfo {
+            number: 1,
+            timestamp: U256::from(12),
+            hash: B256::repeat_byte(1),
+        };
+        storage
+            .store_finalized_l1_block(block, B256::repeat_byte(0))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.load_finalized_l1_block(1).await.unwrap(),
+            Some((block, B256::repeat_byte(0)))
+        );
+        assert_eq!(storage.load_finalized_l1_block(2).await.unwrap(), None);
+
+        // Deposits can be loaded for any range of blocks which has been scanned, no matter how the
+        // blocks were divided when they were stored.
+        let contract = Address::repeat_byte(1);
+        let deposits = vec![
+            FeeInfo::new(Address::repeat_byte(2), 1),
+            FeeInfo::new(Address::repeat_byte(3), 2),
+        ];
+        assert_eq!(
+            storage.load_l1_deposits(contract, 1, 10).await.unwrap(),
+            None
+        );
+        storage
+            .store_l1_deposits(contract, 1, 10, &[(2, deposits[0]), (5, deposits[1])])
+            .await
+            .unwrap();
+        storage
+            .store_l1_deposits(contract, 11, 20, &[])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.load_l1_deposits(contract, 1, 10).await.unwrap(),
+            Some(deposits.clone())
+        );
+        assert_eq!(
+            storage.load_l1_deposits(contract, 1, 20).await.unwrap(),
+            Some(deposits.clone())
+        );
+        assert_eq!(
+            storage.load_l1_deposits(contract, 3, 15).await.unwrap(),
+            Some(vec![deposits[1]])
+        );
+        assert_eq!(
+            storage.load_l1_deposits(contract, 11, 20).await.unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(
+            storage.load_l1_deposits(contract, 1, 21).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage
+                .load_l1_deposits(Address::repeat_byte(4), 1, 10)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // Pruning forgets blocks and deposits below the given height.
+        storage.prune_l1_cache(5).await.unwrap();
+        assert_eq!(storage.load_finalized_l1_block(1).await.unwrap(), None);
+        assert_eq!(
+            storage.load_l1_deposits(contract, 1, 20).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            storage.load_l1_deposits(contract, 5, 20).await.unwrap(),
+            Some(vec![deposits[1]])
+        );
+        storage.prune_l1_cache(21).await.unwrap();
+        assert_eq!(
+            storage.load_l1_deposits(contract, 20, 20).await.unwrap(),
+            None
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     pub async fn test_epoch_info<P: TestablePersistence>() {
         setup_test();
@@ -1446,4 +1684,217 @@ mod persistence_tests {
 
         Ok(())
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub async fn test_import_snapshot<P: TestablePersistence>() {
+        setup_test();
+
+        let tmp = P::tmp_storage().await;
+        let storage = P::connect(&tmp).await;
+        assert_eq!(storage.load_snapshot_state().await.unwrap(), None);
+
+        let instance_state = NodeState::mock();
+        let validated_state = hotshot_types::traits::ValidatedState::genesis(&instance_state).0;
+        let leaf = Leaf2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let qc =
+            QuorumCertificate2::genesis::<TestVersions>(&validated_state, &instance_state).await;
+        let snapshot = StateSnapshot {
+            leaf: leaf.clone(),
+            qc: qc.clone(),
+            fee_merkle_tree: validated_state.fee_merkle_tree.clone(),
+            reward_merkle_tree: validated_state.reward_merkle_tree.clone(),
+            block_merkle_tree: validated_state.block_merkle_tree.clone(),
+        };
+
+        // A snapshot is not imported if it doesn't match the trusted leaf.
+        crate::snapshot::import_snapshot(&storage, &snapshot, Commitment::from_raw([1; 32]))
+            .await
+            .unwrap_err();
+        assert_eq!(storage.load_anchor_leaf().await.unwrap(), None);
+
+        // Nor if the state doesn't match the leaf.
+        let mut invalid = snapshot.clone();
+        invalid
+            .fee_merkle_tree
+            .update(FeeAccount::default(), FeeAmount::from(1))
+            .unwrap();
+        crate::snapshot::import_snapshot(&storage, &invalid, invalid.leaf_commitment())
+            .await
+            .unwrap_err();
+        assert_eq!(storage.load_anchor_leaf().await.unwrap(), None);
+
+        crate::snapshot::import_snapshot(&storage, &snapshot, leaf.commit())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.load_anchor_leaf().await.unwrap(),
+            Some((leaf.clone(), qc))
+        );
+        assert_eq!(
+            storage.load_snapshot_state().await.unwrap(),
+            Some((0, snapshot.validated_state()))
+        );
+        // Stake tables are never taken from a snapshot, since nothing in the trusted leaf commits
+        // to them.
+        assert_eq!(storage.load_stake(EpochNumber::new(1)).await.unwrap(), None);
+
+        // The same snapshot cannot be imported twice.
+        crate::snapshot::import_snapshot(&storage, &snapshot, leaf.commit())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    pub async fn test_migrate_storage<P: TestablePersistence>() {
+        setup_test();
+
+        let tmp = P::tmp_storage().await;
+        let storage = P::connect(&tmp).await;
+
+        // Populate the storage with some consensus data.
+        let validator = Validator::mock();
+        let stake = IndexMap::from_iter([(validator.account, validator)]);
+        storage
+            .store_stake(EpochNumber::new(1), stake.clone())
+            .await
+            .unwrap();
+        storage
+            .add_drb_result(EpochNumber::new(1), [1; 32])
+            .await
+            .unwrap();
+        storage
+            .add_drb_result(EpochNumber::new(2), [2; 32])
+            .await
+            .unwrap();
+
+        let leaf =
+            Leaf2::genesis::<TestVersions>(&ValidatedState::default(), &NodeState::mock()).await;
+        let qc = QuorumCertificate2::genesis::<TestVersions>(
+            &ValidatedState::default(),
+            &NodeState::mock(),
+        )
+        .await;
+        storage
+            .append_decided_leaves(
+                leaf.view_number(),
+                [(&leaf_info(leaf.clone()), qc.clone())],
+                &NullEventConsumer,
+            )
+            .await
+            .unwrap();
+
+        let payload = leaf.block_payload().unwrap();
+        let payload_bytes = payload.encode();
+        let avidm_param = init_avidm_param(2).unwrap();
+        let ns_table = parse_ns_table(payload.byte_len().as_usize(), &payload.ns_table().encode());
+        let (payload_commitment, shares) =
+            AvidMScheme::ns_disperse(&avidm_param, &[1, 1], &payload_bytes, ns_table).unwrap();
+        let (pubkey, privkey) = BLSPubKey::generated_from_seed_indexed([0; 32], 1);
+        let signature = PubKey::sign(&privkey, &[]).unwrap();
+        for view in 1..=3 {
+            let view = ViewNumber::new(view);
+            let vid = VidDisperseShare2::<SeqTypes> {
+                view_number: view,
+                payload_commitment,
+                share: shares[0].clone(),
+                recipient_key: pubkey,
+                epoch: None,
+                target_epoch: None,
+                common: avidm_param.clone(),
+            };
+            storage
+                .append_vid2(&vid.to_proposal(&privkey).unwrap())
+                .await
+                .unwrap();
+
+            let da_proposal = Proposal {
+                data: DaProposal2::<SeqTypes> {
+                    encoded_transactions: payload_bytes.clone(),
+                    metadata: payload.ns_table().clone(),
+                    view_number: view,
+                    epoch: None,
+                    epoch_transition_indicator: EpochTransitionIndicator::NotInTransition,
+                },
+                signature: signature.clone(),
+                _pd: Default::default(),
+            };
+            storage
+                .append_da2(&da_proposal, VidCommitment::V1(payload_commitment))
+                .await
+                .unwrap();
+
+            let quorum_proposal = QuorumProposal2::<SeqTypes> {
+                epoch: None,
+                block_header: leaf.block_header().clone(),
+                view_number: view,
+                justify_qc: qc.clone(),
+                upgrade_certificate: None,
+                view_change_evidence: None,
+                next_drb_result: None,
+                next_epoch_justify_qc: None,
+                state_cert: None,
+            };
+            storage
+                .append_quorum_proposal2(&Proposal {
+                    data: QuorumProposalWrapper {
+                        proposal: quorum_proposal,
+                    },
+                    signature: signature.clone(),
+                    _pd: Default::default(),
+                })
+                .await
+                .unwrap();
+        }
+        storage
+            .record_action(ViewNumber::new(3), None, HotShotAction::Vote)
+            .await
+            .unwrap();
+
+        // Migrate to file system storage, and back to a fresh instance of the original backend.
+        let fs_tmp = <fs::Persistence as TestablePersistence>::tmp_storage().await;
+        let fs_storage = <fs::Persistence as TestablePersistence>::connect(&fs_tmp).await;
+        let checkpoint = fs_tmp.path().join("checkpoint");
+        migrate::migrate_storage(&storage, &fs_storage, None, Some(&checkpoint))
+            .await
+            .unwrap();
+        migrate::verify_storage(&storage, &fs_storage, None)
+            .await
+            .unwrap();
+
+        let tmp2 = P::tmp_storage().await;
+        let storage2 = P::connect(&tmp2).await;
+        migrate::migrate_storage(&fs_storage, &storage2, None, None)
+            .await
+            .unwrap();
+        migrate::verify_storage(&storage, &storage2, None)
+            .await
+            .unwrap();
+        assert_eq!(storage2.load_anchor_leaf().await.unwrap().unwrap().1, qc);
+        assert_eq!(
+            storage2.load_stake(EpochNumber::new(1)).await.unwrap(),
+            Some(stake)
+        );
+        assert_eq!(storage2.load_quorum_proposals().await.unwrap().len(), 3);
+        assert_eq!(
+            storage2.load_latest_acted_view().await.unwrap(),
+            Some(ViewNumber::new(3))
+        );
+
+        // The checkpoint records that the first migration completed, so running it again with the
+        // same checkpoint does nothing, even if the source has changed.
+        storage
+            .record_action(ViewNumber::new(4), None, HotShotAction::Vote)
+            .await
+            .unwrap();
+        migrate::migrate_storage(&storage, &fs_storage, None, Some(&checkpoint))
+            .await
+            .unwrap();
+        assert_eq!(
+            fs_storage.load_latest_acted_view().await.unwrap(),
+            Some(ViewNumber::new(3))
+        );
+        migrate::verify_storage(&storage, &fs_storage, None)
+            .await
+            .unwrap_err();
+    }
 }
@@ -7,15 +7,17 @@ use std::{
     sync::Arc,
 };
 
+use alloy::primitives::{Address, B256};
 use anyhow::{anyhow, Context};
 use async_lock::RwLock;
 use async_trait::async_trait;
 use clap::Parser;
 use espresso_types::{
-    traits::MembershipPersistence,
+    traits::{merge_scanned_l1_range, L1Persistence, MembershipPersistence},
     v0::traits::{EventConsumer, PersistenceOptions, SequencerPersistence},
     v0_3::{EventKey, IndexedStake, StakeTableEvent, Validator},
-    Leaf, Leaf2, NetworkConfig, Payload, SeqTypes,
+    FeeInfo, L1BlockInfo, Leaf, Leaf2, NetworkConfig, Payload, SeqTypes, SignedActionRecord,
+    StateSnapshot, ValidatedState,
 };
 use hotshot::{types::BLSPubKey, InitializerEpochInfo};
 use hotshot_types::{
@@ -153,6 +155,11 @@ impl Inner {
         self.path.join("highest_voted_view")
     }
 
+    /// Path to a directory containing the messages this node signed, one file per view.
+    fn signed_actions_dir_path(&self) -> PathBuf {
+        self.path.join("signed_actions")
+    }
+
     /// Path to a directory containing decided leaves.
     fn decided_leaf_path(&self) -> PathBuf {
         self.path.join("decided_leaves")
@@ -199,6 +206,24 @@ impl Inner {
         self.path.join("stake_table")
     }
 
+    /// Path to a directory containing finalized L1 blocks, one file per block.
+    fn l1_blocks_dir_path(&self) -> PathBuf {
+        self.path.join("l1_blocks")
+    }
+
+    /// Path to a directory containing fee deposits, one subdirectory per fee contract.
+    fn l1_deposits_dir_path(&self) -> PathBuf {
+        self.path.join("l1_deposits")
+    }
+
+    /// Path to a directory containing deposits to `fee_contract`, one file per L1 block.
+    ///
+    /// The range of L1 blocks which has been scanned for deposits is kept in the same directory.
+    fn l1_contract_deposits_dir_path(&self, fee_contract: Address) -> PathBuf {
+        self.l1_deposits_dir_path()
+            .join(format!("{fee_contract:x}"))
+    }
+
     fn next_epoch_qc(&self) -> PathBuf {
         self.path.join("next_epoch_quorum_certificate")
     }
@@ -219,6 +244,11 @@ impl Inner {
         self.path.join("state_cert")
     }
 
+    /// Path to the state of the last imported snapshot.
+    fn snapshot_state_path(&self) -> PathBuf {
+        self.path.join("snapshot_state")
+    }
+
     fn update_migration(&mut self) -> anyhow::Result<()> {
         let path = self.migration();
         let bytes = bincode::serialize(&self.migrated)?;
@@ -298,6 +328,9 @@ impl Inner {
             None,
             prune_intervals,
         )?;
+        // Signed messages are kept for the full retention period even once their views are
+        // decided, so they can still protect against double signing.
+        self.prune_files(self.signed_actions_dir_path(), prune_view, None, &[])?;
 
         // Save the most recent leaf as it will be our anchor point if the node restarts.
         self.prune_files(
@@ -310,6 +343,96 @@ impl Inner {
         Ok(())
     }
 
+    fn update_voted_view(&mut self, view: ViewNumber) -> anyhow::Result<()> {
+        let path = &self.voted_view_path();
+        self.replace(
+            path,
+            |mut file| {
+                let mut bytes = vec![];
+                file.read_to_end(&mut bytes)?;
+                let bytes = bytes
+                    .try_into()
+                    .map_err(|bytes| anyhow!("malformed voted view file: {bytes:?}"))?;
+                let saved_view = ViewNumber::new(u64::from_le_bytes(bytes));
+
+                // Overwrite the file if the saved view is older than the new view.
+                Ok(saved_view < view)
+            },
+            |mut file| {
+                file.write_all(&view.u64().to_le_bytes())?;
+                Ok(())
+            },
+        )
+    }
+
+    /// Load the range of L1 blocks which has been scanned for deposits in `dir`.
+    fn l1_deposits_scanned(&self, dir: &Path) -> anyhow::Result<Option<(u64, u64)>> {
+        let path = dir.join("scanned").with_extension("txt");
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let file = File::open(path)?;
+        let range =
+            serde_json::from_reader(BufReader::new(file)).context("deserialize scanned range")?;
+        Ok(Some(range))
+    }
+
+    fn store_l1_deposits_scanned(&mut self, dir: &Path, range: (u64, u64)) -> anyhow::Result<()> {
+        self.replace(
+            &dir.join("scanned").with_extension("txt"),
+            |_| Ok(true),
+            |file| {
+                serde_json::to_writer(BufWriter::new(file), &range)?;
+                Ok(())
+            },
+        )
+    }
+
+    /// Delete the cached finalized L1 blocks and deposits below L1 block `height`.
+    fn prune_l1_cache(&mut self, height: u64) -> anyhow::Result<()> {
+        let height_view = ViewNumber::new(height);
+        self.prune_files(self.l1_blocks_dir_path(), height_view, None, &[])?;
+
+        let deposits_dir = self.l1_deposits_dir_path();
+        if !deposits_dir.is_dir() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(&deposits_dir)? {
+            let path = entry?.path();
+            if !path.is_dir() {
+                // Earlier versions stored deposits in one file per range of blocks directly in
+                // this directory. These are never loaded anymore.
+                fs::remove_file(&path)?;
+                continue;
+            }
+
+            self.prune_files(path.clone(), height_view, None, &[])?;
+            match self.l1_deposits_scanned(&path)? {
+                Some((_, to)) if to < height => {
+                    fs::remove_file(path.join("scanned").with_extension("txt"))?;
+                },
+                Some((from, to)) if from < height => {
+                    self.store_l1_deposits_scanned(&path, (height, to))?;
+                },
+                _ => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    fn signed_actions_for_view(&self, view: ViewNumber) -> anyhow::Result<Vec<SignedActionRecord>> {
+        let path = self
+            .signed_actions_dir_path()
+            .join(view.u64().to_string())
+            .with_extension("txt");
+        if !path.is_file() {
+            return Ok(vec![]);
+        }
+        let bytes = fs::read(&path).context(format!("reading {}", path.display()))?;
+        bincode::deserialize(&bytes).context("malformed signed actions file")
+    }
+
     fn prune_files(
         &mut self,
         dir_path: PathBuf,
@@ -620,7 +743,15 @@ impl SequencerPersistence for Persistence {
             fs::remove_file(&legacy_path).context("removing legacy anchor leaf file")?;
         }
 
+        let mut l1_finalized = None;
         for (info, qc2) in leaf_chain {
+            let l1 = info
+                .leaf
+                .block_header()
+                .l1_finalized()
+                .map(|block| block.number);
+            l1_finalized = l1_finalized.max(l1);
+
             let view = info.leaf.view_number().u64();
             let file_path = path.join(view.to_string()).with_extension("txt");
             inner.replace(
@@ -656,6 +787,14 @@ impl SequencerPersistence for Persistence {
             },
         }
 
+        // Cached L1 data below the L1 block which the latest decided header is based on will never
+        // be needed again.
+        if let Some(height) = l1_finalized {
+            if let Err(err) = inner.prune_l1_cache(height) {
+                tracing::warn!(height, "pruning L1 cache failed: {err:#}");
+            }
+        }
+
         Ok(())
     }
 
@@ -665,6 +804,48 @@ impl SequencerPersistence for Persistence {
         self.inner.read().await.load_anchor_leaf()
     }
 
+    async fn import_snapshot(&self, snapshot: &StateSnapshot) -> anyhow::Result<()> {
+        let mut inner = self.inner.write().await;
+
+        // Save the state before the leaf, so that we never have the snapshot leaf as our anchor
+        // without also having its state.
+        let state_bytes = bincode::serialize(&(snapshot.height(), snapshot.validated_state()))
+            .context("serializing snapshot state")?;
+        let state_path = inner.snapshot_state_path();
+        inner.replace(
+            &state_path,
+            |_| Ok(true),
+            |mut file| {
+                file.write_all(&state_bytes)?;
+                Ok(())
+            },
+        )?;
+
+        let path = inner.decided_leaf2_path();
+        fs::create_dir_all(&path).context("creating anchor leaf directory")?;
+        let view = snapshot.leaf.view_number().u64();
+        let leaf_bytes = bincode::serialize(&(&snapshot.leaf, &snapshot.qc))?;
+        inner.replace(
+            &path.join(view.to_string()).with_extension("txt"),
+            |_| Ok(true),
+            |mut file| {
+                file.write_all(&leaf_bytes)?;
+                Ok(())
+            },
+        )
+    }
+
+    async fn load_snapshot_state(&self) -> anyhow::Result<Option<(u64, ValidatedState)>> {
+        let path = self.inner.read().await.snapshot_state_path();
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let bytes = fs::read(&path).context("reading snapshot state")?;
+        Ok(Some(
+            bincode::deserialize(&bytes).context("parsing snapshot state")?,
+        ))
+    }
+
     async fn load_da_proposal(
         &self,
         view: ViewNumber,
@@ -770,30 +951,54 @@ impl SequencerPersistence for Persistence {
         _epoch: Option<EpochNumber>,
         action: HotShotAction,
     ) -> anyhow::Result<()> {
-        // Todo Remove this after https://github.com/EspressoSystems/espresso-sequencer/issues/1931
+        // Signed messages are recorded with `record_signed_action`; the only other actions which
+        // matter after a restart are votes and proposals, which advance the highest acted view.
         if !matches!(action, HotShotAction::Propose | HotShotAction::Vote) {
             return Ok(());
         }
+        self.inner.write().await.update_voted_view(view)
+    }
+
+    async fn record_signed_action(&self, record: SignedActionRecord) -> anyhow::Result<()> {
         let mut inner = self.inner.write().await;
-        let path = &inner.voted_view_path();
-        inner.replace(
-            path,
-            |mut file| {
-                let mut bytes = vec![];
-                file.read_to_end(&mut bytes)?;
-                let bytes = bytes
-                    .try_into()
-                    .map_err(|bytes| anyhow!("malformed voted view file: {bytes:?}"))?;
-                let saved_view = ViewNumber::new(u64::from_le_bytes(bytes));
+        let mut records = inner.signed_actions_for_view(record.view)?;
+        record.check_conflicts(&records)?;
+
+        if !records.contains(&record) {
+            records.push(record);
+            let dir_path = inner.signed_actions_dir_path();
+            fs::create_dir_all(&dir_path).context("failed to create signed actions dir")?;
+            let file_path = dir_path
+                .join(record.view.u64().to_string())
+                .with_extension("txt");
+            inner.replace(
+                &file_path,
+                |_| Ok(true),
+                |mut file| {
+                    file.write_all(&bincode::serialize(&records)?)?;
+                    Ok(())
+                },
+            )?;
+        }
 
-                // Overwrite the file if the saved view is older than the new view.
-                Ok(saved_view < view)
-            },
-            |mut file| {
-                file.write_all(&view.u64().to_le_bytes())?;
-                Ok(())
-            },
-        )
+        if record.is_vote_or_proposal() {
+            inner.update_voted_view(record.view)?;
+        }
+        Ok(())
+    }
+
+    async fn load_signed_actions(&self) -> anyhow::Result<Vec<SignedActionRecord>> {
+        let inner = self.inner.read().await;
+        let dir_path = inner.signed_actions_dir_path();
+        if !dir_path.is_dir() {
+            return Ok(vec![]);
+        }
+
+        let mut records = vec![];
+        for (view, _) in view_files(&dir_path)?.sorted_by_key(|(view, _)| *view) {
+            records.extend(inner.signed_actions_for_view(view)?);
+        }
+        Ok(records)
     }
 
     async fn append_quorum_proposal2(
@@ -1491,6 +1696,116 @@ impl MembershipPersistence for Persistence {
     }
 }
 
+#[async_trait]
+impl L1Persistence for Persistence {
+    async fn load_finalized_l1_block(
+        &self,
+        number: u64,
+    ) -> anyhow::Result<Option<(L1BlockInfo, B256)>> {
+        let inner = self.inner.read().await;
+        let file_path = inner
+            .l1_blocks_dir_path()
+            .join(number.to_string())
+            .with_extension("txt");
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(file_path)?;
+        let block =
+            serde_json::from_reader(BufReader::new(file)).context("deserialize L1 block")?;
+        Ok(Some(block))
+    }
+
+    async fn store_finalized_l1_block(
+        &self,
+        block: L1BlockInfo,
+        parent_hash: B256,
+    ) -> anyhow::Result<()> {
+        let mut inner = self.inner.write().await;
+        let dir_path = inner.l1_blocks_dir_path();
+        fs::create_dir_all(&dir_path).context("failed to create L1 blocks dir")?;
+
+        let file_path = dir_path
+            .join(block.number.to_string())
+            .with_extension("txt");
+        inner.replace(
+            &file_path,
+            |_| {
+                // Finalized blocks never change, so there is no need to overwrite the file.
+                Ok(false)
+            },
+            |file| {
+                serde_json::to_writer(BufWriter::new(file), &(block, parent_hash))?;
+                Ok(())
+            },
+        )
+    }
+
+    async fn load_l1_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Option<Vec<FeeInfo>>> {
+        let inner = self.inner.read().await;
+        let dir_path = inner.l1_contract_deposits_dir_path(fee_contract);
+        match inner.l1_deposits_scanned(&dir_path)? {
+            Some((scanned_from, scanned_to)) if scanned_from <= from && to <= scanned_to => {},
+            _ => return Ok(None),
+        }
+
+        let blocks = view_files(&dir_path)?
+            .map(|(block, path)| (block.u64(), path))
+            .filter(|(block, _)| (from..=to).contains(block))
+            .collect::<BTreeMap<_, _>>();
+        let mut deposits = vec![];
+        for path in blocks.into_values() {
+            let file = File::open(path)?;
+            let block_deposits: Vec<FeeInfo> =
+                serde_json::from_reader(BufReader::new(file)).context("deserialize L1 deposits")?;
+            deposits.extend(block_deposits);
+        }
+        Ok(Some(deposits))
+    }
+
+    async fn store_l1_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+        deposits: &[(u64, FeeInfo)],
+    ) -> anyhow::Result<()> {
+        let mut inner = self.inner.write().await;
+        let dir_path = inner.l1_contract_deposits_dir_path(fee_contract);
+        fs::create_dir_all(&dir_path).context("failed to create L1 deposits dir")?;
+
+        let mut by_block = BTreeMap::<u64, Vec<FeeInfo>>::new();
+        for (block, deposit) in deposits {
+            by_block.entry(*block).or_default().push(*deposit);
+        }
+        for (block, deposits) in by_block {
+            let file_path = dir_path.join(block.to_string()).with_extension("txt");
+            inner.replace(
+                &file_path,
+                |_| Ok(false),
+                |file| {
+                    serde_json::to_writer(BufWriter::new(file), &deposits)?;
+                    Ok(())
+                },
+            )?;
+        }
+
+        // Only extend the scanned range once all the deposits in it are stored.
+        let scanned = inner.l1_deposits_scanned(&dir_path)?;
+        inner.store_l1_deposits_scanned(&dir_path, merge_scanned_l1_range(scanned, from, to))
+    }
+
+    async fn prune_l1_cache(&self, height: u64) -> anyhow::Result<()> {
+        self.inner.write().await.prune_l1_cache(height)
+    }
+}
+
 /// Update a `NetworkConfig` that may have originally been persisted with an old version.
 fn migrate_network_config(
     mut network_config: serde_json::Value,
@@ -0,0 +1,588 @@
+//! Migrating consensus storage from one persistence backend to another.
+//!
+//! This allows a node operator to move a node between the file system and SQL backends without
+//! resetting its consensus state. Everything a node needs to restart is copied through the
+//! [`SequencerPersistence`] and [`MembershipPersistence`] interfaces, so any backend can be used as
+//! the source or the destination. Since the SQL backend is built for either Postgres or SQLite, but
+//! not both, moving from SQLite to Postgres is done in two steps, via file system storage.
+//!
+//! The merklized state (the fee, block and reward Merkle trees) is stored by the query service
+//! rather than in consensus storage. When migrating away from a node running the SQL query service,
+//! the state at the anchor leaf is read from its database and imported into the destination as a
+//! snapshot, so that the node restarts with its full state instead of catching it up from peers.
+//!
+//! The migration is resumable. Progress is recorded in an optional checkpoint file after each
+//! [`Stage`], and periodically while copying per-view data, so an interrupted migration can pick up
+//! where it left off. Every write to the destination is idempotent, so repeating part of a stage
+//! after an interruption is harmless.
+
+use std::{
+    cmp::max,
+    collections::BTreeSet,
+    fs,
+    marker::PhantomData,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{ensure, Context};
+use committable::Committable;
+use espresso_types::{
+    traits::{MembershipPersistence, NullEventConsumer},
+    v0::traits::SequencerPersistence,
+    Header, StateSnapshot,
+};
+use hotshot_query_service::data_source::storage::SqlStorage;
+use hotshot_types::{
+    data::{EpochNumber, VidDisperseShare},
+    drb::DrbResult,
+    event::LeafInfo,
+    message::Proposal,
+    traits::{block_contents::BlockHeader, node_implementation::ConsensusTime},
+    vote::HasViewNumber,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{snapshot::export_snapshot, ViewNumber};
+
+/// How many views to copy between saving checkpoints.
+const VIEW_CHECKPOINT_INTERVAL: u64 = 1000;
+
+/// Limit for loading every stake table from storage.
+///
+/// This is not `u64::MAX`, because SQL storage binds the limit as a signed integer.
+const ALL_STAKE_TABLES: u64 = i64::MAX as u64;
+
+/// A stage of a storage migration.
+///
+/// Stages are migrated in the order they are declared.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Stage {
+    /// The network config.
+    Config,
+    /// Stake tables for all known epochs.
+    StakeTables,
+    /// Stake table events fetched from the L1.
+    StakeTableEvents,
+    /// DRB results and epoch root headers.
+    EpochInfo,
+    /// The anchor leaf, and the state of an imported snapshot if we are still anchored at it.
+    AnchorLeaf,
+    /// The fee, block and reward Merkle trees at the anchor leaf, from the source's query service
+    /// database.
+    MerklizedState,
+    /// Quorum proposals, DA proposals and VID shares for each view still in storage.
+    Views,
+    /// Upgrade, next epoch quorum and light client state update certificates.
+    Certificates,
+    /// The latest view in which the node voted or proposed, and every message it signed.
+    ActedView,
+}
+
+impl Stage {
+    const ALL: [Self; 9] = [
+        Self::Config,
+        Self::StakeTables,
+        Self::StakeTableEvents,
+        Self::EpochInfo,
+        Self::AnchorLeaf,
+        Self::MerklizedState,
+        Self::Views,
+        Self::Certificates,
+        Self::ActedView,
+    ];
+}
+
+/// Progress of a storage migration, as saved in a checkpoint file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// Stages which have been fully migrated.
+    pub completed: BTreeSet<Stage>,
+    /// The first view not yet migrated, if the [`Views`](Stage::Views) stage is in progress.
+    pub next_view: Option<u64>,
+}
+
+#[derive(Debug)]
+struct Checkpoint {
+    path: Option<PathBuf>,
+    progress: Progress,
+}
+
+impl Checkpoint {
+    fn load(path: Option<&Path>) -> anyhow::Result<Self> {
+        let progress = match path {
+            Some(path) if path.is_file() => {
+                let bytes = fs::read(path).context(format!("reading {}", path.display()))?;
+                let progress: Progress =
+                    serde_json::from_slice(&bytes).context("malformed checkpoint file")?;
+                tracing::info!(?progress, "resuming migration from {}", path.display());
+                progress
+            },
+            _ => Default::default(),
+        };
+        Ok(Self {
+            path: path.map(Path::to_path_buf),
+            progress,
+        })
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        // Write to a temporary file and rename it, so that an interruption never leaves a partially
+        // written checkpoint.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&self.progress)?)
+            .context(format!("writing {}", tmp.display()))?;
+        fs::rename(&tmp, path).context(format!("saving checkpoint to {}", path.display()))
+    }
+
+    fn complete(&mut self, stage: Stage) -> anyhow::Result<()> {
+        self.progress.completed.insert(stage);
+        self.progress.next_view = None;
+        self.save()
+    }
+}
+
+/// Copy consensus storage from `src` to `dst`.
+///
+/// If `checkpoint` is given, progress is saved to this file as the migration proceeds, and if the
+/// file already exists, the migration resumes from the progress it records.
+///
+/// The source is first migrated to the current storage format if necessary, as the node itself
+/// would do on startup. Otherwise it is not modified.
+///
+/// Only data needed to restart consensus is migrated. In particular, of the decided leaves in `src`
+/// only the anchor leaf is migrated, so any leaves not yet passed on to an event consumer (such as
+/// the query service) will not be delivered to it. Data belonging to the query service is not part
+/// of consensus storage and is not migrated, with the exception of the merklized state: if `state`
+/// is the source's query service database, the state at the anchor leaf is copied from it into the
+/// destination as a snapshot. This fails if that state is not available. If `state` is not given,
+/// the destination node will have to catch up the state from its peers.
+pub async fn migrate_storage<S, D>(
+    src: &S,
+    dst: &D,
+    state: Option<&SqlStorage>,
+    checkpoint: Option<&Path>,
+) -> anyhow::Result<()>
+where
+    S: SequencerPersistence + MembershipPersistence,
+    D: SequencerPersistence + MembershipPersistence,
+{
+    // Bring the source up to date with the current storage format, as the node would on startup,
+    // so that we don't miss data stored in a legacy format.
+    src.migrate_consensus()
+        .await
+        .context("migrating source to current format")?;
+
+    let mut checkpoint = Checkpoint::load(checkpoint)?;
+    for stage in Stage::ALL {
+        if checkpoint.progress.completed.contains(&stage) {
+            tracing::info!(?stage, "stage already migrated");
+            continue;
+        }
+
+        tracing::info!(?stage, "migrating");
+        match stage {
+            Stage::Config => migrate_config(src, dst).await,
+            Stage::StakeTables => migrate_stake_tables(src, dst).await,
+            Stage::StakeTableEvents => migrate_stake_table_events(src, dst).await,
+            Stage::EpochInfo => migrate_epoch_info(src, dst).await,
+            Stage::AnchorLeaf => migrate_anchor_leaf(src, dst).await,
+            Stage::MerklizedState => migrate_merklized_state(src, dst, state).await,
+            Stage::Views => migrate_views(src, dst, &mut checkpoint).await,
+            Stage::Certificates => migrate_certificates(src, dst).await,
+            Stage::ActedView => migrate_acted_view(src, dst).await,
+        }
+        .context(format!("migrating {stage:?}"))?;
+        checkpoint.complete(stage)?;
+    }
+
+    tracing::info!("migration complete");
+    Ok(())
+}
+
+/// Check that `dst` has the same consensus state as `src`.
+///
+/// This checks all the data which is copied by [`migrate_storage`], and fails with a description
+/// of the first difference found. If `state` is given, this also checks that the destination has
+/// the merklized state at the anchor leaf.
+pub async fn verify_storage<S, D>(
+    src: &S,
+    dst: &D,
+    state: Option<&SqlStorage>,
+) -> anyhow::Result<()>
+where
+    S: SequencerPersistence + MembershipPersistence,
+    D: SequencerPersistence + MembershipPersistence,
+{
+    ensure_same(
+        "config",
+        &src.load_config().await?,
+        &dst.load_config().await?,
+    )?;
+    ensure_same(
+        "stake tables",
+        &src.load_latest_stake(ALL_STAKE_TABLES).await?,
+        &dst.load_latest_stake(ALL_STAKE_TABLES).await?,
+    )?;
+    ensure_same(
+        "stake table events",
+        &src.load_events().await?,
+        &dst.load_events().await?,
+    )?;
+    ensure_same(
+        "epoch info",
+        &epoch_info(src).await?,
+        &epoch_info(dst).await?,
+    )?;
+
+    // Storage backends may strip the payload from the anchor leaf, so compare commitments.
+    let src_anchor = src.load_anchor_leaf().await?;
+    let dst_anchor = dst.load_anchor_leaf().await?;
+    ensure_same(
+        "anchor leaf",
+        &src_anchor.as_ref().map(|(leaf, qc)| (leaf.commit(), qc)),
+        &dst_anchor.as_ref().map(|(leaf, qc)| (leaf.commit(), qc)),
+    )?;
+    if let Some((leaf, qc)) = src_anchor {
+        match src.load_snapshot_state().await? {
+            Some((height, src_state)) if height == leaf.height() => {
+                ensure_same(
+                    "snapshot state",
+                    &Some((height, src_state)),
+                    &dst.load_snapshot_state().await?,
+                )?;
+            },
+            _ if state.is_some() && leaf.height() > 0 => {
+                let (height, dst_state) = dst
+                    .load_snapshot_state()
+                    .await?
+                    .context("merklized state missing from destination")?;
+                ensure!(
+                    height == leaf.height(),
+                    "destination has merklized state at height {height}, but the anchor leaf is at \
+                     height {}",
+                    leaf.height()
+                );
+                StateSnapshot {
+                    leaf,
+                    qc,
+                    fee_merkle_tree: dst_state.fee_merkle_tree,
+                    reward_merkle_tree: dst_state.reward_merkle_tree,
+                    block_merkle_tree: dst_state.block_merkle_tree,
+                }
+                .verify()
+                .context("merklized state in destination does not match the anchor leaf")?;
+            },
+            _ => {},
+        }
+    }
+
+    let src_proposals = src.load_quorum_proposals().await?;
+    let dst_proposals = dst.load_quorum_proposals().await?;
+    for view in view_range(src).await? {
+        let view = ViewNumber::new(view);
+        ensure_same(
+            &format!("quorum proposal for view {view:?}"),
+            &src_proposals.get(&view),
+            &dst_proposals.get(&view),
+        )?;
+        ensure_same(
+            &format!("DA proposal for view {view:?}"),
+            &src.load_da_proposal(view).await?,
+            &dst.load_da_proposal(view).await?,
+        )?;
+        ensure_same(
+            &format!("VID share for view {view:?}"),
+            &src.load_vid_share(view).await?,
+            &dst.load_vid_share(view).await?,
+        )?;
+    }
+
+    ensure_same(
+        "upgrade certificate",
+        &src.load_upgrade_certificate().await?,
+        &dst.load_upgrade_certificate().await?,
+    )?;
+    ensure_same(
+        "next epoch quorum certificate",
+        &src.load_next_epoch_quorum_certificate().await?,
+        &dst.load_next_epoch_quorum_certificate().await?,
+    )?;
+    ensure_same(
+        "state certificate",
+        &src.load_state_cert().await?,
+        &dst.load_state_cert().await?,
+    )?;
+    ensure_same(
+        "latest acted view",
+        &src.load_latest_acted_view().await?,
+        &dst.load_latest_acted_view().await?,
+    )?;
+    // Backends may order records signed in the same view differently.
+    ensure_same(
+        "signed actions",
+        &BTreeSet::from_iter(src.load_signed_actions().await?),
+        &BTreeSet::from_iter(dst.load_signed_actions().await?),
+    )?;
+
+    tracing::info!("verified migrated storage");
+    Ok(())
+}
+
+async fn migrate_config(
+    src: &impl SequencerPersistence,
+    dst: &impl SequencerPersistence,
+) -> anyhow::Result<()> {
+    if let Some(cfg) = src.load_config().await? {
+        dst.save_config(&cfg).await?;
+    }
+    Ok(())
+}
+
+async fn migrate_stake_tables(
+    src: &impl MembershipPersistence,
+    dst: &impl MembershipPersistence,
+) -> anyhow::Result<()> {
+    for (epoch, stake) in src
+        .load_latest_stake(ALL_STAKE_TABLES)
+        .await?
+        .unwrap_or_default()
+    {
+        tracing::debug!(?epoch, "migrating stake table");
+        dst.store_stake(epoch, stake).await?;
+    }
+    Ok(())
+}
+
+async fn migrate_stake_table_events(
+    src: &impl MembershipPersistence,
+    dst: &impl MembershipPersistence,
+) -> anyhow::Result<()> {
+    if let Some((l1_block, events)) = src.load_events().await? {
+        dst.store_events(l1_block, events).await?;
+    }
+    Ok(())
+}
+
+async fn migrate_epoch_info(
+    src: &impl SequencerPersistence,
+    dst: &impl SequencerPersistence,
+) -> anyhow::Result<()> {
+    for info in src.load_start_epoch_info().await? {
+        dst.add_drb_result(info.epoch, info.drb_result).await?;
+        if let Some(header) = info.block_header {
+            dst.add_epoch_root(info.epoch, header).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn migrate_anchor_leaf(
+    src: &impl SequencerPersistence,
+    dst: &impl SequencerPersistence,
+) -> anyhow::Result<()> {
+    let Some((leaf, qc)) = src.load_anchor_leaf().await? else {
+        return Ok(());
+    };
+
+    match src.load_snapshot_state().await? {
+        Some((height, state)) if height == leaf.height() => {
+            // The node has not moved on from an imported snapshot yet. Import the snapshot into
+            // the destination as well, so it still starts with the full state.
+            tracing::info!(height, "migrating snapshot state");
+            let snapshot = StateSnapshot {
+                leaf,
+                qc,
+                fee_merkle_tree: state.fee_merkle_tree,
+                reward_merkle_tree: state.reward_merkle_tree,
+                block_merkle_tree: state.block_merkle_tree,
+            };
+            dst.import_snapshot(&snapshot).await
+        },
+        _ => {
+            let view = leaf.view_number();
+            let info = LeafInfo {
+                leaf,
+                vid_share: None,
+                state: Default::default(),
+                delta: None,
+                state_cert: None,
+            };
+            dst.append_decided_leaves(view, [(&info, qc)], &NullEventConsumer)
+                .await
+        },
+    }
+}
+
+async fn migrate_merklized_state(
+    src: &(impl SequencerPersistence + MembershipPersistence),
+    dst: &impl SequencerPersistence,
+    state: Option<&SqlStorage>,
+) -> anyhow::Result<()> {
+    let Some(state) = state else {
+        tracing::warn!(
+            "not migrating merklized state, the node will catch up its state from peers on restart"
+        );
+        return Ok(());
+    };
+    let Some((leaf, _)) = src.load_anchor_leaf().await? else {
+        return Ok(());
+    };
+    let height = leaf.height();
+    if height == 0 {
+        // The genesis state is derived from the genesis config on startup.
+        return Ok(());
+    }
+    if matches!(src.load_snapshot_state().await?, Some((h, _)) if h == height) {
+        // Already migrated with the anchor leaf.
+        return Ok(());
+    }
+
+    tracing::info!(height, "migrating merklized state");
+    let snapshot = export_snapshot(state, Some(height)).await.context(format!(
+        "merklized state at the anchor leaf (height {height}) is not available in the source"
+    ))?;
+    ensure!(
+        snapshot.leaf_commitment() == leaf.commit(),
+        "query service leaf at height {height} does not match the anchor leaf"
+    );
+    dst.import_snapshot(&snapshot).await
+}
+
+async fn migrate_views(
+    src: &impl SequencerPersistence,
+    dst: &impl SequencerPersistence,
+    checkpoint: &mut Checkpoint,
+) -> anyhow::Result<()> {
+    let proposals = src.load_quorum_proposals().await?;
+    let range = view_range(src).await?;
+    let start = max(
+        *range.start(),
+        checkpoint.progress.next_view.unwrap_or_default(),
+    );
+    tracing::info!(from = start, to = range.end(), "migrating views");
+
+    for view in start..=*range.end() {
+        let view = ViewNumber::new(view);
+
+        let proposal = proposals.get(&view);
+        if let Some(proposal) = proposal {
+            dst.append_quorum_proposal2(proposal).await?;
+        }
+
+        let vid_share = src.load_vid_share(view).await?;
+        let vid_commit = vid_share
+            .as_ref()
+            .map(|share| share.data.payload_commitment())
+            .or_else(|| proposal.map(|proposal| proposal.data.block_header().payload_commitment()));
+        if let Some(share) = vid_share {
+            match share.data {
+                VidDisperseShare::V0(data) => {
+                    dst.append_vid(&Proposal {
+                        data,
+                        signature: share.signature,
+                        _pd: PhantomData,
+                    })
+                    .await?
+                },
+                VidDisperseShare::V1(data) => {
+                    dst.append_vid2(&Proposal {
+                        data,
+                        signature: share.signature,
+                        _pd: PhantomData,
+                    })
+                    .await?
+                },
+            }
+        }
+
+        if let Some(da_proposal) = src.load_da_proposal(view).await? {
+            match vid_commit {
+                Some(vid_commit) => dst.append_da2(&da_proposal, vid_commit).await?,
+                None => {
+                    // The payload commitment is normally computed by consensus, which we cannot do
+                    // here without the full network configuration.
+                    tracing::warn!(
+                        ?view,
+                        "skipping DA proposal with no VID share or quorum proposal"
+                    );
+                },
+            }
+        }
+
+        if (view.u64() + 1) % VIEW_CHECKPOINT_INTERVAL == 0 {
+            tracing::info!(?view, "migrated views");
+            checkpoint.progress.next_view = Some(view.u64() + 1);
+            checkpoint.save()?;
+        }
+    }
+    Ok(())
+}
+
+async fn migrate_certificates(
+    src: &impl SequencerPersistence,
+    dst: &impl SequencerPersistence,
+) -> anyhow::Result<()> {
+    if let Some(cert) = src.load_upgrade_certificate().await? {
+        dst.store_upgrade_certificate(Some(cert)).await?;
+    }
+    if let Some(qc) = src.load_next_epoch_quorum_certificate().await? {
+        dst.store_next_epoch_quorum_certificate(qc).await?;
+    }
+    if let Some(cert) = src.load_state_cert().await? {
+        dst.add_state_cert(cert).await?;
+    }
+    Ok(())
+}
+
+async fn migrate_acted_view(
+    src: &impl SequencerPersistence,
+    dst: &impl SequencerPersistence,
+) -> anyhow::Result<()> {
+    dst.import_slashing_protection(src.export_slashing_protection().await?)
+        .await
+}
+
+/// The range of views which may have per-view data in `storage`.
+///
+/// This runs from the oldest stored quorum proposal (or the anchor view, if that is older) to the
+/// newest view we have any record of.
+async fn view_range(storage: &impl SequencerPersistence) -> anyhow::Result<RangeInclusive<u64>> {
+    let proposals = storage.load_quorum_proposals().await?;
+    let anchor_view = storage.load_anchor_view().await?;
+    let first = proposals
+        .keys()
+        .next()
+        .map_or(anchor_view, |view| (*view).min(anchor_view));
+    let last = [
+        proposals.keys().next_back().copied(),
+        storage.load_latest_acted_view().await?,
+    ]
+    .into_iter()
+    .flatten()
+    .fold(anchor_view, max);
+    Ok(first.u64()..=last.u64())
+}
+
+async fn epoch_info(
+    storage: &impl SequencerPersistence,
+) -> anyhow::Result<Vec<(EpochNumber, DrbResult, Option<Header>)>> {
+    Ok(storage
+        .load_start_epoch_info()
+        .await?
+        .into_iter()
+        .map(|info| (info.epoch, info.drb_result, info.block_header))
+        .collect())
+}
+
+fn ensure_same<T: Serialize>(what: &str, src: &T, dst: &T) -> anyhow::Result<()> {
+    ensure!(
+        serde_json::to_value(src)? == serde_json::to_value(dst)?,
+        "{what} differs between source and destination"
+    );
+    Ok(())
+}
@@ -3,13 +3,14 @@
 
 use std::{collections::BTreeMap, sync::Arc};
 
+use alloy::primitives::{Address, B256};
 use anyhow::bail;
 use async_trait::async_trait;
 use espresso_types::{
-    traits::MembershipPersistence,
+    traits::{L1Persistence, MembershipPersistence},
     v0::traits::{EventConsumer, PersistenceOptions, SequencerPersistence},
     v0_3::{EventKey, IndexedStake, StakeTableEvent, Validator},
-    Leaf2, NetworkConfig,
+    FeeInfo, L1BlockInfo, Leaf2, NetworkConfig, SignedActionRecord, StateSnapshot, ValidatedState,
 };
 use hotshot::{types::BLSPubKey, InitializerEpochInfo};
 use hotshot_types::{
@@ -96,6 +97,14 @@ impl SequencerPersistence for NoStorage {
         Ok(None)
     }
 
+    async fn import_snapshot(&self, _snapshot: &StateSnapshot) -> anyhow::Result<()> {
+        bail!("cannot import a snapshot without persistent storage");
+    }
+
+    async fn load_snapshot_state(&self) -> anyhow::Result<Option<(u64, ValidatedState)>> {
+        Ok(None)
+    }
+
     async fn load_da_proposal(
         &self,
         _view: ViewNumber,
@@ -155,6 +164,12 @@ impl SequencerPersistence for NoStorage {
     ) -> anyhow::Result<()> {
         Ok(())
     }
+    async fn record_signed_action(&self, _record: SignedActionRecord) -> anyhow::Result<()> {
+        Ok(())
+    }
+    async fn load_signed_actions(&self) -> anyhow::Result<Vec<SignedActionRecord>> {
+        Ok(vec![])
+    }
     async fn append_quorum_proposal2(
         &self,
         _proposal: &Proposal<SeqTypes, QuorumProposalWrapper<SeqTypes>>,
@@ -278,3 +293,44 @@ impl MembershipPersistence for NoStorage {
         Ok(None)
     }
 }
+
+#[async_trait]
+impl L1Persistence for NoStorage {
+    async fn load_finalized_l1_block(
+        &self,
+        _number: u64,
+    ) -> anyhow::Result<Option<(L1BlockInfo, B256)>> {
+        Ok(None)
+    }
+
+    async fn store_finalized_l1_block(
+        &self,
+        _block: L1BlockInfo,
+        _parent_hash: B256,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn load_l1_deposits(
+        &self,
+        _fee_contract: Address,
+        _from: u64,
+        _to: u64,
+    ) -> anyhow::Result<Option<Vec<FeeInfo>>> {
+        Ok(None)
+    }
+
+    async fn store_l1_deposits(
+        &self,
+        _fee_contract: Address,
+        _from: u64,
+        _to: u64,
+        _deposits: &[(u64, FeeInfo)],
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn prune_l1_cache(&self, _height: u64) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::{collections::BTreeMap, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
+use alloy::primitives::{Address, B256};
 use anyhow::{bail, Context};
 use async_trait::async_trait;
 use clap::Parser;
@@ -8,10 +9,11 @@ use derivative::Derivative;
 use derive_more::derive::{From, Into};
 use espresso_types::{
     parse_duration, parse_size,
-    traits::MembershipPersistence,
+    traits::{merge_scanned_l1_range, L1Persistence, MembershipPersistence},
     v0::traits::{EventConsumer, PersistenceOptions, SequencerPersistence, StateCatchup},
     v0_3::{EventKey, IndexedStake, StakeTableEvent, Validator},
-    BackoffParams, BlockMerkleTree, FeeMerkleTree, Leaf, Leaf2, NetworkConfig, Payload,
+    BackoffParams, BlockMerkleTree, FeeInfo, FeeMerkleTree, L1BlockInfo, Leaf, Leaf2,
+    NetworkConfig, Payload, SignedActionRecord, StateSnapshot, ValidatedState,
 };
 use futures::stream::StreamExt;
 use hotshot::{types::BLSPubKey, InitializerEpochInfo};
@@ -21,8 +23,9 @@ use hotshot_query_service::{
         storage::{
             pruning::PrunerCfg,
             sql::{
-                include_migrations, query_as, syntax_helpers::MAX_FN, Config, Db, SqlStorage,
-                Transaction, TransactionMode, Write,
+                include_migrations, query_as, syntax_helpers::MAX_FN, Config, Db,
+                FileSystemObjectStore, S3Config, S3ObjectStore, SqlStorage, Transaction,
+                TransactionMode, Write,
             },
         },
         Transaction as _, VersionedDataSource,
@@ -50,12 +53,14 @@ use hotshot_types::{
     traits::{
         block_contents::{BlockHeader, BlockPayload},
         node_implementation::ConsensusTime,
+        signer::SignedMessageKind,
     },
     vote::HasViewNumber,
 };
 use indexmap::IndexMap;
 use itertools::Itertools;
 use sqlx::{query, Executor, Row};
+use url::Url;
 
 use crate::{catchup::SqlStateCatchup, NodeType, SeqTypes, ViewNumber, RECENT_STAKE_TABLES_LIMIT};
 
@@ -167,6 +172,10 @@ pub struct Options {
     #[command(flatten)]
     pub(crate) consensus_pruning: ConsensusPruningOptions,
 
+    /// Cold storage for pruned data.
+    #[command(flatten)]
+    pub(crate) cold_storage: ColdStorageOptions,
+
     /// Specifies the maximum number of concurrent fetch requests allowed from peers.
     #[arg(long, env = "ESPRESSO_SEQUENCER_FETCH_RATE_LIMIT")]
     pub(crate) fetch_rate_limit: Option<usize>,
@@ -414,6 +423,11 @@ impl TryFrom<&Options> for Config {
         if opt.archive {
             cfg = cfg.archive();
         }
+        if let Some(path) = &opt.cold_storage.cold_storage_path {
+            cfg = cfg.cold_storage(FileSystemObjectStore::new(path));
+        } else if let Some(s3) = opt.cold_storage.s3_config()? {
+            cfg = cfg.cold_storage(S3ObjectStore::new(s3));
+        }
 
         Ok(cfg)
     }
@@ -509,6 +523,73 @@ impl From<PruningOptions> for PrunerCfg {
     }
 }
 
+/// Cold storage for data removed by the pruner.
+///
+/// If configured, the pruner exports each batch of data to cold storage before deleting it from
+/// the database, and the pruned data remains available through the availability API.
+#[derive(Parser, Clone, Derivative)]
+#[derivative(Debug)]
+pub struct ColdStorageOptions {
+    /// Directory in which to store pruned data.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_COLD_STORAGE_PATH",
+        conflicts_with = "cold_storage_s3_bucket"
+    )]
+    cold_storage_path: Option<PathBuf>,
+
+    /// S3 bucket in which to store pruned data.
+    #[arg(long, env = "ESPRESSO_SEQUENCER_COLD_STORAGE_S3_BUCKET")]
+    cold_storage_s3_bucket: Option<String>,
+
+    /// URL of the S3-compatible API hosting the cold storage bucket.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_COLD_STORAGE_S3_ENDPOINT",
+        default_value = "https://s3.amazonaws.com"
+    )]
+    cold_storage_s3_endpoint: Url,
+
+    /// Region of the cold storage bucket.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_COLD_STORAGE_S3_REGION",
+        default_value = "us-east-1"
+    )]
+    cold_storage_s3_region: String,
+
+    /// Access key ID for the cold storage bucket.
+    #[arg(long, env = "ESPRESSO_SEQUENCER_COLD_STORAGE_S3_ACCESS_KEY_ID")]
+    cold_storage_s3_access_key_id: Option<String>,
+
+    /// Secret access key for the cold storage bucket.
+    // Hide from debug output since it is sensitive.
+    #[arg(long, env = "ESPRESSO_SEQUENCER_COLD_STORAGE_S3_SECRET_ACCESS_KEY")]
+    #[derivative(Debug = "ignore")]
+    cold_storage_s3_secret_access_key: Option<String>,
+}
+
+impl ColdStorageOptions {
+    fn s3_config(&self) -> anyhow::Result<Option<S3Config>> {
+        let Some(bucket) = &self.cold_storage_s3_bucket else {
+            return Ok(None);
+        };
+        Ok(Some(S3Config {
+            endpoint: self.cold_storage_s3_endpoint.clone(),
+            bucket: bucket.clone(),
+            region: self.cold_storage_s3_region.clone(),
+            access_key_id: self
+                .cold_storage_s3_access_key_id
+                .clone()
+                .context("cold storage S3 access key ID is required")?,
+            secret_access_key: self
+                .cold_storage_s3_secret_access_key
+                .clone()
+                .context("cold storage S3 secret access key is required")?,
+        }))
+    }
+}
+
 /// Pruning parameters for ephemeral consensus storage.
 #[derive(Parser, Clone, Copy, Debug)]
 pub struct ConsensusPruningOptions {
@@ -959,8 +1040,38 @@ const PRUNE_TABLES: &[&str] = &[
     "da_proposal2",
     "quorum_proposals2",
     "quorum_certificate2",
+    "signed_action",
 ];
 
+async fn update_voted_view(tx: &mut Transaction<Write>, view: ViewNumber) -> anyhow::Result<()> {
+    let stmt = format!(
+        "INSERT INTO highest_voted_view (id, view) VALUES (0, $1)
+        ON CONFLICT (id) DO UPDATE SET view = {MAX_FN}(highest_voted_view.view, excluded.view)"
+    );
+    tx.execute(query(&stmt).bind(view.u64() as i64)).await?;
+    Ok(())
+}
+
+/// The name under which a kind of signed message is stored in the `action` column of the
+/// `signed_action` table.
+fn kind_to_sql(kind: SignedMessageKind) -> anyhow::Result<String> {
+    match serde_json::to_value(kind)? {
+        serde_json::Value::String(kind) => Ok(kind),
+        value => bail!("unexpected serialization of message kind {kind:?}: {value}"),
+    }
+}
+
+fn kind_from_sql(kind: String) -> anyhow::Result<SignedMessageKind> {
+    serde_json::from_value(serde_json::Value::String(kind)).context("malformed message kind")
+}
+
+/// Whether `err` is a violation of a unique constraint in the database.
+fn is_unique_violation(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<sqlx::Error>()
+        .and_then(sqlx::Error::as_database_error)
+        .is_some_and(|err| err.is_unique_violation())
+}
+
 async fn prune_to_view(tx: &mut Transaction<Write>, view: u64) -> anyhow::Result<()> {
     if view == 0 {
         // Nothing to prune, the entire chain is younger than the retention period.
@@ -1028,9 +1139,17 @@ impl SequencerPersistence for Persistence {
         leaf_chain: impl IntoIterator<Item = (&LeafInfo<SeqTypes>, QuorumCertificate2<SeqTypes>)> + Send,
         consumer: &(impl EventConsumer + 'static),
     ) -> anyhow::Result<()> {
+        let mut l1_finalized = None;
         let values = leaf_chain
             .into_iter()
             .map(|(info, qc2)| {
+                let l1 = info
+                    .leaf
+                    .block_header()
+                    .l1_finalized()
+                    .map(|block| block.number);
+                l1_finalized = l1_finalized.max(l1);
+
                 // The leaf may come with a large payload attached. We don't care about this payload
                 // because we already store it separately, as part of the DA proposal. Storing it
                 // here contributes to load on the DB for no reason, so we remove it before
@@ -1069,6 +1188,14 @@ impl SequencerPersistence for Persistence {
             tracing::warn!(?view, "pruning failed: {err:#}");
         }
 
+        // Cached L1 data below the L1 block which the latest decided header is based on will never
+        // be needed again.
+        if let Some(height) = l1_finalized {
+            if let Err(err) = self.prune_l1_cache(height).await {
+                tracing::warn!(height, "pruning L1 cache failed: {err:#}");
+            }
+        }
+
         Ok(())
     }
 
@@ -1107,6 +1234,49 @@ impl SequencerPersistence for Persistence {
         Ok(Some((leaf2, qc2)))
     }
 
+    async fn import_snapshot(&self, snapshot: &StateSnapshot) -> anyhow::Result<()> {
+        let state_bytes = bincode::serialize(&snapshot.validated_state())
+            .context("serializing snapshot state")?;
+        let leaf_bytes = bincode::serialize(&snapshot.leaf)?;
+        let qc_bytes = bincode::serialize(&snapshot.qc)?;
+
+        // Store everything in one transaction, so that we never have the snapshot leaf as our
+        // anchor without also having its state.
+        let mut tx = self.db.write().await?;
+        tx.upsert(
+            "snapshot_state",
+            ["id", "height", "data"],
+            ["id"],
+            [(0_i64, snapshot.height() as i64, state_bytes)],
+        )
+        .await?;
+        tx.upsert(
+            "anchor_leaf2",
+            ["view", "leaf", "qc"],
+            ["view"],
+            [(
+                snapshot.leaf.view_number().u64() as i64,
+                leaf_bytes,
+                qc_bytes,
+            )],
+        )
+        .await?;
+        tx.commit().await
+    }
+
+    async fn load_snapshot_state(&self) -> anyhow::Result<Option<(u64, ValidatedState)>> {
+        let mut tx = self.db.read().await?;
+        let Some((height, data)) =
+            query_as::<(i64, Vec<u8>)>("SELECT height, data FROM snapshot_state WHERE id = 0")
+                .fetch_optional(tx.as_mut())
+                .await?
+        else {
+            return Ok(None);
+        };
+        let state = bincode::deserialize(&data).context("deserializing snapshot state")?;
+        Ok(Some((height as u64, state)))
+    }
+
     async fn load_anchor_view(&self) -> anyhow::Result<ViewNumber> {
         let mut tx = self.db.read().await?;
         let (view,) = query_as::<(i64,)>("SELECT coalesce(max(view), 0) FROM anchor_leaf2")
@@ -1263,21 +1433,104 @@ impl SequencerPersistence for Persistence {
         _epoch: Option<EpochNumber>,
         action: HotShotAction,
     ) -> anyhow::Result<()> {
-        // Todo Remove this after https://github.com/EspressoSystems/espresso-sequencer/issues/1931
+        // Signed messages are recorded with `record_signed_action`; the only other actions which
+        // matter after a restart are votes and proposals, which advance the highest acted view.
         if !matches!(action, HotShotAction::Propose | HotShotAction::Vote) {
             return Ok(());
         }
 
-        let stmt = format!(
-            "INSERT INTO highest_voted_view (id, view) VALUES (0, $1)
-            ON CONFLICT (id) DO UPDATE SET view = {MAX_FN}(highest_voted_view.view, excluded.view)"
-        );
+        let mut tx = self.db.write().await?;
+        update_voted_view(&mut tx, view).await?;
+        tx.commit().await
+    }
 
+    async fn record_signed_action(&self, record: SignedActionRecord) -> anyhow::Result<()> {
+        let view = record.view.u64() as i64;
+        let action = kind_to_sql(record.kind)?;
         let mut tx = self.db.write().await?;
-        tx.execute(query(&stmt).bind(view.u64() as i64)).await?;
+
+        // Check for conflicts in the same transaction which records the new message, to report
+        // which message the new one conflicts with.
+        let existing =
+            query("SELECT commitment FROM signed_action WHERE view = $1 AND action = $2")
+                .bind(view)
+                .bind(action.as_str())
+                .fetch_all(tx.as_mut())
+                .await?
+                .into_iter()
+                .map(|row| {
+                    let commitment: Vec<u8> = row.try_get("commitment")?;
+                    Ok(SignedActionRecord {
+                        commitment: commitment
+                            .as_slice()
+                            .try_into()
+                            .context("malformed commitment")?,
+                        ..record
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+        record.check_conflicts(&existing)?;
+
+        // A unique index on messages which may only be signed once per view guarantees that two
+        // conflicting messages can never both be recorded, even by concurrent transactions which
+        // both passed the check above.
+        if let Err(err) = tx
+            .upsert(
+                "signed_action",
+                ["view", "action", "commitment", "epoch"],
+                ["view", "action", "commitment"],
+                [(
+                    view,
+                    action,
+                    record.commitment.to_vec(),
+                    record.epoch.map(|epoch| epoch.u64() as i64),
+                )],
+            )
+            .await
+        {
+            if is_unique_violation(&err) {
+                bail!(
+                    "refusing to sign {:?} for view {}: a conflicting message was signed \
+                     concurrently",
+                    record.kind,
+                    record.view,
+                );
+            }
+            return Err(err);
+        }
+        if record.is_vote_or_proposal() {
+            update_voted_view(&mut tx, record.view).await?;
+        }
         tx.commit().await
     }
 
+    async fn load_signed_actions(&self) -> anyhow::Result<Vec<SignedActionRecord>> {
+        let rows = self
+            .db
+            .read()
+            .await?
+            .fetch_all("SELECT view, action, commitment, epoch FROM signed_action ORDER BY view")
+            .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let view: i64 = row.try_get("view")?;
+                let action: String = row.try_get("action")?;
+                let commitment: Vec<u8> = row.try_get("commitment")?;
+                let epoch: Option<i64> = row.try_get("epoch")?;
+                Ok(SignedActionRecord {
+                    kind: kind_from_sql(action)?,
+                    view: ViewNumber::new(view as u64),
+                    epoch: epoch.map(|epoch| EpochNumber::new(epoch as u64)),
+                    commitment: commitment
+                        .as_slice()
+                        .try_into()
+                        .context("malformed commitment")?,
+                })
+            })
+            .collect()
+    }
+
     async fn append_quorum_proposal2(
         &self,
         proposal: &Proposal<SeqTypes, QuorumProposalWrapper<SeqTypes>>,
@@ -2054,7 +2307,9 @@ impl MembershipPersistence for Persistence {
         let mut tx = self.db.write().await?;
 
         let rows = match query_as::<(i64, Vec<u8>)>(
-            "SELECT epoch, stake FROM epoch_drb_and_root ORDER BY epoch DESC LIMIT $1",
+            "SELECT epoch, stake FROM epoch_drb_and_root
+              WHERE stake IS NOT NULL
+              ORDER BY epoch DESC LIMIT $1",
         )
         .bind(limit as i64)
         .fetch_all(tx.as_mut())
@@ -2132,6 +2387,151 @@ impl MembershipPersistence for Persistence {
     }
 }
 
+#[async_trait]
+impl L1Persistence for Persistence {
+    async fn load_finalized_l1_block(
+        &self,
+        number: u64,
+    ) -> anyhow::Result<Option<(L1BlockInfo, B256)>> {
+        let mut tx = self.db.read().await?;
+        let Some((data, parent_hash)) = query_as::<(serde_json::Value, Vec<u8>)>(
+            "SELECT data, parent_hash FROM finalized_l1_block WHERE number = $1",
+        )
+        .bind(number as i64)
+        .fetch_optional(tx.as_mut())
+        .await?
+        else {
+            return Ok(None);
+        };
+        let block = serde_json::from_value(data).context("deserializing L1 block")?;
+        let parent_hash =
+            B256::try_from(parent_hash.as_slice()).context("malformed parent hash")?;
+        Ok(Some((block, parent_hash)))
+    }
+
+    async fn store_finalized_l1_block(
+        &self,
+        block: L1BlockInfo,
+        parent_hash: B256,
+    ) -> anyhow::Result<()> {
+        let data = serde_json::to_value(block).context("serializing L1 block")?;
+        let mut tx = self.db.write().await?;
+        tx.upsert(
+            "finalized_l1_block",
+            ["number", "data", "parent_hash"],
+            ["number"],
+            [(block.number as i64, data, parent_hash.to_vec())],
+        )
+        .await?;
+        tx.commit().await
+    }
+
+    async fn load_l1_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Option<Vec<FeeInfo>>> {
+        let mut tx = self.db.read().await?;
+        let Some((scanned_from, scanned_to)) = query_as::<(i64, i64)>(
+            "SELECT from_block, to_block FROM l1_deposits_scanned WHERE fee_contract = $1",
+        )
+        .bind(fee_contract.to_vec())
+        .fetch_optional(tx.as_mut())
+        .await?
+        else {
+            return Ok(None);
+        };
+        if (scanned_from as u64) > from || (scanned_to as u64) < to {
+            return Ok(None);
+        }
+
+        let rows = query_as::<(serde_json::Value,)>(
+            "SELECT data FROM l1_deposits
+              WHERE fee_contract = $1 AND l1_block >= $2 AND l1_block <= $3
+              ORDER BY l1_block",
+        )
+        .bind(fee_contract.to_vec())
+        .bind(from as i64)
+        .bind(to as i64)
+        .fetch_all(tx.as_mut())
+        .await?;
+        let mut deposits = vec![];
+        for (data,) in rows {
+            let block_deposits: Vec<FeeInfo> =
+                serde_json::from_value(data).context("deserializing L1 deposits")?;
+            deposits.extend(block_deposits);
+        }
+        Ok(Some(deposits))
+    }
+
+    async fn store_l1_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+        deposits: &[(u64, FeeInfo)],
+    ) -> anyhow::Result<()> {
+        let mut by_block = BTreeMap::<u64, Vec<FeeInfo>>::new();
+        for (block, deposit) in deposits {
+            by_block.entry(*block).or_default().push(*deposit);
+        }
+        let rows = by_block
+            .into_iter()
+            .map(|(block, deposits)| {
+                let data = serde_json::to_value(deposits).context("serializing L1 deposits")?;
+                Ok((fee_contract.to_vec(), block as i64, data))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut tx = self.db.write().await?;
+        if !rows.is_empty() {
+            tx.upsert(
+                "l1_deposits",
+                ["fee_contract", "l1_block", "data"],
+                ["fee_contract", "l1_block"],
+                rows,
+            )
+            .await?;
+        }
+
+        // Extend the range of blocks known to be scanned.
+        let scanned = query_as::<(i64, i64)>(
+            "SELECT from_block, to_block FROM l1_deposits_scanned WHERE fee_contract = $1",
+        )
+        .bind(fee_contract.to_vec())
+        .fetch_optional(tx.as_mut())
+        .await?
+        .map(|(from, to)| (from as u64, to as u64));
+        let (from, to) = merge_scanned_l1_range(scanned, from, to);
+        tx.upsert(
+            "l1_deposits_scanned",
+            ["fee_contract", "from_block", "to_block"],
+            ["fee_contract"],
+            [(fee_contract.to_vec(), from as i64, to as i64)],
+        )
+        .await?;
+        tx.commit().await
+    }
+
+    async fn prune_l1_cache(&self, height: u64) -> anyhow::Result<()> {
+        let height = height as i64;
+        let mut tx = self.db.write().await?;
+        tx.execute(query("DELETE FROM finalized_l1_block WHERE number < $1").bind(height))
+            .await?;
+        tx.execute(query("DELETE FROM l1_deposits WHERE l1_block < $1").bind(height))
+            .await?;
+        tx.execute(query("DELETE FROM l1_deposits_scanned WHERE to_block < $1").bind(height))
+            .await?;
+        tx.execute(
+            query("UPDATE l1_deposits_scanned SET from_block = $1 WHERE from_block < $1")
+                .bind(height),
+        )
+        .await?;
+        tx.commit().await
+    }
+}
+
 #[async_trait]
 impl Provider<SeqTypes, VidCommonRequest> for Persistence {
     #[tracing::instrument(skip(self))]
@@ -2553,9 +2953,10 @@ mod test {
         assert_eq!(
             Some(VidCommon::V1(avidm_param)),
             storage
-                .fetch(VidCommonRequest(VidCommitment::V1(
-                    vid_share.data.payload_commitment
-                )))
+                .fetch(VidCommonRequest(
+                    VidCommitment::V1(vid_share.data.payload_commitment),
+                    leaf.height()
+                ))
                 .await
         );
         assert_eq!(
@@ -189,7 +189,12 @@ where
                 },
             }
 
-            let future = self.consensus.read().await.request_proposal(view, leaf)?;
+            let future = self
+                .consensus
+                .read()
+                .await
+                .request_proposal(view, leaf)
+                .await?;
             let proposal = timeout(self.cfg.fetch_timeout, future)
                 .await
                 .context("timed out fetching proposal")?
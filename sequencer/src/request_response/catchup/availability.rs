@@ -0,0 +1,722 @@
+//! Fetching of VID data and payloads from peers over the request-response protocol, and the query
+//! service [`Provider`] implementations built on top of it.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{Arc, OnceLock},
+};
+
+use anyhow::{bail, ensure, Context};
+use async_trait::async_trait;
+use committable::Committable;
+use espresso_types::{
+    traits::SequencerPersistence, NamespaceId, NsProof, NsTable, Payload, PubKey, SeqTypes,
+    Transaction,
+};
+use hotshot::traits::NodeImplementation;
+use hotshot_query_service::{
+    availability::LeafQueryData,
+    data_source::AvailabilityProvider,
+    fetching::{
+        request::{LeafRequest, PayloadRequest, VidCommonRequest},
+        Provider,
+    },
+    VidCommon,
+};
+use hotshot_types::{
+    data::{
+        ns_table, vid_disperse::vid_total_weight, EpochNumber, VidCommitment, VidDisperseShare,
+    },
+    traits::{
+        network::ConnectedNetwork,
+        node_implementation::{ConsensusTime, Versions},
+        EncodeBytes,
+    },
+    utils::epoch_from_block_number,
+    vid::{
+        advz::{advz_scheme, ADVZScheme},
+        avidm::{init_avidm_param, AvidMParam, AvidMScheme},
+    },
+};
+use jf_vid::VidScheme;
+use tokio::time::timeout;
+
+use crate::request_response::{
+    request::{Request, Response},
+    RequestResponseProtocol,
+};
+
+impl<
+        I: NodeImplementation<SeqTypes>,
+        V: Versions,
+        N: ConnectedNetwork<PubKey>,
+        P: SequencerPersistence,
+    > RequestResponseProtocol<I, V, N, P>
+{
+    /// Fetch the VID share of `recipient` for the payload with commitment `commit`
+    pub async fn fetch_vid_share(
+        &self,
+        commit: VidCommitment,
+        recipient: PubKey,
+    ) -> anyhow::Result<VidDisperseShare<SeqTypes>> {
+        tracing::info!("Fetching VID share for {recipient} of payload {commit}");
+
+        // Create the response validation function
+        let response_validation_fn = move |_request: &Request, response: Response| async move {
+            check_vid_share(commit, recipient, response)
+        };
+
+        // Wait for the protocol to send us the VID share
+        let response = self
+            .request_indefinitely(
+                &self.public_key,
+                &self.signer,
+                self.config.incoming_request_ttl,
+                Request::VidShare(commit, recipient),
+                response_validation_fn,
+            )
+            .await
+            .with_context(|| "failed to request VID share")?;
+
+        tracing::info!("Fetched VID share for {recipient} of payload {commit}");
+
+        Ok(response)
+    }
+
+    /// Fetch the VID common data for the payload with commitment `commit`, which is the payload of
+    /// the block at `height`
+    pub async fn fetch_vid_common(
+        &self,
+        commit: VidCommitment,
+        height: u64,
+    ) -> anyhow::Result<VidCommon> {
+        tracing::info!("Fetching VID common for payload {commit}");
+
+        // AvidM common data is not bound to the commitment, so work out what it should be from the
+        // stake table before asking for it
+        let expected_params = match commit {
+            VidCommitment::V0(_) => vec![],
+            VidCommitment::V1(_) => self.expected_avidm_params(height).await?,
+        };
+
+        // Create the response validation function
+        let response_validation_fn = move |_request: &Request, response: Response| {
+            // Clone the expected parameters
+            let expected_params = expected_params.clone();
+            async move { check_vid_common(commit, &expected_params, response) }
+        };
+
+        // Wait for the protocol to send us the VID common data
+        let response = self
+            .request_indefinitely(
+                &self.public_key,
+                &self.signer,
+                self.config.incoming_request_ttl,
+                Request::VidCommon(commit),
+                response_validation_fn,
+            )
+            .await
+            .with_context(|| "failed to request VID common")?;
+
+        tracing::info!("Fetched VID common for payload {commit}");
+
+        Ok(response)
+    }
+
+    /// Get the AvidM parameters the VID common data of the block at `height` could have been
+    /// dispersed with.
+    ///
+    /// The parameters are derived from the total weight of the stake table. A block near the end
+    /// of an epoch may be dispersed to the stake table of the next epoch, so both are allowed.
+    async fn expected_avidm_params(&self, height: u64) -> anyhow::Result<Vec<AvidMParam>> {
+        let epoch_height = self.memberships.epoch_height;
+        let epochs = if epoch_height == 0 {
+            vec![None]
+        } else {
+            let epoch = EpochNumber::new(epoch_from_block_number(height, epoch_height));
+            vec![Some(epoch), Some(epoch + 1)]
+        };
+
+        let mut params = vec![];
+        for epoch in epochs {
+            let membership = match self.memberships.membership_for_epoch(epoch).await {
+                Ok(membership) => membership,
+                Err(err) => {
+                    tracing::debug!("stake table for epoch {epoch:?} not available: {err:#}");
+                    continue;
+                },
+            };
+            let total_weight = vid_total_weight::<SeqTypes>(membership.stake_table().await, epoch);
+            params.push(
+                init_avidm_param(total_weight)
+                    .with_context(|| "unable to initialize AvidM parameters")?,
+            );
+        }
+        ensure!(
+            !params.is_empty(),
+            "no stake table available to check VID common of block {height}"
+        );
+
+        Ok(params)
+    }
+
+    /// Fetch the payload with commitment `commit`, along with its VID common data
+    pub async fn fetch_payload(
+        &self,
+        commit: VidCommitment,
+    ) -> anyhow::Result<(Payload, VidCommon)> {
+        tracing::info!("Fetching payload {commit}");
+
+        // Create the response validation function
+        let response_validation_fn = move |_request: &Request, response: Response| async move {
+            check_payload(commit, response)
+        };
+
+        // Wait for the protocol to send us the payload
+        let response = self
+            .request_indefinitely(
+                &self.public_key,
+                &self.signer,
+                self.config.incoming_request_ttl,
+                Request::Payload(commit),
+                response_validation_fn,
+            )
+            .await
+            .with_context(|| "failed to request payload")?;
+
+        tracing::info!("Fetched payload {commit}");
+
+        Ok(response)
+    }
+
+    /// Fetch the transactions in namespace `ns_id` of the payload with commitment `commit`.
+    ///
+    /// The namespace table of the payload must be provided (usually from its header) so that the
+    /// namespace proof can be checked.
+    pub async fn fetch_namespace_payload(
+        &self,
+        commit: VidCommitment,
+        ns_table: NsTable,
+        ns_id: NamespaceId,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        tracing::info!("Fetching namespace {ns_id} of payload {commit}");
+
+        // A namespace that is not in the payload has no transactions, and no peer could prove
+        // otherwise
+        if ns_table.find_ns_id(&ns_id).is_none() {
+            return Ok(vec![]);
+        }
+
+        // Create the response validation function
+        let response_validation_fn = move |_request: &Request, response: Response| {
+            // Clone the namespace table
+            let ns_table = ns_table.clone();
+            async move { check_namespace_payload(commit, &ns_table, ns_id, response) }
+        };
+
+        // Wait for the protocol to send us the namespace
+        let response = self
+            .request_indefinitely(
+                &self.public_key,
+                &self.signer,
+                self.config.incoming_request_ttl,
+                Request::NamespacePayload(commit, ns_id),
+                response_validation_fn,
+            )
+            .await
+            .with_context(|| "failed to request namespace payload")?;
+
+        tracing::info!("Fetched namespace {ns_id} of payload {commit}");
+
+        Ok(response)
+    }
+
+    /// Fetch the leaf at the height given by `req`, along with a QC for it.
+    ///
+    /// The leaf and QC are checked against the hashes in the request.
+    async fn fetch_leaf_query_data(
+        &self,
+        req: LeafRequest<SeqTypes>,
+    ) -> anyhow::Result<LeafQueryData<SeqTypes>> {
+        tracing::info!("Fetching leaf {} for the query service", req.height);
+
+        // Create the response validation function
+        let response_validation_fn = move |_request: &Request, response: Response| async move {
+            // Make sure the response is a leaf response
+            let Response::Leaf(leaf_chain) = response else {
+                bail!("expected leaf response");
+            };
+
+            // The leaf chain starts with the requested leaf, and one of its descendants is
+            // justified by the QC for it
+            let leaf = leaf_chain
+                .first()
+                .with_context(|| "leaf chain is empty")?
+                .clone();
+            ensure!(leaf.height() == req.height, "leaf has the wrong height");
+            ensure!(
+                leaf.commit() == req.expected_leaf,
+                "leaf has the wrong hash"
+            );
+            let qc = leaf_chain
+                .iter()
+                .map(|leaf| leaf.justify_qc())
+                .find(|qc| qc.commit() == req.expected_qc)
+                .with_context(|| "leaf chain does not contain the expected QC")?;
+
+            // This also drops the payload, if any, since we fetch and store payloads separately
+            LeafQueryData::new(leaf, qc).with_context(|| "QC is not for the leaf")
+        };
+
+        // Wait for the protocol to send us the leaf chain
+        let response = self
+            .request_indefinitely(
+                &self.public_key,
+                &self.signer,
+                self.config.incoming_request_ttl,
+                Request::Leaf(req.height),
+                response_validation_fn,
+            )
+            .await
+            .with_context(|| "failed to request leaf")?;
+
+        tracing::info!("Fetched leaf {} for the query service", req.height);
+
+        Ok(response)
+    }
+}
+
+/// Check that `response` is the VID share of `recipient` for the payload with commitment `commit`
+fn check_vid_share(
+    commit: VidCommitment,
+    recipient: PubKey,
+    response: Response,
+) -> anyhow::Result<VidDisperseShare<SeqTypes>> {
+    // Make sure the response is a VID share response
+    let Response::VidShare(share) = response else {
+        bail!("expected VID share response");
+    };
+
+    // Make sure it is the share we asked for
+    ensure!(
+        share.payload_commitment() == commit,
+        "VID share is for the wrong payload"
+    );
+    ensure!(
+        share.recipient_key() == &recipient,
+        "VID share is for the wrong recipient"
+    );
+
+    // Verify the share against the commitment. The total weight is part of the common data, which
+    // is itself bound to the commitment.
+    let total_weight = match &share {
+        VidDisperseShare::V0(share) => ADVZScheme::get_num_storage_nodes(&share.common) as usize,
+        VidDisperseShare::V1(share) => share.common.total_weights,
+    };
+    share
+        .verify_share(total_weight)
+        .map_err(|_| anyhow::anyhow!("invalid VID share"))?;
+
+    Ok(share)
+}
+
+/// Check that `response` is the VID common data for the payload with commitment `commit`.
+///
+/// AvidM common data must also be one of `expected_params`, since it cannot be checked against the
+/// commitment.
+fn check_vid_common(
+    commit: VidCommitment,
+    expected_params: &[AvidMParam],
+    response: Response,
+) -> anyhow::Result<VidCommon> {
+    // Make sure the response is a VID common response
+    let Response::VidCommon(common) = response else {
+        bail!("expected VID common response");
+    };
+
+    verify_vid_common(commit, &common)?;
+    if let VidCommon::V1(param) = &common {
+        ensure!(
+            expected_params.contains(param),
+            "VID common does not match the stake table (total weight {})",
+            param.total_weights
+        );
+    }
+
+    Ok(common)
+}
+
+/// Check that `response` is the payload with commitment `commit`
+fn check_payload(
+    commit: VidCommitment,
+    response: Response,
+) -> anyhow::Result<(Payload, VidCommon)> {
+    // Make sure the response is a payload response
+    let Response::Payload(payload, common) = response else {
+        bail!("expected payload response");
+    };
+
+    // Recompute the commitment of the payload we were sent
+    verify_vid_common(commit, &common)?;
+    ensure!(
+        payload_commitment(&payload, &common)? == commit,
+        "payload commitment mismatch"
+    );
+
+    Ok((payload, common))
+}
+
+/// Check that `response` proves the transactions in namespace `ns_id` of the payload with
+/// commitment `commit` and namespace table `ns_table`
+fn check_namespace_payload(
+    commit: VidCommitment,
+    ns_table: &NsTable,
+    ns_id: NamespaceId,
+    response: Response,
+) -> anyhow::Result<Vec<Transaction>> {
+    // Make sure the response is a namespace payload response
+    let Response::NamespacePayload(proof, common) = response else {
+        bail!("expected namespace payload response");
+    };
+
+    // Verify the namespace proof
+    verify_vid_common(commit, &common)?;
+    let (transactions, proof_ns_id) = proof
+        .verify(ns_table, &commit, &common)
+        .with_context(|| "invalid namespace proof")?;
+    ensure!(
+        proof_ns_id == ns_id,
+        "namespace proof is for the wrong namespace"
+    );
+
+    Ok(transactions)
+}
+
+/// Check that `common` is consistent with the VID commitment `commit`
+fn verify_vid_common(commit: VidCommitment, common: &VidCommon) -> anyhow::Result<()> {
+    match (commit, common) {
+        (VidCommitment::V0(commit), VidCommon::V0(common)) => {
+            ADVZScheme::is_consistent(&commit, common)
+                .map_err(|_| anyhow::anyhow!("VID common is inconsistent with commitment"))
+        },
+        // AvidM common data is just the VID parameters, which cannot be checked on their own. Any
+        // payload or proof checked with the wrong parameters will fail to match the commitment.
+        (VidCommitment::V1(_), VidCommon::V1(_)) => Ok(()),
+        _ => bail!("VID common version does not match commitment"),
+    }
+}
+
+/// Compute the VID commitment of `payload` using the parameters in `common`
+fn payload_commitment(payload: &Payload, common: &VidCommon) -> anyhow::Result<VidCommitment> {
+    let bytes = payload.encode();
+    match common {
+        VidCommon::V0(common) => {
+            let num_storage_nodes = ADVZScheme::get_num_storage_nodes(common) as usize;
+            advz_scheme(num_storage_nodes)
+                .commit_only(bytes)
+                .map(VidCommitment::V0)
+                .with_context(|| "unable to compute VID commitment")
+        },
+        VidCommon::V1(common) => {
+            let param = init_avidm_param(common.total_weights)
+                .with_context(|| "unable to initialize AvidM parameters")?;
+            let metadata = payload.ns_table().encode();
+            AvidMScheme::commit(
+                &param,
+                &bytes,
+                ns_table::parse_ns_table(bytes.len(), &metadata),
+            )
+            .map(VidCommitment::V1)
+            .with_context(|| "unable to compute AvidM commitment")
+        },
+    }
+}
+
+#[async_trait]
+impl<
+        I: NodeImplementation<SeqTypes>,
+        V: Versions,
+        N: ConnectedNetwork<PubKey>,
+        P: SequencerPersistence,
+    > Provider<SeqTypes, PayloadRequest> for RequestResponseProtocol<I, V, N, P>
+{
+    async fn fetch(&self, req: PayloadRequest) -> Option<Payload> {
+        // Timeout after a few batches
+        let timeout_duration = self.config.request_batch_interval * 3;
+
+        match timeout(timeout_duration, self.fetch_payload(req.0)).await {
+            Ok(Ok((payload, _))) => Some(payload),
+            Ok(Err(err)) => {
+                tracing::warn!("failed to fetch payload {}: {err:#}", req.0);
+                None
+            },
+            Err(_) => {
+                tracing::warn!("timed out while fetching payload {}", req.0);
+                None
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<
+        I: NodeImplementation<SeqTypes>,
+        V: Versions,
+        N: ConnectedNetwork<PubKey>,
+        P: SequencerPersistence,
+    > Provider<SeqTypes, VidCommonRequest> for RequestResponseProtocol<I, V, N, P>
+{
+    async fn fetch(&self, req: VidCommonRequest) -> Option<VidCommon> {
+        // Timeout after a few batches
+        let timeout_duration = self.config.request_batch_interval * 3;
+
+        match timeout(timeout_duration, self.fetch_vid_common(req.0, req.1)).await {
+            Ok(Ok(common)) => Some(common),
+            Ok(Err(err)) => {
+                tracing::warn!("failed to fetch VID common {}: {err:#}", req.0);
+                None
+            },
+            Err(_) => {
+                tracing::warn!("timed out while fetching VID common {}", req.0);
+                None
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl<
+        I: NodeImplementation<SeqTypes>,
+        V: Versions,
+        N: ConnectedNetwork<PubKey>,
+        P: SequencerPersistence,
+    > Provider<SeqTypes, LeafRequest<SeqTypes>> for RequestResponseProtocol<I, V, N, P>
+{
+    async fn fetch(&self, req: LeafRequest<SeqTypes>) -> Option<LeafQueryData<SeqTypes>> {
+        // Timeout after a few batches
+        let timeout_duration = self.config.request_batch_interval * 3;
+
+        match timeout(timeout_duration, self.fetch_leaf_query_data(req)).await {
+            Ok(Ok(leaf)) => Some(leaf),
+            Ok(Err(err)) => {
+                tracing::warn!("failed to fetch leaf {}: {err:#}", req.height);
+                None
+            },
+            Err(_) => {
+                tracing::warn!("timed out while fetching leaf {}", req.height);
+                None
+            },
+        }
+    }
+}
+
+/// A query service [`Provider`] which fetches missing data from peers over the request-response
+/// protocol.
+///
+/// The query service is started before consensus, but the request-response protocol is created
+/// along with consensus. This provider can therefore be created empty and handed to the query
+/// service, and the protocol set once it exists. Until then, all fetches fail.
+#[derive(Clone, Default)]
+pub struct RequestResponseProvider {
+    protocol: Arc<OnceLock<Arc<dyn AvailabilityProvider<SeqTypes>>>>,
+}
+
+impl RequestResponseProvider {
+    /// Start fetching from peers using `protocol`. Only the first call has any effect
+    pub fn set<I, V, N, P>(&self, protocol: RequestResponseProtocol<I, V, N, P>)
+    where
+        I: NodeImplementation<SeqTypes>,
+        V: Versions,
+        N: ConnectedNetwork<PubKey>,
+        P: SequencerPersistence,
+    {
+        if self.protocol.set(Arc::new(protocol)).is_err() {
+            tracing::warn!("request-response provider was already set");
+        }
+    }
+}
+
+impl Debug for RequestResponseProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestResponseProvider")
+            .field("initialized", &self.protocol.get().is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Provider<SeqTypes, PayloadRequest> for RequestResponseProvider {
+    async fn fetch(&self, req: PayloadRequest) -> Option<Payload> {
+        Provider::<SeqTypes, PayloadRequest>::fetch(self.protocol.get()?.as_ref(), req).await
+    }
+}
+
+#[async_trait]
+impl Provider<SeqTypes, VidCommonRequest> for RequestResponseProvider {
+    async fn fetch(&self, req: VidCommonRequest) -> Option<VidCommon> {
+        Provider::<SeqTypes, VidCommonRequest>::fetch(self.protocol.get()?.as_ref(), req).await
+    }
+}
+
+#[async_trait]
+impl Provider<SeqTypes, LeafRequest<SeqTypes>> for RequestResponseProvider {
+    async fn fetch(&self, req: LeafRequest<SeqTypes>) -> Option<LeafQueryData<SeqTypes>> {
+        Provider::<SeqTypes, LeafRequest<SeqTypes>>::fetch(self.protocol.get()?.as_ref(), req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use espresso_types::NodeState;
+    use hotshot_types::{
+        data::{vid_disperse::VidDisperseShare2, ViewNumber},
+        traits::{node_implementation::ConsensusTime, signature_key::SignatureKey, BlockPayload},
+        vid::avidm::init_avidm_param,
+    };
+
+    use super::*;
+
+    const NUM_NODES: usize = 10;
+
+    /// A payload with a transaction in each of two namespaces, along with its VID common data and
+    /// commitment
+    async fn test_payload(tx: &[u8]) -> (Payload, VidCommon, VidCommitment) {
+        let payload = Payload::from_transactions(
+            [
+                Transaction::new(1u32.into(), tx.to_vec()),
+                Transaction::new(2u32.into(), vec![4, 5, 6]),
+            ],
+            &Default::default(),
+            &NodeState::mock(),
+        )
+        .await
+        .unwrap()
+        .0;
+        let common = VidCommon::V1(init_avidm_param(NUM_NODES).unwrap());
+        let commit = payload_commitment(&payload, &common).unwrap();
+        (payload, common, commit)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_vid_share() {
+        let (payload, common, commit) = test_payload(&[1, 2, 3]).await;
+        let VidCommon::V1(param) = common else {
+            unreachable!();
+        };
+        let (key, _) = PubKey::generated_from_seed_indexed([0; 32], 0);
+
+        let bytes = payload.encode();
+        let metadata = payload.ns_table().encode();
+        let (avidm_commit, shares) = AvidMScheme::ns_disperse(
+            &param,
+            &[1; NUM_NODES],
+            &bytes,
+            ns_table::parse_ns_table(bytes.len(), &metadata),
+        )
+        .unwrap();
+        assert_eq!(VidCommitment::V1(avidm_commit), commit);
+        let share = VidDisperseShare::V1(VidDisperseShare2 {
+            view_number: ViewNumber::genesis(),
+            epoch: None,
+            target_epoch: None,
+            payload_commitment: avidm_commit,
+            share: shares[0].clone(),
+            recipient_key: key,
+            common: param,
+        });
+
+        let checked = check_vid_share(commit, key, Response::VidShare(share.clone())).unwrap();
+        assert_eq!(checked, share);
+
+        // A share for someone else
+        let (other_key, _) = PubKey::generated_from_seed_indexed([0; 32], 1);
+        check_vid_share(commit, other_key, Response::VidShare(share.clone())).unwrap_err();
+
+        // A share of another payload
+        let (_, _, other_commit) = test_payload(&[7, 8, 9]).await;
+        check_vid_share(other_commit, key, Response::VidShare(share)).unwrap_err();
+
+        // The wrong kind of response
+        check_vid_share(commit, key, Response::Leaf(vec![])).unwrap_err();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_vid_common() {
+        let (_, common, commit) = test_payload(&[1, 2, 3]).await;
+        let VidCommon::V1(param) = common.clone() else {
+            unreachable!();
+        };
+        check_vid_common(
+            commit,
+            &[param.clone()],
+            Response::VidCommon(common.clone()),
+        )
+        .unwrap();
+
+        // Parameters for a different total weight than the stake table has
+        let other_param = init_avidm_param(NUM_NODES + 1).unwrap();
+        let err =
+            check_vid_common(commit, &[other_param], Response::VidCommon(common)).unwrap_err();
+        assert!(err.to_string().contains("stake table"), "{err:#}");
+
+        // The wrong kind of response
+        check_vid_common(commit, &[param], Response::Leaf(vec![])).unwrap_err();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_payload() {
+        let (payload, common, commit) = test_payload(&[1, 2, 3]).await;
+        let (checked, _) =
+            check_payload(commit, Response::Payload(payload.clone(), common.clone())).unwrap();
+        assert_eq!(checked, payload);
+
+        // A payload that does not match the commitment
+        let (other_payload, ..) = test_payload(&[7, 8, 9]).await;
+        let err =
+            check_payload(commit, Response::Payload(other_payload, common.clone())).unwrap_err();
+        assert!(err.to_string().contains("mismatch"), "{err:#}");
+
+        // The wrong kind of response
+        check_payload(commit, Response::VidCommon(common)).unwrap_err();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_namespace_payload() {
+        let (payload, common, commit) = test_payload(&[1, 2, 3]).await;
+        let ns_table = payload.ns_table().clone();
+        let ns_id = NamespaceId::from(1u32);
+        let ns_index = ns_table.find_ns_id(&ns_id).unwrap();
+        let proof = NsProof::new(&payload, &ns_index, &common).unwrap();
+
+        let transactions = check_namespace_payload(
+            commit,
+            &ns_table,
+            ns_id,
+            Response::NamespacePayload(proof.clone(), common.clone()),
+        )
+        .unwrap();
+        assert_eq!(transactions, vec![Transaction::new(ns_id, vec![1, 2, 3])]);
+
+        // A proof of a different namespace
+        check_namespace_payload(
+            commit,
+            &ns_table,
+            2u32.into(),
+            Response::NamespacePayload(proof.clone(), common.clone()),
+        )
+        .unwrap_err();
+
+        // A proof against a different payload
+        let (_, _, other_commit) = test_payload(&[7, 8, 9]).await;
+        check_namespace_payload(
+            other_commit,
+            &ns_table,
+            ns_id,
+            Response::NamespacePayload(proof, common.clone()),
+        )
+        .unwrap_err();
+
+        // The wrong kind of response
+        check_namespace_payload(commit, &ns_table, ns_id, Response::VidCommon(common)).unwrap_err();
+    }
+}
@@ -189,7 +189,7 @@ impl<
         let response = self
             .request_indefinitely(
                 &self.public_key,
-                &self.private_key,
+                &self.signer,
                 self.config.incoming_request_ttl,
                 Request::Accounts(height, *view, accounts),
                 response_validation_fn,
@@ -241,7 +241,7 @@ impl<
         let response = self
             .request_indefinitely(
                 &self.public_key,
-                &self.private_key,
+                &self.signer,
                 self.config.incoming_request_ttl,
                 Request::Leaf(height),
                 response_validation_fn,
@@ -281,7 +281,7 @@ impl<
         let response = self
             .request_indefinitely(
                 &self.public_key,
-                &self.private_key,
+                &self.signer,
                 self.config.incoming_request_ttl,
                 Request::ChainConfig(commitment),
                 response_validation_fn,
@@ -340,7 +340,7 @@ impl<
         let response = self
             .request_indefinitely(
                 &self.public_key,
-                &self.private_key,
+                &self.signer,
                 self.config.incoming_request_ttl,
                 Request::BlocksFrontier(height, *view),
                 response_validation_fn,
@@ -399,7 +399,7 @@ impl<
         let response = self
             .request_indefinitely(
                 &self.public_key,
-                &self.private_key,
+                &self.signer,
                 self.config.incoming_request_ttl,
                 Request::RewardAccounts(height, *view, accounts),
                 response_validation_fn,
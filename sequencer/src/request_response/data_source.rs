@@ -10,12 +10,13 @@ use espresso_types::{
     retain_accounts,
     traits::SequencerPersistence,
     v0_1::{RewardAccount, RewardMerkleTree},
-    NodeState, PubKey, SeqTypes,
+    NodeState, NsProof, Payload, PubKey, SeqTypes,
 };
+use futures::try_join;
 use hotshot::{traits::NodeImplementation, SystemContext};
-use hotshot_query_service::data_source::storage::SqlStorage;
+use hotshot_query_service::{data_source::storage::SqlStorage, VidCommon};
 use hotshot_types::{
-    data::ViewNumber,
+    data::{VidCommitment, VidDisperseShare, ViewNumber},
     traits::{
         network::ConnectedNetwork,
         node_implementation::{ConsensusTime, Versions},
@@ -237,10 +238,114 @@ impl<
 
                 Ok(Response::RewardAccounts(merkle_tree))
             },
+            Request::VidShare(commit, recipient) => {
+                // VID shares are only kept in consensus memory, so we can only serve shares for
+                // recent views
+                let consensus = self.consensus.consensus();
+                let consensus = consensus.read().await;
+                let share = consensus
+                    .vid_shares()
+                    .values()
+                    .filter_map(|shares| shares.get(recipient))
+                    .flat_map(|shares| shares.values())
+                    .find(|share| share.data.payload_commitment() == *commit)
+                    .with_context(|| {
+                        format!("VID share for {recipient} of payload {commit} not found")
+                    })?;
+
+                Ok(Response::VidShare(share.data.clone()))
+            },
+            Request::VidCommon(commit) => Ok(Response::VidCommon(self.vid_common(*commit).await?)),
+            Request::Payload(commit) => {
+                let (payload, common) = try_join!(self.payload(*commit), self.vid_common(*commit))?;
+                Ok(Response::Payload(payload, common))
+            },
+            Request::NamespacePayload(commit, ns_id) => {
+                let (payload, common) = try_join!(self.payload(*commit), self.vid_common(*commit))?;
+
+                // Prove the namespace against the payload commitment
+                let ns_index = payload
+                    .ns_table()
+                    .find_ns_id(ns_id)
+                    .with_context(|| format!("namespace {ns_id} is not in payload {commit}"))?;
+                let proof = NsProof::new(&payload, &ns_index, &common)
+                    .with_context(|| format!("failed to prove namespace {ns_id}"))?;
+
+                Ok(Response::NamespacePayload(proof, common))
+            },
         }
     }
 }
 
+impl<
+        I: NodeImplementation<SeqTypes>,
+        V: Versions,
+        N: ConnectedNetwork<PubKey>,
+        P: SequencerPersistence,
+    > DataSource<I, V, N, P>
+{
+    /// Get the VID common data for a payload, from memory if possible and otherwise from storage
+    async fn vid_common(&self, commit: VidCommitment) -> Result<VidCommon> {
+        // Every VID share carries the common data, so any share for the payload will do
+        let share = self
+            .consensus
+            .consensus()
+            .read()
+            .await
+            .vid_shares()
+            .values()
+            .flat_map(|shares| shares.values())
+            .flat_map(|shares| shares.values())
+            .find(|share| share.data.payload_commitment() == commit)
+            .map(|share| share.data.clone());
+        if let Some(share) = share {
+            return Ok(match share {
+                VidDisperseShare::V0(share) => VidCommon::V0(share.common),
+                VidDisperseShare::V1(share) => VidCommon::V1(share.common),
+            });
+        }
+
+        // Fall back to storage
+        self.storage
+            .as_ref()
+            .with_context(|| "storage was not initialized")?
+            .get_vid_common(commit)
+            .await
+            .with_context(|| "failed to get VID common from sql storage")
+    }
+
+    /// Get a payload, from memory if possible and otherwise from storage
+    async fn payload(&self, commit: VidCommitment) -> Result<Payload> {
+        // Payloads in memory are indexed by view, so find the view from the VID shares
+        let payload = {
+            let consensus = self.consensus.consensus();
+            let consensus = consensus.read().await;
+            consensus
+                .vid_shares()
+                .iter()
+                .find(|(_, shares)| {
+                    shares
+                        .values()
+                        .flat_map(|shares| shares.values())
+                        .any(|share| share.data.payload_commitment() == commit)
+                })
+                .and_then(|(view, _)| consensus.saved_payloads().get(view))
+                .map(|payload| payload.payload.clone())
+        };
+        if let Some(payload) = payload {
+            return Ok(payload);
+        }
+
+        // Fall back to storage
+        self.storage
+            .as_ref()
+            .with_context(|| "storage was not initialized")?
+            .get_payload(commit)
+            .await
+            .with_context(|| "failed to get payload from sql storage")
+    }
+}
+
 /// Get a partial snapshot of the given reward state, which contains only the specified accounts.
 ///
 /// Fails if one of the requested accounts is not represented in the original `state`.
@@ -1,8 +1,16 @@
+use std::sync::Arc;
+
 use data_source::DataSource;
 use derive_more::derive::Deref;
 use espresso_types::{traits::SequencerPersistence, PubKey, SeqTypes};
-use hotshot::{traits::NodeImplementation, types::BLSPrivKey};
-use hotshot_types::traits::{network::ConnectedNetwork, node_implementation::Versions};
+use hotshot::traits::NodeImplementation;
+use hotshot_types::{
+    epoch_membership::EpochMembershipCoordinator,
+    traits::{
+        metrics::Metrics, network::ConnectedNetwork, node_implementation::Versions,
+        signer::ConsensusSigner,
+    },
+};
 use network::Sender;
 use recipient_source::RecipientSource;
 use request::Request;
@@ -42,8 +50,10 @@ pub struct RequestResponseProtocol<
 
     /// The public key of this node
     public_key: PubKey,
-    /// The private key of this node
-    private_key: BLSPrivKey,
+    /// The signer this node signs its requests with
+    signer: Arc<dyn ConsensusSigner<SeqTypes>>,
+    /// The stake tables, which determine what some fetched data (e.g. VID parameters) should be
+    memberships: EpochMembershipCoordinator<SeqTypes>,
 }
 
 impl<
@@ -69,8 +79,12 @@ impl<
         data_source: DataSource<I, V, N, P>,
         // The public key of this node
         public_key: PubKey,
-        // The private key of this node
-        private_key: BLSPrivKey,
+        // The signer this node signs its requests and responses with
+        signer: Arc<dyn ConsensusSigner<SeqTypes>>,
+        // The stake tables, used to check fetched data
+        memberships: EpochMembershipCoordinator<SeqTypes>,
+        // The metrics to export peer reputation through
+        metrics: &dyn Metrics,
     ) -> Self {
         Self {
             inner: RequestResponse::new(
@@ -79,10 +93,14 @@ impl<
                 receiver,
                 recipient_source,
                 data_source,
+                public_key,
+                Arc::new(Arc::clone(&signer)),
+                metrics,
             ),
             config,
             public_key,
-            private_key,
+            signer,
+            memberships,
         }
     }
 }
@@ -1,13 +1,15 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, ensure, Context, Result};
 use async_trait::async_trait;
 use committable::Commitment;
 use espresso_types::{
     v0_1::{RewardAccount, RewardMerkleTree},
     v0_99::ChainConfig,
-    FeeAccount, FeeMerkleTree, Leaf2,
+    FeeAccount, FeeMerkleTree, Leaf2, NamespaceId, NsProof, Payload, PubKey, SeqTypes,
 };
+use hotshot_query_service::VidCommon;
+use hotshot_types::data::{VidCommitment, VidDisperseShare};
 use request_response::{request::Request as RequestTrait, Serializable};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::api::BlocksFrontier;
 
@@ -29,6 +31,14 @@ pub enum Request {
     BlocksFrontier(Height, ViewNumber),
     /// A request for the reward accounts at a given height and view
     RewardAccounts(Height, ViewNumber, Vec<RewardAccount>),
+    /// A request for the VID share of a particular node for the payload with a given commitment
+    VidShare(VidCommitment, PubKey),
+    /// A request for the VID common data for the payload with a given commitment
+    VidCommon(VidCommitment),
+    /// A request for the full payload with a given commitment
+    Payload(VidCommitment),
+    /// A request for a single namespace of the payload with a given commitment
+    NamespacePayload(VidCommitment, NamespaceId),
 }
 
 /// The outermost response type. This an enum that contains all the possible responses that the
@@ -45,6 +55,16 @@ pub enum Response {
     BlocksFrontier(BlocksFrontier),
     /// A response for the reward accounts at a given height and view
     RewardAccounts(RewardMerkleTree),
+    /// A response for the VID share of a particular node
+    VidShare(VidDisperseShare<SeqTypes>),
+    /// A response for the VID common data of a payload
+    VidCommon(VidCommon),
+    /// A response for a full payload, along with the VID common data needed to check it against
+    /// its commitment
+    Payload(Payload, VidCommon),
+    /// A response for a single namespace of a payload, along with the VID common data needed to
+    /// check the proof
+    NamespacePayload(NsProof, VidCommon),
 }
 
 /// Implement the `RequestTrait` trait for the `Request` type. This tells the request response
@@ -59,15 +79,119 @@ impl RequestTrait for Request {
     }
 }
 
+/// The version of the request-response wire format that a request or response requires.
+///
+/// Version 1 is the original format: plain `bincode` of the enums, which only ever contained the
+/// state catchup variants. Variants added after that are encoded with a [`VERSION_MARKER`] and a
+/// version number in front of the `bincode` body. Nodes that predate versioning see the marker as
+/// an unknown enum variant and reject the message, rather than misinterpreting it, and every node
+/// can tell a message from a newer protocol apart from a malformed one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u16)]
+pub enum WireVersion {
+    /// The original format, with only the state catchup variants
+    V1 = 1,
+    /// Adds the VID share, VID common and payload variants
+    V2 = 2,
+}
+
+impl WireVersion {
+    /// The latest version this node understands
+    pub const LATEST: Self = Self::V2;
+
+    fn from_u16(version: u16) -> Result<Self> {
+        match version {
+            1 => Ok(Self::V1),
+            2 => Ok(Self::V2),
+            _ => bail!("unsupported request-response wire version {version}"),
+        }
+    }
+}
+
+/// Prefix of a versioned message.
+///
+/// Legacy messages start with the `bincode` variant index of the enum, a little endian `u32` that
+/// is never anywhere near `u32::MAX`.
+const VERSION_MARKER: [u8; 4] = u32::MAX.to_le_bytes();
+
+impl Request {
+    /// The wire version needed to encode this request
+    pub fn version(&self) -> WireVersion {
+        match self {
+            Self::Accounts(..)
+            | Self::Leaf(..)
+            | Self::ChainConfig(..)
+            | Self::BlocksFrontier(..)
+            | Self::RewardAccounts(..) => WireVersion::V1,
+            Self::VidShare(..)
+            | Self::VidCommon(..)
+            | Self::Payload(..)
+            | Self::NamespacePayload(..) => WireVersion::V2,
+        }
+    }
+}
+
+impl Response {
+    /// The wire version needed to encode this response
+    pub fn version(&self) -> WireVersion {
+        match self {
+            Self::Accounts(..)
+            | Self::Leaf(..)
+            | Self::ChainConfig(..)
+            | Self::BlocksFrontier(..)
+            | Self::RewardAccounts(..) => WireVersion::V1,
+            Self::VidShare(..)
+            | Self::VidCommon(..)
+            | Self::Payload(..)
+            | Self::NamespacePayload(..) => WireVersion::V2,
+        }
+    }
+}
+
+/// Encode `value` in the oldest wire format that can represent it
+fn encode<T: Serialize>(value: &T, version: WireVersion) -> Result<Vec<u8>> {
+    if version == WireVersion::V1 {
+        // Keep the original encoding so nodes that predate versioning can still read it
+        return bincode::serialize(value).with_context(|| "failed to serialize");
+    }
+
+    let mut bytes = VERSION_MARKER.to_vec();
+    bytes.extend((version as u16).to_le_bytes());
+    bincode::serialize_into(&mut bytes, value).with_context(|| "failed to serialize")?;
+    Ok(bytes)
+}
+
+/// Decode a value in any wire format up to [`WireVersion::LATEST`], returning it along with the
+/// version it was encoded with
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<(T, WireVersion)> {
+    let Some(rest) = bytes.strip_prefix(&VERSION_MARKER) else {
+        let value = bincode::deserialize(bytes).with_context(|| "failed to deserialize")?;
+        return Ok((value, WireVersion::V1));
+    };
+
+    let [v0, v1, body @ ..] = rest else {
+        bail!("versioned message is missing its version");
+    };
+    let version = WireVersion::from_u16(u16::from_le_bytes([*v0, *v1]))?;
+    let value = bincode::deserialize(body).with_context(|| "failed to deserialize")?;
+    Ok((value, version))
+}
+
 /// Implement the `Serializable` trait for the `Request` type. This tells the request response
 /// protocol how to serialize and deserialize the request
 impl Serializable for Request {
     fn to_bytes(&self) -> Result<Vec<u8>> {
-        bincode::serialize(&self).with_context(|| "failed to serialize")
+        encode(self, self.version())
     }
 
     fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        bincode::deserialize(bytes).with_context(|| "failed to deserialize")
+        let (request, version): (Self, _) = decode(bytes)?;
+        ensure!(
+            request.version() <= version,
+            "request requires wire version {:?} but was encoded with {version:?}",
+            request.version()
+        );
+        Ok(request)
     }
 }
 
@@ -75,10 +199,172 @@ impl Serializable for Request {
 /// protocol how to serialize and deserialize the response.
 impl Serializable for Response {
     fn to_bytes(&self) -> Result<Vec<u8>> {
-        bincode::serialize(self).with_context(|| "failed to serialize")
+        encode(self, self.version())
     }
 
     fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        bincode::deserialize(bytes).with_context(|| "failed to deserialize")
+        let (response, version): (Self, _) = decode(bytes)?;
+        ensure!(
+            response.version() <= version,
+            "response requires wire version {:?} but was encoded with {version:?}",
+            response.version()
+        );
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use committable::Committable;
+    use espresso_types::{
+        v0_1::{BLOCK_MERKLE_TREE_HEIGHT, FEE_MERKLE_TREE_HEIGHT, REWARD_MERKLE_TREE_HEIGHT},
+        BlockMerkleTree, Transaction,
+    };
+    use hotshot_types::{
+        traits::{signature_key::SignatureKey, BlockPayload},
+        vid::avidm::init_avidm_param,
+    };
+    use jf_merkle_tree::{AppendableMerkleTreeScheme, MerkleTreeScheme};
+
+    use super::*;
+
+    /// The request enum as it was before versioning, as seen by an old node
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    enum LegacyRequest {
+        Accounts(Height, ViewNumber, Vec<FeeAccount>),
+        Leaf(Height),
+        ChainConfig(Commitment<ChainConfig>),
+        BlocksFrontier(Height, ViewNumber),
+        RewardAccounts(Height, ViewNumber, Vec<RewardAccount>),
+    }
+
+    async fn test_payload() -> (Payload, NamespaceId, VidCommon) {
+        let ns_id = NamespaceId::from(1u32);
+        let payload = Payload::from_transactions(
+            [Transaction::new(ns_id, vec![1, 2, 3])],
+            &Default::default(),
+            &Default::default(),
+        )
+        .await
+        .unwrap()
+        .0;
+        let common = VidCommon::V1(init_avidm_param(10).unwrap());
+        (payload, ns_id, common)
+    }
+
+    fn requests(ns_id: NamespaceId) -> Vec<Request> {
+        let (key, _) = PubKey::generated_from_seed_indexed([0; 32], 0);
+        vec![
+            Request::Accounts(1, 2, vec![FeeAccount::default()]),
+            Request::Leaf(3),
+            Request::ChainConfig(ChainConfig::default().commit()),
+            Request::BlocksFrontier(4, 5),
+            Request::RewardAccounts(6, 7, vec![RewardAccount::default()]),
+            Request::VidShare(VidCommitment::default(), key),
+            Request::VidCommon(VidCommitment::default()),
+            Request::Payload(VidCommitment::default()),
+            Request::NamespacePayload(VidCommitment::default(), ns_id),
+        ]
+    }
+
+    async fn responses() -> Vec<Response> {
+        let (payload, ns_id, common) = test_payload().await;
+        let ns_index = payload.ns_table().find_ns_id(&ns_id).unwrap();
+        let proof = NsProof::new(&payload, &ns_index, &common).unwrap();
+
+        let mut blocks = BlockMerkleTree::new(BLOCK_MERKLE_TREE_HEIGHT);
+        blocks.push(Commitment::from_raw([0; 32])).unwrap();
+        let (_, frontier) = blocks.lookup(0).expect_ok().unwrap();
+
+        vec![
+            Response::Accounts(FeeMerkleTree::new(FEE_MERKLE_TREE_HEIGHT)),
+            Response::Leaf(vec![]),
+            Response::ChainConfig(ChainConfig::default()),
+            Response::BlocksFrontier(frontier),
+            Response::RewardAccounts(RewardMerkleTree::new(REWARD_MERKLE_TREE_HEIGHT)),
+            Response::VidCommon(common.clone()),
+            Response::Payload(payload, common.clone()),
+            Response::NamespacePayload(proof, common),
+        ]
+    }
+
+    #[test]
+    fn test_request_round_trip() {
+        for request in requests(NamespaceId::from(1u32)) {
+            let bytes = request.to_bytes().unwrap();
+            let decoded = Request::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.version(), request.version());
+            assert_eq!(decoded.to_bytes().unwrap(), bytes, "{request:?}");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_response_round_trip() {
+        for response in responses().await {
+            let bytes = response.to_bytes().unwrap();
+            let decoded = Response::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.version(), response.version());
+            assert_eq!(decoded.to_bytes().unwrap(), bytes, "{response:?}");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_legacy_compatibility() {
+        for request in requests(NamespaceId::from(1u32)) {
+            let bytes = request.to_bytes().unwrap();
+            match request.version() {
+                // Original requests are encoded exactly as before, so old nodes can still serve
+                // them
+                WireVersion::V1 => {
+                    assert_eq!(bytes, bincode::serialize(&request).unwrap());
+                    bincode::deserialize::<LegacyRequest>(&bytes).unwrap();
+                },
+                // Newer requests are rejected by old nodes
+                WireVersion::V2 => {
+                    assert!(bytes.starts_with(&VERSION_MARKER));
+                    bincode::deserialize::<LegacyRequest>(&bytes).unwrap_err();
+                },
+            }
+        }
+
+        // Original responses are encoded exactly as before
+        for response in responses().await {
+            if response.version() == WireVersion::V1 {
+                assert_eq!(
+                    response.to_bytes().unwrap(),
+                    bincode::serialize(&response).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_version_checks() {
+        let request = Request::VidCommon(VidCommitment::default());
+
+        // A message from a newer protocol is rejected
+        let mut bytes = VERSION_MARKER.to_vec();
+        bytes.extend(3u16.to_le_bytes());
+        bytes.extend(bincode::serialize(&request).unwrap());
+        let err = Request::from_bytes(&bytes).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("unsupported request-response wire version"),
+            "{err:#}"
+        );
+
+        // So is a newer request encoded with the legacy format
+        Request::from_bytes(&bincode::serialize(&request).unwrap()).unwrap_err();
+
+        // And a truncated versioned message
+        Request::from_bytes(&VERSION_MARKER).unwrap_err();
+
+        // Legacy requests can be sent in the versioned format
+        let bytes = encode(&Request::Leaf(1), WireVersion::V2).unwrap();
+        assert!(matches!(
+            Request::from_bytes(&bytes).unwrap(),
+            Request::Leaf(1)
+        ));
     }
 }
@@ -18,7 +18,9 @@ use super::{
     context::SequencerContext,
     init_node, network,
     options::{Modules, Options},
-    persistence, Genesis, L1Params, NetworkParams,
+    persistence,
+    signer::NodeKeys,
+    Genesis, L1Params, NetworkParams,
 };
 
 pub async fn main() -> anyhow::Result<()> {
@@ -151,7 +153,15 @@ where
     S: DataSourceOptions,
     V: Versions,
 {
-    let (private_staking_key, private_state_key) = opt.private_keys()?;
+    // A node signing with a remote signer learns its keys from the signer, and never loads its
+    // private keys.
+    let keys = match opt.remote_signer.connect().await? {
+        Some(keys) => keys,
+        None => {
+            let (private_staking_key, private_state_key) = opt.private_keys()?;
+            NodeKeys::local(private_staking_key, private_state_key)?
+        },
+    };
     let l1_params = L1Params {
         urls: opt.l1_provider_url,
         options: opt.l1_options,
@@ -165,8 +175,7 @@ where
         orchestrator_url: opt.orchestrator_url,
         state_relay_server_url: opt.state_relay_server_url,
         public_api_url: opt.public_api_url,
-        private_staking_key,
-        private_state_key,
+        keys,
         state_peers: opt.state_peers,
         config_peers: opt.config_peers,
         catchup_backoff: opt.catchup_backoff,
@@ -191,6 +200,7 @@ where
         libp2p_heartbeat_initial_delay: opt.libp2p_heartbeat_initial_delay,
         libp2p_gossip_factor: opt.libp2p_gossip_factor,
         libp2p_gossip_lazy: opt.libp2p_gossip_lazy,
+        signed_request_responses: opt.signed_request_responses,
     };
 
     let marketplace_config = MarketplaceConfig {
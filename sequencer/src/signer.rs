@@ -0,0 +1,583 @@
+//! Signing consensus messages with keys held by a separate process.
+//!
+//! A [`RemoteSigner`] connects to an `espresso-signer` process over TCP or a Unix socket and asks
+//! it to sign votes, proposals and light client states on behalf of the node. The signer keeps a
+//! record of everything it has signed, and refuses to sign two different messages of the same kind
+//! for the same view, or two different light client states for the same block height. Thus even a
+//! misconfigured node, or two nodes accidentally running with the same keys, cannot equivocate.
+//! The signer is sent the votes and proposals themselves rather than commitments to them, so it
+//! works out for itself what it is signing and for which view, rather than trusting the node.
+//!
+//! A node signing with a remote signer never holds its private keys. It learns its public keys and
+//! its Libp2p identity from the signer, and signs everything else it needs to (such as network
+//! handshakes and requests to peers) through the signer as well.
+//!
+//! # Protocol
+//!
+//! Every message is a length-prefixed, bincode-encoded frame. On accepting a connection, the
+//! signer sends a random challenge. The client answers with a random nonce of its own and an
+//! HMAC-SHA256 of both, keyed by a secret shared by the node and the signer, and the signer replies
+//! with the outcome of the authentication. From then on, the client sends [`SignRequest`]s and the
+//! signer answers each with a [`SignResponse`]. Each of these frames carries an HMAC keyed by a
+//! session key derived from the secret, the challenge and the nonce, and covering the direction and
+//! position of the frame in the session, so frames cannot be forged, replayed or reordered.
+
+use std::{fmt::Display, io, path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use alloy::primitives::U256;
+use anyhow::{anyhow, bail, ensure, Context};
+use async_lock::Mutex;
+use async_trait::async_trait;
+use clap::Parser;
+use derivative::Derivative;
+use espresso_types::{PubKey, SeqTypes};
+use hmac::{Hmac, Mac};
+use hotshot::traits::implementations::{
+    derive_libp2p_keypair, libp2p_keypair_from_secret, CdnSigningKey,
+};
+use hotshot_types::{
+    light_client::{
+        LightClientState, StakeTableState, StateKeyPair, StateSignKey, StateSignature, StateVerKey,
+    },
+    signature_key::BLSPrivKey,
+    traits::{
+        signature_key::{SignatureKey, StateSignatureKey},
+        signer::{ConsensusSigner, LocalSigner, SignableMessage},
+    },
+    PeerConfig,
+};
+use libp2p::identity::Keypair;
+use rand::RngCore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, UnixStream},
+    time::timeout,
+};
+use zeroize::Zeroizing;
+
+pub mod server;
+
+/// The largest frame either side of a connection will accept.
+///
+/// This must be large enough for a DA proposal, which carries a whole block payload.
+const MAX_FRAME_LEN: u32 = 1 << 26;
+
+/// How long to wait for the signer to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The keys a node runs with.
+///
+/// The node only holds its public keys and Libp2p identity. Everything else is signed by `signer`,
+/// so a node signing with a [`RemoteSigner`] never sees its private keys.
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct NodeKeys {
+    /// The key the node is staked with
+    pub public_key: PubKey,
+    /// The key the node signs light client states with
+    pub state_public_key: StateVerKey,
+    /// The node's Libp2p identity, which is derived from its staking key
+    #[derivative(Debug = "ignore")]
+    pub libp2p_keypair: Keypair,
+    /// Signs messages with the node's private keys
+    #[derivative(Debug = "ignore")]
+    pub signer: Arc<dyn ConsensusSigner<SeqTypes>>,
+    /// The private staking key, if it is held by this process
+    #[derivative(Debug = "ignore")]
+    staking_key: Option<BLSPrivKey>,
+}
+
+impl NodeKeys {
+    /// Sign with private keys held by this process.
+    pub fn local(staking_key: BLSPrivKey, state_key: StateSignKey) -> anyhow::Result<Self> {
+        let state_public_key = StateKeyPair::from_sign_key(state_key.clone()).ver_key();
+        Ok(Self {
+            public_key: PubKey::from_private(&staking_key),
+            state_public_key,
+            libp2p_keypair: derive_libp2p_keypair::<PubKey>(&staking_key)
+                .context("deriving Libp2p keypair")?,
+            signer: Arc::new(LocalSigner::<SeqTypes>::new(staking_key.clone(), state_key)),
+            staking_key: Some(staking_key),
+        })
+    }
+
+    /// The node's public configuration, with the given stake.
+    pub fn peer_config(&self, stake: U256) -> PeerConfig<SeqTypes> {
+        PeerConfig {
+            stake_table_entry: self.public_key.stake_table_entry(stake),
+            state_ver_key: self.state_public_key.clone(),
+        }
+    }
+
+    /// The key to authenticate with the CDN with.
+    pub fn cdn_signing_key(&self) -> CdnSigningKey<PubKey> {
+        match &self.staking_key {
+            Some(key) => CdnSigningKey::Local(key.clone()),
+            None => CdnSigningKey::from_signer(Arc::clone(&self.signer)),
+        }
+    }
+}
+
+/// Options for signing with a remote signer.
+#[derive(Clone, Derivative, Parser)]
+#[derivative(Debug)]
+pub struct RemoteSignerOptions {
+    /// Address of a remote signer holding the consensus keys.
+    ///
+    /// Either `tcp://HOST:PORT` or `unix:PATH`. If set, the node does not need its private keys:
+    /// votes, proposals, light client states and everything else the node signs are signed by the
+    /// remote signer, which protects against signing conflicting messages.
+    #[clap(
+        long,
+        env = "ESPRESSO_SEQUENCER_REMOTE_SIGNER",
+        requires = "remote_signer_secret_file",
+        conflicts_with_all = [
+            "KEY_FILE",
+            "private_staking_key",
+            "private_state_key",
+            "staking_keystore",
+            "state_keystore",
+        ]
+    )]
+    pub remote_signer: Option<SignerAddress>,
+
+    /// File containing the secret used to authenticate with the remote signer.
+    #[clap(long, env = "ESPRESSO_SEQUENCER_REMOTE_SIGNER_SECRET_FILE")]
+    #[derivative(Debug = "ignore")]
+    pub remote_signer_secret_file: Option<PathBuf>,
+}
+
+impl RemoteSignerOptions {
+    /// Connect to the remote signer, if one is configured, and get the keys of the node it signs
+    /// for.
+    pub async fn connect(&self) -> anyhow::Result<Option<NodeKeys>> {
+        let Some(address) = &self.remote_signer else {
+            return Ok(None);
+        };
+        let secret_file = self
+            .remote_signer_secret_file
+            .as_ref()
+            .context("remote signer secret file is required")?;
+        let keys = RemoteSigner::connect(address.clone(), read_secret(secret_file)?).await?;
+        tracing::info!(%address, public_key = %keys.public_key, "signing with remote signer");
+        Ok(Some(keys))
+    }
+}
+
+/// The address of a remote signer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignerAddress {
+    /// A TCP address, `HOST:PORT`.
+    Tcp(String),
+    /// The path of a Unix socket.
+    Unix(PathBuf),
+}
+
+impl FromStr for SignerAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            ensure!(!path.is_empty(), "missing socket path");
+            Ok(Self::Unix(path.into()))
+        } else if let Some(addr) = s.strip_prefix("tcp://") {
+            ensure!(addr.contains(':'), "missing port in TCP address {addr}");
+            Ok(Self::Tcp(addr.into()))
+        } else {
+            bail!("signer address must start with tcp:// or unix:");
+        }
+    }
+}
+
+impl Display for SignerAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp://{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A bidirectional connection between a node and a signer.
+pub(crate) trait Connection: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Connection for T {}
+
+impl SignerAddress {
+    async fn connect(&self) -> io::Result<Box<dyn Connection>> {
+        Ok(match self {
+            Self::Tcp(addr) => Box::new(TcpStream::connect(addr).await?),
+            Self::Unix(path) => Box::new(UnixStream::connect(path).await?),
+        })
+    }
+}
+
+/// A request for a signature.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignRequest {
+    /// Sign a message with the consensus key.
+    Consensus(SignableMessage<SeqTypes>),
+    /// Sign a light client state.
+    State {
+        state: LightClientState,
+        next_stake_table: StakeTableState,
+    },
+    /// Get the keys of the node the signer signs for.
+    Identity,
+}
+
+/// The answer to a [`SignRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignResponse {
+    Consensus(<PubKey as SignatureKey>::PureAssembledSignatureType),
+    State(StateSignature),
+    Identity(SignerIdentity),
+    /// The signer refused to sign, for the given reason.
+    Refused(String),
+}
+
+/// The keys of the node a signer signs for.
+#[derive(Clone, Derivative, PartialEq, Eq, Serialize, Deserialize)]
+#[derivative(Debug)]
+pub struct SignerIdentity {
+    pub public_key: PubKey,
+    pub state_public_key: StateVerKey,
+    /// The secret of the node's Libp2p keypair, which is derived from its staking key
+    #[derivative(Debug = "ignore")]
+    pub libp2p_secret: [u8; 32],
+}
+
+/// Read the secret shared by a node and its signer from `path`.
+///
+/// Surrounding whitespace is ignored, so the secret can be generated with, for example,
+/// `openssl rand -hex 32 > secret`.
+pub fn read_secret(path: &std::path::Path) -> anyhow::Result<Zeroizing<Vec<u8>>> {
+    let contents = Zeroizing::new(
+        std::fs::read(path).context(format!("reading signer secret {}", path.display()))?,
+    );
+    let secret = Zeroizing::new(contents.trim_ascii().to_vec());
+    ensure!(
+        !secret.is_empty(),
+        "signer secret {} is empty",
+        path.display()
+    );
+    Ok(secret)
+}
+
+/// Compute the response to an authentication challenge.
+pub(crate) fn auth_mac(secret: &[u8], challenge: &[u8], nonce: &[u8]) -> Hmac<Sha256> {
+    keyed_mac(secret, b"espresso-signer-auth", &[challenge, nonce])
+}
+
+/// An HMAC of `parts`, keyed by `key` and domain-separated by `label`.
+fn keyed_mac(key: &[u8], label: &[u8], parts: &[&[u8]]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(label);
+    for part in parts {
+        mac.update(&(part.len() as u64).to_le_bytes());
+        mac.update(part);
+    }
+    mac
+}
+
+/// An authenticated connection between a node and a signer.
+///
+/// Every frame sent over the channel carries an HMAC keyed by a session key, over the direction of
+/// the frame, its sequence number in that direction and its contents.
+pub(crate) struct Channel {
+    conn: Box<dyn Connection>,
+    key: Zeroizing<Vec<u8>>,
+    /// Whether this is the node's end of the channel
+    client: bool,
+    /// The number of frames sent
+    sent: u64,
+    /// The number of frames received
+    received: u64,
+}
+
+impl Channel {
+    /// Set up a channel after the challenge and nonce have been exchanged.
+    pub(crate) fn new(
+        conn: Box<dyn Connection>,
+        secret: &[u8],
+        challenge: &[u8],
+        nonce: &[u8],
+        client: bool,
+    ) -> Self {
+        let key = keyed_mac(secret, b"espresso-signer-session", &[challenge, nonce])
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        Self {
+            conn,
+            key: Zeroizing::new(key),
+            client,
+            sent: 0,
+            received: 0,
+        }
+    }
+
+    fn frame_mac(&self, from_client: bool, seq: u64, payload: &[u8]) -> Hmac<Sha256> {
+        keyed_mac(
+            &self.key,
+            b"espresso-signer-frame",
+            &[&[from_client as u8], &seq.to_le_bytes(), payload],
+        )
+    }
+
+    /// Send `value` as an authenticated frame.
+    pub(crate) async fn send(&mut self, value: &impl Serialize) -> anyhow::Result<()> {
+        let payload = bincode::serialize(value).context("serializing frame")?;
+        let tag = self
+            .frame_mac(self.client, self.sent, &payload)
+            .finalize()
+            .into_bytes()
+            .to_vec();
+        write_frame(&mut self.conn, &(payload, tag)).await?;
+        self.sent += 1;
+        Ok(())
+    }
+
+    /// Receive an authenticated frame, or `None` if the connection was closed.
+    pub(crate) async fn recv<T: DeserializeOwned>(&mut self) -> anyhow::Result<Option<T>> {
+        let Some((payload, tag)) = read_frame::<(Vec<u8>, Vec<u8>)>(&mut self.conn).await? else {
+            return Ok(None);
+        };
+        self.frame_mac(!self.client, self.received, &payload)
+            .verify_slice(&tag)
+            .map_err(|_| anyhow!("frame failed authentication"))?;
+        self.received += 1;
+        Ok(Some(
+            bincode::deserialize(&payload).context("malformed frame")?,
+        ))
+    }
+}
+
+/// Signs consensus messages by sending them to an `espresso-signer` process.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RemoteSigner {
+    address: SignerAddress,
+    #[derivative(Debug = "ignore")]
+    secret: Zeroizing<Vec<u8>>,
+    /// The key which signatures from the signer must be valid for
+    public_key: PubKey,
+    /// The key which light client state signatures from the signer must be valid for
+    state_public_key: StateVerKey,
+    /// The connection to the signer, if one is open
+    #[derivative(Debug = "ignore")]
+    channel: Mutex<Option<Channel>>,
+}
+
+impl RemoteSigner {
+    /// Connect to the signer at `address`, and get the keys of the node it signs for.
+    ///
+    /// Signatures from the signer are checked against the public keys it reports, so that a signer
+    /// which does not hold the private keys it claims to is detected immediately.
+    pub async fn connect(
+        address: SignerAddress,
+        secret: Zeroizing<Vec<u8>>,
+    ) -> anyhow::Result<NodeKeys> {
+        let mut channel = open(&address, &secret).await?;
+        let identity = match request(&mut channel, &SignRequest::Identity).await? {
+            SignResponse::Identity(identity) => identity,
+            SignResponse::Refused(reason) => bail!("signer refused to identify itself: {reason}"),
+            res => bail!("unexpected response from signer: {res:?}"),
+        };
+        let libp2p_keypair = libp2p_keypair_from_secret(identity.libp2p_secret)
+            .context("signer returned an invalid Libp2p secret")?;
+
+        let signer = Self {
+            address,
+            secret,
+            public_key: identity.public_key,
+            state_public_key: identity.state_public_key.clone(),
+            channel: Mutex::new(Some(channel)),
+        };
+        Ok(NodeKeys {
+            public_key: identity.public_key,
+            state_public_key: identity.state_public_key,
+            libp2p_keypair,
+            signer: Arc::new(signer),
+            staking_key: None,
+        })
+    }
+
+    /// Send a request to the signer and wait for its answer.
+    async fn request(&self, req: &SignRequest) -> anyhow::Result<SignResponse> {
+        let mut channel = self.channel.lock().await;
+
+        // If the request fails, the signer may have restarted since the connection was opened, so
+        // try once more with a fresh connection. Repeating a request is always safe, since the
+        // signer will sign the same message again.
+        let mut retried = false;
+        loop {
+            if channel.is_none() {
+                *channel = Some(open(&self.address, &self.secret).await?);
+            }
+            match request(channel.as_mut().unwrap(), req).await {
+                Ok(res) => return Ok(res),
+                Err(err) if !retried => {
+                    tracing::warn!("request to signer failed, reconnecting: {err:#}");
+                    *channel = None;
+                    retried = true;
+                },
+                Err(err) => {
+                    *channel = None;
+                    return Err(err);
+                },
+            }
+        }
+    }
+}
+
+/// Open and authenticate a new connection to the signer at `address`.
+async fn open(address: &SignerAddress, secret: &[u8]) -> anyhow::Result<Channel> {
+    let mut conn = address
+        .connect()
+        .await
+        .context(format!("connecting to signer at {address}"))?;
+    let challenge: Vec<u8> = read_frame(&mut conn)
+        .await?
+        .context("signer closed the connection")?;
+    let mut nonce = vec![0; 32];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let response = auth_mac(secret, &challenge, &nonce)
+        .finalize()
+        .into_bytes()
+        .to_vec();
+    write_frame(&mut conn, &(nonce.clone(), response)).await?;
+    match read_frame::<Result<(), String>>(&mut conn).await? {
+        Some(Ok(())) => Ok(Channel::new(conn, secret, &challenge, &nonce, true)),
+        Some(Err(err)) => bail!("signer rejected authentication: {err}"),
+        None => bail!("signer closed the connection during authentication"),
+    }
+}
+
+/// Send a request over `channel` and wait for the answer.
+async fn request(channel: &mut Channel, req: &SignRequest) -> anyhow::Result<SignResponse> {
+    timeout(REQUEST_TIMEOUT, async {
+        channel.send(req).await?;
+        channel
+            .recv()
+            .await?
+            .context("signer closed the connection")
+    })
+    .await
+    .unwrap_or_else(|_| Err(anyhow!("timed out waiting for signer")))
+}
+
+#[async_trait]
+impl ConsensusSigner<SeqTypes> for RemoteSigner {
+    async fn sign(
+        &self,
+        msg: &SignableMessage<SeqTypes>,
+    ) -> anyhow::Result<<PubKey as SignatureKey>::PureAssembledSignatureType> {
+        // Fail early on messages the signer would refuse anyway.
+        let signed_bytes = msg.signed_bytes()?;
+        match self.request(&SignRequest::Consensus(msg.clone())).await? {
+            SignResponse::Consensus(sig) => {
+                ensure!(
+                    self.public_key.validate(&sig, &signed_bytes),
+                    "signer returned a signature which is not valid for {}",
+                    self.public_key
+                );
+                Ok(sig)
+            },
+            SignResponse::Refused(reason) => {
+                bail!("signer refused to sign {:?}: {reason}", msg.kind())
+            },
+            res => bail!("unexpected response from signer: {res:?}"),
+        }
+    }
+
+    async fn sign_state(
+        &self,
+        state: &LightClientState,
+        next_stake_table: &StakeTableState,
+    ) -> anyhow::Result<StateSignature> {
+        let req = SignRequest::State {
+            state: *state,
+            next_stake_table: *next_stake_table,
+        };
+        match self.request(&req).await? {
+            SignResponse::State(sig) => {
+                ensure!(
+                    self.state_public_key
+                        .verify_state_sig(&sig, state, next_stake_table),
+                    "signer returned a state signature which is not valid for {}",
+                    self.state_public_key
+                );
+                Ok(sig)
+            },
+            SignResponse::Refused(reason) => {
+                bail!("signer refused to sign light client state: {reason}")
+            },
+            res => bail!("unexpected response from signer: {res:?}"),
+        }
+    }
+}
+
+/// Write `value` as a length-prefixed bincode frame.
+pub(crate) async fn write_frame(
+    conn: &mut (impl AsyncWrite + Unpin + ?Sized),
+    value: &impl Serialize,
+) -> anyhow::Result<()> {
+    let bytes = bincode::serialize(value).context("serializing frame")?;
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|len| *len <= MAX_FRAME_LEN)
+        .context("frame too large")?;
+    conn.write_all(&len.to_le_bytes()).await?;
+    conn.write_all(&bytes).await?;
+    conn.flush().await?;
+    Ok(())
+}
+
+/// Read a length-prefixed bincode frame, or `None` if the connection was closed.
+pub(crate) async fn read_frame<T: DeserializeOwned>(
+    conn: &mut (impl AsyncRead + Unpin + ?Sized),
+) -> anyhow::Result<Option<T>> {
+    let mut len = [0; 4];
+    match conn.read_exact(&mut len).await {
+        Ok(_) => {},
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let len = u32::from_le_bytes(len);
+    ensure!(len <= MAX_FRAME_LEN, "frame of {len} bytes is too large");
+
+    let mut bytes = vec![0; len as usize];
+    conn.read_exact(&mut bytes)
+        .await
+        .context("connection closed in frame")?;
+    Ok(Some(
+        bincode::deserialize(&bytes).context("malformed frame")?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_signer_address() {
+        assert_eq!(
+            "tcp://localhost:9000".parse::<SignerAddress>().unwrap(),
+            SignerAddress::Tcp("localhost:9000".into())
+        );
+        assert_eq!(
+            "unix:/run/signer.sock".parse::<SignerAddress>().unwrap(),
+            SignerAddress::Unix("/run/signer.sock".into())
+        );
+        for addr in [
+            "localhost:9000",
+            "tcp://localhost",
+            "unix:",
+            "http://localhost:9000",
+        ] {
+            addr.parse::<SignerAddress>().unwrap_err();
+        }
+    }
+}
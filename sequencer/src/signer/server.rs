@@ -0,0 +1,681 @@
+//! The `espresso-signer` side of the remote signing protocol.
+
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy::primitives::B256;
+use anyhow::{bail, ensure, Context};
+use async_lock::Mutex;
+use derivative::Derivative;
+use espresso_types::{PubKey, SeqTypes, SignedActionRecord};
+use hmac::Mac;
+use hotshot::traits::implementations::derive_libp2p_secret;
+use hotshot_types::{
+    light_client::{LightClientState, StakeTableState, StateKeyPair, StateSignKey},
+    signature_key::BLSPrivKey,
+    traits::{
+        node_implementation::ConsensusTime,
+        signature_key::SignatureKey,
+        signer::{ConsensusSigner, LocalSigner, SignableMessage},
+    },
+};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::net::{TcpListener, UnixListener};
+use zeroize::Zeroizing;
+
+use super::{
+    auth_mac, read_frame, write_frame, Channel, Connection, SignRequest, SignResponse,
+    SignerAddress, SignerIdentity,
+};
+
+/// How many of the most recent views the signing history remembers messages for.
+///
+/// The signer refuses to sign messages for views older than this, since it no longer knows whether
+/// it has already signed a different message for them.
+const HISTORY_VIEWS: usize = 10_000;
+
+/// How many of the most recent block heights the signing history remembers light client states
+/// for.
+const HISTORY_STATES: usize = 10_000;
+
+/// An entry in the [`SigningHistory`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum HistoryEntry {
+    /// A consensus message was signed.
+    Message(SignedActionRecord),
+    /// A light client state was signed for block `height`.
+    State { height: u64, digest: [u8; 32] },
+}
+
+impl HistoryEntry {
+    /// The entry recording a consensus message, if it needs to be recorded at all.
+    ///
+    /// Messages which may be signed any number of times in the same view can never conflict, so
+    /// they are not recorded. Fails if the message is malformed, so that nothing which would not be
+    /// signed is recorded.
+    fn message(msg: &SignableMessage<SeqTypes>) -> anyhow::Result<Option<Self>> {
+        // The kind and view of the message, and the bytes to be signed, are all computed from the
+        // message itself, so a client cannot label a message as something it is not.
+        let signed = msg.signed_bytes()?;
+        let kind = msg.kind();
+        if !kind.is_unique_per_view() {
+            return Ok(None);
+        }
+        let view = msg
+            .view()
+            .context(format!("{kind:?} is not for any particular view"))?;
+        let digest: [u8; 32] = Sha256::digest(signed).into();
+        Ok(Some(Self::Message(SignedActionRecord {
+            kind,
+            view,
+            epoch: None,
+            commitment: B256::from(digest),
+        })))
+    }
+
+    fn state(state: &LightClientState, next_stake_table: &StakeTableState) -> anyhow::Result<Self> {
+        let bytes = bincode::serialize(&(state, next_stake_table))?;
+        Ok(Self::State {
+            height: state.block_height,
+            digest: Sha256::digest(bytes).into(),
+        })
+    }
+
+    /// The entry recording `req`, if it needs to be recorded at all.
+    fn for_request(req: &SignRequest) -> anyhow::Result<Option<Self>> {
+        Ok(match req {
+            SignRequest::Consensus(msg) => Self::message(msg)?,
+            SignRequest::State {
+                state,
+                next_stake_table,
+            } => Some(Self::state(state, next_stake_table)?),
+            SignRequest::Identity => None,
+        })
+    }
+}
+
+/// A record of everything a signer has signed, used to refuse conflicting requests.
+///
+/// Consensus messages are recorded as [`SignedActionRecord`]s and checked with the same rules as
+/// the signing history kept in consensus storage. Only digests of the signed messages are
+/// recorded, so a request can be checked against the history without being able to recover what
+/// was signed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SigningHistory {
+    /// The messages signed in each recent view
+    messages: BTreeMap<u64, Vec<SignedActionRecord>>,
+    /// The digest of the light client state signed at each recent block height
+    states: BTreeMap<u64, [u8; 32]>,
+}
+
+impl SigningHistory {
+    /// Record a consensus message about to be signed.
+    ///
+    /// Fails if the message is malformed, if a different message of the same kind has already been
+    /// signed for its view, or if its view is too old to tell.
+    pub fn record_message(&mut self, msg: &SignableMessage<SeqTypes>) -> anyhow::Result<()> {
+        match HistoryEntry::message(msg)? {
+            Some(entry) => self.record(entry),
+            None => Ok(()),
+        }
+    }
+
+    /// Record a light client state about to be signed.
+    ///
+    /// Fails if a different state, or the same state with a different stake table, has already
+    /// been signed for the same block height, or if the height is too old to tell.
+    pub fn record_state(
+        &mut self,
+        state: &LightClientState,
+        next_stake_table: &StakeTableState,
+    ) -> anyhow::Result<()> {
+        self.record(HistoryEntry::state(state, next_stake_table)?)
+    }
+
+    fn record(&mut self, entry: HistoryEntry) -> anyhow::Result<()> {
+        if self.check(&entry)? {
+            self.insert(entry);
+        }
+        Ok(())
+    }
+
+    /// Check `entry` against the history.
+    ///
+    /// Returns whether the entry is new, or an error if it conflicts with the history.
+    fn check(&self, entry: &HistoryEntry) -> anyhow::Result<bool> {
+        match entry {
+            HistoryEntry::Message(record) => {
+                let view = record.view.u64();
+                let signed = self
+                    .messages
+                    .get(&view)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                if signed.contains(record) {
+                    return Ok(false);
+                }
+                record.check_conflicts(signed)?;
+                ensure_recent(&self.messages, view, HISTORY_VIEWS)
+                    .context(format!("{:?} for view {view}", record.kind))?;
+            },
+            HistoryEntry::State { height, digest } => {
+                if let Some(signed) = self.states.get(height) {
+                    ensure!(
+                        signed == digest,
+                        "already signed a conflicting light client state for height {height}"
+                    );
+                    return Ok(false);
+                }
+                ensure_recent(&self.states, *height, HISTORY_STATES)
+                    .context(format!("light client state for height {height}"))?;
+            },
+        }
+        Ok(true)
+    }
+
+    /// Add `entry` to the history, forgetting the oldest entries if it is full.
+    fn insert(&mut self, entry: HistoryEntry) {
+        match entry {
+            HistoryEntry::Message(record) => {
+                self.messages
+                    .entry(record.view.u64())
+                    .or_default()
+                    .push(record);
+                truncate(&mut self.messages, HISTORY_VIEWS);
+            },
+            HistoryEntry::State { height, digest } => {
+                self.states.insert(height, digest);
+                truncate(&mut self.states, HISTORY_STATES);
+            },
+        }
+    }
+
+    /// All the entries in the history.
+    fn entries(&self) -> impl Iterator<Item = HistoryEntry> + '_ {
+        let messages = self
+            .messages
+            .values()
+            .flatten()
+            .map(|record| HistoryEntry::Message(*record));
+        let states = self
+            .states
+            .iter()
+            .map(|(height, digest)| HistoryEntry::State {
+                height: *height,
+                digest: *digest,
+            });
+        messages.chain(states)
+    }
+
+    /// The number of entries in the history.
+    fn len(&self) -> usize {
+        self.messages.values().map(Vec::len).sum::<usize>() + self.states.len()
+    }
+}
+
+/// Fail if `index` is older than everything in a full `history`, in which case it may have been
+/// forgotten.
+fn ensure_recent<T>(history: &BTreeMap<u64, T>, index: u64, capacity: usize) -> anyhow::Result<()> {
+    if history.len() >= capacity && !history.contains_key(&index) {
+        if let Some((&oldest, _)) = history.first_key_value() {
+            ensure!(
+                index > oldest,
+                "too old to check against the signing history"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Forget the oldest entries in `history` until it holds at most `capacity` entries.
+fn truncate<T>(history: &mut BTreeMap<u64, T>, capacity: usize) {
+    while history.len() > capacity {
+        history.pop_first();
+    }
+}
+
+/// A [`SigningHistory`] persisted to an append-only log.
+///
+/// Each new entry is appended to the log as a line of JSON, and synced to disk before the
+/// corresponding signature is released, so the signer cannot be tricked into signing conflicting
+/// messages by restarting it. Once the log has grown well past the size of the history, it is
+/// compacted by rewriting it with just the entries still in the history.
+#[derive(Debug)]
+pub struct SlashingProtection {
+    path: PathBuf,
+    log: File,
+    /// The number of entries in the log
+    log_len: usize,
+    history: SigningHistory,
+}
+
+impl SlashingProtection {
+    /// Load the signing history from the log at `path`, or start a new one if the file does not
+    /// exist.
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let mut history = SigningHistory::default();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => {
+                let mut lines = contents.split_inclusive('\n').peekable();
+                while let Some(line) = lines.next() {
+                    match serde_json::from_str(line) {
+                        Ok(entry) => history.insert(entry),
+                        // The signer may have crashed in the middle of appending the last entry,
+                        // in which case the signature for it was never released.
+                        Err(err) if lines.peek().is_none() && !line.ends_with('\n') => {
+                            tracing::warn!(
+                                path = %path.display(),
+                                "ignoring truncated last entry in signing history: {err:#}"
+                            );
+                        },
+                        Err(err) => {
+                            return Err(err)
+                                .context(format!("malformed signing history {}", path.display()))
+                        },
+                    }
+                }
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                tracing::warn!(path = %path.display(), "starting new signing history");
+            },
+            Err(err) => {
+                return Err(err).context(format!("reading signing history {}", path.display()))
+            },
+        }
+
+        let log_len = history.len();
+        let log = compact(&path, &history)?;
+        Ok(Self {
+            path,
+            log,
+            log_len,
+            history,
+        })
+    }
+
+    /// Check `req` against the history and record it, if it does not conflict.
+    fn record(&mut self, req: &SignRequest) -> anyhow::Result<()> {
+        let Some(entry) = HistoryEntry::for_request(req)? else {
+            return Ok(());
+        };
+        if !self.history.check(&entry)? {
+            return Ok(());
+        }
+
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        self.log
+            .write_all(&line)
+            .and_then(|()| self.log.sync_data())
+            .context(format!("appending to {}", self.path.display()))?;
+        self.history.insert(entry);
+        self.log_len += 1;
+
+        if self.log_len > 2 * (HISTORY_VIEWS + HISTORY_STATES) {
+            self.log = compact(&self.path, &self.history)?;
+            self.log_len = self.history.len();
+        }
+        Ok(())
+    }
+}
+
+/// Atomically replace the log at `path` with the entries in `history`, and open it for appending.
+fn compact(path: &Path, history: &SigningHistory) -> anyhow::Result<File> {
+    let tmp = path.with_extension("tmp");
+    let mut file =
+        BufWriter::new(File::create(&tmp).context(format!("creating {}", tmp.display()))?);
+    for entry in history.entries() {
+        serde_json::to_writer(&mut file, &entry)?;
+        file.write_all(b"\n")?;
+    }
+    file.into_inner()
+        .map_err(|err| err.into_error())?
+        .sync_all()
+        .context(format!("writing {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).context(format!("replacing {}", path.display()))?;
+    OpenOptions::new()
+        .append(true)
+        .open(path)
+        .context(format!("opening {}", path.display()))
+}
+
+/// A signer serving requests from nodes over the remote signing protocol.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct SignerServer {
+    #[derivative(Debug = "ignore")]
+    keys: LocalSigner<SeqTypes>,
+    identity: SignerIdentity,
+    #[derivative(Debug = "ignore")]
+    secret: Zeroizing<Vec<u8>>,
+    protection: Mutex<SlashingProtection>,
+}
+
+impl SignerServer {
+    pub fn new(
+        private_key: BLSPrivKey,
+        state_private_key: StateSignKey,
+        secret: Zeroizing<Vec<u8>>,
+        protection: SlashingProtection,
+    ) -> Self {
+        let identity = SignerIdentity {
+            public_key: PubKey::from_private(&private_key),
+            state_public_key: StateKeyPair::from_sign_key(state_private_key.clone()).ver_key(),
+            libp2p_secret: derive_libp2p_secret::<PubKey>(&private_key),
+        };
+        Self {
+            identity,
+            keys: LocalSigner::new(private_key, state_private_key),
+            secret,
+            protection: Mutex::new(protection),
+        }
+    }
+
+    /// Answer a single request.
+    pub async fn handle(&self, req: SignRequest) -> SignResponse {
+        if req == SignRequest::Identity {
+            return SignResponse::Identity(self.identity.clone());
+        }
+
+        // Hold the lock until the signature is computed, so that concurrent conflicting requests
+        // are checked against each other.
+        let mut protection = self.protection.lock().await;
+        if let Err(err) = protection.record(&req) {
+            tracing::error!(public_key = %self.identity.public_key, ?req, "refusing to sign: {err:#}");
+            return SignResponse::Refused(format!("{err:#}"));
+        }
+        let res = match &req {
+            SignRequest::Consensus(msg) => self.keys.sign(msg).await.map(SignResponse::Consensus),
+            SignRequest::State {
+                state,
+                next_stake_table,
+            } => self
+                .keys
+                .sign_state(state, next_stake_table)
+                .await
+                .map(SignResponse::State),
+            SignRequest::Identity => unreachable!("identity requests are answered above"),
+        };
+        res.unwrap_or_else(|err| SignResponse::Refused(format!("{err:#}")))
+    }
+
+    /// Listen for connections at `address` and serve them until an error occurs.
+    pub async fn serve(self: Arc<Self>, address: &SignerAddress) -> anyhow::Result<()> {
+        tracing::info!(%address, public_key = %self.identity.public_key, "starting signer");
+        match address {
+            SignerAddress::Tcp(addr) => {
+                let listener = TcpListener::bind(addr)
+                    .await
+                    .context(format!("binding {address}"))?;
+                loop {
+                    let (conn, peer) = listener.accept().await?;
+                    tracing::info!(%peer, "accepted connection");
+                    self.clone().spawn_connection(Box::new(conn));
+                }
+            },
+            SignerAddress::Unix(path) => {
+                let listener = UnixListener::bind(path).context(format!("binding {address}"))?;
+                loop {
+                    let (conn, _) = listener.accept().await?;
+                    tracing::info!("accepted connection");
+                    self.clone().spawn_connection(Box::new(conn));
+                }
+            },
+        }
+    }
+
+    fn spawn_connection(self: Arc<Self>, conn: Box<dyn Connection>) {
+        tokio::spawn(async move {
+            if let Err(err) = self.handle_connection(conn).await {
+                tracing::warn!("connection closed: {err:#}");
+            }
+        });
+    }
+
+    async fn handle_connection(&self, mut conn: Box<dyn Connection>) -> anyhow::Result<()> {
+        let mut challenge = vec![0; 32];
+        rand::thread_rng().fill_bytes(&mut challenge);
+        write_frame(&mut conn, &challenge).await?;
+        let Some((nonce, response)) = read_frame::<(Vec<u8>, Vec<u8>)>(&mut conn).await? else {
+            bail!("client closed the connection during authentication");
+        };
+        if auth_mac(&self.secret, &challenge, &nonce)
+            .verify_slice(&response)
+            .is_err()
+        {
+            write_frame(&mut conn, &Err::<(), _>("invalid secret".to_string())).await?;
+            bail!("client failed to authenticate");
+        }
+        write_frame(&mut conn, &Ok::<_, String>(())).await?;
+
+        let mut channel = Channel::new(conn, &self.secret, &challenge, &nonce, false);
+        while let Some(req) = channel.recv().await? {
+            let res = self.handle(req).await;
+            channel.send(&res).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use committable::Commitment;
+    use hotshot::traits::implementations::derive_libp2p_peer_id;
+    use hotshot_types::{
+        data::ViewNumber,
+        light_client::StateVerKey,
+        simple_vote::{QuorumData2, TimeoutData2, ViewSyncPreCommitData2},
+        traits::signature_key::StateSignatureKey,
+    };
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::signer::RemoteSigner;
+
+    /// A quorum vote in `view` for the leaf with commitment `[leaf; 32]`.
+    fn vote(view: u64, leaf: u8) -> SignableMessage<SeqTypes> {
+        SignableMessage::Vote {
+            view: ViewNumber::new(view),
+            data: QuorumData2 {
+                leaf_commit: Commitment::from_raw([leaf; 32]),
+                epoch: None,
+                block_number: None,
+            }
+            .into(),
+        }
+    }
+
+    /// A vote to time out `view`.
+    fn timeout(view: u64) -> SignableMessage<SeqTypes> {
+        SignableMessage::Vote {
+            view: ViewNumber::new(view),
+            data: TimeoutData2 {
+                view: ViewNumber::new(view),
+                epoch: None,
+            }
+            .into(),
+        }
+    }
+
+    /// A view sync pre-commit vote in `view` for `relay`.
+    fn view_sync(view: u64, relay: u64) -> SignableMessage<SeqTypes> {
+        SignableMessage::Vote {
+            view: ViewNumber::new(view),
+            data: ViewSyncPreCommitData2 {
+                relay,
+                round: ViewNumber::new(view),
+                epoch: None,
+            }
+            .into(),
+        }
+    }
+
+    #[test]
+    fn test_signing_history() {
+        let mut history = SigningHistory::default();
+
+        history.record_message(&vote(1, 0)).unwrap();
+        // Signing the same message again is fine.
+        history.record_message(&vote(1, 0)).unwrap();
+        // Signing a different message for the same view is not.
+        history.record_message(&vote(1, 1)).unwrap_err();
+        // Messages of a different kind are independent.
+        history.record_message(&timeout(1)).unwrap();
+        history.record_message(&vote(2, 1)).unwrap();
+        // Some kinds of message may be signed any number of times in the same view.
+        history.record_message(&view_sync(1, 0)).unwrap();
+        history.record_message(&view_sync(1, 1)).unwrap();
+        history
+            .record_message(&SignableMessage::Other(b"a".to_vec()))
+            .unwrap();
+        history
+            .record_message(&SignableMessage::Other(b"b".to_vec()))
+            .unwrap();
+
+        let state = LightClientState {
+            block_height: 10,
+            ..Default::default()
+        };
+        let other_state = LightClientState {
+            view_number: 1,
+            ..state
+        };
+        let stake_table = StakeTableState::default();
+        history.record_state(&state, &stake_table).unwrap();
+        history.record_state(&state, &stake_table).unwrap();
+        history
+            .record_state(&other_state, &stake_table)
+            .unwrap_err();
+    }
+
+    #[test]
+    fn test_signing_history_too_old() {
+        let mut history = SigningHistory::default();
+        for view in 1..=HISTORY_VIEWS as u64 {
+            history.record_message(&vote(view, 0)).unwrap();
+        }
+        // The oldest views are forgotten, and can no longer be signed for.
+        history
+            .record_message(&vote(HISTORY_VIEWS as u64 + 1, 0))
+            .unwrap();
+        history.record_message(&vote(1, 0)).unwrap_err();
+        // Views still in the history can be checked.
+        history.record_message(&vote(2, 0)).unwrap();
+        history.record_message(&vote(2, 1)).unwrap_err();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_relabelled_vote_refused() {
+        let dir = TempDir::new().unwrap();
+        let (_, private_key) = PubKey::generated_from_seed_indexed([0; 32], 0);
+        let (_, state_private_key) = StateVerKey::generated_from_seed_indexed([0; 32], 0);
+        let server = SignerServer::new(
+            private_key,
+            state_private_key,
+            Zeroizing::new(b"secret".to_vec()),
+            SlashingProtection::open(dir.path().join("history.log")).unwrap(),
+        );
+
+        let res = server.handle(SignRequest::Consensus(vote(1, 0))).await;
+        assert!(matches!(res, SignResponse::Consensus(_)), "{res:?}");
+        let res = server.handle(SignRequest::Consensus(vote(1, 1))).await;
+        assert!(matches!(res, SignResponse::Refused(_)), "{res:?}");
+
+        // The conflicting vote cannot be signed by passing off its commitment as an arbitrary
+        // message either.
+        let conflicting = vote(1, 1).signed_bytes().unwrap();
+        let res = server
+            .handle(SignRequest::Consensus(SignableMessage::Other(conflicting)))
+            .await;
+        assert!(matches!(res, SignResponse::Refused(_)), "{res:?}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_signer() {
+        let dir = TempDir::new().unwrap();
+        let socket = SignerAddress::Unix(dir.path().join("signer.sock"));
+        let secret = Zeroizing::new(b"secret".to_vec());
+
+        let (public_key, private_key) = PubKey::generated_from_seed_indexed([0; 32], 0);
+        let (state_public_key, state_private_key) =
+            StateVerKey::generated_from_seed_indexed([0; 32], 0);
+        let server = Arc::new(SignerServer::new(
+            private_key.clone(),
+            state_private_key,
+            secret.clone(),
+            SlashingProtection::open(dir.path().join("history.log")).unwrap(),
+        ));
+        tokio::spawn({
+            let socket = socket.clone();
+            async move { server.serve(&socket).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        // A client with the wrong secret cannot connect.
+        RemoteSigner::connect(socket.clone(), Zeroizing::new(b"wrong".to_vec()))
+            .await
+            .unwrap_err();
+
+        // The node learns its keys from the signer.
+        let keys = RemoteSigner::connect(socket, secret).await.unwrap();
+        assert_eq!(keys.public_key, public_key);
+        assert_eq!(keys.state_public_key, state_public_key);
+        assert_eq!(
+            keys.libp2p_keypair.public().to_peer_id(),
+            derive_libp2p_peer_id::<PubKey>(&private_key).unwrap()
+        );
+
+        let signer = keys.signer;
+        let sig = signer.sign(&vote(1, 0)).await.unwrap();
+        assert!(public_key.validate(&sig, &vote(1, 0).signed_bytes().unwrap()));
+        signer.sign(&vote(1, 0)).await.unwrap();
+        signer.sign(&vote(1, 1)).await.unwrap_err();
+
+        let state = LightClientState::default();
+        let stake_table = StakeTableState::default();
+        signer.sign_state(&state, &stake_table).await.unwrap();
+
+        // The history survives a restart of the signer.
+        let history = SlashingProtection::open(dir.path().join("history.log")).unwrap();
+        let mut conflict = history.history.clone();
+        conflict.record_message(&vote(1, 1)).unwrap_err();
+    }
+
+    #[test]
+    fn test_slashing_protection_log() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("history.log");
+        let request = |view, leaf| SignRequest::Consensus(vote(view, leaf));
+
+        let mut protection = SlashingProtection::open(&path).unwrap();
+        protection.record(&request(1, 0)).unwrap();
+        protection.record(&request(2, 0)).unwrap();
+        // Repeated requests are not logged again.
+        protection.record(&request(2, 0)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        drop(protection);
+
+        // A crash in the middle of appending an entry loses only that entry.
+        let mut log = OpenOptions::new().append(true).open(&path).unwrap();
+        log.write_all(br#"{"Message":{"kind":"QuorumVote","#)
+            .unwrap();
+        drop(log);
+
+        let mut protection = SlashingProtection::open(&path).unwrap();
+        let mut expected = SigningHistory::default();
+        expected.record_message(&vote(1, 0)).unwrap();
+        expected.record_message(&vote(2, 0)).unwrap();
+        assert_eq!(protection.history, expected);
+        protection.record(&request(1, 1)).unwrap_err();
+        protection.record(&request(3, 0)).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 3);
+    }
+}
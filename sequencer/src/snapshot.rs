@@ -0,0 +1,174 @@
+//! Exporting and importing state snapshots.
+//!
+//! A [`StateSnapshot`] contains everything a new node needs to start consensus from a recent
+//! decided leaf with the full state, instead of replaying the chain from genesis or catching up
+//! the state account by account from its peers. Snapshots are exported from the merklized state
+//! stored by a node running the SQL query service, and imported into the consensus storage of the
+//! new node before it starts.
+
+use anyhow::{bail, ensure, Context};
+use committable::Commitment;
+use espresso_types::{
+    v0::traits::SequencerPersistence,
+    v0_1::{RewardAccount, RewardAmount, RewardMerkleTree, REWARD_MERKLE_TREE_HEIGHT},
+    BlockMerkleTree, FeeAccount, FeeAmount, FeeMerkleTree, Leaf2, StateSnapshot,
+    BLOCK_MERKLE_TREE_HEIGHT, FEE_MERKLE_TREE_HEIGHT,
+};
+use hotshot_query_service::{
+    availability::LeafId,
+    data_source::{
+        storage::{
+            sql::{query_as, Transaction, TransactionMode},
+            AvailabilityStorage, MerklizedStateHeightStorage, SqlStorage,
+        },
+        VersionedDataSource,
+    },
+};
+use hotshot_types::vote::HasViewNumber;
+use jf_merkle_tree::{prelude::MerkleNode, ForgetableMerkleTreeScheme, UniversalMerkleTreeScheme};
+use serde::de::DeserializeOwned;
+
+use crate::{api::sql::load_frontier, SeqTypes};
+
+/// Export a snapshot of the state at `height` from the query service database.
+///
+/// If `height` is not given, the latest state in the database is exported. Otherwise, the database
+/// must have the merklized state for `height`, which is only the case for nodes which keep the full
+/// state history, or if `height` is recent enough not to have been pruned.
+pub async fn export_snapshot(
+    storage: &SqlStorage,
+    height: Option<u64>,
+) -> anyhow::Result<StateSnapshot> {
+    let mut tx = storage.read().await.context("opening transaction")?;
+    let last_height = tx.get_last_state_height().await? as u64;
+    let height = height.unwrap_or(last_height);
+    ensure!(
+        height <= last_height,
+        "state at height {height} is not available, latest state is at height {last_height}"
+    );
+
+    let leaf = tx
+        .get_leaf(LeafId::<SeqTypes>::from(height as usize))
+        .await
+        .context(format!("leaf {height} not available"))?;
+    let header = leaf.header();
+
+    tracing::info!(height, "loading fee state");
+    let fee_merkle_tree = FeeMerkleTree::from_kv_set(
+        FEE_MERKLE_TREE_HEIGHT,
+        load_merkle_entries::<_, FeeAccount, FeeAmount>(&mut tx, "fee_merkle_tree", height).await?,
+    )?;
+
+    tracing::info!(height, "loading reward state");
+    let reward_merkle_tree = RewardMerkleTree::from_kv_set(
+        REWARD_MERKLE_TREE_HEIGHT,
+        load_merkle_entries::<_, RewardAccount, RewardAmount>(
+            &mut tx,
+            "reward_merkle_tree",
+            height,
+        )
+        .await?,
+    )?;
+
+    let block_merkle_tree = if height == 0 {
+        BlockMerkleTree::new(BLOCK_MERKLE_TREE_HEIGHT)
+    } else {
+        let mut tree = BlockMerkleTree::from_commitment(header.block_merkle_tree_root());
+        let frontier = load_frontier(&mut tx, height).await?;
+        match frontier.proof.first().context("empty frontier proof")? {
+            MerkleNode::Leaf { pos, elem, .. } => tree
+                .remember(*pos, *elem, &frontier)
+                .context("failed to remember frontier")?,
+            _ => bail!("invalid frontier proof"),
+        }
+        tree
+    };
+    drop(tx);
+
+    let snapshot = StateSnapshot {
+        leaf: leaf.leaf().clone(),
+        qc: leaf.qc().clone(),
+        fee_merkle_tree,
+        reward_merkle_tree,
+        block_merkle_tree,
+    };
+    snapshot
+        .verify()
+        .context("exported snapshot is inconsistent")?;
+    Ok(snapshot)
+}
+
+/// Verify a snapshot and import it into consensus storage.
+///
+/// The snapshot is only imported if its leaf has the commitment `trusted_leaf`, which must be
+/// obtained from a trusted node. [`StateSnapshot::verify`] does not check the signatures on the
+/// snapshot's QC, and could not do so meaningfully anyway, since a new node has no stake table to
+/// check them against yet. The trusted commitment is what authenticates the snapshot; everything
+/// else in it is checked against the leaf. The snapshot carries no stake tables, so the node
+/// rebuilds those from the L1 once it starts.
+///
+/// This fails if the storage already has an anchor leaf at or after the snapshot leaf: a snapshot
+/// is meant for bootstrapping a new node, not for rolling back an existing one.
+pub async fn import_snapshot(
+    persistence: &impl SequencerPersistence,
+    snapshot: &StateSnapshot,
+    trusted_leaf: Commitment<Leaf2>,
+) -> anyhow::Result<()> {
+    ensure!(
+        snapshot.leaf_commitment() == trusted_leaf,
+        "snapshot leaf {} does not match trusted leaf {trusted_leaf}",
+        snapshot.leaf_commitment()
+    );
+    snapshot.verify().context("invalid snapshot")?;
+
+    if let Some((anchor, _)) = persistence.load_anchor_leaf().await? {
+        ensure!(
+            anchor.view_number() < snapshot.leaf.view_number(),
+            "storage already has anchor leaf from view {:?}, which is not older than snapshot leaf \
+             from view {:?}",
+            anchor.view_number(),
+            snapshot.leaf.view_number()
+        );
+    }
+
+    tracing::info!(
+        height = snapshot.height(),
+        leaf = %snapshot.leaf_commitment(),
+        "importing snapshot"
+    );
+    persistence.import_snapshot(snapshot).await
+}
+
+/// Load all the entries of a merklized state as of `height`.
+async fn load_merkle_entries<Mode, K, V>(
+    tx: &mut Transaction<Mode>,
+    table: &str,
+    height: u64,
+) -> anyhow::Result<Vec<(K, V)>>
+where
+    Mode: TransactionMode,
+    K: DeserializeOwned,
+    V: DeserializeOwned,
+{
+    // For each leaf node, take its latest version as of `height`. Entries which were deleted have
+    // a latest version with no entry.
+    let sql = format!(
+        "SELECT idx, entry FROM {table} AS t
+          WHERE t.entry IS NOT NULL
+            AND t.created = (
+              SELECT max(created) FROM {table} WHERE path = t.path AND created <= $1
+            )"
+    );
+    query_as::<(serde_json::Value, serde_json::Value)>(&sql)
+        .bind(height as i64)
+        .fetch_all(tx.as_mut())
+        .await?
+        .into_iter()
+        .map(|(key, entry)| {
+            Ok((
+                serde_json::from_value(key).context("malformed merkle index")?,
+                serde_json::from_value(entry).context("malformed merkle entry")?,
+            ))
+        })
+        .collect()
+}
@@ -6,19 +6,20 @@ use std::{
 };
 
 use async_lock::RwLock;
+use derivative::Derivative;
 use espresso_types::{traits::SequencerPersistence, PubKey};
-use hotshot::types::{Event, EventType, SchnorrPubKey};
+use hotshot::types::{Event, EventType};
 use hotshot_types::{
     event::LeafInfo,
     light_client::{
-        compute_stake_table_commitment, LightClientState, StakeTableState, StateSignKey,
-        StateSignature, StateSignatureRequestBody, StateVerKey,
+        compute_stake_table_commitment, LightClientState, StakeTableState, StateSignature,
+        StateSignatureRequestBody, StateVerKey,
     },
     traits::{
         block_contents::BlockHeader,
         network::ConnectedNetwork,
         node_implementation::{NodeType, Versions},
-        signature_key::StateSignatureKey,
+        signer::ConsensusSigner,
     },
     utils::{is_ge_epoch_root, option_epoch_from_block_number},
 };
@@ -34,10 +35,12 @@ pub mod relay_server;
 /// Capacity for the in memory signature storage.
 const SIGNATURE_STORAGE_CAPACITY: usize = 100;
 
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct StateSigner<ApiVer: StaticVersionType> {
-    /// Key for signing a new light client state
-    sign_key: StateSignKey,
+    /// Signer for new light client states
+    #[derivative(Debug = "ignore")]
+    signer: Arc<dyn ConsensusSigner<SeqTypes>>,
 
     /// Key for verifying a light client state
     ver_key: StateVerKey,
@@ -60,14 +63,14 @@ pub struct StateSigner<ApiVer: StaticVersionType> {
 
 impl<ApiVer: StaticVersionType> StateSigner<ApiVer> {
     pub fn new(
-        sign_key: StateSignKey,
+        signer: Arc<dyn ConsensusSigner<SeqTypes>>,
         ver_key: StateVerKey,
         voting_stake_table: StakeTableState,
         voting_stake_table_epoch: Option<<SeqTypes as NodeType>::Epoch>,
         stake_table_capacity: usize,
     ) -> Self {
         Self {
-            sign_key,
+            signer,
             ver_key,
             voting_stake_table,
             voting_stake_table_epoch,
@@ -147,7 +150,13 @@ impl<ApiVer: StaticVersionType> StateSigner<ApiVer> {
                     }
                 }
 
-                let signature = self.sign_new_state(&state, self.voting_stake_table).await;
+                let signature = match self.sign_new_state(&state, self.voting_stake_table).await {
+                    Ok(signature) => signature,
+                    Err(err) => {
+                        tracing::error!("Failed to sign light client state: {err:#}");
+                        return;
+                    },
+                };
 
                 if let Some(client) = &self.relay_server_client {
                     let request_body = StateSignatureRequestBody {
@@ -184,13 +193,8 @@ impl<ApiVer: StaticVersionType> StateSigner<ApiVer> {
         &self,
         state: &LightClientState,
         next_stake_table: StakeTableState,
-    ) -> StateSignature {
-        let signature = <SchnorrPubKey as StateSignatureKey>::sign_state(
-            &self.sign_key,
-            state,
-            &next_stake_table,
-        )
-        .unwrap();
+    ) -> anyhow::Result<StateSignature> {
+        let signature = self.signer.sign_state(state, &next_stake_table).await?;
         let mut pool_guard = self.signatures.write().await;
         pool_guard.push(
             state.block_height,
@@ -205,7 +209,7 @@ impl<ApiVer: StaticVersionType> StateSigner<ApiVer> {
             "New signature added for block height {}",
             state.block_height
         );
-        signature
+        Ok(signature)
     }
 }
 
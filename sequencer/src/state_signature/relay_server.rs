@@ -1,5 +1,6 @@
 use std::{
-    collections::{hash_map::Entry, BTreeSet, HashMap},
+    borrow::Cow,
+    collections::{hash_map::Entry, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     path::PathBuf,
     time::Duration,
 };
@@ -12,12 +13,17 @@ use espresso_contract_deployer::network_config::{
 };
 use espresso_types::{config::PublicNetworkConfig, SeqTypes};
 use futures::FutureExt;
+use hotshot_query_service::metrics::PrometheusMetrics;
 use hotshot_types::{
     light_client::{one_honest_threshold, StateSignaturesBundle, StateVerKey},
-    traits::signature_key::{StakeTableEntryType, StateSignatureKey},
+    traits::{
+        metrics::{Counter, Metrics as _},
+        signature_key::{StakeTableEntryType, StateSignatureKey},
+    },
     utils::{epoch_from_block_number, is_gt_epoch_root},
     PeerConfig,
 };
+use serde::{Deserialize, Serialize};
 use tide_disco::{
     api::ApiError,
     error::ServerError,
@@ -30,6 +36,83 @@ use vbs::version::{StaticVersion, StaticVersionType};
 
 use super::{LightClientState, StateSignatureRequestBody};
 
+mod storage;
+
+pub use storage::{AddedSignature, RelayStorage};
+
+/// Number of certified bundles kept in memory for the history endpoint, if there is no persistent
+/// storage.
+const CERTIFIED_HISTORY_CAPACITY: usize = 1000;
+
+/// Number of equivocations kept in memory for the evidence endpoint, if there is no persistent
+/// storage.
+const EQUIVOCATION_HISTORY_CAPACITY: usize = 1000;
+
+/// Evidence that a state signer signed two conflicting light client states for the same height.
+///
+/// Both signed messages are included, so anyone can check the evidence by verifying the two
+/// signatures against `key`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EquivocationEvidence {
+    /// The key of the offending signer.
+    pub key: StateVerKey,
+    /// The block height both messages sign a light client state for.
+    pub height: u64,
+    /// The epoch of `height`.
+    pub epoch: u64,
+    /// The first message received from the signer for this height.
+    pub first: StateSignatureRequestBody,
+    /// The conflicting message.
+    pub second: StateSignatureRequestBody,
+}
+
+impl EquivocationEvidence {
+    /// Evidence of an equivocation, if `first` and `second` are conflicting messages from the same
+    /// signer.
+    fn detect(
+        epoch: u64,
+        first: StateSignatureRequestBody,
+        second: &StateSignatureRequestBody,
+    ) -> Option<Self> {
+        if first.key != second.key
+            || first.state.block_height != second.state.block_height
+            || (first.state == second.state && first.next_stake == second.next_stake)
+        {
+            return None;
+        }
+        Some(Self {
+            key: second.key.clone(),
+            height: second.state.block_height,
+            epoch,
+            first,
+            second: second.clone(),
+        })
+    }
+}
+
+/// Metrics reported by the relay server.
+struct RelayServerMetrics {
+    /// Number of signatures accepted.
+    signatures: Box<dyn Counter>,
+    /// Number of signatures rejected because they did not verify.
+    invalid_signatures: Box<dyn Counter>,
+    /// Number of distinct equivocations detected.
+    equivocations: Box<dyn Counter>,
+    /// Number of signatures rejected because the signer equivocated.
+    rejected_signatures: Box<dyn Counter>,
+}
+
+impl RelayServerMetrics {
+    fn new(registry: &PrometheusMetrics) -> Self {
+        Self {
+            signatures: registry.create_counter("signatures".into(), None),
+            invalid_signatures: registry.create_counter("invalid_signatures".into(), None),
+            equivocations: registry.create_counter("equivocations".into(), None),
+            rejected_signatures: registry.create_counter("rejected_signatures".into(), None),
+        }
+    }
+}
+
 /// State that checks the light client state update and the signature collection
 pub struct StateRelayServerState {
     /// Sequencer endpoint to query for stake table info
@@ -47,14 +130,38 @@ pub struct StateRelayServerState {
     /// NOTE: nested hash-map because state signer could "vote/sign" different light client state for the same height
     bundles: HashMap<u64, HashMap<LightClientState, StateSignaturesBundle>>,
 
-    /// The latest state signatures bundle whose total weight exceeds the threshold
-    latest_available_bundle: Option<StateSignaturesBundle>,
+    /// Recent state signatures bundles whose total weight exceeds the threshold, by block height
+    ///
+    /// Unused if there is persistent storage, which keeps the full history instead.
+    certified: BTreeMap<u64, StateSignaturesBundle>,
     /// The block height of the latest available state signature bundle
     latest_block_height: Option<u64>,
 
     /// A ordered queue of block heights, used for garbage collection.
     queue: BTreeSet<u64>,
 
+    /// The first message signed by each key, for each block height, used to detect equivocations
+    ///
+    /// Unused if there is persistent storage, which keeps the signatures instead.
+    signed: HashMap<u64, HashMap<StateVerKey, StateSignatureRequestBody>>,
+    /// Recent evidence of equivocations
+    ///
+    /// Unused if there is persistent storage, which keeps all evidence instead.
+    equivocations: VecDeque<EquivocationEvidence>,
+    /// Keys which have equivocated, by epoch
+    ///
+    /// Unused if there is persistent storage, which is queried instead.
+    equivocators: HashMap<u64, HashSet<StateVerKey>>,
+    /// Whether to reject signatures from keys which have equivocated in the same epoch
+    reject_equivocators: bool,
+
+    /// Prometheus registry for the relay server metrics
+    registry: PrometheusMetrics,
+    metrics: RelayServerMetrics,
+
+    /// Persistent storage, shared with other replicas of this relay server
+    storage: Option<RelayStorage>,
+
     /// shutdown signal
     shutdown: Option<oneshot::Receiver<()>>,
 }
@@ -62,6 +169,8 @@ pub struct StateRelayServerState {
 impl StateRelayServerState {
     /// Init the server state
     pub fn new(sequencer_url: Url) -> Self {
+        let registry = PrometheusMetrics::default();
+        let metrics = RelayServerMetrics::new(&registry);
         Self {
             sequencer_url,
             blocks_per_epoch: None,
@@ -69,9 +178,16 @@ impl StateRelayServerState {
             thresholds: HashMap::new(),
             known_nodes: HashMap::new(),
             bundles: HashMap::new(),
-            latest_available_bundle: None,
+            certified: BTreeMap::new(),
             latest_block_height: None,
             queue: BTreeSet::new(),
+            signed: HashMap::new(),
+            equivocations: VecDeque::new(),
+            equivocators: HashMap::new(),
+            reject_equivocators: false,
+            registry,
+            metrics,
+            storage: None,
             shutdown: None,
         }
     }
@@ -110,15 +226,19 @@ impl StateRelayServerState {
             .sum();
 
         // init local state
-        self.thresholds
-            .insert(first_epoch, one_honest_threshold(genesis_total_stake));
-
+        let threshold = one_honest_threshold(genesis_total_stake);
         let mut genesis_known_nodes = HashMap::<StateVerKey, U256>::new();
         for entry in genesis_stake_table {
             genesis_known_nodes
                 .insert(entry.state_ver_key.clone(), entry.stake_table_entry.stake());
         }
+        if let Some(storage) = &self.storage {
+            storage
+                .store_stake_table(first_epoch, threshold, &genesis_known_nodes)
+                .await?;
+        }
 
+        self.thresholds.insert(first_epoch, threshold);
         self.known_nodes.insert(first_epoch, genesis_known_nodes);
 
         tracing::info!(%first_epoch, "Stake table synced ");
@@ -150,6 +270,15 @@ impl StateRelayServerState {
             return Ok(());
         }
 
+        if let Some(storage) = &self.storage {
+            if let Some((threshold, nodes)) = storage.load_stake_table(epoch).await? {
+                self.known_nodes.insert(epoch, nodes);
+                self.thresholds.insert(epoch, threshold);
+                tracing::info!(%epoch, "Stake table loaded from storage ");
+                return Ok(());
+            }
+        }
+
         tracing::info!(%epoch,"Syncing stake table ");
 
         let peer_configs = {
@@ -195,9 +324,14 @@ impl StateRelayServerState {
             new_nodes.insert(peer.state_ver_key.clone(), weight);
             total_weights += weight;
         }
+        let threshold = one_honest_threshold(total_weights);
+        if let Some(storage) = &self.storage {
+            storage
+                .store_stake_table(epoch, threshold, &new_nodes)
+                .await?;
+        }
         self.known_nodes.insert(epoch, new_nodes);
-        self.thresholds
-            .insert(epoch, one_honest_threshold(total_weights));
+        self.thresholds.insert(epoch, threshold);
 
         tracing::info!(%epoch, "Stake table synced ");
         Ok(())
@@ -218,6 +352,7 @@ impl StateRelayServerState {
                 return;
             }
             self.bundles.remove(&height);
+            self.signed.remove(&height);
             self.queue.pop_first();
             tracing::debug!(%height, "garbage collected for ");
         }
@@ -227,6 +362,7 @@ impl StateRelayServerState {
             for epoch in oldest_epoch..newest_epoch {
                 self.thresholds.remove(&epoch);
                 self.known_nodes.remove(&epoch);
+                self.equivocators.remove(&epoch);
                 tracing::debug!(%epoch, "garbage collected for ");
             }
         }
@@ -243,6 +379,20 @@ impl StateRelayServerState {
         self
     }
 
+    /// Persist signatures, stake tables and certified bundles in `storage`.
+    ///
+    /// Several relay servers using the same storage act as replicas of each other.
+    pub fn with_storage(mut self, storage: RelayStorage) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Reject all further signatures from a key for the rest of an epoch, once it has equivocated.
+    pub fn with_reject_equivocators(mut self, reject: bool) -> Self {
+        self.reject_equivocators = reject;
+        self
+    }
+
     pub fn with_blocks_per_epoch(mut self, blocks_per_epoch: u64) -> Self {
         self.blocks_per_epoch = Some(blocks_per_epoch);
         self
@@ -262,6 +412,62 @@ impl StateRelayServerState {
         self.known_nodes = known_nodes;
         self
     }
+
+    /// Check whether `key` has equivocated during `epoch`.
+    async fn has_equivocated(&self, epoch: u64, key: &StateVerKey) -> anyhow::Result<bool> {
+        match &self.storage {
+            Some(storage) => storage.has_equivocated(epoch, key).await,
+            None => Ok(self
+                .equivocators
+                .get(&epoch)
+                .is_some_and(|keys| keys.contains(key))),
+        }
+    }
+
+    /// Check whether the signer of `req` has already signed a conflicting message for the same
+    /// height, and if so, record evidence of the equivocation in memory.
+    ///
+    /// Returns `true` if `req` is an equivocation.
+    fn check_equivocation(&mut self, epoch: u64, req: &StateSignatureRequestBody) -> bool {
+        let height = req.state.block_height;
+        let Some(evidence) = self
+            .signed
+            .get(&height)
+            .and_then(|signed| signed.get(&req.key))
+            .cloned()
+            .and_then(|first| EquivocationEvidence::detect(epoch, first, req))
+        else {
+            return false;
+        };
+
+        self.equivocators
+            .entry(epoch)
+            .or_default()
+            .insert(req.key.clone());
+        if !self
+            .equivocations
+            .iter()
+            .any(|e| e.height == height && e.key == req.key)
+        {
+            self.report_equivocation(&evidence);
+            self.equivocations.push_back(evidence);
+            if self.equivocations.len() > EQUIVOCATION_HISTORY_CAPACITY {
+                self.equivocations.pop_front();
+            }
+        }
+        true
+    }
+
+    /// Log and count a newly detected equivocation.
+    fn report_equivocation(&self, evidence: &EquivocationEvidence) {
+        tracing::warn!(
+            height = evidence.height,
+            epoch = evidence.epoch,
+            key = %evidence.key,
+            "Detected conflicting state signatures"
+        );
+        self.metrics.equivocations.add(1);
+    }
 }
 
 #[async_trait::async_trait]
@@ -269,7 +475,23 @@ pub trait StateRelayServerDataSource {
     /// Get the latest available signatures bundle.
     /// # Errors
     /// Errors if there's no available signatures bundle.
-    fn get_latest_signature_bundle(&self) -> Result<StateSignaturesBundle, ServerError>;
+    async fn get_latest_signature_bundle(&self) -> Result<StateSignaturesBundle, ServerError>;
+
+    /// Get the signatures bundle certified for block height `height`.
+    /// # Errors
+    /// Errors if no signatures bundle was certified for this height, or it is no longer available.
+    async fn get_signature_bundle(&self, height: u64)
+        -> Result<StateSignaturesBundle, ServerError>;
+
+    /// Get evidence of state signers signing conflicting light client states, optionally only for
+    /// equivocations in `epoch`.
+    async fn get_equivocations(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<Vec<EquivocationEvidence>, ServerError>;
+
+    /// Prometheus metrics reported by the relay server.
+    fn metrics(&self) -> &PrometheusMetrics;
 
     /// Post a signature to the relay server
     /// # Errors
@@ -279,18 +501,72 @@ pub trait StateRelayServerDataSource {
 
 #[async_trait::async_trait]
 impl StateRelayServerDataSource for StateRelayServerState {
-    fn get_latest_signature_bundle(&self) -> Result<StateSignaturesBundle, ServerError> {
-        match &self.latest_available_bundle {
-            Some(bundle) => Ok(bundle.clone()),
-            None => Err(ServerError::catch_all(
+    async fn get_latest_signature_bundle(&self) -> Result<StateSignaturesBundle, ServerError> {
+        let bundle = match &self.storage {
+            Some(storage) => storage.load_latest_bundle().await.map_err(internal_error)?,
+            None => self
+                .certified
+                .last_key_value()
+                .map(|(_, bundle)| bundle.clone()),
+        };
+        bundle.ok_or_else(|| {
+            ServerError::catch_all(
                 StatusCode::NOT_FOUND,
                 "The light client state signatures are not ready.".to_owned(),
-            )),
+            )
+        })
+    }
+
+    async fn get_signature_bundle(
+        &self,
+        height: u64,
+    ) -> Result<StateSignaturesBundle, ServerError> {
+        let bundle = match &self.storage {
+            Some(storage) => storage.load_bundle(height).await.map_err(internal_error)?,
+            None => self.certified.get(&height).cloned(),
+        };
+        bundle.ok_or_else(|| {
+            ServerError::catch_all(
+                StatusCode::NOT_FOUND,
+                format!("No light client state signatures bundle is available for block height {height}."),
+            )
+        })
+    }
+
+    async fn get_equivocations(
+        &self,
+        epoch: Option<u64>,
+    ) -> Result<Vec<EquivocationEvidence>, ServerError> {
+        match &self.storage {
+            Some(storage) => storage
+                .load_equivocations(epoch)
+                .await
+                .map_err(internal_error),
+            None => Ok(self
+                .equivocations
+                .iter()
+                .filter(|e| epoch.is_none_or(|epoch| e.epoch == epoch))
+                .cloned()
+                .collect()),
         }
     }
 
+    fn metrics(&self) -> &PrometheusMetrics {
+        &self.registry
+    }
+
     async fn post_signature(&mut self, req: StateSignatureRequestBody) -> Result<(), ServerError> {
         let block_height = req.state.block_height;
+        if self.latest_block_height.is_none() {
+            // After a restart, pick up from the latest bundle certified by us or another replica.
+            if let Some(storage) = &self.storage {
+                self.latest_block_height = storage
+                    .load_latest_bundle()
+                    .await
+                    .map_err(internal_error)?
+                    .map(|bundle| bundle.state.block_height);
+            }
+        }
         if block_height <= self.latest_block_height.unwrap_or(0) {
             // This signature is no longer needed
             return Ok(());
@@ -299,9 +575,7 @@ impl StateRelayServerDataSource for StateRelayServerState {
         let blocks_per_epoch = match self.blocks_per_epoch {
             Some(v) => v,
             None => {
-                self.init_genesis().await.map_err(|e| {
-                    ServerError::catch_all(StatusCode::INTERNAL_SERVER_ERROR, format!("{e}"))
-                })?;
+                self.init_genesis().await.map_err(internal_error)?;
                 self.blocks_per_epoch
                     .expect("internal err, init_genesis() wrong")
             },
@@ -316,9 +590,9 @@ impl StateRelayServerDataSource for StateRelayServerState {
         }
 
         if !self.known_nodes.contains_key(&epoch) {
-            self.sync_stake_table(block_height).await.map_err(|e| {
-                ServerError::catch_all(StatusCode::INTERNAL_SERVER_ERROR, format!("{e}"))
-            })?;
+            self.sync_stake_table(block_height)
+                .await
+                .map_err(internal_error)?;
         }
 
         // retrieve the signer/sender's weight from the correct stake table for that epoch
@@ -328,13 +602,13 @@ impl StateRelayServerDataSource for StateRelayServerState {
                 "Stake table not found".to_owned(),
             ));
         };
-        let Some(threshold) = self.thresholds.get(&epoch) else {
+        let Some(&threshold) = self.thresholds.get(&epoch) else {
             return Err(ServerError::catch_all(
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "Threshold not found".to_owned(),
             ));
         };
-        let Some(weight) = nodes.get(&req.key) else {
+        let Some(&weight) = nodes.get(&req.key) else {
             return Err(ServerError::catch_all(
                 StatusCode::UNAUTHORIZED,
                 "Signature posted by nodes not on the stake table".to_owned(),
@@ -347,49 +621,99 @@ impl StateRelayServerDataSource for StateRelayServerState {
             .verify_state_sig(&req.signature, &req.state, &req.next_stake)
         {
             tracing::info!("Received invalid request: {:?}", req);
+            self.metrics.invalid_signatures.add(1);
             return Err(ServerError::catch_all(
                 StatusCode::BAD_REQUEST,
                 "The posted signature is not valid.".to_owned(),
             ));
         }
 
-        let bundles_at_height = self.bundles.entry(block_height).or_default();
-        self.queue.insert(block_height);
+        if self.reject_equivocators
+            && self
+                .has_equivocated(epoch, &req.key)
+                .await
+                .map_err(internal_error)?
+        {
+            self.metrics.rejected_signatures.add(1);
+            return Err(equivocator_error());
+        }
 
-        let bundle = bundles_at_height
-            .entry(req.state)
-            .or_insert(StateSignaturesBundle {
-                state: req.state,
-                next_stake: req.next_stake,
-                signatures: Default::default(),
-                accumulated_weight: U256::from(0),
-            });
         tracing::debug!(
             "Accepting new signature for block height {} from {}.",
             block_height,
             req.key
         );
-        match bundle.signatures.entry(req.key) {
-            Entry::Occupied(_) => {
-                // A signature is already posted for this key with this state
-                return Err(ServerError::catch_all(
-                    StatusCode::BAD_REQUEST,
-                    "A signature of this light client state is already posted at this block height for this key.".to_owned(),
-                ));
+        let bundle = match &self.storage {
+            Some(storage) => {
+                let added = storage
+                    .add_signature(epoch, &req, self.reject_equivocators)
+                    .await
+                    .map_err(internal_error)?;
+                if let Some(evidence) = &added.equivocation {
+                    if added.new_equivocation {
+                        self.report_equivocation(evidence);
+                    }
+                    if self.reject_equivocators {
+                        self.metrics.rejected_signatures.add(1);
+                        return Err(equivocator_error());
+                    }
+                }
+                let Some(signatures) = added.signatures else {
+                    return Err(duplicate_signature_error());
+                };
+                storage::bundle_signatures(&req, signatures, &self.known_nodes[&epoch])
             },
-            Entry::Vacant(entry) => {
-                entry.insert(req.signature);
-                bundle.accumulated_weight += *weight;
+            None => {
+                if self.check_equivocation(epoch, &req) && self.reject_equivocators {
+                    self.metrics.rejected_signatures.add(1);
+                    return Err(equivocator_error());
+                }
+                let bundles_at_height = self.bundles.entry(block_height).or_default();
+                self.queue.insert(block_height);
+                self.signed
+                    .entry(block_height)
+                    .or_default()
+                    .entry(req.key.clone())
+                    .or_insert_with(|| req.clone());
+
+                let bundle = bundles_at_height
+                    .entry(req.state)
+                    .or_insert(StateSignaturesBundle {
+                        state: req.state,
+                        next_stake: req.next_stake,
+                        signatures: Default::default(),
+                        accumulated_weight: U256::from(0),
+                    });
+                match bundle.signatures.entry(req.key) {
+                    Entry::Occupied(_) => {
+                        // A signature is already posted for this key with this state
+                        return Err(duplicate_signature_error());
+                    },
+                    Entry::Vacant(entry) => {
+                        entry.insert(req.signature);
+                        bundle.accumulated_weight += weight;
+                    },
+                }
+                bundle.clone()
             },
-        }
+        };
+        self.metrics.signatures.add(1);
 
-        if bundle.accumulated_weight >= *threshold {
+        if bundle.accumulated_weight >= threshold {
             tracing::info!(
                 "State signature bundle at block height {} is ready to serve.",
                 block_height
             );
             self.latest_block_height = Some(block_height);
-            self.latest_available_bundle = Some(bundle.clone());
+            match &self.storage {
+                Some(storage) => storage.certify(&bundle).await.map_err(internal_error)?,
+                None => {
+                    self.certified.insert(block_height, bundle);
+                    if self.certified.len() > CERTIFIED_HISTORY_CAPACITY {
+                        self.certified.pop_first();
+                    }
+                },
+            }
 
             // garbage collect
             self.prune(block_height);
@@ -399,6 +723,24 @@ impl StateRelayServerDataSource for StateRelayServerState {
     }
 }
 
+fn internal_error(err: anyhow::Error) -> ServerError {
+    ServerError::catch_all(StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}"))
+}
+
+fn duplicate_signature_error() -> ServerError {
+    ServerError::catch_all(
+        StatusCode::BAD_REQUEST,
+        "A signature of this light client state is already posted at this block height for this key.".to_owned(),
+    )
+}
+
+fn equivocator_error() -> ServerError {
+    ServerError::catch_all(
+        StatusCode::FORBIDDEN,
+        "This key has signed conflicting light client states in this epoch.".to_owned(),
+    )
+}
+
 /// configurability options for the web server
 #[derive(Args, Default)]
 pub struct Options {
@@ -433,7 +775,28 @@ where
     };
 
     api.get("getlateststate", |_req, state| {
-        async move { state.get_latest_signature_bundle() }.boxed()
+        async move { state.get_latest_signature_bundle().await }.boxed()
+    })?
+    .get("getstate", |req, state| {
+        async move {
+            let height = req
+                .integer_param("height")
+                .map_err(ServerError::from_request_error)?;
+            state.get_signature_bundle(height).await
+        }
+        .boxed()
+    })?
+    .get("getequivocations", |req, state| {
+        async move {
+            let epoch = req
+                .opt_integer_param("epoch")
+                .map_err(ServerError::from_request_error)?;
+            state.get_equivocations(epoch).await
+        }
+        .boxed()
+    })?
+    .metrics("metrics", |_req, state| {
+        async move { Ok(Cow::Borrowed(state.metrics())) }.boxed()
     })?
     .post("poststatesignature", move |req, state| {
         async move {
@@ -491,3 +854,317 @@ pub async fn run_relay_server_with_state<ApiVer: StaticVersionType + 'static>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use hotshot_query_service::data_source::storage::sql::testing::TmpDb;
+    use hotshot_types::light_client::StakeTableState;
+    use sequencer_utils::test_utils::setup_test;
+
+    use super::*;
+    use crate::api::sql::impl_testable_data_source::tmp_options;
+
+    const BLOCKS_PER_EPOCH: u64 = 10_000;
+
+    /// A stake table of three equally weighted keys, any two of which reach the threshold.
+    fn stake_table() -> HashMap<StateVerKey, U256> {
+        (0..3)
+            .map(|i| {
+                let (key, _) = StateVerKey::generated_from_seed_indexed([0; 32], i);
+                (key, U256::from(1))
+            })
+            .collect()
+    }
+
+    fn in_memory_relay_server() -> StateRelayServerState {
+        let epoch = epoch_from_block_number(1, BLOCKS_PER_EPOCH);
+        StateRelayServerState::new("http://localhost".parse().unwrap())
+            .with_blocks_per_epoch(BLOCKS_PER_EPOCH)
+            .with_epoch_start_block(0)
+            .with_thresholds([(epoch, U256::from(2))].into())
+            .with_known_nodes([(epoch, stake_table())].into())
+    }
+
+    async fn relay_server(db: &TmpDb) -> StateRelayServerState {
+        let storage = RelayStorage::connect(&tmp_options(db)).await.unwrap();
+        in_memory_relay_server().with_storage(storage)
+    }
+
+    fn light_client_state(view: u64, height: u64) -> LightClientState {
+        LightClientState {
+            view_number: view,
+            block_height: height,
+            ..Default::default()
+        }
+    }
+
+    fn signature(signer: u64, height: u64) -> StateSignatureRequestBody {
+        sign(
+            signer,
+            light_client_state(height, height),
+            StakeTableState::default(),
+        )
+    }
+
+    /// A signature on a different light client state for the same height as [`signature`].
+    fn conflicting_signature(signer: u64, height: u64) -> StateSignatureRequestBody {
+        sign(
+            signer,
+            light_client_state(height + 1, height),
+            StakeTableState::default(),
+        )
+    }
+
+    fn sign(
+        signer: u64,
+        state: LightClientState,
+        next_stake: StakeTableState,
+    ) -> StateSignatureRequestBody {
+        let (key, priv_key) = StateVerKey::generated_from_seed_indexed([0; 32], signer);
+        let signature = StateVerKey::sign_state(&priv_key, &state, &next_stake).unwrap();
+        StateSignatureRequestBody {
+            key,
+            state,
+            next_stake,
+            signature,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_relay_server_restart() {
+        setup_test();
+        let db = TmpDb::init().await;
+
+        // Post one signature, not enough to certify a bundle, and restart.
+        let mut relay = relay_server(&db).await;
+        relay.post_signature(signature(0, 1)).await.unwrap();
+        relay.get_latest_signature_bundle().await.unwrap_err();
+        drop(relay);
+
+        // The signature from before the restart counts towards the bundle.
+        let mut relay = relay_server(&db).await;
+        relay.post_signature(signature(1, 1)).await.unwrap();
+        let bundle = relay.get_latest_signature_bundle().await.unwrap();
+        assert_eq!(bundle.state.block_height, 1);
+        assert_eq!(bundle.signatures.len(), 2);
+        assert_eq!(bundle.accumulated_weight, U256::from(2));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_relay_server_replicas() {
+        setup_test();
+        let db = TmpDb::init().await;
+        let mut relay1 = relay_server(&db).await;
+        let mut relay2 = relay_server(&db).await;
+
+        // Signatures posted to different replicas certify a bundle together.
+        relay1.post_signature(signature(0, 1)).await.unwrap();
+        relay2.post_signature(signature(1, 1)).await.unwrap();
+        for relay in [&relay1, &relay2] {
+            let bundle = relay.get_latest_signature_bundle().await.unwrap();
+            assert_eq!(bundle.state.block_height, 1);
+            assert_eq!(bundle.signatures.len(), 2);
+        }
+
+        // Even if the signatures which complete a bundle arrive at both replicas at the same time.
+        for height in 2..10 {
+            let (res1, res2) = tokio::join!(
+                relay1.post_signature(signature(0, height)),
+                relay2.post_signature(signature(1, height)),
+            );
+            res1.unwrap();
+            res2.unwrap();
+            let bundle = relay1.get_signature_bundle(height).await.unwrap();
+            assert_eq!(bundle.signatures.len(), 2);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_relay_server_history() {
+        setup_test();
+        let db = TmpDb::init().await;
+        let mut relay = relay_server(&db).await;
+
+        for height in 1..=3 {
+            relay.post_signature(signature(0, height)).await.unwrap();
+            relay.post_signature(signature(1, height)).await.unwrap();
+        }
+        assert_eq!(
+            relay
+                .get_latest_signature_bundle()
+                .await
+                .unwrap()
+                .state
+                .block_height,
+            3
+        );
+
+        // Past bundles are still available, also after a restart.
+        let relay = relay_server(&db).await;
+        for height in 1..=3 {
+            let bundle = relay.get_signature_bundle(height).await.unwrap();
+            assert_eq!(bundle.state.block_height, height);
+            assert_eq!(bundle.signatures.len(), 2);
+        }
+        relay.get_signature_bundle(4).await.unwrap_err();
+    }
+
+    fn counter(relay: &StateRelayServerState, name: &str) -> usize {
+        relay.metrics().get_counter(name).unwrap().get()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_equivocation() {
+        setup_test();
+        let mut relay = in_memory_relay_server();
+        let epoch = epoch_from_block_number(1, BLOCKS_PER_EPOCH);
+
+        // Signing the same message twice is not an equivocation.
+        let first = signature(0, 1);
+        relay.post_signature(first.clone()).await.unwrap();
+        relay.post_signature(first.clone()).await.unwrap_err();
+        assert!(relay.get_equivocations(None).await.unwrap().is_empty());
+
+        // Signing a different state for the same height is.
+        let second = conflicting_signature(0, 1);
+        relay.post_signature(second.clone()).await.unwrap();
+        let evidence = relay.get_equivocations(None).await.unwrap();
+        assert_eq!(evidence.len(), 1);
+        assert_eq!(evidence[0].key, first.key);
+        assert_eq!(evidence[0].height, 1);
+        assert_eq!(evidence[0].epoch, epoch);
+        assert_eq!(evidence[0].first.state, first.state);
+        assert_eq!(evidence[0].second.state, second.state);
+
+        // So is signing the same state with a different next stake table.
+        let first = signature(0, 2);
+        let next_stake = StakeTableState {
+            threshold: 1u64.into(),
+            ..Default::default()
+        };
+        let second = sign(0, first.state, next_stake);
+        relay.post_signature(first).await.unwrap();
+        relay.post_signature(second.clone()).await.unwrap();
+        let evidence = relay.get_equivocations(Some(epoch)).await.unwrap();
+        assert_eq!(evidence.len(), 2);
+        assert_eq!(evidence[1].second.next_stake, second.next_stake);
+        assert!(relay
+            .get_equivocations(Some(epoch + 1))
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Further conflicting signatures for the same height are not counted again.
+        let third = sign(0, light_client_state(3, 1), StakeTableState::default());
+        relay.post_signature(third).await.unwrap();
+        assert_eq!(relay.get_equivocations(None).await.unwrap().len(), 2);
+        assert_eq!(counter(&relay, "equivocations"), 2);
+        assert_eq!(counter(&relay, "rejected_signatures"), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reject_equivocators() {
+        setup_test();
+        let mut relay = in_memory_relay_server().with_reject_equivocators(true);
+
+        relay.post_signature(signature(0, 1)).await.unwrap();
+        let err = relay
+            .post_signature(conflicting_signature(0, 1))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+        assert_eq!(relay.get_equivocations(None).await.unwrap().len(), 1);
+
+        // The equivocator is rejected for the rest of the epoch, even for other heights.
+        let err = relay.post_signature(signature(0, 2)).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+
+        // The rejected signature did not count towards a bundle, but other signers still do.
+        relay.post_signature(signature(1, 1)).await.unwrap();
+        relay.get_latest_signature_bundle().await.unwrap_err();
+        relay.post_signature(signature(2, 1)).await.unwrap();
+        let bundle = relay.get_latest_signature_bundle().await.unwrap();
+        assert_eq!(bundle.state, signature(0, 1).state);
+        assert_eq!(bundle.signatures.len(), 2);
+        assert!(!bundle.signatures.contains_key(&signature(0, 1).key));
+
+        assert_eq!(counter(&relay, "equivocations"), 1);
+        assert_eq!(counter(&relay, "rejected_signatures"), 2);
+        assert_eq!(counter(&relay, "signatures"), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_in_memory_history_caps() {
+        setup_test();
+        let mut relay = in_memory_relay_server();
+
+        // Only the most recent equivocations are kept.
+        let num_equivocations = EQUIVOCATION_HISTORY_CAPACITY as u64 + 1;
+        for height in 1..=num_equivocations {
+            relay.post_signature(signature(0, height)).await.unwrap();
+            relay
+                .post_signature(conflicting_signature(0, height))
+                .await
+                .unwrap();
+        }
+        let evidence = relay.get_equivocations(None).await.unwrap();
+        assert_eq!(evidence.len(), EQUIVOCATION_HISTORY_CAPACITY);
+        assert_eq!(evidence[0].height, 2);
+        assert_eq!(evidence.last().unwrap().height, num_equivocations);
+        assert_eq!(counter(&relay, "equivocations"), num_equivocations as usize);
+
+        // Only the most recent certified bundles are kept.
+        let start = num_equivocations + 1;
+        let end = start + CERTIFIED_HISTORY_CAPACITY as u64;
+        for height in start..=end {
+            relay.post_signature(signature(1, height)).await.unwrap();
+            relay.post_signature(signature(2, height)).await.unwrap();
+        }
+        relay.get_signature_bundle(start).await.unwrap_err();
+        relay.get_signature_bundle(start + 1).await.unwrap();
+        assert_eq!(
+            relay
+                .get_latest_signature_bundle()
+                .await
+                .unwrap()
+                .state
+                .block_height,
+            end
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_equivocation_replicas() {
+        setup_test();
+        let db = TmpDb::init().await;
+        let mut relay1 = relay_server(&db).await.with_reject_equivocators(true);
+        let mut relay2 = relay_server(&db).await.with_reject_equivocators(true);
+        let epoch = epoch_from_block_number(1, BLOCKS_PER_EPOCH);
+
+        // Conflicting signatures posted to different replicas at the same time are caught, and
+        // only one of them is accepted.
+        let (res1, res2) = tokio::join!(
+            relay1.post_signature(signature(0, 1)),
+            relay2.post_signature(conflicting_signature(0, 1)),
+        );
+        let err = match (res1, res2) {
+            (Ok(()), Err(err)) | (Err(err), Ok(())) => err,
+            res => panic!("expected exactly one signature to be rejected: {res:?}"),
+        };
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+        let evidence = relay1.get_equivocations(Some(epoch)).await.unwrap();
+        assert_eq!(evidence.len(), 1);
+        assert_eq!(evidence[0].key, signature(0, 1).key);
+        assert_eq!(
+            counter(&relay1, "equivocations") + counter(&relay2, "equivocations"),
+            1
+        );
+
+        // Both replicas reject the equivocator for the rest of the epoch.
+        for relay in [&mut relay1, &mut relay2] {
+            let err = relay.post_signature(signature(0, 2)).await.unwrap_err();
+            assert_eq!(err.status(), StatusCode::FORBIDDEN);
+        }
+        assert_eq!(relay2.get_equivocations(None).await.unwrap().len(), 1);
+    }
+}
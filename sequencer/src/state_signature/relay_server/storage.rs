@@ -0,0 +1,354 @@
+//! SQL persistence for the state relay server.
+//!
+//! With persistent storage, collected signatures and certified bundles survive a restart of the
+//! relay server, and several relay servers sharing the same database can serve as replicas of each
+//! other behind a load balancer. Each signature is stored individually, so a signature posted to
+//! any replica counts towards the bundle of every replica, and a bundle is certified by whichever
+//! replica receives the signature that takes it over the threshold.
+//!
+//! Signatures for the same height are added one at a time across all replicas. Otherwise, two
+//! replicas adding the last two signatures needed for a bundle at the same time could each miss the
+//! other's signature, and neither would certify the bundle.
+
+use std::collections::HashMap;
+
+use alloy::primitives::U256;
+use anyhow::Context;
+use hotshot_query_service::data_source::{
+    storage::{
+        sql::{query_as, Config},
+        SqlStorage,
+    },
+    Transaction as _, VersionedDataSource,
+};
+use hotshot_types::light_client::{StateSignatureRequestBody, StateSignaturesBundle, StateVerKey};
+use sqlx::query;
+#[cfg(not(feature = "embedded-db"))]
+use sqlx::{pool::PoolConnection, Postgres};
+
+use super::EquivocationEvidence;
+use crate::persistence;
+
+/// The outcome of [`RelayStorage::add_signature`].
+#[derive(Clone, Debug)]
+pub struct AddedSignature {
+    /// Evidence of an equivocation, if the same key already signed a conflicting message for the
+    /// same height.
+    pub equivocation: Option<EquivocationEvidence>,
+    /// Whether the evidence in `equivocation` was not already stored.
+    pub new_equivocation: bool,
+    /// All the signatures stored for the same height and state, including the new one.
+    ///
+    /// This is `None` if the signature was not added, because the same key has already signed
+    /// this state, or because it is an equivocation and equivocators are rejected.
+    pub signatures: Option<Vec<StateSignatureRequestBody>>,
+}
+
+/// Persistent storage shared by state relay server replicas.
+#[derive(Clone, Debug)]
+pub struct RelayStorage {
+    db: SqlStorage,
+}
+
+impl RelayStorage {
+    /// Connect to the database, creating the relay server tables if necessary.
+    pub async fn connect(opt: &persistence::sql::Options) -> anyhow::Result<Self> {
+        Ok(Self {
+            db: SqlStorage::connect(Config::try_from(opt)?).await?,
+        })
+    }
+
+    /// Load the threshold and stake table for `epoch`.
+    pub async fn load_stake_table(
+        &self,
+        epoch: u64,
+    ) -> anyhow::Result<Option<(U256, HashMap<StateVerKey, U256>)>> {
+        let mut tx = self.db.read().await?;
+        let Some((threshold, stake_table)) = query_as::<(String, Vec<u8>)>(
+            "SELECT threshold, stake_table FROM state_relay_stake_table WHERE epoch = $1",
+        )
+        .bind(epoch as i64)
+        .fetch_optional(tx.as_mut())
+        .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some((
+            threshold.parse().context("malformed threshold")?,
+            bincode::deserialize(&stake_table).context("malformed stake table")?,
+        )))
+    }
+
+    /// Store the threshold and stake table for `epoch`.
+    pub async fn store_stake_table(
+        &self,
+        epoch: u64,
+        threshold: U256,
+        stake_table: &HashMap<StateVerKey, U256>,
+    ) -> anyhow::Result<()> {
+        let stake_table = bincode::serialize(stake_table).context("serializing stake table")?;
+        let mut tx = self.db.write().await?;
+        tx.upsert(
+            "state_relay_stake_table",
+            ["epoch", "threshold", "stake_table"],
+            ["epoch"],
+            [(epoch as i64, threshold.to_string(), stake_table)],
+        )
+        .await?;
+        tx.commit().await
+    }
+
+    /// Add a signature for a light client state in `epoch` to storage.
+    ///
+    /// If the same key has already signed a conflicting message for the same height, evidence of
+    /// the equivocation is stored, and if `reject_equivocators` is set the signature is not added.
+    /// Checking for a conflicting signature and adding the new one happen atomically, so that
+    /// conflicting signatures posted to different replicas at the same time are always caught.
+    pub async fn add_signature(
+        &self,
+        epoch: u64,
+        req: &StateSignatureRequestBody,
+        reject_equivocators: bool,
+    ) -> anyhow::Result<AddedSignature> {
+        // With SQLite, write transactions are already serialized by the database lock, which is
+        // taken before a transaction reads anything.
+        #[cfg(not(feature = "embedded-db"))]
+        let lock = HeightLock::acquire(&self.db, req.state.block_height).await?;
+        let res = self.insert_signature(epoch, req, reject_equivocators).await;
+        #[cfg(not(feature = "embedded-db"))]
+        lock.release().await?;
+        res
+    }
+
+    async fn insert_signature(
+        &self,
+        epoch: u64,
+        req: &StateSignatureRequestBody,
+        reject_equivocators: bool,
+    ) -> anyhow::Result<AddedSignature> {
+        let height = req.state.block_height as i64;
+        let state = bincode::serialize(&req.state)?;
+        let key = bincode::serialize(&req.key)?;
+        let data = bincode::serialize(req).context("serializing signature")?;
+
+        let mut tx = self.db.write().await?;
+        let first = query_as::<(Vec<u8>,)>(
+            "SELECT data FROM state_relay_signature WHERE height = $1 AND key = $2 LIMIT 1",
+        )
+        .bind(height)
+        .bind(&key)
+        .fetch_optional(tx.as_mut())
+        .await?
+        .map(|(data,)| bincode::deserialize(&data).context("malformed signature"))
+        .transpose()?;
+        let mut added = AddedSignature {
+            equivocation: first.and_then(|first| EquivocationEvidence::detect(epoch, first, req)),
+            new_equivocation: false,
+            signatures: None,
+        };
+
+        if let Some(evidence) = &added.equivocation {
+            let res = query(
+                "INSERT INTO state_relay_equivocation (height, key, epoch, data)
+                 VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+            )
+            .bind(height)
+            .bind(&key)
+            .bind(epoch as i64)
+            .bind(bincode::serialize(evidence).context("serializing equivocation evidence")?)
+            .execute(tx.as_mut())
+            .await?;
+            added.new_equivocation = res.rows_affected() > 0;
+            if reject_equivocators {
+                tx.commit().await?;
+                return Ok(added);
+            }
+        }
+
+        let res = query(
+            "INSERT INTO state_relay_signature (height, state, key, data) VALUES ($1, $2, $3, $4)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(height)
+        .bind(&state)
+        .bind(key)
+        .bind(data)
+        .execute(tx.as_mut())
+        .await?;
+        if res.rows_affected() > 0 {
+            added.signatures = Some(
+                query_as::<(Vec<u8>,)>(
+                    "SELECT data FROM state_relay_signature WHERE height = $1 AND state = $2",
+                )
+                .bind(height)
+                .bind(&state)
+                .fetch_all(tx.as_mut())
+                .await?
+                .into_iter()
+                .map(|(data,)| bincode::deserialize(&data).context("malformed signature"))
+                .collect::<anyhow::Result<_>>()?,
+            );
+        }
+        tx.commit().await?;
+        Ok(added)
+    }
+
+    /// Check whether `key` has equivocated during `epoch`.
+    pub async fn has_equivocated(&self, epoch: u64, key: &StateVerKey) -> anyhow::Result<bool> {
+        let key = bincode::serialize(key)?;
+        let mut tx = self.db.read().await?;
+        Ok(
+            query("SELECT 1 FROM state_relay_equivocation WHERE epoch = $1 AND key = $2 LIMIT 1")
+                .bind(epoch as i64)
+                .bind(key)
+                .fetch_optional(tx.as_mut())
+                .await?
+                .is_some(),
+        )
+    }
+
+    /// Load all stored evidence of equivocations, optionally restricted to `epoch`.
+    pub async fn load_equivocations(
+        &self,
+        epoch: Option<u64>,
+    ) -> anyhow::Result<Vec<EquivocationEvidence>> {
+        let mut tx = self.db.read().await?;
+        let rows = match epoch {
+            Some(epoch) => {
+                query_as::<(Vec<u8>,)>(
+                    "SELECT data FROM state_relay_equivocation WHERE epoch = $1 ORDER BY height",
+                )
+                .bind(epoch as i64)
+                .fetch_all(tx.as_mut())
+                .await?
+            },
+            None => {
+                query_as::<(Vec<u8>,)>("SELECT data FROM state_relay_equivocation ORDER BY height")
+                    .fetch_all(tx.as_mut())
+                    .await?
+            },
+        };
+        rows.into_iter()
+            .map(|(data,)| bincode::deserialize(&data).context("malformed equivocation evidence"))
+            .collect()
+    }
+
+    /// Store a certified bundle, and delete the signatures it makes obsolete.
+    pub async fn certify(&self, bundle: &StateSignaturesBundle) -> anyhow::Result<()> {
+        let height = bundle.state.block_height as i64;
+        let data = bincode::serialize(bundle).context("serializing bundle")?;
+
+        let mut tx = self.db.write().await?;
+        tx.upsert(
+            "state_relay_bundle",
+            ["height", "data"],
+            ["height"],
+            [(height, data)],
+        )
+        .await?;
+        query("DELETE FROM state_relay_signature WHERE height <= $1")
+            .bind(height)
+            .execute(tx.as_mut())
+            .await?;
+        tx.commit().await
+    }
+
+    /// Load the certified bundle with the greatest height.
+    pub async fn load_latest_bundle(&self) -> anyhow::Result<Option<StateSignaturesBundle>> {
+        let mut tx = self.db.read().await?;
+        query_as::<(Vec<u8>,)>("SELECT data FROM state_relay_bundle ORDER BY height DESC LIMIT 1")
+            .fetch_optional(tx.as_mut())
+            .await?
+            .map(|(data,)| bincode::deserialize(&data).context("malformed bundle"))
+            .transpose()
+    }
+
+    /// Load the certified bundle for `height`.
+    pub async fn load_bundle(&self, height: u64) -> anyhow::Result<Option<StateSignaturesBundle>> {
+        let mut tx = self.db.read().await?;
+        query_as::<(Vec<u8>,)>("SELECT data FROM state_relay_bundle WHERE height = $1")
+            .bind(height as i64)
+            .fetch_optional(tx.as_mut())
+            .await?
+            .map(|(data,)| bincode::deserialize(&data).context("malformed bundle"))
+            .transpose()
+    }
+}
+
+/// A Postgres advisory lock on a block height, shared by all replicas using the same database.
+///
+/// This is a session lock held on a connection of its own, rather than a transaction lock, so that
+/// it is taken before the transaction it protects begins. Write transactions are serializable, and
+/// take their snapshot before any statement in them can block on a lock, so a transaction could not
+/// see data committed by the previous holder of a lock it took itself.
+#[cfg(not(feature = "embedded-db"))]
+struct HeightLock {
+    conn: Option<PoolConnection<Postgres>>,
+    height: i64,
+}
+
+#[cfg(not(feature = "embedded-db"))]
+impl HeightLock {
+    async fn acquire(db: &SqlStorage, height: u64) -> anyhow::Result<Self> {
+        let height = height as i64;
+        let mut conn = db.pool().acquire().await?;
+        query("SELECT pg_advisory_lock($1)")
+            .bind(height)
+            .execute(&mut *conn)
+            .await?;
+        Ok(Self {
+            conn: Some(conn),
+            height,
+        })
+    }
+
+    async fn release(mut self) -> anyhow::Result<()> {
+        let Some(mut conn) = self.conn.take() else {
+            return Ok(());
+        };
+        if let Err(err) = query("SELECT pg_advisory_unlock($1)")
+            .bind(self.height)
+            .execute(&mut *conn)
+            .await
+        {
+            // Close the connection, which releases the lock, rather than return it to the pool.
+            drop(conn.detach());
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "embedded-db"))]
+impl Drop for HeightLock {
+    fn drop(&mut self) {
+        // If the lock was never released, for example because the request holding it was
+        // cancelled, close the connection, which releases the lock, rather than return it to the
+        // pool still locked.
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+/// Assemble signatures on the light client state in `req` into a bundle, weighed according to
+/// `stake_table`.
+pub(super) fn bundle_signatures(
+    req: &StateSignatureRequestBody,
+    signatures: impl IntoIterator<Item = StateSignatureRequestBody>,
+    stake_table: &HashMap<StateVerKey, U256>,
+) -> StateSignaturesBundle {
+    let mut bundle = StateSignaturesBundle {
+        state: req.state,
+        next_stake: req.next_stake,
+        signatures: Default::default(),
+        accumulated_weight: U256::ZERO,
+    };
+    for sig in signatures {
+        if let Some(weight) = stake_table.get(&sig.key) {
+            bundle.accumulated_weight += *weight;
+            bundle.signatures.insert(sig.key, sig.signature);
+        }
+    }
+    bundle
+}
@@ -1,10 +1,18 @@
-use alloy::primitives::{utils::format_ether, Address};
-use anyhow::Result;
+use std::collections::BTreeSet;
+
+use alloy::{
+    eips::BlockId,
+    primitives::{utils::format_ether, Address, U256},
+    providers::Provider,
+};
+use anyhow::{Context, Result};
 use espresso_types::{
-    v0_3::{StakeTableFetcher, Validator},
+    v0_3::{StakeTableEvent, StakeTableFetcher, Validator},
     L1Client,
 };
+use hotshot_contract_adapter::sol_types::StakeTable;
 use hotshot_types::signature_key::BLSPubKey;
+use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::parse::Commission;
@@ -24,6 +32,294 @@ pub async fn stake_table_info(
         .collect())
 }
 
+/// The stake currently delegated by an account to one validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationInfo {
+    pub validator: Address,
+    pub amount: U256,
+    /// If the validator has exited, the time (in seconds since the Unix epoch) after which the
+    /// delegation can be claimed with `claim-validator-exit`.
+    pub validator_exit_unlocks_at: Option<u64>,
+}
+
+/// An undelegation which has not been claimed yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingUndelegation {
+    pub validator: Address,
+    pub amount: U256,
+    /// The time (in seconds since the Unix epoch) after which the funds can be claimed with
+    /// `claim-withdrawal`.
+    pub unlocks_at: u64,
+    /// Whether the unlock time had passed as of the queried L1 block.
+    pub claimable: bool,
+}
+
+/// A stake table contract event involving a particular address.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StakeTableHistoryEvent {
+    ValidatorRegistered {
+        account: Address,
+        commission: u16,
+    },
+    ValidatorExit {
+        validator: Address,
+    },
+    Delegated {
+        delegator: Address,
+        validator: Address,
+        amount: U256,
+    },
+    Undelegated {
+        delegator: Address,
+        validator: Address,
+        amount: U256,
+    },
+    ConsensusKeysUpdated {
+        account: Address,
+    },
+    Withdrawal {
+        account: Address,
+        amount: U256,
+    },
+}
+
+impl StakeTableHistoryEvent {
+    fn involves(&self, address: Address) -> bool {
+        match self {
+            Self::ValidatorRegistered { account, .. }
+            | Self::ConsensusKeysUpdated { account }
+            | Self::Withdrawal { account, .. } => *account == address,
+            Self::ValidatorExit { validator } => *validator == address,
+            Self::Delegated {
+                delegator,
+                validator,
+                ..
+            }
+            | Self::Undelegated {
+                delegator,
+                validator,
+                ..
+            } => *delegator == address || *validator == address,
+        }
+    }
+}
+
+impl From<StakeTableEvent> for StakeTableHistoryEvent {
+    fn from(event: StakeTableEvent) -> Self {
+        match event {
+            StakeTableEvent::Register(event) => Self::ValidatorRegistered {
+                account: event.account,
+                commission: event.commission,
+            },
+            StakeTableEvent::Deregister(event) => Self::ValidatorExit {
+                validator: event.validator,
+            },
+            StakeTableEvent::Delegate(event) => Self::Delegated {
+                delegator: event.delegator,
+                validator: event.validator,
+                amount: event.amount,
+            },
+            StakeTableEvent::Undelegate(event) => Self::Undelegated {
+                delegator: event.delegator,
+                validator: event.validator,
+                amount: event.amount,
+            },
+            StakeTableEvent::KeyUpdate(event) => Self::ConsensusKeysUpdated {
+                account: event.account,
+            },
+        }
+    }
+}
+
+/// A [`StakeTableHistoryEvent`] with its position on L1.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakeTableHistoryEntry {
+    pub l1_block: u64,
+    pub log_index: u64,
+    #[serde(flatten)]
+    pub event: StakeTableHistoryEvent,
+}
+
+/// Maximum number of L1 blocks to request logs for in a single RPC call.
+const MAX_LOG_BLOCK_RANGE: u64 = 10000;
+
+/// Export all stake table events involving `address`, up to and including `l1_block_number`.
+///
+/// An event involves an address if the address is the validator or delegator of the event, or
+/// the account which registered, updated its keys or withdrew funds.
+pub async fn stake_table_history(
+    l1_url: Url,
+    stake_table_address: Address,
+    address: Address,
+    l1_block_number: u64,
+) -> Result<Vec<StakeTableHistoryEntry>> {
+    let l1 = L1Client::new(vec![l1_url])?;
+    let events = StakeTableFetcher::fetch_events_from_contract(
+        l1.clone(),
+        stake_table_address,
+        None,
+        l1_block_number,
+    )
+    .await?
+    .sort_events()?;
+    let mut history = events
+        .into_iter()
+        .map(|((l1_block, log_index), event)| StakeTableHistoryEntry {
+            l1_block,
+            log_index,
+            event: event.into(),
+        })
+        .filter(|entry| entry.event.involves(address))
+        .collect::<Vec<_>>();
+
+    // Withdrawals do not affect the stake table, so they are not part of the events fetched for
+    // the sequencer. Fetch them separately.
+    let st = StakeTable::new(stake_table_address, &l1.provider);
+    let mut from = st
+        .initializedAtBlock()
+        .call()
+        .await
+        .context("getting stake table initialization block")?
+        ._0
+        .to::<u64>();
+    while from <= l1_block_number {
+        let to = l1_block_number.min(from + MAX_LOG_BLOCK_RANGE - 1);
+        let withdrawals = st
+            .Withdrawal_filter()
+            .topic1(address.into_word())
+            .from_block(from)
+            .to_block(to)
+            .query()
+            .await
+            .with_context(|| format!("getting withdrawals in L1 blocks {from}..={to}"))?;
+        for (event, log) in withdrawals {
+            history.push(StakeTableHistoryEntry {
+                l1_block: log.block_number.context("block number")?,
+                log_index: log.log_index.context("log index")?,
+                event: StakeTableHistoryEvent::Withdrawal {
+                    account: event.account,
+                    amount: event.amount,
+                },
+            });
+        }
+        from = to + 1;
+    }
+
+    history.sort_by_key(|entry| (entry.l1_block, entry.log_index));
+    Ok(history)
+}
+
+/// The validators `delegator` has ever delegated to, according to the stake table events up to
+/// `l1_block_number`.
+async fn delegated_validators(
+    l1: &L1Client,
+    stake_table_address: Address,
+    delegator: Address,
+    l1_block_number: u64,
+) -> Result<BTreeSet<Address>> {
+    let events = StakeTableFetcher::fetch_events_from_contract(
+        l1.clone(),
+        stake_table_address,
+        None,
+        l1_block_number,
+    )
+    .await?
+    .sort_events()?;
+    Ok(events
+        .into_iter()
+        .filter_map(|(_, event)| match event {
+            StakeTableEvent::Delegate(event) if event.delegator == delegator => {
+                Some(event.validator)
+            },
+            _ => None,
+        })
+        .collect())
+}
+
+/// List the current delegations of `delegator`, as of `l1_block_number`.
+pub async fn delegations(
+    l1_url: Url,
+    stake_table_address: Address,
+    delegator: Address,
+    l1_block_number: u64,
+) -> Result<Vec<DelegationInfo>> {
+    let l1 = L1Client::new(vec![l1_url])?;
+    let st = StakeTable::new(stake_table_address, &l1.provider);
+    let block = BlockId::number(l1_block_number);
+
+    let mut delegations = vec![];
+    for validator in
+        delegated_validators(&l1, stake_table_address, delegator, l1_block_number).await?
+    {
+        let amount = st
+            .delegations(validator, delegator)
+            .call()
+            .block(block)
+            .await
+            .with_context(|| format!("getting delegation to {validator}"))?
+            .amount;
+        if amount.is_zero() {
+            continue;
+        }
+        let exit = st
+            .validatorExits(validator)
+            .call()
+            .block(block)
+            .await
+            .with_context(|| format!("getting exit of {validator}"))?
+            .unlocksAt;
+        delegations.push(DelegationInfo {
+            validator,
+            amount,
+            validator_exit_unlocks_at: (!exit.is_zero()).then(|| exit.to::<u64>()),
+        });
+    }
+    Ok(delegations)
+}
+
+/// List the undelegations of `delegator` which have not been claimed, as of `l1_block_number`.
+pub async fn pending_undelegations(
+    l1_url: Url,
+    stake_table_address: Address,
+    delegator: Address,
+    l1_block_number: u64,
+) -> Result<Vec<PendingUndelegation>> {
+    let l1 = L1Client::new(vec![l1_url])?;
+    let st = StakeTable::new(stake_table_address, &l1.provider);
+    let block = BlockId::number(l1_block_number);
+    let timestamp = l1
+        .provider
+        .get_block(block)
+        .await?
+        .with_context(|| format!("L1 block {l1_block_number} not found"))?
+        .header
+        .timestamp;
+
+    let mut pending = vec![];
+    for validator in
+        delegated_validators(&l1, stake_table_address, delegator, l1_block_number).await?
+    {
+        let undelegation = st
+            .undelegations(validator, delegator)
+            .call()
+            .block(block)
+            .await
+            .with_context(|| format!("getting undelegation from {validator}"))?;
+        if undelegation.amount.is_zero() {
+            continue;
+        }
+        let unlocks_at = undelegation.unlocksAt.to::<u64>();
+        pending.push(PendingUndelegation {
+            validator,
+            amount: undelegation.amount,
+            unlocks_at,
+            claimable: unlocks_at <= timestamp,
+        });
+    }
+    Ok(pending)
+}
+
 pub fn display_stake_table(stake_table: Vec<Validator<BLSPubKey>>, compact: bool) -> Result<()> {
     let mut stake_table = stake_table.clone();
     stake_table.sort_by(|a, b| a.stake.cmp(&b.stake));
@@ -60,3 +356,92 @@ pub fn display_stake_table(stake_table: Vec<Validator<BLSPubKey>>, compact: bool
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::deploy::TestSystem;
+
+    #[tokio::test]
+    async fn test_delegation_queries() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        system.register_validator().await?;
+        system.delegate(U256::from(123)).await?;
+        system.undelegate(U256::from(23)).await?;
+
+        let validator = system.deployer_address;
+        let delegator = system.deployer_address;
+        let l1_block = system.provider.get_block_number().await?;
+
+        let delegations = delegations(
+            system.rpc_url.clone(),
+            system.stake_table,
+            delegator,
+            l1_block,
+        )
+        .await?;
+        assert_eq!(
+            delegations,
+            vec![DelegationInfo {
+                validator,
+                amount: U256::from(100),
+                validator_exit_unlocks_at: None,
+            }]
+        );
+
+        let pending = pending_undelegations(
+            system.rpc_url.clone(),
+            system.stake_table,
+            delegator,
+            l1_block,
+        )
+        .await?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].validator, validator);
+        assert_eq!(pending[0].amount, U256::from(23));
+        assert!(!pending[0].claimable);
+
+        let history = stake_table_history(
+            system.rpc_url.clone(),
+            system.stake_table,
+            delegator,
+            l1_block,
+        )
+        .await?;
+        let events = history
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            events[0],
+            StakeTableHistoryEvent::ValidatorRegistered { account, .. } if account == validator
+        ));
+        assert_eq!(
+            events[1..],
+            [
+                StakeTableHistoryEvent::Delegated {
+                    delegator,
+                    validator,
+                    amount: U256::from(123),
+                },
+                StakeTableHistoryEvent::Undelegated {
+                    delegator,
+                    validator,
+                    amount: U256::from(23),
+                },
+            ]
+        );
+
+        // Unrelated addresses have no history.
+        let history = stake_table_history(
+            system.rpc_url.clone(),
+            system.stake_table,
+            Address::random(),
+            l1_block,
+        )
+        .await?;
+        assert!(history.is_empty());
+
+        Ok(())
+    }
+}
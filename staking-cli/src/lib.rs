@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use alloy::{
     eips::BlockId,
     network::EthereumWallet,
@@ -10,12 +12,13 @@ use alloy::{
 use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
 use clap_serde_derive::ClapSerde;
+use hotshot_types::light_client::STAKE_TABLE_CAPACITY;
 pub(crate) use hotshot_types::{
     light_client::{StateSignKey, StateVerKey},
     signature_key::BLSPrivKey,
 };
 pub(crate) use jf_signature::bls_over_bn254::KeyPair as BLSKeyPair;
-use parse::Commission;
+use parse::{Commission, PrivKeyOrKeystore};
 use sequencer_utils::logging;
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -27,6 +30,8 @@ pub mod info;
 pub mod l1;
 pub mod parse;
 pub mod registration;
+pub mod rewards;
+pub mod transaction;
 
 pub mod deploy;
 
@@ -49,9 +54,17 @@ pub struct Config {
     #[arg(long, env = "STAKE_TABLE_ADDRESS")]
     pub stake_table_address: Address,
 
+    /// Espresso query service API, used to look up rewards.
+    #[arg(long, env = "ESPRESSO_URL")]
+    pub espresso_url: Option<Url>,
+
     #[command(flatten)]
     pub signer: SignerConfig,
 
+    #[command(flatten)]
+    #[serde(skip)]
+    pub transaction: TransactionOptions,
+
     #[command(flatten)]
     #[serde(skip)]
     pub logging: logging::Config,
@@ -80,6 +93,44 @@ pub struct SignerConfig {
     pub ledger: bool,
 }
 
+/// Options for commands which send a transaction.
+#[derive(Clone, Debug, Default, Parser)]
+pub struct TransactionOptions {
+    /// Simulate the transaction with `eth_call` instead of sending it.
+    ///
+    /// If the transaction would revert, the reason is reported.
+    #[arg(long, conflicts_with = "export_unsigned")]
+    pub dry_run: bool,
+
+    /// Write the unsigned transaction to this file instead of sending it.
+    ///
+    /// The transaction can then be signed on another machine with the `sign` command and sent with
+    /// the `broadcast` command.
+    #[arg(long, value_name = "FILE")]
+    pub export_unsigned: Option<PathBuf>,
+
+    /// The account that will sign the transaction, for `--dry-run` and `--export-unsigned`.
+    ///
+    /// Defaults to the signer account. If set, no signer needs to be configured.
+    #[arg(long)]
+    pub from: Option<Address>,
+
+    /// The nonce to use for `--export-unsigned`.
+    ///
+    /// Defaults to the next nonce of the account. Set it when exporting several transactions
+    /// before broadcasting any of them.
+    #[arg(long)]
+    pub nonce: Option<u64>,
+
+    /// The gas limit to use for `--export-unsigned`.
+    ///
+    /// If set, the transaction is not simulated and the gas limit is not estimated. This is
+    /// needed when exporting a transaction which depends on an earlier transaction that has not
+    /// been broadcast yet, for example a delegation after an approval.
+    #[arg(long, requires = "export_unsigned")]
+    pub gas_limit: Option<u64>,
+}
+
 #[derive(Clone, Debug)]
 pub enum ValidSignerConfig {
     Mnemonic {
@@ -240,17 +291,95 @@ pub enum Commands {
     },
     /// Print the signer account address.
     Account,
+    /// Show the rewards accrued by an account, verified against the Espresso reward state.
+    ///
+    /// Everything read from the Espresso node is verified relative to the state finalized by the
+    /// light client contract.
+    Rewards {
+        /// The address to check. Defaults to the signer account.
+        #[arg(long)]
+        address: Option<Address>,
+
+        /// The Espresso block height to read the rewards at.
+        ///
+        /// Defaults to the latest height finalized by the light client contract.
+        #[arg(long)]
+        espresso_block_height: Option<u64>,
+
+        /// The stake table capacity the light client proofs are generated with.
+        #[arg(long, env = "ESPRESSO_SEQUENCER_STAKE_TABLE_CAPACITY", default_value_t = STAKE_TABLE_CAPACITY)]
+        stake_table_capacity: usize,
+    },
+    /// List the current delegations of an account.
+    Delegations {
+        /// The delegator address. Defaults to the signer account.
+        #[arg(long)]
+        address: Option<Address>,
+
+        /// The L1 block number to read the delegations at.
+        ///
+        /// Defaults to the latest block.
+        #[arg(long)]
+        l1_block_number: Option<BlockId>,
+    },
+    /// List the unclaimed undelegations of an account and when they unlock.
+    PendingUndelegations {
+        /// The delegator address. Defaults to the signer account.
+        #[arg(long)]
+        address: Option<Address>,
+
+        /// The L1 block number to read the undelegations at.
+        ///
+        /// Defaults to the latest block.
+        #[arg(long)]
+        l1_block_number: Option<BlockId>,
+    },
+    /// Export the history of stake table events involving an address as JSON.
+    History {
+        /// The address to export events for. Defaults to the signer account.
+        #[arg(long)]
+        address: Option<Address>,
+
+        /// The last L1 block number to include events from.
+        ///
+        /// Defaults to the latest block.
+        #[arg(long)]
+        l1_block_number: Option<BlockId>,
+
+        /// Write the history to this file instead of stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
     /// Register to become a validator.
     RegisterValidator {
         /// The consensus signing key. Used to sign a message to prove ownership of the key.
-        #[arg(long, value_parser = parse::parse_bls_priv_key, env = "CONSENSUS_PRIVATE_KEY")]
-        consensus_private_key: BLSPrivKey,
+        ///
+        /// Either the key itself, or the path of an encrypted keystore containing it, unlocked
+        /// with the password from `--keystore-password-file`.
+        #[arg(
+            long,
+            value_parser = parse::parse_bls_priv_key_or_keystore,
+            env = "CONSENSUS_PRIVATE_KEY"
+        )]
+        consensus_private_key: PrivKeyOrKeystore<BLSPrivKey>,
 
         /// The state signing key.
         ///
+        /// Either the key itself, or the path of an encrypted keystore containing it.
+        ///
         /// TODO: Used to sign a message to prove ownership of the key.
-        #[arg(long, value_parser = parse::parse_state_priv_key, env = "STATE_PRIVATE_KEY")]
-        state_private_key: StateSignKey,
+        #[arg(
+            long,
+            value_parser = parse::parse_state_priv_key_or_keystore,
+            env = "STATE_PRIVATE_KEY"
+        )]
+        state_private_key: PrivKeyOrKeystore<StateSignKey>,
+
+        /// File containing the password of the keystores given as private keys.
+        ///
+        /// If not given, the password is read from the first line of stdin.
+        #[arg(long, env = "KEYSTORE_PASSWORD_FILE")]
+        keystore_password_file: Option<PathBuf>,
 
         /// The commission to charge delegators
         #[arg(long, value_parser = parse::parse_commission, env = "COMMISSION")]
@@ -259,14 +388,33 @@ pub enum Commands {
     /// Update a validators Espresso consensus signing keys.
     UpdateConsensusKeys {
         /// The consensus signing key. Used to sign a message to prove ownership of the key.
-        #[arg(long, value_parser = parse::parse_bls_priv_key, env = "CONSENSUS_PRIVATE_KEY")]
-        consensus_private_key: BLSPrivKey,
+        ///
+        /// Either the key itself, or the path of an encrypted keystore containing it, unlocked
+        /// with the password from `--keystore-password-file`.
+        #[arg(
+            long,
+            value_parser = parse::parse_bls_priv_key_or_keystore,
+            env = "CONSENSUS_PRIVATE_KEY"
+        )]
+        consensus_private_key: PrivKeyOrKeystore<BLSPrivKey>,
 
         /// The state signing key.
         ///
+        /// Either the key itself, or the path of an encrypted keystore containing it.
+        ///
         /// TODO: Used to sign a message to prove ownership of the key.
-        #[arg(long, value_parser = parse::parse_state_priv_key, env = "STATE_PRIVATE_KEY")]
-        state_private_key: StateSignKey,
+        #[arg(
+            long,
+            value_parser = parse::parse_state_priv_key_or_keystore,
+            env = "STATE_PRIVATE_KEY"
+        )]
+        state_private_key: PrivKeyOrKeystore<StateSignKey>,
+
+        /// File containing the password of the keystores given as private keys.
+        ///
+        /// If not given, the password is read from the first line of stdin.
+        #[arg(long, env = "KEYSTORE_PASSWORD_FILE")]
+        keystore_password_file: Option<PathBuf>,
     },
     /// Deregister a validator.
     DeregisterValidator {},
@@ -323,6 +471,24 @@ pub enum Commands {
         #[arg(long, value_parser = parse_ether)]
         amount: U256,
     },
+    /// Sign a transaction exported with `--export-unsigned`.
+    ///
+    /// This does not need access to L1, so it can be run on an offline machine.
+    Sign {
+        /// The unsigned transaction file.
+        #[arg(long)]
+        input: PathBuf,
+
+        /// Where to write the signed transaction.
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Send a transaction signed with the `sign` command.
+    Broadcast {
+        /// The signed transaction file.
+        #[arg(long)]
+        input: PathBuf,
+    },
     /// Register the validators and delegates for the local demo.
     StakeForDemo {
         /// The number of validators to register.
@@ -18,8 +18,17 @@ use staking_cli::{
     claim::{claim_validator_exit, claim_withdrawal},
     delegation::{approve, delegate, undelegate},
     demo::stake_for_demo,
-    info::{display_stake_table, stake_table_info},
+    info::{
+        delegations, display_stake_table, pending_undelegations, stake_table_history,
+        stake_table_info,
+    },
+    parse::unlock_keys,
     registration::{deregister_validator, register_validator, update_consensus_keys},
+    rewards::{reward_balance, verified_client},
+    transaction::{
+        broadcast_transaction, contract_call, dry_run, prepare_transaction, read_json,
+        sign_transaction, write_json,
+    },
     Commands, Config, ValidSignerConfig,
 };
 use sysinfo::System;
@@ -93,6 +102,34 @@ fn exit(msg: impl AsRef<str>) -> ! {
     std::process::exit(1);
 }
 
+/// Resolve an optional L1 block to a block number, defaulting to the latest block.
+async fn l1_block_number(config: &Config, block: Option<BlockId>) -> Result<u64> {
+    let provider = ProviderBuilder::new().on_http(config.rpc_url.clone());
+    let query_block = block.unwrap_or(BlockId::latest());
+    let l1_block = provider.get_block(query_block).await?.unwrap_or_else(|| {
+        exit_err("Failed to get block {query_block}", "Block not found");
+    });
+    Ok(l1_block.header.number)
+}
+
+/// Use `address` if given, otherwise the address of the configured signer.
+async fn address_or_account(config: &Config, address: Option<Address>) -> Result<Address> {
+    match address {
+        Some(address) => Ok(address),
+        None => {
+            let (_, account) = TryInto::<ValidSignerConfig>::try_into(config.signer.clone())?
+                .wallet()
+                .await?;
+            Ok(account)
+        },
+    }
+}
+
+fn print_json(value: &impl serde::Serialize) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
 #[tokio::main]
 pub async fn main() -> Result<()> {
     let mut cli = Args::parse();
@@ -195,15 +232,10 @@ pub async fn main() -> Result<()> {
     // Commands that don't need a signer
     match config.commands {
         Commands::StakeTable {
-            l1_block_number,
+            l1_block_number: block,
             compact,
         } => {
-            let provider = ProviderBuilder::new().on_http(config.rpc_url.clone());
-            let query_block = l1_block_number.unwrap_or(BlockId::latest());
-            let l1_block = provider.get_block(query_block).await?.unwrap_or_else(|| {
-                exit_err("Failed to get block {query_block}", "Block not found");
-            });
-            let l1_block_resolved = l1_block.header.number;
+            let l1_block_resolved = l1_block_number(&config, block).await?;
             tracing::info!("Getting stake table info at block {l1_block_resolved}");
             let stake_table = stake_table_info(
                 config.rpc_url.clone(),
@@ -214,9 +246,130 @@ pub async fn main() -> Result<()> {
             display_stake_table(stake_table, compact)?;
             return Ok(());
         },
+        Commands::Rewards {
+            address,
+            espresso_block_height,
+            stake_table_capacity,
+        } => {
+            let address = address_or_account(&config, address).await?;
+            let espresso_url = config.espresso_url.clone().unwrap_or_else(|| {
+                exit("Espresso URL is not set use --espresso-url or ESPRESSO_URL")
+            });
+            let client = verified_client(
+                espresso_url,
+                config.rpc_url.clone(),
+                config.stake_table_address,
+                stake_table_capacity,
+            )
+            .await?;
+            tracing::info!("Getting rewards for {address}");
+            print_json(&reward_balance(&client, address, espresso_block_height).await?)?;
+            return Ok(());
+        },
+        Commands::Delegations {
+            address,
+            l1_block_number: block,
+        } => {
+            let address = address_or_account(&config, address).await?;
+            let l1_block = l1_block_number(&config, block).await?;
+            tracing::info!("Getting delegations of {address} at block {l1_block}");
+            let delegations = delegations(
+                config.rpc_url.clone(),
+                config.stake_table_address,
+                address,
+                l1_block,
+            )
+            .await?;
+            print_json(&delegations)?;
+            return Ok(());
+        },
+        Commands::PendingUndelegations {
+            address,
+            l1_block_number: block,
+        } => {
+            let address = address_or_account(&config, address).await?;
+            let l1_block = l1_block_number(&config, block).await?;
+            tracing::info!("Getting pending undelegations of {address} at block {l1_block}");
+            let undelegations = pending_undelegations(
+                config.rpc_url.clone(),
+                config.stake_table_address,
+                address,
+                l1_block,
+            )
+            .await?;
+            print_json(&undelegations)?;
+            return Ok(());
+        },
+        Commands::History {
+            address,
+            l1_block_number: block,
+            ref output,
+        } => {
+            let address = address_or_account(&config, address).await?;
+            let l1_block = l1_block_number(&config, block).await?;
+            tracing::info!("Exporting stake table history of {address} up to block {l1_block}");
+            let history = stake_table_history(
+                config.rpc_url.clone(),
+                config.stake_table_address,
+                address,
+                l1_block,
+            )
+            .await?;
+            match output {
+                Some(path) => {
+                    write_json(path, &history)?;
+                    tracing::info!("Wrote {} events to {}", history.len(), path.display());
+                },
+                None => print_json(&history)?,
+            }
+            return Ok(());
+        },
+        Commands::Broadcast { input } => {
+            let tx = read_json(&input)?;
+            let provider = ProviderBuilder::new().on_http(config.rpc_url.clone());
+            match broadcast_transaction(&provider, tx).await {
+                Ok(receipt) => {
+                    tracing::info!("Success! transaction hash: {}", receipt.transaction_hash)
+                },
+                Err(err) => exit_err("Failed:", err),
+            }
+            return Ok(());
+        },
         _ => {}, // Other commands handled below.
     }
 
+    // Transactions can be simulated or exported without access to the signer.
+    if config.transaction.dry_run || config.transaction.export_unsigned.is_some() {
+        let from = address_or_account(&config, config.transaction.from).await?;
+        let Some(call) = contract_call(&config, from)? else {
+            exit("--dry-run and --export-unsigned can only be used with commands that send a transaction")
+        };
+        let provider = ProviderBuilder::new().on_http(config.rpc_url.clone());
+        tracing::info!("{}", call.description);
+        match &config.transaction.export_unsigned {
+            Some(path) => {
+                let tx = prepare_transaction(
+                    &provider,
+                    from,
+                    call,
+                    config.transaction.nonce,
+                    config.transaction.gas_limit,
+                )
+                .await
+                .unwrap_or_else(|err| exit_err("Failed:", err));
+                write_json(path, &tx)?;
+                tracing::info!("Unsigned transaction written to {}", path.display());
+            },
+            None => {
+                dry_run(&provider, from, &call)
+                    .await
+                    .unwrap_or_else(|err| exit_err("Transaction would fail", err));
+                tracing::info!("Success! The transaction would not revert");
+            },
+        }
+        return Ok(());
+    }
+
     let (wallet, account) = TryInto::<ValidSignerConfig>::try_into(config.signer.clone())?
         .wallet()
         .await?;
@@ -225,6 +378,16 @@ pub async fn main() -> Result<()> {
             println!("{account}");
             return Ok(());
         },
+        Commands::Sign { input, output } => {
+            let tx = sign_transaction(&wallet, account, read_json(&input)?).await?;
+            write_json(&output, &tx)?;
+            tracing::info!(
+                "Signed transaction {} written to {}",
+                tx.hash,
+                output.display()
+            );
+            return Ok(());
+        },
         _ => {}, // Other commands handled after shared setup.
     };
 
@@ -248,8 +411,14 @@ pub async fn main() -> Result<()> {
         Commands::RegisterValidator {
             consensus_private_key,
             state_private_key,
+            keystore_password_file,
             commission,
         } => {
+            let (consensus_private_key, state_private_key) = unlock_keys(
+                &consensus_private_key,
+                &state_private_key,
+                keystore_password_file.as_deref(),
+            )?;
             tracing::info!("Registering validator {account} with commission {commission}");
             register_validator(
                 &provider,
@@ -264,7 +433,13 @@ pub async fn main() -> Result<()> {
         Commands::UpdateConsensusKeys {
             consensus_private_key,
             state_private_key,
+            keystore_password_file,
         } => {
+            let (consensus_private_key, state_private_key) = unlock_keys(
+                &consensus_private_key,
+                &state_private_key,
+                keystore_password_file.as_deref(),
+            )?;
             tracing::info!("Updating validator {account} with new keys");
             update_consensus_keys(
                 &provider,
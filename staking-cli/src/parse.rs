@@ -1,10 +1,17 @@
-use std::{fmt::Display, str::FromStr as _};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr as _,
+};
 
+use anyhow::{ensure, Context as _};
 use derive_more::From;
 use hotshot_types::{light_client::StateSignKey, signature_key::BLSPrivKey};
 use rust_decimal::{prelude::ToPrimitive as _, Decimal};
+use sequencer_utils::keystore::{read_password, Keystore};
 use tagged_base64::{TaggedBase64, Tb64Error};
 use thiserror::Error;
+use zeroize::Zeroizing;
 
 pub fn parse_bls_priv_key(s: &str) -> Result<BLSPrivKey, Tb64Error> {
     TaggedBase64::parse(s)?.try_into()
@@ -14,6 +21,79 @@ pub fn parse_state_priv_key(s: &str) -> Result<StateSignKey, Tb64Error> {
     TaggedBase64::parse(s)?.try_into()
 }
 
+/// A private key given on the command line, either directly or as an encrypted keystore.
+#[derive(Clone, Debug)]
+pub enum PrivKeyOrKeystore<K> {
+    Key(K),
+    Keystore(PathBuf),
+}
+
+impl<K> PrivKeyOrKeystore<K>
+where
+    K: Clone + TryFrom<TaggedBase64, Error = Tb64Error>,
+{
+    /// Get the private key, decrypting the keystore if necessary.
+    ///
+    /// The password is read from `password_file`, or from the first line of stdin, the first time
+    /// it is needed and cached in `password`, so that several keystores can be unlocked without
+    /// prompting more than once.
+    pub fn unlock(
+        &self,
+        password_file: Option<&Path>,
+        password: &mut Option<Zeroizing<String>>,
+    ) -> anyhow::Result<K> {
+        let path = match self {
+            Self::Key(key) => return Ok(key.clone()),
+            Self::Keystore(path) => path,
+        };
+        if password.is_none() {
+            *password = Some(read_password(password_file)?);
+        }
+        let password = password.as_deref().context("keystore password missing")?;
+        Ok(Keystore::load(path)?
+            .decrypt(password)
+            .with_context(|| format!("decrypting keystore {}", path.display()))?
+            .try_into()?)
+    }
+}
+
+/// Parse a BLS private key, or the path of an encrypted keystore containing one.
+pub fn parse_bls_priv_key_or_keystore(s: &str) -> anyhow::Result<PrivKeyOrKeystore<BLSPrivKey>> {
+    parse_priv_key_or_keystore(s)
+}
+
+/// Parse a state private key, or the path of an encrypted keystore containing one.
+pub fn parse_state_priv_key_or_keystore(
+    s: &str,
+) -> anyhow::Result<PrivKeyOrKeystore<StateSignKey>> {
+    parse_priv_key_or_keystore(s)
+}
+
+fn parse_priv_key_or_keystore<K>(s: &str) -> anyhow::Result<PrivKeyOrKeystore<K>>
+where
+    K: TryFrom<TaggedBase64, Error = Tb64Error>,
+{
+    if let Ok(key) = TaggedBase64::parse(s) {
+        return Ok(PrivKeyOrKeystore::Key(key.try_into()?));
+    }
+    let path = PathBuf::from(s);
+    ensure!(path.is_file(), "neither a private key nor a keystore file");
+    Ok(PrivKeyOrKeystore::Keystore(path))
+}
+
+/// Get the consensus and state private keys, decrypting keystores with the same password.
+pub fn unlock_keys(
+    consensus_private_key: &PrivKeyOrKeystore<BLSPrivKey>,
+    state_private_key: &PrivKeyOrKeystore<StateSignKey>,
+    password_file: Option<&Path>,
+) -> anyhow::Result<(BLSPrivKey, StateSignKey)> {
+    let mut password = None;
+    Ok((
+        consensus_private_key.unlock(password_file, &mut password)?,
+        state_private_key.unlock(password_file, &mut password)?,
+    ))
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct Commission(u16);
 
@@ -15,7 +15,7 @@ use jf_signature::constants::CS_ID_BLS_BN254;
 
 use crate::{parse::Commission, BLSKeyPair, StateVerKey};
 
-fn prepare_bls_payload(
+pub(crate) fn prepare_bls_payload(
     bls_key_pair: &BLSKeyPair,
     validator_address: Address,
 ) -> (G2PointSol, G1PointSol) {
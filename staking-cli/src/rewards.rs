@@ -0,0 +1,96 @@
+use alloy::{
+    primitives::{Address, U256},
+    providers::ProviderBuilder,
+};
+use anyhow::{Context, Result};
+use client::{SequencerClient, TrustedCheckpoint, VerifiedSequencerClient};
+use hotshot_contract_adapter::sol_types::{LightClientV2, StakeTable};
+use hotshot_state_prover::service::read_contract_state;
+use hotshot_types::utils::epoch_from_block_number;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// The rewards accrued by an account, as of an Espresso block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardBalance {
+    pub address: Address,
+    /// The Espresso block height the balance was read at.
+    pub height: u64,
+    pub balance: U256,
+}
+
+/// Connect to an Espresso query service, verifying everything it returns against the state
+/// finalized by the light client contract.
+///
+/// The light client contract is found through the stake table contract at `stake_table_address`.
+/// `stake_table_capacity` must match the capacity the light client proofs are generated with.
+pub async fn verified_client(
+    espresso_url: Url,
+    l1_url: Url,
+    stake_table_address: Address,
+    stake_table_capacity: usize,
+) -> Result<VerifiedSequencerClient> {
+    let provider = ProviderBuilder::new().on_http(l1_url);
+    let light_client_address = StakeTable::new(stake_table_address, &provider)
+        .lightClient()
+        .call()
+        .await
+        .context("getting light client address")?
+        ._0;
+    let (state, stake_table_state) = read_contract_state(provider.clone(), light_client_address)
+        .await
+        .context("reading light client state")?;
+
+    // A light client without epochs has no epoch height, in which case a single stake table
+    // certifies the whole chain.
+    let epoch_height = LightClientV2::new(light_client_address, &provider)
+        .blocksPerEpoch()
+        .call()
+        .await
+        .ok()
+        .map(|res| res._0)
+        .filter(|epoch_height| *epoch_height > 0);
+    let epoch =
+        epoch_height.map(|epoch_height| epoch_from_block_number(state.block_height, epoch_height));
+
+    let client = SequencerClient::new(espresso_url);
+    let checkpoint = TrustedCheckpoint::from_light_client_state(
+        &client,
+        state,
+        stake_table_state,
+        stake_table_capacity,
+        epoch,
+    )
+    .await
+    .context("verifying light client checkpoint")?;
+    Ok(VerifiedSequencerClient::new(
+        client,
+        checkpoint,
+        epoch_height,
+    ))
+}
+
+/// Get the reward balance of `address` as of Espresso block `height`.
+///
+/// The balance is checked with a Merkle proof against the reward Merkle root of a header verified
+/// by `client`. If `height` is not given, the balance is read at the latest header `client`
+/// trusts.
+pub async fn reward_balance(
+    client: &VerifiedSequencerClient,
+    address: Address,
+    height: Option<u64>,
+) -> Result<RewardBalance> {
+    let height = match height {
+        Some(height) => height,
+        None => client.latest_trusted_header().await.height(),
+    };
+    let balance = client
+        .get_reward_balance(address, height)
+        .await
+        .with_context(|| format!("getting reward balance of {address} at height {height}"))?;
+    Ok(RewardBalance {
+        address,
+        height,
+        balance: balance.0,
+    })
+}
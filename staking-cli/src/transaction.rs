@@ -0,0 +1,485 @@
+//! Simulating transactions, and building, signing and sending them in separate steps.
+//!
+//! This allows validators to keep their L1 keys off the machine which talks to L1: an unsigned
+//! transaction is exported on the online machine, signed on the offline machine and the signed
+//! transaction is broadcast from the online machine again.
+
+use std::path::Path;
+
+use alloy::{
+    contract::{CallBuilder, Error as ContractError},
+    eips::eip2718::Encodable2718 as _,
+    network::{EthereumWallet, TransactionBuilder as _},
+    primitives::{utils::format_ether, Address, Bytes, TxHash},
+    providers::Provider,
+    rpc::types::{TransactionReceipt, TransactionRequest},
+    sol_types::SolCall,
+};
+use anyhow::{bail, ensure, Context, Result};
+use hotshot_contract_adapter::sol_types::{
+    EdOnBN254PointSol,
+    EspToken::{self, EspTokenErrors},
+    StakeTable::{self, StakeTableErrors},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    parse::unlock_keys, registration::prepare_bls_payload, BLSKeyPair, Commands, Config,
+    StateVerKey,
+};
+
+/// A contract call made by a command which sends a transaction.
+#[derive(Clone, Debug)]
+pub struct ContractCall {
+    pub to: Address,
+    pub input: Bytes,
+    /// What the call does, for the benefit of whoever signs it.
+    pub description: String,
+}
+
+/// A transaction ready to be signed, with nonce, gas and fees already filled in.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub description: String,
+    pub request: TransactionRequest,
+}
+
+/// A signed transaction ready to be broadcast.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub description: String,
+    pub hash: TxHash,
+    /// The EIP-2718 encoded transaction.
+    pub raw: Bytes,
+}
+
+/// The contract call made by the configured command when sent by `from`.
+///
+/// Returns [`None`] if the command does not send a transaction. Fails if a key needed to build the
+/// call cannot be unlocked.
+pub fn contract_call(config: &Config, from: Address) -> Result<Option<ContractCall>> {
+    let stake_table = config.stake_table_address;
+    let token = config.token_address;
+    let (to, input, description) = match &config.commands {
+        Commands::RegisterValidator {
+            consensus_private_key,
+            state_private_key,
+            keystore_password_file,
+            commission,
+        } => {
+            let (consensus_private_key, state_private_key) = unlock_keys(
+                consensus_private_key,
+                state_private_key,
+                keystore_password_file.as_deref(),
+            )?;
+            let bls_key_pair: BLSKeyPair = consensus_private_key.into();
+            let schnorr_vk: StateVerKey = (&state_private_key).into();
+            let (bls_vk, bls_sig) = prepare_bls_payload(&bls_key_pair, from);
+            let schnorr_vk: EdOnBN254PointSol = schnorr_vk.to_affine().into();
+            let call = StakeTable::registerValidatorCall {
+                blsVK: bls_vk,
+                schnorrVK: schnorr_vk,
+                blsSig: bls_sig.into(),
+                commission: commission.to_evm(),
+            };
+            (
+                stake_table,
+                call.abi_encode(),
+                format!("Register validator {from} with commission {commission}"),
+            )
+        },
+        Commands::UpdateConsensusKeys {
+            consensus_private_key,
+            state_private_key,
+            keystore_password_file,
+        } => {
+            let (consensus_private_key, state_private_key) = unlock_keys(
+                consensus_private_key,
+                state_private_key,
+                keystore_password_file.as_deref(),
+            )?;
+            let bls_key_pair: BLSKeyPair = consensus_private_key.into();
+            let schnorr_vk: StateVerKey = (&state_private_key).into();
+            let (bls_vk, bls_sig) = prepare_bls_payload(&bls_key_pair, from);
+            let schnorr_vk: EdOnBN254PointSol = schnorr_vk.to_affine().into();
+            let call = StakeTable::updateConsensusKeysCall {
+                newBlsVK: bls_vk,
+                newSchnorrVK: schnorr_vk,
+                newBlsSig: bls_sig.into(),
+            };
+            (
+                stake_table,
+                call.abi_encode(),
+                format!("Update consensus keys of validator {from}"),
+            )
+        },
+        Commands::DeregisterValidator {} => (
+            stake_table,
+            StakeTable::deregisterValidatorCall {}.abi_encode(),
+            format!("Deregister validator {from}"),
+        ),
+        Commands::Approve { amount } => (
+            token,
+            EspToken::approveCall {
+                spender: stake_table,
+                value: *amount,
+            }
+            .abi_encode(),
+            format!(
+                "Approve stake table {stake_table} to spend {} ESP",
+                format_ether(*amount)
+            ),
+        ),
+        Commands::Delegate {
+            validator_address,
+            amount,
+        } => (
+            stake_table,
+            StakeTable::delegateCall {
+                validator: *validator_address,
+                amount: *amount,
+            }
+            .abi_encode(),
+            format!(
+                "Delegate {} ESP to {validator_address}",
+                format_ether(*amount)
+            ),
+        ),
+        Commands::Undelegate {
+            validator_address,
+            amount,
+        } => (
+            stake_table,
+            StakeTable::undelegateCall {
+                validator: *validator_address,
+                amount: *amount,
+            }
+            .abi_encode(),
+            format!(
+                "Undelegate {} ESP from {validator_address}",
+                format_ether(*amount)
+            ),
+        ),
+        Commands::ClaimWithdrawal { validator_address } => (
+            stake_table,
+            StakeTable::claimWithdrawalCall {
+                validator: *validator_address,
+            }
+            .abi_encode(),
+            format!("Claim withdrawal from {validator_address}"),
+        ),
+        Commands::ClaimValidatorExit { validator_address } => (
+            stake_table,
+            StakeTable::claimValidatorExitCall {
+                validator: *validator_address,
+            }
+            .abi_encode(),
+            format!("Claim validator exit of {validator_address}"),
+        ),
+        Commands::Transfer { to, amount } => (
+            token,
+            EspToken::transferCall {
+                to: *to,
+                value: *amount,
+            }
+            .abi_encode(),
+            format!("Transfer {} ESP to {to}", format_ether(*amount)),
+        ),
+        _ => return Ok(None),
+    };
+    Ok(Some(ContractCall {
+        to,
+        input: input.into(),
+        description,
+    }))
+}
+
+/// Simulate `call` sent by `from` with `eth_call`.
+///
+/// If the call would revert, the error explains why, as far as the revert reason can be decoded.
+pub async fn dry_run(provider: impl Provider, from: Address, call: &ContractCall) -> Result<()> {
+    CallBuilder::new_raw(&provider, call.input.clone())
+        .from(from)
+        .to(call.to)
+        .call()
+        .await
+        .map_err(|err| anyhow::anyhow!(describe_revert(&err)))?;
+    Ok(())
+}
+
+/// Turn a failed contract call into a human-readable error message.
+pub fn describe_revert(err: &ContractError) -> String {
+    if let Some(err) = err.as_decoded_interface_error::<StakeTableErrors>() {
+        return describe_stake_table_error(&err);
+    }
+    if let Some(err) = err.as_decoded_interface_error::<EspTokenErrors>() {
+        return format!("ESP token error: {err:?}");
+    }
+    format!("{err:#}")
+}
+
+fn describe_stake_table_error(err: &StakeTableErrors) -> String {
+    match err {
+        StakeTableErrors::ValidatorAlreadyRegistered(_) => {
+            "a validator is already registered with this account".into()
+        },
+        StakeTableErrors::ValidatorInactive(_) => {
+            "the validator is not registered or has exited".into()
+        },
+        StakeTableErrors::ValidatorAlreadyExited(_) => "the validator has already exited".into(),
+        StakeTableErrors::ValidatorNotExited(_) => "the validator has not exited".into(),
+        StakeTableErrors::PrematureWithdrawal(_) => {
+            "the funds are still locked, wait for the exit escrow period to end".into()
+        },
+        StakeTableErrors::InsufficientAllowance(e) => format!(
+            "the stake table may only spend {} ESP, but {} ESP are needed, use `approve` first",
+            format_ether(e._0),
+            format_ether(e._1)
+        ),
+        StakeTableErrors::InsufficientBalance(e) => {
+            format!("insufficient ESP balance: {} ESP", format_ether(e._0))
+        },
+        StakeTableErrors::NothingToWithdraw(_) => "there are no funds to withdraw".into(),
+        StakeTableErrors::InvalidSchnorrVK(_) => "the state verification key is invalid".into(),
+        StakeTableErrors::BlsKeyAlreadyUsed(_) => {
+            "the consensus key has already been registered".into()
+        },
+        StakeTableErrors::InvalidCommission(_) => "the commission is invalid".into(),
+        StakeTableErrors::UndelegationAlreadyExists(_) => {
+            "there is already a pending undelegation from this validator, claim it first".into()
+        },
+        StakeTableErrors::ZeroAmount(_) => "the amount must not be zero".into(),
+        StakeTableErrors::BLSSigVerificationFailed(_) => {
+            "the consensus key signature is invalid".into()
+        },
+        err => format!("stake table error: {err:?}"),
+    }
+}
+
+/// Fill in nonce, gas and fees for `call` sent by `from`, so it can be signed offline.
+///
+/// If `nonce` is not given, the next nonce of `from` is used; pass it explicitly when exporting
+/// several transactions before broadcasting any of them.
+///
+/// Unless `gas_limit` is given, the call is simulated first, so a transaction which would revert
+/// is never exported, and the gas limit is estimated. A transaction which depends on an earlier
+/// one that has not been broadcast yet (e.g. a delegation after an approval) cannot be simulated,
+/// so it needs an explicit gas limit.
+pub async fn prepare_transaction(
+    provider: impl Provider,
+    from: Address,
+    call: ContractCall,
+    nonce: Option<u64>,
+    gas_limit: Option<u64>,
+) -> Result<UnsignedTransaction> {
+    if gas_limit.is_none() {
+        dry_run(&provider, from, &call).await?;
+    }
+
+    let nonce = match nonce {
+        Some(nonce) => nonce,
+        None => provider.get_transaction_count(from).await?,
+    };
+    let request = TransactionRequest::default()
+        .with_from(from)
+        .with_to(call.to)
+        .with_input(call.input)
+        .with_nonce(nonce)
+        .with_chain_id(provider.get_chain_id().await?);
+    let gas_limit = match gas_limit {
+        Some(gas_limit) => gas_limit,
+        None => provider
+            .estimate_gas(request.clone())
+            .await
+            .context("estimating gas")?,
+    };
+    let fees = provider
+        .estimate_eip1559_fees()
+        .await
+        .context("estimating fees")?;
+    let request = request
+        .with_gas_limit(gas_limit)
+        .with_max_fee_per_gas(fees.max_fee_per_gas)
+        .with_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+
+    Ok(UnsignedTransaction {
+        description: call.description,
+        request,
+    })
+}
+
+/// Sign a transaction exported with [`prepare_transaction`]. This does not need L1 access.
+pub async fn sign_transaction(
+    wallet: &EthereumWallet,
+    account: Address,
+    tx: UnsignedTransaction,
+) -> Result<SignedTransaction> {
+    let from = tx.request.from.context("transaction has no sender")?;
+    ensure!(
+        from == account,
+        "transaction must be signed by {from}, but the signer account is {account}"
+    );
+    let envelope = tx
+        .request
+        .build(wallet)
+        .await
+        .context("signing transaction")?;
+    Ok(SignedTransaction {
+        description: tx.description,
+        hash: *envelope.tx_hash(),
+        raw: envelope.encoded_2718().into(),
+    })
+}
+
+/// Send a transaction signed with [`sign_transaction`] and wait for its receipt.
+pub async fn broadcast_transaction(
+    provider: impl Provider,
+    tx: SignedTransaction,
+) -> Result<TransactionReceipt> {
+    let receipt = provider
+        .send_raw_transaction(&tx.raw)
+        .await?
+        .get_receipt()
+        .await?;
+    if !receipt.status() {
+        bail!("transaction {} reverted", receipt.transaction_hash);
+    }
+    Ok(receipt)
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("parsing {}", path.display()))
+}
+
+pub fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)
+        .with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::{primitives::U256, providers::WalletProvider as _};
+
+    use super::*;
+    use crate::deploy::TestSystem;
+
+    fn delegate_call(system: &TestSystem, amount: U256) -> ContractCall {
+        ContractCall {
+            to: system.stake_table,
+            input: StakeTable::delegateCall {
+                validator: system.deployer_address,
+                amount,
+            }
+            .abi_encode()
+            .into(),
+            description: "delegate".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_decodes_revert() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        let call = delegate_call(&system, U256::from(123));
+
+        // The validator is not registered yet.
+        let err = dry_run(&system.provider, system.deployer_address, &call)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("not registered"),
+            "unexpected error: {err:#}"
+        );
+
+        system.register_validator().await?;
+        dry_run(&system.provider, system.deployer_address, &call).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prepare_sign_broadcast() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        system.register_validator().await?;
+        let amount = U256::from(123);
+
+        let tx = prepare_transaction(
+            &system.provider,
+            system.deployer_address,
+            delegate_call(&system, amount),
+            None,
+            None,
+        )
+        .await?;
+
+        // Round trip through JSON, as if the transaction was moved to another machine.
+        let tx: UnsignedTransaction = serde_json::from_str(&serde_json::to_string(&tx)?)?;
+        let wallet = system.provider.wallet().clone();
+        let signed = sign_transaction(&wallet, system.deployer_address, tx.clone()).await?;
+
+        // The wrong signer is rejected.
+        assert!(sign_transaction(&wallet, Address::random(), tx)
+            .await
+            .is_err());
+
+        let receipt = broadcast_transaction(&system.provider, signed.clone()).await?;
+        assert_eq!(receipt.transaction_hash, signed.hash);
+        let event = receipt.decoded_log::<StakeTable::Delegated>().unwrap();
+        assert_eq!(event.amount, amount);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export_dependent_transactions() -> Result<()> {
+        let system = TestSystem::deploy().await?;
+        system.register_validator().await?;
+        system.approve(U256::ZERO).await?;
+        let amount = U256::from(123);
+        let from = system.deployer_address;
+        let nonce = system.provider.get_transaction_count(from).await?;
+
+        let approve = ContractCall {
+            to: system.token,
+            input: EspToken::approveCall {
+                spender: system.stake_table,
+                value: amount,
+            }
+            .abi_encode()
+            .into(),
+            description: "approve".into(),
+        };
+        let approve =
+            prepare_transaction(&system.provider, from, approve, Some(nonce), None).await?;
+
+        // The delegation would revert until the approval lands, so it can't be simulated.
+        prepare_transaction(
+            &system.provider,
+            from,
+            delegate_call(&system, amount),
+            Some(nonce + 1),
+            None,
+        )
+        .await
+        .unwrap_err();
+        let delegate = prepare_transaction(
+            &system.provider,
+            from,
+            delegate_call(&system, amount),
+            Some(nonce + 1),
+            Some(500_000),
+        )
+        .await?;
+        assert_eq!(delegate.request.gas, Some(500_000));
+
+        let wallet = system.provider.wallet().clone();
+        for tx in [approve, delegate] {
+            let signed = sign_transaction(&wallet, from, tx).await?;
+            broadcast_transaction(&system.provider, signed).await?;
+        }
+        assert_eq!(system.allowance(from).await?, U256::ZERO);
+
+        Ok(())
+    }
+}
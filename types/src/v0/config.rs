@@ -229,13 +229,13 @@ impl From<NetworkConfig<SeqTypes>> for PublicNetworkConfig {
 impl PublicNetworkConfig {
     pub fn into_network_config(
         self,
-        my_own_validator_config: ValidatorConfig<SeqTypes>,
+        my_own_public_key: PubKey,
     ) -> anyhow::Result<NetworkConfig<SeqTypes>> {
         let node_index = self
             .config
             .known_nodes_with_stake
             .iter()
-            .position(|peer| peer.stake_table_entry.stake_key == my_own_validator_config.public_key)
+            .position(|peer| peer.stake_table_entry.stake_key == my_own_public_key)
             .unwrap_or(0) as u64;
 
         Ok(NetworkConfig {
@@ -220,7 +220,7 @@ impl BidTx {
         Ok(())
     }
     /// Cryptographic signature verification
-    fn verify(&self) -> Result<(), ExecutionError> {
+    pub fn verify(&self) -> Result<(), ExecutionError> {
         self.body
             .account
             .validate_builder_signature(&self.signature, self.body.commit().as_ref())
@@ -247,6 +247,10 @@ impl BidTx {
     pub fn view(&self) -> ViewNumber {
         self.body.view
     }
+    /// Get the namespaces the bid is for
+    pub fn namespaces(&self) -> &[NamespaceId] {
+        &self.body.namespaces
+    }
     /// Get the `url` field from the body.
     pub fn url(&self) -> Url {
         self.body.url()
@@ -67,6 +67,29 @@ impl Payload {
         PayloadByteLen(self.raw_payload.len())
     }
 
+    /// A payload containing only the namespaces of this payload for which `keep` returns `true`.
+    ///
+    /// The result has a different commitment than this payload, so it cannot be checked against
+    /// the header of this payload. It is only useful for reading the kept transactions.
+    pub fn restrict_namespaces(&self, mut keep: impl FnMut(NamespaceId) -> bool) -> Self {
+        let mut payload = Vec::new();
+        let mut ns_table_builder = NsTableBuilder::new();
+        for index in self.ns_table.iter() {
+            let Some(ns_id) = self.ns_table.read_ns_id(&index) else {
+                continue;
+            };
+            if !keep(ns_id) {
+                continue;
+            }
+            payload.extend(self.ns_payload(&index).as_bytes_slice());
+            ns_table_builder.append_entry(ns_id, payload.len());
+        }
+        Self {
+            raw_payload: payload,
+            ns_table: ns_table_builder.into_ns_table(),
+        }
+    }
+
     // PRIVATE HELPERS START HERE
 
     /// Need a sync version of [`BlockPayload::from_transactions`] in order to impl [`BlockPayload::empty`].
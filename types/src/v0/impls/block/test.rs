@@ -163,6 +163,40 @@ async fn enforce_max_block_size() {
     assert_eq!(block.len(block.ns_table()), tx_count_expected - 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn restrict_namespaces() {
+    setup_test();
+    let mut rng = jf_utils::test_rng();
+    let test = ValidTest::from_tx_lengths(vec![vec![5, 8], vec![7], vec![10, 5, 8]], &mut rng);
+    let block =
+        Payload::from_transactions(test.all_txs(), &Default::default(), &Default::default())
+            .await
+            .unwrap()
+            .0;
+
+    // keep every namespace but the first
+    let dropped = *test.nss.keys().next().unwrap();
+    let restricted = block.restrict_namespaces(|ns_id| ns_id != dropped);
+    assert_eq!(restricted.ns_table().iter().count(), test.nss.len() - 1);
+    assert!(restricted.ns_table().find_ns_id(&dropped).is_none());
+    let expected = test
+        .nss
+        .iter()
+        .filter(|(ns_id, _)| **ns_id != dropped)
+        .flat_map(|(_, txs)| txs.clone())
+        .collect::<Vec<_>>();
+    let actual = restricted
+        .iter(restricted.ns_table())
+        .map(|index| restricted.transaction(&index).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(actual, expected);
+
+    // keep nothing
+    let empty = block.restrict_namespaces(|_| false);
+    assert_eq!(empty.ns_table().iter().count(), 0);
+    assert_eq!(empty.encode().len(), 0);
+}
+
 // TODO lots of infra here that could be reused in other tests.
 pub struct ValidTest {
     pub nss: BTreeMap<NamespaceId, Vec<Transaction>>,
@@ -19,12 +19,12 @@ use alloy::{
     },
     transports::{http::Http, RpcError, TransportErrorKind},
 };
-use anyhow::Context;
+use anyhow::{bail, ensure, Context};
 use async_trait::async_trait;
 use clap::Parser;
 use committable::{Commitment, Committable, RawCommitmentBuilder};
 use futures::{
-    future::{Future, TryFuture, TryFutureExt},
+    future::{join_all, Future, TryFuture, TryFutureExt},
     stream::{self, StreamExt},
 };
 use hotshot_contract_adapter::sol_types::FeeContract;
@@ -34,17 +34,23 @@ use parking_lot::RwLock;
 use tokio::{
     spawn,
     sync::{Mutex, MutexGuard, Notify},
-    time::{sleep, Duration},
+    time::{sleep, timeout, Duration},
 };
 use tower_service::Service;
 use tracing::Instrument;
 use url::Url;
 
 use super::{
-    v0_1::{L1BlockInfoWithParent, SingleTransport, SingleTransportStatus, SwitchingTransport},
-    L1BlockInfo, L1ClientMetrics, L1State, L1UpdateTask,
+    v0_1::{
+        L1BlockInfoWithParent, L1Provider, SingleTransport, SingleTransportStatus,
+        SwitchingTransport,
+    },
+    L1BlockInfo, L1ClientMetrics, L1Quorum, L1State, L1UpdateTask,
+};
+use crate::{
+    traits::L1Persistence, FeeInfo, L1Client, L1ClientOptions, L1Event, L1QuorumFailurePolicy,
+    L1Snapshot,
 };
-use crate::{FeeInfo, L1Client, L1ClientOptions, L1Event, L1Snapshot};
 
 impl PartialOrd for L1BlockInfo {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -156,6 +162,13 @@ impl L1ClientMetrics {
             failure_metrics.push(failures.create(vec![url_index.to_string()]));
         }
 
+        // Count, for each URL, how often it disagrees with the quorum in quorum-read mode
+        let disagreements =
+            metrics.counter_family("quorum_disagreements".into(), vec!["provider".into()]);
+        let disagreement_metrics = (0..num_urls)
+            .map(|url_index| disagreements.create(vec![url_index.to_string()]))
+            .collect();
+
         Self {
             head: metrics.create_gauge("head".into(), None).into(),
             finalized: metrics.create_gauge("finalized".into(), None).into(),
@@ -164,6 +177,13 @@ impl L1ClientMetrics {
                 .into(),
             failovers: metrics.create_counter("failovers".into(), None).into(),
             failures: Arc::new(failure_metrics),
+            quorum_failures: metrics
+                .create_counter("quorum_failures".into(), None)
+                .into(),
+            quorum_fallbacks: metrics
+                .create_counter("quorum_fallbacks".into(), None)
+                .into(),
+            quorum_disagreements: Arc::new(disagreement_metrics),
         }
     }
 }
@@ -175,6 +195,13 @@ impl SwitchingTransport {
         let Some(first_url) = urls.first().cloned() else {
             return Err(anyhow::anyhow!("No valid URLs provided"));
         };
+        if let Some(threshold) = opt.l1_quorum_threshold {
+            ensure!(
+                threshold > 0 && threshold <= urls.len(),
+                "L1 quorum threshold {threshold} must be between 1 and the number of L1 providers ({})",
+                urls.len()
+            );
+        }
 
         // Create the metrics
         let metrics = L1ClientMetrics::new(&**opt.metrics, urls.len());
@@ -431,6 +458,22 @@ impl L1Client {
         receiver.set_await_active(false);
         receiver.set_overflow(true);
 
+        // In quorum-read mode, keep a separate provider for each URL, so reads can be sent to all
+        // of them independently of the switching transport.
+        let quorum = opt.l1_quorum_threshold.map(|threshold| {
+            Arc::new(L1Quorum {
+                providers: transport
+                    .urls
+                    .iter()
+                    .map(|url| ProviderBuilder::new().on_http(url.clone()))
+                    .collect(),
+                threshold,
+                policy: opt.l1_quorum_failure_policy,
+                timeout: opt.l1_quorum_timeout,
+                metrics: transport.metrics().clone(),
+            })
+        });
+
         Self {
             provider,
             transport,
@@ -438,6 +481,8 @@ impl L1Client {
             sender,
             receiver: receiver.deactivate(),
             update_task: Default::default(),
+            quorum,
+            persistence: None,
         }
     }
 
@@ -455,6 +500,16 @@ impl L1Client {
         .connect(vec![anvil.endpoint().parse()?])
     }
 
+    /// Cache finalized L1 data in `persistence`.
+    ///
+    /// Finalized blocks and fee deposits are loaded from storage before falling back to the L1
+    /// providers, and stored once fetched, so they are not fetched again after a restart. This must
+    /// be called before [`spawn_tasks`](Self::spawn_tasks).
+    pub fn with_persistence(mut self, persistence: impl L1Persistence) -> Self {
+        self.persistence = Some(Arc::new(persistence));
+        self
+    }
+
     /// Start the background tasks which keep the L1 client up to date.
     pub async fn spawn_tasks(&self) {
         let mut update_task = self.update_task.0.lock().await;
@@ -484,10 +539,13 @@ impl L1Client {
         let metrics = self.metrics().clone();
         let polling_interval = opt.l1_polling_interval;
         let transport = self.transport.clone();
+        let quorum = self.quorum.clone();
+        let persistence = self.persistence.clone();
 
         let span = tracing::warn_span!("L1 client update");
 
         async move {
+            let mut last_stored = None;
 
             for i in 0.. {
                 let ws;
@@ -586,7 +644,7 @@ impl L1Client {
                             // A new block has been produced. This happens fairly rarely, so it is now ok to
                             // poll to see if a new block has been finalized.
                             let finalized = loop {
-                                match fetch_finalized_block_from_rpc(&rpc).await {
+                                match fetch_finalized_block(&rpc, quorum.as_deref()).await {
                                     Ok(finalized) => break finalized,
                                     Err(err) => {
                                         tracing::warn!("Error getting finalized block: {err:#}");
@@ -595,6 +653,15 @@ impl L1Client {
                                 }
                             };
 
+                            // Store a new finalized block before announcing it, so that anyone who
+                            // hears about it can also load it from storage.
+                            if let Some(finalized) = finalized {
+                                if Some(finalized.info.number) > last_stored {
+                                    store_finalized_block(persistence.as_deref(), finalized).await;
+                                    last_stored = Some(finalized.info.number);
+                                }
+                            }
+
                             // Update the state snapshot;
                             let mut state = state.lock().await;
                             if head > state.snapshot.head {
@@ -825,6 +892,18 @@ impl L1Client {
             state.snapshot,
         );
 
+        // Finalized blocks never change, so if this block was stored before, use it without
+        // going to the L1.
+        if self.persistence.is_some() && !state.finalized.contains(&number) {
+            drop(state);
+            let block = load_finalized_block(self.persistence.as_deref(), number).await;
+            state = self.state.lock().await;
+            if let Some(block) = block {
+                state.put_finalized(block);
+                return (state, block.info);
+            }
+        }
+
         if let Some(safety_margin) = self.options().l1_finalized_safety_margin {
             if number < latest_finalized.number.saturating_sub(safety_margin) {
                 // If the requested block height is so old that we can assume all L1 providers have
@@ -861,7 +940,7 @@ impl L1Client {
                 // Don't hold state lock while fetching from network.
                 drop(state);
                 let block = loop {
-                    match fetch_finalized_block_from_rpc(&self.provider).await {
+                    match fetch_finalized_block(&self.provider, self.quorum.as_deref()).await {
                         Ok(Some(block)) => {
                             break block;
                         },
@@ -875,6 +954,7 @@ impl L1Client {
                         },
                     }
                 };
+                store_finalized_block(self.persistence.as_deref(), block).await;
                 state = self.state.lock().await;
                 state.put_finalized(block);
                 break block;
@@ -905,24 +985,24 @@ impl L1Client {
         // Don't hold state lock while fetching from network.
         drop(state);
         let block = loop {
-            let block = match self.provider.get_block(id).await {
-                Ok(Some(block)) => block,
-                Ok(None) => {
-                    tracing::warn!(
-                        %id,
-                        "provider error: finalized L1 block should always be available"
-                    );
-                    self.retry_delay().await;
-                    continue;
-                },
+            let res = self
+                .quorum_read("finalized block", |provider| async move {
+                    let block = provider
+                        .get_block(id)
+                        .await?
+                        .context("provider error: finalized L1 block should always be available")?;
+                    Ok(L1BlockInfoWithParent::from(&block))
+                })
+                .await;
+            match res {
+                Ok(block) => break block,
                 Err(err) => {
                     tracing::warn!(%id, "failed to get finalized L1 block: {err:#}");
                     self.retry_delay().await;
-                    continue;
                 },
-            };
-            break (&block).into();
+            }
         };
+        store_finalized_block(self.persistence.as_deref(), block).await;
         state = self.state.lock().await;
         state.put_finalized(block);
         (state, block)
@@ -948,6 +1028,16 @@ impl L1Client {
         // haven't processed *any* blocks yet.
         let prev = prev_finalized.map(|prev| prev + 1).unwrap_or(0);
 
+        // Deposits in finalized blocks never change, so if the whole range was scanned before,
+        // use the stored deposits.
+        if let Some(deposits) = self
+            .load_deposits(fee_contract_address, prev, new_finalized)
+            .await
+        {
+            tracing::debug!(prev, new_finalized, "loaded deposits from storage");
+            return deposits;
+        }
+
         // Divide the range `prev_finalized..=new_finalized` into chunks of size
         // `events_max_block_range`.
         let mut start = prev;
@@ -967,21 +1057,48 @@ impl L1Client {
         // Fetch events for each chunk.
         let events = stream::iter(chunks).then(|(from, to)| {
             let retry_delay = opt.l1_retry_delay;
-            let fee_contract = FeeContract::new(fee_contract_address, self.provider.clone());
             async move {
+                // Some chunks may still have been scanned before.
+                if let Some(deposits) = self.load_deposits(fee_contract_address, from, to).await {
+                    tracing::debug!(from, to, "loaded deposits from storage");
+                    return stream::iter(deposits);
+                }
                 tracing::debug!(from, to, "fetch events in range");
 
                 // query for deposit events, loop until successful.
                 loop {
-                    match fee_contract
-                        .Deposit_filter()
-                        .address(*fee_contract.address())
-                        .from_block(from)
-                        .to_block(to)
-                        .query()
-                        .await
-                    {
-                        Ok(events) => break stream::iter(events),
+                    let res = self
+                        .quorum_read("deposits", |provider| async move {
+                            let fee_contract = FeeContract::new(fee_contract_address, provider);
+                            let events = fee_contract
+                                .Deposit_filter()
+                                .address(*fee_contract.address())
+                                .from_block(from)
+                                .to_block(to)
+                                .query()
+                                .await?;
+                            events
+                                .into_iter()
+                                .map(|(deposit, log)| {
+                                    let block = log
+                                        .block_number
+                                        .context("deposit log has no block number")?;
+                                    Ok((block, FeeInfo::from(deposit)))
+                                })
+                                .collect::<anyhow::Result<Vec<_>>>()
+                        })
+                        .await;
+                    match res {
+                        Ok(events) => {
+                            self.store_deposits(fee_contract_address, from, to, &events)
+                                .await;
+                            break stream::iter(
+                                events
+                                    .into_iter()
+                                    .map(|(_, deposit)| deposit)
+                                    .collect::<Vec<_>>(),
+                            );
+                        },
                         Err(err) => {
                             tracing::warn!(from, to, %err, "Fee L1Event Error");
                             sleep(retry_delay).await;
@@ -990,11 +1107,7 @@ impl L1Client {
                 }
             }
         });
-        events
-            .flatten()
-            .map(|(deposit, _)| FeeInfo::from(deposit))
-            .collect()
-            .await
+        events.flatten().collect().await
     }
 
     /// Check if the given address is a proxy contract.
@@ -1040,10 +1153,64 @@ impl L1Client {
         }
     }
 
+    /// Perform a read which, in quorum-read mode, must be agreed on by enough providers.
+    ///
+    /// `op` performs the read against a given provider. If quorum reads are disabled, it is only
+    /// run against the current provider of the switching transport.
+    pub(crate) async fn quorum_read<T, Fut>(
+        &self,
+        what: &str,
+        op: impl Fn(L1Provider) -> Fut,
+    ) -> anyhow::Result<T>
+    where
+        T: PartialEq,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        quorum_read(&self.provider, self.quorum.as_deref(), what, op).await
+    }
+
     pub(crate) fn options(&self) -> &L1ClientOptions {
         self.transport.options()
     }
 
+    /// Load the deposits in the given range of blocks from storage, if they were stored before.
+    async fn load_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+    ) -> Option<Vec<FeeInfo>> {
+        let persistence = self.persistence.as_ref()?;
+        persistence
+            .load_l1_deposits(fee_contract, from, to)
+            .await
+            .inspect_err(|err| tracing::warn!(from, to, "failed to load L1 deposits: {err:#}"))
+            .ok()
+            .flatten()
+    }
+
+    /// Store the deposits in the given range of blocks, so they do not have to be fetched again.
+    ///
+    /// Each deposit is given along with the L1 block it was made in. Failures are only logged,
+    /// since the deposits can always be fetched from the L1 again.
+    async fn store_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+        deposits: &[(u64, FeeInfo)],
+    ) {
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        if let Err(err) = persistence
+            .store_l1_deposits(fee_contract, from, to, deposits)
+            .await
+        {
+            tracing::warn!(from, to, "failed to store L1 deposits: {err:#}");
+        }
+    }
+
     fn metrics(&self) -> &L1ClientMetrics {
         self.transport.metrics()
     }
@@ -1086,6 +1253,215 @@ impl L1State {
     }
 }
 
+impl L1Quorum {
+    /// Run `op` against every provider, in parallel.
+    async fn read_each<T, Fut>(&self, op: impl Fn(L1Provider) -> Fut) -> Vec<anyhow::Result<T>>
+    where
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        join_all(self.providers.iter().map(|provider| {
+            timeout(self.timeout, op(provider.clone()))
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out")))
+        }))
+        .await
+    }
+
+    /// Run `op` against every provider, and return the response returned by at least `threshold`
+    /// of them.
+    async fn read<T, Fut>(&self, what: &str, op: impl Fn(L1Provider) -> Fut) -> anyhow::Result<T>
+    where
+        T: PartialEq,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let responses = self.read_each(op).await;
+        self.agree(what, responses)
+    }
+
+    /// Find the response, out of one response per provider, which enough providers agree on.
+    fn agree<T: PartialEq>(
+        &self,
+        what: &str,
+        responses: Vec<anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        // Group equal responses, along with the indices of the providers which returned them.
+        let mut groups: Vec<(T, Vec<usize>)> = vec![];
+        for (provider, res) in responses.into_iter().enumerate() {
+            match res {
+                Ok(value) => match groups.iter_mut().find(|(other, _)| *other == value) {
+                    Some((_, providers)) => providers.push(provider),
+                    None => groups.push((value, vec![provider])),
+                },
+                Err(err) => {
+                    tracing::warn!(provider, what, "L1 provider failed quorum read: {err:#}")
+                },
+            }
+        }
+
+        // If the threshold allows it, two different responses can each have enough support. That
+        // means too many providers are faulty to trust either of them.
+        let mut quorums = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, providers))| providers.len() >= self.threshold)
+            .map(|(i, _)| i);
+        let (Some(quorum), None) = (quorums.next(), quorums.next()) else {
+            self.metrics.quorum_failures.add(1);
+            let groups = groups
+                .iter()
+                .map(|(_, providers)| providers)
+                .collect::<Vec<_>>();
+            bail!(
+                "L1 providers did not reach a quorum of {} on {what}: responses grouped by \
+                 provider {groups:?}",
+                self.threshold
+            );
+        };
+
+        let (value, agreeing) = groups.swap_remove(quorum);
+        for (_, providers) in &groups {
+            tracing::warn!(
+                what,
+                ?providers,
+                ?agreeing,
+                "L1 providers disagree with quorum"
+            );
+            for provider in providers {
+                if let Some(counter) = self.metrics.quorum_disagreements.get(*provider) {
+                    counter.add(1);
+                }
+            }
+        }
+        Ok(value)
+    }
+
+    /// Apply the failure policy to the result `res` of a quorum read.
+    ///
+    /// If the providers failed to reach a quorum and the policy is to fall back, the result of
+    /// `fallback` is returned instead.
+    async fn or_fallback<T>(
+        &self,
+        what: &str,
+        res: anyhow::Result<T>,
+        fallback: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        match (res, self.policy) {
+            (Ok(value), _) => Ok(value),
+            (Err(err), L1QuorumFailurePolicy::Halt) => Err(err),
+            (Err(err), L1QuorumFailurePolicy::Fallback) => {
+                tracing::warn!(
+                    what,
+                    "falling back to a single L1 provider after quorum failure: {err:#}"
+                );
+                self.metrics.quorum_fallbacks.add(1);
+                fallback.await
+            },
+        }
+    }
+}
+
+async fn quorum_read<T, Fut>(
+    rpc: &L1Provider,
+    quorum: Option<&L1Quorum>,
+    what: &str,
+    op: impl Fn(L1Provider) -> Fut,
+) -> anyhow::Result<T>
+where
+    T: PartialEq,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let Some(quorum) = quorum else {
+        return op(rpc.clone()).await;
+    };
+    let res = quorum.read(what, &op).await;
+    quorum.or_fallback(what, res, op(rpc.clone())).await
+}
+
+/// Fetch the latest finalized block, which a quorum of providers agree on in quorum-read mode.
+async fn fetch_finalized_block(
+    rpc: &L1Provider,
+    quorum: Option<&L1Quorum>,
+) -> anyhow::Result<Option<L1BlockInfoWithParent>> {
+    let Some(quorum) = quorum else {
+        return fetch_finalized_block_from_rpc(rpc).await;
+    };
+    let res = fetch_finalized_block_by_quorum(quorum).await;
+    quorum
+        .or_fallback("finalized block", res, fetch_finalized_block_from_rpc(rpc))
+        .await
+}
+
+async fn fetch_finalized_block_by_quorum(
+    quorum: &L1Quorum,
+) -> anyhow::Result<Option<L1BlockInfoWithParent>> {
+    // Providers may lag each other slightly in finalizing new blocks, so they will often not agree
+    // on the very latest finalized block. Instead, find the highest block which at least
+    // `threshold` providers consider finalized, and then check that they agree on that block.
+    let mut numbers = quorum
+        .read_each(|provider| async move {
+            let block = fetch_finalized_block_from_rpc(&provider).await?;
+            Ok(block.map(|block| block.info.number))
+        })
+        .await
+        .into_iter()
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    numbers.sort_unstable_by(|a, b| b.cmp(a));
+    let Some(number) = numbers.get(quorum.threshold - 1) else {
+        quorum.metrics.quorum_failures.add(1);
+        bail!(
+            "only {} L1 providers returned a finalized block, need {}",
+            numbers.len(),
+            quorum.threshold
+        );
+    };
+    let Some(number) = *number else {
+        tracing::warn!("no finalized block yet");
+        return Ok(None);
+    };
+
+    let block = quorum
+        .read("finalized block", |provider| async move {
+            let block = provider
+                .get_block(BlockId::number(number))
+                .await?
+                .context(format!("finalized block {number} not available"))?;
+            Ok(L1BlockInfoWithParent::from(&block))
+        })
+        .await?;
+    Ok(Some(block))
+}
+
+/// Load a finalized block from storage, if it was stored before.
+async fn load_finalized_block(
+    persistence: Option<&dyn L1Persistence>,
+    number: u64,
+) -> Option<L1BlockInfoWithParent> {
+    let (info, parent_hash) = persistence?
+        .load_finalized_l1_block(number)
+        .await
+        .inspect_err(|err| tracing::warn!(number, "failed to load finalized L1 block: {err:#}"))
+        .ok()??;
+    Some(L1BlockInfoWithParent { info, parent_hash })
+}
+
+/// Store a finalized block, so it does not have to be fetched again.
+///
+/// Failures are only logged, since the block can always be fetched from the L1 again.
+async fn store_finalized_block(
+    persistence: Option<&dyn L1Persistence>,
+    block: L1BlockInfoWithParent,
+) {
+    let Some(persistence) = persistence else {
+        return;
+    };
+    if let Err(err) = persistence
+        .store_finalized_l1_block(block.info, block.parent_hash)
+        .await
+    {
+        tracing::warn!(?block, "failed to store finalized L1 block: {err:#}");
+    }
+}
+
 async fn fetch_finalized_block_from_rpc(
     rpc: &impl Provider,
 ) -> anyhow::Result<Option<L1BlockInfoWithParent>> {
@@ -1103,7 +1479,12 @@ async fn fetch_finalized_block_from_rpc(
 
 #[cfg(test)]
 mod test {
-    use std::{ops::Add, time::Duration};
+    use std::{
+        cmp::max,
+        collections::{BTreeMap, HashMap},
+        ops::Add,
+        time::Duration,
+    };
 
     use alloy::{
         eips::BlockNumberOrTag,
@@ -1112,11 +1493,13 @@ mod test {
         providers::layers::AnvilProvider,
     };
     use espresso_contract_deployer::{deploy_fee_contract_proxy, Contracts};
+    use hotshot_types::traits::metrics::NoMetrics;
     use portpicker::pick_unused_port;
     use sequencer_utils::test_utils::setup_test;
     use time::OffsetDateTime;
 
     use super::*;
+    use crate::traits::merge_scanned_l1_range;
 
     async fn new_l1_client_opt(
         anvil: &Arc<AnvilInstance>,
@@ -1138,6 +1521,89 @@ mod test {
         l1_client
     }
 
+    /// In-memory storage for finalized L1 data.
+    #[derive(Clone, Debug, Default)]
+    struct MemoryL1Persistence {
+        blocks: Arc<parking_lot::Mutex<HashMap<u64, (L1BlockInfo, B256)>>>,
+        deposits: Arc<parking_lot::Mutex<BTreeMap<(Address, u64), Vec<FeeInfo>>>>,
+        scanned: Arc<parking_lot::Mutex<HashMap<Address, (u64, u64)>>>,
+    }
+
+    #[async_trait]
+    impl L1Persistence for MemoryL1Persistence {
+        async fn load_finalized_l1_block(
+            &self,
+            number: u64,
+        ) -> anyhow::Result<Option<(L1BlockInfo, B256)>> {
+            Ok(self.blocks.lock().get(&number).copied())
+        }
+
+        async fn store_finalized_l1_block(
+            &self,
+            block: L1BlockInfo,
+            parent_hash: B256,
+        ) -> anyhow::Result<()> {
+            self.blocks
+                .lock()
+                .insert(block.number, (block, parent_hash));
+            Ok(())
+        }
+
+        async fn load_l1_deposits(
+            &self,
+            fee_contract: Address,
+            from: u64,
+            to: u64,
+        ) -> anyhow::Result<Option<Vec<FeeInfo>>> {
+            match self.scanned.lock().get(&fee_contract) {
+                Some(&(scanned_from, scanned_to)) if scanned_from <= from && to <= scanned_to => {},
+                _ => return Ok(None),
+            }
+            Ok(Some(
+                self.deposits
+                    .lock()
+                    .range((fee_contract, from)..=(fee_contract, to))
+                    .flat_map(|(_, deposits)| deposits.clone())
+                    .collect(),
+            ))
+        }
+
+        async fn store_l1_deposits(
+            &self,
+            fee_contract: Address,
+            from: u64,
+            to: u64,
+            deposits: &[(u64, FeeInfo)],
+        ) -> anyhow::Result<()> {
+            let mut stored = self.deposits.lock();
+            for block in from..=to {
+                stored.remove(&(fee_contract, block));
+            }
+            for (block, deposit) in deposits {
+                stored
+                    .entry((fee_contract, *block))
+                    .or_default()
+                    .push(*deposit);
+            }
+            let mut scanned = self.scanned.lock();
+            let range = merge_scanned_l1_range(scanned.get(&fee_contract).copied(), from, to);
+            scanned.insert(fee_contract, range);
+            Ok(())
+        }
+
+        async fn prune_l1_cache(&self, height: u64) -> anyhow::Result<()> {
+            self.blocks.lock().retain(|number, _| *number >= height);
+            self.deposits
+                .lock()
+                .retain(|(_, block), _| *block >= height);
+            self.scanned.lock().retain(|_, (from, to)| {
+                *from = max(*from, height);
+                *to >= height
+            });
+            Ok(())
+        }
+    }
+
     async fn new_l1_client(anvil: &Arc<AnvilInstance>, include_ws: bool) -> L1Client {
         new_l1_client_opt(anvil, |opt| {
             if include_ws {
@@ -1689,6 +2155,106 @@ mod test {
         provider.get_block_number().await.unwrap_err();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_l1_persistence() -> anyhow::Result<()> {
+        setup_test();
+
+        let anvil = Anvil::new().block_time_f64(0.1).spawn();
+        let wallet = anvil.wallet().unwrap();
+        let deployer = wallet.default_signer().address();
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .on_http(anvil.endpoint_url());
+        let mut contracts = Contracts::new();
+
+        let fee_proxy_addr = deploy_fee_contract_proxy(&provider, &mut contracts, deployer).await?;
+        let receipt = FeeContract::new(fee_proxy_addr, &provider)
+            .deposit(deployer)
+            .value(parse_ether("1")?)
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+        assert!(receipt.inner.is_success());
+        let height = provider.get_block_number().await?;
+
+        // Pretend we have already stored block 1, with a hash which the L1 would never return.
+        let persistence = MemoryL1Persistence::default();
+        let stored = L1BlockInfo {
+            number: 1,
+            hash: B256::repeat_byte(1),
+            ..Default::default()
+        };
+        persistence
+            .store_finalized_l1_block(stored, B256::ZERO)
+            .await?;
+
+        let opt = L1ClientOptions {
+            l1_events_max_block_range: 1,
+            l1_polling_interval: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let l1_client = opt
+            .clone()
+            .connect(vec![anvil.endpoint_url()])?
+            .with_persistence(persistence.clone());
+        l1_client.spawn_tasks().await;
+
+        // Finalized blocks are loaded from storage if possible, and stored once fetched.
+        assert_eq!(l1_client.wait_for_finalized_block(1).await, stored);
+        let block = l1_client.wait_for_finalized_block(height).await;
+        assert_eq!(persistence.blocks.lock()[&height].0, block);
+
+        // Deposits are stored once fetched.
+        let deposits = l1_client
+            .get_finalized_deposits(fee_proxy_addr, None, height)
+            .await;
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(persistence.deposits.lock().len(), 1);
+        assert_eq!(persistence.scanned.lock()[&fee_proxy_addr], (0, height));
+
+        // Stored deposits can be loaded even when no L1 provider is reachable, no matter how the
+        // range is divided into chunks.
+        let offline = L1ClientOptions {
+            l1_events_max_block_range: 2,
+            ..opt
+        }
+        .connect(vec!["http://notarealurl:1234".parse().unwrap()])?
+        .with_persistence(persistence);
+        let cached = timeout(
+            Duration::from_secs(5),
+            offline.get_finalized_deposits(fee_proxy_addr, None, height),
+        )
+        .await?;
+        assert_eq!(cached, deposits);
+        let cached = timeout(
+            Duration::from_secs(5),
+            offline.get_finalized_deposits(fee_proxy_addr, Some(0), height),
+        )
+        .await?;
+        assert_eq!(cached, deposits);
+
+        // Pruning forgets everything below the given height.
+        persistence.prune_l1_cache(height).await?;
+        assert!(persistence
+            .blocks
+            .lock()
+            .keys()
+            .all(|number| *number >= height));
+        assert_eq!(
+            persistence.scanned.lock()[&fee_proxy_addr],
+            (height, height)
+        );
+        assert_eq!(
+            persistence
+                .load_l1_deposits(fee_proxy_addr, 0, height)
+                .await?,
+            None
+        );
+
+        Ok(())
+    }
+
     // Checks that the L1 client initialized the state on startup even
     // if the L1 is not currently mining blocks. It's useful for testing that we
     // don't require an L1 that is continuously mining blocks.
@@ -1710,4 +2276,121 @@ mod test {
         }
         panic!("L1 state of L1Client not initialized");
     }
+
+    #[test]
+    fn test_quorum_threshold_out_of_range() {
+        let url: Url = "http://localhost:1234".parse().unwrap();
+        for threshold in [0, 3] {
+            let opt = L1ClientOptions {
+                l1_quorum_threshold: Some(threshold),
+                ..Default::default()
+            };
+            opt.connect(vec![url.clone(), url.clone()]).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_quorum_agree() {
+        let quorum = L1Quorum {
+            providers: vec![],
+            threshold: 2,
+            policy: L1QuorumFailurePolicy::Halt,
+            timeout: Duration::from_secs(1),
+            metrics: L1ClientMetrics::new(&NoMetrics, 3),
+        };
+
+        // A response wins if enough providers agree on it, even if others disagree or fail.
+        assert_eq!(quorum.agree("test", vec![Ok(1), Ok(1), Ok(2)]).unwrap(), 1);
+        assert_eq!(
+            quorum
+                .agree("test", vec![Ok(1), Err(anyhow::anyhow!("error")), Ok(1)])
+                .unwrap(),
+            1
+        );
+
+        // Without enough agreeing providers, the read fails.
+        quorum.agree("test", vec![Ok(1), Ok(2), Ok(3)]).unwrap_err();
+        quorum
+            .agree("test", vec![Ok(1), Err(anyhow::anyhow!("error")), Ok(2)])
+            .unwrap_err();
+
+        // If two different responses both reach the threshold, neither can be trusted.
+        let quorum = L1Quorum {
+            threshold: 1,
+            ..quorum
+        };
+        quorum.agree("test", vec![Ok(1), Ok(1), Ok(2)]).unwrap_err();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quorum_read() -> anyhow::Result<()> {
+        setup_test();
+
+        let anvil = Anvil::new().block_time(1).spawn();
+        let wallet = anvil.wallet().unwrap();
+        let deployer = wallet.default_signer().address();
+        let provider = ProviderBuilder::new()
+            .wallet(wallet)
+            .on_http(anvil.endpoint_url());
+        let mut contracts = Contracts::new();
+
+        // Deposit into the fee contract, so there are events for the providers to agree on.
+        let fee_proxy_addr = deploy_fee_contract_proxy(&provider, &mut contracts, deployer).await?;
+        let receipt = FeeContract::new(fee_proxy_addr, &provider)
+            .deposit(deployer)
+            .value(parse_ether("1")?)
+            .send()
+            .await?
+            .get_receipt()
+            .await?;
+        assert!(receipt.inner.is_success());
+        let height = provider.get_block_number().await?;
+
+        // Two providers which agree.
+        let l1_client = L1ClientOptions {
+            l1_polling_interval: Duration::from_secs(1),
+            l1_quorum_threshold: Some(2),
+            ..Default::default()
+        }
+        .connect(vec![anvil.endpoint_url(), anvil.endpoint_url()])?;
+        l1_client.spawn_tasks().await;
+
+        let block = l1_client.wait_for_finalized_block(height).await;
+        assert_eq!(block.number, height);
+        let deposits = l1_client
+            .get_finalized_deposits(fee_proxy_addr, None, height)
+            .await;
+        assert_eq!(deposits.len(), 1);
+        assert_eq!(deposits[0].account().0, deployer);
+
+        // A quorum cannot be reached if one of the providers is unavailable.
+        let urls = vec![
+            anvil.endpoint_url(),
+            "http://notarealurl:1234".parse().unwrap(),
+        ];
+        let opt = L1ClientOptions {
+            l1_quorum_threshold: Some(2),
+            l1_quorum_timeout: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let read_height = |client: L1Client| async move {
+            client
+                .quorum_read("block number", |provider| async move {
+                    Ok(provider.get_block_number().await?)
+                })
+                .await
+        };
+        read_height(opt.clone().connect(urls.clone())?)
+            .await
+            .unwrap_err();
+
+        // Unless the policy allows falling back to a single provider.
+        let opt = L1ClientOptions {
+            l1_quorum_failure_policy: L1QuorumFailurePolicy::Fallback,
+            ..opt
+        };
+        read_height(opt.connect(urls)?).await.unwrap();
+
+        Ok(())
+    }
 }
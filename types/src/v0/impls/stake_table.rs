@@ -7,12 +7,12 @@ use std::{
 
 use alloy::{
     primitives::{Address, U256},
+    providers::Provider,
     rpc::types::Log,
 };
 use anyhow::{bail, Context};
 use async_lock::{Mutex, RwLock};
 use committable::Committable;
-use futures::stream::{self, StreamExt};
 use hotshot::types::{BLSPubKey, SchnorrPubKey, SignatureKey as _};
 use hotshot_contract_adapter::sol_types::StakeTable::{
     self, ConsensusKeysUpdated, Delegated, Undelegated, ValidatorExit, ValidatorRegistered,
@@ -23,7 +23,7 @@ use hotshot_types::{
         election::{generate_stake_cdf, select_randomized_leader, RandomizedCommittee},
         DrbResult,
     },
-    stake_table::StakeTableEntry,
+    stake_table::{self, StakeTableEntry},
     traits::{
         election::Membership,
         node_implementation::{ConsensusTime, NodeType},
@@ -47,7 +47,7 @@ use super::{
 
 type Epoch = <SeqTypes as NodeType>::Epoch;
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Default, PartialEq)]
 pub struct StakeTableEvents {
     registrations: Vec<(ValidatorRegistered, Log)>,
     deregistrations: Vec<(ValidatorExit, Log)>,
@@ -57,6 +57,15 @@ pub struct StakeTableEvents {
 }
 
 impl StakeTableEvents {
+    /// Append the events in `other`, which must come from a later L1 block range.
+    fn extend(&mut self, other: Self) {
+        self.registrations.extend(other.registrations);
+        self.deregistrations.extend(other.deregistrations);
+        self.delegated.extend(other.delegated);
+        self.undelegated.extend(other.undelegated);
+        self.keys.extend(other.keys);
+    }
+
     pub fn sort_events(self) -> anyhow::Result<Vec<(EventKey, StakeTableEvent)>> {
         let mut events: Vec<(EventKey, StakeTableEvent)> = Vec::new();
         let Self {
@@ -438,10 +447,42 @@ impl StakeTableFetcher {
         contract: Address,
         to_block: u64,
     ) -> anyhow::Result<Vec<(EventKey, StakeTableEvent)>> {
+        let (events, _) = self.load_or_fetch_events(contract, to_block).await?;
+        Ok(events)
+    }
+
+    /// Get all stake table events up to `to_block`, only querying the L1 for events which are not
+    /// already in storage.
+    ///
+    /// Also returns whether any events were fetched from the L1, in which case the storage should
+    /// be updated.
+    async fn load_or_fetch_events(
+        &self,
+        contract: Address,
+        to_block: u64,
+    ) -> anyhow::Result<(Vec<(EventKey, StakeTableEvent)>, bool)> {
         let persistence_lock = self.persistence.lock().await;
         let res = persistence_lock.load_events().await?;
         drop(persistence_lock);
 
+        // Events in finalized blocks never change, so if storage already covers `to_block` (e.g.
+        // when replaying an old epoch during catchup), there is nothing to fetch from the L1.
+        if let Some((block, events)) = &res {
+            if *block >= to_block {
+                tracing::info!(
+                    block,
+                    to_block,
+                    "loaded events from storage without querying the L1"
+                );
+                let events = events
+                    .iter()
+                    .filter(|((event_block, _), _)| *event_block <= to_block)
+                    .cloned()
+                    .collect();
+                return Ok((events, false));
+            }
+        }
+
         let from_block = res
             .as_ref()
             .map(|(block, _)| block + 1)
@@ -479,7 +520,7 @@ impl StakeTableFetcher {
             tracing::warn!("Duplicate events found and removed. This should not normally happen.")
         }
 
-        Ok(events)
+        Ok((events, true))
     }
 
     /// Fetch all stake table events from L1
@@ -489,21 +530,26 @@ impl StakeTableFetcher {
         from_block: Option<u64>,
         to_block: u64,
     ) -> anyhow::Result<StakeTableEvents> {
-        let stake_table_contract = StakeTable::new(contract, l1_client.provider.clone());
-
         // get the block number when the contract was initialized
         // to avoid fetching events from block number 0
         let from_block = match from_block {
             Some(block) => block,
             None => {
                 loop {
-                    match stake_table_contract.initializedAtBlock().call().await {
-                        Ok(init_block) => {
-                            break init_block._0.to::<u64>();
-                        },
+                    let res = l1_client
+                        .quorum_read("stake table initialization block", |provider| async move {
+                            let init_block = StakeTable::new(contract, provider)
+                                .initializedAtBlock()
+                                .call()
+                                .await?;
+                            Ok(init_block._0.to::<u64>())
+                        })
+                        .await;
+                    match res {
+                        Ok(init_block) => break init_block,
                         Err(err) => {
                             // Retry fetching incase of an error
-                            tracing::warn!(%err, "Failed to retrieve initial block, retrying..");
+                            tracing::warn!("Failed to retrieve initial block, retrying: {err:#}");
                             sleep(l1_client.options().l1_retry_delay).await;
                         },
                     }
@@ -528,132 +574,77 @@ impl StakeTableFetcher {
             Some(chunk)
         });
 
-        // fetch registered events
-        // retry if the call to the provider to fetch the events fails
-        let registered_events = stream::iter(chunks.clone()).then(|(from, to)| {
-            let retry_delay = l1_client.options().l1_retry_delay;
-            let stake_table_contract = stake_table_contract.clone();
-            async move {
-                tracing::debug!(from, to, "fetch ValidatorRegistered events in range");
-                loop {
-                    match stake_table_contract
-                        .clone()
-                        .ValidatorRegistered_filter()
-                        .from_block(from)
-                        .to_block(to)
-                        .query()
-                        .await
-                    {
-                        Ok(events) => break stream::iter(events),
-                        Err(err) => {
-                            tracing::warn!(from, to, %err, "ValidatorRegistered Error");
-                            sleep(retry_delay).await;
-                        },
-                    }
-                }
-            }
-        });
-
-        // fetch validator de registration events
-        let deregistered_events = stream::iter(chunks.clone()).then(|(from, to)| {
-            let retry_delay = l1_client.options().l1_retry_delay;
-            let stake_table_contract = stake_table_contract.clone();
-            async move {
-                tracing::debug!(from, to, "fetch ValidatorExit events in range");
-                loop {
-                    match stake_table_contract
-                        .ValidatorExit_filter()
-                        .from_block(from)
-                        .to_block(to)
-                        .query()
-                        .await
-                    {
-                        Ok(events) => break stream::iter(events),
-                        Err(err) => {
-                            tracing::warn!(from, to, %err, "ValidatorExit Error");
-                            sleep(retry_delay).await;
-                        },
-                    }
-                }
-            }
-        });
-
-        // fetch delegated events
-        let delegated_events = stream::iter(chunks.clone()).then(|(from, to)| {
-            let retry_delay = l1_client.options().l1_retry_delay;
-            let stake_table_contract = stake_table_contract.clone();
-            async move {
-                tracing::debug!(from, to, "fetch Delegated events in range");
-                loop {
-                    match stake_table_contract
-                        .Delegated_filter()
-                        .from_block(from)
-                        .to_block(to)
-                        .query()
-                        .await
-                    {
-                        Ok(events) => break stream::iter(events),
-                        Err(err) => {
-                            tracing::warn!(from, to, %err, "Delegated Error");
-                            sleep(retry_delay).await;
-                        },
-                    }
-                }
-            }
-        });
-        // fetch undelegated events
-        let undelegated_events = stream::iter(chunks.clone()).then(|(from, to)| {
-            let retry_delay = l1_client.options().l1_retry_delay;
-            let stake_table_contract = stake_table_contract.clone();
-            async move {
-                tracing::debug!(from, to, "fetch Undelegated events in range");
-                loop {
-                    match stake_table_contract
-                        .Undelegated_filter()
-                        .from_block(from)
-                        .to_block(to)
-                        .query()
-                        .await
-                    {
-                        Ok(events) => break stream::iter(events),
-                        Err(err) => {
-                            tracing::warn!(from, to, %err, "Undelegated Error");
-                            sleep(retry_delay).await;
-                        },
-                    }
+        // fetch all events in each chunk
+        // retry if the call to the provider to fetch the events fails, or if the providers do not
+        // agree on the events in quorum-read mode
+        let mut events = StakeTableEvents::default();
+        for (from, to) in chunks {
+            tracing::debug!(from, to, "fetch stake table events in range");
+            loop {
+                let res = l1_client
+                    .quorum_read("stake table events", |provider| {
+                        Self::fetch_events_in_range(provider, contract, from, to)
+                    })
+                    .await;
+                match res {
+                    Ok(chunk) => {
+                        events.extend(chunk);
+                        break;
+                    },
+                    Err(err) => {
+                        tracing::warn!(from, to, "Stake table events error: {err:#}");
+                        sleep(l1_client.options().l1_retry_delay).await;
+                    },
                 }
             }
-        });
+        }
 
-        // fetch consensus keys updated events
-        let keys_update_events = stream::iter(chunks).then(|(from, to)| {
-            let retry_delay = l1_client.options().l1_retry_delay;
-            let stake_table_contract = stake_table_contract.clone();
-            async move {
-                tracing::debug!(from, to, "fetch ConsensusKeysUpdated events in range");
-                loop {
-                    match stake_table_contract
-                        .ConsensusKeysUpdated_filter()
-                        .from_block(from)
-                        .to_block(to)
-                        .query()
-                        .await
-                    {
-                        Ok(events) => break stream::iter(events),
-                        Err(err) => {
-                            tracing::warn!(from, to, %err, "ConsensusKeysUpdated Error");
-                            sleep(retry_delay).await;
-                        },
-                    }
-                }
-            }
-        });
+        Ok(events)
+    }
 
-        let registrations = registered_events.flatten().collect().await;
-        let deregistrations = deregistered_events.flatten().collect().await;
-        let delegated = delegated_events.flatten().collect().await;
-        let undelegated = undelegated_events.flatten().collect().await;
-        let keys = keys_update_events.flatten().collect().await;
+    /// Fetch the stake table events in the L1 block range `[from, to]` from a single provider.
+    async fn fetch_events_in_range<P: Provider>(
+        provider: P,
+        contract: Address,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<StakeTableEvents> {
+        let stake_table_contract = StakeTable::new(contract, provider);
+        let registrations = stake_table_contract
+            .ValidatorRegistered_filter()
+            .from_block(from)
+            .to_block(to)
+            .query()
+            .await
+            .context("fetching ValidatorRegistered events")?;
+        let deregistrations = stake_table_contract
+            .ValidatorExit_filter()
+            .from_block(from)
+            .to_block(to)
+            .query()
+            .await
+            .context("fetching ValidatorExit events")?;
+        let delegated = stake_table_contract
+            .Delegated_filter()
+            .from_block(from)
+            .to_block(to)
+            .query()
+            .await
+            .context("fetching Delegated events")?;
+        let undelegated = stake_table_contract
+            .Undelegated_filter()
+            .from_block(from)
+            .to_block(to)
+            .query()
+            .await
+            .context("fetching Undelegated events")?;
+        let keys = stake_table_contract
+            .ConsensusKeysUpdated_filter()
+            .from_block(from)
+            .to_block(to)
+            .query()
+            .await
+            .context("fetching ConsensusKeysUpdated events")?;
 
         Ok(StakeTableEvents {
             registrations,
@@ -674,7 +665,12 @@ impl StakeTableFetcher {
         contract: Address,
         to_block: u64,
     ) -> anyhow::Result<Vec<(EventKey, StakeTableEvent)>> {
-        let events = self.fetch_events(contract, to_block).await?;
+        let (events, fetched) = self.load_or_fetch_events(contract, to_block).await?;
+        if !fetched {
+            // Storage already covers `to_block`; storing these events would only throw away the
+            // later ones.
+            return Ok(events);
+        }
 
         tracing::info!("storing events in storage to_block={to_block:?}");
 
@@ -1154,29 +1150,12 @@ impl Membership<SeqTypes> for EpochCommittees {
 
     /// Get the voting success threshold for the committee
     fn success_threshold(&self, epoch: Option<Epoch>) -> U256 {
-        let total_stake = self.total_stake(epoch);
-        let one = U256::ONE;
-        let two = U256::from(2);
-        let three = U256::from(3);
-        if total_stake < U256::MAX / two {
-            ((total_stake * two) / three) + one
-        } else {
-            ((total_stake / three) * two) + two
-        }
+        stake_table::success_threshold(self.total_stake(epoch))
     }
 
     /// Get the voting success threshold for the committee
     fn da_success_threshold(&self, epoch: Option<Epoch>) -> U256 {
-        let total_stake = self.total_da_stake(epoch);
-        let one = U256::ONE;
-        let two = U256::from(2);
-        let three = U256::from(3);
-
-        if total_stake < U256::MAX / two {
-            ((total_stake * two) / three) + one
-        } else {
-            ((total_stake / three) * two) + two
-        }
+        stake_table::success_threshold(self.total_da_stake(epoch))
     }
 
     /// Get the voting failure threshold for the committee
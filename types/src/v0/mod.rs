@@ -14,7 +14,11 @@ pub mod config;
 mod header;
 mod impls;
 mod nsproof;
+mod signed_action;
+mod snapshot;
+mod staking;
 pub mod traits;
+mod tx_status;
 mod utils;
 pub use header::Header;
 #[cfg(any(test, feature = "testing"))]
@@ -24,6 +28,10 @@ pub use impls::{
     EpochCommittees, FeeError, ProposalValidationError, StateValidationError,
 };
 pub use nsproof::*;
+pub use signed_action::*;
+pub use snapshot::*;
+pub use staking::*;
+pub use tx_status::*;
 pub use utils::*;
 use vbs::version::{StaticVersion, StaticVersionType};
 
@@ -90,6 +98,7 @@ reexport_unchanged_types!(
     L1BlockInfo,
     L1Client,
     L1ClientOptions,
+    L1QuorumFailurePolicy,
     L1Snapshot,
     NamespaceId,
     NsIndex,
@@ -124,7 +133,7 @@ reexport_unchanged_types!(
     BlockSize,
 );
 
-pub(crate) use v0_3::{L1ClientMetrics, L1Event, L1State, L1UpdateTask};
+pub(crate) use v0_3::{L1ClientMetrics, L1Event, L1Quorum, L1State, L1UpdateTask};
 
 #[derive(
     Clone, Copy, Debug, Default, Hash, Eq, PartialEq, PartialOrd, Ord, Deserialize, Serialize,
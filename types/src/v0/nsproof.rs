@@ -1,9 +1,10 @@
+use anyhow::{ensure, Context};
 use hotshot_query_service::VidCommon;
 use hotshot_types::data::VidCommitment;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    v0::{NamespaceId, NsIndex, NsPayload, NsTable, Payload, Transaction},
+    v0::{Header, NamespaceId, NsIndex, NsPayload, NsTable, Payload, Transaction},
     v0_1::ADVZNsProof,
     v0_3::AvidMNsProof,
 };
@@ -14,6 +15,59 @@ pub struct NamespaceProofQueryData {
     pub transactions: Vec<Transaction>,
 }
 
+/// The transactions of a set of namespaces in one block, with proofs.
+///
+/// This contains everything needed to check that the transactions are exactly the contents of
+/// their namespaces in the block with header `header`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockNamespacesQueryData {
+    pub header: Header,
+    pub vid_common: VidCommon,
+    /// Each requested namespace, with its transactions and proof.
+    ///
+    /// If a namespace is not present in the block, it has no proof and no transactions.
+    pub namespaces: Vec<(NamespaceId, NamespaceProofQueryData)>,
+}
+
+impl BlockNamespacesQueryData {
+    /// Check the proof for each namespace, returning the verified transactions.
+    ///
+    /// For a namespace with no proof, this checks that the namespace is absent from the block's
+    /// namespace table.
+    pub fn verify(&self) -> anyhow::Result<Vec<(NamespaceId, Vec<Transaction>)>> {
+        let ns_table = self.header.ns_table();
+        let commit = self.header.payload_commitment();
+        self.namespaces
+            .iter()
+            .map(|(ns_id, data)| {
+                let Some(proof) = &data.proof else {
+                    ensure!(
+                        ns_table.find_ns_id(ns_id).is_none(),
+                        "missing proof for namespace {ns_id}, which is present in the block"
+                    );
+                    ensure!(
+                        data.transactions.is_empty(),
+                        "transactions given for absent namespace {ns_id}"
+                    );
+                    return Ok((*ns_id, vec![]));
+                };
+                let (transactions, proof_ns_id) = proof
+                    .verify(ns_table, &commit, &self.vid_common)
+                    .with_context(|| format!("invalid proof for namespace {ns_id}"))?;
+                ensure!(
+                    proof_ns_id == *ns_id,
+                    "proof is for wrong namespace: {proof_ns_id} != {ns_id}"
+                );
+                ensure!(
+                    transactions == data.transactions,
+                    "transactions for namespace {ns_id} do not match proof"
+                );
+                Ok((*ns_id, transactions))
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ADVZNamespaceProofQueryData {
     pub proof: Option<ADVZNsProof>,
@@ -0,0 +1,153 @@
+use alloy::primitives::B256;
+use anyhow::ensure;
+use hotshot_types::{
+    data::{EpochNumber, ViewNumber},
+    traits::signer::SignedMessageKind,
+};
+use serde::{Deserialize, Serialize};
+
+/// A record of a message this node signed and sent.
+///
+/// These records are kept in consensus storage, or by a remote signer, so that even after a
+/// restart the node never signs two conflicting messages for the same view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SignedActionRecord {
+    /// The kind of message which was signed.
+    pub kind: SignedMessageKind,
+    /// The view the message was signed for.
+    pub view: ViewNumber,
+    /// The epoch the message was signed in, if epochs were enabled.
+    pub epoch: Option<EpochNumber>,
+    /// Commitment to the signed content of the message.
+    pub commitment: B256,
+}
+
+impl SignedActionRecord {
+    /// Whether at most one message of this kind may be signed in each view.
+    pub fn is_unique_per_view(&self) -> bool {
+        self.kind.is_unique_per_view()
+    }
+
+    /// Whether signing `self` would be a double sign, given that `other` was already signed.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        self.is_unique_per_view()
+            && self.kind == other.kind
+            && self.view == other.view
+            && self.commitment != other.commitment
+    }
+
+    /// Whether this action counts towards the highest view this node has voted or proposed in.
+    pub fn is_vote_or_proposal(&self) -> bool {
+        matches!(
+            self.kind,
+            SignedMessageKind::QuorumVote
+                | SignedMessageKind::TimeoutVote
+                | SignedMessageKind::ViewSyncVote
+                | SignedMessageKind::QuorumProposal
+        )
+    }
+
+    /// Fail if signing `self` would conflict with any of the `existing` records.
+    pub fn check_conflicts<'a>(
+        &self,
+        existing: impl IntoIterator<Item = &'a Self>,
+    ) -> anyhow::Result<()> {
+        for other in existing {
+            ensure!(
+                !self.conflicts_with(other),
+                "refusing to sign {:?} for view {} with commitment {}: conflicts with previously \
+                 signed commitment {}",
+                self.kind,
+                self.view,
+                self.commitment,
+                other.commitment,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Format for moving a node's signing history between machines.
+///
+/// Before a validator is started on a new machine, the history exported from its old storage must
+/// be imported into the new storage, so the node does not sign messages conflicting with ones it
+/// signed before the move.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashingProtectionInterchange {
+    /// Version of the interchange format.
+    pub version: u32,
+    /// The highest view this node has voted or proposed in.
+    pub latest_acted_view: Option<ViewNumber>,
+    /// Every signed message which is still retained in storage.
+    pub records: Vec<SignedActionRecord>,
+}
+
+impl SlashingProtectionInterchange {
+    /// The current version of the interchange format.
+    pub const VERSION: u32 = 1;
+
+    pub fn new(
+        latest_acted_view: Option<ViewNumber>,
+        records: impl IntoIterator<Item = SignedActionRecord>,
+    ) -> Self {
+        Self {
+            version: Self::VERSION,
+            latest_acted_view,
+            records: records.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hotshot_types::traits::node_implementation::ConsensusTime;
+
+    use super::*;
+
+    fn record(kind: SignedMessageKind, view: u64, commitment: u8) -> SignedActionRecord {
+        SignedActionRecord {
+            kind,
+            view: ViewNumber::new(view),
+            epoch: None,
+            commitment: B256::repeat_byte(commitment),
+        }
+    }
+
+    #[test]
+    fn test_signed_action_conflicts() {
+        let vote = record(SignedMessageKind::QuorumVote, 1, 1);
+
+        // Signing the same message again is fine.
+        assert!(!vote.conflicts_with(&vote));
+        // So is signing a different message for a different view or of a different kind.
+        assert!(!vote.conflicts_with(&record(SignedMessageKind::QuorumVote, 2, 2)));
+        assert!(!vote.conflicts_with(&record(SignedMessageKind::DaVote, 1, 2)));
+        // A different message of the same kind in the same view is a double sign.
+        assert!(vote.conflicts_with(&record(SignedMessageKind::QuorumVote, 1, 2)));
+        assert!(vote
+            .check_conflicts(&[record(SignedMessageKind::QuorumVote, 1, 2)])
+            .is_err());
+
+        // View sync votes may be signed any number of times in the same view.
+        let view_sync = record(SignedMessageKind::ViewSyncVote, 1, 1);
+        assert!(!view_sync.conflicts_with(&record(SignedMessageKind::ViewSyncVote, 1, 2)));
+        // Quorum and timeout votes in the same view are different kinds of message.
+        assert!(!vote.conflicts_with(&record(SignedMessageKind::TimeoutVote, 1, 2)));
+    }
+
+    #[test]
+    fn test_interchange_round_trip() {
+        let interchange = SlashingProtectionInterchange::new(
+            Some(ViewNumber::new(2)),
+            [
+                record(SignedMessageKind::QuorumVote, 1, 1),
+                record(SignedMessageKind::QuorumProposal, 2, 2),
+            ],
+        );
+        let json = serde_json::to_string(&interchange).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SlashingProtectionInterchange>(&json).unwrap(),
+            interchange
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use anyhow::{bail, ensure, Context};
+use committable::{Commitment, Committable};
+use hotshot_types::{simple_certificate::QuorumCertificate2, vote::HasViewNumber};
+use jf_merkle_tree::MerkleTreeScheme;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    v0_1::RewardMerkleTree, BlockMerkleTree, FeeMerkleTree, Leaf2, SeqTypes, ValidatedState,
+};
+
+/// Magic bytes at the start of every snapshot file.
+const SNAPSHOT_MAGIC: &[u8; 8] = b"ESPSNAP\0";
+
+/// The version of the snapshot file format written by this software.
+pub const STATE_SNAPSHOT_VERSION: u16 = 1;
+
+/// A snapshot of the full state of the chain at a decided leaf.
+///
+/// A new node can import a snapshot to start consensus from the snapshot's leaf with complete fee
+/// and reward state, rather than replaying from genesis or catching up the state account by
+/// account from its peers.
+///
+/// Stake tables are deliberately not part of a snapshot: nothing in the leaf commits to them, so
+/// they could not be checked on import. A node started from a snapshot rebuilds them from the stake
+/// table contract on the L1, like any node which is missing a stake table.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// The decided leaf the snapshot was taken at.
+    pub leaf: Leaf2,
+    /// A quorum certificate for `leaf`.
+    pub qc: QuorumCertificate2<SeqTypes>,
+    /// The full fee state after applying `leaf`.
+    pub fee_merkle_tree: FeeMerkleTree,
+    /// The full reward state after applying `leaf`.
+    pub reward_merkle_tree: RewardMerkleTree,
+    /// The frontier of the block Merkle tree after applying `leaf`.
+    pub block_merkle_tree: BlockMerkleTree,
+}
+
+impl StateSnapshot {
+    /// The height of the leaf the snapshot was taken at.
+    pub fn height(&self) -> u64 {
+        self.leaf.height()
+    }
+
+    /// The commitment of the leaf the snapshot was taken at.
+    pub fn leaf_commitment(&self) -> Commitment<Leaf2> {
+        self.leaf.commit()
+    }
+
+    /// Check that the snapshot is consistent.
+    ///
+    /// This checks that `qc` certifies `leaf`, and that each of the Merkle trees matches the
+    /// corresponding root in the certified header. It does not check the signatures on the QC, so
+    /// it does not authenticate the snapshot: a snapshot must only be imported after comparing its
+    /// [leaf commitment](Self::leaf_commitment) with one obtained from a trusted node.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.qc.view_number() == self.leaf.view_number(),
+            "QC is for view {:?}, but leaf is from view {:?}",
+            self.qc.view_number(),
+            self.leaf.view_number()
+        );
+        ensure!(
+            self.qc.data.leaf_commit == self.leaf.commit(),
+            "QC does not certify snapshot leaf"
+        );
+
+        let header = self.leaf.block_header();
+        ensure!(
+            self.fee_merkle_tree.commitment() == header.fee_merkle_tree_root(),
+            "fee state does not match header"
+        );
+        ensure!(
+            self.reward_merkle_tree.commitment() == header.reward_merkle_tree_root(),
+            "reward state does not match header"
+        );
+        ensure!(
+            self.block_merkle_tree.commitment() == header.block_merkle_tree_root(),
+            "block state does not match header"
+        );
+        Ok(())
+    }
+
+    /// The validated state consensus should start from when starting at the snapshot's leaf.
+    pub fn validated_state(&self) -> ValidatedState {
+        ValidatedState {
+            block_merkle_tree: self.block_merkle_tree.clone(),
+            fee_merkle_tree: self.fee_merkle_tree.clone(),
+            reward_merkle_tree: self.reward_merkle_tree.clone(),
+            chain_config: self.leaf.block_header().chain_config(),
+        }
+    }
+
+    /// Write the snapshot in the versioned snapshot file format.
+    pub fn write(&self, mut w: impl Write) -> anyhow::Result<()> {
+        w.write_all(SNAPSHOT_MAGIC)?;
+        w.write_all(&STATE_SNAPSHOT_VERSION.to_le_bytes())?;
+        bincode::serialize_into(&mut w, self).context("serializing snapshot")?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Read a snapshot in the versioned snapshot file format.
+    pub fn read(mut r: impl Read) -> anyhow::Result<Self> {
+        let mut magic = [0; SNAPSHOT_MAGIC.len()];
+        r.read_exact(&mut magic)
+            .context("reading snapshot header")?;
+        ensure!(&magic == SNAPSHOT_MAGIC, "not a state snapshot");
+
+        let mut version = [0; 2];
+        r.read_exact(&mut version)
+            .context("reading snapshot version")?;
+        match u16::from_le_bytes(version) {
+            1 => bincode::deserialize_from(r).context("deserializing snapshot"),
+            v => bail!("unsupported snapshot version {v}"),
+        }
+    }
+
+    /// Save the snapshot to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path).context(format!("creating {}", path.display()))?;
+        self.write(BufWriter::new(file))
+    }
+
+    /// Load a snapshot from a file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).context(format!("opening {}", path.display()))?;
+        Self::read(BufReader::new(file))
+    }
+}
@@ -0,0 +1,55 @@
+use alloy::primitives::{Address, U256};
+use hotshot_types::data::EpochNumber;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    v0::PubKey,
+    v0_1::RewardAmount,
+    v0_3::{Delegator, Validator},
+};
+
+/// A validator in the stake table for some epoch, as listed by the staking explorer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorSummary {
+    pub account: Address,
+    pub stake_table_key: PubKey,
+    /// The total stake delegated to this validator.
+    pub stake: U256,
+    /// The commission of the validator, in basis points.
+    pub commission: u16,
+    /// The number of accounts delegating to this validator.
+    pub num_delegators: u64,
+    /// The number of blocks proposed by this validator which were decided during the epoch.
+    pub blocks_proposed: u64,
+    /// The number of views this validator led during the epoch without a block being decided.
+    pub views_missed: u64,
+}
+
+/// Full information about a validator in some epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorDetail {
+    pub epoch: EpochNumber,
+    pub validator: Validator<PubKey>,
+    pub blocks_proposed: u64,
+    pub views_missed: u64,
+}
+
+/// The reward balance of an account at the end of an epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RewardHistoryEntry {
+    pub epoch: EpochNumber,
+    /// The height of the block whose reward state this balance was read from.
+    pub height: u64,
+    /// The balance of the account, or [`None`] if the account had not accrued any rewards yet.
+    pub balance: Option<RewardAmount>,
+}
+
+/// The delegations of an account in some epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegatorSummary {
+    pub address: Address,
+    pub epoch: EpochNumber,
+    pub delegations: Vec<Delegator>,
+    /// The latest known reward balance of the account.
+    pub reward_balance: Option<RewardAmount>,
+}
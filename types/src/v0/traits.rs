@@ -1,8 +1,14 @@
 //! This module contains all the traits used for building the sequencer types.
 //! It also includes some trait implementations that cannot be implemented in an external crate.
-use std::{cmp::max, collections::BTreeMap, fmt::Debug, ops::Range, sync::Arc};
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+    fmt::Debug,
+    ops::Range,
+    sync::Arc,
+};
 
-use alloy::primitives::U256;
+use alloy::primitives::{Address, B256, U256};
 use anyhow::{bail, ensure, Context};
 use async_trait::async_trait;
 use committable::Commitment;
@@ -26,6 +32,7 @@ use hotshot_types::{
     },
     traits::{
         node_implementation::{ConsensusTime, NodeType, Versions},
+        signer::SignedMessageKind,
         storage::Storage,
         ValidatedState as HotShotState,
     },
@@ -40,10 +47,12 @@ use super::{
     utils::BackoffParams,
     v0_1::{RewardAccount, RewardAccountProof, RewardMerkleCommitment},
     v0_3::{EventKey, IndexedStake, StakeTableEvent, Validator},
+    SignedActionRecord, SlashingProtectionInterchange,
 };
 use crate::{
     v0::impls::ValidatedState, v0_99::ChainConfig, BlockMerkleTree, Event, FeeAccount,
-    FeeAccountProof, FeeMerkleCommitment, Leaf2, NetworkConfig, SeqTypes,
+    FeeAccountProof, FeeInfo, FeeMerkleCommitment, L1BlockInfo, Leaf2, NetworkConfig, SeqTypes,
+    StateSnapshot,
 };
 
 #[async_trait]
@@ -373,7 +382,7 @@ impl<T: StateCatchup + ?Sized> StateCatchup for Arc<T> {
 
 #[async_trait]
 pub trait PersistenceOptions: Clone + Send + Sync + Debug + 'static {
-    type Persistence: SequencerPersistence + MembershipPersistence;
+    type Persistence: SequencerPersistence + MembershipPersistence + L1Persistence;
 
     fn set_view_retention(&mut self, view_retention: u64);
     async fn create(&mut self) -> anyhow::Result<Self::Persistence>;
@@ -407,6 +416,71 @@ pub trait MembershipPersistence: Send + Sync + 'static {
     async fn load_events(&self) -> anyhow::Result<Option<(u64, Vec<(EventKey, StakeTableEvent)>)>>;
 }
 
+/// Trait used by the L1 client to cache finalized L1 data in the persistence layer.
+///
+/// Finalized L1 data never changes, so once it has been fetched it can be served from storage
+/// instead of the L1 providers, even after a restart.
+#[async_trait]
+pub trait L1Persistence: Send + Sync + 'static {
+    /// Load the finalized L1 block with the given number, and the hash of its parent.
+    async fn load_finalized_l1_block(
+        &self,
+        number: u64,
+    ) -> anyhow::Result<Option<(L1BlockInfo, B256)>>;
+
+    /// Store a finalized L1 block, and the hash of its parent.
+    async fn store_finalized_l1_block(
+        &self,
+        block: L1BlockInfo,
+        parent_hash: B256,
+    ) -> anyhow::Result<()>;
+
+    /// Load the deposits made to `fee_contract` in the L1 blocks `from..=to`.
+    ///
+    /// Returns `None` unless every block in the range has already been scanned for deposits.
+    async fn load_l1_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Option<Vec<FeeInfo>>>;
+
+    /// Store all the deposits made to `fee_contract` in the finalized L1 blocks `from..=to`.
+    ///
+    /// Each deposit is given along with the number of the L1 block it was made in. Deposits are
+    /// stored by block, so that they can later be loaded for any range of scanned blocks, no
+    /// matter how the blocks were split up when they were scanned.
+    async fn store_l1_deposits(
+        &self,
+        fee_contract: Address,
+        from: u64,
+        to: u64,
+        deposits: &[(u64, FeeInfo)],
+    ) -> anyhow::Result<()>;
+
+    /// Forget the cached finalized L1 blocks and deposits below L1 block `height`.
+    ///
+    /// This is called once a block based on the finalized L1 block `height` is decided, since
+    /// nothing below that L1 block will be needed to build or validate later blocks.
+    async fn prune_l1_cache(&self, height: u64) -> anyhow::Result<()>;
+}
+
+/// The range of L1 blocks known to have been scanned for deposits, after scanning `from..=to`.
+///
+/// `scanned` is the range known to have been scanned before. Only a single contiguous range is
+/// tracked, so if the two ranges are disjoint, the later one is kept.
+pub fn merge_scanned_l1_range(scanned: Option<(u64, u64)>, from: u64, to: u64) -> (u64, u64) {
+    match scanned {
+        Some((scanned_from, scanned_to))
+            if from <= scanned_to.saturating_add(1) && to.saturating_add(1) >= scanned_from =>
+        {
+            (min(scanned_from, from), max(scanned_to, to))
+        },
+        Some((scanned_from, scanned_to)) if scanned_to > to => (scanned_from, scanned_to),
+        _ => (from, to),
+    }
+}
+
 #[async_trait]
 pub trait SequencerPersistence: Sized + Send + Sync + Clone + 'static {
     /// Use this storage as a state catchup backend, if supported.
@@ -514,9 +588,20 @@ pub trait SequencerPersistence: Sized + Send + Sync + Clone + 'static {
                 )
             },
         };
+        let snapshot_state = self
+            .load_snapshot_state()
+            .await
+            .context("loading snapshot state")?;
         let validated_state = if leaf.block_header().height() == 0 {
             // If we are starting from genesis, we can provide the full state.
             genesis_validated_state
+        } else if let Some(state) = snapshot_state
+            .filter(|(height, _)| *height == leaf.block_header().height())
+            .map(|(_, state)| state)
+        {
+            // If we are starting from an imported snapshot, we have the full state as well.
+            tracing::info!("starting from imported snapshot state");
+            state
         } else {
             // Otherwise, we will have to construct a sparse state and fetch missing data during
             // catchup.
@@ -660,6 +745,18 @@ pub trait SequencerPersistence: Sized + Send + Sync + Clone + 'static {
     async fn load_anchor_leaf(
         &self,
     ) -> anyhow::Result<Option<(Leaf2, QuorumCertificate2<SeqTypes>)>>;
+
+    /// Import a state snapshot, so that consensus starts from the snapshot's leaf.
+    ///
+    /// The snapshot's leaf is stored as the anchor leaf, its stake tables are stored for their
+    /// respective epochs, and its state is stored so that consensus can start with the full state
+    /// instead of catching up missing accounts from peers. The caller is responsible for
+    /// [verifying](StateSnapshot::verify) the snapshot first.
+    async fn import_snapshot(&self, snapshot: &StateSnapshot) -> anyhow::Result<()>;
+
+    /// Load the state stored by the last [`import_snapshot`](Self::import_snapshot), along with the
+    /// height of the snapshot.
+    async fn load_snapshot_state(&self) -> anyhow::Result<Option<(u64, ValidatedState)>>;
     async fn append_vid(
         &self,
         proposal: &Proposal<SeqTypes, ADVZDisperseShare<SeqTypes>>,
@@ -681,6 +778,51 @@ pub trait SequencerPersistence: Sized + Send + Sync + Clone + 'static {
         action: HotShotAction,
     ) -> anyhow::Result<()>;
 
+    /// Record a message this node signed.
+    ///
+    /// Fails, without recording anything, if the message conflicts with one already recorded, so
+    /// that consensus does not send it. Votes and proposals also advance the view returned by
+    /// [`load_latest_acted_view`](Self::load_latest_acted_view).
+    async fn record_signed_action(&self, record: SignedActionRecord) -> anyhow::Result<()>;
+
+    /// Load every record of a signed message which has not yet been garbage collected.
+    async fn load_signed_actions(&self) -> anyhow::Result<Vec<SignedActionRecord>>;
+
+    /// Export this node's signing history, to be imported when moving it to a new machine.
+    async fn export_slashing_protection(&self) -> anyhow::Result<SlashingProtectionInterchange> {
+        Ok(SlashingProtectionInterchange::new(
+            self.load_latest_acted_view().await?,
+            self.load_signed_actions().await?,
+        ))
+    }
+
+    /// Import a signing history exported from another machine.
+    ///
+    /// Fails without importing any records if the imported history conflicts with the history
+    /// already in this storage.
+    async fn import_slashing_protection(
+        &self,
+        interchange: SlashingProtectionInterchange,
+    ) -> anyhow::Result<()> {
+        ensure!(
+            interchange.version == SlashingProtectionInterchange::VERSION,
+            "unsupported slashing protection interchange version {}",
+            interchange.version
+        );
+        let existing = self.load_signed_actions().await?;
+        for record in &interchange.records {
+            record.check_conflicts(&existing)?;
+        }
+
+        if let Some(view) = interchange.latest_acted_view {
+            self.record_action(view, None, HotShotAction::Vote).await?;
+        }
+        for record in interchange.records {
+            self.record_signed_action(record).await?;
+        }
+        Ok(())
+    }
+
     async fn append_quorum_proposal2(
         &self,
         proposal: &Proposal<SeqTypes, QuorumProposalWrapper<SeqTypes>>,
@@ -820,6 +962,24 @@ impl<P: SequencerPersistence> Storage<SeqTypes> for Arc<P> {
         (**self).record_action(view, epoch, action).await
     }
 
+    async fn record_signed_action(
+        &self,
+        view: ViewNumber,
+        epoch: Option<EpochNumber>,
+        _action: HotShotAction,
+        kind: SignedMessageKind,
+        commitment: [u8; 32],
+    ) -> anyhow::Result<()> {
+        (**self)
+            .record_signed_action(SignedActionRecord {
+                kind,
+                view,
+                epoch,
+                commitment: B256::from(commitment),
+            })
+            .await
+    }
+
     async fn update_high_qc(&self, _high_qc: QuorumCertificate<SeqTypes>) -> anyhow::Result<()> {
         Ok(())
     }
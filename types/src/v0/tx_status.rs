@@ -0,0 +1,136 @@
+use anyhow::{bail, ensure, Context};
+use committable::{Commitment, Committable};
+use hotshot_query_service::VidCommon;
+use jf_merkle_tree::{MerkleCommitment, MerkleTreeScheme};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    v0::{Header, Transaction},
+    BlockMerkleCommitment, BlockMerkleTree, NsProof,
+};
+
+/// The status of a transaction, as seen by a sequencer node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// The transaction was not submitted through this node and has not been sequenced.
+    Unknown,
+    /// The transaction was submitted through this node and is waiting to be sequenced.
+    Pending {
+        /// When the transaction was submitted, in seconds since the Unix epoch.
+        submitted_at: u64,
+    },
+    /// The transaction was sequenced at position `index` in the block at `height`.
+    Included { height: u64, index: u64 },
+    /// The transaction was submitted through this node but was not sequenced in time, and is no
+    /// longer being tracked. It may be resubmitted.
+    Dropped {
+        /// When the transaction was submitted, in seconds since the Unix epoch.
+        submitted_at: u64,
+    },
+}
+
+impl TransactionStatus {
+    /// Whether this status is final, meaning the node will not report any further changes.
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Included { .. } | Self::Dropped { .. })
+    }
+}
+
+/// The result of submitting one transaction in a batch.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionSubmitResult {
+    pub hash: Commitment<Transaction>,
+    /// Why the transaction was rejected, or [`None`] if it was accepted.
+    pub error: Option<String>,
+}
+
+impl TransactionSubmitResult {
+    /// Whether the transaction was accepted.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Proof that a transaction was sequenced.
+///
+/// The receipt contains a namespace proof showing that the transaction is included in the block
+/// with header `header`, and, once the following block has been decided, a block Merkle proof
+/// showing that `header` is part of the chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub transaction: Transaction,
+    /// The position of the transaction within its namespace in the block.
+    ///
+    /// This is relative to the namespace, rather than the whole block, so that it can be checked
+    /// against `ns_proof`.
+    pub index: u64,
+    /// The header of the block containing the transaction.
+    pub header: Header,
+    /// Proof of the namespace containing the transaction, against the payload commitment in
+    /// `header`.
+    pub ns_proof: NsProof,
+    /// VID common data needed to check `ns_proof`.
+    pub vid_common: VidCommon,
+    /// Proof that `header` is in the block Merkle tree of the next header.
+    ///
+    /// This is `None` if the next block has not been decided yet, or if the node does not store
+    /// merklized state.
+    pub block_proof: Option<<BlockMerkleTree as MerkleTreeScheme>::MembershipProof>,
+}
+
+impl TransactionReceipt {
+    /// The hash of the transaction.
+    pub fn hash(&self) -> Commitment<Transaction> {
+        self.transaction.commit()
+    }
+
+    /// The height of the block containing the transaction.
+    pub fn height(&self) -> u64 {
+        self.header.height()
+    }
+
+    /// Check that the transaction is included in the block with header `header`, at position
+    /// `index` within its namespace.
+    pub fn verify_inclusion(&self) -> anyhow::Result<()> {
+        let (transactions, ns) = self
+            .ns_proof
+            .verify(
+                self.header.ns_table(),
+                &self.header.payload_commitment(),
+                &self.vid_common,
+            )
+            .context("invalid namespace proof")?;
+        ensure!(
+            ns == self.transaction.namespace(),
+            "namespace proof is for wrong namespace: {ns} != {}",
+            self.transaction.namespace()
+        );
+        let index = usize::try_from(self.index).context("transaction index out of range")?;
+        ensure!(
+            transactions.get(index) == Some(&self.transaction),
+            "transaction {} is not at position {index} in namespace {ns}",
+            self.hash()
+        );
+        Ok(())
+    }
+
+    /// Check that `header` is part of the chain committed to by `root`.
+    ///
+    /// `root` must be the block Merkle tree root of the header immediately following `header`;
+    /// this is the header the node used to generate `block_proof`.
+    pub fn verify_block_proof(&self, root: BlockMerkleCommitment) -> anyhow::Result<()> {
+        let proof = self
+            .block_proof
+            .as_ref()
+            .context("receipt has no block Merkle proof")?;
+        BlockMerkleTree::verify(root.digest(), self.height(), proof)
+            .context("malformed block Merkle proof")?
+            .or_else(|_| bail!("invalid block Merkle proof"))?;
+        ensure!(
+            proof.elem() == Some(&self.header.commit()),
+            "block Merkle proof is for wrong header"
+        );
+        Ok(())
+    }
+}
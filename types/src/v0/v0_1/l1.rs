@@ -9,7 +9,7 @@ use alloy::{
 };
 use alloy_compat::ethers_serde;
 use async_broadcast::{InactiveReceiver, Sender};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use derive_more::Deref;
 use hotshot_types::traits::metrics::{Counter, Gauge, Metrics, NoMetrics};
 use lru::LruCache;
@@ -26,7 +26,7 @@ use tokio::{
 };
 use url::Url;
 
-use crate::v0::utils::parse_duration;
+use crate::v0::{traits::L1Persistence, utils::parse_duration};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, Hash, PartialEq, Eq)]
 pub struct L1BlockInfo {
@@ -188,17 +188,55 @@ pub struct L1ClientOptions {
     #[arg(long, env = "ESPRESSO_SEQUENCER_L1_FINALIZED_SAFETY_MARGIN")]
     pub l1_finalized_safety_margin: Option<u64>,
 
+    /// Number of L1 providers which must agree on finalized blocks, deposits and stake table events.
+    ///
+    /// If specified, these reads are sent to every configured L1 provider, and a response is only
+    /// used once at least this many providers return the same response. This allows using several
+    /// third-party RPC providers without trusting any single one of them. Must be at most the
+    /// number of L1 providers. If not specified, reads use one provider at a time, failing over to
+    /// the next provider only after errors.
+    #[arg(long, env = "ESPRESSO_SEQUENCER_L1_QUORUM_THRESHOLD")]
+    pub l1_quorum_threshold: Option<usize>,
+
+    /// What to do when the L1 providers fail to reach a quorum on a read.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_L1_QUORUM_FAILURE_POLICY",
+        value_enum,
+        default_value = "halt"
+    )]
+    pub l1_quorum_failure_policy: L1QuorumFailurePolicy,
+
+    /// Maximum time to wait for each provider to respond to a quorum read.
+    #[arg(
+        long,
+        env = "ESPRESSO_SEQUENCER_L1_QUORUM_TIMEOUT",
+        default_value = "30s",
+        value_parser = parse_duration,
+    )]
+    pub l1_quorum_timeout: Duration,
+
     #[arg(skip = Arc::<Box<dyn Metrics>>::new(Box::new(NoMetrics)))]
     pub metrics: Arc<Box<dyn Metrics>>,
 }
 
+/// How an [`L1Client`] in quorum-read mode handles providers failing to reach a quorum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum L1QuorumFailurePolicy {
+    /// Retry the read until enough providers agree, halting progress in the meantime.
+    #[default]
+    Halt,
+    /// Use the response of the current provider, as if quorum reads were disabled.
+    Fallback,
+}
+
 /// Type alias for alloy provider
 pub type L1Provider = FillProvider<
     JoinFill<Identity, <Ethereum as RecommendedFillers>::RecommendedFillers>,
     RootProvider,
 >;
 
-#[derive(Clone, Debug, Deref)]
+#[derive(Clone, derive_more::derive::Debug, Deref)]
 /// An Ethereum provider and configuration to interact with the L1.
 ///
 /// This client runs asynchronously, updating an in-memory snapshot of the relevant L1 information
@@ -221,6 +259,11 @@ pub struct L1Client {
     pub(crate) receiver: InactiveReceiver<L1Event>,
     /// Async task which updates the shared state.
     pub(crate) update_task: Arc<L1UpdateTask>,
+    /// Providers to cross-check reads against, if quorum reads are enabled.
+    pub(crate) quorum: Option<Arc<L1Quorum>>,
+    /// Storage for finalized L1 data, consulted before querying the L1 providers.
+    #[debug(skip)]
+    pub(crate) persistence: Option<Arc<dyn L1Persistence>>,
 }
 
 /// In-memory view of the L1 state, updated asynchronously.
@@ -247,6 +290,19 @@ pub(crate) struct L1ClientMetrics {
     pub(crate) reconnects: Arc<dyn Counter>,
     pub(crate) failovers: Arc<dyn Counter>,
     pub(crate) failures: Arc<Vec<Box<dyn Counter>>>,
+    pub(crate) quorum_failures: Arc<dyn Counter>,
+    pub(crate) quorum_fallbacks: Arc<dyn Counter>,
+    pub(crate) quorum_disagreements: Arc<Vec<Box<dyn Counter>>>,
+}
+
+/// Every configured L1 provider, used to cross-check reads in quorum-read mode.
+#[derive(Clone, Debug)]
+pub(crate) struct L1Quorum {
+    pub(crate) providers: Vec<L1Provider>,
+    pub(crate) threshold: usize,
+    pub(crate) policy: L1QuorumFailurePolicy,
+    pub(crate) timeout: Duration,
+    pub(crate) metrics: L1ClientMetrics,
 }
 
 /// An RPC client with multiple remote (HTTP) providers.
@@ -0,0 +1,422 @@
+//! Password-encrypted keystores for consensus keys.
+//!
+//! The format follows [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335): the secret is encrypted
+//! with AES-128-CTR under a key derived from the password with scrypt or PBKDF2, and a SHA-256
+//! checksum over the second half of the derived key and the ciphertext detects a wrong password.
+//! Unlike EIP-2335, which only covers BLS12-381 keys, the secret here is the value of the
+//! [`TaggedBase64`] encoding of any private key, and the tag is stored alongside it, so the same
+//! format holds both BLS staking keys and Schnorr state keys. The tag is covered by the checksum
+//! as well, so that it cannot be changed without the password.
+//!
+//! The password is used as UTF-8 bytes, without the NFKD normalization of EIP-2335.
+
+use std::{
+    fmt::Display,
+    fs,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use aes::Aes128;
+use alloy::hex;
+use anyhow::{bail, ensure, Context};
+use clap::ValueEnum;
+use ctr::cipher::{KeyIvInit, StreamCipher};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tagged_base64::TaggedBase64;
+use zeroize::Zeroizing;
+
+/// The version of the keystore format written by this software.
+pub const KEYSTORE_VERSION: u32 = 4;
+
+type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+
+/// Length of the key derived from the password.
+///
+/// The first half is the AES key, the second half goes into the checksum.
+const DKLEN: usize = 32;
+
+/// Key derivation function used to turn a password into an encryption key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KdfFunction {
+    /// scrypt, with N = 2^18, r = 8, p = 1.
+    #[default]
+    Scrypt,
+    /// PBKDF2 with HMAC-SHA256, with 2^18 iterations.
+    Pbkdf2,
+}
+
+/// Parameters of the key derivation function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u64,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+impl KdfParams {
+    /// The recommended parameters for `function`, with a fresh random salt.
+    pub fn new(function: KdfFunction) -> Self {
+        let salt = hex::encode(random_bytes::<32>());
+        match function {
+            KdfFunction::Scrypt => Self::Scrypt {
+                dklen: DKLEN,
+                n: 1 << 18,
+                r: 8,
+                p: 1,
+                salt,
+            },
+            KdfFunction::Pbkdf2 => Self::Pbkdf2 {
+                dklen: DKLEN,
+                c: 1 << 18,
+                prf: "hmac-sha256".into(),
+                salt,
+            },
+        }
+    }
+
+    fn function(&self) -> KdfFunction {
+        match self {
+            Self::Scrypt { .. } => KdfFunction::Scrypt,
+            Self::Pbkdf2 { .. } => KdfFunction::Pbkdf2,
+        }
+    }
+
+    fn derive_key(&self, password: &str) -> anyhow::Result<Zeroizing<[u8; DKLEN]>> {
+        let mut key = Zeroizing::new([0; DKLEN]);
+        match self {
+            Self::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                ensure!(*dklen == DKLEN, "unsupported derived key length {dklen}");
+                ensure!(
+                    n.is_power_of_two(),
+                    "scrypt parameter n must be a power of 2"
+                );
+                let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p, DKLEN)
+                    .context("invalid scrypt parameters")?;
+                scrypt::scrypt(
+                    password.as_bytes(),
+                    &hex::decode(salt).context("malformed salt")?,
+                    &params,
+                    &mut key[..],
+                )
+                .context("deriving key")?;
+            },
+            Self::Pbkdf2 {
+                dklen,
+                c,
+                prf,
+                salt,
+            } => {
+                ensure!(*dklen == DKLEN, "unsupported derived key length {dklen}");
+                ensure!(prf == "hmac-sha256", "unsupported PBKDF2 function {prf}");
+                pbkdf2::pbkdf2_hmac::<Sha256>(
+                    password.as_bytes(),
+                    &hex::decode(salt).context("malformed salt")?,
+                    *c,
+                    &mut key[..],
+                );
+            },
+        }
+        Ok(key)
+    }
+}
+
+/// A step of the encryption, as represented in an EIP-2335 keystore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Module<F, P> {
+    pub function: F,
+    pub params: P,
+    pub message: String,
+}
+
+/// Parameters of the cipher.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub iv: String,
+}
+
+/// Parameters of the checksum, of which there are none.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecksumParams {}
+
+/// The encrypted secret and everything needed to decrypt it, given the password.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub kdf: Module<KdfFunction, KdfParams>,
+    pub checksum: Module<String, ChecksumParams>,
+    pub cipher: Module<String, CipherParams>,
+}
+
+/// A password-encrypted private key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub crypto: KeystoreCrypto,
+    pub description: String,
+    /// The public key corresponding to the encrypted private key.
+    pub pubkey: String,
+    /// The [`TaggedBase64`] tag of the encrypted private key.
+    pub tag: String,
+    pub version: u32,
+}
+
+impl Keystore {
+    /// Encrypt `key` under `password`, with the recommended parameters for `kdf`.
+    pub fn encrypt(
+        key: &TaggedBase64,
+        pubkey: impl Display,
+        password: &str,
+        kdf: KdfFunction,
+    ) -> anyhow::Result<Self> {
+        Self::encrypt_with_params(key, pubkey, password, KdfParams::new(kdf))
+    }
+
+    /// Encrypt `key` under `password`, with custom key derivation parameters.
+    pub fn encrypt_with_params(
+        key: &TaggedBase64,
+        pubkey: impl Display,
+        password: &str,
+        kdf: KdfParams,
+    ) -> anyhow::Result<Self> {
+        let derived = kdf.derive_key(password)?;
+        let iv = random_bytes::<16>();
+
+        let mut ciphertext = key.value();
+        Aes128Ctr::new(derived[..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+        Ok(Self {
+            crypto: KeystoreCrypto {
+                kdf: Module {
+                    function: kdf.function(),
+                    params: kdf,
+                    message: String::new(),
+                },
+                checksum: Module {
+                    function: "sha256".into(),
+                    params: ChecksumParams {},
+                    message: hex::encode(checksum(&derived, &key.tag(), &ciphertext)),
+                },
+                cipher: Module {
+                    function: "aes-128-ctr".into(),
+                    params: CipherParams {
+                        iv: hex::encode(iv),
+                    },
+                    message: hex::encode(ciphertext),
+                },
+            },
+            description: String::new(),
+            pubkey: pubkey.to_string(),
+            tag: key.tag(),
+            version: KEYSTORE_VERSION,
+        })
+    }
+
+    /// Decrypt the private key.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `password` is wrong or the keystore is malformed.
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<TaggedBase64> {
+        ensure!(
+            self.version == KEYSTORE_VERSION,
+            "unsupported keystore version {}",
+            self.version
+        );
+        let crypto = &self.crypto;
+        ensure!(
+            crypto.kdf.function == crypto.kdf.params.function(),
+            "KDF parameters do not match KDF function {:?}",
+            crypto.kdf.function
+        );
+        ensure!(
+            crypto.checksum.function == "sha256",
+            "unsupported checksum function {}",
+            crypto.checksum.function
+        );
+        ensure!(
+            crypto.cipher.function == "aes-128-ctr",
+            "unsupported cipher {}",
+            crypto.cipher.function
+        );
+
+        let derived = crypto.kdf.params.derive_key(password)?;
+        let ciphertext = hex::decode(&crypto.cipher.message).context("malformed ciphertext")?;
+        let expected = hex::decode(&crypto.checksum.message).context("malformed checksum")?;
+        if checksum(&derived, &self.tag, &ciphertext).as_slice() != expected {
+            bail!("incorrect password or tampered keystore");
+        }
+
+        let iv: [u8; 16] = hex::decode(&crypto.cipher.params.iv)
+            .ok()
+            .and_then(|iv| iv.try_into().ok())
+            .context("malformed IV")?;
+        let mut secret = Zeroizing::new(ciphertext);
+        Aes128Ctr::new(derived[..16].into(), &iv.into()).apply_keystream(&mut secret);
+        TaggedBase64::new(&self.tag, &secret).context("malformed key tag")
+    }
+
+    /// Load a keystore from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).context(format!("reading keystore {}", path.display()))?;
+        serde_json::from_slice(&bytes).context(format!("malformed keystore {}", path.display()))
+    }
+
+    /// Write the keystore to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .context(format!("writing keystore {}", path.display()))
+    }
+}
+
+/// Read a keystore password from `file`, or from the first line of stdin if no file is given.
+///
+/// A single trailing newline is not considered part of the password.
+pub fn read_password(file: Option<&Path>) -> anyhow::Result<Zeroizing<String>> {
+    let mut password = Zeroizing::new(String::new());
+    match file {
+        Some(path) => {
+            *password = fs::read_to_string(path)
+                .context(format!("reading password file {}", path.display()))?;
+        },
+        None => {
+            io::stdin()
+                .lock()
+                .read_line(&mut password)
+                .context("reading password from stdin")?;
+        },
+    }
+    let len = password
+        .strip_suffix('\n')
+        .map(|p| p.strip_suffix('\r').unwrap_or(p))
+        .map_or(password.len(), str::len);
+    password.truncate(len);
+    Ok(password)
+}
+
+fn checksum(derived: &[u8; DKLEN], tag: &str, ciphertext: &[u8]) -> [u8; 32] {
+    // The tag is length-prefixed, so it cannot be confused with the start of the ciphertext.
+    Sha256::new()
+        .chain_update(&derived[16..])
+        .chain_update((tag.len() as u64).to_le_bytes())
+        .chain_update(tag.as_bytes())
+        .chain_update(ciphertext)
+        .finalize()
+        .into()
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+#[cfg(test)]
+mod test {
+    use hotshot::types::{BLSPubKey, SignatureKey};
+    use hotshot_types::light_client::StateKeyPair;
+
+    use super::*;
+
+    /// Cheap parameters, so the tests don't spend their time deriving keys.
+    fn test_params(kdf: KdfFunction) -> KdfParams {
+        match KdfParams::new(kdf) {
+            KdfParams::Scrypt { salt, .. } => KdfParams::Scrypt {
+                dklen: DKLEN,
+                n: 1 << 4,
+                r: 8,
+                p: 1,
+                salt,
+            },
+            KdfParams::Pbkdf2 { prf, salt, .. } => KdfParams::Pbkdf2 {
+                dklen: DKLEN,
+                c: 16,
+                prf,
+                salt,
+            },
+        }
+    }
+
+    #[test]
+    fn test_keystore_round_trip() {
+        let (bls_pub, bls_priv) = BLSPubKey::generated_from_seed_indexed([0; 32], 0);
+        let state = StateKeyPair::generate_from_seed_indexed([0; 32], 0);
+        let keys = [
+            (bls_priv.to_tagged_base64().unwrap(), bls_pub.to_string()),
+            (
+                state.sign_key_ref().to_tagged_base64().unwrap(),
+                state.ver_key().to_string(),
+            ),
+        ];
+
+        for kdf in [KdfFunction::Scrypt, KdfFunction::Pbkdf2] {
+            for (key, pubkey) in &keys {
+                let keystore =
+                    Keystore::encrypt_with_params(key, pubkey, "password", test_params(kdf))
+                        .unwrap();
+                assert_eq!(keystore.pubkey, *pubkey);
+                assert_ne!(keystore.crypto.cipher.message, hex::encode(key.value()));
+
+                // The keystore survives serialization.
+                let keystore: Keystore =
+                    serde_json::from_str(&serde_json::to_string(&keystore).unwrap()).unwrap();
+                assert_eq!(keystore.decrypt("password").unwrap(), *key);
+                keystore.decrypt("wrong password").unwrap_err();
+            }
+        }
+    }
+
+    #[test]
+    fn test_keystore_tampered() {
+        let (_, priv_key) = BLSPubKey::generated_from_seed_indexed([0; 32], 0);
+        let key = priv_key.to_tagged_base64().unwrap();
+        let mut keystore = Keystore::encrypt_with_params(
+            &key,
+            "pubkey",
+            "password",
+            test_params(KdfFunction::Scrypt),
+        )
+        .unwrap();
+
+        let mut ciphertext = hex::decode(&keystore.crypto.cipher.message).unwrap();
+        ciphertext[0] ^= 1;
+        keystore.crypto.cipher.message = hex::encode(ciphertext);
+        keystore.decrypt("password").unwrap_err();
+    }
+
+    #[test]
+    fn test_keystore_tampered_tag() {
+        let (_, priv_key) = BLSPubKey::generated_from_seed_indexed([0; 32], 0);
+        let key = priv_key.to_tagged_base64().unwrap();
+        let mut keystore = Keystore::encrypt_with_params(
+            &key,
+            "pubkey",
+            "password",
+            test_params(KdfFunction::Scrypt),
+        )
+        .unwrap();
+
+        keystore.tag = "SCHNORR_SIGNING_KEY".into();
+        keystore.decrypt("password").unwrap_err();
+    }
+}